@@ -0,0 +1,291 @@
+//! skillguard：Agent Skills Guard 的命令行扫描工具
+//!
+//! 使用与桌面应用完全相同的规则扫描技能目录或文件，便于在 CI 中拦截高风险技能。
+//!
+//! 退出码（固定不变，便于流水线判断）：
+//! - 0：通过
+//! - 1：违反退出策略（硬触发、禁用规则、严重程度或评分阈值）
+//! - 2：命令行参数错误
+//! - 3：扫描失败（路径不存在、读取失败等）
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use skillguard_core::models::security::{IssueSeverity, SecurityReport};
+use skillguard_core::policy::{ExitPolicy, PolicyViolation};
+use skillguard_core::sarif::to_sarif;
+use skillguard_core::security::{ScanFilter, SecurityScanner};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const EXIT_OK: u8 = 0;
+const EXIT_POLICY_VIOLATION: u8 = 1;
+const EXIT_USAGE: u8 = 2;
+const EXIT_SCAN_ERROR: u8 = 3;
+
+#[derive(Parser)]
+#[command(name = "skillguard", version, about = "Scan agent skills with the Agent Skills Guard rule set")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 扫描技能目录或单个文件
+    Scan(ScanArgs),
+}
+
+#[derive(clap::Args)]
+struct ScanArgs {
+    /// 技能目录或文件路径
+    path: PathBuf,
+
+    /// 输出格式
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// 存在该严重程度及以上的问题时不通过
+    #[arg(long, value_enum)]
+    fail_on: Option<FailOn>,
+
+    /// 安全评分低于该值时不通过（0-100）
+    #[arg(long, value_parser = clap::value_parser!(i32).range(0..=100))]
+    min_score: Option<i32>,
+
+    /// 命中指定规则 ID 时不通过（可重复或以逗号分隔）
+    #[arg(long = "block-rule", value_delimiter = ',')]
+    block_rules: Vec<String>,
+
+    /// 命中硬触发规则时仍然通过（默认不通过，与桌面应用的安装拦截一致）
+    #[arg(long)]
+    allow_hard_triggers: bool,
+
+    /// 只向标准输出写入一行 JSON 结果（包含报告与策略判定），不输出其他信息
+    #[arg(long, short)]
+    quiet: bool,
+
+    /// 建议信息使用的语言（zh / en / ja / ko / de / fr / es）
+    #[arg(long, default_value = "en")]
+    locale: String,
+
+    /// 单个文件的扫描大小上限（MB），超过的文件跳过扫描并报告警告
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..), default_value_t = 10)]
+    max_file_size: u64,
+
+    /// 只扫描匹配的文件（glob 模式，可重复或以逗号分隔）
+    #[arg(long = "include", value_delimiter = ',')]
+    include: Vec<String>,
+
+    /// 额外忽略的目录或文件（glob 模式，可重复或以逗号分隔）
+    #[arg(long = "exclude", value_delimiter = ',')]
+    exclude: Vec<String>,
+
+    /// 不使用默认忽略模式（.git、node_modules、__pycache__、dist 等）
+    #[arg(long)]
+    no_default_ignores: bool,
+}
+
+impl ScanArgs {
+    fn exit_policy(&self) -> ExitPolicy {
+        ExitPolicy {
+            min_score: self.min_score,
+            fail_on_severity: self.fail_on.map(FailOn::severity),
+            blocked_rules: self.block_rules.clone(),
+            fail_on_hard_trigger: !self.allow_hard_triggers,
+        }
+    }
+}
+
+/// --quiet 模式下输出的结果
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QuietOutput<'a> {
+    passed: bool,
+    exit_code: u8,
+    score: Option<i32>,
+    violations: &'a [PolicyViolation],
+    error: Option<String>,
+    report: Option<&'a SecurityReport>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+    Sarif,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum FailOn {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl FailOn {
+    /// --fail-on 指定的"不通过"的最低级别对应的问题严重程度
+    fn severity(self) -> IssueSeverity {
+        match self {
+            FailOn::Low => IssueSeverity::Info,
+            FailOn::Medium => IssueSeverity::Warning,
+            FailOn::High => IssueSeverity::Error,
+            FailOn::Critical => IssueSeverity::Critical,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) => {
+            let _ = e.print();
+            // --help / --version 不是错误
+            return ExitCode::from(if e.use_stderr() { EXIT_USAGE } else { EXIT_OK });
+        }
+    };
+
+    match cli.command {
+        Command::Scan(args) => ExitCode::from(run_scan(&args)),
+    }
+}
+
+fn run_scan(args: &ScanArgs) -> u8 {
+    let report = match scan_path(args) {
+        Ok(report) => report,
+        Err(e) => {
+            if args.quiet {
+                print_quiet(&QuietOutput {
+                    passed: false,
+                    exit_code: EXIT_SCAN_ERROR,
+                    score: None,
+                    violations: &[],
+                    error: Some(format!("{:#}", e)),
+                    report: None,
+                });
+            } else {
+                eprintln!("skillguard: {:#}", e);
+            }
+            return EXIT_SCAN_ERROR;
+        }
+    };
+
+    let violations = args.exit_policy().evaluate(&report);
+    let exit_code = if violations.is_empty() { EXIT_OK } else { EXIT_POLICY_VIOLATION };
+
+    if args.quiet {
+        print_quiet(&QuietOutput {
+            passed: violations.is_empty(),
+            exit_code,
+            score: Some(report.score),
+            violations: &violations,
+            error: None,
+            report: Some(&report),
+        });
+        return exit_code;
+    }
+
+    let printed = match args.format {
+        Format::Text => {
+            print_text(&report, &violations);
+            Ok(())
+        }
+        Format::Json => serde_json::to_string_pretty(&report).map(|json| println!("{}", json)),
+        Format::Sarif => serde_json::to_string_pretty(&to_sarif(&report, &args.locale)).map(|json| println!("{}", json)),
+    };
+    if let Err(e) = printed {
+        eprintln!("skillguard: {}", e);
+        return EXIT_SCAN_ERROR;
+    }
+
+    exit_code
+}
+
+fn print_quiet(output: &QuietOutput) {
+    match serde_json::to_string(output) {
+        Ok(json) => println!("{}", json),
+        Err(e) => println!("{{\"passed\":false,\"exitCode\":{},\"error\":{:?}}}", EXIT_SCAN_ERROR, e.to_string()),
+    }
+}
+
+fn scan_path(args: &ScanArgs) -> anyhow::Result<SecurityReport> {
+    let (path, locale) = (args.path.as_path(), args.locale.as_str());
+    let max_file_size = args.max_file_size * 1024 * 1024;
+    let filter = ScanFilter::new(&args.include, &args.exclude, !args.no_default_ignores)?;
+    let scanner = SecurityScanner::new().with_max_file_size(max_file_size).with_filter(filter);
+    let skill_id = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string());
+
+    if path.is_dir() {
+        let dir = path
+            .to_str()
+            .ok_or_else(|| anyhow::anyhow!("路径包含无效字符: {:?}", path))?;
+        scanner.scan_directory(dir, &skill_id, locale)
+    } else if path.is_file() {
+        let size = std::fs::metadata(path)?.len();
+        if size > max_file_size {
+            anyhow::bail!("文件过大，超过扫描大小上限（{} 字节 > {} 字节）: {:?}", size, max_file_size, path);
+        }
+        let bytes = std::fs::read(path)?;
+        let content = String::from_utf8_lossy(&bytes);
+        scanner.scan_file(&content, &path.to_string_lossy(), locale)
+    } else {
+        anyhow::bail!("路径不存在: {:?}", path)
+    }
+}
+
+fn print_text(report: &SecurityReport, violations: &[PolicyViolation]) {
+    println!(
+        "{}: score {} ({}){}",
+        report.skill_id,
+        report.score,
+        report.level.as_str(),
+        if report.blocked { ", BLOCKED" } else { "" }
+    );
+    println!("scanned {} file(s), {} issue(s)", report.scanned_files.len(), report.issues.len());
+
+    for issue in &report.issues {
+        let location = match (&issue.file_path, issue.line_number) {
+            (Some(file), Some(line)) => format!("{}:{}", file, line),
+            (Some(file), None) => file.clone(),
+            _ => "-".to_string(),
+        };
+        println!("  [{:?}] {} {}", issue.severity, location, issue.description);
+    }
+
+    for issue in &report.hard_trigger_issues {
+        println!("  ! {}", issue);
+    }
+
+    if violations.is_empty() {
+        println!("policy: passed");
+    } else {
+        println!("policy: failed ({} violation(s))", violations.len());
+        for violation in violations {
+            println!("  - {}", describe_violation(violation));
+        }
+    }
+}
+
+fn describe_violation(violation: &PolicyViolation) -> String {
+    let location = |file: &Option<String>, line: &Option<usize>| match (file, line) {
+        (Some(file), Some(line)) => format!(" at {}:{}", file, line),
+        (Some(file), None) => format!(" in {}", file),
+        _ => String::new(),
+    };
+
+    match violation {
+        PolicyViolation::HardTrigger { issue } => format!("hard trigger: {}", issue),
+        PolicyViolation::BlockedRule { rule_id, file, line } => {
+            format!("blocked rule {}{}", rule_id, location(file, line))
+        }
+        PolicyViolation::SeverityExceeded { rule_id, severity, file, line } => {
+            format!("{:?} issue {}{}", severity, rule_id, location(file, line))
+        }
+        PolicyViolation::ScoreBelowMinimum { score, min_score } => {
+            format!("score {} is below minimum {}", score, min_score)
+        }
+    }
+}
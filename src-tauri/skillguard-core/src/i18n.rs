@@ -0,0 +1,256 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+/// 默认语言
+pub const DEFAULT_LOCALE: &str = "zh";
+
+/// 内置翻译的语言
+pub const BUILTIN_LOCALES: &[&str] = &["zh", "en", "ja", "ko", "de", "fr", "es"];
+
+lazy_static::lazy_static! {
+    /// 运行时加载的翻译：locale -> (扁平化的 key -> 文本)
+    /// 翻译文本在加载后常驻内存（泄漏为 'static），以满足 rust_i18n::Backend 返回借用的要求
+    static ref EXTERNAL_TRANSLATIONS: RwLock<HashMap<&'static str, HashMap<String, &'static str>>> =
+        RwLock::new(HashMap::new());
+}
+
+/// 外部翻译的版本号，每次加载语言文件后递增，供缓存的本地化文本判断是否需要重建
+static TRANSLATIONS_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+pub(crate) fn translations_generation() -> u64 {
+    TRANSLATIONS_GENERATION.load(Ordering::Acquire)
+}
+
+/// 辅助函数：验证 locale 参数（内置语言或已加载的外部语言），不支持时使用中文
+pub fn validate_locale(locale: &str) -> &str {
+    if is_supported_locale(locale) {
+        locale
+    } else {
+        DEFAULT_LOCALE
+    }
+}
+
+/// 是否为内置语言或已加载的外部语言
+pub fn is_supported_locale(locale: &str) -> bool {
+    BUILTIN_LOCALES.contains(&locale)
+        || EXTERNAL_TRANSLATIONS
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains_key(locale)
+}
+
+/// 所有可用语言（内置语言在前）
+pub fn available_locales() -> Vec<String> {
+    let mut locales: Vec<String> = BUILTIN_LOCALES.iter().map(|l| l.to_string()).collect();
+    let mut external: Vec<String> = EXTERNAL_TRANSLATIONS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .keys()
+        .filter(|l| !BUILTIN_LOCALES.contains(l))
+        .map(|l| l.to_string())
+        .collect();
+    external.sort();
+    locales.extend(external);
+    locales
+}
+
+/// 将系统语言（如 "ja-JP"、"zh_CN.UTF-8"、"pt-BR"）匹配到可用语言，无法匹配时返回 None
+pub fn match_system_locale(system_locale: &str) -> Option<String> {
+    let normalized = system_locale
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .replace('_', "-");
+    let available = available_locales();
+
+    // 先完整匹配（如外部加载的 pt-BR），再按主语言匹配
+    available
+        .iter()
+        .find(|l| l.eq_ignore_ascii_case(&normalized))
+        .or_else(|| {
+            let language = normalized.split('-').next().unwrap_or_default();
+            available.iter().find(|l| l.eq_ignore_ascii_case(language))
+        })
+        .cloned()
+}
+
+/// 从目录加载额外的语言文件（<locale>.yml / <locale>.yaml / <locale>.json）
+/// 格式与内置 locales 目录相同；已有语言中的同名 key 会被覆盖，便于独立于版本发布修正翻译
+/// 返回成功加载的语言列表，单个文件解析失败只记录警告
+pub fn load_locale_dir(dir: &Path) -> Result<Vec<String>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut loaded = Vec::new();
+    let entries = fs::read_dir(dir).context(format!("无法读取语言文件目录: {:?}", dir))?;
+
+    for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+        let (Some(locale), Some(ext)) = (
+            path.file_stem().and_then(|s| s.to_str()),
+            path.extension().and_then(|s| s.to_str()),
+        ) else {
+            continue;
+        };
+        if !matches!(ext, "yml" | "yaml" | "json") || !is_valid_locale_name(locale) {
+            continue;
+        }
+
+        match parse_locale_file(&path, ext) {
+            Ok(translations) => {
+                add_translations(locale, translations);
+                log::info!("已加载语言文件: {:?}", path);
+                loaded.push(locale.to_string());
+            }
+            Err(e) => log::warn!("解析语言文件失败: {:?}, 错误: {:#}", path, e),
+        }
+    }
+
+    Ok(loaded)
+}
+
+/// 语言名只允许字母、数字与连字符（如 pt-BR）
+fn is_valid_locale_name(locale: &str) -> bool {
+    !locale.is_empty()
+        && locale.len() <= 16
+        && locale.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+fn parse_locale_file(path: &Path, ext: &str) -> Result<HashMap<String, String>> {
+    let content = fs::read_to_string(path)?;
+    let value: Value = if ext == "json" {
+        serde_json::from_str(&content)?
+    } else {
+        serde_yaml::from_str(&content)?
+    };
+
+    if !value.is_object() {
+        anyhow::bail!("语言文件顶层必须是对象");
+    }
+
+    let mut translations = HashMap::new();
+    flatten_keys("", &value, &mut translations);
+    Ok(translations)
+}
+
+fn flatten_keys(prefix: &str, value: &Value, out: &mut HashMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten_keys(&key, value, out);
+            }
+        }
+        Value::String(text) => {
+            out.insert(prefix.to_string(), text.clone());
+        }
+        Value::Number(_) | Value::Bool(_) => {
+            out.insert(prefix.to_string(), value.to_string());
+        }
+        _ => {}
+    }
+}
+
+fn add_translations(locale: &str, translations: HashMap<String, String>) {
+    let mut all = EXTERNAL_TRANSLATIONS.write().unwrap_or_else(|e| e.into_inner());
+    let locale: &'static str = match all.keys().find(|l| **l == locale) {
+        Some(existing) => existing,
+        None => Box::leak(locale.to_string().into_boxed_str()),
+    };
+
+    let entry = all.entry(locale).or_default();
+    for (key, text) in translations {
+        entry.insert(key, Box::leak(text.into_boxed_str()));
+    }
+    TRANSLATIONS_GENERATION.fetch_add(1, Ordering::AcqRel);
+}
+
+/// rust_i18n 扩展后端：优先使用运行时加载的翻译，找不到时回退到编译期内置的翻译
+pub struct ExternalTranslations;
+
+impl rust_i18n::Backend for ExternalTranslations {
+    fn available_locales(&self) -> Vec<&str> {
+        EXTERNAL_TRANSLATIONS
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys()
+            .copied()
+            .collect()
+    }
+
+    fn translate(&self, locale: &str, key: &str) -> Option<&str> {
+        EXTERNAL_TRANSLATIONS
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(locale)
+            .and_then(|translations| translations.get(key).copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_i18n::t;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_builtin_locales_translate() {
+        for locale in BUILTIN_LOCALES {
+            let text = t!("security.no_issues", locale = locale);
+            assert!(!text.contains("security.no_issues"), "missing translation for {}", locale);
+        }
+        assert_ne!(
+            t!("security.no_issues", locale = "ja"),
+            t!("security.no_issues", locale = "en")
+        );
+    }
+
+    #[test]
+    fn test_rule_texts_localized() {
+        for rule in crate::security::SecurityRules::get_all_patterns() {
+            for locale in ["zh", "en"] {
+                for text in [rule.name(locale), rule.description(locale), rule.remediation(locale)] {
+                    assert!(!text.contains("rules."), "missing {} translation for {}", locale, rule.id);
+                }
+            }
+        }
+
+        let rule = crate::security::SecurityRules::get_all_patterns()
+            .iter()
+            .find(|r| r.id == "RM_RF_ROOT")
+            .unwrap();
+        assert_eq!(rule.name("zh"), "删除根目录");
+        assert_eq!(rule.name("en"), "Delete root directory");
+        // 未提供规则翻译的语言回退到英文
+        assert_eq!(rule.name("ja"), "Delete root directory");
+    }
+
+    #[test]
+    fn test_load_external_locale() {
+        let dir = tempdir().expect("tempdir");
+        fs::write(
+            dir.path().join("pt-BR.yml"),
+            "security:\n  no_issues: \"✅ Nenhum problema de segurança encontrado\"\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("broken.json"), "{ not json").unwrap();
+
+        let loaded = load_locale_dir(dir.path()).unwrap();
+        assert_eq!(loaded, vec!["pt-BR".to_string()]);
+
+        assert_eq!(validate_locale("pt-BR"), "pt-BR");
+        assert_eq!(validate_locale("xx"), DEFAULT_LOCALE);
+        assert_eq!(
+            t!("security.no_issues", locale = "pt-BR"),
+            "✅ Nenhum problema de segurança encontrado"
+        );
+        assert_eq!(match_system_locale("pt_BR.UTF-8").as_deref(), Some("pt-BR"));
+        assert_eq!(match_system_locale("ja-JP").as_deref(), Some("ja"));
+        assert_eq!(match_system_locale("ru-RU"), None);
+    }
+}
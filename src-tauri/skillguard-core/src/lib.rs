@@ -0,0 +1,14 @@
+//! Agent Skills Guard 安全扫描核心：规则、扫描器与报告模型
+//!
+//! 桌面应用与 `skillguard` 命令行工具共用同一套规则，保证 CI 中的检测结果与应用内一致。
+
+// 初始化 i18n：缺失的词条（如规则文本仅有中英文）先回退到英文再回退到中文；运行时加载的语言文件优先于内置翻译
+rust_i18n::i18n!("locales", fallback = ["en", "zh"], backend = crate::i18n::ExternalTranslations);
+
+pub mod i18n;
+pub mod models;
+pub mod paths;
+pub mod security;
+pub mod sarif;
+pub mod policy;
+pub mod report;
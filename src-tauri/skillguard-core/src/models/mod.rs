@@ -0,0 +1,3 @@
+pub mod security;
+
+pub use security::*;
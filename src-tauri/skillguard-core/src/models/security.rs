@@ -0,0 +1,323 @@
+use serde::{Deserialize, Serialize};
+
+/// 安全检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityReport {
+    pub skill_id: String,
+    pub score: i32,
+    pub level: SecurityLevel,
+    pub issues: Vec<SecurityIssue>,
+    pub recommendations: Vec<String>,
+    pub blocked: bool,  // 是否被硬触发规则阻止安装
+    pub hard_trigger_issues: Vec<String>,  // 触发的硬阻止规则列表
+    pub scanned_files: Vec<String>,  // 已扫描的文件列表
+    /// 被用户调整过级别、权重或硬触发标记的规则 ID
+    #[serde(default)]
+    pub modified_rules: Vec<String>,
+    /// 技能可执行的操作（声明的工具与扫描检测到的能力）
+    #[serde(default)]
+    pub capabilities: CapabilitySummary,
+}
+
+impl SecurityReport {
+    /// 按文件汇总问题：包含所有已扫描文件（无问题的文件计数为 0），按严重程度、问题数从高到低排序
+    pub fn file_breakdown(&self) -> Vec<FileIssueSummary> {
+        let mut files: Vec<FileIssueSummary> = Vec::new();
+
+        let paths = self
+            .scanned_files
+            .iter()
+            .chain(self.issues.iter().filter_map(|i| i.file_path.as_ref()));
+        for path in paths {
+            if !files.iter().any(|f| &f.file_path == path) {
+                files.push(FileIssueSummary {
+                    file_path: path.clone(),
+                    issue_count: 0,
+                    max_severity: None,
+                });
+            }
+        }
+
+        for issue in &self.issues {
+            let Some(path) = &issue.file_path else { continue };
+            if let Some(file) = files.iter_mut().find(|f| &f.file_path == path) {
+                file.issue_count += 1;
+                file.max_severity = file.max_severity.max(Some(issue.severity));
+            }
+        }
+
+        files.sort_by(|a, b| {
+            b.max_severity
+                .cmp(&a.max_severity)
+                .then(b.issue_count.cmp(&a.issue_count))
+                .then(a.file_path.cmp(&b.file_path))
+        });
+        files
+    }
+}
+
+/// 单个文件的问题统计
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileIssueSummary {
+    pub file_path: String,
+    pub issue_count: usize,
+    /// 最高严重程度（无问题时为 None）
+    pub max_severity: Option<IssueSeverity>,
+}
+
+/// 一次扫描中命中的硬触发规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardTriggerRecord {
+    pub scanned_at: String,
+    pub issues: Vec<String>,
+}
+
+/// 一次扫描的评分（用于评分趋势图）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreHistoryPoint {
+    pub scanned_at: String,
+    pub score: i32,
+    pub level: SecurityLevel,
+    pub issue_count: usize,
+}
+
+/// 规则命中统计（累计所有保存过的扫描）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleStatistics {
+    pub rule_id: String,
+    /// 本地化的规则名称（未知规则为空）
+    #[serde(default)]
+    pub rule_name: String,
+    /// 累计命中次数
+    pub hit_count: u64,
+    /// 命中该规则的扫描次数
+    pub scan_count: u64,
+    pub last_hit_at: Option<String>,
+    /// 用户反馈的误报次数
+    pub false_positive_count: u64,
+}
+
+/// 用户反馈的误报记录（代码片段已脱敏）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FalsePositiveReport {
+    pub id: i64,
+    pub rule_id: String,
+    pub skill_id: Option<String>,
+    pub file_path: Option<String>,
+    pub snippet: String,
+    pub note: Option<String>,
+    pub reported_at: String,
+}
+
+/// 技能能力（可执行的操作类型），按风险从高到低排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// 执行 shell 命令或启动子进程
+    Shell,
+    /// 提升权限（sudo、修改 sudoers 等）
+    Privilege,
+    /// 持久化（crontab 等）
+    Persistence,
+    /// 访问网络
+    Network,
+    /// 读取敏感文件（密钥、凭据等）
+    SensitiveFiles,
+    /// 写入、修改或删除文件
+    FileWrite,
+    /// 读取文件
+    FileRead,
+}
+
+impl Capability {
+    /// i18n 词条键（security.capabilities.<key>）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Capability::Shell => "shell",
+            Capability::Privilege => "privilege",
+            Capability::Persistence => "persistence",
+            Capability::Network => "network",
+            Capability::SensitiveFiles => "sensitive_files",
+            Capability::FileWrite => "file_write",
+            Capability::FileRead => "file_read",
+        }
+    }
+}
+
+/// 能力摘要：清单声明的工具权限与扫描检测到的能力
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapabilitySummary {
+    /// 清单 frontmatter 中声明的工具（allowed-tools 等）
+    pub allowed_tools: Vec<String>,
+    /// 由声明的工具推断的能力
+    pub declared: Vec<Capability>,
+    /// 扫描检测到的能力
+    pub detected: Vec<Capability>,
+    /// 可读摘要（按扫描语言），如“该技能可以：执行 shell 命令、访问网络”
+    pub summary: String,
+}
+
+/// 安全等级（默认阈值下的分数范围）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SecurityLevel {
+    Safe,      // 90-100
+    Low,       // 70-89
+    Medium,    // 50-69
+    High,      // 30-49
+    Critical,  // 0-29
+}
+
+/// 安全等级的最低分数：不低于 safe 为 Safe，不低于 low 为 Low，以此类推，低于 high 为 Critical
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LevelThresholds {
+    pub safe: i32,
+    pub low: i32,
+    pub medium: i32,
+    pub high: i32,
+}
+
+impl Default for LevelThresholds {
+    fn default() -> Self {
+        Self { safe: 90, low: 70, medium: 50, high: 30 }
+    }
+}
+
+impl LevelThresholds {
+    /// 阈值需在 1-100 之间且从 Safe 到 High 严格递减
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let thresholds = [self.safe, self.low, self.medium, self.high];
+        if thresholds.iter().any(|t| !(1..=100).contains(t)) {
+            anyhow::bail!("安全等级阈值需在 1-100 之间");
+        }
+        if thresholds.windows(2).any(|pair| pair[0] <= pair[1]) {
+            anyhow::bail!(
+                "安全等级阈值需从高到低排列: Safe {} > Low {} > Medium {} > High {}",
+                self.safe, self.low, self.medium, self.high
+            );
+        }
+        Ok(())
+    }
+
+    pub fn level(&self, score: i32) -> SecurityLevel {
+        if score >= self.safe {
+            SecurityLevel::Safe
+        } else if score >= self.low {
+            SecurityLevel::Low
+        } else if score >= self.medium {
+            SecurityLevel::Medium
+        } else if score >= self.high {
+            SecurityLevel::High
+        } else {
+            SecurityLevel::Critical
+        }
+    }
+}
+
+impl SecurityLevel {
+    /// 按默认阈值计算安全等级
+    pub fn from_score(score: i32) -> Self {
+        LevelThresholds::default().level(score)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecurityLevel::Safe => "Safe",
+            SecurityLevel::Low => "Low",
+            SecurityLevel::Medium => "Medium",
+            SecurityLevel::High => "High",
+            SecurityLevel::Critical => "Critical",
+        }
+    }
+}
+
+/// 安全问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityIssue {
+    pub severity: IssueSeverity,
+    pub category: IssueCategory,
+    pub description: String,
+    pub line_number: Option<usize>,
+    pub code_snippet: Option<String>,
+    pub file_path: Option<String>,  // 记录哪个文件有风险
+    /// 命中的规则 ID（非规则产生的提示信息为 None）
+    #[serde(default)]
+    pub rule_id: Option<String>,
+    /// 说明文字中仅被提及（未执行）而降为 Info 的问题，记录其原始级别
+    #[serde(default)]
+    pub original_severity: Option<IssueSeverity>,
+}
+
+/// 问题所在位置的上下文代码（用于带语法高亮的问题展示）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IssueContext {
+    pub file_path: String,
+    pub line_number: usize,
+    pub rule_id: Option<String>,
+    /// 文件扩展名（小写），供前端选择高亮语言
+    pub language: Option<String>,
+    /// 问题行及前后各若干行（密钥已打码）
+    pub lines: Vec<ContextLine>,
+}
+
+/// 上下文中的一行
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContextLine {
+    pub number: usize,
+    pub text: String,
+    /// 命中规则的范围（字符偏移，左闭右开）；只在问题行上设置，无法定位时为整行
+    pub highlight: Option<(usize, usize)>,
+}
+
+/// 问题严重程度（按声明顺序由低到高，可直接比较）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum IssueSeverity {
+    Info,
+    Warning,
+    Error,
+    Critical,
+}
+
+/// 问题分类
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum IssueCategory {
+    FileSystem,         // 文件系统操作
+    Network,            // 网络请求
+    ProcessExecution,   // 进程执行
+    DataExfiltration,   // 数据泄露风险
+    DangerousFunction,  // 危险函数调用
+    ObfuscatedCode,     // 代码混淆
+    Other,
+}
+
+/// Skill 扫描结果（用于前端展示）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillScanResult {
+    pub skill_id: String,
+    pub skill_name: String,
+    pub score: i32,
+    pub level: String,
+    pub scanned_at: String,
+    pub report: SecurityReport,
+    /// 按文件汇总的问题
+    #[serde(default)]
+    pub files: Vec<FileIssueSummary>,
+    /// 历次扫描命中的硬触发规则（从新到旧）
+    #[serde(default)]
+    pub hard_trigger_history: Vec<HardTriggerRecord>,
+}
+
+impl SkillScanResult {
+    pub fn new(skill_id: String, skill_name: String, scanned_at: String, report: SecurityReport) -> Self {
+        Self {
+            skill_id,
+            skill_name,
+            score: report.score,
+            level: report.level.as_str().to_string(),
+            scanned_at,
+            files: report.file_breakdown(),
+            hard_trigger_history: Vec::new(),
+            report,
+        }
+    }
+}
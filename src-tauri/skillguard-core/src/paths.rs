@@ -0,0 +1,85 @@
+//! 跨平台路径处理：Windows 扩展长度路径与保留文件名
+
+use std::path::{Component, Path, PathBuf};
+
+/// Windows 保留的设备名（不区分大小写，带扩展名时同样保留，如 `aux.txt`）
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "CONIN$", "CONOUT$",
+    "COM0", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9", "COM¹", "COM²", "COM³",
+    "LPT0", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9", "LPT¹", "LPT²", "LPT³",
+];
+
+/// Windows 下为绝对路径加上 `\\?\` 前缀，突破 MAX_PATH（260 字符）限制，并允许访问保留名称的文件；
+/// 其他系统原样返回
+#[cfg(windows)]
+pub fn extended_length_path(path: &Path) -> PathBuf {
+    let raw = path.as_os_str().to_string_lossy();
+    if raw.starts_with(r"\\?\") || !path.is_absolute() {
+        return path.to_path_buf();
+    }
+    // 扩展长度路径不做规范化，需统一使用反斜杠
+    let raw = raw.replace('/', "\\");
+    match raw.strip_prefix(r"\\") {
+        Some(unc) => PathBuf::from(format!(r"\\?\UNC\{}", unc)),
+        None => PathBuf::from(format!(r"\\?\{}", raw)),
+    }
+}
+
+#[cfg(not(windows))]
+pub fn extended_length_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+/// 文件名在 Windows 上是否不可用：保留的设备名（含带扩展名的形式），或以空格、`.` 结尾
+pub fn is_windows_reserved_name(name: &str) -> bool {
+    if name.is_empty() || name == "." || name == ".." {
+        return false;
+    }
+    if name.ends_with(' ') || name.ends_with('.') {
+        return true;
+    }
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    WINDOWS_RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(stem))
+}
+
+/// 相对路径中在 Windows 上不可用的第一个路径组成部分
+pub fn windows_reserved_component(relative: &Path) -> Option<String> {
+    relative.components().find_map(|component| match component {
+        Component::Normal(name) => {
+            let name = name.to_string_lossy();
+            is_windows_reserved_name(&name).then(|| name.to_string())
+        }
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_reserved_names() {
+        for name in ["aux", "CON", "nul.txt", "Com1.log", "lpt9", "com¹", "notes.", "trailing "] {
+            assert!(is_windows_reserved_name(name), "{name}");
+        }
+        for name in ["auxiliary", "console.md", "com10", "SKILL.md", ".", "..", ".aux"] {
+            assert!(!is_windows_reserved_name(name), "{name}");
+        }
+        assert_eq!(windows_reserved_component(Path::new("scripts/aux/run.py")), Some("aux".to_string()));
+        assert_eq!(windows_reserved_component(Path::new("scripts/run.py")), None);
+        assert_eq!(extended_length_path(Path::new("relative/path")), PathBuf::from("relative/path"));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_extended_length_path() {
+        assert_eq!(
+            extended_length_path(Path::new(r"C:\Users\me/skills")),
+            PathBuf::from(r"\\?\C:\Users\me\skills")
+        );
+        assert_eq!(
+            extended_length_path(Path::new(r"\\server\share\skills")),
+            PathBuf::from(r"\\?\UNC\server\share\skills")
+        );
+    }
+}
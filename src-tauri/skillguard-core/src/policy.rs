@@ -0,0 +1,130 @@
+use crate::models::security::{IssueSeverity, SecurityReport};
+use serde::{Deserialize, Serialize};
+
+/// CI 退出策略：决定一份扫描报告是否"通过"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExitPolicy {
+    /// 最低安全评分，低于该分数视为不通过
+    pub min_score: Option<i32>,
+    /// 不通过的最低严重程度，存在该级别及以上的问题视为不通过
+    pub fail_on_severity: Option<IssueSeverity>,
+    /// 禁止命中的规则 ID
+    pub blocked_rules: Vec<String>,
+    /// 命中硬触发规则（桌面应用中会禁止安装）时视为不通过
+    pub fail_on_hard_trigger: bool,
+}
+
+impl Default for ExitPolicy {
+    fn default() -> Self {
+        Self {
+            min_score: None,
+            fail_on_severity: None,
+            blocked_rules: Vec::new(),
+            fail_on_hard_trigger: true,
+        }
+    }
+}
+
+/// 违反策略的原因
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PolicyViolation {
+    #[serde(rename_all = "camelCase")]
+    HardTrigger { issue: String },
+    #[serde(rename_all = "camelCase")]
+    BlockedRule { rule_id: String, file: Option<String>, line: Option<usize> },
+    #[serde(rename_all = "camelCase")]
+    SeverityExceeded { rule_id: String, severity: IssueSeverity, file: Option<String>, line: Option<usize> },
+    #[serde(rename_all = "camelCase")]
+    ScoreBelowMinimum { score: i32, min_score: i32 },
+}
+
+impl ExitPolicy {
+    /// 按固定顺序（硬触发、禁用规则、严重程度、评分）检查报告，返回所有违反项
+    pub fn evaluate(&self, report: &SecurityReport) -> Vec<PolicyViolation> {
+        let mut violations = Vec::new();
+
+        if self.fail_on_hard_trigger && report.blocked {
+            violations.extend(
+                report
+                    .hard_trigger_issues
+                    .iter()
+                    .map(|issue| PolicyViolation::HardTrigger { issue: issue.clone() }),
+            );
+        }
+
+        // 只检查规则产生的问题，文件过大、二进制文件等提示信息不参与判断
+        let rule_issues = report
+            .issues
+            .iter()
+            .filter_map(|issue| issue.rule_id.as_ref().map(|rule_id| (rule_id, issue)));
+
+        for (rule_id, issue) in rule_issues {
+            if self.blocked_rules.iter().any(|r| r.eq_ignore_ascii_case(rule_id)) {
+                violations.push(PolicyViolation::BlockedRule {
+                    rule_id: rule_id.clone(),
+                    file: issue.file_path.clone(),
+                    line: issue.line_number,
+                });
+            }
+
+            if self.fail_on_severity.is_some_and(|min| issue.severity >= min) {
+                violations.push(PolicyViolation::SeverityExceeded {
+                    rule_id: rule_id.clone(),
+                    severity: issue.severity,
+                    file: issue.file_path.clone(),
+                    line: issue.line_number,
+                });
+            }
+        }
+
+        if let Some(min_score) = self.min_score {
+            if report.score < min_score {
+                violations.push(PolicyViolation::ScoreBelowMinimum {
+                    score: report.score,
+                    min_score,
+                });
+            }
+        }
+
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::SecurityScanner;
+
+    fn sample_report() -> SecurityReport {
+        let content = r#"
+api_key = "sk-1234567890abcdef1234567890abcdef"
+"#;
+        SecurityScanner::new().scan_file(content, "SKILL.md", "en").unwrap()
+    }
+
+    #[test]
+    fn test_default_policy_passes_without_hard_trigger() {
+        let report = sample_report();
+        assert!(!report.blocked);
+        assert!(ExitPolicy::default().evaluate(&report).is_empty());
+    }
+
+    #[test]
+    fn test_policy_thresholds() {
+        let report = sample_report();
+
+        let policy = ExitPolicy {
+            min_score: Some(100),
+            fail_on_severity: Some(IssueSeverity::Error),
+            blocked_rules: vec!["api_key".to_string()],
+            ..ExitPolicy::default()
+        };
+        let violations = policy.evaluate(&report);
+
+        assert!(violations.iter().any(|v| matches!(v, PolicyViolation::BlockedRule { rule_id, .. } if rule_id == "API_KEY")));
+        assert!(violations.iter().any(|v| matches!(v, PolicyViolation::SeverityExceeded { .. })));
+        assert!(violations.iter().any(|v| matches!(v, PolicyViolation::ScoreBelowMinimum { .. })));
+    }
+}
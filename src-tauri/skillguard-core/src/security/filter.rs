@@ -0,0 +1,127 @@
+use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::path::Path;
+
+/// 默认忽略的目录（版本控制、依赖与构建产物），通常是技能附带的第三方内容
+pub const DEFAULT_IGNORE_PATTERNS: &[&str] = &[
+    ".git",
+    "node_modules",
+    "__pycache__",
+    "dist",
+    "target",
+    "build",
+    ".venv",
+    "venv",
+];
+
+/// 扫描路径过滤：按 glob 模式排除目录/文件，或只扫描匹配的文件
+///
+/// 模式同时与相对扫描根目录的路径（使用 `/` 分隔）和文件名匹配，
+/// 因此 `node_modules` 会忽略任意层级的同名目录，`docs/**` 只忽略根目录下的 docs。
+/// [`DEFAULT_IGNORE_PATTERNS`] 只与目录名匹配，名为 `build` 的脚本文件仍会被扫描
+#[derive(Debug, Clone)]
+pub struct ScanFilter {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+    default_ignores: Option<GlobSet>,
+}
+
+impl ScanFilter {
+    /// 创建过滤规则
+    ///
+    /// * `include` - 只扫描匹配的文件（为空时扫描全部文件）
+    /// * `exclude` - 额外忽略的目录/文件
+    /// * `default_ignores` - 是否同时使用 [`DEFAULT_IGNORE_PATTERNS`]
+    pub fn new(include: &[String], exclude: &[String], default_ignores: bool) -> Result<Self> {
+        let defaults: Vec<String> = DEFAULT_IGNORE_PATTERNS.iter().map(|pattern| pattern.to_string()).collect();
+
+        Ok(Self {
+            include: if include.is_empty() { None } else { Some(build_glob_set(include)?) },
+            exclude: build_glob_set(exclude)?,
+            default_ignores: if default_ignores { Some(build_glob_set(&defaults)?) } else { None },
+        })
+    }
+
+    /// 目录是否被忽略（rel 为相对扫描根目录的路径）
+    pub fn is_excluded(&self, rel: &Path) -> bool {
+        matches_path(&self.exclude, rel) || self.in_ignored_dir(rel)
+    }
+
+    /// 文件是否需要扫描：未被忽略，且在指定了 include 时至少匹配一个模式
+    pub fn should_scan_file(&self, rel: &Path) -> bool {
+        let ignored = matches_path(&self.exclude, rel) || rel.parent().is_some_and(|parent| self.in_ignored_dir(parent));
+        !ignored && self.include.as_ref().is_none_or(|include| matches_path(include, rel))
+    }
+
+    /// 目录路径的任意一级是否为默认忽略的目录
+    fn in_ignored_dir(&self, dir: &Path) -> bool {
+        self.default_ignores
+            .as_ref()
+            .is_some_and(|defaults| dir.components().any(|component| defaults.is_match(component.as_os_str())))
+    }
+}
+
+impl Default for ScanFilter {
+    fn default() -> Self {
+        Self::new(&[], &[], true).expect("默认忽略模式无效")
+    }
+}
+
+fn build_glob_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        // 允许 "node_modules/" 这类以分隔符结尾的写法
+        let pattern = pattern.trim_end_matches('/');
+        builder.add(Glob::new(pattern).with_context(|| format!("无效的匹配模式: {}", pattern))?);
+    }
+    builder.build().context("构建匹配模式失败")
+}
+
+fn matches_path(set: &GlobSet, rel: &Path) -> bool {
+    set.is_match(rel) || rel.file_name().is_some_and(|name| set.is_match(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_filter_patterns() {
+        let filter = ScanFilter::default();
+        assert!(filter.is_excluded(Path::new("node_modules")));
+        assert!(filter.is_excluded(Path::new("vendor/pkg/node_modules")));
+        assert!(filter.should_scan_file(Path::new("scripts/run.sh")));
+        assert!(!filter.should_scan_file(Path::new("dist/bundle.js")));
+        assert!(!filter.should_scan_file(Path::new("src/target/debug/run.sh")));
+
+        let filter = ScanFilter::new(
+            &["*.sh".to_string(), "SKILL.md".to_string()],
+            &["tests/**".to_string(), "*.min.js".to_string()],
+            false,
+        )
+        .unwrap();
+        assert!(!filter.is_excluded(Path::new("node_modules")));
+        assert!(filter.should_scan_file(Path::new("SKILL.md")));
+        assert!(filter.should_scan_file(Path::new("scripts/run.sh")));
+        assert!(!filter.should_scan_file(Path::new("README.md")));
+        assert!(!filter.should_scan_file(Path::new("tests/run.sh")));
+        assert!(filter.is_excluded(Path::new("assets/app.min.js")));
+
+        assert!(ScanFilter::new(&[], &["[".to_string()], true).is_err());
+    }
+
+    #[test]
+    fn test_default_ignores_only_match_directories() {
+        // 与默认忽略目录同名的脚本文件仍需扫描
+        let filter = ScanFilter::default();
+        assert!(filter.is_excluded(Path::new("build")));
+        assert!(filter.should_scan_file(Path::new("build")));
+        assert!(filter.should_scan_file(Path::new("scripts/dist")));
+        assert!(filter.should_scan_file(Path::new("venv")));
+        assert!(!filter.should_scan_file(Path::new("build/build")));
+
+        // 用户指定的忽略模式仍同时匹配目录与文件
+        let filter = ScanFilter::new(&[], &["build".to_string()], true).unwrap();
+        assert!(!filter.should_scan_file(Path::new("build")));
+    }
+}
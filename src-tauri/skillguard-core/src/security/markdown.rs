@@ -0,0 +1,129 @@
+use crate::security::rules::Language;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::ops::Range;
+
+lazy_static! {
+    /// 提醒读者不要执行的警告语句（"do NOT run"、"切勿执行"）
+    static ref DO_NOT_RUN: Regex = Regex::new(
+        r"(?i)\b(do\s+not|don't|never|avoid)\s+(run|running|execute|executing|paste|type)\b|(不要|切勿|请勿|禁止)(运行|执行|粘贴)"
+    )
+    .expect("Invalid regex pattern");
+}
+
+/// Markdown 文件中一行所在的区域
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MarkdownLine {
+    /// 说明文字（代码块之外）
+    Prose,
+    /// 代码块的起止标记行
+    Fence,
+    /// 代码块内容；语言未标注或无法识别时为 None
+    Code(Option<Language>),
+}
+
+/// 逐行跟踪 Markdown 的围栏代码块（``` 或 ~~~，闭合标记不短于起始标记），
+/// 流式扫描时无需读入整个文件；未闭合的代码块延续到文件末尾
+#[derive(Debug, Default)]
+pub(crate) struct MarkdownTracker {
+    /// 当前代码块的标记字符、标记长度与语言
+    open: Option<(char, usize, Option<Language>)>,
+}
+
+impl MarkdownTracker {
+    pub(crate) fn next_line(&mut self, line: &str) -> MarkdownLine {
+        let trimmed = line.trim_start();
+
+        if let Some((fence, length, language)) = self.open {
+            let closing = trimmed.trim_end();
+            if closing.len() >= length && closing.chars().all(|c| c == fence) {
+                self.open = None;
+                return MarkdownLine::Fence;
+            }
+            return MarkdownLine::Code(language);
+        }
+
+        for fence in ['`', '~'] {
+            let length = trimmed.chars().take_while(|&c| c == fence).count();
+            if length >= 3 {
+                let info = trimmed[length..].split_whitespace().next().unwrap_or_default();
+                self.open = Some((fence, length, Language::from_fence_info(info)));
+                return MarkdownLine::Fence;
+            }
+        }
+        MarkdownLine::Prose
+    }
+}
+
+/// 说明文字中只是"提及"而非要求执行的位置：引用块、"不要运行"之类的警告语句，
+/// 或命中范围（字节偏移）完全位于行内代码中
+pub(crate) fn is_mention(line: &str, span: Range<usize>) -> bool {
+    line.trim_start().starts_with('>')
+        || DO_NOT_RUN.is_match(line)
+        || inline_code_spans(line).iter().any(|code| code.start <= span.start && span.end <= code.end)
+}
+
+/// 行内代码（成对的等长反引号）内容的字节范围
+fn inline_code_spans(line: &str) -> Vec<Range<usize>> {
+    let bytes = line.as_bytes();
+    let mut spans = Vec::new();
+    // 未闭合的行内代码：内容起始位置与反引号个数
+    let mut open: Option<(usize, usize)> = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] != b'`' {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < bytes.len() && bytes[i] == b'`' {
+            i += 1;
+        }
+        match open {
+            Some((content_start, length)) if length == i - start => {
+                spans.push(content_start..start);
+                open = None;
+            }
+            Some(_) => {}
+            None => open = Some((i, i - start)),
+        }
+    }
+    spans
+}
+
+pub(crate) fn is_markdown(path: &str) -> bool {
+    path.rsplit(['/', '\\'])
+        .next()
+        .and_then(|name| name.rsplit_once('.'))
+        .is_some_and(|(_, ext)| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_tracker() {
+        let content = "Run it:\n````bash title=\"x\"\n```\nsudo ls\n````\ntext\n~~~PY\nimport os\n";
+        let mut tracker = MarkdownTracker::default();
+        let regions: Vec<_> = content.lines().map(|line| tracker.next_line(line)).collect();
+        assert_eq!(regions, vec![
+            MarkdownLine::Prose,
+            MarkdownLine::Fence,
+            MarkdownLine::Code(Some(Language::Shell)),
+            MarkdownLine::Code(Some(Language::Shell)),
+            MarkdownLine::Fence,
+            MarkdownLine::Prose,
+            MarkdownLine::Fence,
+            MarkdownLine::Code(Some(Language::Python)),
+        ]);
+        assert!(is_markdown("docs/SKILL.md") && !is_markdown("run.sh"));
+
+        let line = "Use `sudo` only with ``a ` b``, never sudo";
+        assert!(is_mention(line, 5..9));
+        assert!(!is_mention(line, 38..42));
+        assert!(is_mention("> sudo rm x", 2..6));
+        assert!(is_mention("Do NOT run sudo here", 11..15));
+    }
+}
@@ -0,0 +1,34 @@
+mod capabilities;
+mod catalog;
+mod context;
+mod endpoints;
+mod filter;
+mod markdown;
+pub mod prompt_injection;
+mod python_calls;
+mod scanner;
+mod shell_ast;
+pub mod rules;
+
+pub use capabilities::parse_allowed_tools;
+pub use context::issue_context;
+pub use endpoints::{EndpointAllowlist, UNDECLARED_ENDPOINT_RULE_ID};
+pub use filter::{ScanFilter, DEFAULT_IGNORE_PATTERNS};
+pub use scanner::{path_checksum, path_checksum_renamed, FileChanges, IncrementalScan, ScanCache, SecurityScanner, DEFAULT_MAX_FILE_SIZE};
+pub use rules::{RuleOverride, SecurityRules, PROTECTED_RULES};
+
+use crate::models::security::*;
+use anyhow::Result;
+
+/// 语法分析（shell 脚本、Python 调用）发现的危险调用：命中的内置规则 ID 与所在行（1 起始）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StructuralFinding {
+    pub rule_id: &'static str,
+    pub line_number: usize,
+}
+
+/// 安全检查器特征
+pub trait SecurityChecker {
+    fn scan_file(&self, content: &str, file_path: &str) -> Result<SecurityReport>;
+    fn calculate_score(&self, issues: &[SecurityIssue]) -> i32;
+}
@@ -0,0 +1,497 @@
+use super::StructuralFinding;
+use std::collections::HashMap;
+
+/// 字符串字面量前缀（不区分大小写）
+const STRING_PREFIXES: &[&str] = &["r", "u", "b", "f", "br", "rb", "fr", "rf"];
+/// 未被重新绑定时按内置函数解析的名称
+const BUILTINS: &[&str] = &["eval", "exec", "getattr", "__import__"];
+/// `from <module> import *` 时绑定的危险函数
+const STAR_EXPORTS: &[(&str, &[&str])] = &[
+    ("os", &["system", "popen"]),
+    ("subprocess", &["run", "call", "Popen", "check_call", "check_output", "getoutput", "getstatusoutput"]),
+];
+/// `getattr(getattr(...), ...)` 的最大嵌套解析深度
+const MAX_GETATTR_DEPTH: usize = 8;
+
+/// 文件是否为需要做调用分析的 Python 脚本
+pub(crate) fn is_analyzable(path: &str) -> bool {
+    path.rsplit(['/', '\\'])
+        .next()
+        .and_then(|name| name.rsplit_once('.'))
+        .is_some_and(|(_, ext)| ext.eq_ignore_ascii_case("py"))
+}
+
+/// 分析 Python 脚本中的危险调用：跟踪 import 别名（`import os as o`、`from os import system as s`）、
+/// 简单的赋值别名（`run = os.system`）与 `__import__("os")`、`getattr(os, "system")` 等动态取值，
+/// 按解析后的完整名称识别 eval/exec/os.system 以及跨行传入 shell=True 的 subprocess 调用。
+/// 注释与字符串中的内容不会被当作调用
+pub(crate) fn analyze(source: &str) -> Vec<StructuralFinding> {
+    let mut analyzer = Analyzer {
+        tokens: tokenize(source),
+        aliases: HashMap::new(),
+        findings: Vec::new(),
+    };
+    analyzer.run();
+    analyzer.findings
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Kind {
+    /// 标识符或数字
+    Word(String),
+    /// 字符串字面量的内容（不含前缀与引号，转义保持原样）
+    Str(String),
+    Op(String),
+    /// 逻辑行结束（括号外的换行、`;` 与 `:`）
+    Newline,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: Kind,
+    line: usize,
+}
+
+/// 轻量词法分析：跳过注释，完整读取（含三引号）字符串，括号内的换行不结束逻辑行
+fn tokenize(source: &str) -> Vec<Token> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\n' => {
+                if depth == 0 {
+                    tokens.push(Token { kind: Kind::Newline, line });
+                }
+                line += 1;
+                i += 1;
+            }
+            '#' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '\\' if chars.get(i + 1) == Some(&'\n') => {
+                line += 1;
+                i += 2;
+            }
+            '\'' | '"' => {
+                let start_line = line;
+                let (value, end) = read_string(&chars, i, &mut line);
+                tokens.push(Token { kind: Kind::Str(value), line: start_line });
+                i = end;
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                if matches!(chars.get(i), Some('\'' | '"')) && STRING_PREFIXES.contains(&word.to_ascii_lowercase().as_str()) {
+                    let start_line = line;
+                    let (value, end) = read_string(&chars, i, &mut line);
+                    tokens.push(Token { kind: Kind::Str(value), line: start_line });
+                    i = end;
+                } else {
+                    tokens.push(Token { kind: Kind::Word(word), line });
+                }
+            }
+            '(' | '[' | '{' => {
+                depth += 1;
+                tokens.push(Token { kind: Kind::Op(c.to_string()), line });
+                i += 1;
+            }
+            ')' | ']' | '}' => {
+                depth = depth.saturating_sub(1);
+                tokens.push(Token { kind: Kind::Op(c.to_string()), line });
+                i += 1;
+            }
+            ';' | ':' if depth == 0 => {
+                tokens.push(Token { kind: Kind::Newline, line });
+                i += 1;
+            }
+            '.' | ',' | ';' | ':' => {
+                tokens.push(Token { kind: Kind::Op(c.to_string()), line });
+                i += 1;
+            }
+            c if is_operator_char(c) => {
+                let start = i;
+                while i < chars.len() && is_operator_char(chars[i]) {
+                    i += 1;
+                }
+                tokens.push(Token { kind: Kind::Op(chars[start..i].iter().collect()), line });
+            }
+            _ => i += 1,
+        }
+    }
+    tokens
+}
+
+fn is_operator_char(c: char) -> bool {
+    "=<>!+-*/%&|^~@".contains(c)
+}
+
+/// 读取从 start（引号位置）开始的字符串，返回内容与结束位置；单引号字符串遇到换行视为结束
+fn read_string(chars: &[char], start: usize, line: &mut usize) -> (String, usize) {
+    let quote = chars[start];
+    let triple = chars.get(start + 1) == Some(&quote) && chars.get(start + 2) == Some(&quote);
+    let mut i = if triple { start + 3 } else { start + 1 };
+    let mut value = String::new();
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\\' {
+            value.push(c);
+            if let Some(&next) = chars.get(i + 1) {
+                if next == '\n' {
+                    *line += 1;
+                }
+                value.push(next);
+            }
+            i += 2;
+            continue;
+        }
+        if c == quote && (!triple || (chars.get(i + 1) == Some(&quote) && chars.get(i + 2) == Some(&quote))) {
+            return (value, if triple { i + 3 } else { i + 1 });
+        }
+        if c == '\n' {
+            if !triple {
+                return (value, i);
+            }
+            *line += 1;
+        }
+        value.push(c);
+        i += 1;
+    }
+    (value, i)
+}
+
+struct Analyzer {
+    tokens: Vec<Token>,
+    /// 本地名称 → 完整名称（如 `o` → `os`、`s` → `os.system`）
+    aliases: HashMap<String, String>,
+    findings: Vec<StructuralFinding>,
+}
+
+impl Analyzer {
+    fn run(&mut self) {
+        let mut i = 0;
+        let mut statement_start = true;
+
+        while i < self.tokens.len() {
+            if self.tokens[i].kind == Kind::Newline {
+                statement_start = true;
+                i += 1;
+                continue;
+            }
+            if statement_start {
+                if self.is_word(i, "import") {
+                    i = self.parse_import(i + 1);
+                    continue;
+                }
+                if self.is_word(i, "from") {
+                    i = self.parse_from_import(i + 1);
+                    continue;
+                }
+                if let Some(next) = self.parse_alias_assignment(i) {
+                    i = next;
+                    continue;
+                }
+            }
+            statement_start = false;
+
+            let follows_attribute = i > 0
+                && (self.is_op(i - 1, ".") || ["def", "class"].iter().any(|keyword| self.is_word(i - 1, keyword)));
+            if follows_attribute {
+                i += 1;
+                continue;
+            }
+            match self.primary(i, 0) {
+                Some((qualified, end)) => {
+                    if self.is_op(end, "(") {
+                        if let Some(rule_id) = self.call_rule(&qualified, end) {
+                            self.push(rule_id, self.tokens[i].line);
+                        }
+                    }
+                    i = end.max(i + 1);
+                }
+                None => i += 1,
+            }
+        }
+    }
+
+    fn push(&mut self, rule_id: &'static str, line_number: usize) {
+        let finding = StructuralFinding { rule_id, line_number };
+        if !self.findings.contains(&finding) {
+            self.findings.push(finding);
+        }
+    }
+
+    fn word(&self, i: usize) -> Option<&str> {
+        match self.tokens.get(i).map(|token| &token.kind) {
+            Some(Kind::Word(word)) => Some(word),
+            _ => None,
+        }
+    }
+
+    fn string(&self, i: usize) -> Option<&str> {
+        match self.tokens.get(i).map(|token| &token.kind) {
+            Some(Kind::Str(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn is_word(&self, i: usize, expected: &str) -> bool {
+        self.word(i) == Some(expected)
+    }
+
+    fn is_op(&self, i: usize, expected: &str) -> bool {
+        matches!(self.tokens.get(i).map(|token| &token.kind), Some(Kind::Op(op)) if op == expected)
+    }
+
+    fn is_statement_end(&self, i: usize) -> bool {
+        self.tokens.get(i).is_none_or(|token| token.kind == Kind::Newline)
+    }
+
+    /// 点分名称（`os.path`），返回名称与结束位置
+    fn dotted_name(&self, mut i: usize) -> Option<(String, usize)> {
+        let mut name = self.word(i)?.to_string();
+        i += 1;
+        while self.is_op(i, ".") {
+            let Some(part) = self.word(i + 1) else {
+                break;
+            };
+            name.push('.');
+            name.push_str(part);
+            i += 2;
+        }
+        Some((name, i))
+    }
+
+    /// `import a.b as c, d`
+    fn parse_import(&mut self, mut i: usize) -> usize {
+        while let Some((module, end)) = self.dotted_name(i) {
+            i = end;
+            if self.is_word(i, "as") {
+                if let Some(alias) = self.word(i + 1).map(str::to_string) {
+                    self.aliases.insert(alias, module);
+                }
+                i += 2;
+            } else {
+                let head = module.split('.').next().unwrap_or_default().to_string();
+                self.aliases.insert(head.clone(), head);
+            }
+            if !self.is_op(i, ",") {
+                break;
+            }
+            i += 1;
+        }
+        self.skip_statement(i)
+    }
+
+    /// `from a.b import (c as d, e)`、`from os import *`
+    fn parse_from_import(&mut self, mut i: usize) -> usize {
+        while self.is_op(i, ".") {
+            i += 1;
+        }
+        let Some((module, end)) = self.dotted_name(i) else {
+            return self.skip_statement(i);
+        };
+        i = end;
+        if !self.is_word(i, "import") {
+            return self.skip_statement(i);
+        }
+        i += 1;
+        if self.is_op(i, "(") {
+            i += 1;
+        }
+
+        if self.is_op(i, "*") {
+            for (_, names) in STAR_EXPORTS.iter().filter(|(exporter, _)| *exporter == module) {
+                for name in names.iter() {
+                    self.aliases.insert(name.to_string(), format!("{}.{}", module, name));
+                }
+            }
+            return self.skip_statement(i);
+        }
+
+        while let Some(name) = self.word(i).map(str::to_string) {
+            i += 1;
+            let local = match self.word(i + 1) {
+                Some(alias) if self.is_word(i, "as") => {
+                    i += 2;
+                    alias.to_string()
+                }
+                _ => name.clone(),
+            };
+            self.aliases.insert(local, format!("{}.{}", module, name));
+            if !self.is_op(i, ",") {
+                break;
+            }
+            i += 1;
+        }
+        self.skip_statement(i)
+    }
+
+    /// `name = <可解析的名称>` 记录别名；其他对 name 的赋值会清除已有别名。
+    /// 只有记录了别名时返回语句结束位置，其余情况继续按普通语句扫描右侧的调用
+    fn parse_alias_assignment(&mut self, i: usize) -> Option<usize> {
+        let name = self.word(i)?.to_string();
+        if !self.is_op(i + 1, "=") {
+            return None;
+        }
+        match self.primary(i + 2, 0) {
+            Some((qualified, end)) if self.is_statement_end(end) => {
+                self.aliases.insert(name, qualified);
+                Some(end)
+            }
+            _ => {
+                self.aliases.remove(&name);
+                None
+            }
+        }
+    }
+
+    fn skip_statement(&self, mut i: usize) -> usize {
+        while !self.is_statement_end(i) {
+            i += 1;
+        }
+        i
+    }
+
+    fn resolve_name(&self, name: &str) -> String {
+        match self.aliases.get(name) {
+            Some(qualified) => qualified.clone(),
+            None if BUILTINS.contains(&name) => format!("builtins.{}", name),
+            None => name.to_string(),
+        }
+    }
+
+    /// 解析从 i 开始的表达式的完整名称：名称与属性访问，以及 `__import__("os")`、
+    /// `importlib.import_module("os")`、`getattr(<表达式>, "attr")` 形式的动态取值
+    fn primary(&self, start: usize, depth: usize) -> Option<(String, usize)> {
+        let mut qualified = self.resolve_name(self.word(start)?);
+        let mut i = start + 1;
+
+        loop {
+            if self.is_op(i, ".") {
+                if let Some(attribute) = self.word(i + 1) {
+                    qualified = format!("{}.{}", qualified, attribute);
+                    i += 2;
+                    continue;
+                }
+            }
+            if self.is_op(i, "(") {
+                if matches!(qualified.as_str(), "builtins.__import__" | "importlib.import_module") {
+                    if let (Some(module), true) = (self.string(i + 1), self.is_op(i + 2, ")")) {
+                        qualified = module.to_string();
+                        i += 3;
+                        continue;
+                    }
+                }
+                if qualified == "builtins.getattr" && depth < MAX_GETATTR_DEPTH {
+                    if let Some((target, end)) = self.primary(i + 1, depth + 1) {
+                        if let (true, Some(attribute), true) = (self.is_op(end, ","), self.string(end + 1), self.is_op(end + 2, ")")) {
+                            qualified = format!("{}.{}", target, attribute);
+                            i = end + 3;
+                            continue;
+                        }
+                    }
+                }
+            }
+            break;
+        }
+        Some((qualified, i))
+    }
+
+    /// 调用对应的规则；open 为调用左括号的位置
+    fn call_rule(&self, qualified: &str, open: usize) -> Option<&'static str> {
+        match qualified {
+            "builtins.eval" => Some("PY_EVAL"),
+            "builtins.exec" => Some("PY_EXEC"),
+            "os.system" | "os.popen" => Some("OS_SYSTEM"),
+            "subprocess.getoutput" | "subprocess.getstatusoutput" => Some("SUBPROCESS_SHELL"),
+            "subprocess.run" | "subprocess.call" | "subprocess.Popen" | "subprocess.check_call" | "subprocess.check_output"
+                if self.passes_shell_true(open) =>
+            {
+                Some("SUBPROCESS_SHELL")
+            }
+            _ => None,
+        }
+    }
+
+    /// 调用参数（可跨多行）中是否直接传入 shell=True
+    fn passes_shell_true(&self, open: usize) -> bool {
+        let mut depth = 0usize;
+        for i in open..self.tokens.len() {
+            match &self.tokens[i].kind {
+                Kind::Op(op) if matches!(op.as_str(), "(" | "[" | "{") => depth += 1,
+                Kind::Op(op) if matches!(op.as_str(), ")" | "]" | "}") => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return false;
+                    }
+                }
+                Kind::Word(word)
+                    if depth == 1
+                        && word == "shell"
+                        && self.is_op(i + 1, "=")
+                        && self.word(i + 2).is_some_and(|value| value == "True" || value == "1") =>
+                {
+                    return true;
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule_ids(source: &str) -> Vec<(&'static str, usize)> {
+        analyze(source).into_iter().map(|f| (f.rule_id, f.line_number)).collect()
+    }
+
+    #[test]
+    fn test_python_call_aliases() {
+        let source = r#"import os as o, subprocess as sp
+from subprocess import (
+    Popen as P,
+)
+from builtins import eval as evaluate
+run = o.system
+
+# o.system("in a comment")
+print("eval(not code)")
+run("id")
+sp.run(
+    cmd,
+    shell=True,
+)
+P(["ls"], shell=False)
+evaluate(payload)
+getattr(__import__("os"), "popen")("id")
+obj.system("ok"); ex = exec
+ex(code)
+"#;
+        assert_eq!(rule_ids(source), vec![
+            ("OS_SYSTEM", 10),
+            ("SUBPROCESS_SHELL", 11),
+            ("PY_EVAL", 16),
+            ("OS_SYSTEM", 17),
+            ("PY_EXEC", 19),
+        ]);
+
+        // 重新赋值后别名失效
+        assert!(rule_ids("import os\nrun = os.system\nrun = print\nrun('x')\n").is_empty());
+        assert!(is_analyzable("scripts/Setup.PY"));
+        assert!(!is_analyzable("setup.pyc"));
+    }
+}
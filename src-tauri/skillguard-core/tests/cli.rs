@@ -0,0 +1,42 @@
+//! skillguard 命令行的退出码测试
+
+use std::path::Path;
+use std::process::Command;
+
+/// 只包含一个指定严重程度问题的技能目录：(--fail-on 对应的级别, 文件名, 内容)
+const FIXTURES: &[(&str, &str, &str)] = &[
+    ("low", "client.py", "import requests\nrequests.get(URL)\n"),
+    ("medium", "client.py", "import urllib.request\nurllib.request.urlopen(URL)\n"),
+    ("high", "run.sh", "chmod 777 /tmp/data\n"),
+    ("critical", "run.sh", "rm -rf /\n"),
+];
+
+const LEVELS: &[&str] = &["low", "medium", "high", "critical"];
+
+fn scan(dir: &Path, fail_on: &str) -> i32 {
+    Command::new(env!("CARGO_BIN_EXE_skillguard"))
+        .arg("scan")
+        .arg(dir)
+        .args(["--fail-on", fail_on, "--allow-hard-triggers", "--quiet"])
+        .output()
+        .expect("run skillguard")
+        .status
+        .code()
+        .expect("exit code")
+}
+
+#[test]
+fn test_fail_on_fails_at_the_level_and_above() {
+    let root = tempfile::tempdir().expect("tempdir");
+
+    for (level, (issue_level, file, content)) in FIXTURES.iter().enumerate() {
+        let dir = root.path().join(issue_level);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(file), content).unwrap();
+
+        for (threshold, fail_on) in LEVELS.iter().enumerate() {
+            let expected = if level >= threshold { 1 } else { 0 };
+            assert_eq!(scan(&dir, fail_on), expected, "{} issue with --fail-on {}", issue_level, fail_on);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use crate::commands::AppState;
+use crate::models::{AppError, ErrorKind};
+use crate::services::settings::UpdateChannel;
+use serde::Serialize;
+use tauri::{Emitter, State};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+/// 稳定版更新清单（GitHub 最新正式发布）
+const STABLE_ENDPOINT: &str =
+    "https://github.com/tanaer/agent-skills-guard-pro/releases/latest/download/latest.json";
+/// 测试版更新清单（固定的 updater-beta 预发布，每次发布测试版时覆盖）
+const BETA_ENDPOINT: &str =
+    "https://github.com/tanaer/agent-skills-guard-pro/releases/download/updater-beta/latest.json";
+
+/// 可用的应用更新
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppUpdateInfo {
+    pub current_version: String,
+    pub version: String,
+    pub channel: UpdateChannel,
+    pub date: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// 下载进度（app-update-progress 事件）
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AppUpdateProgress {
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+fn channel_endpoint(channel: UpdateChannel) -> &'static str {
+    match channel {
+        UpdateChannel::Stable => STABLE_ENDPOINT,
+        UpdateChannel::Beta => BETA_ENDPOINT,
+    }
+}
+
+/// 按所选渠道检查更新
+/// 更新包的签名在下载后使用 tauri.conf.json 中配置的公钥校验，校验失败时拒绝安装
+async fn find_update(app: &tauri::AppHandle, channel: UpdateChannel) -> Result<Option<Update>, AppError> {
+    let endpoint = tauri::Url::parse(channel_endpoint(channel)).map_err(|e| AppError::internal(e.to_string()))?;
+
+    app.updater_builder()
+        .endpoints(vec![endpoint])
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .build()
+        .map_err(|e| AppError::internal(format!("初始化更新检查失败: {}", e)))?
+        .check()
+        .await
+        .map_err(|e| AppError::new(ErrorKind::Network, format!("检查应用更新失败: {}", e)))
+}
+
+/// 检查应用更新，没有新版本时返回 None
+pub(crate) async fn check_app_update(
+    app: &tauri::AppHandle,
+    channel: UpdateChannel,
+) -> Result<Option<AppUpdateInfo>, AppError> {
+    let update = find_update(app, channel).await?;
+
+    Ok(update.map(|update| AppUpdateInfo {
+        current_version: update.current_version,
+        version: update.version,
+        channel,
+        date: update.date.map(|date| date.to_string()),
+        notes: update.body,
+    }))
+}
+
+/// 按设置中的更新渠道检查应用更新
+#[tauri::command]
+pub async fn check_for_app_updates(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<AppUpdateInfo>, AppError> {
+    let channel = state.settings.get().updates.channel;
+    check_app_update(&app, channel).await
+}
+
+/// 下载并安装应用更新，签名校验通过后重启应用
+/// 下载过程中发送 app-update-progress 事件
+#[tauri::command]
+pub async fn install_app_update(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let channel = state.settings.get().updates.channel;
+    let update = find_update(&app, channel)
+        .await?
+        .ok_or_else(|| AppError::not_found("当前已是最新版本"))?;
+
+    log::info!("开始下载应用更新: {} -> {}", update.current_version, update.version);
+
+    let mut downloaded = 0u64;
+    update
+        .download_and_install(
+            |chunk_length, total| {
+                downloaded += chunk_length as u64;
+                let _ = app.emit("app-update-progress", AppUpdateProgress { downloaded, total });
+            },
+            || log::info!("应用更新下载完成，正在校验签名并安装"),
+        )
+        .await
+        .map_err(|e| AppError::internal(format!("安装应用更新失败: {}", e)))?;
+
+    log::info!("应用更新已安装，正在重启");
+    app.request_restart();
+    Ok(())
+}
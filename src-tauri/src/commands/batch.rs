@@ -0,0 +1,223 @@
+use crate::commands::security::scan_installed_skill;
+use crate::commands::AppState;
+use crate::i18n::validate_locale;
+use crate::models::AppError;
+use crate::services::impact::UninstallConfirmation;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, State};
+
+/// 单次批量操作的技能数量上限
+const MAX_BATCH_SIZE: usize = 500;
+/// 标签的最大长度（字符）
+const MAX_TAG_LENGTH: usize = 40;
+
+/// 批量操作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchOperation {
+    Uninstall,
+    Rescan,
+    Tag,
+    DeleteRecord,
+}
+
+/// 批量操作中单个技能的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchItemResult {
+    pub skill_id: String,
+    /// 失败时的错误，成功时为 None
+    pub error: Option<AppError>,
+}
+
+/// 批量操作进度（batch-progress 事件），每处理完一个技能发送一次
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchProgress {
+    pub operation: BatchOperation,
+    pub completed: usize,
+    pub total: usize,
+    pub skill_id: String,
+    pub error: Option<AppError>,
+}
+
+/// 逐个记录批量操作的结果并发送进度
+struct BatchRun<'a> {
+    app: &'a AppHandle,
+    operation: BatchOperation,
+    total: usize,
+    results: Vec<BatchItemResult>,
+}
+
+impl<'a> BatchRun<'a> {
+    fn new(app: &'a AppHandle, operation: BatchOperation, total: usize) -> Self {
+        Self { app, operation, total, results: Vec::with_capacity(total) }
+    }
+
+    fn record(&mut self, skill_id: String, result: Result<(), AppError>) {
+        let error = result.err();
+        if let Some(error) = &error {
+            log::warn!("批量操作 {:?} 失败: {}, 错误: {}", self.operation, skill_id, error.message);
+        }
+
+        let progress = BatchProgress {
+            operation: self.operation,
+            completed: self.results.len() + 1,
+            total: self.total,
+            skill_id: skill_id.clone(),
+            error: error.clone(),
+        };
+        if let Err(e) = self.app.emit("batch-progress", &progress) {
+            log::warn!("发送 batch-progress 事件失败: {}", e);
+        }
+        self.results.push(BatchItemResult { skill_id, error });
+    }
+
+    fn finish(self) -> Vec<BatchItemResult> {
+        let failed = self.results.iter().filter(|result| result.error.is_some()).count();
+        log::info!("批量操作 {:?} 完成: {} 个技能，{} 个失败", self.operation, self.results.len(), failed);
+        self.results
+    }
+}
+
+/// 去除重复的技能 ID（保持顺序），并检查数量上限
+fn batch_ids(skill_ids: Vec<String>) -> Result<Vec<String>, AppError> {
+    let mut unique: Vec<String> = Vec::with_capacity(skill_ids.len());
+    for skill_id in skill_ids {
+        if !unique.contains(&skill_id) {
+            unique.push(skill_id);
+        }
+    }
+    if unique.len() > MAX_BATCH_SIZE {
+        return Err(AppError::invalid_input(format!("一次最多处理 {} 个技能", MAX_BATCH_SIZE)));
+    }
+    Ok(unique)
+}
+
+/// 规范化标签：去除首尾空白与重复项，拒绝空标签与过长的标签
+fn normalize_tags(tags: Vec<String>) -> Result<Vec<String>, AppError> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim().to_string();
+        if tag.is_empty() {
+            return Err(AppError::invalid_input("标签不能为空"));
+        }
+        if tag.chars().count() > MAX_TAG_LENGTH {
+            return Err(AppError::invalid_input(format!("标签不能超过 {} 个字符: {}", MAX_TAG_LENGTH, tag)));
+        }
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    Ok(normalized)
+}
+
+/// 批量卸载技能（影响确认同 uninstall_skill，对所有技能生效）；单个技能失败不影响其他技能
+#[tauri::command]
+pub async fn uninstall_skills(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    skill_ids: Vec<String>,
+    confirm: Option<UninstallConfirmation>,
+) -> Result<Vec<BatchItemResult>, AppError> {
+    let skill_ids = batch_ids(skill_ids)?;
+    let confirm = confirm.unwrap_or_default();
+    let manager = &state.skill_manager;
+    let _operation = manager.begin_operation().await;
+
+    let mut batch = BatchRun::new(&app, BatchOperation::Uninstall, skill_ids.len());
+    for skill_id in skill_ids {
+        let id = skill_id.clone();
+        let result = manager.run_blocking(move |manager| {
+            manager.uninstall_impact(&id, None)?.check_confirmed(&confirm)?;
+            manager.uninstall_skill(&id)
+        }).await;
+        batch.record(skill_id, result.map_err(AppError::from));
+    }
+    state.notify_status_changed();
+    Ok(batch.finish())
+}
+
+/// 批量重新扫描已安装的技能
+#[tauri::command]
+pub async fn rescan_skills(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    skill_ids: Vec<String>,
+    locale: String,
+) -> Result<Vec<BatchItemResult>, AppError> {
+    let skill_ids = batch_ids(skill_ids)?;
+    let locale = validate_locale(&locale).to_string();
+    let mut skills: HashMap<String, _> = state.db.get_skills()?.into_iter().map(|skill| (skill.id.clone(), skill)).collect();
+    let scanner = Arc::new(state.settings.get().scan_policy.scanner());
+
+    let mut batch = BatchRun::new(&app, BatchOperation::Rescan, skill_ids.len());
+    for skill_id in skill_ids {
+        let result = match skills.remove(&skill_id) {
+            None => Err(AppError::not_found(format!("技能不存在: {}", skill_id))),
+            Some(skill) if !skill.installed || skill.local_path.is_none() => {
+                Err(AppError::invalid_input(format!("技能未安装: {}", skill.name)))
+            }
+            Some(skill) => {
+                let db = Arc::clone(&state.db);
+                let scanner = Arc::clone(&scanner);
+                let locale = locale.clone();
+                tokio::task::spawn_blocking(move || scan_installed_skill(&db, &scanner, skill, &locale))
+                    .await
+                    .unwrap_or_else(|e| Err(anyhow::anyhow!("扫描任务异常退出: {}", e)))
+                    .map(|_| ())
+                    .map_err(AppError::from)
+            }
+        };
+        batch.record(skill_id, result);
+    }
+    state.notify_status_changed();
+    Ok(batch.finish())
+}
+
+/// 获取所有技能的标签（按技能 ID 分组）
+#[tauri::command]
+pub async fn get_skill_tags(state: State<'_, AppState>) -> Result<HashMap<String, Vec<String>>, AppError> {
+    Ok(state.db.get_skill_tags()?)
+}
+
+/// 批量为技能添加与移除标签
+#[tauri::command]
+pub async fn tag_skills(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    skill_ids: Vec<String>,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Vec<BatchItemResult>, AppError> {
+    let skill_ids = batch_ids(skill_ids)?;
+    let add = normalize_tags(add)?;
+    let remove = normalize_tags(remove)?;
+
+    let mut batch = BatchRun::new(&app, BatchOperation::Tag, skill_ids.len());
+    for skill_id in skill_ids {
+        let result = state.db.update_skill_tags(&skill_id, &add, &remove);
+        batch.record(skill_id, result.map_err(AppError::from));
+    }
+    Ok(batch.finish())
+}
+
+/// 批量删除技能记录（同 delete_skill，只删除数据库记录）
+#[tauri::command]
+pub async fn delete_skill_records(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    skill_ids: Vec<String>,
+) -> Result<Vec<BatchItemResult>, AppError> {
+    let skill_ids = batch_ids(skill_ids)?;
+
+    let mut batch = BatchRun::new(&app, BatchOperation::DeleteRecord, skill_ids.len());
+    for skill_id in skill_ids {
+        let result = state.db.delete_skill(&skill_id);
+        batch.record(skill_id, result.map_err(AppError::from));
+    }
+    state.notify_status_changed();
+    Ok(batch.finish())
+}
@@ -0,0 +1,113 @@
+use crate::commands::AppState;
+use crate::models::AppError;
+use crate::services::credentials::GITHUB_TOKEN_ACCOUNT;
+use crate::services::github_auth::{
+    self, DeviceAuthorization, DevicePoll, GitHubSignIn, SignInStatus, DEFAULT_SCOPES, TOKEN_SCOPES_KEY,
+};
+use crate::services::CredentialStore;
+use serde::Serialize;
+use tauri::State;
+
+/// 当前 GitHub 登录状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHubAuthStatus {
+    pub has_token: bool,
+    pub login: Option<String>,
+    /// Token 授予的权限（细粒度 Token 不返回权限列表，此时为 None）
+    pub scopes: Option<Vec<String>>,
+    /// required_scopes 中尚未授予的权限，需重新登录授予
+    pub missing_scopes: Vec<String>,
+}
+
+/// 记录的已授予权限
+fn granted_scopes(state: &AppState) -> Vec<String> {
+    state.db.get_setting(TOKEN_SCOPES_KEY).ok().flatten().map(|scopes| github_auth::parse_scopes(&scopes)).unwrap_or_default()
+}
+
+/// 开始 GitHub 设备授权登录，返回需要在浏览器中输入的授权码，之后按 interval 调用 poll_github_sign_in
+/// 已登录时在已授予的权限基础上申请 scopes 中的额外权限
+#[tauri::command]
+pub async fn start_github_sign_in(
+    state: State<'_, AppState>,
+    sign_in: State<'_, GitHubSignIn>,
+    scopes: Option<Vec<String>>,
+) -> Result<DeviceAuthorization, AppError> {
+    let client_id = github_auth::client_id(state.settings.get().github.oauth_client_id.as_deref())
+        .ok_or_else(|| AppError::invalid_input("未配置 GitHub OAuth App 的 Client ID，请在设置中填写，或改用 Personal Access Token"))?;
+
+    let requested = DEFAULT_SCOPES
+        .iter()
+        .map(|scope| scope.to_string())
+        .chain(scopes.unwrap_or_default())
+        .chain(granted_scopes(&state));
+    let requested = github_auth::normalize_scopes(requested);
+    Ok(sign_in.start(&state.http_client, &client_id, &requested).await?)
+}
+
+/// 查询设备授权登录的结果；授权完成后将 Token 保存到系统钥匙串并立即对 GitHub 请求生效
+#[tauri::command]
+pub async fn poll_github_sign_in(
+    state: State<'_, AppState>,
+    sign_in: State<'_, GitHubSignIn>,
+) -> Result<SignInStatus, AppError> {
+    let (token, scopes) = match sign_in.poll(&state.http_client).await? {
+        DevicePoll::Pending | DevicePoll::SlowDown(_) => return Ok(SignInStatus::Pending),
+        DevicePoll::Expired => return Ok(SignInStatus::Expired),
+        DevicePoll::Denied => return Ok(SignInStatus::Denied),
+        DevicePoll::Granted { token, scopes } => (token, scopes),
+    };
+
+    CredentialStore::set(GITHUB_TOKEN_ACCOUNT, &token)?;
+    state.github.set_token(Some(token));
+    state.db.set_setting(TOKEN_SCOPES_KEY, &scopes.join(","))?;
+
+    let login = match state.github.fetch_token_info().await {
+        Ok(info) => Some(info.login),
+        Err(e) => {
+            log::warn!("查询 GitHub 账号失败: {}", e);
+            None
+        }
+    };
+    log::info!("已通过设备授权登录 GitHub: {:?}, 权限: {:?}", login, scopes);
+    Ok(SignInStatus::Authorized { login, scopes })
+}
+
+/// 取消进行中的设备授权登录
+#[tauri::command]
+pub async fn cancel_github_sign_in(sign_in: State<'_, GitHubSignIn>) -> Result<(), AppError> {
+    sign_in.cancel();
+    Ok(())
+}
+
+/// 查询当前 Token 对应的账号与权限，并更新记录的已授予权限
+#[tauri::command]
+pub async fn get_github_auth_status(
+    state: State<'_, AppState>,
+    required_scopes: Option<Vec<String>>,
+) -> Result<GitHubAuthStatus, AppError> {
+    if !state.github.has_token() {
+        return Ok(GitHubAuthStatus {
+            has_token: false,
+            login: None,
+            scopes: None,
+            missing_scopes: required_scopes.unwrap_or_default(),
+        });
+    }
+
+    let info = state.github.fetch_token_info().await?;
+    match &info.scopes {
+        Some(scopes) => state.db.set_setting(TOKEN_SCOPES_KEY, &scopes.join(","))?,
+        None => state.db.delete_setting(TOKEN_SCOPES_KEY)?,
+    }
+    let missing_scopes = match &info.scopes {
+        Some(scopes) => github_auth::missing_scopes(scopes, &required_scopes.unwrap_or_default()),
+        None => Vec::new(),
+    };
+    Ok(GitHubAuthStatus {
+        has_token: true,
+        login: Some(info.login),
+        scopes: info.scopes,
+        missing_scopes,
+    })
+}
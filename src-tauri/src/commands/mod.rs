@@ -1,19 +1,69 @@
 pub mod security;
-
-use crate::models::{Repository, Skill, FeaturedRepositoriesConfig};
-use crate::services::{Database, GitHubService, SkillManager, ProxyConfig, ProxyService};
+pub mod app_update;
+pub mod profile;
+pub mod statistics;
+pub mod window;
+pub mod batch;
+pub mod github_auth;
+
+use crate::models::{AppError, CommitSignatureStatus, ErrorKind, InstallResult, InstallStatus, LocalScanResult, Repository, SkillPage, SkillQuery, SkillSortContext, MAX_DISCOVERY_DEPTH, Skill, SkillEvent, SkillEventKind, SkillHealthReport, SkillReview, SkillReviewStatus, MAX_REVIEWER_LENGTH, SkillReadme, SkillRelocation, SourceType, SkillList, SkillListFormat, SkillProvenance, SuspiciousUpdate, TimelineEntry, FeaturedRepositoriesConfig, GitHubUrl, SecurityReport};
+use crate::services::{AppLogger, CachePaths, CredentialStore, Database, DatabaseBackups, GitHubService, LatestCommitQuery, LogEntry, PathPolicy, ProfileStore, SkillManager, NetworkConfig, ProxyConfig, ProxyService, Settings, SettingsService};
+use crate::services::settings::ScanPolicy;
+use crate::services::credentials::{database_key_account, GITHUB_TOKEN_ACCOUNT, INSTALL_PASSPHRASE_ACCOUNT};
+use crate::services::{dropped, fs_ops, portable, priority};
+use crate::services::archive::ArchiveFormat;
+use crate::services::dropped::DroppedItemKind;
+use crate::services::impact::{UninstallConfirmation, UninstallImpact};
+use crate::services::skill_backups::SkillBackup;
+use crate::services::dir_access::{self, DirAccessReport, DirFixOptions};
+use crate::services::profile::active_profile;
+use crate::services::github::is_commit_changed;
+use crate::services::remote_content::{RemoteContentKind, RemoteContentService, RemoteContentStatus};
+use log::LevelFilter;
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 use tauri::Manager;
 use tauri::State;
-use tokio::sync::Mutex;
 
 pub struct AppState {
     pub db: Arc<Database>,
-    pub skill_manager: Arc<Mutex<SkillManager>>,
+    pub skill_manager: Arc<SkillManager>,
     pub github: Arc<GitHubService>,
+    pub settings: Arc<SettingsService>,
+    pub backups: Arc<DatabaseBackups>,
     /// 共享的 HTTP 客户端，已配置代理
     pub http_client: Arc<reqwest::Client>,
+    /// 远程内容（精选仓库列表等）的下载与缓存
+    pub remote_content: Arc<RemoteContentService>,
+    pub profiles: Arc<ProfileStore>,
+    /// 安全状态变化通知（托盘图标据此刷新）
+    pub status_changed: Arc<tokio::sync::Notify>,
+}
+
+impl AppState {
+    /// 扫描结果、安装状态或更新检查结果变化后调用，通知托盘刷新安全状态
+    pub fn notify_status_changed(&self) {
+        self.status_changed.notify_one();
+    }
+
+    /// 检查前端传入的写入路径是否位于允许的目录中（见 [`PathPolicy`]）
+    pub fn check_write_path(&self, path: &str) -> Result<PathBuf, AppError> {
+        PathPolicy::new(&self.settings.get().directories).check(path)
+    }
+
+    /// 检查导出文件（报告、技能清单、数据库备份）的写入路径是否位于批准的导出目录中
+    pub fn check_export_path(&self, path: &str) -> Result<PathBuf, AppError> {
+        PathPolicy::exports(&self.settings.get().directories).check(path)
+    }
+
+    /// 检查安装命令指定的安装目录（未指定时使用默认或记住的目录，无需检查），返回解析后的安装目录
+    fn check_install_path(&self, install_path: Option<&str>) -> Result<Option<String>, AppError> {
+        match install_path.map(str::trim).filter(|path| !path.is_empty()) {
+            Some(path) => self.check_write_path(path).map(|path| Some(path.to_string_lossy().to_string())),
+            None => Ok(None),
+        }
+    }
 }
 
 /// 添加仓库
@@ -22,11 +72,10 @@ pub async fn add_repository(
     state: State<'_, AppState>,
     url: String,
     name: String,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     let repo = Repository::new(url, name);
     let repo_id = repo.id.clone();
-    state.db.add_repository(&repo)
-        .map_err(|e| e.to_string())?;
+    state.db.add_repository(&repo)?;
     Ok(repo_id)
 }
 
@@ -34,89 +83,262 @@ pub async fn add_repository(
 #[tauri::command]
 pub async fn get_repositories(
     state: State<'_, AppState>,
-) -> Result<Vec<Repository>, String> {
+) -> Result<Vec<Repository>, AppError> {
     state.db.get_repositories()
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
+}
+
+/// 验证仓库提交的签名，结果保存在仓库信息中
+#[tauri::command]
+pub async fn verify_repository_signature(
+    state: State<'_, AppState>,
+    repo_id: String,
+) -> Result<CommitSignatureStatus, AppError> {
+    let manager = &state.skill_manager;
+    manager.verify_repository_signature(&repo_id).await
+        .map_err(AppError::from)
+}
+
+/// 设置仓库是否要求经过受信任密钥签名的提交才允许安装
+#[tauri::command]
+pub async fn set_repository_require_signed_commits(
+    state: State<'_, AppState>,
+    repo_id: String,
+    required: bool,
+) -> Result<(), AppError> {
+    state.db.set_repository_require_signed_commits(&repo_id, required)
+        .map_err(AppError::from)
+}
+
+/// 设置仓库是否跳过后台定时刷新
+#[tauri::command]
+pub async fn set_repository_skip_auto_refresh(
+    state: State<'_, AppState>,
+    repo_id: String,
+    skip: bool,
+) -> Result<(), AppError> {
+    state.db.set_repository_skip_auto_refresh(&repo_id, skip)
+        .map_err(AppError::from)
+}
+
+/// 设置仓库的技能发现路径：包含/排除的 glob 模式（相对仓库根目录）与技能目录的最大深度
+#[tauri::command]
+pub async fn set_repository_scan_paths(
+    state: State<'_, AppState>,
+    repo_id: String,
+    include_patterns: Vec<String>,
+    exclude_patterns: Vec<String>,
+    max_depth: Option<u32>,
+) -> Result<(), AppError> {
+    let clean = |patterns: Vec<String>| -> Vec<String> {
+        patterns.into_iter().map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect()
+    };
+    let (include_patterns, exclude_patterns) = (clean(include_patterns), clean(exclude_patterns));
+
+    crate::security::ScanFilter::new(&include_patterns, &exclude_patterns, false)
+        .map_err(|e| AppError::invalid_input(format!("{:#}", e)))?;
+    if max_depth.is_some_and(|depth| !(1..=MAX_DISCOVERY_DEPTH).contains(&depth)) {
+        return Err(AppError::invalid_input(format!("最大扫描深度需在 1-{} 之间", MAX_DISCOVERY_DEPTH)));
+    }
+
+    state.db.set_repository_scan_paths(&repo_id, &include_patterns, &exclude_patterns, max_depth)
+        .map_err(AppError::from)
+}
+
+/// 删除仓库的结果
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryDeletionResult {
+    /// 删除的未安装技能数量
+    pub deleted_skills: usize,
+    /// 随仓库一起卸载的技能数量
+    pub uninstalled_skills: usize,
+    /// 保留并标记为来源已删除的已安装技能数量
+    pub orphaned_skills: usize,
+    /// 清理缓存释放的字节数
+    pub freed_bytes: u64,
 }
 
-/// 删除仓库（同时删除未安装的技能和清理缓存）
+/// 删除仓库
+///
+/// 始终删除未安装的技能并清理仓库缓存（目录与数据库中的缓存信息）；
+/// 已安装的技能默认保留并标记为来源已删除，uninstall_skills 为 true 时一并卸载
 #[tauri::command]
 pub async fn delete_repository(
     state: State<'_, AppState>,
     repo_id: String,
-) -> Result<(), String> {
+    uninstall_skills: Option<bool>,
+) -> Result<RepositoryDeletionResult, AppError> {
     // 1. 获取仓库信息
-    let repo = state.db.get_repository(&repo_id)
-        .map_err(|e| e.to_string())?
+    let repo = state.db.get_repository(&repo_id)?
         .ok_or_else(|| "仓库不存在".to_string())?;
 
     let repository_url = repo.url.clone();
-    let cache_path = repo.cache_path.clone();
-
-    // 2. 删除未安装的技能（使用事务）
-    let deleted_skills_count = state.db.delete_uninstalled_skills_by_repository_url(&repository_url)
-        .map_err(|e| e.to_string())?;
-
-    log::info!("删除仓库 {} 的 {} 个未安装技能", repo.name, deleted_skills_count);
+    let mut result = RepositoryDeletionResult {
+        deleted_skills: 0,
+        uninstalled_skills: 0,
+        orphaned_skills: 0,
+        freed_bytes: 0,
+    };
 
-    // 3. 清理缓存目录（失败不中断）
-    if let Some(cache_path_str) = cache_path {
-        let cache_path_buf = std::path::PathBuf::from(&cache_path_str);
-        if cache_path_buf.exists() {
-            match std::fs::remove_dir_all(&cache_path_buf) {
-                Ok(_) => log::info!("成功删除缓存目录: {:?}", cache_path_buf),
-                Err(e) => log::warn!("删除缓存目录失败，但不影响仓库删除: {:?}, 错误: {}", cache_path_buf, e),
+    // 2. 按需卸载已安装的技能（单个失败不中断，剩余的技能按保留处理）
+    if uninstall_skills.unwrap_or(false) {
+        let installed: Vec<String> = state.db.get_skills()?
+            .into_iter()
+            .filter(|s| s.repository_url == repository_url && s.installed)
+            .map(|s| s.id)
+            .collect();
+
+        let manager = &state.skill_manager;
+        let _operation = manager.begin_operation().await;
+        for skill_id in installed {
+            let id = skill_id.clone();
+            match manager.run_blocking(move |manager| manager.uninstall_skill(&id)).await {
+                Ok(()) => result.uninstalled_skills += 1,
+                Err(e) => log::warn!("卸载仓库技能失败: {}: {:#}", skill_id, e),
             }
-        } else {
-            log::info!("缓存目录不存在，跳过清理: {:?}", cache_path_buf);
         }
     }
 
-    // 4. 删除仓库记录
-    state.db.delete_repository(&repo_id)
-        .map_err(|e| e.to_string())?;
+    // 3. 删除未安装的技能，保留的已安装技能标记为来源已删除
+    result.deleted_skills = state.db.delete_uninstalled_skills_by_repository_url(&repository_url)?;
+    result.orphaned_skills = state.db.mark_skills_source_removed(&repository_url)?;
+
+    log::info!(
+        "删除仓库 {}: 删除 {} 个未安装技能，卸载 {} 个，保留 {} 个已安装技能",
+        repo.name, result.deleted_skills, result.uninstalled_skills, result.orphaned_skills
+    );
+
+    // 4. 清理缓存目录和数据库中的缓存信息（失败不中断）
+    match remove_repository_cache(&state, &repo) {
+        Ok(freed) => result.freed_bytes = freed,
+        Err(e) => log::warn!("清理仓库缓存失败，但不影响仓库删除: {}", e),
+    }
+
+    // 5. 删除仓库记录
+    state.db.delete_repository(&repo_id)?;
+
+    if result.uninstalled_skills > 0 || result.orphaned_skills > 0 {
+        state.notify_status_changed();
+    }
 
     log::info!("成功删除仓库: {}", repo.name);
-    Ok(())
+    Ok(result)
 }
 
-/// 扫描仓库中的 skills
+/// 扫描仓库中的 skills（失败时记录到仓库的 last_error，成功后清除）
 #[tauri::command]
 pub async fn scan_repository(
     state: State<'_, AppState>,
     repo_id: String,
-) -> Result<Vec<Skill>, String> {
+) -> Result<Vec<Skill>, AppError> {
+    let result = scan_repository_skills(state.clone(), repo_id.clone()).await;
+    record_repository_result(&state, &repo_id, result.as_ref().err());
+    if result.is_ok() {
+        refresh_repository_stats(&state, &repo_id).await;
+    }
+    result
+}
+
+/// 更新仓库的星标数与所有者信息（缓存未过期时跳过，失败只记录警告）
+async fn refresh_repository_stats(state: &AppState, repo_id: &str) {
+    let Ok(Some(repo)) = state.db.get_repository(repo_id) else {
+        return;
+    };
+    let Ok((owner, repo_name, _)) = Repository::from_github_url(&repo.url) else {
+        return;
+    };
+    let cached = state.db.get_repository_stats().unwrap_or_default();
+    if cached.get(&repo.url).is_some_and(|stats| !stats.is_stale(chrono::Utc::now())) {
+        return;
+    }
+
+    match state.github.fetch_repository_stats(&repo.url, &owner, &repo_name).await {
+        Ok(stats) => {
+            if let Err(e) = state.db.save_repository_stats(&stats) {
+                log::warn!("保存仓库统计信息失败: {}: {}", repo.url, e);
+            }
+        }
+        Err(e) => log::warn!("获取仓库统计信息失败: {}: {}", repo.url, e),
+    }
+}
+
+/// 获取技能的热度与来源信号：仓库星标数、收录所在仓库的精选分类数、首次出现时间与所有者账号创建时间
+#[tauri::command]
+pub async fn get_skills_provenance(
+    state: State<'_, AppState>,
+) -> Result<Vec<SkillProvenance>, AppError> {
+    let stats = state.db.get_repository_stats()?;
+    let featured = featured_repositories(&state)
+        .inspect_err(|e| log::warn!("读取精选仓库列表失败: {}", e.message))
+        .ok();
+
+    let provenance = state.db.get_skills()?
+        .into_iter()
+        .map(|skill| {
+            let repo_stats = stats.get(&skill.repository_url);
+            let featured_count = featured.iter()
+                .flat_map(|config| &config.categories)
+                .filter(|category| {
+                    category.repositories.iter()
+                        .any(|repo| crate::models::same_repository(&repo.url, &skill.repository_url))
+                })
+                .count();
+            SkillProvenance {
+                skill_id: skill.id,
+                stars: repo_stats.map(|s| s.stars),
+                featured_count,
+                first_seen_at: skill.first_seen_at,
+                owner: repo_stats.map(|s| s.owner.clone()),
+                owner_created_at: repo_stats.and_then(|s| s.owner_created_at),
+            }
+        })
+        .collect();
+
+    Ok(provenance)
+}
+
+/// 记录仓库最近一次操作的结果（记录失败只记录警告）
+fn record_repository_result(state: &AppState, repo_id: &str, error: Option<&AppError>) {
+    let message = error.map(|e| e.message.as_str());
+    if let Err(e) = state.db.set_repository_last_error(repo_id, message) {
+        log::warn!("记录仓库错误状态失败: {}: {}", repo_id, e);
+    }
+}
+
+async fn scan_repository_skills(
+    state: State<'_, AppState>,
+    repo_id: String,
+) -> Result<Vec<Skill>, AppError> {
     use chrono::Utc;
 
     // 获取仓库信息
-    let repo = state.db.get_repository(&repo_id)
-        .map_err(|e| e.to_string())?
+    let repo = state.db.get_repository(&repo_id)?
         .ok_or_else(|| "仓库不存在".to_string())?;
 
-    let (owner, repo_name, branch) = Repository::from_github_url(&repo.url)
-        .map_err(|e| e.to_string())?;
+    let (owner, repo_name, branch) = Repository::from_github_url(&repo.url)?;
 
     // 确定缓存基础目录
-    let cache_base_dir = dirs::cache_dir()
-        .ok_or("无法获取缓存目录".to_string())?
-        .join("agent-skills-guard")
-        .join("repositories");
+    let cache_base_dir = CachePaths::require()?.repositories();
 
     let mut skills = if let Some(cache_path) = &repo.cache_path {
         // 使用缓存扫描(0次API请求)
         log::info!("使用本地缓存扫描仓库: {}", repo.name);
 
         let cache_path_buf = std::path::PathBuf::from(cache_path);
-        if cache_path_buf.exists() && cache_path_buf.is_dir() {
-            state.github.scan_cached_repository(&cache_path_buf, &repo.url, repo.scan_subdirs)
-                .map_err(|e| format!("扫描缓存失败: {}", e))?
+        if cache_path_buf.exists()
+            && cache_path_buf.is_dir()
+            && state.github.verify_repository_cache(&cache_path_buf, &cache_base_dir, &owner, &repo_name)
+        {
+            state.github.scan_cached_repository(&cache_path_buf, &repo)
+                .map_err(|e| AppError::from(e).context("扫描缓存失败"))?
         } else {
-            // 缓存路径不存在，重新下载
-            log::warn!("缓存路径不存在，重新下载: {:?}", cache_path_buf);
+            // 缓存路径不存在或校验失败，重新下载
+            log::warn!("缓存路径不存在或校验失败，重新下载: {:?}", cache_path_buf);
             let (extract_dir, commit_sha) = state.github
                 .download_repository_archive(&owner, &repo_name, branch.as_deref(), &cache_base_dir)
                 .await
-                .map_err(|e| format!("下载仓库压缩包失败: {}", e))?;
+                .map_err(|e| AppError::from(e).context("下载仓库压缩包失败"))?;
 
             // 更新数据库缓存信息
             state.db.update_repository_cache(
@@ -124,10 +346,10 @@ pub async fn scan_repository(
                 &extract_dir.to_string_lossy(),
                 Utc::now(),
                 Some(&commit_sha),
-            ).map_err(|e| e.to_string())?;
+            )?;
 
-            state.github.scan_cached_repository(&extract_dir, &repo.url, repo.scan_subdirs)
-                .map_err(|e| format!("扫描缓存失败: {}", e))?
+            state.github.scan_cached_repository(&extract_dir, &repo)
+                .map_err(|e| AppError::from(e).context("扫描缓存失败"))?
         }
     } else {
         // 首次扫描: 下载压缩包并缓存(1次API请求)
@@ -136,7 +358,7 @@ pub async fn scan_repository(
         let (extract_dir, commit_sha) = state.github
             .download_repository_archive(&owner, &repo_name, branch.as_deref(), &cache_base_dir)
             .await
-            .map_err(|e| format!("下载仓库压缩包失败: {}", e))?;
+            .map_err(|e| AppError::from(e).context("下载仓库压缩包失败"))?;
 
         // 更新数据库缓存信息
         state.db.update_repository_cache(
@@ -144,11 +366,11 @@ pub async fn scan_repository(
             &extract_dir.to_string_lossy(),
             Utc::now(),
             Some(&commit_sha),
-        ).map_err(|e| e.to_string())?;
+        )?;
 
         // 扫描本地缓存
-        state.github.scan_cached_repository(&extract_dir, &repo.url, repo.scan_subdirs)
-            .map_err(|e| format!("扫描缓存失败: {}", e))?
+        state.github.scan_cached_repository(&extract_dir, &repo)
+            .map_err(|e| AppError::from(e).context("扫描缓存失败"))?
     };
 
     // 第一步：对扫描结果按名称去重（同一仓库内同名技能只保留一个）
@@ -235,8 +457,7 @@ pub async fn scan_repository(
             continue;
         }
 
-        state.db.save_skill(skill)
-            .map_err(|e| e.to_string())?;
+        state.db.save_skill(skill)?;
     }
 
     Ok(skills)
@@ -246,44 +467,167 @@ pub async fn scan_repository(
 #[tauri::command]
 pub async fn get_skills(
     state: State<'_, AppState>,
-) -> Result<Vec<Skill>, String> {
-    let manager = state.skill_manager.lock().await;
+) -> Result<Vec<Skill>, AppError> {
+    let manager = &state.skill_manager;
     manager.get_all_skills()
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
+}
+
+/// 按条件筛选、排序并分页查询技能目录，前端无需加载全部技能即可排序
+#[tauri::command]
+pub async fn query_skills(
+    state: State<'_, AppState>,
+    query: SkillQuery,
+) -> Result<SkillPage, AppError> {
+    let context = SkillSortContext {
+        stars: state.db.get_repository_stats()?
+            .into_iter()
+            .map(|(url, stats)| (url, stats.stars))
+            .collect(),
+        upstream_updated: state.db.get_repositories()?
+            .into_iter()
+            .filter_map(|repo| Some((repo.url, repo.cached_at?)))
+            .collect(),
+    };
+    let skills = state.skill_manager.get_all_skills()?;
+    Ok(query.apply(skills, &context))
 }
 
 /// 获取已安装的 skills
 #[tauri::command]
 pub async fn get_installed_skills(
     state: State<'_, AppState>,
-) -> Result<Vec<Skill>, String> {
-    let manager = state.skill_manager.lock().await;
+) -> Result<Vec<Skill>, AppError> {
+    let manager = &state.skill_manager;
     manager.get_installed_skills()
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
+}
+
+/// 获取技能的说明文档（SKILL.md 及其引用的 Markdown 文件），附带提示注入检测结果
+#[tauri::command]
+pub async fn get_skill_readme(
+    state: State<'_, AppState>,
+    skill_id: String,
+    locale: String,
+) -> Result<SkillReadme, AppError> {
+    let manager = &state.skill_manager;
+    manager.get_skill_readme(&skill_id, &locale).await
+        .map_err(AppError::from)
+}
+
+/// 检查已安装技能的完整性，返回存在问题的安装及建议的修复操作
+#[tauri::command]
+pub async fn check_skills_health(
+    state: State<'_, AppState>,
+) -> Result<Vec<SkillHealthReport>, AppError> {
+    let manager = &state.skill_manager;
+    Ok(manager.run_blocking(|manager| manager.check_skills_health()).await?)
+}
+
+/// 重定位失效的技能安装路径（移动工具目录或换机器恢复主目录后）
+///
+/// 给出原目录与新目录时按前缀改写，否则自动在各工具的技能目录中查找；只改写新路径存在的记录
+#[tauri::command]
+pub async fn relocate_skills(
+    state: State<'_, AppState>,
+    old_base: Option<String>,
+    new_base: Option<String>,
+) -> Result<Vec<SkillRelocation>, AppError> {
+    // 重定位后的路径会用于之后的更新与修复
+    let new_base = match new_base.as_deref() {
+        Some(new_base) => Some(state.check_write_path(new_base)?.to_string_lossy().to_string()),
+        None => None,
+    };
+    let manager = &state.skill_manager;
+    let _operation = manager.begin_operation().await;
+    let relocations = manager
+        .run_blocking(move |manager| manager.relocate_skills(old_base.as_deref(), new_base.as_deref()))
+        .await?;
+    state.notify_status_changed();
+    Ok(relocations)
+}
+
+/// 列出更新备份（按时间从新到旧），指定 skill_id 时只返回该技能的备份
+#[tauri::command]
+pub async fn list_skill_backups(
+    state: State<'_, AppState>,
+    skill_id: Option<String>,
+) -> Result<Vec<SkillBackup>, AppError> {
+    let backups = state
+        .skill_manager
+        .run_blocking(move |manager| manager.list_skill_backups(skill_id.as_deref()))
+        .await?;
+    Ok(backups)
 }
 
-/// 安装 skill
+/// 安装 skill；被安全扫描或扫描策略阻止时返回 blocked 状态的结果及阻止原因。
+/// 高风险技能需要传入确认文本（管理口令或技能名称）
 #[tauri::command]
 pub async fn install_skill(
     state: State<'_, AppState>,
     skill_id: String,
     install_path: Option<String>,
-) -> Result<(), String> {
-    let manager = state.skill_manager.lock().await;
-    manager.install_skill(&skill_id, install_path, false).await
-        .map_err(|e| e.to_string())
+    confirmation: Option<String>,
+) -> Result<InstallResult, AppError> {
+    let install_path = state.check_install_path(install_path.as_deref())?;
+    let manager = &state.skill_manager;
+    let _operation = manager.begin_operation().await;
+    let result = InstallResult::from_outcome(&skill_id, manager.install_skill(&skill_id, install_path, false, confirmation.as_deref()).await)?;
+    if result.status == InstallStatus::Installed {
+        state.notify_status_changed();
+    }
+    Ok(result)
+}
+
+/// 从直接地址（技能清单的原始文件地址或 Gist）安装技能，扫描通过后返回安装的技能
+/// 评分低于最低安装评分（策略允许确认时）或风险较高时需要传入确认文本
+#[tauri::command]
+pub async fn install_skill_from_url(
+    state: State<'_, AppState>,
+    url: String,
+    install_path: Option<String>,
+    locale: String,
+    confirmation: Option<String>,
+) -> Result<Skill, AppError> {
+    let install_path = state.check_install_path(install_path.as_deref())?;
+    let manager = &state.skill_manager;
+    let _operation = manager.begin_operation().await;
+    let skill = manager.install_skill_from_url(&url, install_path, &locale, confirmation.as_deref()).await?;
+    state.notify_status_changed();
+    Ok(skill)
+}
+
+/// 按安装时记录的 commit 清空并重新安装技能（发现篡改或文件被误删后恢复），不会更新到分支的最新版本
+#[tauri::command]
+pub async fn reinstall_skill(
+    state: State<'_, AppState>,
+    skill_id: String,
+    locale: String,
+    confirmation: Option<String>,
+) -> Result<Skill, AppError> {
+    let manager = &state.skill_manager;
+    let _operation = manager.begin_operation().await;
+    let skill = manager.reinstall_skill(&skill_id, &locale, confirmation.as_deref()).await?;
+    state.notify_status_changed();
+    Ok(skill)
 }
 
-/// 同步 skill (跳过安全扫描)
+/// 同步 skill (跳过安全扫描，按保存的安全等级检查是否需要确认)
 #[tauri::command]
 pub async fn sync_skill(
     state: State<'_, AppState>,
     skill_id: String,
     install_path: Option<String>,
-) -> Result<(), String> {
-    let manager = state.skill_manager.lock().await;
-    manager.install_skill(&skill_id, install_path, true).await
-        .map_err(|e| e.to_string())
+    confirmation: Option<String>,
+) -> Result<InstallResult, AppError> {
+    let install_path = state.check_install_path(install_path.as_deref())?;
+    let manager = &state.skill_manager;
+    let _operation = manager.begin_operation().await;
+    let result = InstallResult::from_outcome(&skill_id, manager.install_skill(&skill_id, install_path, true, confirmation.as_deref()).await)?;
+    if result.status == InstallStatus::Installed {
+        state.notify_status_changed();
+    }
+    Ok(result)
 }
 
 /// 准备安装技能：下载并扫描，但不标记为已安装
@@ -292,22 +636,33 @@ pub async fn prepare_skill_installation(
     state: State<'_, AppState>,
     skill_id: String,
     locale: String,
-) -> Result<crate::models::security::SecurityReport, String> {
-    let manager = state.skill_manager.lock().await;
+) -> Result<crate::models::security::SecurityReport, AppError> {
+    let manager = &state.skill_manager;
+    let _operation = manager.begin_operation().await;
     manager.prepare_skill_installation(&skill_id, &locale).await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
 }
 
-/// 确认安装技能：标记为已安装
+/// 确认安装技能：标记为已安装（评分过低或风险较高且策略要求确认时，需传入技能名称或管理口令作为确认文本）
 #[tauri::command]
 pub async fn confirm_skill_installation(
     state: State<'_, AppState>,
     skill_id: String,
     install_path: Option<String>,
-) -> Result<(), String> {
-    let manager = state.skill_manager.lock().await;
-    manager.confirm_skill_installation(&skill_id, install_path)
-        .map_err(|e| e.to_string())
+    confirmation: Option<String>,
+) -> Result<InstallResult, AppError> {
+    let install_path = state.check_install_path(install_path.as_deref())?;
+    let manager = &state.skill_manager;
+    let _operation = manager.begin_operation().await;
+    let id = skill_id.clone();
+    let outcome = manager
+        .run_blocking(move |manager| manager.confirm_skill_installation(&id, install_path, confirmation.as_deref()))
+        .await;
+    let result = InstallResult::from_outcome(&skill_id, outcome)?;
+    if result.status == InstallStatus::Installed {
+        state.notify_status_changed();
+    }
+    Ok(result)
 }
 
 /// 取消安装技能：删除已下载的文件
@@ -315,33 +670,110 @@ pub async fn confirm_skill_installation(
 pub async fn cancel_skill_installation(
     state: State<'_, AppState>,
     skill_id: String,
-) -> Result<(), String> {
-    let manager = state.skill_manager.lock().await;
+) -> Result<(), AppError> {
+    let manager = &state.skill_manager;
+    // 中止进行中的确认安装（复制文件），再清除准备阶段的数据
+    manager.request_cancel();
+    let _operation = manager.begin_operation().await;
     manager.cancel_skill_installation(&skill_id)
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
+}
+
+/// 检查卸载技能（`path` 为空时卸载全部安装路径）的影响，供前端在卸载前展示并请用户逐项确认
+#[tauri::command]
+pub async fn check_uninstall_impact(
+    state: State<'_, AppState>,
+    skill_id: String,
+    path: Option<String>,
+) -> Result<UninstallImpact, AppError> {
+    let impact = state.skill_manager
+        .run_blocking(move |manager| manager.uninstall_impact(&skill_id, path.as_deref()))
+        .await?;
+    Ok(impact)
 }
 
 /// 卸载 skill
+///
+/// 存在依赖它的技能、指向它的符号链接或引用它的技能清单时，需要在 `confirm` 中逐项确认，
+/// 否则返回 ConfirmationRequired 错误且不删除任何文件
 #[tauri::command]
 pub async fn uninstall_skill(
     state: State<'_, AppState>,
     skill_id: String,
-) -> Result<(), String> {
-    let manager = state.skill_manager.lock().await;
-    manager.uninstall_skill(&skill_id)
-        .map_err(|e| e.to_string())
+    confirm: Option<UninstallConfirmation>,
+) -> Result<(), AppError> {
+    let manager = &state.skill_manager;
+    let _operation = manager.begin_operation().await;
+    manager.run_blocking(move |manager| {
+        manager.uninstall_impact(&skill_id, None)?
+            .check_confirmed(&confirm.unwrap_or_default())?;
+        manager.uninstall_skill(&skill_id)
+    }).await?;
+    state.notify_status_changed();
+    Ok(())
 }
 
-/// 卸载特定路径的技能
+/// 卸载特定路径的技能（影响确认同 uninstall_skill）
 #[tauri::command]
 pub async fn uninstall_skill_path(
     state: State<'_, AppState>,
     skill_id: String,
     path: String,
-) -> Result<(), String> {
-    let manager = state.skill_manager.lock().await;
-    manager.uninstall_skill_path(&skill_id, &path)
-        .map_err(|e| e.to_string())
+    confirm: Option<UninstallConfirmation>,
+) -> Result<(), AppError> {
+    let manager = &state.skill_manager;
+    let _operation = manager.begin_operation().await;
+    manager.run_blocking(move |manager| {
+        manager.uninstall_impact(&skill_id, Some(&path))?
+            .check_confirmed(&confirm.unwrap_or_default())?;
+        manager.uninstall_skill_path(&skill_id, &path)
+    }).await?;
+    state.notify_status_changed();
+    Ok(())
+}
+
+/// 重命名技能的安装目录（同时更新安装路径记录与指向它的符号链接），返回更新后的技能
+#[tauri::command]
+pub async fn rename_installed_skill(
+    state: State<'_, AppState>,
+    skill_id: String,
+    new_name: String,
+) -> Result<Skill, AppError> {
+    let manager = &state.skill_manager;
+    let _operation = manager.begin_operation().await;
+    let skill = manager
+        .run_blocking(move |manager| manager.rename_installed_skill(&skill_id, &new_name))
+        .await?;
+    state.notify_status_changed();
+    Ok(skill)
+}
+
+/// 检查技能目录能否写入（只读属性、云同步占位文件），指定 skill_id 时检查该技能的各安装目录
+#[tauri::command]
+pub async fn check_skill_directories(
+    state: State<'_, AppState>,
+    skill_id: Option<String>,
+) -> Result<Vec<DirAccessReport>, AppError> {
+    let reports = state
+        .skill_manager
+        .run_blocking(move |manager| manager.check_skill_dirs(skill_id.as_deref()))
+        .await?;
+    Ok(reports)
+}
+
+/// 修复技能目录：去除只读属性、下载云同步占位文件，返回修复后的检查结果
+#[tauri::command]
+pub async fn fix_skill_directory(
+    state: State<'_, AppState>,
+    path: String,
+    options: DirFixOptions,
+) -> Result<DirAccessReport, AppError> {
+    let manager = &state.skill_manager;
+    let _operation = manager.begin_operation().await;
+    let report = manager
+        .run_blocking(move |manager| manager.fix_skill_dir(&path, options))
+        .await?;
+    Ok(report)
 }
 
 /// 删除 skill 记录
@@ -349,19 +781,22 @@ pub async fn uninstall_skill_path(
 pub async fn delete_skill(
     state: State<'_, AppState>,
     skill_id: String,
-) -> Result<(), String> {
-    state.db.delete_skill(&skill_id)
-        .map_err(|e| e.to_string())
+) -> Result<(), AppError> {
+    state.db.delete_skill(&skill_id)?;
+    state.notify_status_changed();
+    Ok(())
 }
 
 /// 扫描本地技能目录并导入未追踪的技能
 #[tauri::command]
 pub async fn scan_local_skills(
     state: State<'_, AppState>,
-) -> Result<Vec<Skill>, String> {
-    let manager = state.skill_manager.lock().await;
-    manager.scan_local_skills()
-        .map_err(|e| e.to_string())
+) -> Result<LocalScanResult, AppError> {
+    let manager = &state.skill_manager;
+    let _operation = manager.begin_operation().await;
+    let result = manager.run_blocking(|manager| manager.scan_local_skills()).await?;
+    state.notify_status_changed();
+    Ok(result)
 }
 
 /// 清理指定仓库的缓存
@@ -369,76 +804,260 @@ pub async fn scan_local_skills(
 pub async fn clear_repository_cache(
     state: State<'_, AppState>,
     repo_id: String,
-) -> Result<(), String> {
-    let repo = state.db.get_repository(&repo_id)
-        .map_err(|e| e.to_string())?
+) -> Result<(), AppError> {
+    let repo = state.db.get_repository(&repo_id)?
         .ok_or("仓库不存在")?;
 
-    if let Some(cache_path) = &repo.cache_path {
-        let cache_path_buf = std::path::PathBuf::from(cache_path);
+    remove_repository_cache(&state, &repo)?;
+    Ok(())
+}
 
-        // 验证缓存路径是否在预期的缓存目录中
-        let expected_cache_base = dirs::cache_dir()
-            .ok_or("无法获取缓存目录".to_string())?
-            .join("agent-skills-guard")
-            .join("repositories");
-
-        // 删除整个仓库缓存目录（包括archive.zip和extracted/）
-        if let Some(parent) = cache_path_buf.parent() {
-            // 安全检查：确保路径在预期的缓存目录中
-            if !parent.starts_with(&expected_cache_base) {
-                return Err("缓存路径无效".to_string());
-            }
+/// 删除仓库缓存目录（包括 archive.zip 和 extracted/）并清除数据库中的缓存信息，返回释放的字节数
+///
+/// 先清除数据库中的缓存信息，删除文件失败不影响数据库一致性（只记录警告）
+fn remove_repository_cache(state: &AppState, repo: &Repository) -> Result<u64, AppError> {
+    let Some(cache_path) = &repo.cache_path else {
+        return Ok(0);
+    };
+    let cache_path_buf = std::path::PathBuf::from(cache_path);
 
-            // 先清除数据库中的缓存信息
-            state.db.clear_repository_cache_metadata(&repo_id)
-                .map_err(|e| e.to_string())?;
+    // 验证缓存路径是否在预期的缓存目录中
+    let expected_cache_base = CachePaths::require()?.repositories();
 
-            // 然后删除文件（即使失败也不影响数据库一致性）
-            if parent.exists() {
-                if let Err(e) = std::fs::remove_dir_all(parent) {
-                    log::warn!("删除缓存目录失败，但数据库已清理: {:?}，错误: {}", parent, e);
-                    // 不返回错误，因为数据库已经一致
-                } else {
-                    log::info!("已删除缓存目录: {:?}", parent);
-                }
-            }
+    let Some(parent) = cache_path_buf.parent() else {
+        return Ok(0);
+    };
+    if !parent.starts_with(&expected_cache_base) {
+        return Err(AppError::invalid_input("缓存路径无效"));
+    }
+
+    state.db.clear_repository_cache_metadata(&repo.id)?;
+
+    let mut freed = 0;
+    if parent.exists() {
+        let size = fs_ops::dir_size(parent).unwrap_or(0);
+        if let Err(e) = fs_ops::remove_dir_all(parent) {
+            log::warn!("删除缓存目录失败，但数据库已清理: {:?}，错误: {}", parent, e);
+        } else {
+            log::info!("已删除缓存目录: {:?}", parent);
+            freed = size;
         }
     }
 
-    Ok(())
+    release_cache_manifest(&state.github, &repo.url, true);
+    Ok(freed)
 }
 
-/// 刷新仓库缓存（清理后重新扫描）
+/// 刷新仓库缓存（清理后重新扫描）。持有写操作锁，避免与正在从该缓存安装或更新的操作交错
 #[tauri::command]
 pub async fn refresh_repository_cache(
     state: State<'_, AppState>,
     repo_id: String,
-) -> Result<Vec<Skill>, String> {
+) -> Result<Vec<Skill>, AppError> {
+    let _operation = state.skill_manager.begin_operation().await;
+
     // 先清理缓存
     clear_repository_cache(state.clone(), repo_id.clone()).await?;
 
     // 重新扫描（会自动下载新版本）
-    scan_repository(state, repo_id).await
+    scan_repository(state.clone(), repo_id).await
+}
+
+/// 后台刷新单个仓库的结果
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryRefreshResult {
+    pub repo_id: String,
+    pub repo_name: String,
+    /// 刷新后新出现的技能名称
+    pub new_skills: Vec<String>,
+    /// SKILL.md 内容发生变化的技能名称
+    pub changed_skills: Vec<String>,
+    /// 刷新后仓库中不再存在的技能名称
+    pub removed_skills: Vec<String>,
+    /// 被标记为可疑更新的技能（内容变化且新出现高危/严重问题）
+    pub suspicious_updates: Vec<SuspiciousUpdate>,
+}
+
+/// 后台刷新发现新技能时发送给前端的通知（new-skills-available 事件）
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewSkillsNotification {
+    /// 新技能总数
+    pub count: usize,
+    pub repositories: Vec<RepositoryRefreshResult>,
+}
+
+/// 需要后台刷新的仓库：已启用、未关闭自动刷新、扫描过，且缓存时间超过刷新间隔
+fn is_refresh_due(repo: &Repository, interval: chrono::Duration, now: chrono::DateTime<chrono::Utc>) -> bool {
+    repo.enabled
+        && !repo.skip_auto_refresh
+        && repo.last_scanned.is_some()
+        && repo.cached_at.is_none_or(|cached_at| now - cached_at >= interval)
+}
+
+/// 后台定时刷新到期的仓库，返回有新增或变化技能的仓库（单个仓库失败只记录警告）
+pub async fn refresh_due_repositories(state: State<'_, AppState>) -> Vec<RepositoryRefreshResult> {
+    let interval_hours = state.settings.get().refresh.interval_hours;
+    if interval_hours == 0 {
+        return Vec::new();
+    }
+
+    let repos = match state.db.get_repositories() {
+        Ok(repos) => repos,
+        Err(e) => {
+            log::warn!("后台刷新仓库失败，无法读取仓库列表: {}", e);
+            return Vec::new();
+        }
+    };
+    let interval = chrono::Duration::hours(interval_hours as i64);
+    let now = chrono::Utc::now();
+
+    let mut results = Vec::new();
+    for repo in repos.iter().filter(|repo| is_refresh_due(repo, interval, now)) {
+        match refresh_repository_if_changed(state.clone(), repo).await {
+            Ok(Some(result)) => {
+                log::info!(
+                    "后台刷新仓库 {}：新增 {} 个技能，{} 个技能有变化",
+                    repo.name, result.new_skills.len(), result.changed_skills.len()
+                );
+                for update in &result.suspicious_updates {
+                    log::warn!("技能 {} 的更新可疑: {:?}", update.skill_name, update.new_findings);
+                }
+                if !result.new_skills.is_empty()
+                    || !result.changed_skills.is_empty()
+                    || !result.removed_skills.is_empty()
+                    || !result.suspicious_updates.is_empty()
+                {
+                    results.push(result);
+                }
+            }
+            Ok(None) => {
+                log::info!("后台刷新仓库 {}：没有新的提交", repo.name);
+                record_repository_result(&state, &repo.id, None);
+            }
+            Err(e) => {
+                log::warn!("后台刷新仓库 {} 失败: {}", repo.name, e);
+                record_repository_result(&state, &repo.id, Some(&e));
+            }
+        }
+    }
+
+    if !results.is_empty() {
+        state.notify_status_changed();
+    }
+    results
+}
+
+/// 刷新仓库：最新提交与缓存一致时只更新缓存时间并返回 None，否则重新下载并扫描
+async fn refresh_repository_if_changed(
+    state: State<'_, AppState>,
+    repo: &Repository,
+) -> Result<Option<RepositoryRefreshResult>, AppError> {
+    if let (Some(cache_path), Some(cached_sha)) = (&repo.cache_path, &repo.cached_commit_sha) {
+        let (owner, repo_name, branch) = Repository::from_github_url(&repo.url)?;
+        let latest_sha = state.github
+            .resolve_commit_sha(&owner, &repo_name, branch.as_deref().unwrap_or("HEAD"))
+            .await?;
+        if !is_commit_changed(cached_sha, &latest_sha) && std::path::Path::new(cache_path).is_dir() {
+            state.db.update_repository_cache(&repo.id, cache_path, chrono::Utc::now(), Some(cached_sha))?;
+            return Ok(None);
+        }
+    }
+
+    let previous: std::collections::HashMap<String, Skill> = state.db.get_skills()?
+        .into_iter()
+        .filter(|skill| skill.repository_url == repo.url)
+        .map(|skill| (skill.id.clone(), skill))
+        .collect();
+
+    let skills = refresh_repository_cache(state.clone(), repo.id.clone()).await?;
+
+    let mut result = RepositoryRefreshResult {
+        repo_id: repo.id.clone(),
+        repo_name: repo.name.clone(),
+        new_skills: Vec::new(),
+        changed_skills: Vec::new(),
+        removed_skills: previous.values()
+            .filter(|old| !skills.iter().any(|skill| skill.id == old.id))
+            .map(|old| old.name.clone())
+            .collect(),
+        suspicious_updates: Vec::new(),
+    };
+    for skill in &skills {
+        match previous.get(&skill.id) {
+            None => result.new_skills.push(skill.name.clone()),
+            Some(old) if old.checksum.is_some() && old.checksum != skill.checksum => {
+                result.changed_skills.push(skill.name.clone())
+            }
+            Some(_) => {}
+        }
+    }
+
+    // 之前扫描过的技能重新扫描新内容，与上次的扫描报告比较（上次的报告保留作为基线）
+    let refreshed = state.db.get_repository(&repo.id)?;
+    let Some(cache_path) = refreshed.as_ref().and_then(|repo| repo.cache_path.clone()) else {
+        return Ok(Some(result));
+    };
+    let commit_sha = refreshed.and_then(|repo| repo.cached_commit_sha);
+    let reports = state.db.get_scan_reports()?;
+    let settings = state.settings.get();
+    let locale = settings.locale;
+    let low_priority = settings.background.low_priority_scans;
+
+    for skill in &skills {
+        let (Some(old), Some((previous_report, _))) = (previous.get(&skill.id), reports.get(&skill.id)) else {
+            continue;
+        };
+
+        // 后台刷新触发的扫描，按设置以较低优先级运行
+        let manager = Arc::clone(&state.skill_manager);
+        let (cache_path, scanned, locale) = (PathBuf::from(&cache_path), skill.clone(), locale.clone());
+        let current = match priority::run_background(low_priority, move || {
+            manager.scan_cached_skill(&cache_path, &scanned, &locale)
+        })
+        .await
+        {
+            Ok(report) => report,
+            Err(e) => {
+                log::warn!("重新扫描技能 {} 失败: {}", skill.name, e);
+                continue;
+            }
+        };
+
+        let checksum_changed = old.checksum.is_some() && old.checksum != skill.checksum;
+        match SuspiciousUpdate::detect(skill, checksum_changed, previous_report, &current) {
+            Some(mut update) => {
+                update.commit_sha = commit_sha.clone();
+                state.db.save_suspicious_update(&update)?;
+                state.db.add_skill_event(&SkillEvent {
+                    commit_sha: commit_sha.clone(),
+                    detail: Some(format!("评分 {} -> {}", update.previous_score, update.current_score)),
+                    ..SkillEvent::new(&skill.id, SkillEventKind::Flagged)
+                })?;
+                result.suspicious_updates.push(update);
+            }
+            None if !SuspiciousUpdate::has_severe_findings(&current) => state.db.delete_suspicious_update(&skill.id)?,
+            None => {}
+        }
+    }
+
+    Ok(Some(result))
 }
 
 /// 一键清除所有仓库缓存（但保留仓库记录）
 #[tauri::command]
 pub async fn clear_all_repository_caches(
     state: State<'_, AppState>,
-) -> Result<ClearAllCachesResult, String> {
-    let repos = state.db.get_repositories()
-        .map_err(|e| e.to_string())?;
+) -> Result<ClearAllCachesResult, AppError> {
+    let repos = state.db.get_repositories()?;
 
     let mut cleared_count = 0;
     let mut failed_count = 0;
     let mut total_size_freed: u64 = 0;
 
     // 获取缓存基础目录
-    let cache_base_dir = dirs::cache_dir()
-        .ok_or("无法获取缓存目录".to_string())?
-        .join("agent-skills-guard")
-        .join("repositories");
+    let cache_base_dir = CachePaths::require()?.repositories();
 
     if !cache_base_dir.exists() {
         // 缓存目录不存在，无需清理
@@ -464,7 +1083,7 @@ pub async fn clear_all_repository_caches(
 
                 // 计算目录大小（在删除前）
                 if parent.exists() {
-                    if let Ok(size) = dir_size(parent) {
+                    if let Ok(size) = fs_ops::dir_size(parent) {
                         total_size_freed += size;
                     }
                 }
@@ -478,7 +1097,7 @@ pub async fn clear_all_repository_caches(
 
                 // 删除文件
                 if parent.exists() {
-                    if let Err(e) = std::fs::remove_dir_all(parent) {
+                    if let Err(e) = fs_ops::remove_dir_all(parent) {
                         log::warn!("删除缓存目录失败: {:?}，错误: {}", parent, e);
                         failed_count += 1;
                     } else {
@@ -489,10 +1108,20 @@ pub async fn clear_all_repository_caches(
                     // 数据库中有记录但文件不存在，只清理元数据
                     cleared_count += 1;
                 }
+
+                release_cache_manifest(&state.github, &repo.url, false);
             }
         }
     }
 
+    // 统一回收不再被引用的 blob
+    if let Some(store) = state.github.blob_store() {
+        match store.collect_garbage() {
+            Ok((_, freed)) => total_size_freed += freed,
+            Err(e) => log::warn!("回收 blob 失败: {}", e),
+        }
+    }
+
     log::info!("清除所有缓存完成: 成功 {}, 失败 {}, 释放 {} 字节",
         cleared_count, failed_count, total_size_freed);
 
@@ -504,64 +1133,246 @@ pub async fn clear_all_repository_caches(
     })
 }
 
-/// 获取缓存统计信息
+/// 删除全部更新临时目录（staging），对应的待确认更新会被取消
 #[tauri::command]
-pub async fn get_cache_stats(
+pub async fn clear_staging_caches(
     state: State<'_, AppState>,
-) -> Result<CacheStats, String> {
-    let repos = state.db.get_repositories()
-        .map_err(|e| e.to_string())?;
-
-    let mut total_cached = 0;
-    let mut total_size: u64 = 0;
+) -> Result<CacheClearResult, AppError> {
+    let manager = &state.skill_manager;
+    let _operation = manager.begin_operation().await;
+    let (removed_count, freed_bytes) = manager.run_blocking(|manager| manager.clear_staging_dirs(None)).await?;
+    Ok(CacheClearResult { removed_count, freed_bytes })
+}
 
-    for repo in &repos {
-        if let Some(cache_path) = &repo.cache_path {
-            if let Some(parent) = std::path::PathBuf::from(cache_path).parent() {
-                if parent.exists() {
-                    total_cached += 1;
+/// 删除全部更新前保留的安装目录备份
+#[tauri::command]
+pub async fn clear_skill_backups(
+    state: State<'_, AppState>,
+) -> Result<CacheClearResult, AppError> {
+    let manager = &state.skill_manager;
+    let _operation = manager.begin_operation().await;
+    let (removed_count, freed_bytes) = manager.run_blocking(|manager| manager.clear_backup_dirs(None)).await?;
+    Ok(CacheClearResult { removed_count, freed_bytes })
+}
 
-                    // 计算目录大小
-                    if let Ok(size) = dir_size(parent) {
-                        total_size += size;
-                    }
-                }
+/// 删除已解压的仓库压缩包（archive.zip），解压后的缓存保留可用
+#[tauri::command]
+pub async fn clear_extracted_archives() -> Result<CacheClearResult, AppError> {
+    let repositories_dir = CachePaths::require()?.repositories();
+
+    fs_ops::run_blocking(move || {
+        let mut result = CacheClearResult { removed_count: 0, freed_bytes: 0 };
+        for (archive, size) in extracted_archives(&repositories_dir) {
+            if let Err(e) = std::fs::remove_file(&archive) {
+                log::warn!("删除压缩包失败: {:?}，错误: {}", archive, e);
+                continue;
             }
+            result.removed_count += 1;
+            result.freed_bytes += size;
         }
-    }
-
-    Ok(CacheStats {
-        total_repositories: repos.len(),
-        cached_repositories: total_cached,
-        total_size_bytes: total_size,
+        log::info!("已删除 {} 个已解压的压缩包，释放 {} 字节", result.removed_count, result.freed_bytes);
+        Ok(result)
     })
+    .await
+    .map_err(AppError::from)
 }
 
-/// 计算目录大小
-fn dir_size(path: &std::path::Path) -> Result<u64, std::io::Error> {
-    use walkdir::WalkDir;
-
-    let mut size = 0;
+/// 删除超过指定天数未刷新的仓库缓存（保留仓库记录）
+#[tauri::command]
+pub async fn clear_unused_caches(
+    state: State<'_, AppState>,
+    days: u32,
+) -> Result<CacheClearResult, AppError> {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(i64::from(days));
+    let mut result = CacheClearResult { removed_count: 0, freed_bytes: 0 };
 
-    for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
-        if entry.file_type().is_file() {
-            size += entry.metadata()?.len();
+    for repo in state.db.get_repositories()? {
+        if repo.cache_path.is_none() || repo.cached_at.is_some_and(|cached_at| cached_at >= cutoff) {
+            continue;
+        }
+        match remove_repository_cache(&state, &repo) {
+            Ok(freed) => {
+                result.removed_count += 1;
+                result.freed_bytes += freed;
+            }
+            Err(e) => log::warn!("清理仓库 {} 的缓存失败: {}", repo.name, e),
         }
     }
 
-    Ok(size)
+    log::info!("已清理 {} 个超过 {} 天未刷新的仓库缓存，释放 {} 字节", result.removed_count, days, result.freed_bytes);
+    Ok(result)
 }
 
-/// 缓存统计信息
-#[derive(serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct CacheStats {
-    pub total_repositories: usize,
-    pub cached_repositories: usize,
-    pub total_size_bytes: u64,
+/// 获取缓存占用明细
+#[tauri::command]
+pub async fn get_cache_usage(state: State<'_, AppState>) -> Result<CacheUsage, AppError> {
+    let cache_paths = CachePaths::require()?;
+    let backup_root = state.skill_manager.backup_root();
+
+    fs_ops::run_blocking(move || {
+        let size_of = |dir: PathBuf| {
+            if dir.exists() { fs_ops::dir_size(&dir).unwrap_or(0) } else { 0 }
+        };
+
+        let repositories_dir = cache_paths.repositories();
+        let archives_bytes: u64 = extracted_archives(&repositories_dir).iter().map(|(_, size)| size).sum();
+        let repositories_bytes = size_of(repositories_dir).saturating_sub(archives_bytes);
+        let staging_bytes = size_of(cache_paths.staging());
+        let backups_bytes = backup_root
+            .filter(|dir| dir.exists())
+            .map(|dir| fs_ops::dir_size(&dir).unwrap_or(0))
+            .unwrap_or(0);
+        let blobs_bytes = size_of(cache_paths.blobs());
+
+        Ok(CacheUsage {
+            repositories_bytes,
+            archives_bytes,
+            staging_bytes,
+            backups_bytes,
+            blobs_bytes,
+            total_bytes: repositories_bytes + archives_bytes + staging_bytes + backups_bytes + blobs_bytes,
+        })
+    })
+    .await
+    .map_err(AppError::from)
 }
 
-/// 清除所有缓存的结果
+/// 仓库缓存中已解压完成的压缩包及其大小（解压目录存在时才视为已解压）
+fn extracted_archives(repositories_dir: &std::path::Path) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = std::fs::read_dir(repositories_dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|repo_dir| repo_dir.join("extracted").is_dir())
+        .filter_map(|repo_dir| {
+            let archive = repo_dir.join("archive.zip");
+            let size = std::fs::metadata(&archive).ok()?.len();
+            Some((archive, size))
+        })
+        .collect()
+}
+
+/// 获取缓存统计信息
+#[tauri::command]
+pub async fn get_cache_stats(
+    state: State<'_, AppState>,
+) -> Result<CacheStats, AppError> {
+    let repos = state.db.get_repositories()?;
+
+    let (total_cached, total_size) = cache_usage(&repos);
+
+    Ok(CacheStats {
+        total_repositories: repos.len(),
+        cached_repositories: total_cached,
+        total_size_bytes: total_size,
+        temp_size_bytes: temp_dirs_usage(state.skill_manager.backup_root().as_deref()),
+    })
+}
+
+/// 统计仓库缓存：返回 (已缓存的仓库数, 缓存总大小)
+pub(crate) fn cache_usage(repos: &[Repository]) -> (usize, u64) {
+    let mut total_cached = 0;
+    let mut total_size: u64 = 0;
+
+    for repo in repos {
+        if let Some(cache_path) = &repo.cache_path {
+            if let Some(parent) = std::path::PathBuf::from(cache_path).parent() {
+                if parent.exists() {
+                    total_cached += 1;
+
+                    // 计算目录大小
+                    if let Ok(size) = fs_ops::dir_size(parent) {
+                        total_size += size;
+                    }
+                }
+            }
+        }
+    }
+
+    (total_cached, total_size)
+}
+
+/// 统计更新临时目录（staging）与更新备份目录占用的空间
+pub(crate) fn temp_dirs_usage(backup_root: Option<&std::path::Path>) -> u64 {
+    CachePaths::current()
+        .map(|paths| paths.staging())
+        .into_iter()
+        .chain(backup_root.map(std::path::Path::to_path_buf))
+        .filter(|dir| dir.exists())
+        .filter_map(|dir| fs_ops::dir_size(&dir).ok())
+        .sum()
+}
+
+/// 删除仓库缓存对应的内容寻址清单，可选立即回收无引用的 blob
+fn release_cache_manifest(github: &GitHubService, repo_url: &str, collect_garbage: bool) {
+    let store = match github.blob_store() {
+        Some(store) => store,
+        None => return,
+    };
+
+    let (owner, repo_name, _) = match Repository::from_github_url(repo_url) {
+        Ok(parsed) => parsed,
+        Err(_) => return,
+    };
+
+    if let Some(cache_paths) = CachePaths::current() {
+        let repositories_dir = cache_paths.repositories();
+        let key = crate::services::BlobStore::manifest_key(&repositories_dir, &owner, &repo_name);
+        if let Err(e) = store.remove_manifest(&key) {
+            log::warn!("删除缓存清单失败: {}", e);
+        }
+    }
+
+    if collect_garbage {
+        if let Err(e) = store.collect_garbage() {
+            log::warn!("回收 blob 失败: {}", e);
+        }
+    }
+}
+
+/// 缓存统计信息
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub total_repositories: usize,
+    pub cached_repositories: usize,
+    pub total_size_bytes: u64,
+    /// 更新临时目录与更新备份占用的空间（超过保留期限后自动清理）
+    pub temp_size_bytes: u64,
+}
+
+/// 单项缓存清理的结果
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheClearResult {
+    /// 删除的目录或文件数
+    pub removed_count: usize,
+    pub freed_bytes: u64,
+}
+
+/// 缓存占用明细（字节）
+///
+/// 仓库缓存与 staging 中的文件以硬链接共享 blobs 中的内容，同一份内容会在多项中分别计入
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheUsage {
+    /// 仓库缓存（不含压缩包）
+    pub repositories_bytes: u64,
+    /// 已下载的仓库压缩包
+    pub archives_bytes: u64,
+    /// 更新临时目录
+    pub staging_bytes: u64,
+    /// 更新前保留的安装目录备份
+    pub backups_bytes: u64,
+    /// 内容寻址存储
+    pub blobs_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// 清除所有缓存的结果
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ClearAllCachesResult {
@@ -573,7 +1384,7 @@ pub struct ClearAllCachesResult {
 
 /// 打开技能目录
 #[tauri::command]
-pub async fn open_skill_directory(local_path: String) -> Result<(), String> {
+pub async fn open_skill_directory(local_path: String) -> Result<(), AppError> {
     use std::process::Command;
 
     #[cfg(target_os = "windows")]
@@ -581,7 +1392,7 @@ pub async fn open_skill_directory(local_path: String) -> Result<(), String> {
         Command::new("explorer")
             .arg(&local_path)
             .spawn()
-            .map_err(|e| format!("Failed to open directory: {}", e))?;
+            .map_err(|e| AppError::from(e).context("Failed to open directory"))?;
     }
 
     #[cfg(target_os = "macos")]
@@ -589,7 +1400,7 @@ pub async fn open_skill_directory(local_path: String) -> Result<(), String> {
         Command::new("open")
             .arg(&local_path)
             .spawn()
-            .map_err(|e| format!("Failed to open directory: {}", e))?;
+            .map_err(|e| AppError::from(e).context("Failed to open directory"))?;
     }
 
     #[cfg(target_os = "linux")]
@@ -597,7 +1408,7 @@ pub async fn open_skill_directory(local_path: String) -> Result<(), String> {
         Command::new("xdg-open")
             .arg(&local_path)
             .spawn()
-            .map_err(|e| format!("Failed to open directory: {}", e))?;
+            .map_err(|e| AppError::from(e).context("Failed to open directory"))?;
     }
 
     Ok(())
@@ -605,7 +1416,7 @@ pub async fn open_skill_directory(local_path: String) -> Result<(), String> {
 
 /// 获取默认的用户目录安装路径
 #[tauri::command]
-pub async fn get_default_install_path() -> Result<String, String> {
+pub async fn get_default_install_path() -> Result<String, AppError> {
     let user_path = dirs::home_dir()
         .ok_or("无法获取用户主目录")?
         .join(".claude")
@@ -614,9 +1425,12 @@ pub async fn get_default_install_path() -> Result<String, String> {
     Ok(user_path.to_string_lossy().to_string())
 }
 
-/// 打开文件夹选择器，让用户选择自定义安装路径
+/// 打开文件夹选择器，让用户选择自定义安装路径；选择的目录加入批准的安装目录
 #[tauri::command]
-pub async fn select_custom_install_path(app: tauri::AppHandle) -> Result<Option<String>, String> {
+pub async fn select_custom_install_path(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, AppError> {
     use tauri_plugin_dialog::DialogExt;
 
     let folder_path = app.dialog()
@@ -633,94 +1447,511 @@ pub async fn select_custom_install_path(app: tauri::AppHandle) -> Result<Option<
         match std::fs::write(&test_file, "test") {
             Ok(_) => {
                 let _ = std::fs::remove_file(&test_file);
+                approve_install_dir(&state, &path)?;
                 Ok(Some(path.to_string_lossy().to_string()))
             }
-            Err(_) => Err("选择的目录不可写，请检查权限".to_string())
+            Err(_) => Err(AppError::new(ErrorKind::PermissionDenied, "选择的目录不可写，请检查权限"))
         }
     } else {
         Ok(None)
     }
 }
 
-const FEATURED_REPOSITORIES_REMOTE_URL: &str =
-    "https://raw.githubusercontent.com/tanaer/agent-skills-guard-pro/main/featured-repositories.yaml";
-const DEFAULT_FEATURED_REPOSITORIES_YAML: &str = include_str!("../../../featured-repositories.yaml");
+/// 选择导出文件的保存位置（报告、技能清单、数据库备份等），所在目录加入批准的导出目录
+#[tauri::command]
+pub async fn select_export_path(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    default_name: Option<String>,
+) -> Result<Option<String>, AppError> {
+    use tauri_plugin_dialog::DialogExt;
 
-fn featured_repositories_cache_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    let app_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let mut dialog = app.dialog().file().set_title("选择导出位置");
+    if let Some(name) = default_name.as_deref().map(str::trim).filter(|name| !name.is_empty()) {
+        dialog = dialog.set_file_name(name);
+    }
+    let Some(file_path) = dialog.blocking_save_file() else {
+        return Ok(None);
+    };
 
-    std::fs::create_dir_all(&app_dir)
-        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    let path = PathBuf::from(file_path.to_string());
+    let dir = path.parent()
+        .filter(|dir| dir.is_absolute())
+        .ok_or_else(|| AppError::invalid_input(format!("无效的导出路径: {}", path.display())))?;
+    if !PathPolicy::exports(&state.settings.get().directories).allows(dir) {
+        state.settings.update(|settings| settings.directories.approved_export_dirs.push(dir.to_path_buf()))?;
+        log::info!("已批准导出目录: {:?}", dir);
+    }
+    Ok(Some(path.to_string_lossy().to_string()))
+}
 
-    Ok(app_dir.join("featured-repositories.yaml"))
+/// 将用户在目录选择器中选择的目录加入批准的安装目录（已在允许的目录中时不重复添加）
+fn approve_install_dir(state: &AppState, dir: &std::path::Path) -> Result<(), AppError> {
+    if PathPolicy::new(&state.settings.get().directories).allows(dir) {
+        return Ok(());
+    }
+    state.settings.update(|settings| settings.directories.approved_install_dirs.push(dir.to_path_buf()))?;
+    log::info!("已批准安装目录: {:?}", dir);
+    Ok(())
+}
+
+const DEFAULT_FEATURED_REPOSITORIES_YAML: &str = include_str!("../../../featured-repositories.yaml");
+
+/// 应用数据目录：便携模式下为可执行文件旁的 data 目录，否则为系统应用数据目录
+pub(crate) fn app_data_dir(app: &tauri::AppHandle) -> Result<PathBuf, AppError> {
+    if let Some(data_root) = portable::data_root() {
+        return Ok(data_root.to_path_buf());
+    }
+
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::new(ErrorKind::Io, format!("Failed to get app data directory: {}", e)))
 }
 
 /// 获取精选仓库列表
 #[tauri::command]
-pub async fn get_featured_repositories(app: tauri::AppHandle) -> Result<FeaturedRepositoriesConfig, String> {
-    // 1) 优先读取 app_data_dir 下的缓存文件（支持在线刷新后持久化）
-    let cache_path = featured_repositories_cache_path(&app)?;
-    if let Ok(cached_yaml) = std::fs::read_to_string(&cache_path) {
+pub async fn get_featured_repositories(state: State<'_, AppState>) -> Result<FeaturedRepositoriesConfig, AppError> {
+    featured_repositories(&state)
+}
+
+fn featured_repositories(state: &AppState) -> Result<FeaturedRepositoriesConfig, AppError> {
+    // 1) 优先读取在线刷新后缓存的内容
+    if let Some(cached_yaml) = state.remote_content.cached(RemoteContentKind::FeaturedRepositories) {
         match serde_yaml::from_str::<FeaturedRepositoriesConfig>(&cached_yaml) {
             Ok(config) => return Ok(config),
-            Err(e) => {
-                log::warn!(
-                    "精选仓库缓存文件解析失败，将回退到内置默认配置: {:?}, 错误: {}",
-                    cache_path,
-                    e
-                );
-            }
+            Err(e) => log::warn!("精选仓库缓存文件解析失败，将回退到内置默认配置: {}", e),
         }
     }
 
     // 2) 回退到编译期内置的默认 YAML（用于首次启动/离线/打包环境）
     serde_yaml::from_str::<FeaturedRepositoriesConfig>(DEFAULT_FEATURED_REPOSITORIES_YAML)
-        .map_err(|e| format!("Failed to parse default featured repositories: {}", e))
+        .map_err(|e| AppError::internal(format!("Failed to parse default featured repositories: {}", e)))
 }
 
-/// 刷新精选仓库列表（从 GitHub 下载最新 YAML 并写入 app_data_dir 缓存）
+/// 刷新精选仓库列表（内容未变化时不重新下载）
 #[tauri::command]
 pub async fn refresh_featured_repositories(
-    app: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<FeaturedRepositoriesConfig, String> {
-    use std::io::Write;
+) -> Result<FeaturedRepositoriesConfig, AppError> {
+    refresh_remote_content_kind(&state, RemoteContentKind::FeaturedRepositories).await?;
+    featured_repositories(&state)
+}
 
-    let yaml_content = state.http_client
-        .get(FEATURED_REPOSITORIES_REMOTE_URL)
-        .header(reqwest::header::USER_AGENT, "agent-skills-guard")
-        .send()
-        .await
-        .map_err(|e| format!("Failed to download featured repositories: {}", e))?
-        .error_for_status()
-        .map_err(|e| format!("Failed to download featured repositories: {}", e))?
-        .text()
+/// 校验下载的远程内容，通过后才写入缓存
+fn validate_remote_content(kind: RemoteContentKind, content: &str) -> anyhow::Result<()> {
+    match kind {
+        RemoteContentKind::FeaturedRepositories => {
+            serde_yaml::from_str::<FeaturedRepositoriesConfig>(content)?;
+        }
+    }
+    Ok(())
+}
+
+async fn refresh_remote_content_kind(state: &AppState, kind: RemoteContentKind) -> Result<RemoteContentStatus, AppError> {
+    let network = state.settings.get().network;
+    Ok(state.remote_content.refresh(kind, &network, |content| validate_remote_content(kind, content)).await?)
+}
+
+/// 刷新过期的远程内容（后台定时调用），失败的内容在状态中记录错误
+pub async fn refresh_stale_remote_content(state: &AppState) -> Vec<RemoteContentStatus> {
+    let mut statuses = Vec::new();
+    for &kind in RemoteContentKind::ALL {
+        if state.remote_content.status(kind).stale {
+            let status = refresh_remote_content_kind(state, kind).await;
+            statuses.push(status.unwrap_or_else(|_| state.remote_content.status(kind)));
+        }
+    }
+    statuses
+}
+
+/// 获取各远程内容的缓存状态（上次更新、检查时间，是否过期及上次失败原因）
+#[tauri::command]
+pub async fn get_remote_content_status(state: State<'_, AppState>) -> Result<Vec<RemoteContentStatus>, AppError> {
+    Ok(RemoteContentKind::ALL.iter().map(|&kind| state.remote_content.status(kind)).collect())
+}
+
+/// 立即刷新远程内容（kinds 为空时刷新全部），单项失败不影响其他内容，失败原因见返回状态的 lastError
+#[tauri::command]
+pub async fn refresh_remote_content(
+    state: State<'_, AppState>,
+    kinds: Option<Vec<RemoteContentKind>>,
+) -> Result<Vec<RemoteContentStatus>, AppError> {
+    let kinds = kinds.filter(|kinds| !kinds.is_empty()).unwrap_or_else(|| RemoteContentKind::ALL.to_vec());
+    let mut statuses = Vec::new();
+    for kind in kinds {
+        let status = refresh_remote_content_kind(&state, kind).await;
+        statuses.push(status.unwrap_or_else(|_| state.remote_content.status(kind)));
+    }
+    Ok(statuses)
+}
+
+/// 导入技能清单的结果
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillListImportResult {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// 本次新添加的仓库地址
+    pub added_repositories: Vec<String>,
+    /// 清单引用且已扫描的技能，可直接安装
+    pub skills: Vec<Skill>,
+    /// 扫描后在仓库中未找到的技能（仓库地址#路径）
+    pub missing_skills: Vec<String>,
+    /// 扫描失败的仓库及错误信息
+    pub failed_repositories: Vec<String>,
+    /// 内容与清单中固定的哈希不一致（或无法校验）的技能及原因，不会出现在 skills 中
+    pub integrity_failures: Vec<String>,
+}
+
+/// 导入技能清单（URL 或本地文件，JSON/YAML）：添加清单引用的仓库并扫描，返回引用的技能
+///
+/// 已添加的仓库直接复用；单个仓库扫描失败不中断导入。清单为技能固定了内容哈希时校验仓库中的内容，
+/// 一致时记录该哈希，安装时再次校验
+#[tauri::command]
+pub async fn import_skill_list(
+    state: State<'_, AppState>,
+    source: String,
+) -> Result<SkillListImportResult, AppError> {
+    let content = read_skill_list_source(&state, source.trim()).await?;
+    let list = SkillList::parse(&content).map_err(|e| AppError::invalid_input(format!("{:#}", e)))?;
+
+    let mut result = SkillListImportResult {
+        name: list.name.clone(),
+        description: list.description.clone(),
+        added_repositories: Vec::new(),
+        skills: Vec::new(),
+        missing_skills: Vec::new(),
+        failed_repositories: Vec::new(),
+        integrity_failures: Vec::new(),
+    };
+
+    for (url, paths) in list.repositories() {
+        let (repo_id, added) = find_or_add_repository(&state, &url)?;
+        if added {
+            result.added_repositories.push(url.clone());
+        }
+
+        let skills = match scan_repository(state.clone(), repo_id).await {
+            Ok(skills) => skills,
+            Err(e) => {
+                log::warn!("导入技能清单时扫描仓库失败: {}: {}", url, e.message);
+                result.failed_repositories.push(format!("{}: {}", url, e.message));
+                continue;
+            }
+        };
+
+        if paths.is_empty() {
+            result.skills.extend(skills);
+            continue;
+        }
+        for path in paths {
+            let Some(skill) = skills.iter().find(|skill| crate::models::normalize_skill_path(&skill.file_path) == path) else {
+                result.missing_skills.push(format!("{}#{}", url, path));
+                continue;
+            };
+            if let Some(sha256) = list.expected_hash(&url, &path) {
+                let (pinned, sha256) = (skill.clone(), sha256.to_string());
+                let verified = state.skill_manager.run_blocking(move |manager| manager.pin_skill_content(&pinned, &sha256)).await;
+                if let Err(e) = verified {
+                    log::warn!("技能清单中的技能未通过内容校验: {}#{}: {}", url, path, e);
+                    result.integrity_failures.push(format!("{}#{}: {}", url, path, AppError::from(e).message));
+                    continue;
+                }
+            }
+            result.skills.push(skill.clone());
+        }
+    }
+
+    log::info!(
+        "导入技能清单 {:?}: 新增 {} 个仓库，{} 个技能，未找到 {} 个，失败仓库 {} 个，未通过内容校验 {} 个",
+        result.name, result.added_repositories.len(), result.skills.len(),
+        result.missing_skills.len(), result.failed_repositories.len(), result.integrity_failures.len()
+    );
+    Ok(result)
+}
+
+/// 拖放路径的处理结果
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroppedItemResult {
+    pub path: String,
+    pub kind: DroppedItemKind,
+    /// 技能目录或压缩包的安全扫描报告（安装前检查）
+    pub report: Option<SecurityReport>,
+    /// 导入技能清单的结果
+    pub skill_list: Option<SkillListImportResult>,
+    /// 文本文件中本次新添加的仓库地址
+    pub added_repositories: Vec<String>,
+    /// 文本文件中的仓库扫描出的技能，可直接安装
+    pub skills: Vec<Skill>,
+    /// 处理失败（或部分仓库扫描失败）时的错误信息
+    pub error: Option<String>,
+}
+
+/// 处理拖放到窗口的路径：按类型分别处理后返回每一项的结果（单项失败不影响其他项）
+///
+/// - 技能目录、技能压缩包：扫描并返回安全报告
+/// - 技能清单（JSON/YAML）：按 import_skill_list 导入
+/// - 包含仓库地址的 .txt 文件：添加其中的仓库并扫描
+#[tauri::command]
+pub async fn handle_dropped_paths(
+    state: State<'_, AppState>,
+    paths: Vec<String>,
+    locale: String,
+) -> Result<Vec<DroppedItemResult>, AppError> {
+    let locale = crate::i18n::validate_locale(&locale).to_string();
+    let settings = state.settings.get();
+    let names = settings.discovery.manifest_names();
+    let scanner = Arc::new(settings.scan_policy.scanner());
+
+    let mut results = Vec::with_capacity(paths.len());
+    for path in paths {
+        let path_buf = PathBuf::from(&path);
+        let kind = {
+            let (path_buf, names) = (path_buf.clone(), names.clone());
+            fs_ops::run_blocking(move || dropped::classify_dropped_path(&path_buf, &names)).await
+        };
+        let mut result = DroppedItemResult {
+            path: path.clone(),
+            kind: DroppedItemKind::Unsupported,
+            report: None,
+            skill_list: None,
+            added_repositories: Vec::new(),
+            skills: Vec::new(),
+            error: None,
+        };
+        let kind = match kind {
+            Ok(kind) => kind,
+            Err(e) => {
+                log::warn!("无法识别拖放的路径 {}: {}", path, e);
+                result.error = Some(format!("{:#}", e));
+                results.push(result);
+                continue;
+            }
+        };
+        result.kind = kind;
+
+        match kind {
+            DroppedItemKind::SkillFolder => {
+                let scanner = Arc::clone(&scanner);
+                let locale = locale.clone();
+                let skill_id = path_buf.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+                match fs_ops::run_blocking(move || scanner.scan_directory(&path, &skill_id, &locale)).await {
+                    Ok(report) => result.report = Some(report),
+                    Err(e) => result.error = Some(format!("{:#}", e)),
+                }
+            }
+            DroppedItemKind::Archive => {
+                let scanner = Arc::clone(&scanner);
+                let locale = locale.clone();
+                let format = fs_ops::run_blocking({
+                    let path_buf = path_buf.clone();
+                    move || ArchiveFormat::detect(&path_buf)
+                })
+                .await;
+                match format {
+                    Ok(Some(format)) => {
+                        match fs_ops::run_blocking(move || security::scan_archive(&scanner, &path_buf, format, &locale)).await {
+                            Ok(report) => result.report = Some(report),
+                            Err(e) => result.error = Some(format!("{:#}", e)),
+                        }
+                    }
+                    Ok(None) => result.kind = DroppedItemKind::Unsupported,
+                    Err(e) => result.error = Some(format!("{:#}", e)),
+                }
+            }
+            DroppedItemKind::SkillList => match import_skill_list(state.clone(), path).await {
+                Ok(imported) => result.skill_list = Some(imported),
+                Err(e) => result.error = Some(e.message),
+            },
+            DroppedItemKind::RepositoryUrls => {
+                if let Err(e) = add_dropped_repositories(&state, &path_buf, &mut result).await {
+                    result.error = Some(e.message);
+                }
+            }
+            DroppedItemKind::Unsupported => {}
+        }
+
+        if result.kind == DroppedItemKind::Unsupported && result.error.is_none() {
+            result.error = Some("不支持的文件或目录（需为技能目录、技能压缩包、技能清单或仓库地址列表）".to_string());
+        }
+        results.push(result);
+    }
+
+    log::info!(
+        "处理拖放的 {} 个路径，失败 {} 个",
+        results.len(), results.iter().filter(|result| result.error.is_some()).count()
+    );
+    Ok(results)
+}
+
+/// 添加并扫描仓库的结果
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddAndScanResult {
+    pub repo_id: String,
+    /// 是否为本次新添加的仓库
+    pub added: bool,
+    /// 扫描出的技能；地址指向技能目录时只包含该目录下的技能
+    pub skills: Vec<Skill>,
+}
+
+/// 一键添加并扫描 GitHub 仓库或技能地址（剪贴板识别的地址）
+#[tauri::command]
+pub async fn add_and_scan_repository_url(
+    state: State<'_, AppState>,
+    url: String,
+) -> Result<AddAndScanResult, AppError> {
+    let parsed = GitHubUrl::parse(&url)
+        .ok_or_else(|| AppError::invalid_input(format!("不是有效的 GitHub 仓库地址: {}", url)))?;
+    let (repo_id, added) = find_or_add_repository(&state, &parsed.repository_url())?;
+
+    let mut skills = scan_repository(state.clone(), repo_id.clone()).await?;
+    if let Some(path) = &parsed.path {
+        let prefix = format!("{}/", path);
+        skills.retain(|skill| {
+            let skill_path = crate::models::normalize_skill_path(&skill.file_path);
+            skill_path == *path || skill_path.starts_with(&prefix)
+        });
+    }
+
+    Ok(AddAndScanResult { repo_id, added, skills })
+}
+
+/// 添加文本文件中的仓库并扫描；单个仓库扫描失败只记入结果的错误信息
+async fn add_dropped_repositories(
+    state: &State<'_, AppState>,
+    path: &std::path::Path,
+    result: &mut DroppedItemResult,
+) -> Result<(), AppError> {
+    let content = tokio::fs::read_to_string(path)
         .await
-        .map_err(|e| format!("Failed to read featured repositories content: {}", e))?;
+        .map_err(|e| AppError::from(e).context("无法读取仓库地址列表"))?;
+
+    let mut failures = Vec::new();
+    for url in dropped::repository_urls(&content) {
+        let (repo_id, added) = find_or_add_repository(state, &url)?;
+        if added {
+            result.added_repositories.push(url.clone());
+        }
+        match scan_repository(state.clone(), repo_id).await {
+            Ok(skills) => result.skills.extend(skills),
+            Err(e) => {
+                log::warn!("扫描拖放的仓库失败: {}: {}", url, e.message);
+                failures.push(format!("{}: {}", url, e.message));
+            }
+        }
+    }
+    if !failures.is_empty() {
+        result.error = Some(failures.join("; "));
+    }
+    Ok(())
+}
 
-    // 先校验解析成功，再落盘
-    let config: FeaturedRepositoriesConfig = serde_yaml::from_str(&yaml_content)
-        .map_err(|e| format!("Failed to parse downloaded featured repositories: {}", e))?;
+/// 查找已添加的同一仓库，未添加时新增，返回 (仓库 ID, 是否新添加)
+fn find_or_add_repository(state: &AppState, url: &str) -> Result<(String, bool), AppError> {
+    let existing = state.db.get_repositories()?
+        .into_iter()
+        .find(|repo| crate::models::same_repository(&repo.url, url));
+    if let Some(repo) = existing {
+        return Ok((repo.id, false));
+    }
 
-    let cache_path = featured_repositories_cache_path(&app)?;
-    let cache_dir = cache_path
-        .parent()
-        .ok_or_else(|| "Failed to get featured repositories cache directory".to_string())?;
+    let (_, repo_name, _) = Repository::from_github_url(url)?;
+    let repo = Repository::new(url.to_string(), repo_name);
+    state.db.add_repository(&repo)?;
+    Ok((repo.id, true))
+}
 
-    let mut tmp = tempfile::NamedTempFile::new_in(cache_dir)
-        .map_err(|e| format!("Failed to create temp file: {}", e))?;
-    tmp.write_all(yaml_content.as_bytes())
-        .map_err(|e| format!("Failed to write temp file: {}", e))?;
-    tmp.flush()
-        .map_err(|e| format!("Failed to flush temp file: {}", e))?;
+/// 导出技能清单的结果
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillListExportResult {
+    /// 清单内容（与导入格式相同）
+    pub content: String,
+    pub skill_count: usize,
+    /// 上传到 Gist 时的网页地址
+    pub gist_url: Option<String>,
+}
 
-    tmp.persist(&cache_path)
-        .map_err(|e| format!("Failed to persist featured repositories cache: {}", e))?;
+/// 将已安装的技能导出为技能清单（格式同 import_skill_list），可写入文件或上传为 GitHub Gist
+///
+/// format 为 json 或 yaml；upload_gist 为 true 时使用已配置的 GitHub Token 创建 Gist（默认私密）；
+/// pin_hashes 默认为 true，记录每个技能安装目录的内容哈希，在其他机器导入时校验
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_skill_list(
+    state: State<'_, AppState>,
+    name: Option<String>,
+    description: Option<String>,
+    format: String,
+    path: Option<String>,
+    upload_gist: Option<bool>,
+    public: Option<bool>,
+    pin_hashes: Option<bool>,
+) -> Result<SkillListExportResult, AppError> {
+    let format = SkillListFormat::parse(&format)
+        .ok_or_else(|| AppError::invalid_input(format!("不支持的清单格式: {}", format)))?;
+
+    let skills = state.skill_manager.get_installed_skills()?;
+    let mut list = SkillList::from_skills(name, description, &skills);
+    if list.skills.is_empty() {
+        return Err(AppError::invalid_input("没有可导出的已安装技能（本地技能不会被导出）"));
+    }
+    list.attach_reviews(&skills, &state.db.get_skill_reviews()?);
+    if pin_hashes.unwrap_or(true) {
+        list = fs_ops::run_blocking(move || {
+            list.pin_hashes(&skills, |dir| {
+                fs_ops::dir_hash(dir).inspect_err(|e| log::warn!("无法计算技能目录的哈希: {:?}, 错误: {}", dir, e)).ok()
+            });
+            Ok(list)
+        }).await?;
+    }
+    let content = list.render(format)?;
+
+    if let Some(path) = &path {
+        let path = state.check_export_path(path)?;
+        tokio::fs::write(&path, &content)
+            .await
+            .map_err(|e| AppError::from(e).context("无法写入技能清单文件"))?;
+    }
 
-    Ok(config)
+    let gist_url = if upload_gist.unwrap_or(false) {
+        let filename = format!("skills.{}", format.extension());
+        let description = list.name.as_deref().unwrap_or("Agent skills");
+        let url = state.github
+            .create_gist(&filename, &content, description, public.unwrap_or(false))
+            .await?;
+        log::info!("技能清单已上传到 Gist: {}", url);
+        Some(url)
+    } else {
+        None
+    };
+
+    Ok(SkillListExportResult {
+        skill_count: list.skills.len(),
+        content,
+        gist_url,
+    })
+}
+
+/// 读取技能清单内容：http(s) 地址通过网络下载，其余视为本地文件路径
+async fn read_skill_list_source(state: &AppState, source: &str) -> Result<String, AppError> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        return state.http_client
+            .get(source)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| AppError::new(ErrorKind::Network, format!("下载技能清单失败: {}", e)))?
+            .text()
+            .await
+            .map_err(|e| AppError::new(ErrorKind::Network, format!("读取技能清单内容失败: {}", e)));
+    }
+
+    tokio::fs::read_to_string(source)
+        .await
+        .map_err(|e| AppError::from(e).context("无法读取技能清单文件"))
 }
 
 /// 检查仓库是否已添加
@@ -728,28 +1959,132 @@ pub async fn refresh_featured_repositories(
 pub async fn is_repository_added(
     state: State<'_, AppState>,
     url: String,
-) -> Result<bool, String> {
-    let repos = state.db.get_repositories()
-        .map_err(|e| e.to_string())?;
+) -> Result<bool, AppError> {
+    let repos = state.db.get_repositories()?;
 
     Ok(repos.iter().any(|r| r.url == url))
 }
 
 /// 检查已安装技能的更新
 /// 返回：Vec<(skill_id, latest_commit_sha)>
+/// 已配置 GitHub Token 时通过 GraphQL 批量查询，失败或未配置时回退到 REST 逐个查询
 #[tauri::command]
 pub async fn check_skills_updates(
     state: State<'_, AppState>,
-) -> Result<Vec<(String, String)>, String> {
-    let manager = state.skill_manager.lock().await;
-    let installed_skills = manager.get_installed_skills()
-        .map_err(|e| e.to_string())?;
+) -> Result<Vec<(String, String)>, AppError> {
+    let updates = find_skill_updates(&state).await?;
+    record_update_check(&state, &updates);
+    Ok(updates)
+}
 
-    let mut updates = Vec::new();
+/// 记录本次检查更新的结果，供概览统计与后台检查使用
+fn record_update_check(state: &AppState, updates: &[(String, String)]) {
+    let record = statistics::UpdateCheckRecord {
+        checked_at: chrono::Utc::now().to_rfc3339(),
+        skill_ids: updates.iter().map(|(id, _)| id.clone()).collect(),
+    };
+    match serde_json::to_string(&record) {
+        Ok(json) => {
+            if let Err(e) = state.db.set_setting(statistics::LAST_UPDATE_CHECK_KEY, &json) {
+                log::warn!("保存更新检查结果失败: {}", e);
+            }
+        }
+        Err(e) => log::warn!("序列化更新检查结果失败: {}", e),
+    }
+    state.notify_status_changed();
+}
+
+/// 后台检查发现的技能更新（skill-updates-available 事件），多个技能合并为一条通知
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillUpdatesNotification {
+    pub count: usize,
+    pub skills: Vec<SkillUpdateNotice>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillUpdateNotice {
+    pub skill_id: String,
+    pub skill_name: String,
+    /// 最新提交 SHA（直接地址来源为内容哈希）
+    pub latest: String,
+}
+
+/// 距上次检查更新超过设置的间隔时在后台检查，返回需要通知的更新：
+/// 跳过关闭了更新通知的技能，以及上次检查时已发现的更新（避免每次检查重复通知）
+pub async fn check_skill_updates_if_due(state: State<'_, AppState>) -> Vec<SkillUpdateNotice> {
+    let settings = state.settings.get();
+    let interval_hours = settings.background.update_check_interval_hours;
+    if interval_hours == 0 {
+        return Vec::new();
+    }
+
+    let previous = statistics::last_update_check(&state.db).ok().flatten();
+    let last_checked = previous.as_ref()
+        .and_then(|record| chrono::DateTime::parse_from_rfc3339(&record.checked_at).ok());
+    if last_checked.is_some_and(|at| chrono::Utc::now() - at.with_timezone(&chrono::Utc) < chrono::Duration::hours(interval_hours as i64)) {
+        return Vec::new();
+    }
 
+    let updates = match find_skill_updates(&state).await {
+        Ok(updates) => updates,
+        Err(e) => {
+            log::warn!("后台检查技能更新失败: {}", e.message);
+            return Vec::new();
+        }
+    };
+    record_update_check(&state, &updates);
+
+    let known: Vec<String> = previous.map(|record| record.skill_ids).unwrap_or_default();
+    let muted = &settings.notifications.muted_skill_updates;
+    let names: std::collections::HashMap<String, String> = state.db.get_skills()
+        .map(|skills| skills.into_iter().map(|skill| (skill.id, skill.name)).collect())
+        .unwrap_or_default();
+    updates
+        .into_iter()
+        .filter(|(skill_id, _)| !known.contains(skill_id) && !muted.contains(skill_id))
+        .map(|(skill_id, latest)| SkillUpdateNotice {
+            skill_name: names.get(&skill_id).cloned().unwrap_or_else(|| skill_id.clone()),
+            skill_id,
+            latest,
+        })
+        .collect()
+}
+
+/// 开启或关闭单个技能的更新通知
+#[tauri::command]
+pub async fn set_skill_update_notifications(
+    state: State<'_, AppState>,
+    skill_id: String,
+    enabled: bool,
+) -> Result<(), AppError> {
+    state.settings.update(|settings| {
+        let muted = &mut settings.notifications.muted_skill_updates;
+        muted.retain(|id| id != &skill_id);
+        if !enabled {
+            muted.push(skill_id);
+        }
+    })?;
+    Ok(())
+}
+
+async fn find_skill_updates(
+    state: &AppState,
+) -> Result<Vec<(String, String)>, AppError> {
+    let manager = &state.skill_manager;
+    let installed_skills = manager.get_installed_skills()?;
+
+    // 收集可检查更新的技能（直接地址来源的技能按内容哈希单独检查）
+    let mut candidates = Vec::new();
+    let mut direct_url_skills = Vec::new();
     for skill in installed_skills {
-        // 跳过本地技能
-        if skill.repository_url == "local" {
+        if skill.source_type == SourceType::DirectUrl && !skill.source_removed {
+            direct_url_skills.push(skill);
+            continue;
+        }
+        // 跳过不支持更新的来源（本地导入等）和来源仓库已删除的技能
+        if !skill.source_type.supports_updates() || skill.source_removed {
             continue;
         }
 
@@ -762,6 +2097,61 @@ pub async fn check_skills_updates(
             }
         };
 
+        candidates.push((skill, owner, repo));
+    }
+
+    let mut updates = Vec::new();
+    for skill in direct_url_skills {
+        match manager.check_direct_url_update(&skill).await {
+            Ok(Some(checksum)) => {
+                log::info!("技能 {} 的内容已变化: {}", skill.name, checksum);
+                updates.push((skill.id, checksum));
+            }
+            Ok(None) => log::debug!("技能 {} 无更新", skill.name),
+            Err(e) => log::warn!("检查技能 {} 更新时出错: {}", skill.name, e),
+        }
+    }
+
+    if state.github.has_token() {
+        // 没有 installed_commit_sha 的技能无法判断是否更新，不参与批量查询
+        let batchable: Vec<_> = candidates
+            .iter()
+            .filter(|(skill, _, _)| skill.installed_commit_sha.is_some())
+            .collect();
+
+        let queries: Vec<LatestCommitQuery> = batchable
+            .iter()
+            .map(|(skill, owner, repo)| LatestCommitQuery {
+                owner: owner.clone(),
+                repo: repo.clone(),
+                path: skill.file_path.clone(),
+            })
+            .collect();
+
+        match state.github.batch_latest_commits(&queries).await {
+            Ok(latest) => {
+                for ((skill, _, _), latest_sha) in batchable.iter().zip(latest) {
+                    let installed_sha = skill.installed_commit_sha.as_deref().unwrap_or_default();
+                    match latest_sha {
+                        Some(latest_sha) if is_commit_changed(installed_sha, &latest_sha) => {
+                            log::info!("技能 {} 有更新可用: {}", skill.name, latest_sha);
+                            updates.push((skill.id.clone(), latest_sha));
+                        }
+                        Some(_) => log::debug!("技能 {} 无更新", skill.name),
+                        None => log::warn!("未能获取技能 {} 的最新提交", skill.name),
+                    }
+                }
+
+                log::info!("检查更新完成（GraphQL 批量），发现 {} 个技能有更新", updates.len());
+                return Ok(updates);
+            }
+            Err(e) => {
+                log::warn!("GraphQL 批量检查更新失败，回退到逐个查询: {}", e);
+            }
+        }
+    }
+
+    for (skill, owner, repo) in candidates {
         // 检查更新
         match state.github
             .check_skill_update(
@@ -789,16 +2179,106 @@ pub async fn check_skills_updates(
     Ok(updates)
 }
 
+/// 获取后台刷新发现的可疑更新（与普通更新分开展示）
+#[tauri::command]
+pub async fn get_suspicious_updates(
+    state: State<'_, AppState>,
+) -> Result<Vec<SuspiciousUpdate>, AppError> {
+    state.db.get_suspicious_updates()
+        .map_err(AppError::from)
+}
+
+/// 忽略技能的可疑更新提示
+#[tauri::command]
+pub async fn dismiss_suspicious_update(
+    state: State<'_, AppState>,
+    skill_id: String,
+) -> Result<(), AppError> {
+    state.db.delete_suspicious_update(&skill_id)?;
+    state.notify_status_changed();
+    Ok(())
+}
+
+/// 记录对已安装技能当前版本的人工审阅（覆盖之前的审阅），安装的版本变化后审阅会被标记为过期
+#[tauri::command]
+pub async fn mark_skill_reviewed(
+    state: State<'_, AppState>,
+    skill_id: String,
+    reviewer: String,
+    note: Option<String>,
+) -> Result<SkillReviewStatus, AppError> {
+    let reviewer = reviewer.trim();
+    if reviewer.is_empty() || reviewer.chars().count() > MAX_REVIEWER_LENGTH {
+        return Err(AppError::invalid_input(format!("审阅人名称不能为空，且不超过 {} 个字符", MAX_REVIEWER_LENGTH)));
+    }
+    let skill = state.db.get_skills()?
+        .into_iter()
+        .find(|s| s.id == skill_id)
+        .ok_or_else(|| AppError::not_found(format!("未找到 skill: {}", skill_id)))?;
+    if !skill.installed {
+        return Err(AppError::invalid_input(format!("技能未安装，无法记录审阅: {}", skill.name)));
+    }
+
+    let review = SkillReview::new(&skill, reviewer, note);
+    state.db.save_skill_review(&review)?;
+    let event = SkillEvent {
+        commit_sha: review.commit_sha.clone(),
+        detail: Some(review.reviewer.clone()),
+        ..SkillEvent::new(&skill.id, SkillEventKind::Reviewed)
+    };
+    if let Err(e) = state.db.add_skill_event(&event) {
+        log::warn!("记录审阅事件失败: {}", e);
+    }
+    log::info!("技能 {} 已由 {} 审阅（commit: {:?}）", skill.name, review.reviewer, review.commit_sha);
+    Ok(SkillReviewStatus::new(review, &skill))
+}
+
+/// 删除技能的审阅记录
+#[tauri::command]
+pub async fn clear_skill_review(
+    state: State<'_, AppState>,
+    skill_id: String,
+) -> Result<(), AppError> {
+    state.db.delete_skill_review(&skill_id)?;
+    Ok(())
+}
+
+/// 获取所有审阅记录，outdated 表示已安装的版本不再是审阅过的版本
+#[tauri::command]
+pub async fn get_skill_reviews(
+    state: State<'_, AppState>,
+) -> Result<Vec<SkillReviewStatus>, AppError> {
+    let mut reviews = state.db.get_skill_reviews()?;
+    let mut statuses: Vec<SkillReviewStatus> = state.db.get_skills()?
+        .iter()
+        .filter_map(|skill| reviews.remove(&skill.id).map(|review| SkillReviewStatus::new(review, skill)))
+        .collect();
+    statuses.sort_by(|a, b| b.outdated.cmp(&a.outdated).then_with(|| a.skill_name.cmp(&b.skill_name)));
+    Ok(statuses)
+}
+
+/// 获取技能的时间线（安装、更新、卸载、可疑标记与评分变化，从旧到新）
+#[tauri::command]
+pub async fn get_skill_timeline(
+    state: State<'_, AppState>,
+    skill_id: String,
+) -> Result<Vec<TimelineEntry>, AppError> {
+    let events = state.db.get_skill_events(&skill_id)?;
+    let scores = state.db.get_score_history(&skill_id, &state.settings.get().scan_policy.level_thresholds())?;
+    Ok(crate::models::build_skill_timeline(&events, &scores))
+}
+
 /// 准备技能更新
 #[tauri::command]
 pub async fn prepare_skill_update(
     state: State<'_, AppState>,
     skill_id: String,
     locale: String,
-) -> Result<(crate::models::security::SecurityReport, Vec<String>), String> {
-    let manager = state.skill_manager.lock().await;
+) -> Result<(crate::models::security::SecurityReport, Vec<String>), AppError> {
+    let manager = &state.skill_manager;
+    let _operation = manager.begin_operation().await;
     manager.prepare_skill_update(&skill_id, &locale).await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
 }
 
 /// 确认技能更新
@@ -807,10 +2287,12 @@ pub async fn confirm_skill_update(
     state: State<'_, AppState>,
     skill_id: String,
     force_overwrite: bool,
-) -> Result<(), String> {
-    let manager = state.skill_manager.lock().await;
-    manager.confirm_skill_update(&skill_id, force_overwrite)
-        .map_err(|e| e.to_string())
+) -> Result<(), AppError> {
+    let manager = &state.skill_manager;
+    let _operation = manager.begin_operation().await;
+    manager.run_blocking(move |manager| manager.confirm_skill_update(&skill_id, force_overwrite)).await?;
+    state.notify_status_changed();
+    Ok(())
 }
 
 /// 取消技能更新
@@ -818,20 +2300,36 @@ pub async fn confirm_skill_update(
 pub async fn cancel_skill_update(
     state: State<'_, AppState>,
     skill_id: String,
-) -> Result<(), String> {
-    let manager = state.skill_manager.lock().await;
-    manager.cancel_skill_update(&skill_id)
-        .map_err(|e| e.to_string())
+) -> Result<(), AppError> {
+    let manager = &state.skill_manager;
+    // 中止进行中的确认更新（恢复备份），再清理 staging 目录
+    manager.request_cancel();
+    let _operation = manager.begin_operation().await;
+    manager.run_blocking(move |manager| manager.cancel_skill_update(&skill_id)).await
+        .map_err(AppError::from)
+}
+
+/// 取消正在进行的技能安装/更新（中止文件复制）
+#[tauri::command]
+pub async fn cancel_skill_operation(
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    state.skill_manager.request_cancel();
+    Ok(())
 }
 
 /// 检查并自动扫描未扫描的仓库（用于首次启动）
 #[tauri::command]
 pub async fn auto_scan_unscanned_repositories(
     state: State<'_, AppState>,
-) -> Result<Vec<String>, String> {
+) -> Result<Vec<String>, AppError> {
+    if !state.settings.get().scan_policy.auto_scan_repositories {
+        log::info!("已关闭自动扫描仓库");
+        return Ok(vec![]);
+    }
+
     // 获取所有未扫描的仓库
-    let unscanned_repos = state.db.get_unscanned_repositories()
-        .map_err(|e| e.to_string())?;
+    let unscanned_repos = state.db.get_unscanned_repositories()?;
 
     if unscanned_repos.is_empty() {
         log::info!("没有需要自动扫描的仓库");
@@ -862,23 +2360,154 @@ pub async fn auto_scan_unscanned_repositories(
     Ok(scanned_repos)
 }
 
-const PROXY_CONFIG_KEY: &str = "proxy_config";
+/// 获取应用设置
+#[tauri::command]
+pub async fn get_settings(
+    state: State<'_, AppState>,
+) -> Result<Settings, AppError> {
+    Ok(state.settings.get())
+}
 
-/// 获取代理配置
+/// 保存应用设置（校验失败时返回错误，成功后通知各服务并向前端发送 settings-changed 事件）
 #[tauri::command]
-pub async fn get_proxy_config(
+pub async fn set_settings(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<ProxyConfig, String> {
-    let config_json = state.db.get_setting(PROXY_CONFIG_KEY)
-        .map_err(|e| e.to_string())?;
+    mut settings: Settings,
+    passphrase: Option<String>,
+) -> Result<(), AppError> {
+    let previous_settings = state.settings.get();
+
+    // 快捷键变化时先检测冲突，避免保存一个无法生效的快捷键
+    if settings.hotkeys.enabled && settings.hotkeys != previous_settings.hotkeys {
+        check_shortcut_available(&app, &settings.hotkeys.toggle_window)?;
+    }
+
+    // 关闭高风险安装确认需要当前的管理口令
+    if previous_settings.scan_policy.confirm_risky_installs && !settings.scan_policy.confirm_risky_installs {
+        let stored = CredentialStore::get(INSTALL_PASSPHRASE_ACCOUNT)?;
+        ScanPolicy::verify_install_passphrase(stored.as_deref(), passphrase.as_deref())?;
+    }
+
+    // 批准的安装与导出目录只能通过目录选择器添加，这里只允许移除
+    let previous = &previous_settings.directories;
+    settings.directories.approved_install_dirs.retain(|dir| previous.approved_install_dirs.contains(dir));
+    settings.directories.approved_export_dirs.retain(|dir| previous.approved_export_dirs.contains(dir));
 
-    match config_json {
-        Some(json) => {
-            serde_json::from_str(&json)
-                .map_err(|e| format!("解析代理配置失败: {}", e))
+    // 默认安装目录变化时检查目录是否存在且可写入，且位于允许写入的目录中
+    if (settings.directories.skills_dir.is_some() || settings.directories.default_tool().is_some())
+        && settings.directories.resolved_skills_dir() != previous.resolved_skills_dir()
+    {
+        let skills_dir = PathPolicy::new(previous).check(&settings.directories.resolved_skills_dir().to_string_lossy())?;
+        dir_access::ensure_install_target(&skills_dir)?;
+        if settings.directories.skills_dir.is_some() {
+            settings.directories.skills_dir = Some(skills_dir);
         }
-        None => Ok(ProxyConfig::default())
     }
+
+    // 更新备份位置变化时检查是否通过目录选择器选择且可写入；清理备份会删除备份目录中的内容，
+    // 因此不能与技能安装目录重叠
+    if let Some(backup_dir) = settings.cache.backup_dir.clone() {
+        if previous_settings.cache.backup_dir.as_ref() != Some(&backup_dir) {
+            let backup_dir = PathPolicy::new(previous).check(&backup_dir.to_string_lossy())?;
+            let installed_dirs: Vec<PathBuf> = state.db.get_skills()?
+                .into_iter()
+                .filter(|skill| skill.installed)
+                .flat_map(|skill| skill.local_paths.unwrap_or_default().into_iter().chain(skill.local_path))
+                .map(PathBuf::from)
+                .collect();
+            let overlaps = [previous, &settings.directories]
+                .iter()
+                .any(|directories| PathPolicy::install_roots(directories, &installed_dirs).overlaps(&backup_dir));
+            if overlaps {
+                return Err(AppError::invalid_input(format!("备份位置不能位于技能安装目录中或包含技能安装目录: {:?}", backup_dir)));
+            }
+            dir_access::ensure_install_target(&backup_dir)?;
+            settings.cache.backup_dir = Some(backup_dir);
+        }
+    }
+
+    state.settings.set(settings)?;
+
+    log::info!("应用设置已保存");
+    Ok(())
+}
+
+/// 检查全局快捷键是否可用：格式有效且未被其他应用占用（本应用已注册的视为可用）
+fn check_shortcut_available(app: &tauri::AppHandle, shortcut: &str) -> Result<(), AppError> {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+    let parsed = Shortcut::from_str(shortcut.trim())
+        .map_err(|e| AppError::invalid_input(format!("无效的快捷键 {}: {}", shortcut, e)))?;
+
+    let manager = app.global_shortcut();
+    if manager.is_registered(parsed) {
+        return Ok(());
+    }
+
+    // 试注册一次：被其他应用占用时系统会拒绝
+    manager.register(parsed)
+        .map_err(|e| AppError::invalid_input(format!("快捷键 {} 已被其他应用占用: {}", shortcut, e)))?;
+    if let Err(e) = manager.unregister(parsed) {
+        log::warn!("注销试注册的快捷键失败: {}", e);
+    }
+
+    Ok(())
+}
+
+/// 检查全局快捷键是否可用（供设置页在保存前提示冲突）
+#[tauri::command]
+pub async fn check_global_shortcut(
+    app: tauri::AppHandle,
+    shortcut: String,
+) -> Result<(), AppError> {
+    check_shortcut_available(&app, &shortcut)
+}
+
+/// 后台保护是否已暂停
+#[tauri::command]
+pub async fn get_protection_status(
+    state: State<'_, AppState>,
+) -> Result<bool, AppError> {
+    Ok(state.settings.get().background.protection_paused)
+}
+
+/// 暂停后台保护（与托盘菜单中的“暂停保护”相同）
+#[tauri::command]
+pub async fn pause_protection(
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    state.settings.update(|settings| settings.background.protection_paused = true)?;
+    Ok(())
+}
+
+/// 恢复后台保护
+#[tauri::command]
+pub async fn resume_protection(
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    state.settings.update(|settings| settings.background.protection_paused = false)?;
+    Ok(())
+}
+
+/// 系统中是否已注册开机自启动（与设置中的 launch_at_login 对应）
+#[tauri::command]
+pub async fn is_autostart_enabled() -> Result<bool, AppError> {
+    Ok(crate::services::autostart::is_enabled())
+}
+
+/// 获取可用的界面语言（内置语言及应用数据目录中加载的语言）
+#[tauri::command]
+pub async fn get_available_locales() -> Result<Vec<String>, AppError> {
+    Ok(skillguard_core::i18n::available_locales())
+}
+
+/// 获取代理配置
+#[tauri::command]
+pub async fn get_proxy_config(
+    state: State<'_, AppState>,
+) -> Result<ProxyConfig, AppError> {
+    Ok(state.settings.get().proxy)
 }
 
 /// 保存代理配置
@@ -886,27 +2515,212 @@ pub async fn get_proxy_config(
 pub async fn save_proxy_config(
     state: State<'_, AppState>,
     config: ProxyConfig,
-) -> Result<(), String> {
-    let config_json = serde_json::to_string(&config)
-        .map_err(|e| format!("序列化代理配置失败: {}", e))?;
-
-    state.db.set_setting(PROXY_CONFIG_KEY, &config_json)
-        .map_err(|e| e.to_string())?;
-
-    log::info!("代理配置已保存: enabled={}, host={}, port={}",
+) -> Result<(), AppError> {
+    log::info!("保存代理配置: enabled={}, host={}, port={}",
         config.enabled, config.host, config.port);
 
+    state.settings.update(|s| s.proxy = config)?;
+
     Ok(())
 }
 
 /// 测试代理连接
 #[tauri::command]
 pub async fn test_proxy(
+    state: State<'_, AppState>,
     config: ProxyConfig,
-) -> Result<(), String> {
-    ProxyService::test_proxy(&config)
+) -> Result<(), AppError> {
+    let network = state.settings.get().network;
+    ProxyService::test_proxy(&config, &network)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
+}
+
+/// 获取 GitHub API 限流状态（配额耗尽时包含预计恢复时间与排队请求数）
+#[tauri::command]
+pub async fn get_github_rate_limit_status(
+    state: State<'_, AppState>,
+) -> Result<crate::services::RateLimitStatus, AppError> {
+    Ok(state.github.rate_limit_status())
+}
+
+/// 获取网络配置（超时与重试）
+#[tauri::command]
+pub async fn get_network_config(
+    state: State<'_, AppState>,
+) -> Result<NetworkConfig, AppError> {
+    Ok(state.settings.get().network)
+}
+
+/// 保存网络配置（重启应用后对所有 HTTP 客户端生效）
+#[tauri::command]
+pub async fn save_network_config(
+    state: State<'_, AppState>,
+    config: NetworkConfig,
+) -> Result<(), AppError> {
+    log::info!("保存网络配置: connect={}s, read={}s, retries={}",
+        config.connect_timeout_secs, config.read_timeout_secs, config.max_retries);
+
+    state.settings.update(|s| s.network = config)?;
+
+    Ok(())
+}
+
+/// 是否已配置 GitHub Token（不向前端返回 Token 本身）
+#[tauri::command]
+pub async fn has_github_token(
+    state: State<'_, AppState>,
+) -> Result<bool, AppError> {
+    Ok(state.github.has_token())
+}
+
+/// 保存 GitHub Token 到系统钥匙串（传入空值时清除），立即对 GitHub 请求生效
+#[tauri::command]
+pub async fn save_github_token(
+    state: State<'_, AppState>,
+    token: Option<String>,
+) -> Result<(), AppError> {
+    let token = token
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+
+    CredentialStore::store(GITHUB_TOKEN_ACCOUNT, token.as_deref())?;
+    state.github.set_token(token.clone());
+    // 手动填写的 Token 权限未知，需要时重新查询
+    state.db.delete_setting(crate::services::github_auth::TOKEN_SCOPES_KEY)?;
+
+    log::info!("GitHub Token 已{}", if token.is_some() { "保存" } else { "清除" });
+    Ok(())
+}
+
+/// 设置确认安装高风险技能时使用的管理口令（保存到系统钥匙串，传入空值时清除）。
+/// 已设置口令时需要传入当前口令
+#[tauri::command]
+pub async fn set_install_passphrase(passphrase: Option<String>, current: Option<String>) -> Result<(), AppError> {
+    let stored = CredentialStore::get(INSTALL_PASSPHRASE_ACCOUNT)?;
+    ScanPolicy::verify_install_passphrase(stored.as_deref(), current.as_deref())?;
+
+    let passphrase = passphrase
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+
+    CredentialStore::store(INSTALL_PASSPHRASE_ACCOUNT, passphrase.as_deref())?;
+
+    log::info!("安装管理口令已{}", if passphrase.is_some() { "设置" } else { "清除" });
+    Ok(())
+}
+
+/// 本地数据库是否已加密
+#[tauri::command]
+pub async fn get_database_encryption_status(
+    state: State<'_, AppState>,
+) -> Result<bool, AppError> {
+    Ok(state.db.is_encrypted())
+}
+
+/// 开启或关闭本地数据库加密（SQLCipher），密钥保存在系统钥匙串中
+/// 开启时将现有明文数据库导出为加密数据库，关闭时反向导出
+#[tauri::command]
+pub async fn set_database_encryption(
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), AppError> {
+    if enabled == state.db.is_encrypted() {
+        return Ok(());
+    }
+
+    // 密钥保存在本机钥匙串中，便携模式下换一台机器将无法打开加密的数据库
+    if enabled && portable::is_portable() {
+        return Err(AppError::invalid_input("便携模式下不支持数据库加密：密钥保存在本机钥匙串中，换机后将无法打开数据库"));
+    }
+
+    let key_account = database_key_account(active_profile());
+
+    if enabled {
+        // 先保存密钥再加密，避免加密完成后密钥丢失导致数据库无法打开
+        let key = Database::generate_key()?;
+        CredentialStore::set(&key_account, &key)
+            .map_err(|e| format!("无法保存数据库密钥: {}", e))?;
+
+        if let Err(e) = state.db.rekey(None, Some(&key)) {
+            let _ = CredentialStore::delete(&key_account);
+            return Err(AppError::new(ErrorKind::Database, format!("加密数据库失败: {}", e)));
+        }
+    } else {
+        let key = CredentialStore::get(&key_account)?
+            .ok_or_else(|| "系统钥匙串中没有找到数据库密钥".to_string())?;
+
+        state.db.rekey(Some(&key), None)
+            .map_err(|e| AppError::from(e).context("解密数据库失败"))?;
+
+        if let Err(e) = CredentialStore::delete(&key_account) {
+            log::warn!("删除数据库密钥失败: {}", e);
+        }
+    }
+
+    // 旧的自动备份与新的加密状态不一致，立即生成一份新的备份
+    if let Err(e) = state.backups.create(&state.db) {
+        log::warn!("自动备份数据库失败: {}", e);
+    }
+
+    log::info!("数据库加密已{}", if enabled { "开启" } else { "关闭" });
+    Ok(())
+}
+
+/// 备份数据库到用户选择的文件（需位于批准的导出目录中）
+#[tauri::command]
+pub async fn backup_database(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<(), AppError> {
+    let path = state.check_export_path(&path)?;
+    state.db.backup_to(&path)
+        .map_err(AppError::from)
+}
+
+/// 从用户选择的备份文件恢复数据库
+#[tauri::command]
+pub async fn restore_database(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<(), AppError> {
+    // 恢复前先备份当前数据库，便于撤销
+    if let Err(e) = state.backups.create(&state.db) {
+        log::warn!("恢复前自动备份失败: {}", e);
+    }
+
+    state.db.restore_from(&PathBuf::from(path))
+        .map_err(AppError::from)
+}
+
+/// 默认返回的日志条数
+const DEFAULT_LOG_LIMIT: usize = 500;
+
+/// 获取最近的应用日志（level 为最低严重程度，如 warn 表示只返回 warn 与 error）
+#[tauri::command]
+pub async fn get_recent_logs(
+    limit: Option<usize>,
+    level: Option<String>,
+) -> Result<Vec<LogEntry>, AppError> {
+    let min_level = match level {
+        Some(level) => LevelFilter::from_str(&level)
+            .map_err(|_| AppError::invalid_input(format!("不支持的日志级别: {}", level)))?,
+        None => LevelFilter::Trace,
+    };
+
+    AppLogger::recent(limit.unwrap_or(DEFAULT_LOG_LIMIT), min_level)
+        .map_err(AppError::from)
+}
+
+/// 调整日志级别（立即生效并保存到设置）
+#[tauri::command]
+pub async fn set_log_level(
+    state: State<'_, AppState>,
+    level: String,
+) -> Result<(), AppError> {
+    state.settings.update(|s| s.log_level = level.to_lowercase())?;
+
+    Ok(())
 }
 
 /// 翻译文本（使用 Google Translate 免费接口）
@@ -916,7 +2730,7 @@ pub async fn translate_text(
     text: String,
     target_lang: String,
     source_lang: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     use reqwest::header::USER_AGENT;
 
     if text.trim().is_empty() {
@@ -938,16 +2752,16 @@ pub async fn translate_text(
         .header(USER_AGENT, "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
         .send()
         .await
-        .map_err(|e| format!("翻译请求失败: {}", e))?;
+        .map_err(|e| AppError::new(ErrorKind::Network, format!("翻译请求失败: {}", e)))?;
 
     if !response.status().is_success() {
-        return Err(format!("翻译 API 返回错误: {}", response.status()));
+        return Err(AppError::new(ErrorKind::Network, format!("翻译 API 返回错误: {}", response.status())));
     }
 
     let body = response
         .text()
         .await
-        .map_err(|e| format!("读取翻译响应失败: {}", e))?;
+        .map_err(|e| AppError::new(ErrorKind::Network, format!("读取翻译响应失败: {}", e)))?;
 
     // 解析 Google Translate 响应
     // 格式: [[["translated text","original text",null,null,10]],null,"en",...]
@@ -973,7 +2787,7 @@ pub async fn translate_text(
     }
 
     if translated.is_empty() {
-        return Err("无法解析翻译结果".to_string());
+        return Err(AppError::internal("无法解析翻译结果"));
     }
 
     log::debug!("翻译成功: {} -> {}", &text[..text.len().min(50)], &translated[..translated.len().min(50)]);
@@ -986,13 +2800,69 @@ use crate::models::{AiTool, FileNode, get_all_supported_tools};
 
 /// 获取所有支持的 AI 工具列表（含安装状态检测）
 #[tauri::command]
-pub async fn get_supported_tools() -> Result<Vec<AiTool>, String> {
+pub async fn get_supported_tools() -> Result<Vec<AiTool>, AppError> {
     Ok(get_all_supported_tools())
 }
 
+/// 按工具筛选技能：返回声明兼容任一指定工具（或未声明兼容工具）的技能
+/// 未指定工具时使用本机检测到的工具
+#[tauri::command]
+pub async fn get_skills_by_tools(
+    state: State<'_, AppState>,
+    tool_ids: Option<Vec<String>>,
+) -> Result<Vec<Skill>, AppError> {
+    let tool_ids = tool_ids.unwrap_or_else(|| {
+        get_all_supported_tools()
+            .into_iter()
+            .filter(|tool| tool.is_installed)
+            .map(|tool| tool.id)
+            .collect()
+    });
+
+    let skills = state.skill_manager
+        .get_all_skills()?;
+
+    Ok(skills
+        .into_iter()
+        .filter(|skill| tool_ids.iter().any(|id| skill.is_compatible_with(id)))
+        .collect())
+}
+
+/// 检查技能是否兼容安装目标所属的工具，不兼容时返回提示信息
+#[tauri::command]
+pub async fn check_skill_compatibility(
+    state: State<'_, AppState>,
+    skill_id: String,
+    install_path: Option<String>,
+) -> Result<Option<String>, AppError> {
+    let manager = &state.skill_manager;
+    manager.check_skill_compatibility(&skill_id, install_path)
+        .map_err(AppError::from)
+}
+
+/// 获取各技能记住的安装目录（技能 ID -> 安装目录），未记录的技能安装到默认安装目录
+#[tauri::command]
+pub async fn get_skill_install_targets(
+    state: State<'_, AppState>,
+) -> Result<std::collections::HashMap<String, String>, AppError> {
+    Ok(state.db.get_skill_install_targets()?)
+}
+
+/// 设置技能的安装目录，之后安装与更新直接使用该目录；为空时恢复使用默认安装目录
+#[tauri::command]
+pub async fn set_skill_install_target(
+    state: State<'_, AppState>,
+    skill_id: String,
+    install_path: Option<String>,
+) -> Result<(), AppError> {
+    let install_path = state.check_install_path(install_path.as_deref())?;
+    state.skill_manager.set_skill_install_target(&skill_id, install_path.as_deref())
+        .map_err(AppError::from)
+}
+
 /// 获取指定工具的技能目录树结构
 #[tauri::command]
-pub async fn get_tool_skills_tree(tool_id: String) -> Result<Vec<FileNode>, String> {
+pub async fn get_tool_skills_tree(tool_id: String) -> Result<Vec<FileNode>, AppError> {
     let tools = get_all_supported_tools();
     let tool = tools
         .iter()
@@ -1005,14 +2875,14 @@ pub async fn get_tool_skills_tree(tool_id: String) -> Result<Vec<FileNode>, Stri
         return Ok(vec![]);
     }
 
-    fn build_tree(path: &std::path::Path) -> Result<Vec<FileNode>, String> {
+    fn build_tree(path: &std::path::Path) -> Result<Vec<FileNode>, AppError> {
         let mut nodes = Vec::new();
         
         let entries = std::fs::read_dir(path)
-            .map_err(|e| format!("无法读取目录: {}", e))?;
+            .map_err(|e| AppError::from(e).context("无法读取目录"))?;
         
         for entry in entries {
-            let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+            let entry = entry.map_err(|e| AppError::from(e).context("读取目录项失败"))?;
             let file_path = entry.path();
             let file_name = entry.file_name().to_string_lossy().to_string();
             
@@ -1053,24 +2923,24 @@ pub async fn get_tool_skills_tree(tool_id: String) -> Result<Vec<FileNode>, Stri
 
 /// 读取指定技能文件内容
 #[tauri::command]
-pub async fn read_skill_file(file_path: String) -> Result<String, String> {
+pub async fn read_skill_file(file_path: String) -> Result<String, AppError> {
     let path = std::path::Path::new(&file_path);
     
     if !path.exists() {
-        return Err("文件不存在".to_string());
+        return Err(AppError::not_found("文件不存在"));
     }
     
     if !path.is_file() {
-        return Err("路径不是文件".to_string());
+        return Err(AppError::invalid_input("路径不是文件"));
     }
     
     std::fs::read_to_string(path)
-        .map_err(|e| format!("读取文件失败: {}", e))
+        .map_err(|e| AppError::from(e).context("读取文件失败"))
 }
 
 /// 打开工具的技能文件夹
 #[tauri::command]
-pub async fn open_tool_folder(tool_id: String) -> Result<(), String> {
+pub async fn open_tool_folder(tool_id: String) -> Result<(), AppError> {
     let tools = get_all_supported_tools();
     let tool = tools
         .iter()
@@ -1085,7 +2955,7 @@ pub async fn open_tool_folder(tool_id: String) -> Result<(), String> {
     } else if tool.base_path.exists() {
         tool.base_path.clone()
     } else {
-        return Err(format!("工具目录不存在: {}", tool.base_path.display()));
+        return Err(AppError::not_found(format!("工具目录不存在: {}", tool.base_path.display())));
     };
 
     #[cfg(target_os = "windows")]
@@ -1093,7 +2963,7 @@ pub async fn open_tool_folder(tool_id: String) -> Result<(), String> {
         std::process::Command::new("explorer")
             .arg(&path_to_open)
             .spawn()
-            .map_err(|e| format!("打开文件夹失败: {}", e))?;
+            .map_err(|e| AppError::from(e).context("打开文件夹失败"))?;
     }
 
     #[cfg(target_os = "macos")]
@@ -1101,7 +2971,7 @@ pub async fn open_tool_folder(tool_id: String) -> Result<(), String> {
         std::process::Command::new("open")
             .arg(&path_to_open)
             .spawn()
-            .map_err(|e| format!("打开文件夹失败: {}", e))?;
+            .map_err(|e| AppError::from(e).context("打开文件夹失败"))?;
     }
 
     #[cfg(target_os = "linux")]
@@ -1109,7 +2979,7 @@ pub async fn open_tool_folder(tool_id: String) -> Result<(), String> {
         std::process::Command::new("xdg-open")
             .arg(&path_to_open)
             .spawn()
-            .map_err(|e| format!("打开文件夹失败: {}", e))?;
+            .map_err(|e| AppError::from(e).context("打开文件夹失败"))?;
     }
 
     Ok(())
@@ -1130,7 +3000,7 @@ pub struct ToolInstallPath {
 
 /// 获取所有已安装工具的技能安装路径
 #[tauri::command]
-pub async fn get_installed_tool_paths() -> Result<Vec<ToolInstallPath>, String> {
+pub async fn get_installed_tool_paths() -> Result<Vec<ToolInstallPath>, AppError> {
     let tools = get_all_supported_tools();
     let mut paths: Vec<ToolInstallPath> = Vec::new();
     let mut is_first = true;
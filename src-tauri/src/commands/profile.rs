@@ -0,0 +1,58 @@
+use crate::commands::AppState;
+use crate::models::AppError;
+use crate::services::credentials::database_key_account;
+use crate::services::profile::{self, Profile, ProfileInfo};
+use crate::services::CredentialStore;
+use tauri::State;
+
+/// 获取所有配置档
+#[tauri::command]
+pub async fn list_profiles(state: State<'_, AppState>) -> Result<Vec<ProfileInfo>, AppError> {
+    Ok(state.profiles.list())
+}
+
+/// 创建配置档（拥有独立的数据库、设置、缓存与技能目录映射）
+#[tauri::command]
+pub async fn create_profile(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<Profile, AppError> {
+    state.profiles.create(&name).map_err(AppError::from)
+}
+
+/// 删除配置档及其数据与缓存
+#[tauri::command]
+pub async fn delete_profile(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<(), AppError> {
+    state.profiles.delete(&name)?;
+
+    if let Err(e) = CredentialStore::delete(&database_key_account(&name)) {
+        log::warn!("删除配置档数据库密钥失败: {}", e);
+    }
+    Ok(())
+}
+
+/// 切换配置档：记录新的配置档并重启应用（数据库与各服务在启动时按配置档初始化）
+#[tauri::command]
+pub async fn switch_profile(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<(), AppError> {
+    state.profiles.set_active(&name)?;
+
+    if name == profile::active_profile() {
+        return Ok(());
+    }
+
+    // 切换前备份当前配置档的数据库
+    if let Err(e) = state.backups.create(&state.db) {
+        log::warn!("切换配置档前自动备份失败: {}", e);
+    }
+
+    log::info!("切换配置档: {} -> {}，正在重启应用", profile::active_profile(), name);
+    app.request_restart();
+    Ok(())
+}
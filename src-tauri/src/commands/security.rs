@@ -1,96 +1,468 @@
+use crate::commands::statistics::LAST_FULL_SCAN_KEY;
 use crate::commands::AppState;
-use crate::models::security::{SecurityReport, SkillScanResult, SecurityLevel};
-use crate::models::Skill;
-use crate::security::SecurityScanner;
+use crate::models::security::{IssueContext, SecurityReport, SkillScanResult};
+use crate::models::{normalize_checksum, AppError, PathChecksum, Skill, SkillChecksumVerification};
+use crate::security::{FileChanges, SecurityScanner};
+use skillguard_core::report::{self, ReportFormat, ReportMetadata, SkillReport};
+use crate::services::archive::{self, ArchiveFormat};
+use crate::services::{fs_ops, priority, Database};
+use crate::services::settings::ThreatResponse;
+use crate::services::skill_manager::scan_directory_with_changes;
 use crate::i18n::validate_locale;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use rust_i18n::t;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use tauri::State;
+use std::sync::Arc;
+use tauri::{Emitter, State};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// 最近一次启动检查的变化汇总（JSON）
+const LAST_STARTUP_SCAN_KEY: &str = "last_startup_scan";
+
+/// 同时扫描的 skill 数量上限
+const MAX_CONCURRENT_SCANS: usize = 4;
+
+/// 批量扫描进度（scan-progress 事件）
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanProgress {
+    pub completed: usize,
+    pub total: usize,
+    pub skill_id: String,
+    pub skill_name: String,
+    /// 扫描失败时的错误信息
+    pub error: Option<String>,
+}
 
 /// 扫描所有已安装的 skills
+/// 每个 skill 扫描整个安装目录，多个 skill 并发扫描；每完成一个发送 scan-progress 事件
 #[tauri::command]
 pub async fn scan_all_installed_skills(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
     locale: String,
-) -> Result<Vec<SkillScanResult>, String> {
-    let locale = validate_locale(&locale);
-    let skills = state.db.get_skills().map_err(|e| e.to_string())?;
+) -> Result<Vec<SkillScanResult>, AppError> {
+    let locale = validate_locale(&locale).to_string();
+    let skills = state.db.get_skills()?;
     let installed_skills: Vec<Skill> = skills.into_iter()
         .filter(|s| s.installed && s.local_path.is_some())
         .collect();
 
-    let scanner = SecurityScanner::new();
-    let mut results = Vec::new();
+    let total = installed_skills.len();
+    let scanner = Arc::new(state.settings.get().scan_policy.scanner());
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_SCANS));
+    let mut tasks = JoinSet::new();
+
+    for (index, skill) in installed_skills.into_iter().enumerate() {
+        let db = Arc::clone(&state.db);
+        let scanner = Arc::clone(&scanner);
+        let semaphore = Arc::clone(&semaphore);
+        let locale = locale.clone();
+
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let skill_id = skill.id.clone();
+            let skill_name = skill.name.clone();
+
+            let result = tokio::task::spawn_blocking(move || scan_installed_skill(&db, &scanner, skill, &locale))
+                .await
+                .unwrap_or_else(|e| Err(anyhow::anyhow!("扫描任务异常退出: {}", e)));
+
+            (index, skill_id, skill_name, result)
+        });
+    }
 
-    for mut skill in installed_skills {
-        if let Some(local_path) = &skill.local_path {
-            // local_path 是目录路径，扫描整个目录
-            let path = PathBuf::from(local_path);
+    let mut results = Vec::with_capacity(total);
+    let mut completed = 0;
 
-            // 检查目录是否存在
-            if !path.exists() || !path.is_dir() {
-                eprintln!("Skill directory does not exist: {:?}", path);
+    while let Some(joined) = tasks.join_next().await {
+        let Ok((index, skill_id, skill_name, result)) = joined else {
+            continue;
+        };
+        completed += 1;
+
+        let error = match result {
+            Ok(scan_result) => {
+                results.push((index, scan_result));
+                None
+            }
+            Err(e) => {
+                log::warn!("扫描 skill 失败: {}, 错误: {}", skill_name, e);
+                Some(e.to_string())
+            }
+        };
+
+        let progress = ScanProgress { completed, total, skill_id, skill_name, error };
+        if let Err(e) = app.emit("scan-progress", &progress) {
+            log::warn!("发送 scan-progress 事件失败: {}", e);
+        }
+    }
+
+    if let Err(e) = state.db.set_setting(LAST_FULL_SCAN_KEY, &chrono::Utc::now().to_rfc3339()) {
+        log::warn!("保存全量扫描时间失败: {}", e);
+    }
+    state.notify_status_changed();
+
+    // 并发完成顺序不固定，按原始顺序返回
+    results.sort_by_key(|(index, _)| *index);
+    Ok(results.into_iter().map(|(_, result)| result).collect())
+}
+
+/// 定时扫描发现的已安装技能威胁（installed-skill-threats 事件）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledSkillThreat {
+    pub skill_id: String,
+    pub skill_name: String,
+    pub hard_trigger_issues: Vec<String>,
+    /// 执行的自动处理
+    pub response: ThreatResponse,
+    /// 隔离后的目录（仅隔离时）
+    pub quarantine_path: Option<String>,
+    /// 自动处理失败时的错误信息
+    pub error: Option<String>,
+}
+
+/// 距上次全量扫描超过设置的间隔时，在后台重新扫描所有已安装的技能；
+/// 新命中硬触发规则的技能按设置自动通知、禁用或隔离，无需用户操作
+pub async fn rescan_installed_skills_if_due(state: State<'_, AppState>) -> Vec<InstalledSkillThreat> {
+    let settings = state.settings.get();
+    let interval_hours = settings.background.rescan_interval_hours;
+    if interval_hours == 0 {
+        return Vec::new();
+    }
+
+    let last_full_scan = state.db.get_setting(LAST_FULL_SCAN_KEY).ok().flatten()
+        .and_then(|value| chrono::DateTime::parse_from_rfc3339(&value).ok());
+    if last_full_scan.is_some_and(|at| chrono::Utc::now() - at.with_timezone(&chrono::Utc) < chrono::Duration::hours(interval_hours as i64)) {
+        return Vec::new();
+    }
+
+    // 扫描与自动处理都在写操作锁内进行，避免扫描期间技能被更新或卸载，处理的是扫描过的那份文件
+    let manager = &state.skill_manager;
+    let _operation = manager.begin_operation().await;
+
+    let skills = match state.db.get_skills() {
+        Ok(skills) => skills,
+        Err(e) => {
+            log::warn!("定时扫描已安装技能失败，无法读取技能列表: {}", e);
+            return Vec::new();
+        }
+    };
+    let previous_reports = state.db.get_scan_reports().unwrap_or_default();
+    let scanner = Arc::new(settings.scan_policy.scanner());
+    let response = settings.background.threat_response;
+    let locale = validate_locale(&settings.locale).to_string();
+
+    // 按设置限制同时扫描的线程数，并以较低优先级运行
+    let low_priority = settings.background.low_priority_scans;
+    let semaphore = Arc::new(Semaphore::new(settings.background.max_scan_threads.max(1) as usize));
+    let mut tasks = JoinSet::new();
+    for (index, skill) in skills.into_iter().filter(|s| s.installed && s.local_path.is_some()).enumerate() {
+        let db = Arc::clone(&state.db);
+        let scanner = Arc::clone(&scanner);
+        let semaphore = Arc::clone(&semaphore);
+        let locale = locale.clone();
+
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let (skill_id, skill_name) = (skill.id.clone(), skill.name.clone());
+            let result = priority::run_background(low_priority, move || scan_installed_skill(&db, &scanner, skill, &locale)).await;
+            (index, skill_id, skill_name, result)
+        });
+    }
+
+    let mut scanned = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok(result) = joined {
+            scanned.push(result);
+        }
+    }
+    scanned.sort_by_key(|(index, ..)| *index);
+
+    let mut threats = Vec::new();
+    for (_, skill_id, skill_name, result) in scanned {
+        let previously_blocked = previous_reports.get(&skill_id).is_some_and(|(report, _)| report.blocked);
+        let report = match result {
+            Ok(result) => result.report,
+            Err(e) => {
+                log::warn!("定时扫描技能失败: {}, 错误: {}", skill_name, e);
                 continue;
             }
+        };
+        if !report.blocked {
+            continue;
+        }
 
-            match scanner.scan_directory(
-                path.to_str().unwrap_or(""),
-                &skill.id,
-                &locale
-            ) {
-                Ok(report) => {
-                    // 更新 skill 的安全信息
-                    skill.security_score = Some(report.score);
-                    skill.security_level = Some(report.level.as_str().to_string());
-                    skill.security_issues = Some(
-                        report.issues.iter()
-                            .map(|i| {
-                                let file_info = i.file_path.as_ref()
-                                    .map(|f| format!("[{}] ", f))
-                                    .unwrap_or_default();
-                                format!("{}{:?}: {}", file_info, i.severity, i.description)
-                            })
-                            .collect()
-                    );
-                    skill.scanned_at = Some(chrono::Utc::now());
+        log::warn!("定时扫描发现技能 {} 命中硬触发规则: {:?}", skill_name, report.hard_trigger_issues);
+        let mut threat = InstalledSkillThreat {
+            skill_id: skill_id.clone(),
+            skill_name,
+            hard_trigger_issues: report.hard_trigger_issues.clone(),
+            response,
+            quarantine_path: None,
+            error: None,
+        };
 
-                    // 保存到数据库
-                    if let Err(e) = state.db.save_skill(&skill) {
-                        eprintln!("Failed to save skill {}: {}", skill.name, e);
-                    }
+        let detail = report.hard_trigger_issues.join("; ");
+        let outcome = match response {
+            ThreatResponse::Notify => Ok(!previously_blocked),
+            ThreatResponse::Disable => {
+                manager.run_blocking(move |manager| manager.disable_skill(&skill_id, &detail)).await
+                    .map(|disabled| disabled > 0 || !previously_blocked)
+            }
+            ThreatResponse::Quarantine => {
+                manager.run_blocking(move |manager| manager.quarantine_skill(&skill_id, &detail)).await
+                    .map(|dir| {
+                        threat.quarantine_path = Some(dir.to_string_lossy().to_string());
+                        true
+                    })
+            }
+        };
+        match outcome {
+            Ok(true) => threats.push(threat),
+            Ok(false) => {}
+            Err(e) => {
+                log::warn!("自动处理技能 {} 失败: {}", threat.skill_name, e);
+                threat.error = Some(e.to_string());
+                threats.push(threat);
+            }
+        }
+    }
 
-                    results.push(SkillScanResult {
-                        skill_id: skill.id.clone(),
-                        skill_name: skill.name.clone(),
-                        score: report.score,
-                        level: report.level.as_str().to_string(),
-                        scanned_at: chrono::Utc::now().to_rfc3339(),
-                        report,
-                    });
-                }
-                Err(e) => {
-                    eprintln!("Failed to scan skill {}: {}", skill.name, e);
+    if let Err(e) = state.db.set_setting(LAST_FULL_SCAN_KEY, &chrono::Utc::now().to_rfc3339()) {
+        log::warn!("保存全量扫描时间失败: {}", e);
+    }
+    state.notify_status_changed();
+
+    threats
+}
+
+/// 启动检查中文件或安全状况有变化的已安装技能
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillChangeSummary {
+    pub skill_id: String,
+    pub skill_name: String,
+    pub path: String,
+    /// 安装目录已不存在
+    pub missing: bool,
+    /// 与上次扫描相比的文件变化
+    pub files: FileChanges,
+    pub previous_score: Option<i32>,
+    pub score: Option<i32>,
+    /// 本次新命中硬触发规则
+    pub newly_blocked: bool,
+    /// 安全状况变差（评分下降、新命中硬触发规则或安装目录丢失）
+    pub regression: bool,
+}
+
+/// 启动检查的变化汇总（startup-scan-summary 事件）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupScanSummary {
+    pub checked_at: chrono::DateTime<chrono::Utc>,
+    /// 上一次启动检查的时间
+    pub previous_checked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub scanned: usize,
+    pub failed: usize,
+    /// 有变化的技能（没有变化的技能不列出）
+    pub changes: Vec<SkillChangeSummary>,
+}
+
+impl StartupScanSummary {
+    pub fn regressions(&self) -> Vec<SkillChangeSummary> {
+        self.changes.iter().filter(|change| change.regression).cloned().collect()
+    }
+}
+
+/// 启动时快速检查所有已安装技能：基于上次扫描的缓存增量扫描，汇总与上次运行相比的文件与安全状况变化，
+/// 保存汇总供前端读取
+pub async fn run_startup_scan(state: State<'_, AppState>) -> Result<StartupScanSummary> {
+    let settings = state.settings.get();
+    let previous_checked_at = load_startup_scan_summary(&state.db).map(|summary| summary.checked_at);
+    let previous_reports = state.db.get_scan_reports()?;
+    let scanner = Arc::new(settings.scan_policy.scanner());
+    let locale = validate_locale(&settings.locale).to_string();
+
+    let low_priority = settings.background.low_priority_scans;
+    let semaphore = Arc::new(Semaphore::new(settings.background.max_scan_threads.max(1) as usize));
+    let mut tasks = JoinSet::new();
+    let skills: Vec<Skill> = state.db.get_skills()?.into_iter().filter(|s| s.installed && s.local_path.is_some()).collect();
+    for (index, skill) in skills.into_iter().enumerate() {
+        let db = Arc::clone(&state.db);
+        let scanner = Arc::clone(&scanner);
+        let semaphore = Arc::clone(&semaphore);
+        let locale = locale.clone();
+
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let scanned_skill = skill.clone();
+            let result = priority::run_background(low_priority, move || scan_installed_skill_with_changes(&db, &scanner, scanned_skill, &locale)).await;
+            (index, skill, result)
+        });
+    }
+
+    let mut scanned = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok(result) = joined {
+            scanned.push(result);
+        }
+    }
+    scanned.sort_by_key(|(index, ..)| *index);
+
+    let mut summary = StartupScanSummary {
+        checked_at: chrono::Utc::now(),
+        previous_checked_at,
+        scanned: 0,
+        failed: 0,
+        changes: Vec::new(),
+    };
+    for (_, skill, result) in scanned {
+        let path = skill.local_path.clone().unwrap_or_default();
+        let previous = previous_reports.get(&skill.id).map(|(report, _)| report);
+        let previous_score = previous.map(|report| report.score).or(skill.security_score);
+        let mut change = SkillChangeSummary {
+            skill_id: skill.id.clone(),
+            skill_name: skill.name.clone(),
+            path: path.clone(),
+            missing: false,
+            files: FileChanges::default(),
+            previous_score,
+            score: None,
+            newly_blocked: false,
+            regression: false,
+        };
+
+        match result {
+            Ok((result, files)) => {
+                summary.scanned += 1;
+                change.score = Some(result.report.score);
+                change.newly_blocked = result.report.blocked && !previous.is_some_and(|report| report.blocked);
+                change.regression = change.newly_blocked || previous_score.is_some_and(|score| result.report.score < score);
+                change.files = files.unwrap_or_default();
+                if change.files.is_empty() && change.score == previous_score && !change.newly_blocked {
+                    continue;
                 }
             }
+            Err(e) if !PathBuf::from(&path).is_dir() => {
+                log::warn!("启动检查发现技能 {} 的安装目录不存在: {}", skill.name, e);
+                change.missing = true;
+                change.regression = true;
+            }
+            Err(e) => {
+                log::warn!("启动检查扫描技能失败: {}, 错误: {}", skill.name, e);
+                summary.failed += 1;
+                continue;
+            }
         }
+        summary.changes.push(change);
     }
 
-    Ok(results)
+    log::info!(
+        "启动检查完成: 扫描 {} 个技能, {} 个有变化, {} 个安全状况变差",
+        summary.scanned, summary.changes.len(), summary.regressions().len()
+    );
+    if let Err(e) = serde_json::to_string(&summary).map_err(anyhow::Error::from).and_then(|json| state.db.set_setting(LAST_STARTUP_SCAN_KEY, &json)) {
+        log::warn!("保存启动检查结果失败: {}", e);
+    }
+    if let Err(e) = state.db.set_setting(LAST_FULL_SCAN_KEY, &summary.checked_at.to_rfc3339()) {
+        log::warn!("保存全量扫描时间失败: {}", e);
+    }
+    state.notify_status_changed();
+    Ok(summary)
+}
+
+fn load_startup_scan_summary(db: &Database) -> Option<StartupScanSummary> {
+    let json = db.get_setting(LAST_STARTUP_SCAN_KEY).ok().flatten()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// 获取最近一次启动检查的变化汇总
+#[tauri::command]
+pub async fn get_startup_scan_summary(state: State<'_, AppState>) -> Result<Option<StartupScanSummary>, AppError> {
+    Ok(load_startup_scan_summary(&state.db))
+}
+
+/// 扫描单个已安装 skill 的目录，并保存摘要与完整报告
+pub(crate) fn scan_installed_skill(db: &Database, scanner: &SecurityScanner, skill: Skill, locale: &str) -> Result<SkillScanResult> {
+    scan_installed_skill_with_changes(db, scanner, skill, locale).map(|(result, _)| result)
+}
+
+/// 同 [`scan_installed_skill`]，同时返回与上次扫描相比的文件变化
+fn scan_installed_skill_with_changes(
+    db: &Database,
+    scanner: &SecurityScanner,
+    mut skill: Skill,
+    locale: &str,
+) -> Result<(SkillScanResult, Option<FileChanges>)> {
+    // local_path 是目录路径，扫描整个目录
+    let path = PathBuf::from(skill.local_path.as_deref().unwrap_or_default());
+    if !path.is_dir() {
+        anyhow::bail!(t!("common.errors.directory_not_exist", locale = locale, path = path.display()));
+    }
+
+    let (report, changes) = scan_directory_with_changes(db, scanner, &path, &skill.id, locale)?;
+    let scanned_at = chrono::Utc::now();
+
+    // 更新 skill 的安全信息
+    skill.security_score = Some(report.score);
+    skill.security_level = Some(report.level.as_str().to_string());
+    skill.security_issues = Some(
+        report.issues.iter()
+            .map(|i| {
+                let file_info = i.file_path.as_ref()
+                    .map(|f| format!("[{}] ", f))
+                    .unwrap_or_default();
+                format!("{}{:?}: {}", file_info, i.severity, i.description)
+            })
+            .collect()
+    );
+    skill.scanned_at = Some(scanned_at);
+
+    // 保存到数据库
+    db.save_skill(&skill)?;
+    db.save_scan_report(&skill.id, &report, scanned_at)?;
+
+    let mut result = SkillScanResult::new(skill.id.clone(), skill.name.clone(), scanned_at.to_rfc3339(), report);
+    result.hard_trigger_history = db
+        .get_hard_trigger_history()?
+        .remove(&skill.id)
+        .unwrap_or_default();
+
+    Ok((result, changes))
 }
 
 /// 获取缓存的扫描结果
 #[tauri::command]
 pub async fn get_scan_results(
     state: State<'_, AppState>,
-) -> Result<Vec<SkillScanResult>, String> {
+) -> Result<Vec<SkillScanResult>, AppError> {
     use crate::models::security::{SecurityIssue, IssueSeverity, IssueCategory};
 
-    let skills = state.db.get_skills().map_err(|e| e.to_string())?;
+    let skills = state.db.get_skills()?;
+    let mut saved_reports = state.db.get_scan_reports()?;
+    let mut history = state.db.get_hard_trigger_history()?;
+    // 保存的报告按扫描时的阈值划分等级，阈值调整后按当前阈值重新计算
+    let thresholds = state.settings.get().scan_policy.level_thresholds();
 
     let results: Vec<SkillScanResult> = skills.into_iter()
         .filter(|s| s.installed && s.security_score.is_some())
         .map(|s| {
+            // 优先使用保存的完整报告（安装/更新时的扫描只更新摘要，报告早于摘要时视为过期）
+            let saved = saved_reports.remove(&s.id).filter(|(_, scanned_at)| {
+                s.scanned_at.is_none_or(|t| scanned_at.timestamp() >= t.timestamp())
+            });
+            let hard_trigger_history = history.remove(&s.id).unwrap_or_default();
+            if let Some((mut report, scanned_at)) = saved {
+                report.level = thresholds.level(report.score);
+                let mut result = SkillScanResult::new(s.id.clone(), s.name.clone(), scanned_at.to_rfc3339(), report);
+                result.hard_trigger_history = hard_trigger_history;
+                return result;
+            }
+
+            // 旧版本只保存了问题摘要：从字符串还原
             // 解析 security_issues 字符串为 SecurityIssue 对象
             let issues = if let Some(issue_strings) = &s.security_issues {
                 issue_strings.iter().filter_map(|issue_str| {
@@ -127,6 +499,8 @@ pub async fn get_scan_results(
                                 line_number: None,
                                 code_snippet: None,
                                 file_path,
+                                rule_id: None,
+                                original_severity: None,
                             })
                         } else {
                             // 兼容旧格式（没有 Severity 前缀）：保留原始文本，避免丢失规则名等信息
@@ -137,6 +511,8 @@ pub async fn get_scan_results(
                                 line_number: None,
                                 code_snippet: None,
                                 file_path,
+                                rule_id: None,
+                                original_severity: None,
                             })
                         }
                     } else {
@@ -148,6 +524,8 @@ pub async fn get_scan_results(
                             line_number: None,
                             code_snippet: None,
                             file_path,
+                            rule_id: None,
+                            original_severity: None,
                         })
                     }
                 }).collect()
@@ -158,12 +536,14 @@ pub async fn get_scan_results(
             let report = SecurityReport {
                 skill_id: s.id.clone(),
                 score: s.security_score.unwrap_or(0),
-                level: SecurityLevel::from_score(s.security_score.unwrap_or(0)),
+                level: thresholds.level(s.security_score.unwrap_or(0)),
                 issues,
                 recommendations: vec![], // 建议信息暂时为空，未来可以存储到数据库
                 blocked: false,
                 hard_trigger_issues: vec![],
                 scanned_files: vec![], // 缓存结果中没有扫描文件列表
+                modified_rules: vec![],
+                capabilities: Default::default(),
             };
 
             SkillScanResult {
@@ -172,6 +552,8 @@ pub async fn get_scan_results(
                 score: s.security_score.unwrap_or(0),
                 level: s.security_level.clone().unwrap_or_else(|| "Unknown".to_string()),
                 scanned_at: s.scanned_at.map(|d| d.to_rfc3339()).unwrap_or_else(|| chrono::Utc::now().to_rfc3339()),
+                files: report.file_breakdown(),
+                hard_trigger_history,
                 report,
             }
         })
@@ -180,46 +562,226 @@ pub async fn get_scan_results(
     Ok(results)
 }
 
-/// 扫描单个 skill 文件（用于安装前检查）
+/// 扫描单个 skill 文件或 skill 压缩包（用于安装前检查）
 ///
 /// # 参数
 ///
-/// * `archive_path` - skill 文件的路径（可以是压缩包内的 SKILL.md，或已解压的文件路径）
+/// * `archive_path` - skill 压缩包（zip、tar、tar.gz/tgz、7z，按文件头识别）或单个 skill 文件的路径
 ///
 /// # 返回
 ///
-/// 返回包含安全评分、等级和问题列表的 SecurityReport
+/// 返回包含安全评分、等级和问题列表的 SecurityReport；压缩包与目录扫描的报告结构相同
 #[tauri::command]
 pub async fn scan_skill_archive(
+    state: State<'_, AppState>,
     archive_path: String,
     locale: String,
-) -> Result<SecurityReport, String> {
-    let locale = validate_locale(&locale);
-    let scanner = SecurityScanner::new();
+) -> Result<SecurityReport, AppError> {
+    let locale = validate_locale(&locale).to_string();
+    let scan_policy = state.settings.get().scan_policy;
+    let scanner = scan_policy.scanner();
 
     // 验证文件存在性
-    let path = std::path::Path::new(&archive_path);
+    let path = PathBuf::from(&archive_path);
     if !path.exists() {
-        return Err(t!("common.errors.file_not_found", locale = locale, path = &archive_path).to_string());
+        return Err(AppError::not_found(t!("common.errors.file_not_found", locale = locale, path = &archive_path)));
     }
     if !path.is_file() {
-        return Err(t!("common.errors.path_not_file", locale = locale, path = &archive_path).to_string());
+        return Err(AppError::invalid_input(t!("common.errors.path_not_file", locale = locale, path = &archive_path)));
+    }
+
+    let format = ArchiveFormat::detect(&path).map_err(|e| AppError {
+        message: t!("common.errors.read_failed", locale = locale, path = &archive_path, error = e.to_string()).to_string(),
+        ..AppError::from(e)
+    })?;
+    if let Some(format) = format {
+        return fs_ops::run_blocking(move || scan_archive(&scanner, &path, format, &locale))
+            .await
+            .map_err(AppError::from);
+    }
+
+    // 超过扫描大小上限的文件不读入内存
+    let size = path.metadata().map(|m| m.len()).unwrap_or_default();
+    let limit = scan_policy.max_scan_file_size_mb.saturating_mul(1024 * 1024);
+    if size > limit {
+        return Err(AppError::invalid_input(t!("common.errors.file_too_large", locale = locale, path = &archive_path, size = size, limit = limit)));
     }
 
     // 读取文件内容
-    let content = std::fs::read_to_string(path)
-        .map_err(|e| t!("common.errors.read_failed",
-            locale = locale,
-            path = &archive_path,
-            error = e.to_string()
-        ).to_string())?;
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| AppError {
+            message: t!("common.errors.read_failed", locale = locale, path = &archive_path, error = e.to_string()).to_string(),
+            ..AppError::from(e)
+        })?;
 
     let report = scanner.scan_file(&content, &archive_path, &locale)
-        .map_err(|e| t!("common.errors.scan_failed",
+        .map_err(|e| AppError::internal(t!("common.errors.scan_failed",
             locale = locale,
             path = &archive_path,
             error = e.to_string()
-        ).to_string())?;
+        )))?;
 
     Ok(report)
 }
+
+/// 将压缩包解压到临时目录后按目录扫描；临时目录在返回时（包括出错时）自动删除
+pub(crate) fn scan_archive(scanner: &SecurityScanner, path: &std::path::Path, format: ArchiveFormat, locale: &str) -> Result<SecurityReport> {
+    let sandbox = tempfile::Builder::new()
+        .prefix("skill-archive-")
+        .tempdir()
+        .context("无法创建临时目录")?;
+
+    let summary = archive::extract_archive(path, format, sandbox.path()).map_err(|e| {
+        anyhow::anyhow!(t!("common.errors.archive_extract_failed", locale = locale, path = path.display(), error = format!("{:#}", e)))
+    })?;
+    log::info!(
+        "已解压压缩包 {:?}（{:?}）：{} 个条目，{} 字节，跳过 {} 个",
+        path, format, summary.entries, summary.total_bytes, summary.skipped.len()
+    );
+
+    let skill_id = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let report = scanner.scan_directory(&sandbox.path().to_string_lossy(), &skill_id, locale)
+        .map_err(|e| anyhow::anyhow!(t!("common.errors.scan_failed", locale = locale, path = path.display(), error = e.to_string())))?;
+    Ok(report)
+}
+
+/// 导出单个 skill 的安全报告（Markdown 或 HTML；PDF 由前端打印 HTML 报告生成）
+/// 包含基本信息、评分历史、所有问题（含代码片段与修复建议）以及带 SHA-256 的文件清单
+/// 指定 path 时写入文件，始终返回报告内容
+#[tauri::command]
+pub async fn export_skill_report(
+    state: State<'_, AppState>,
+    skill_id: String,
+    format: String,
+    path: Option<String>,
+    locale: String,
+) -> Result<String, AppError> {
+    let locale = validate_locale(&locale).to_string();
+    let format = ReportFormat::parse(&format).ok_or_else(|| format!("不支持的报告格式: {}", format))?;
+
+    let skill = state.db.get_skills()?
+        .into_iter()
+        .find(|s| s.id == skill_id)
+        .ok_or_else(|| format!("未找到 skill: {}", skill_id))?;
+    let (mut scan_report, scanned_at) = state.db.get_scan_reports()?
+        .remove(&skill_id)
+        .ok_or_else(|| format!("skill 尚未扫描: {}", skill.name))?;
+    let thresholds = state.settings.get().scan_policy.level_thresholds();
+    scan_report.level = thresholds.level(scan_report.score);
+    let score_history = state.db.get_score_history(&skill_id, &thresholds)?;
+
+    let content = tokio::task::spawn_blocking(move || -> Result<String> {
+        let files = match skill.local_path.as_deref().map(std::path::Path::new) {
+            Some(dir) if dir.is_dir() => report::file_inventory(dir)?,
+            _ => Vec::new(),
+        };
+
+        let skill_report = SkillReport {
+            metadata: ReportMetadata {
+                skill_id: skill.id,
+                name: skill.name,
+                description: skill.description,
+                repository: skill.repository_url,
+                version: skill.version,
+                author: skill.author,
+                installed_at: skill.installed_at.map(|t| t.to_rfc3339()),
+                commit_sha: skill.installed_commit_sha,
+                local_path: skill.local_path,
+            },
+            report: scan_report,
+            scanned_at: Some(scanned_at.to_rfc3339()),
+            score_history,
+            files,
+            generated_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        Ok(report::render(&skill_report, format, &locale))
+    })
+    .await
+    .map_err(|e| AppError::internal(e.to_string()))??;
+
+    if let Some(path) = path {
+        let path = state.check_export_path(&path)?;
+        std::fs::write(&path, &content).map_err(|e| AppError::from(e).context(format!("无法写入报告文件 {:?}", path)))?;
+    }
+
+    Ok(content)
+}
+
+/// 计算文件或目录的 SHA256 校验和（目录按相对路径与内容计算，见 SecurityScanner::calculate_path_checksum），
+/// 指定 expected 时同时与之比较（如技能发布页面上的哈希）
+#[tauri::command]
+pub async fn compute_path_checksum(path: String, expected: Option<String>) -> Result<PathChecksum, AppError> {
+    let expected = expected.as_deref().map(str::trim).filter(|checksum| !checksum.is_empty())
+        .map(normalize_checksum)
+        .transpose()?;
+    let path_buf = PathBuf::from(&path);
+    if !path_buf.exists() {
+        return Err(AppError::not_found(format!("路径不存在: {}", path)));
+    }
+
+    let is_dir = path_buf.is_dir();
+    let checksum = fs_ops::run_blocking(move || SecurityScanner::new().calculate_path_checksum(&path_buf)).await?;
+    Ok(PathChecksum::new(path, is_dir, checksum, expected.as_deref()))
+}
+
+/// 校验已安装技能的内容：计算各安装目录的校验和，与 expected（未提供时为导入技能清单时固定的哈希）比较
+#[tauri::command]
+pub async fn verify_skill_checksum(
+    state: State<'_, AppState>,
+    skill_id: String,
+    expected: Option<String>,
+) -> Result<SkillChecksumVerification, AppError> {
+    let verification = state
+        .skill_manager
+        .run_blocking(move |manager| manager.verify_skill_checksum(&skill_id, expected.as_deref()))
+        .await?;
+    Ok(verification)
+}
+
+/// 每个问题默认展示的上下文行数（问题行前后各若干行）
+const DEFAULT_ISSUE_CONTEXT_LINES: usize = 3;
+
+/// 获取 skill 扫描报告中每个问题的上下文代码（从已安装副本或仓库缓存读取），命中范围已标出
+///
+/// 没有文件或行号的问题、文件已不存在或内容变化导致行号越界的问题会被跳过
+#[tauri::command]
+pub async fn get_issue_context(
+    state: State<'_, AppState>,
+    skill_id: String,
+    context_lines: Option<usize>,
+) -> Result<Vec<IssueContext>, AppError> {
+    let context_lines = context_lines.unwrap_or(DEFAULT_ISSUE_CONTEXT_LINES).min(50);
+
+    let skill = state.db.get_skills()?
+        .into_iter()
+        .find(|s| s.id == skill_id)
+        .ok_or_else(|| AppError::not_found(format!("未找到 skill: {}", skill_id)))?;
+    let (scan_report, _) = state.db.get_scan_reports()?
+        .remove(&skill_id)
+        .ok_or_else(|| AppError::not_found(format!("skill 尚未扫描: {}", skill.name)))?;
+    let dir = state.skill_manager.skill_files_dir(&skill)?
+        .ok_or_else(|| AppError::not_found(format!("找不到 skill 的文件（未安装且没有缓存）: {}", skill.name)))?;
+
+    let contexts = tokio::task::spawn_blocking(move || {
+        let mut files: std::collections::HashMap<String, Option<String>> = std::collections::HashMap::new();
+        scan_report.issues.iter()
+            .filter_map(|issue| {
+                let file_path = issue.file_path.as_deref()?;
+                // 报告中的路径相对技能目录，拒绝越出目录的路径
+                if std::path::Path::new(file_path).components().any(|c| !matches!(c, std::path::Component::Normal(_))) {
+                    return None;
+                }
+                let content = files
+                    .entry(file_path.to_string())
+                    .or_insert_with(|| std::fs::read(dir.join(file_path)).ok().map(|bytes| String::from_utf8_lossy(&bytes).into_owned()))
+                    .as_deref()?;
+                crate::security::issue_context(content, issue, context_lines)
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| AppError::internal(format!("读取问题上下文失败: {}", e)))?;
+
+    Ok(contexts)
+}
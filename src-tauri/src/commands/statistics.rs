@@ -0,0 +1,213 @@
+use crate::commands::{cache_usage, temp_dirs_usage, AppState};
+use crate::i18n::validate_locale;
+use crate::models::{AppError, FalsePositiveReport, RuleStatistics};
+use crate::security::SecurityRules;
+use crate::services::{feedback, Database};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tauri::State;
+
+/// 最近一次全量扫描时间（app_settings 键）
+pub(crate) const LAST_FULL_SCAN_KEY: &str = "last_full_scan_at";
+/// 最近一次检查更新的结果（app_settings 键）
+pub(crate) const LAST_UPDATE_CHECK_KEY: &str = "last_update_check";
+
+/// 返回的高频规则数量
+const TOP_RULES_LIMIT: usize = 10;
+
+/// 最近一次检查更新的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateCheckRecord {
+    pub checked_at: String,
+    /// 有更新可用的 skill ID
+    pub skill_ids: Vec<String>,
+}
+
+/// 命中次数最多的规则
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleHitCount {
+    pub rule_id: String,
+    pub rule_name: String,
+    /// 所有已安装 skill 中的命中次数
+    pub hits: usize,
+    /// 命中该规则的 skill 数量
+    pub skills: usize,
+}
+
+/// 概览统计信息
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Statistics {
+    pub repositories: usize,
+    pub skills: usize,
+    pub installed_skills: usize,
+    /// 已安装 skill 的安全等级分布（Safe/Low/Medium/High/Critical，未扫描的计入 Unscanned）
+    pub security_levels: BTreeMap<String, usize>,
+    pub top_rules: Vec<RuleHitCount>,
+    pub cached_repositories: usize,
+    pub cache_size_bytes: u64,
+    /// 更新临时目录与更新备份占用的空间
+    pub temp_size_bytes: u64,
+    pub last_full_scan_at: Option<String>,
+    pub last_update_check_at: Option<String>,
+    /// 最近一次检查更新时有更新可用、且仍处于安装状态的 skill 数量
+    pub updates_available: usize,
+}
+
+/// 读取最近一次检查更新的结果
+pub(crate) fn last_update_check(db: &Database) -> anyhow::Result<Option<UpdateCheckRecord>> {
+    Ok(db
+        .get_setting(LAST_UPDATE_CHECK_KEY)?
+        .and_then(|json| serde_json::from_str::<UpdateCheckRecord>(&json).ok()))
+}
+
+/// 获取概览统计信息（供概览页一次性加载）
+#[tauri::command]
+pub async fn get_statistics(
+    state: State<'_, AppState>,
+    locale: Option<String>,
+) -> Result<Statistics, AppError> {
+    let locale = validate_locale(locale.as_deref().unwrap_or_default()).to_string();
+
+    let repos = state.db.get_repositories()?;
+    let skills = state.db.get_skills()?;
+    let installed: Vec<_> = skills.iter().filter(|s| s.installed).collect();
+
+    let mut security_levels = BTreeMap::new();
+    for skill in &installed {
+        let level = skill.security_level.clone().unwrap_or_else(|| "Unscanned".to_string());
+        *security_levels.entry(level).or_insert(0) += 1;
+    }
+
+    let installed_ids: HashSet<&str> = installed.iter().map(|s| s.id.as_str()).collect();
+    let reports = state.db.get_scan_reports()?;
+
+    // rule_id -> (命中次数, 命中的 skill)
+    let mut rule_hits: HashMap<String, (usize, HashSet<String>)> = HashMap::new();
+    for (skill_id, (report, _)) in &reports {
+        if !installed_ids.contains(skill_id.as_str()) {
+            continue;
+        }
+        for rule_id in report.issues.iter().filter_map(|i| i.rule_id.as_ref()) {
+            let entry = rule_hits.entry(rule_id.clone()).or_default();
+            entry.0 += 1;
+            entry.1.insert(skill_id.clone());
+        }
+    }
+
+    let rules = SecurityRules::get_all_patterns();
+    let mut top_rules: Vec<RuleHitCount> = rule_hits
+        .into_iter()
+        .map(|(rule_id, (hits, skill_ids))| RuleHitCount {
+            rule_name: rules
+                .iter()
+                .find(|r| r.id == rule_id)
+                .map(|r| r.name(&locale))
+                .unwrap_or_else(|| rule_id.clone()),
+            rule_id,
+            hits,
+            skills: skill_ids.len(),
+        })
+        .collect();
+    top_rules.sort_by(|a, b| {
+        b.skills
+            .cmp(&a.skills)
+            .then(b.hits.cmp(&a.hits))
+            .then(a.rule_id.cmp(&b.rule_id))
+    });
+    top_rules.truncate(TOP_RULES_LIMIT);
+
+    let (cached_repositories, cache_size_bytes) = cache_usage(&repos);
+
+    let last_full_scan_at = state.db.get_setting(LAST_FULL_SCAN_KEY)?;
+    let last_update_check = last_update_check(&state.db)?;
+    let updates_available = last_update_check
+        .as_ref()
+        .map(|record| {
+            record.skill_ids.iter().filter(|id| installed_ids.contains(id.as_str())).count()
+        })
+        .unwrap_or(0);
+
+    Ok(Statistics {
+        repositories: repos.len(),
+        skills: skills.len(),
+        installed_skills: installed.len(),
+        security_levels,
+        top_rules,
+        cached_repositories,
+        cache_size_bytes,
+        temp_size_bytes: temp_dirs_usage(state.skill_manager.backup_root().as_deref()),
+        last_full_scan_at,
+        last_update_check_at: last_update_check.map(|record| record.checked_at),
+        updates_available,
+    })
+}
+
+/// 获取各规则的累计命中统计及误报反馈次数
+#[tauri::command]
+pub async fn get_rule_statistics(
+    state: State<'_, AppState>,
+    locale: Option<String>,
+) -> Result<Vec<RuleStatistics>, AppError> {
+    let locale = validate_locale(locale.as_deref().unwrap_or_default()).to_string();
+    let rules = SecurityRules::get_all_patterns();
+
+    let mut stats = state.db.get_rule_statistics()?;
+    for stat in &mut stats {
+        if let Some(rule) = rules.iter().find(|r| r.id == stat.rule_id) {
+            stat.rule_name = rule.name(&locale);
+        }
+    }
+
+    Ok(stats)
+}
+
+/// 反馈误报：记录规则 ID 与脱敏后的代码片段，返回记录 ID
+#[tauri::command]
+pub async fn report_false_positive(
+    state: State<'_, AppState>,
+    rule_id: String,
+    snippet: String,
+    skill_id: Option<String>,
+    file_path: Option<String>,
+    note: Option<String>,
+) -> Result<i64, AppError> {
+    if !SecurityRules::get_all_patterns().iter().any(|r| r.id == rule_id) {
+        return Err(AppError::not_found(format!("未知的规则: {}", rule_id)));
+    }
+
+    let report = FalsePositiveReport {
+        id: 0,
+        rule_id,
+        skill_id,
+        file_path: file_path.map(|path| feedback::anonymize_snippet(&path)),
+        snippet: feedback::anonymize_snippet(&snippet),
+        note: note.map(|note| note.trim().to_string()).filter(|note| !note.is_empty()),
+        reported_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let id = state.db.add_false_positive_report(&report)?;
+    log::info!("已记录误报反馈: 规则 {}, 记录 {}", report.rule_id, id);
+    Ok(id)
+}
+
+/// 获取本地记录的误报反馈（从新到旧）
+#[tauri::command]
+pub async fn get_false_positive_reports(
+    state: State<'_, AppState>,
+) -> Result<Vec<FalsePositiveReport>, AppError> {
+    state.db.get_false_positive_reports().map_err(AppError::from)
+}
+
+/// 将误报反馈导出为 JSON 文件，返回导出的记录数
+#[tauri::command]
+pub async fn export_false_positive_reports(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<usize, AppError> {
+    let path = state.check_export_path(&path)?;
+    let reports = state.db.get_false_positive_reports()?;
+    feedback::export_false_positives(&reports, &path)?;
+    Ok(reports.len())
+}
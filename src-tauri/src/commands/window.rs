@@ -0,0 +1,128 @@
+use crate::models::AppError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager, State, WebviewUrl, WebviewWindowBuilder};
+
+/// 详情窗口标签前缀（capabilities 中按此前缀授权）
+const DETAIL_WINDOW_PREFIX: &str = "detail-";
+
+/// 详情窗口的内容类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DetailKind {
+    /// 技能详情（市场中的技能信息）
+    Skill,
+    /// 扫描报告
+    Report,
+}
+
+impl DetailKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DetailKind::Skill => "skill",
+            DetailKind::Report => "report",
+        }
+    }
+}
+
+/// 交给详情窗口的状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetailWindowState {
+    pub kind: DetailKind,
+    pub id: String,
+    /// 打开窗口的页面传入的数据（如当前筛选条件、要对比的版本），原样交给新窗口
+    pub payload: Option<serde_json::Value>,
+}
+
+/// 已打开的详情窗口的状态（窗口标签 → 状态），窗口关闭时删除
+#[derive(Default)]
+pub struct DetailWindows {
+    states: Mutex<HashMap<String, DetailWindowState>>,
+}
+
+impl DetailWindows {
+    fn insert(&self, label: &str, state: DetailWindowState) {
+        self.states.lock().unwrap_or_else(|e| e.into_inner()).insert(label.to_string(), state);
+    }
+
+    fn get(&self, label: &str) -> Option<DetailWindowState> {
+        self.states.lock().unwrap_or_else(|e| e.into_inner()).get(label).cloned()
+    }
+
+    fn remove(&self, label: &str) {
+        self.states.lock().unwrap_or_else(|e| e.into_inner()).remove(label);
+    }
+}
+
+/// 同一内容只打开一个窗口：标签由类型与 ID 的哈希组成（窗口标签不允许任意字符）
+fn detail_window_label(kind: DetailKind, id: &str) -> String {
+    let hash = hex::encode(Sha256::digest(id.as_bytes()));
+    format!("{}{}-{}", DETAIL_WINDOW_PREFIX, kind.as_str(), &hash[..16])
+}
+
+/// 在新窗口中打开技能详情或扫描报告，便于与主窗口并排对比；返回窗口标签
+///
+/// 页面地址为 `index.html#/detail/{kind}/{id}`，新窗口加载后通过 get_detail_window_state 读取传入的状态。
+/// 同一内容的窗口已打开时更新其状态（发送 detail-window-state 事件）并聚焦
+#[tauri::command]
+pub async fn open_detail_window(
+    app: tauri::AppHandle,
+    windows: State<'_, DetailWindows>,
+    kind: DetailKind,
+    id: String,
+    title: Option<String>,
+    payload: Option<serde_json::Value>,
+) -> Result<String, AppError> {
+    if id.trim().is_empty() {
+        return Err(AppError::invalid_input("详情窗口的 ID 不能为空"));
+    }
+
+    let label = detail_window_label(kind, &id);
+    let state = DetailWindowState { kind, id: id.clone(), payload };
+
+    if let Some(window) = app.get_webview_window(&label) {
+        windows.insert(&label, state.clone());
+        if let Err(e) = window.emit_to(&label, "detail-window-state", &state) {
+            log::warn!("发送 detail-window-state 事件失败: {}", e);
+        }
+        window.unminimize().ok();
+        window.set_focus().map_err(|e| AppError::internal(format!("无法聚焦详情窗口: {}", e)))?;
+        return Ok(label);
+    }
+
+    windows.insert(&label, state);
+    let url = format!("index.html#/detail/{}/{}", kind.as_str(), urlencoding::encode(&id));
+    let window = WebviewWindowBuilder::new(&app, &label, WebviewUrl::App(url.into()))
+        .title(title.unwrap_or_else(|| "Agent Skills Guard".to_string()))
+        .inner_size(960.0, 720.0)
+        .min_inner_size(600.0, 400.0)
+        .build()
+        .map_err(|e| {
+            windows.remove(&label);
+            AppError::internal(format!("无法打开详情窗口: {}", e))
+        })?;
+
+    // 窗口关闭时删除其状态
+    let app_handle = app.clone();
+    let closed_label = label.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Destroyed = event {
+            app_handle.state::<DetailWindows>().remove(&closed_label);
+        }
+    });
+
+    log::info!("打开详情窗口 {}: {:?} {}", label, kind, id);
+    Ok(label)
+}
+
+/// 详情窗口读取打开时传入的状态（页面刷新后可再次读取；不是详情窗口时返回 None）
+#[tauri::command]
+pub async fn get_detail_window_state(
+    window: tauri::WebviewWindow,
+    windows: State<'_, DetailWindows>,
+) -> Result<Option<DetailWindowState>, AppError> {
+    Ok(windows.get(window.label()))
+}
@@ -1,26 +1,61 @@
-// 初始化 i18n，设置 fallback 语言为中文
-rust_i18n::i18n!("locales", fallback = "zh");
+// 初始化 i18n：缺失的词条先回退到英文再回退到中文；运行时加载的语言文件优先于内置翻译
+rust_i18n::i18n!("skillguard-core/locales", fallback = ["en", "zh"], backend = skillguard_core::i18n::ExternalTranslations);
 
 pub mod commands;
-mod i18n;
 pub mod models;
-pub mod security;
 pub mod services;
 
-use commands::security::{get_scan_results, scan_all_installed_skills, scan_skill_archive};
+// 安全扫描规则与扫描器位于 skillguard-core，与命令行工具共用
+pub use skillguard_core::security;
+use skillguard_core::i18n;
+
+use commands::security::{
+    compute_path_checksum, export_skill_report, get_issue_context, get_scan_results, get_startup_scan_summary, scan_all_installed_skills,
+    scan_skill_archive, verify_skill_checksum,
+};
+use commands::app_update::{check_for_app_updates, install_app_update};
+use commands::batch::{delete_skill_records, get_skill_tags, rescan_skills, tag_skills, uninstall_skills};
+use commands::github_auth::{cancel_github_sign_in, get_github_auth_status, poll_github_sign_in, start_github_sign_in};
+use commands::profile::{create_profile, delete_profile, list_profiles, switch_profile};
+use commands::window::{get_detail_window_state, open_detail_window, DetailWindows};
+use commands::statistics::{
+    export_false_positive_reports, get_false_positive_reports, get_rule_statistics, get_statistics,
+    report_false_positive,
+};
 use commands::AppState;
 use services::{Database, SkillManager};
 use std::sync::Arc;
 use tauri::menu::{MenuBuilder, MenuItemBuilder};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder};
-use tauri::Manager;
-use tokio::sync::Mutex;
+use tauri::{Emitter, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
 
 const MAIN_WINDOW_LABEL: &str = "main";
 const MENU_SHOW: &str = "show";
 const MENU_HIDE: &str = "hide";
+const MENU_TOGGLE_PROTECTION: &str = "toggle_protection";
 const MENU_QUIT: &str = "quit";
 
+/// 定时清理过期临时目录的间隔
+const TEMP_CLEANUP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(6 * 60 * 60);
+/// 启动后首次检查需要后台刷新的仓库前的等待时间（避开启动时的扫描与更新检查）
+const REPOSITORY_REFRESH_STARTUP_DELAY: std::time::Duration = std::time::Duration::from_secs(2 * 60);
+/// 检查需要后台刷新的仓库的间隔（各仓库是否到期由设置的刷新间隔决定）
+const REPOSITORY_REFRESH_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+/// 启动后首次检查是否需要定时扫描已安装技能前的等待时间
+const INSTALLED_RESCAN_STARTUP_DELAY: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+/// 检查是否需要定时扫描已安装技能的间隔（是否到期由设置的扫描间隔决定）
+const INSTALLED_RESCAN_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+/// 启动后首次检查是否需要定时检查技能更新前的等待时间
+const SKILL_UPDATE_CHECK_STARTUP_DELAY: std::time::Duration = std::time::Duration::from_secs(3 * 60);
+/// 检查是否需要定时检查技能更新的间隔（是否到期由设置的检查间隔决定）
+const SKILL_UPDATE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+/// 启动后首次检查远程内容的延迟
+const REMOTE_CONTENT_STARTUP_DELAY: std::time::Duration = std::time::Duration::from_secs(60);
+/// 检查远程内容是否过期的间隔（各内容的有效期见 RemoteContentKind::max_age）
+const REMOTE_CONTENT_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
 #[cfg(target_os = "macos")]
 const MACOS_TRAY_TEMPLATE_ICON: tauri::image::Image<'static> =
     tauri::include_image!("icons/tray-icon-template.png");
@@ -40,6 +75,106 @@ fn maybe_suppress_macos_os_activity_logs() {
     std::env::set_var("OS_ACTIVITY_MODE", "disable");
 }
 
+/// 打开数据库：数据库文件已加密时从系统钥匙串读取密钥
+/// 打开后执行完整性检查，损坏时自动从最近的备份恢复
+fn open_database(db_path: std::path::PathBuf, backups: &services::DatabaseBackups) -> anyhow::Result<Database> {
+    if !Database::is_encrypted_file(&db_path) {
+        return backups.open_with_recovery(db_path, None);
+    }
+
+    let key_account = services::credentials::database_key_account(services::profile::active_profile());
+    let key = services::CredentialStore::get(&key_account)?
+        .ok_or_else(|| anyhow::anyhow!("数据库已加密，但系统钥匙串中没有找到密钥"))?;
+    log::info!("打开加密数据库");
+    backups.open_with_recovery(db_path, Some(&key))
+}
+
+/// 设置代理环境变量，使所有 reqwest 客户端（包括 updater 等插件）都能自动使用代理
+/// 传入 None 时清除环境变量，确保不意外使用代理
+///
+/// 环境变量只能表达全局代理与 NO_PROXY，按主机覆盖的规则只对应用自己构建的客户端生效
+fn apply_proxy_env(config: Option<&services::ProxyConfig>) {
+    match config {
+        Some(config) => {
+            log::info!("已加载代理配置: {}:{}", config.host, config.port);
+            let proxy_url = format!("socks5h://{}:{}", config.host, config.port);
+            std::env::set_var("HTTP_PROXY", &proxy_url);
+            std::env::set_var("HTTPS_PROXY", &proxy_url);
+            std::env::set_var("ALL_PROXY", &proxy_url);
+            std::env::set_var("NO_PROXY", config.no_proxy_env());
+        }
+        None => {
+            log::info!("代理未启用");
+            std::env::remove_var("HTTP_PROXY");
+            std::env::remove_var("HTTPS_PROXY");
+            std::env::remove_var("ALL_PROXY");
+            std::env::remove_var("NO_PROXY");
+        }
+    }
+}
+
+/// 订阅设置变更：更新代理环境变量、语言、SkillManager，并向前端发送 settings-changed 事件
+fn spawn_settings_listener(
+    app_handle: tauri::AppHandle,
+    settings: &services::SettingsService,
+    skill_manager: Arc<SkillManager>,
+) {
+    let mut receiver = settings.subscribe();
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let change = match receiver.recv().await {
+                Ok(change) => change,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("设置变更事件积压，跳过 {} 条", skipped);
+                    continue;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            };
+
+            if change.proxy_changed() {
+                let proxy = &change.current.proxy;
+                apply_proxy_env(proxy.enabled.then_some(proxy));
+            }
+
+            if change.locale_changed() {
+                rust_i18n::set_locale(&change.current.locale);
+            }
+
+            if change.log_level_changed() {
+                services::AppLogger::set_level(change.current.log_level_filter());
+            }
+
+            if change.hotkeys_changed() {
+                apply_hotkeys(&app_handle, Some(&change.previous.hotkeys), &change.current.hotkeys);
+            }
+
+            if change.launch_at_login_changed() {
+                if let Err(e) = services::autostart::set_enabled(change.current.background.launch_at_login) {
+                    log::warn!("更新开机自启动失败: {}", e);
+                }
+            }
+
+            if change.protection_changed() {
+                let paused = change.current.background.protection_paused;
+                log::info!("后台保护已{}", if paused { "暂停" } else { "恢复" });
+                update_tray_menu(&app_handle, paused);
+                app_handle.state::<AppState>().notify_status_changed();
+            }
+
+            if change.proxy_changed() || change.network_changed() {
+                log::info!("代理或网络配置已变更，重启应用后对 GitHub 请求生效");
+            }
+
+            skill_manager.apply_settings(&change.current);
+
+            if let Err(e) = app_handle.emit("settings-changed", &change.current) {
+                log::warn!("发送 settings-changed 事件失败: {}", e);
+            }
+        }
+    });
+}
+
 /// 获取托盘菜单文本（中英文双语）
 ///
 /// 返回值：(显示窗口文本, 隐藏窗口文本, 退出文本)
@@ -48,21 +183,422 @@ fn get_menu_texts() -> (&'static str, &'static str, &'static str) {
     ("显示 / Show", "隐藏 / Hide", "退出 / Quit")
 }
 
-fn create_tray_menu(app: &tauri::AppHandle) -> Result<tauri::menu::Menu<tauri::Wry>, tauri::Error> {
+/// 托盘图标的基础图标（macOS 使用模板图标，其他系统使用应用图标）
+fn tray_base_icon(app: &tauri::AppHandle) -> Option<tauri::image::Image<'static>> {
+    #[cfg(target_os = "macos")]
+    {
+        let _ = app;
+        Some(MACOS_TRAY_TEMPLATE_ICON.clone())
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        app.default_window_icon().map(|icon| icon.clone().to_owned())
+    }
+}
+
+/// 托盘显示的安全状态
+struct TrayStatus {
+    /// 存在 Critical 级别问题的已安装技能数量
+    critical_skills: usize,
+    /// 最近一次检查更新时有更新可用的已安装技能数量
+    updates_available: usize,
+    protection_paused: bool,
+}
+
+impl TrayStatus {
+    fn load(state: &AppState) -> anyhow::Result<Self> {
+        let installed: Vec<_> = state.db.get_skills()?.into_iter().filter(|s| s.installed).collect();
+
+        let critical_skills = installed
+            .iter()
+            .filter(|s| s.security_level.as_deref() == Some("Critical"))
+            .count();
+        let updates_available = commands::statistics::last_update_check(&state.db)?
+            .map(|record| {
+                record
+                    .skill_ids
+                    .iter()
+                    .filter(|id| installed.iter().any(|s| &s.id == *id))
+                    .count()
+            })
+            .unwrap_or(0);
+
+        Ok(Self {
+            critical_skills,
+            updates_available,
+            protection_paused: state.settings.get().background.protection_paused,
+        })
+    }
+
+    /// 托盘提示文本（中英文双语，每种状态一行）
+    fn tooltip(&self) -> String {
+        let mut lines = vec!["Agent Skills Guard".to_string()];
+        if self.critical_skills > 0 {
+            lines.push(format!(
+                "{} 个技能存在严重风险 / {} skill(s) with critical findings",
+                self.critical_skills, self.critical_skills
+            ));
+        }
+        if self.updates_available > 0 {
+            lines.push(format!(
+                "{} 个技能有可用更新 / {} update(s) available",
+                self.updates_available, self.updates_available
+            ));
+        }
+        if self.protection_paused {
+            lines.push("保护已暂停 / Protection paused".to_string());
+        }
+        lines.join("\n")
+    }
+
+    /// 图标角标颜色（按严重程度取最高的一种状态），无需提示时返回 None
+    fn badge_color(&self) -> Option<[u8; 3]> {
+        if self.critical_skills > 0 {
+            Some([0xE5, 0x39, 0x35])
+        } else if self.protection_paused {
+            Some([0xFB, 0x8C, 0x00])
+        } else if self.updates_available > 0 {
+            Some([0x1E, 0x88, 0xE5])
+        } else {
+            None
+        }
+    }
+}
+
+/// 在图标右下角绘制圆形角标
+fn badge_icon(base: &tauri::image::Image<'_>, color: [u8; 3]) -> tauri::image::Image<'static> {
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+
+    let radius = width.min(height) as f32 * 0.22;
+    let (cx, cy) = (width as f32 - radius - 1.0, height as f32 - radius - 1.0);
+    for y in 0..height {
+        for x in 0..width {
+            let distance = ((x as f32 + 0.5 - cx).powi(2) + (y as f32 + 0.5 - cy).powi(2)).sqrt();
+            if distance <= radius {
+                let offset = ((y * width + x) * 4) as usize;
+                rgba[offset..offset + 3].copy_from_slice(&color);
+                rgba[offset + 3] = 0xFF;
+            }
+        }
+    }
+
+    tauri::image::Image::new_owned(rgba, width, height)
+}
+
+/// 根据当前安全状态刷新托盘图标与提示
+fn refresh_tray_status(app: &tauri::AppHandle) {
+    let (Some(state), Some(tray)) = (
+        app.try_state::<AppState>(),
+        app.try_state::<tauri::tray::TrayIcon<tauri::Wry>>(),
+    ) else {
+        return;
+    };
+
+    let status = match TrayStatus::load(&state) {
+        Ok(status) => status,
+        Err(e) => {
+            log::warn!("读取安全状态失败: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = tray.set_tooltip(Some(status.tooltip())) {
+        log::warn!("更新托盘提示失败: {}", e);
+    }
+
+    let Some(base) = tray_base_icon(app) else {
+        return;
+    };
+    let badge = status.badge_color();
+    let icon = match badge {
+        Some(color) => badge_icon(&base, color),
+        None => base,
+    };
+    if let Err(e) = tray.set_icon(Some(icon)) {
+        log::warn!("更新托盘图标失败: {}", e);
+    }
+    // 带彩色角标时不能作为模板图标（模板图标只保留透明度）
+    #[cfg(target_os = "macos")]
+    if let Err(e) = tray.set_icon_as_template(badge.is_none()) {
+        log::warn!("更新托盘图标失败: {}", e);
+    }
+}
+
+/// 后台维护：启动时恢复上次运行中断的安装/更新，之后定时清理过期的更新临时目录与备份
+///
+/// 各步骤持有写操作锁执行，不会与安装、更新同时修改文件
+fn spawn_maintenance_task(skill_manager: Arc<SkillManager>) {
+    tauri::async_runtime::spawn(async move {
+        {
+            let _operation = skill_manager.begin_operation().await;
+            match skill_manager.run_blocking(|manager| manager.recover_pending_operations()).await {
+                Ok(0) => {}
+                Ok(count) => log::info!("已处理 {} 个未完成的安装/更新操作", count),
+                Err(e) => log::warn!("恢复未完成的安装/更新操作失败: {}", e),
+            }
+        }
+
+        loop {
+            {
+                let _operation = skill_manager.begin_operation().await;
+                match skill_manager.run_blocking(|manager| manager.cleanup_temp_dirs()).await {
+                    Ok((0, _)) => {}
+                    Ok((removed, freed)) => log::info!("已清理 {} 个过期临时目录，释放 {} 字节", removed, freed),
+                    Err(e) => log::warn!("清理过期临时目录失败: {}", e),
+                }
+            }
+            tokio::time::sleep(TEMP_CLEANUP_INTERVAL).await;
+        }
+    });
+}
+
+/// 后台定时刷新仓库：重新下载有新提交的到期仓库，发现新技能或可疑更新时通知前端
+///
+/// 暂停后台保护时跳过本轮检查
+fn spawn_repository_refresh_task(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(REPOSITORY_REFRESH_STARTUP_DELAY).await;
+
+        loop {
+            let state = app_handle.state::<AppState>();
+            let settings = state.settings.get();
+
+            if !settings.background.protection_paused {
+                let repositories = commands::refresh_due_repositories(state).await;
+                let count: usize = repositories.iter().map(|result| result.new_skills.len()).sum();
+                let suspicious: Vec<_> = repositories.iter().flat_map(|result| result.suspicious_updates.clone()).collect();
+
+                if !suspicious.is_empty() && settings.notifications.enabled && settings.notifications.scan_findings {
+                    if let Err(e) = app_handle.emit("suspicious-skill-updates", &suspicious) {
+                        log::warn!("发送 suspicious-skill-updates 事件失败: {}", e);
+                    }
+                }
+
+                if count > 0 && settings.notifications.enabled && settings.notifications.new_skills {
+                    let notification = commands::NewSkillsNotification { count, repositories };
+                    if let Err(e) = app_handle.emit("new-skills-available", &notification) {
+                        log::warn!("发送 new-skills-available 事件失败: {}", e);
+                    }
+                }
+            }
+
+            tokio::time::sleep(REPOSITORY_REFRESH_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// 后台定时扫描已安装技能：命中硬触发规则的技能按设置自动通知、禁用或隔离，并通知前端
+///
+/// 开启启动检查时先检查一次并发送变化汇总；暂停后台保护时跳过本轮检查
+fn spawn_installed_rescan_task(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let settings = app_handle.state::<AppState>().settings.get();
+        if settings.background.rescan_on_startup && !settings.background.protection_paused {
+            run_startup_scan(&app_handle).await;
+        }
+
+        tokio::time::sleep(INSTALLED_RESCAN_STARTUP_DELAY).await;
+
+        loop {
+            let state = app_handle.state::<AppState>();
+            let settings = state.settings.get();
+
+            if !settings.background.protection_paused {
+                let threats = commands::security::rescan_installed_skills_if_due(state).await;
+                // 自动禁用或隔离了技能时无论通知设置如何都要告知前端，否则技能会无提示地消失
+                let acted = threats.iter().any(|threat| !matches!(threat.response, services::settings::ThreatResponse::Notify));
+                let notify = settings.notifications.enabled && settings.notifications.scan_findings;
+                if !threats.is_empty() && (acted || notify) {
+                    if let Err(e) = app_handle.emit("installed-skill-threats", &threats) {
+                        log::warn!("发送 installed-skill-threats 事件失败: {}", e);
+                    }
+                }
+            }
+
+            tokio::time::sleep(INSTALLED_RESCAN_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// 后台定时检查已安装技能的更新，新发现的更新合并为一条通知发送给前端
+///
+/// 暂停后台保护时跳过本轮检查
+fn spawn_skill_update_task(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SKILL_UPDATE_CHECK_STARTUP_DELAY).await;
+
+        loop {
+            let state = app_handle.state::<AppState>();
+            let settings = state.settings.get();
+
+            if !settings.background.protection_paused {
+                let skills = commands::check_skill_updates_if_due(state).await;
+                if !skills.is_empty() && settings.notifications.enabled && settings.notifications.skill_updates {
+                    let notification = commands::SkillUpdatesNotification { count: skills.len(), skills };
+                    if let Err(e) = app_handle.emit("skill-updates-available", &notification) {
+                        log::warn!("发送 skill-updates-available 事件失败: {}", e);
+                    }
+                }
+            }
+
+            tokio::time::sleep(SKILL_UPDATE_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// 后台定时刷新过期的远程内容，内容有变化时通知前端
+fn spawn_remote_content_task(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(REMOTE_CONTENT_STARTUP_DELAY).await;
+
+        loop {
+            let state = app_handle.state::<AppState>();
+            let updated: Vec<_> = commands::refresh_stale_remote_content(&state)
+                .await
+                .into_iter()
+                .filter(|status| status.changed)
+                .collect();
+            if !updated.is_empty() {
+                if let Err(e) = app_handle.emit("remote-content-updated", &updated) {
+                    log::warn!("发送 remote-content-updated 事件失败: {}", e);
+                }
+            }
+
+            tokio::time::sleep(REMOTE_CONTENT_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// 启动检查：发送变化汇总，安全状况变差的技能按通知设置另行通知
+async fn run_startup_scan(app_handle: &tauri::AppHandle) {
+    let state = app_handle.state::<AppState>();
+    let settings = state.settings.get();
+    let summary = match commands::security::run_startup_scan(state).await {
+        Ok(summary) => summary,
+        Err(e) => {
+            log::warn!("启动检查失败: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = app_handle.emit("startup-scan-summary", &summary) {
+        log::warn!("发送 startup-scan-summary 事件失败: {}", e);
+    }
+    let regressions = summary.regressions();
+    if !regressions.is_empty() && settings.notifications.enabled && settings.notifications.scan_findings {
+        if let Err(e) = app_handle.emit("skill-regressions", &regressions) {
+            log::warn!("发送 skill-regressions 事件失败: {}", e);
+        }
+    }
+}
+
+/// 剪贴板中识别出的 GitHub 地址（clipboard-github-url 事件）
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ClipboardGitHubUrl {
+    /// 添加仓库时使用的地址
+    repository_url: String,
+    owner: String,
+    repo: String,
+    /// 地址指向仓库内的技能目录时的路径
+    path: Option<String>,
+    already_added: bool,
+}
+
+/// 主窗口获得焦点时（已在设置中开启）检查剪贴板：内容为新的 GitHub 仓库/技能地址时通知前端，
+/// 由用户决定是否添加并扫描
+fn check_clipboard_for_github_url(app_handle: &tauri::AppHandle) {
+    let state = app_handle.state::<AppState>();
+    if !state.settings.get().clipboard.detect_github_urls {
+        return;
+    }
+
+    let text = match app_handle.clipboard().read_text() {
+        Ok(text) => text,
+        Err(e) => {
+            log::debug!("读取剪贴板失败: {}", e);
+            return;
+        }
+    };
+    let Some(url) = app_handle.state::<services::clipboard::ClipboardWatcher>().check(&text) else {
+        return;
+    };
+
+    let repository_url = url.repository_url();
+    let already_added = state.db.get_repositories()
+        .map(|repos| repos.iter().any(|repo| models::same_repository(&repo.url, &repository_url)))
+        .unwrap_or(false);
+    let detected = ClipboardGitHubUrl {
+        repository_url,
+        owner: url.owner,
+        repo: url.repo,
+        path: url.path,
+        already_added,
+    };
+    if let Err(e) = app_handle.emit("clipboard-github-url", &detected) {
+        log::warn!("发送 clipboard-github-url 事件失败: {}", e);
+    }
+}
+
+/// 等待安全状态变化通知并刷新托盘（连续多次通知只刷新一次）
+fn spawn_tray_status_listener(app_handle: tauri::AppHandle, status_changed: Arc<tokio::sync::Notify>) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            status_changed.notified().await;
+            refresh_tray_status(&app_handle);
+        }
+    });
+}
+
+/// 获取暂停/恢复保护菜单项文本（中英文双语）
+fn get_protection_menu_text(protection_paused: bool) -> &'static str {
+    if protection_paused {
+        "恢复保护 / Resume Protection"
+    } else {
+        "暂停保护 / Pause Protection"
+    }
+}
+
+fn create_tray_menu(
+    app: &tauri::AppHandle,
+    protection_paused: bool,
+) -> Result<tauri::menu::Menu<tauri::Wry>, tauri::Error> {
     let (show_text, hide_text, quit_text) = get_menu_texts();
 
     let show_item = MenuItemBuilder::with_id(MENU_SHOW, show_text).build(app)?;
     let hide_item = MenuItemBuilder::with_id(MENU_HIDE, hide_text).build(app)?;
+    let protection_item =
+        MenuItemBuilder::with_id(MENU_TOGGLE_PROTECTION, get_protection_menu_text(protection_paused)).build(app)?;
     let quit_item = MenuItemBuilder::with_id(MENU_QUIT, quit_text).build(app)?;
 
     MenuBuilder::new(app)
         .item(&show_item)
         .item(&hide_item)
         .separator()
+        .item(&protection_item)
+        .separator()
         .item(&quit_item)
         .build()
 }
 
+/// 保护状态变更后重建托盘菜单（更新暂停/恢复菜单项文本）
+fn update_tray_menu(app: &tauri::AppHandle, protection_paused: bool) {
+    let Some(tray) = app.try_state::<tauri::tray::TrayIcon<tauri::Wry>>() else {
+        return;
+    };
+
+    match create_tray_menu(app, protection_paused) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                log::warn!("更新托盘菜单失败: {}", e);
+            }
+        }
+        Err(e) => log::warn!("创建托盘菜单失败: {}", e),
+    }
+}
+
 fn handle_menu_event(app: &tauri::AppHandle, event: tauri::menu::MenuEvent) {
     log::debug!("菜单事件: {}", event.id().as_ref());
 
@@ -88,6 +624,15 @@ fn handle_menu_event(app: &tauri::AppHandle, event: tauri::menu::MenuEvent) {
                 log::error!("无法获取主窗口");
             }
         }
+        MENU_TOGGLE_PROTECTION => {
+            let state = app.state::<AppState>();
+            let result = state.settings.update(|settings| {
+                settings.background.protection_paused = !settings.background.protection_paused;
+            });
+            if let Err(e) = result {
+                log::warn!("切换后台保护状态失败: {}", e);
+            }
+        }
         MENU_QUIT => {
             log::info!("用户通过托盘菜单退出应用");
             app.exit(0);
@@ -106,29 +651,58 @@ fn handle_tray_event(tray: &tauri::tray::TrayIcon<tauri::Wry>, event: tauri::tra
     } = event
     {
         log::debug!("托盘图标被点击");
-        let app = tray.app_handle();
-        if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
-            match window.is_visible() {
-                Ok(true) => {
-                    if let Err(e) = window.hide() {
-                        log::warn!("隐藏窗口失败: {}", e);
-                    }
+        toggle_main_window(tray.app_handle());
+    }
+}
+
+/// 切换主窗口显示状态（托盘图标点击与全局快捷键共用）
+fn toggle_main_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+        match window.is_visible() {
+            Ok(true) => {
+                if let Err(e) = window.hide() {
+                    log::warn!("隐藏窗口失败: {}", e);
                 }
-                Ok(false) => {
-                    if let Err(e) = window.show() {
-                        log::warn!("显示窗口失败: {}", e);
-                    }
-                    if let Err(e) = window.set_focus() {
-                        log::warn!("设置窗口焦点失败: {}", e);
-                    }
+            }
+            Ok(false) => {
+                if let Err(e) = window.show() {
+                    log::warn!("显示窗口失败: {}", e);
                 }
-                Err(e) => {
-                    log::error!("检查窗口可见性失败: {}", e);
+                if let Err(e) = window.set_focus() {
+                    log::warn!("设置窗口焦点失败: {}", e);
                 }
             }
-        } else {
-            log::error!("无法获取主窗口");
+            Err(e) => {
+                log::error!("检查窗口可见性失败: {}", e);
+            }
         }
+    } else {
+        log::error!("无法获取主窗口");
+    }
+}
+
+/// 按设置注册显示/隐藏主窗口的全局快捷键（先注销旧的快捷键）
+/// 快捷键被其他应用占用时只记录警告，不影响启动
+fn apply_hotkeys(
+    app: &tauri::AppHandle,
+    previous: Option<&services::settings::HotkeySettings>,
+    current: &services::settings::HotkeySettings,
+) {
+    let manager = app.global_shortcut();
+
+    if let Some(previous) = previous.filter(|hotkeys| hotkeys.enabled) {
+        if let Err(e) = manager.unregister(previous.toggle_window.trim()) {
+            log::warn!("注销全局快捷键 {} 失败: {}", previous.toggle_window, e);
+        }
+    }
+
+    if !current.enabled {
+        return;
+    }
+
+    match manager.register(current.toggle_window.trim()) {
+        Ok(()) => log::info!("已注册全局快捷键: {}", current.toggle_window),
+        Err(e) => log::warn!("注册全局快捷键 {} 失败（可能已被其他应用占用）: {}", current.toggle_window, e),
     }
 }
 
@@ -137,8 +711,8 @@ pub fn run() {
     #[cfg(target_os = "macos")]
     maybe_suppress_macos_os_activity_logs();
 
-    // 初始化日志
-    env_logger::init();
+    // 初始化日志（应用数据目录确定后再写入日志文件）
+    services::AppLogger::init();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_os::init())
@@ -148,95 +722,147 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, _shortcut, event| {
+                    if event.state() == ShortcutState::Pressed {
+                        toggle_main_window(app);
+                    }
+                })
+                .build(),
+        )
         .setup(|app| {
-            // 获取应用数据目录
-            let app_dir = app
-                .path()
-                .app_data_dir()
+            // 获取应用数据目录（便携模式下为可执行文件旁的 data 目录）
+            let app_dir = commands::app_data_dir(app.handle())
                 .expect("Failed to get app data directory");
 
             std::fs::create_dir_all(&app_dir).expect("Failed to create app data directory");
 
-            let db_path = app_dir.join("agent-skills.db");
+            if let Err(e) = services::AppLogger::attach_file(&app_dir.join("logs")) {
+                log::warn!("无法写入日志文件: {}", e);
+            }
+
+            // 确定本次启动使用的配置档：数据库、备份与缓存均按配置档隔离
+            let profiles = Arc::new(services::ProfileStore::new(app_dir.clone()));
+            let profile = profiles.active();
+            services::profile::activate(&profile);
+            let data_dir = profiles.data_dir(&profile);
+            std::fs::create_dir_all(&data_dir).expect("Failed to create profile data directory");
+            log::info!("当前配置档: {}", profile);
+
+            let db_path = data_dir.join("agent-skills.db");
 
-            // 初始化数据库
-            let db = Database::new(db_path).expect("Failed to initialize database");
+            // 初始化数据库（已加密时从系统钥匙串读取密钥）
+            let backups = Arc::new(services::DatabaseBackups::new(data_dir.join("backups")));
+            let db = open_database(db_path, &backups).expect("Failed to initialize database");
 
             let db = Arc::new(db);
 
-            // 加载代理配置
-            let proxy_config = match db.get_setting("proxy_config") {
-                Ok(Some(json)) => {
-                    match serde_json::from_str::<services::ProxyConfig>(&json) {
-                        Ok(config) if config.enabled => {
-                            log::info!("已加载代理配置: {}:{}", config.host, config.port);
-                            
-                            // 设置环境变量，使所有 reqwest 客户端（包括 updater 等插件）都能自动使用代理
-                            let proxy_url = format!("socks5h://{}:{}", config.host, config.port);
-                            std::env::set_var("HTTP_PROXY", &proxy_url);
-                            std::env::set_var("HTTPS_PROXY", &proxy_url);
-                            std::env::set_var("ALL_PROXY", &proxy_url);
-                            
-                            Some(config)
-                        }
-                        Ok(_) => {
-                            log::info!("代理配置存在但未启用");
-                            // 清除环境变量确保不意外使用
-                            std::env::remove_var("HTTP_PROXY");
-                            std::env::remove_var("HTTPS_PROXY");
-                            std::env::remove_var("ALL_PROXY");
-                            None
-                        }
-                        Err(e) => {
-                            log::warn!("解析代理配置失败: {}", e);
-                            None
-                        }
-                    }
-                }
-                _ => {
-                    log::info!("未找到代理配置");
-                    None
-                }
-            };
+            // 迁移旧版本的缓存目录（需在创建仓库缓存、blob 存储等目录之前）
+            match services::paths::migrate_legacy_cache(&db) {
+                Ok(0) => {}
+                Ok(count) => log::info!("已更新 {} 条缓存路径记录", count),
+                Err(e) => log::warn!("迁移缓存目录失败: {}", e),
+            }
+
+            // 加载应用数据目录中的额外语言文件（可独立于版本发布更新翻译）
+            match i18n::load_locale_dir(&app_dir.join("locales")) {
+                Ok(loaded) if !loaded.is_empty() => log::info!("已加载额外语言: {:?}", loaded),
+                Ok(_) => {}
+                Err(e) => log::warn!("加载额外语言文件失败: {}", e),
+            }
+
+            // 加载应用设置（首次运行时从旧版代理/网络配置迁移）
+            let settings = Arc::new(
+                services::SettingsService::load(Arc::clone(&db)).expect("Failed to load settings")
+            );
+            let current_settings = settings.get();
+
+            apply_proxy_env(current_settings.proxy.enabled.then_some(&current_settings.proxy));
+            let proxy_config = current_settings.proxy.clone();
+
+            let network_config = current_settings.network.clone();
+            log::info!(
+                "已加载网络配置: connect={}s, read={}s, retries={}",
+                network_config.connect_timeout_secs, network_config.read_timeout_secs, network_config.max_retries
+            );
+
+            rust_i18n::set_locale(&current_settings.locale);
+
+            // 未通过 RUST_LOG 指定级别时使用设置中的日志级别
+            if std::env::var_os("RUST_LOG").is_none() {
+                services::AppLogger::set_level(current_settings.log_level_filter());
+            }
 
             // 创建共享 HTTP 客户端（已配置代理）
             let http_client = Arc::new(
-                services::ProxyService::build_http_client(proxy_config.as_ref())
+                services::ProxyService::build_http_client(Some(&proxy_config), &network_config)
                     .expect("Failed to build HTTP client")
             );
 
             // 初始化 GitHub 服务（使用代理配置）
-            let github = Arc::new(services::GitHubService::new_with_proxy(proxy_config));
+            let github = Arc::new(services::GitHubService::new_with_proxy(Some(proxy_config), network_config));
+
+            // 从系统钥匙串加载 GitHub Token（用于提升 API 配额及 GraphQL 批量查询），旧版明文 Token 自动迁移
+            let token = services::CredentialStore::migrate_plaintext_setting(
+                &db,
+                "github_token",
+                services::credentials::GITHUB_TOKEN_ACCOUNT,
+            );
+            github.set_token(token);
+            if github.has_token() {
+                log::info!("已加载 GitHub Token");
+            }
 
             // 初始化 SkillManager
             let skill_manager = SkillManager::new(Arc::clone(&db), Arc::clone(&github));
-            let skill_manager = Arc::new(Mutex::new(skill_manager));
+            skill_manager.apply_settings(&current_settings);
+            let progress_handle = app.handle().clone();
+            skill_manager.set_progress_handler(Arc::new(move |progress| {
+                if let Err(e) = progress_handle.emit("skill-fs-progress", progress) {
+                    log::warn!("发送 skill-fs-progress 事件失败: {}", e);
+                }
+            }));
+            let skill_manager = Arc::new(skill_manager);
+
+            // 恢复上次运行中断的安装/更新，并定时清理过期的临时目录
+            spawn_maintenance_task(Arc::clone(&skill_manager));
+
+            // 监听设置变更，通知各服务并转发给前端
+            spawn_settings_listener(app.handle().clone(), &settings, Arc::clone(&skill_manager));
+
+            // 安全状态变化时刷新托盘图标与提示
+            let status_changed = Arc::new(tokio::sync::Notify::new());
+            spawn_tray_status_listener(app.handle().clone(), Arc::clone(&status_changed));
+
+            // 精选仓库列表等远程内容的缓存（与配置档无关，位于应用数据目录）
+            let remote_content = Arc::new(services::remote_content::RemoteContentService::new(
+                Arc::clone(&http_client),
+                app_dir.clone(),
+            ));
 
             // 设置应用状态
             app.manage(AppState {
                 db,
                 skill_manager,
                 github,
+                settings,
+                backups,
                 http_client,
+                remote_content,
+                profiles,
+                status_changed,
             });
+            app.manage(services::clipboard::ClipboardWatcher::default());
+            app.manage(services::github_auth::GitHubSignIn::default());
+            app.manage(DetailWindows::default());
 
             // 初始化系统托盘
-            let icon = {
-                #[cfg(target_os = "macos")]
-                {
-                    MACOS_TRAY_TEMPLATE_ICON.clone()
-                }
-
-                #[cfg(not(target_os = "macos"))]
-                {
-                    app.default_window_icon()
-                        .ok_or("无法获取默认窗口图标")?
-                        .clone()
-                }
-            };
+            let icon = tray_base_icon(app.handle()).ok_or("无法获取默认窗口图标")?;
 
             let app_handle = app.handle();
-            let menu = create_tray_menu(&app_handle)?;
+            let menu = create_tray_menu(&app_handle, current_settings.background.protection_paused)?;
 
             let tray = TrayIconBuilder::new()
                 .icon(icon)
@@ -249,11 +875,64 @@ pub fn run() {
 
             // 存储托盘实例到 app state
             app.manage(tray);
+            app.state::<AppState>().notify_status_changed();
+
+            apply_hotkeys(app.handle(), None, &current_settings.hotkeys);
+
+            // 按设置的间隔在后台刷新仓库，发现新技能时通知前端
+            spawn_repository_refresh_task(app.handle().clone());
+
+            // 按设置的间隔在后台重新扫描已安装技能，命中硬触发规则时自动处理
+            spawn_installed_rescan_task(app.handle().clone());
+
+            // 按设置的间隔在后台检查技能更新，按技能的通知偏好合并通知
+            spawn_skill_update_task(app.handle().clone());
+
+            // 在后台刷新过期的远程内容（精选仓库列表等）
+            spawn_remote_content_task(app.handle().clone());
+
+            // 启动时按所选渠道检查应用更新，有新版本时通知前端
+            if current_settings.updates.check_on_startup {
+                let app_handle = app.handle().clone();
+                let channel = current_settings.updates.channel;
+                tauri::async_runtime::spawn(async move {
+                    match commands::app_update::check_app_update(&app_handle, channel).await {
+                        Ok(Some(update)) => {
+                            log::info!("发现应用新版本: {} -> {}", update.current_version, update.version);
+                            if let Err(e) = app_handle.emit("app-update-available", &update) {
+                                log::warn!("发送 app-update-available 事件失败: {}", e);
+                            }
+                        }
+                        Ok(None) => log::info!("应用已是最新版本"),
+                        Err(e) => log::warn!("{}", e),
+                    }
+                });
+            }
+
+            // 开机自启动项与设置保持一致（可执行文件位置可能已变化）
+            if current_settings.background.launch_at_login {
+                if let Err(e) = services::autostart::set_enabled(true) {
+                    log::warn!("更新开机自启动失败: {}", e);
+                }
+            }
+
+            // 开机自启动时只显示托盘图标，后台保护继续运行
+            if services::autostart::launched_minimized() {
+                log::info!("开机自启动，最小化到托盘");
+                if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+                    if let Err(e) = window.hide() {
+                        log::warn!("隐藏窗口失败: {}", e);
+                    }
+                }
+            }
 
             // 监听窗口关闭请求，改为隐藏到托盘
             if let Some(main_window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
                 let app_handle = app.handle().clone();
                 main_window.on_window_event(move |event| {
+                    if let tauri::WindowEvent::Focused(true) = event {
+                        check_clipboard_for_github_url(&app_handle);
+                    }
                     if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                         log::info!("窗口关闭请求，隐藏到托盘而不是退出");
                         // 阻止默认关闭行为
@@ -278,43 +957,130 @@ pub fn run() {
             commands::add_repository,
             commands::get_repositories,
             commands::delete_repository,
+            commands::verify_repository_signature,
+            commands::set_repository_require_signed_commits,
+            commands::set_repository_scan_paths,
+            commands::set_repository_skip_auto_refresh,
             commands::scan_repository,
             commands::get_skills,
             commands::get_installed_skills,
+            commands::query_skills,
+            commands::check_skills_health,
+            commands::relocate_skills,
+            commands::list_skill_backups,
+            commands::get_skill_readme,
             commands::install_skill,
             commands::prepare_skill_installation,
             commands::confirm_skill_installation,
+            commands::install_skill_from_url,
             commands::sync_skill,
+            commands::reinstall_skill,
             commands::cancel_skill_installation,
             commands::uninstall_skill,
             commands::uninstall_skill_path,
+            commands::check_uninstall_impact,
+            commands::rename_installed_skill,
+            commands::check_skill_directories,
+            commands::fix_skill_directory,
             commands::delete_skill,
             commands::scan_local_skills,
+            uninstall_skills,
+            rescan_skills,
+            get_skill_tags,
+            tag_skills,
+            delete_skill_records,
             commands::clear_repository_cache,
             commands::clear_all_repository_caches,
+            commands::clear_staging_caches,
+            commands::clear_skill_backups,
+            commands::clear_extracted_archives,
+            commands::clear_unused_caches,
+            commands::get_cache_usage,
             commands::refresh_repository_cache,
             commands::get_cache_stats,
             commands::open_skill_directory,
             commands::get_default_install_path,
             commands::select_custom_install_path,
+            commands::select_export_path,
             commands::get_featured_repositories,
             commands::refresh_featured_repositories,
+            commands::get_remote_content_status,
+            commands::refresh_remote_content,
             commands::is_repository_added,
             commands::check_skills_updates,
+            commands::set_skill_update_notifications,
             commands::prepare_skill_update,
             commands::confirm_skill_update,
             commands::cancel_skill_update,
+            commands::get_suspicious_updates,
+            commands::dismiss_suspicious_update,
+            commands::get_skill_timeline,
+            commands::mark_skill_reviewed,
+            commands::clear_skill_review,
+            commands::get_skill_reviews,
+            commands::import_skill_list,
+            commands::handle_dropped_paths,
+            commands::add_and_scan_repository_url,
+            commands::export_skill_list,
+            commands::get_skills_provenance,
+            commands::cancel_skill_operation,
             commands::auto_scan_unscanned_repositories,
             commands::get_proxy_config,
             commands::save_proxy_config,
             commands::test_proxy,
+            commands::get_network_config,
+            commands::save_network_config,
+            commands::get_settings,
+            commands::set_settings,
+            commands::check_global_shortcut,
+            commands::get_protection_status,
+            commands::pause_protection,
+            commands::resume_protection,
+            commands::is_autostart_enabled,
+            commands::get_available_locales,
+            commands::has_github_token,
+            commands::save_github_token,
+            start_github_sign_in,
+            poll_github_sign_in,
+            cancel_github_sign_in,
+            get_github_auth_status,
+            commands::set_install_passphrase,
+            commands::get_database_encryption_status,
+            commands::set_database_encryption,
+            commands::backup_database,
+            commands::restore_database,
+            commands::get_recent_logs,
+            commands::set_log_level,
+            commands::get_github_rate_limit_status,
             scan_all_installed_skills,
+            get_startup_scan_summary,
             get_scan_results,
             scan_skill_archive,
+            export_skill_report,
+            compute_path_checksum,
+            verify_skill_checksum,
+            get_issue_context,
+            get_statistics,
+            get_rule_statistics,
+            report_false_positive,
+            get_false_positive_reports,
+            export_false_positive_reports,
+            list_profiles,
+            create_profile,
+            delete_profile,
+            switch_profile,
+            open_detail_window,
+            get_detail_window_state,
+            check_for_app_updates,
+            install_app_update,
             commands::translate_text,
             // 工具管理命令
             commands::get_supported_tools,
             commands::get_tool_skills_tree,
+            commands::get_skills_by_tools,
+            commands::check_skill_compatibility,
+            commands::get_skill_install_targets,
+            commands::set_skill_install_target,
             commands::read_skill_file,
             commands::open_tool_folder,
             commands::get_installed_tool_paths,
@@ -0,0 +1,101 @@
+use serde::Serialize;
+
+use super::AppError;
+
+/// 比较的校验和来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecksumSource {
+    /// 调用方提供（如发布页面上的哈希）
+    Provided,
+    /// 导入技能清单时固定的内容哈希
+    Pinned,
+}
+
+/// 校验结果状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecksumStatus {
+    /// 所有路径的校验和都与预期一致
+    Verified,
+    /// 至少一个路径的校验和与预期不一致
+    Mismatch,
+    /// 没有可比较的校验和（或没有安装目录）
+    Unverified,
+}
+
+/// 文件或目录的校验和（SHA256，见 SecurityScanner::calculate_path_checksum）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathChecksum {
+    pub path: String,
+    pub is_dir: bool,
+    pub checksum: String,
+    /// 与预期的校验和是否一致（没有预期的校验和时为 None）
+    pub matches: Option<bool>,
+}
+
+impl PathChecksum {
+    pub fn new(path: String, is_dir: bool, checksum: String, expected: Option<&str>) -> Self {
+        let matches = expected.map(|expected| checksum.eq_ignore_ascii_case(expected));
+        Self { path, is_dir, checksum, matches }
+    }
+}
+
+/// 已安装技能的内容校验结果（每个安装目录分别计算）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillChecksumVerification {
+    pub skill_id: String,
+    pub expected: Option<String>,
+    pub source: Option<ChecksumSource>,
+    pub paths: Vec<PathChecksum>,
+    pub status: ChecksumStatus,
+}
+
+impl SkillChecksumVerification {
+    pub fn new(skill_id: &str, expected: Option<(String, ChecksumSource)>, paths: Vec<PathChecksum>) -> Self {
+        let status = if paths.is_empty() || paths.iter().any(|path| path.matches.is_none()) {
+            ChecksumStatus::Unverified
+        } else if paths.iter().all(|path| path.matches == Some(true)) {
+            ChecksumStatus::Verified
+        } else {
+            ChecksumStatus::Mismatch
+        };
+        let (expected, source) = expected.unzip();
+        Self { skill_id: skill_id.to_string(), expected, source, paths, status }
+    }
+}
+
+/// 规范化调用方提供的 SHA256（去除空白与 "sha256:" 前缀，转为小写），格式无效时返回错误
+pub fn normalize_checksum(checksum: &str) -> Result<String, AppError> {
+    let trimmed = checksum.trim();
+    let hex = trimmed.strip_prefix("sha256:").unwrap_or(trimmed);
+    if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AppError::invalid_input(format!("无效的 SHA256 校验和: {}", checksum)));
+    }
+    Ok(hex.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_verification_status() {
+        let hash = "ab".repeat(32);
+        assert_eq!(normalize_checksum(&format!(" sha256:{} ", hash.to_uppercase())).unwrap(), hash);
+        assert!(normalize_checksum("abc").is_err());
+
+        let path = |checksum: &str, expected: Option<&str>| PathChecksum::new("/skills/pdf".to_string(), true, checksum.to_string(), expected);
+        let expected = Some((hash.clone(), ChecksumSource::Provided));
+        let verified = SkillChecksumVerification::new("pdf", expected.clone(), vec![path(&hash, Some(&hash))]);
+        assert_eq!(verified.status, ChecksumStatus::Verified);
+
+        let mismatch = SkillChecksumVerification::new("pdf", expected, vec![path(&hash, Some(&hash)), path(&"cd".repeat(32), Some(&hash))]);
+        assert_eq!(mismatch.status, ChecksumStatus::Mismatch);
+
+        assert_eq!(SkillChecksumVerification::new("pdf", None, vec![path(&hash, None)]).status, ChecksumStatus::Unverified);
+        assert_eq!(SkillChecksumVerification::new("pdf", None, Vec::new()).status, ChecksumStatus::Unverified);
+    }
+}
@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 错误类别，前端据此区分处理方式（重试、提示配置、展示扫描结果等）并显示本地化文案
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// GitHub API 速率限制
+    RateLimited,
+    /// 网络请求失败（连接失败、超时、服务器错误）
+    Network,
+    /// 未授权或无权限访问远程资源
+    Unauthorized,
+    /// 安全扫描或扫描策略阻止了操作
+    BlockedByScan,
+    /// 仓库要求的提交签名校验未通过
+    SignatureRejected,
+    /// 下载的技能内容与技能清单中固定的哈希不一致
+    IntegrityMismatch,
+    /// 资源不存在（技能、仓库、文件等）
+    NotFound,
+    /// 参数或设置无效
+    InvalidInput,
+    /// 用户取消了操作
+    Cancelled,
+    /// 操作有需要用户确认的影响（如卸载被其他技能依赖的技能）
+    ConfirmationRequired,
+    /// 本地文件系统权限不足
+    PermissionDenied,
+    /// 写入路径不在允许的目录中（技能目录或用户批准过的目录）
+    PathNotAllowed,
+    /// 本地文件读写失败
+    Io,
+    /// 数据库读写失败
+    Database,
+    /// 其他未分类的错误
+    Internal,
+}
+
+impl ErrorKind {
+    /// 该类错误稍后重试是否可能成功
+    pub fn is_retryable(self) -> bool {
+        matches!(self, ErrorKind::RateLimited | ErrorKind::Network)
+    }
+}
+
+/// 命令返回给前端的结构化错误
+///
+/// 服务层可通过 `anyhow::Error::from(AppError::new(..))` 携带错误类别，
+/// 未携带类别的错误在转换时按错误链中的底层错误类型归类
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppError {
+    pub kind: ErrorKind,
+    /// 面向用户的错误说明
+    pub message: String,
+    /// 完整的错误链等排查信息
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// 稍后重试是否可能成功
+    pub retryable: bool,
+}
+
+impl AppError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            details: None,
+            retryable: kind.is_retryable(),
+        }
+    }
+
+    /// 在错误说明前加上操作描述，如 "扫描缓存失败: 原因"
+    pub fn context(mut self, context: impl fmt::Display) -> Self {
+        self.message = format!("{}: {}", context, self.message);
+        self
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidInput, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::NotFound, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Internal, message)
+    }
+
+    /// 按错误链中的底层错误归类（用于未显式携带类别的错误）
+    fn classify(error: &anyhow::Error) -> ErrorKind {
+        for cause in error.chain() {
+            if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+                return match e.status().map(|s| s.as_u16()) {
+                    Some(401 | 403) => ErrorKind::Unauthorized,
+                    Some(404) => ErrorKind::NotFound,
+                    Some(429) => ErrorKind::RateLimited,
+                    _ => ErrorKind::Network,
+                };
+            }
+            if cause.is::<super::InstallBlocked>() {
+                return ErrorKind::BlockedByScan;
+            }
+            if cause.is::<rusqlite::Error>() {
+                return ErrorKind::Database;
+            }
+            if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+                return match e.kind() {
+                    std::io::ErrorKind::NotFound => ErrorKind::NotFound,
+                    std::io::ErrorKind::PermissionDenied => ErrorKind::PermissionDenied,
+                    std::io::ErrorKind::TimedOut => ErrorKind::Network,
+                    _ => ErrorKind::Io,
+                };
+            }
+        }
+        ErrorKind::Internal
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<anyhow::Error> for AppError {
+    fn from(error: anyhow::Error) -> Self {
+        // message 与以往一致，只取最外层说明；多层错误时在 details 中保留完整错误链
+        let message = error.to_string();
+        let chain = (error.chain().count() > 1).then(|| format!("{:#}", error));
+
+        match error.chain().find_map(|cause| cause.downcast_ref::<AppError>()) {
+            Some(inner) => Self {
+                message,
+                details: chain.or_else(|| inner.details.clone()),
+                ..inner.clone()
+            },
+            None => Self {
+                details: chain,
+                ..Self::new(Self::classify(&error), message)
+            },
+        }
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        anyhow::Error::from(error).into()
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        Self::internal(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_app_error_from_anyhow() {
+        let error = anyhow::Error::from(AppError::new(ErrorKind::RateLimited, "rate limited"))
+            .context("扫描仓库失败");
+        let app_error = AppError::from(error);
+        assert_eq!(app_error.kind, ErrorKind::RateLimited);
+        assert!(app_error.retryable);
+        assert_eq!(app_error.message, "扫描仓库失败");
+        assert_eq!(app_error.details.as_deref(), Some("扫描仓库失败: rate limited"));
+
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+        let app_error = AppError::from(Err::<(), _>(io).context("读取文件失败").unwrap_err());
+        assert_eq!(app_error.kind, ErrorKind::NotFound);
+        assert!(!app_error.retryable);
+
+        let json = serde_json::to_value(AppError::invalid_input("bad")).unwrap();
+        assert_eq!(json, serde_json::json!({"kind": "invalid_input", "message": "bad", "retryable": false}));
+    }
+}
@@ -0,0 +1,207 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::security::ScoreHistoryPoint;
+
+/// 技能历史事件类型（skill_events 表）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillEventKind {
+    /// 安装到某个目录
+    Installed,
+    /// 更新到新的 commit
+    Updated,
+    /// 从某个目录（或所有目录）卸载
+    Uninstalled,
+    /// 仓库刷新时被标记为可疑更新
+    Flagged,
+    /// 后台扫描命中硬触发规则后被自动禁用
+    Disabled,
+    /// 后台扫描命中硬触发规则后被自动隔离
+    Quarantined,
+    /// 审阅人确认了当前安装的版本
+    Reviewed,
+    /// 按安装时的 commit 重新安装到某个目录
+    Reinstalled,
+}
+
+impl SkillEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SkillEventKind::Installed => "installed",
+            SkillEventKind::Updated => "updated",
+            SkillEventKind::Uninstalled => "uninstalled",
+            SkillEventKind::Flagged => "flagged",
+            SkillEventKind::Disabled => "disabled",
+            SkillEventKind::Quarantined => "quarantined",
+            SkillEventKind::Reviewed => "reviewed",
+            SkillEventKind::Reinstalled => "reinstalled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "installed" => Some(SkillEventKind::Installed),
+            "updated" => Some(SkillEventKind::Updated),
+            "uninstalled" => Some(SkillEventKind::Uninstalled),
+            "flagged" => Some(SkillEventKind::Flagged),
+            "disabled" => Some(SkillEventKind::Disabled),
+            "quarantined" => Some(SkillEventKind::Quarantined),
+            "reviewed" => Some(SkillEventKind::Reviewed),
+            "reinstalled" => Some(SkillEventKind::Reinstalled),
+            _ => None,
+        }
+    }
+}
+
+/// 技能的一条历史事件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillEvent {
+    pub skill_id: String,
+    pub kind: SkillEventKind,
+    /// 涉及的安装目录（卸载所有目录时为 None）
+    pub path: Option<String>,
+    /// 安装/更新后的 commit SHA
+    pub commit_sha: Option<String>,
+    /// 附加说明（如可疑更新的评分变化）
+    pub detail: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl SkillEvent {
+    pub fn new(skill_id: &str, kind: SkillEventKind) -> Self {
+        Self {
+            skill_id: skill_id.to_string(),
+            kind,
+            path: None,
+            commit_sha: None,
+            detail: None,
+            occurred_at: Utc::now(),
+        }
+    }
+}
+
+/// 时间线条目类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineEntryKind {
+    Installed,
+    Updated,
+    Uninstalled,
+    Flagged,
+    Disabled,
+    Quarantined,
+    Reviewed,
+    Reinstalled,
+    /// 扫描评分变化（首次扫描时 previous_score 为空）
+    ScoreChanged,
+}
+
+impl From<SkillEventKind> for TimelineEntryKind {
+    fn from(kind: SkillEventKind) -> Self {
+        match kind {
+            SkillEventKind::Installed => TimelineEntryKind::Installed,
+            SkillEventKind::Updated => TimelineEntryKind::Updated,
+            SkillEventKind::Uninstalled => TimelineEntryKind::Uninstalled,
+            SkillEventKind::Flagged => TimelineEntryKind::Flagged,
+            SkillEventKind::Disabled => TimelineEntryKind::Disabled,
+            SkillEventKind::Quarantined => TimelineEntryKind::Quarantined,
+            SkillEventKind::Reviewed => TimelineEntryKind::Reviewed,
+            SkillEventKind::Reinstalled => TimelineEntryKind::Reinstalled,
+        }
+    }
+}
+
+/// 技能时间线中的一条记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    pub kind: TimelineEntryKind,
+    pub occurred_at: DateTime<Utc>,
+    pub path: Option<String>,
+    pub commit_sha: Option<String>,
+    pub previous_score: Option<i32>,
+    pub score: Option<i32>,
+    pub detail: Option<String>,
+}
+
+/// 由历史事件与评分历史组装时间线（从旧到新）：评分只在首次扫描和发生变化时生成条目，
+/// 无法解析时间的评分记录会被跳过
+pub fn build_skill_timeline(events: &[SkillEvent], scores: &[ScoreHistoryPoint]) -> Vec<TimelineEntry> {
+    let mut timeline: Vec<TimelineEntry> = events
+        .iter()
+        .map(|event| TimelineEntry {
+            kind: event.kind.into(),
+            occurred_at: event.occurred_at,
+            path: event.path.clone(),
+            commit_sha: event.commit_sha.clone(),
+            previous_score: None,
+            score: None,
+            detail: event.detail.clone(),
+        })
+        .collect();
+
+    let mut previous_score = None;
+    for point in scores {
+        let Ok(scanned_at) = DateTime::parse_from_rfc3339(&point.scanned_at) else {
+            continue;
+        };
+        if previous_score == Some(point.score) {
+            continue;
+        }
+        timeline.push(TimelineEntry {
+            kind: TimelineEntryKind::ScoreChanged,
+            occurred_at: scanned_at.with_timezone(&Utc),
+            path: None,
+            commit_sha: None,
+            previous_score,
+            score: Some(point.score),
+            detail: None,
+        });
+        previous_score = Some(point.score);
+    }
+
+    // 稳定排序：同一时刻的事件保持写入顺序
+    timeline.sort_by_key(|entry| entry.occurred_at);
+    timeline
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SecurityLevel;
+    use chrono::Duration;
+
+    #[test]
+    fn test_build_skill_timeline() {
+        let start = Utc::now() - Duration::days(3);
+        let event = |kind, offset_hours| SkillEvent {
+            occurred_at: start + Duration::hours(offset_hours),
+            ..SkillEvent::new("skill-1", kind)
+        };
+        let score = |score, offset_hours| ScoreHistoryPoint {
+            scanned_at: (start + Duration::hours(offset_hours)).to_rfc3339(),
+            score,
+            level: SecurityLevel::from_score(score),
+            issue_count: 0,
+        };
+
+        let events = vec![
+            event(SkillEventKind::Installed, 1),
+            event(SkillEventKind::Updated, 24),
+            event(SkillEventKind::Uninstalled, 48),
+        ];
+        let scores = vec![score(90, 0), score(90, 12), score(60, 23)];
+
+        let timeline = build_skill_timeline(&events, &scores);
+        let kinds: Vec<_> = timeline.iter().map(|entry| entry.kind).collect();
+        assert_eq!(kinds, vec![
+            TimelineEntryKind::ScoreChanged,
+            TimelineEntryKind::Installed,
+            TimelineEntryKind::ScoreChanged,
+            TimelineEntryKind::Updated,
+            TimelineEntryKind::Uninstalled,
+        ]);
+        assert_eq!((timeline[0].previous_score, timeline[0].score), (None, Some(90)));
+        assert_eq!((timeline[2].previous_score, timeline[2].score), (Some(90), Some(60)));
+    }
+}
@@ -0,0 +1,186 @@
+use serde::Serialize;
+use std::fmt;
+
+use super::SecurityReport;
+
+/// 安装结果状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallStatus {
+    Installed,
+    /// 被安全扫描或扫描策略阻止，未写入安装目录
+    Blocked,
+}
+
+/// 阻止安装的原因（前端据此生成本地化说明）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case", rename_all_fields = "camelCase")]
+pub enum BlockReason {
+    /// 命中硬触发规则
+    HardTrigger { issue: String },
+    /// 安全评分低于设置的最低安装评分；confirmable 表示输入技能名称确认后可以继续安装
+    LowScore { score: i32, min_score: i32, confirmable: bool },
+    /// 尚未完成安全扫描
+    NotScanned,
+    /// 高风险技能需要输入确认文本（设置了管理口令时为口令，否则为技能名称）
+    ConfirmationRequired { security_level: String, passphrase: bool },
+    /// 安装后的文件与安全扫描时的内容不一致
+    ContentMismatch { files: Vec<String> },
+}
+
+impl BlockReason {
+    /// 面向日志与不展示结构化结果的调用方的说明
+    pub fn message(&self, skill_name: &str) -> String {
+        match self {
+            BlockReason::HardTrigger { issue } => format!("安全检测发现严重威胁：{}", issue),
+            BlockReason::LowScore { score, min_score, confirmable: false } => {
+                format!("安全评分 {} 低于设置的最低安装评分 {}，已阻止安装", score, min_score)
+            }
+            BlockReason::LowScore { score, min_score, confirmable: true } => format!(
+                "安全评分 {} 低于设置的最低安装评分 {}，请输入技能名称“{}”以确认安装",
+                score, min_score, skill_name
+            ),
+            BlockReason::NotScanned => "技能尚未完成安全扫描，无法确认安装".to_string(),
+            BlockReason::ConfirmationRequired { passphrase: true, .. } => {
+                "该技能风险较高，请输入管理口令以确认安装".to_string()
+            }
+            BlockReason::ConfirmationRequired { passphrase: false, .. } => {
+                format!("该技能风险较高，请输入技能名称“{}”以确认安装", skill_name)
+            }
+            BlockReason::ContentMismatch { files } => {
+                format!("安装后的文件与安全扫描时的内容不一致，已取消安装: {}", files.join(", "))
+            }
+        }
+    }
+}
+
+/// 安装被阻止的错误：服务层以错误返回，安装命令将其转换为 [`InstallStatus::Blocked`] 的结果，
+/// 其他调用方按 BlockedByScan 类别的错误处理
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstallBlocked {
+    pub skill_name: String,
+    pub reasons: Vec<BlockReason>,
+    pub scan: Option<ScanSummary>,
+}
+
+impl InstallBlocked {
+    pub fn new(skill_name: &str, reasons: Vec<BlockReason>) -> Self {
+        Self { skill_name: skill_name.to_string(), reasons, scan: None }
+    }
+
+    /// 命中硬触发规则时阻止安装
+    pub fn hard_triggers(skill_name: &str, report: &SecurityReport) -> Self {
+        let reasons = report.hard_trigger_issues.iter()
+            .map(|issue| BlockReason::HardTrigger { issue: issue.clone() })
+            .collect();
+        Self { scan: Some(ScanSummary::from(report)), ..Self::new(skill_name, reasons) }
+    }
+
+    pub fn with_scan(mut self, report: &SecurityReport) -> Self {
+        self.scan = Some(ScanSummary::from(report));
+        self
+    }
+}
+
+impl fmt::Display for InstallBlocked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let messages: Vec<String> = self.reasons.iter().map(|reason| reason.message(&self.skill_name)).collect();
+        f.write_str(&messages.join("\n"))
+    }
+}
+
+impl std::error::Error for InstallBlocked {}
+
+/// 安全扫描摘要
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanSummary {
+    pub score: i32,
+    pub level: String,
+    pub issue_count: usize,
+    pub hard_trigger_count: usize,
+    pub scanned_files: usize,
+}
+
+impl From<&SecurityReport> for ScanSummary {
+    fn from(report: &SecurityReport) -> Self {
+        Self {
+            score: report.score,
+            level: report.level.as_str().to_string(),
+            issue_count: report.issues.len(),
+            hard_trigger_count: report.hard_trigger_issues.len(),
+            scanned_files: report.scanned_files.len(),
+        }
+    }
+}
+
+/// 安装、同步与确认安装命令的结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallResult {
+    pub skill_id: String,
+    pub status: InstallStatus,
+    /// 安装目录（被阻止时为 None）
+    pub installed_path: Option<String>,
+    pub files_copied: usize,
+    /// 本次安装使用的扫描结果（跳过扫描且没有保存的扫描报告时为 None）
+    pub scan: Option<ScanSummary>,
+    pub block_reasons: Vec<BlockReason>,
+}
+
+impl InstallResult {
+    pub fn installed(skill_id: &str, installed_path: String, files_copied: usize, scan: Option<ScanSummary>) -> Self {
+        Self {
+            skill_id: skill_id.to_string(),
+            status: InstallStatus::Installed,
+            installed_path: Some(installed_path),
+            files_copied,
+            scan,
+            block_reasons: Vec::new(),
+        }
+    }
+
+    /// 被阻止的安装转换为结果，其他错误原样返回
+    pub fn from_outcome(skill_id: &str, outcome: anyhow::Result<Self>) -> anyhow::Result<Self> {
+        let error = match outcome {
+            Ok(result) => return Ok(result),
+            Err(error) => error,
+        };
+        match error.chain().find_map(|cause| cause.downcast_ref::<InstallBlocked>()) {
+            Some(blocked) => {
+                log::warn!("安装被阻止: {}, {}", skill_id, blocked);
+                Ok(Self {
+                    skill_id: skill_id.to_string(),
+                    status: InstallStatus::Blocked,
+                    installed_path: None,
+                    files_copied: 0,
+                    scan: blocked.scan.clone(),
+                    block_reasons: blocked.reasons.clone(),
+                })
+            }
+            None => Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_result_from_blocked_outcome() {
+        let blocked = InstallBlocked::new("demo", vec![BlockReason::LowScore { score: 40, min_score: 60, confirmable: true }]);
+        assert_eq!(blocked.to_string(), "安全评分 40 低于设置的最低安装评分 60，请输入技能名称“demo”以确认安装");
+
+        let outcome = Err(anyhow::Error::from(blocked.clone()).context("安装失败"));
+        let result = InstallResult::from_outcome("skill-1", outcome).unwrap();
+        assert_eq!(result.status, InstallStatus::Blocked);
+        assert_eq!(result.block_reasons, blocked.reasons);
+        assert_eq!(
+            serde_json::to_value(&result.block_reasons[0]).unwrap(),
+            serde_json::json!({"kind": "low_score", "score": 40, "minScore": 60, "confirmable": true})
+        );
+
+        assert!(InstallResult::from_outcome("skill-1", Err(anyhow::anyhow!("network"))).is_err());
+    }
+}
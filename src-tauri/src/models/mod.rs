@@ -1,11 +1,27 @@
 pub mod skill;
 pub mod repository;
-pub mod security;
 pub mod featured;
 pub mod tool;
+pub mod error;
+pub mod operation;
+pub mod history;
+pub mod skill_list;
+pub mod skill_query;
+pub mod review;
+pub mod install;
+pub mod checksum;
 
 pub use skill::*;
 pub use repository::*;
+pub use skillguard_core::models::security;
 pub use security::*;
 pub use featured::*;
 pub use tool::*;
+pub use error::{AppError, ErrorKind};
+pub use operation::*;
+pub use history::*;
+pub use skill_list::*;
+pub use skill_query::*;
+pub use review::*;
+pub use install::*;
+pub use checksum::*;
@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 需要崩溃恢复的写操作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKind {
+    /// 确认安装（从缓存复制到安装目录）
+    Install,
+    /// 确认更新（备份原目录后从 staging 复制）
+    Update,
+}
+
+impl OperationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OperationKind::Install => "install",
+            OperationKind::Update => "update",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "install" => Some(OperationKind::Install),
+            "update" => Some(OperationKind::Update),
+            _ => None,
+        }
+    }
+}
+
+/// 写操作已完成的步骤
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationStep {
+    /// 已开始，安装目录可能被部分删除或写入（更新时可能正在创建备份）
+    Started,
+    /// 原安装目录已完整备份到 backup_dir（仅更新）
+    BackedUp,
+    /// 文件已写入安装目录并通过校验，尚未更新数据库
+    Copied,
+}
+
+impl OperationStep {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OperationStep::Started => "started",
+            OperationStep::BackedUp => "backed_up",
+            OperationStep::Copied => "copied",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "started" => Some(OperationStep::Started),
+            "backed_up" => Some(OperationStep::BackedUp),
+            "copied" => Some(OperationStep::Copied),
+            _ => None,
+        }
+    }
+}
+
+/// 未完成的写操作记录（pending_operations 表）
+///
+/// 操作开始时写入、结束时删除；启动时仍存在的记录说明上次运行在操作中途退出
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingOperation {
+    pub id: String,
+    pub skill_id: String,
+    pub kind: OperationKind,
+    pub step: OperationStep,
+    /// 写入的安装目录
+    pub target_dir: String,
+    /// 原安装目录的备份位置（仅更新）
+    pub backup_dir: Option<String>,
+    /// 写入版本的 commit SHA，完成操作时写回技能记录
+    pub commit_sha: Option<String>,
+    pub started_at: DateTime<Utc>,
+}
+
+impl PendingOperation {
+    pub fn new(skill_id: &str, kind: OperationKind, target_dir: &str) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            skill_id: skill_id.to_string(),
+            kind,
+            step: OperationStep::Started,
+            target_dir: target_dir.to_string(),
+            backup_dir: None,
+            commit_sha: None,
+            started_at: Utc::now(),
+        }
+    }
+}
@@ -1,6 +1,16 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use anyhow::{Result, anyhow};
+use crate::security::ScanFilter;
+
+/// 技能发现允许设置的最大目录深度
+pub const MAX_DISCOVERY_DEPTH: u32 = 10;
+
+/// 启用子目录扫描时技能目录的默认最大深度（相对仓库根目录）
+pub const DEFAULT_DISCOVERY_DEPTH: usize = 6;
+
+/// 发现技能时始终跳过的目录名（不受仓库路径配置影响）
+pub const IGNORED_DISCOVERY_DIRS: &[&str] = &[".git", "node_modules"];
 
 /// GitHub 仓库配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +27,44 @@ pub struct Repository {
     pub cache_path: Option<String>,
     pub cached_at: Option<DateTime<Utc>>,
     pub cached_commit_sha: Option<String>,
+    /// 要求最新提交经过受信任密钥签名后才允许安装
+    #[serde(default)]
+    pub require_signed_commits: bool,
+    /// 最近一次提交签名验证结果
+    #[serde(default)]
+    pub commit_signature: Option<CommitSignatureStatus>,
+    /// 发现技能时只收录匹配的技能目录（glob，相对仓库根目录，为空时不限制）
+    #[serde(default)]
+    pub scan_include_patterns: Vec<String>,
+    /// 发现技能时跳过的目录（glob，相对仓库根目录或目录名）
+    #[serde(default)]
+    pub scan_exclude_patterns: Vec<String>,
+    /// 技能目录相对仓库根目录的最大深度（None 时使用默认深度）
+    #[serde(default)]
+    pub scan_max_depth: Option<u32>,
+    /// 不参与后台定时刷新
+    #[serde(default)]
+    pub skip_auto_refresh: bool,
+    /// 最近一次扫描/下载失败的错误信息（成功后清除）
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub last_error_at: Option<DateTime<Utc>>,
+}
+
+/// 提交签名验证结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommitSignatureStatus {
+    pub commit_sha: String,
+    /// GitHub 验证签名有效
+    pub signed: bool,
+    /// GitHub 给出的验证结果（valid / unsigned / unknown_key / bad_email 等）
+    pub reason: String,
+    /// 签名中的签名者密钥 ID / 指纹（大写十六进制）
+    pub signer_key_ids: Vec<String>,
+    /// 签名有效且签名者在受信任密钥列表中
+    pub verified: bool,
+    pub checked_at: DateTime<Utc>,
 }
 
 impl Repository {
@@ -33,9 +81,34 @@ impl Repository {
             cache_path: None,
             cached_at: None,
             cached_commit_sha: None,
+            require_signed_commits: false,
+            commit_signature: None,
+            scan_include_patterns: Vec::new(),
+            scan_exclude_patterns: Vec::new(),
+            scan_max_depth: None,
+            skip_auto_refresh: false,
+            last_error: None,
+            last_error_at: None,
         }
     }
 
+    /// 发现技能（查找包含 SKILL.md 的目录）时的路径过滤规则
+    ///
+    /// 排除模式作用于遍历的目录，包含模式只决定哪些技能目录被收录
+    pub fn discovery_filter(&self) -> Result<ScanFilter> {
+        ScanFilter::new(&self.scan_include_patterns, &self.scan_exclude_patterns, false)
+    }
+
+    /// 技能目录相对仓库根目录的最大深度
+    ///
+    /// 未启用子目录扫描时只查找根目录下一层；否则为设置的最大深度（未设置时为 [`DEFAULT_DISCOVERY_DEPTH`]）
+    pub fn discovery_depth(&self) -> usize {
+        if !self.scan_subdirs {
+            return 1;
+        }
+        self.scan_max_depth.map_or(DEFAULT_DISCOVERY_DEPTH, |depth| depth as usize)
+    }
+
     /// 从 GitHub URL 提取仓库信息
     /// 支持格式: 
     /// - https://github.com/owner/repo
@@ -78,6 +151,101 @@ impl Repository {
     }
 }
 
+/// 识别为单个 GitHub 地址的文本的最大长度
+const MAX_GITHUB_URL_LEN: usize = 2048;
+
+/// 识别出的 GitHub 仓库或技能地址（https://github.com/owner/repo[/tree/branch/path]）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GitHubUrl {
+    pub owner: String,
+    pub repo: String,
+    pub branch: Option<String>,
+    /// 技能目录相对仓库根目录的路径（tree/blob 地址中分支之后的部分）
+    pub path: Option<String>,
+}
+
+impl GitHubUrl {
+    /// 整段文本是否为一个 github.com 仓库或仓库内目录的地址；
+    /// 与 [`Repository::from_github_url`] 不同，不接受其他域名或不完整的路径
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.len() > MAX_GITHUB_URL_LEN || text.chars().any(char::is_whitespace) {
+            return None;
+        }
+        let rest = text
+            .strip_prefix("https://")
+            .or_else(|| text.strip_prefix("http://"))
+            .unwrap_or(text);
+        let rest = rest.strip_prefix("www.").unwrap_or(rest);
+        let rest = rest.strip_prefix("github.com/")?;
+        let rest = rest.split(['?', '#']).next().unwrap_or_default();
+
+        let mut parts = rest.split('/').filter(|part| !part.is_empty());
+        let valid = |part: &str| part.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        let owner = parts.next().filter(|part| valid(part))?;
+        let repo = parts.next().map(|part| part.trim_end_matches(".git")).filter(|part| !part.is_empty() && valid(part))?;
+
+        let (branch, path) = match parts.next() {
+            None => (None, None),
+            Some("tree" | "blob") => {
+                let branch = parts.next()?.to_string();
+                let path: Vec<&str> = parts.collect();
+                (Some(branch), Some(path.join("/")).filter(|path| !path.is_empty()))
+            }
+            Some(_) => return None,
+        };
+
+        Some(Self {
+            owner: owner.to_lowercase(),
+            repo: repo.to_lowercase(),
+            branch,
+            path,
+        })
+    }
+
+    /// 仓库地址（指定了分支时包含分支，与添加仓库时使用的格式一致）
+    pub fn repository_url(&self) -> String {
+        match &self.branch {
+            Some(branch) => format!("https://github.com/{}/{}/tree/{}", self.owner, self.repo, branch),
+            None => format!("https://github.com/{}/{}", self.owner, self.repo),
+        }
+    }
+}
+
+/// 仓库的热度与来源信息（扫描仓库时从 GitHub 获取并缓存）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RepositoryStats {
+    pub repository_url: String,
+    pub stars: u64,
+    pub owner: String,
+    /// 所有者账号（用户或组织）的创建时间
+    pub owner_created_at: Option<DateTime<Utc>>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl RepositoryStats {
+    /// 缓存的统计信息在此时间内不重新获取，避免每次扫描都消耗 API 配额
+    pub const REFRESH_INTERVAL_HOURS: i64 = 24;
+
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        now - self.fetched_at >= chrono::Duration::hours(Self::REFRESH_INTERVAL_HOURS)
+    }
+}
+
+/// 技能列表中展示的热度与来源信号
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillProvenance {
+    pub skill_id: String,
+    /// 所在仓库的星标数（尚未获取时为 None）
+    pub stars: Option<u64>,
+    /// 收录该技能所在仓库的精选分类数量
+    pub featured_count: usize,
+    /// 技能首次出现在本地索引中的时间
+    pub first_seen_at: Option<DateTime<Utc>>,
+    pub owner: Option<String>,
+    pub owner_created_at: Option<DateTime<Utc>>,
+}
+
 /// GitHub API 响应 - 目录内容
 #[derive(Debug, Clone, Deserialize)]
 pub struct GitHubContent {
@@ -89,3 +257,44 @@ pub struct GitHubContent {
     pub sha: String,
     pub size: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_discovery_filter_and_depth() {
+        let mut repo = Repository::new("https://github.com/owner/repo".to_string(), "repo".to_string());
+        repo.scan_include_patterns = vec!["skills/**".to_string()];
+        repo.scan_exclude_patterns = vec!["examples".to_string()];
+
+        let filter = repo.discovery_filter().unwrap();
+        assert!(filter.is_excluded(Path::new("skills/examples")));
+        assert!(!filter.is_excluded(Path::new("node_modules")), "Default ignores should not apply");
+        assert!(filter.should_scan_file(Path::new("skills/pdf")));
+        assert!(!filter.should_scan_file(Path::new("tools/pdf")));
+
+        assert_eq!(repo.discovery_depth(), DEFAULT_DISCOVERY_DEPTH);
+        repo.scan_max_depth = Some(8);
+        assert_eq!(repo.discovery_depth(), 8);
+        repo.scan_subdirs = false;
+        assert_eq!(repo.discovery_depth(), 1);
+    }
+
+    #[test]
+    fn test_parse_github_url() {
+        let url = GitHubUrl::parse("https://github.com/Anthropics/skills/tree/main/document-skills/pdf?tab=readme").unwrap();
+        assert_eq!((url.owner.as_str(), url.repo.as_str()), ("anthropics", "skills"));
+        assert_eq!(url.branch.as_deref(), Some("main"));
+        assert_eq!(url.path.as_deref(), Some("document-skills/pdf"));
+        assert_eq!(url.repository_url(), "https://github.com/anthropics/skills/tree/main");
+
+        let url = GitHubUrl::parse(" github.com/owner/repo.git\n").unwrap();
+        assert_eq!(url.repository_url(), "https://github.com/owner/repo");
+
+        for text in ["https://gitlab.com/owner/repo", "owner/repo", "https://github.com/owner", "see https://github.com/owner/repo", "https://github.com/owner/repo/issues/1"] {
+            assert!(GitHubUrl::parse(text).is_none(), "{}", text);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::Skill;
+
+/// 审阅人名称的最大长度（字符）
+pub const MAX_REVIEWER_LENGTH: usize = 100;
+
+/// 人工审阅记录：审阅人确认过某个版本的技能内容，只保存在本地（skill_reviews 表）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillReview {
+    pub skill_id: String,
+    pub reviewer: String,
+    pub reviewed_at: DateTime<Utc>,
+    /// 审阅时安装的 commit
+    pub commit_sha: Option<String>,
+    /// 审阅时的技能内容校验和（没有 commit 记录的技能据此比对）
+    pub checksum: Option<String>,
+    pub note: Option<String>,
+}
+
+impl SkillReview {
+    /// 记录对技能当前安装版本的审阅
+    pub fn new(skill: &Skill, reviewer: &str, note: Option<String>) -> Self {
+        Self {
+            skill_id: skill.id.clone(),
+            reviewer: reviewer.trim().to_string(),
+            reviewed_at: Utc::now(),
+            commit_sha: skill.installed_commit_sha.clone(),
+            checksum: skill.checksum.clone(),
+            note: note.map(|note| note.trim().to_string()).filter(|note| !note.is_empty()),
+        }
+    }
+
+    /// 已安装的版本与审阅的版本不一致（commit 优先，缺少 commit 时比对内容校验和；都无法比对时视为不一致）
+    pub fn is_outdated(&self, skill: &Skill) -> bool {
+        if !skill.installed {
+            return false;
+        }
+        if let (Some(reviewed), Some(installed)) = (&self.commit_sha, &skill.installed_commit_sha) {
+            // 兼容短 SHA
+            return !(reviewed.starts_with(installed.as_str()) || installed.starts_with(reviewed.as_str()));
+        }
+        match (&self.checksum, &skill.checksum) {
+            (Some(reviewed), Some(current)) => reviewed != current,
+            _ => true,
+        }
+    }
+}
+
+/// 审阅记录及其是否仍对应当前安装的版本
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillReviewStatus {
+    #[serde(flatten)]
+    pub review: SkillReview,
+    pub skill_name: String,
+    pub installed_commit_sha: Option<String>,
+    /// 已安装的版本不再是审阅过的版本，需要重新审阅
+    pub outdated: bool,
+}
+
+impl SkillReviewStatus {
+    pub fn new(review: SkillReview, skill: &Skill) -> Self {
+        Self {
+            outdated: review.is_outdated(skill),
+            skill_name: skill.name.clone(),
+            installed_commit_sha: skill.installed_commit_sha.clone(),
+            review,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_review_outdated_when_installed_version_changes() {
+        let mut skill = Skill {
+            installed: true,
+            installed_commit_sha: Some("0123456789abcdef0123456789abcdef01234567".to_string()),
+            checksum: Some("aaa".to_string()),
+            ..Skill::new("pdf".to_string(), "https://github.com/owner/repo".to_string(), "skills/pdf".to_string())
+        };
+        let review = SkillReview::new(&skill, "  Alice ", Some(" ".to_string()));
+        assert_eq!(review.reviewer, "Alice");
+        assert_eq!(review.note, None);
+        assert!(!review.is_outdated(&skill));
+
+        // 短 SHA 视为同一 commit
+        skill.installed_commit_sha = Some("0123456".to_string());
+        assert!(!review.is_outdated(&skill));
+
+        skill.installed_commit_sha = Some("fedcba9876543210fedcba9876543210fedcba98".to_string());
+        assert!(SkillReviewStatus::new(review.clone(), &skill).outdated);
+
+        // 没有 commit 记录时比对内容校验和
+        skill.installed_commit_sha = None;
+        assert!(!review.is_outdated(&skill));
+        skill.checksum = Some("bbb".to_string());
+        assert!(review.is_outdated(&skill));
+
+        skill.installed = false;
+        assert!(!review.is_outdated(&skill));
+    }
+}
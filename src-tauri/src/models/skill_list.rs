@@ -0,0 +1,256 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::{Repository, Skill, SkillReview};
+
+/// 技能清单文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkillListFormat {
+    Json,
+    Yaml,
+}
+
+impl SkillListFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Some(SkillListFormat::Json),
+            "yaml" | "yml" => Some(SkillListFormat::Yaml),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SkillListFormat::Json => "json",
+            SkillListFormat::Yaml => "yaml",
+        }
+    }
+}
+
+/// 精选技能清单（社区“入门包”）：引用若干仓库中的技能，JSON 或 YAML 格式
+///
+/// ```yaml
+/// name: Document toolkit
+/// skills:
+///   - repository: https://github.com/anthropics/skills
+///     path: document-skills/pdf
+///   - repository: https://github.com/owner/repo   # 省略 path 表示引用仓库中的所有技能
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillList {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub skills: Vec<SkillListEntry>,
+}
+
+/// 清单中引用的一个技能（或整个仓库）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillListEntry {
+    pub repository: String,
+    /// 技能目录相对仓库根目录的路径
+    #[serde(default)]
+    pub path: Option<String>,
+    /// 导出时技能目录内容的 SHA256（见 fs_ops::dir_hash），导入与安装时校验，防止上游在两次安装之间被改写
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// 导出时已安装版本的人工审阅记录
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review: Option<SkillListReview>,
+}
+
+/// 清单中附带的审阅记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillListReview {
+    pub reviewer: String,
+    pub reviewed_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_sha: Option<String>,
+}
+
+impl SkillList {
+    /// 解析清单内容（YAML 兼容 JSON），并校验每个仓库 URL
+    pub fn parse(content: &str) -> Result<Self> {
+        let list: SkillList = serde_yaml::from_str(content).context("无法解析技能清单（需为 JSON 或 YAML）")?;
+
+        if list.skills.is_empty() {
+            anyhow::bail!("技能清单为空");
+        }
+        for entry in &list.skills {
+            Repository::from_github_url(&entry.repository)
+                .with_context(|| format!("技能清单中的仓库地址无效: {}", entry.repository))?;
+        }
+
+        Ok(list)
+    }
+
+    /// 由已安装的技能生成清单（跳过本地技能），导入时可还原同一组技能
+    pub fn from_skills(name: Option<String>, description: Option<String>, skills: &[Skill]) -> Self {
+        let mut entries: Vec<SkillListEntry> = skills
+            .iter()
+            .filter(|skill| skill.installed && !skill.source_type.is_local())
+            .map(|skill| SkillListEntry {
+                repository: skill.repository_url.clone(),
+                path: Some(normalize_skill_path(&skill.file_path)).filter(|p| !p.is_empty() && p != "."),
+                sha256: None,
+                review: None,
+            })
+            .collect();
+        entries.sort_by(|a, b| (&a.repository, &a.path).cmp(&(&b.repository, &b.path)));
+        entries.dedup();
+
+        Self { name, description, skills: entries }
+    }
+
+    /// 为引用单个技能的条目记录已安装目录的内容哈希，`hash` 返回安装目录的哈希（无法计算时为 None）
+    pub fn pin_hashes(&mut self, skills: &[Skill], hash: impl Fn(&Path) -> Option<String>) {
+        for entry in &mut self.skills {
+            let Some(path) = entry.path.as_deref().map(normalize_skill_path) else {
+                continue;
+            };
+            entry.sha256 = skills
+                .iter()
+                .filter(|skill| skill.installed && same_repository(&skill.repository_url, &entry.repository))
+                .filter(|skill| normalize_skill_path(&skill.file_path) == path)
+                .find_map(|skill| skill.local_path.as_deref().and_then(|dir| hash(Path::new(dir))));
+        }
+    }
+
+    /// 为引用单个技能的条目附上审阅记录（只附上仍对应已安装版本的记录）
+    pub fn attach_reviews(&mut self, skills: &[Skill], reviews: &HashMap<String, SkillReview>) {
+        for entry in &mut self.skills {
+            let Some(path) = entry.path.as_deref().map(normalize_skill_path) else {
+                continue;
+            };
+            entry.review = skills
+                .iter()
+                .filter(|skill| skill.installed && same_repository(&skill.repository_url, &entry.repository))
+                .filter(|skill| normalize_skill_path(&skill.file_path) == path)
+                .find_map(|skill| reviews.get(&skill.id).filter(|review| !review.is_outdated(skill)))
+                .map(|review| SkillListReview {
+                    reviewer: review.reviewer.clone(),
+                    reviewed_at: review.reviewed_at,
+                    commit_sha: review.commit_sha.clone(),
+                });
+        }
+    }
+
+    /// 清单中为该技能固定的内容哈希
+    pub fn expected_hash(&self, repository: &str, path: &str) -> Option<&str> {
+        self.skills
+            .iter()
+            .filter(|entry| same_repository(&entry.repository, repository))
+            .find(|entry| entry.path.as_deref().map(normalize_skill_path).as_deref() == Some(path))
+            .and_then(|entry| entry.sha256.as_deref())
+    }
+
+    /// 序列化为指定格式
+    pub fn render(&self, format: SkillListFormat) -> Result<String> {
+        Ok(match format {
+            SkillListFormat::Json => serde_json::to_string_pretty(self)?,
+            SkillListFormat::Yaml => serde_yaml::to_string(self)?,
+        })
+    }
+
+    /// 清单引用的仓库（按 owner/repo 去重，保持首次出现的顺序）及其引用的技能路径，
+    /// 路径列表为空表示引用仓库中的所有技能
+    pub fn repositories(&self) -> Vec<(String, Vec<String>)> {
+        let mut repositories: Vec<(String, Vec<String>)> = Vec::new();
+        let mut whole_repositories = Vec::new();
+
+        for entry in &self.skills {
+            let index = match repositories.iter().position(|(url, _)| same_repository(url, &entry.repository)) {
+                Some(index) => index,
+                None => {
+                    repositories.push((entry.repository.trim().to_string(), Vec::new()));
+                    repositories.len() - 1
+                }
+            };
+            match entry.path.as_deref().map(normalize_skill_path).filter(|p| !p.is_empty() && p != ".") {
+                Some(path) if !repositories[index].1.contains(&path) => repositories[index].1.push(path),
+                Some(_) => {}
+                None => whole_repositories.push(index),
+            }
+        }
+
+        for index in whole_repositories {
+            repositories[index].1.clear();
+        }
+        repositories
+    }
+}
+
+/// 两个仓库地址是否指向同一个 GitHub 仓库（忽略大小写、.git 后缀与分支）
+pub fn same_repository(a: &str, b: &str) -> bool {
+    match (Repository::from_github_url(a), Repository::from_github_url(b)) {
+        (Ok((owner_a, repo_a, _)), Ok((owner_b, repo_b, _))) => owner_a == owner_b && repo_a == repo_b,
+        _ => a.trim_end_matches('/') == b.trim_end_matches('/'),
+    }
+}
+
+/// 统一技能路径格式（/ 分隔，去掉首尾的 /）
+pub fn normalize_skill_path(path: &str) -> String {
+    path.replace('\\', "/").trim_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_skill_list() {
+        let yaml = "name: starter\nskills:\n  - repository: https://github.com/Owner/Repo\n    path: /skills/pdf/\n  \
+                    - repository: https://github.com/owner/repo.git\n    path: skills/docx\n  \
+                    - repository: https://github.com/other/tools\n";
+        let list = SkillList::parse(yaml).unwrap();
+        assert_eq!(list.name.as_deref(), Some("starter"));
+        assert_eq!(list.repositories(), vec![
+            ("https://github.com/Owner/Repo".to_string(), vec!["skills/pdf".to_string(), "skills/docx".to_string()]),
+            ("https://github.com/other/tools".to_string(), Vec::new()),
+        ]);
+
+        let json = r#"{"skills": [{"repository": "https://github.com/owner/repo", "path": "a"}]}"#;
+        assert_eq!(SkillList::parse(json).unwrap().skills.len(), 1);
+
+        assert!(SkillList::parse(r#"{"skills": []}"#).is_err());
+        assert!(SkillList::parse(r#"{"skills": [{"repository": "not-a-url"}]}"#).is_err());
+    }
+
+    #[test]
+    fn test_skill_list_export_roundtrip() {
+        let installed = |url: &str, path: &str| Skill {
+            installed: true,
+            ..Skill::new(path.to_string(), url.to_string(), path.to_string())
+        };
+        let skills = vec![
+            installed("https://github.com/owner/repo", "skills/pdf"),
+            installed("https://github.com/owner/repo", "skills/pdf"),
+            installed("local", "/home/user/.claude/skills/mine"),
+            Skill::new("docx".to_string(), "https://github.com/owner/repo".to_string(), "skills/docx".to_string()),
+        ];
+
+        let mut list = SkillList::from_skills(Some("team".to_string()), None, &skills);
+        assert_eq!(list.skills.len(), 1);
+
+        let skills = vec![Skill {
+            local_path: Some("/skills/pdf".to_string()),
+            installed_commit_sha: Some("abc123".to_string()),
+            ..skills[0].clone()
+        }];
+        list.pin_hashes(&skills, |dir| Some(format!("hash:{}", dir.display())));
+        assert_eq!(list.expected_hash("https://github.com/Owner/Repo.git", "skills/pdf"), Some("hash:/skills/pdf"));
+        assert_eq!(list.expected_hash("https://github.com/owner/repo", "skills/docx"), None);
+
+        let reviews = HashMap::from([(skills[0].id.clone(), SkillReview::new(&skills[0], "alice", None))]);
+        list.attach_reviews(&skills, &reviews);
+        assert_eq!(list.skills[0].review.as_ref().map(|r| r.reviewer.as_str()), Some("alice"));
+
+        for format in [SkillListFormat::Json, SkillListFormat::Yaml] {
+            assert_eq!(SkillList::parse(&list.render(format).unwrap()).unwrap(), list);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use super::{Skill, LOCAL_REPOSITORY_URL};
+
+/// 单页技能数量上限
+pub const MAX_SKILL_PAGE_SIZE: usize = 500;
+
+/// 技能目录的排序方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillSort {
+    #[default]
+    Name,
+    /// 安全评分
+    SecurityScore,
+    /// 所在仓库最近一次从上游刷新的时间
+    UpstreamUpdated,
+    /// 安装时间
+    InstalledAt,
+    /// 所在仓库的星标数
+    Stars,
+}
+
+/// 技能目录查询（筛选、排序与分页）
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SkillQuery {
+    /// 按名称与描述搜索（不区分大小写），名称匹配的排在描述匹配之前
+    pub search: Option<String>,
+    /// 只返回该仓库所有者的技能
+    pub repository_owner: Option<String>,
+    /// 不返回已安装的技能
+    pub hide_installed: bool,
+    /// 返回本地导入的技能（默认只返回仓库中的技能）
+    pub include_local: bool,
+    pub sort: SkillSort,
+    /// 倒序；缺少排序值的技能始终排在最后
+    pub descending: bool,
+    pub offset: usize,
+    /// 每页数量，0 表示使用上限
+    pub limit: usize,
+}
+
+/// 查询结果中的一页技能
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillPage {
+    pub skills: Vec<Skill>,
+    /// 筛选后的技能总数（用于分页）
+    pub total: usize,
+    pub offset: usize,
+}
+
+/// 排序需要的仓库信息（按 repository_url）
+#[derive(Debug, Clone, Default)]
+pub struct SkillSortContext {
+    pub stars: HashMap<String, u64>,
+    pub upstream_updated: HashMap<String, DateTime<Utc>>,
+}
+
+impl SkillQuery {
+    /// 筛选、排序并分页；同值时按名称与 ID 排序，保证分页结果稳定
+    pub fn apply(&self, skills: Vec<Skill>, context: &SkillSortContext) -> SkillPage {
+        let search = self.search.as_deref().map(|s| s.trim().to_lowercase()).filter(|s| !s.is_empty());
+        let name_matches = |skill: &Skill| search.as_deref().is_none_or(|search| skill.name.to_lowercase().contains(search));
+
+        let mut skills: Vec<Skill> = skills
+            .into_iter()
+            .filter(|skill| self.include_local || skill.repository_owner.as_deref() != Some(LOCAL_REPOSITORY_URL))
+            .filter(|skill| self.repository_owner.as_ref().is_none_or(|owner| skill.repository_owner.as_ref() == Some(owner)))
+            .filter(|skill| !self.hide_installed || !skill.installed)
+            .filter(|skill| {
+                search.as_deref().is_none_or(|search| {
+                    name_matches(skill)
+                        || skill.description.as_deref().is_some_and(|d| d.to_lowercase().contains(search))
+                })
+            })
+            .collect();
+
+        skills.sort_by(|a, b| {
+            name_matches(b)
+                .cmp(&name_matches(a))
+                .then_with(|| self.compare(a, b, context))
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+
+        let total = skills.len();
+        let limit = if self.limit == 0 { MAX_SKILL_PAGE_SIZE } else { self.limit.min(MAX_SKILL_PAGE_SIZE) };
+        let skills = skills.into_iter().skip(self.offset).take(limit).collect();
+        SkillPage { skills, total, offset: self.offset }
+    }
+
+    fn compare(&self, a: &Skill, b: &Skill, context: &SkillSortContext) -> Ordering {
+        match self.sort {
+            SkillSort::Name => {
+                let ordering = a.name.to_lowercase().cmp(&b.name.to_lowercase());
+                if self.descending { ordering.reverse() } else { ordering }
+            }
+            SkillSort::SecurityScore => self.compare_optional(a.security_score, b.security_score),
+            SkillSort::UpstreamUpdated => self.compare_optional(
+                context.upstream_updated.get(&a.repository_url),
+                context.upstream_updated.get(&b.repository_url),
+            ),
+            SkillSort::InstalledAt => self.compare_optional(
+                a.installed_at.filter(|_| a.installed),
+                b.installed_at.filter(|_| b.installed),
+            ),
+            SkillSort::Stars => self.compare_optional(
+                context.stars.get(&a.repository_url),
+                context.stars.get(&b.repository_url),
+            ),
+        }
+    }
+
+    /// 比较可能缺失的排序值，缺失的排在最后
+    fn compare_optional<T: Ord>(&self, a: Option<T>, b: Option<T>) -> Ordering {
+        match (a, b) {
+            (Some(a), Some(b)) if self.descending => b.cmp(&a),
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skill_query_sort_and_paginate() {
+        let skill = |name: &str, repo: &str, score: Option<i32>| Skill {
+            id: format!("{}#{}", repo, name),
+            name: name.to_string(),
+            repository_url: repo.to_string(),
+            repository_owner: Some("owner".to_string()),
+            security_score: score,
+            ..Default::default()
+        };
+        let skills = vec![
+            skill("pdf", "https://github.com/owner/a", Some(70)),
+            skill("docx", "https://github.com/owner/b", Some(90)),
+            skill("xlsx", "https://github.com/owner/b", None),
+            Skill { repository_owner: Some(LOCAL_REPOSITORY_URL.to_string()), ..skill("mine", "local", Some(100)) },
+        ];
+        let context = SkillSortContext {
+            stars: HashMap::from([("https://github.com/owner/a".to_string(), 500), ("https://github.com/owner/b".to_string(), 20)]),
+            ..Default::default()
+        };
+        let names = |page: SkillPage| page.skills.into_iter().map(|s| s.name).collect::<Vec<_>>();
+
+        let query = SkillQuery::default();
+        assert_eq!(names(query.apply(skills.clone(), &context)), vec!["docx", "pdf", "xlsx"]);
+
+        // 缺少评分的技能无论正序倒序都排在最后
+        let query = SkillQuery { sort: SkillSort::SecurityScore, descending: true, ..Default::default() };
+        assert_eq!(names(query.apply(skills.clone(), &context)), vec!["docx", "pdf", "xlsx"]);
+        let query = SkillQuery { sort: SkillSort::SecurityScore, ..Default::default() };
+        assert_eq!(names(query.apply(skills.clone(), &context)), vec!["pdf", "docx", "xlsx"]);
+
+        let query = SkillQuery { sort: SkillSort::Stars, descending: true, offset: 1, limit: 1, ..Default::default() };
+        let page = query.apply(skills.clone(), &context);
+        assert_eq!(page.total, 3);
+        assert_eq!(names(page), vec!["docx"]);
+
+        let query = SkillQuery { search: Some("X".to_string()), include_local: true, ..Default::default() };
+        assert_eq!(names(query.apply(skills, &context)), vec!["docx", "xlsx"]);
+    }
+}
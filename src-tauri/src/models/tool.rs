@@ -98,3 +98,33 @@ pub fn get_all_supported_tools() -> Vec<AiTool> {
         AiTool::new("trae", "Trae", home.join(".trae"), "skills"),
     ]
 }
+
+/// 工具的常见别名（名称规范化后无法直接匹配的）
+const TOOL_ALIASES: &[(&str, &str)] = &[("copilot", "github-copilot"), ("vs-code", "vscode")];
+
+/// 将 SKILL.md 中声明的工具名称规范化为工具 ID
+/// 按 ID、显示名称（忽略大小写与空格、连字符等符号）及别名匹配，无法匹配时返回小写的原始名称
+pub fn normalize_tool_id(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    let compact = |value: &str| -> String {
+        value.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase()
+    };
+
+    if let Some((_, id)) = TOOL_ALIASES.iter().find(|(alias, _)| *alias == name) {
+        return id.to_string();
+    }
+
+    get_all_supported_tools()
+        .into_iter()
+        .find(|tool| tool.id == name || compact(&tool.name) == compact(&name))
+        .map(|tool| tool.id)
+        .unwrap_or(name)
+}
+
+/// 根据安装路径判断目标工具（路径位于工具目录下时）
+pub fn tool_for_path(path: &std::path::Path) -> Option<AiTool> {
+    get_all_supported_tools()
+        .into_iter()
+        .filter(|tool| path.starts_with(&tool.base_path))
+        .max_by_key(|tool| tool.base_path.components().count())
+}
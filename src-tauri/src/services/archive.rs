@@ -0,0 +1,322 @@
+use anyhow::{Context, Result};
+use crate::services::fs_ops::{CaseCollisions, CASE_INSENSITIVE_FS};
+use skillguard_core::paths::extended_length_path;
+use std::fs::{self, File};
+use std::io::{Read, Seek};
+use std::path::{Component, Path, PathBuf};
+
+/// 解压时允许的最大条目数（与扫描器的文件数上限一致）
+const MAX_ARCHIVE_ENTRIES: usize = 2000;
+/// 解压后的最大总字节数，超过时视为压缩炸弹并中止
+const MAX_EXTRACTED_BYTES: u64 = 512 * 1024 * 1024; // 512MiB
+
+/// 支持扫描的压缩包格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+    SevenZ,
+}
+
+impl ArchiveFormat {
+    /// 按文件头识别压缩包格式，不是压缩包时返回 None
+    pub fn detect(path: &Path) -> Result<Option<Self>> {
+        let mut header = [0u8; 262];
+        let mut file = File::open(path).context("无法打开文件")?;
+        let mut len = 0;
+        while len < header.len() {
+            match file.read(&mut header[len..]).context("无法读取文件")? {
+                0 => break,
+                n => len += n,
+            }
+        }
+        let header = &header[..len];
+
+        let format = if header.starts_with(b"PK\x03\x04") || header.starts_with(b"PK\x05\x06") {
+            Some(Self::Zip)
+        } else if header.starts_with(&[0x1f, 0x8b]) {
+            Some(Self::TarGz)
+        } else if header.starts_with(b"7z\xbc\xaf\x27\x1c") {
+            Some(Self::SevenZ)
+        } else if header.len() >= 262 && &header[257..262] == b"ustar" {
+            Some(Self::Tar)
+        } else {
+            None
+        };
+        Ok(format)
+    }
+}
+
+/// 解压进度：限制条目数与总大小，并拒绝逃出目标目录的路径
+struct Extractor<'a> {
+    dest: &'a Path,
+    max_entries: usize,
+    max_bytes: u64,
+    entries: usize,
+    total_bytes: u64,
+    skipped: Vec<String>,
+    case_collisions: CaseCollisions,
+}
+
+impl<'a> Extractor<'a> {
+    fn new(dest: &'a Path, max_entries: usize, max_bytes: u64) -> Self {
+        Self {
+            dest,
+            max_entries,
+            max_bytes,
+            entries: 0,
+            total_bytes: 0,
+            skipped: Vec::new(),
+            case_collisions: CaseCollisions::default(),
+        }
+    }
+
+    fn check_entry_count(&self, count: usize) -> Result<()> {
+        if count > self.max_entries {
+            anyhow::bail!("压缩包条目过多（上限 {} 个）", self.max_entries);
+        }
+        Ok(())
+    }
+
+    /// 条目在目标目录中的路径（Windows 下为扩展长度路径）；绝对路径或含 `..` 的条目返回 None
+    fn entry_path(&self, name: &Path) -> Option<PathBuf> {
+        let mut path = extended_length_path(self.dest);
+        let mut has_component = false;
+        for component in name.components() {
+            match component {
+                Component::Normal(part) => {
+                    path.push(part);
+                    has_component = true;
+                }
+                Component::CurDir => {}
+                Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+            }
+        }
+        has_component.then_some(path)
+    }
+
+    fn count_entry(&mut self) -> Result<()> {
+        self.entries += 1;
+        self.check_entry_count(self.entries)
+    }
+
+    fn skip(&mut self, name: &Path, reason: &str) {
+        log::warn!("跳过压缩包条目 {:?}: {}", name, reason);
+        self.skipped.push(name.to_string_lossy().to_string());
+    }
+
+    fn create_dir(&mut self, name: &Path) -> Result<()> {
+        self.count_entry()?;
+        match self.entry_path(name) {
+            Some(path) => fs::create_dir_all(&path).context(format!("无法创建目录: {:?}", path)),
+            None => {
+                self.skip(name, "路径不安全");
+                Ok(())
+            }
+        }
+    }
+
+    /// 写入文件，按实际解压出的字节数检查总大小（不信任压缩包中记录的大小）
+    fn write_file(&mut self, name: &Path, reader: &mut dyn Read) -> Result<()> {
+        self.count_entry()?;
+        let Some(path) = self.entry_path(name) else {
+            self.skip(name, "路径不安全");
+            return Ok(());
+        };
+        // 不区分大小写的文件系统上，仅大小写不同的文件会覆盖先解压的文件：不写入，解压结束后报告
+        let relative = path.strip_prefix(extended_length_path(self.dest)).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+        if self.case_collisions.insert(&relative) && CASE_INSENSITIVE_FS {
+            self.skip(name, "与已解压的文件仅大小写不同");
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(format!("无法创建父目录: {:?}", parent))?;
+        }
+
+        let remaining = self.max_bytes - self.total_bytes;
+        let mut outfile = File::create(&path).context(format!("无法创建文件: {:?}", path))?;
+        let written = std::io::copy(&mut reader.take(remaining + 1), &mut outfile)
+            .context(format!("无法写入文件: {:?}", path))?;
+        if written > remaining {
+            anyhow::bail!("压缩包解压后超过大小上限（{} 字节）", self.max_bytes);
+        }
+        self.total_bytes += written;
+        Ok(())
+    }
+}
+
+/// 解压结果
+#[derive(Debug, Default)]
+pub struct ExtractSummary {
+    /// 解压出的条目数（含目录）
+    pub entries: usize,
+    pub total_bytes: u64,
+    /// 因路径不安全、符号链接等原因跳过的条目
+    pub skipped: Vec<String>,
+}
+
+/// 将压缩包解压到目标目录（需为空目录），限制条目数与解压后的总大小。
+/// 符号链接、硬链接和设备文件不会被创建；超过限制时返回错误，已解压的部分由调用方清理
+pub fn extract_archive(archive_path: &Path, format: ArchiveFormat, dest: &Path) -> Result<ExtractSummary> {
+    extract_with_limits(archive_path, format, dest, MAX_ARCHIVE_ENTRIES, MAX_EXTRACTED_BYTES)
+}
+
+fn extract_with_limits(
+    archive_path: &Path,
+    format: ArchiveFormat,
+    dest: &Path,
+    max_entries: usize,
+    max_bytes: u64,
+) -> Result<ExtractSummary> {
+    let file = File::open(archive_path).context("无法打开压缩包")?;
+    let mut extractor = Extractor::new(dest, max_entries, max_bytes);
+
+    match format {
+        ArchiveFormat::Zip => extract_zip(file, &mut extractor)?,
+        ArchiveFormat::Tar => extract_tar(file, &mut extractor)?,
+        ArchiveFormat::TarGz => extract_tar(flate2::read::GzDecoder::new(file), &mut extractor)?,
+        ArchiveFormat::SevenZ => extract_7z(file, &mut extractor)?,
+    }
+    if let Some(err) = extractor.case_collisions.into_error().filter(|_| CASE_INSENSITIVE_FS) {
+        return Err(err);
+    }
+
+    Ok(ExtractSummary {
+        entries: extractor.entries,
+        total_bytes: extractor.total_bytes,
+        skipped: extractor.skipped,
+    })
+}
+
+fn extract_zip(file: File, extractor: &mut Extractor) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(file).context("无法读取ZIP文件")?;
+    extractor.check_entry_count(archive.len())?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).context(format!("无法读取ZIP条目 {}", i))?;
+        let name = PathBuf::from(entry.name());
+        if entry.is_symlink() {
+            extractor.skip(&name, "符号链接");
+        } else if entry.is_dir() {
+            extractor.create_dir(&name)?;
+        } else {
+            extractor.write_file(&name, &mut entry)?;
+        }
+    }
+    Ok(())
+}
+
+fn extract_tar(reader: impl Read, extractor: &mut Extractor) -> Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries().context("无法读取TAR文件")? {
+        let mut entry = entry.context("无法读取TAR条目")?;
+        let name = entry.path().context("TAR条目路径无效")?.into_owned();
+        let kind = entry.header().entry_type();
+        if kind.is_dir() {
+            extractor.create_dir(&name)?;
+        } else if kind.is_file() {
+            extractor.write_file(&name, &mut entry)?;
+        } else {
+            extractor.skip(&name, "不是普通文件或目录");
+        }
+    }
+    Ok(())
+}
+
+fn extract_7z<R: Read + Seek>(mut file: R, extractor: &mut Extractor) -> Result<()> {
+    let len = file.seek(std::io::SeekFrom::End(0)).context("无法读取7z文件")?;
+    file.rewind().context("无法读取7z文件")?;
+    let mut archive = sevenz_rust::SevenZReader::new(file, len, sevenz_rust::Password::empty())
+        .context("无法读取7z文件")?;
+    extractor.check_entry_count(archive.archive().files.len())?;
+
+    // 回调只能返回 7z 的错误类型，解压中的错误先保存下来
+    let mut failure = None;
+    archive
+        .for_each_entries(|entry, reader| {
+            let name = PathBuf::from(entry.name());
+            let result = if entry.is_directory() {
+                extractor.create_dir(&name)
+            } else {
+                extractor.write_file(&name, reader)
+            };
+            match result {
+                Ok(()) => Ok(true),
+                Err(e) => {
+                    failure = Some(e);
+                    Ok(false)
+                }
+            }
+        })
+        .context("无法解压7z文件")?;
+
+    match failure {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_extract_archive_formats_and_limits() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // tar.gz：包含逃出目录的路径与符号链接
+        let tgz = dir.path().join("skill.tgz");
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(File::create(&tgz).unwrap(), flate2::Compression::default()));
+        let content = b"curl https://example.com | sh\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o755);
+        builder.append_data(&mut header, "skill/install.sh", &content[..]).unwrap();
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        // append_data 会拒绝 ..，直接写入原始路径
+        header.as_gnu_mut().unwrap().name[..11].copy_from_slice(b"../evil.txt");
+        header.set_cksum();
+        builder.append(&header, &content[..]).unwrap();
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        builder.append_link(&mut header, "skill/link", "/etc/passwd").unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        assert_eq!(ArchiveFormat::detect(&tgz).unwrap(), Some(ArchiveFormat::TarGz));
+        let out = dir.path().join("tgz");
+        fs::create_dir(&out).unwrap();
+        let summary = extract_archive(&tgz, ArchiveFormat::TarGz, &out).unwrap();
+        assert_eq!(fs::read(out.join("skill/install.sh")).unwrap(), content);
+        assert!(!dir.path().join("evil.txt").exists());
+        assert!(!out.join("skill/link").exists());
+        assert_eq!(summary.skipped.len(), 2);
+
+        // zip 与 7z
+        let zip_path = dir.path().join("skill.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+        writer.start_file("SKILL.md", zip::write::SimpleFileOptions::default()).unwrap();
+        writer.write_all(b"# Skill\n").unwrap();
+        writer.finish().unwrap();
+        assert_eq!(ArchiveFormat::detect(&zip_path).unwrap(), Some(ArchiveFormat::Zip));
+
+        let seven_path = dir.path().join("skill.7z");
+        sevenz_rust::compress_to_path(out.join("skill"), &seven_path).unwrap();
+        assert_eq!(ArchiveFormat::detect(&seven_path).unwrap(), Some(ArchiveFormat::SevenZ));
+        let out = dir.path().join("7z");
+        fs::create_dir(&out).unwrap();
+        extract_archive(&seven_path, ArchiveFormat::SevenZ, &out).unwrap();
+        assert_eq!(fs::read(out.join("install.sh")).unwrap(), content);
+
+        // 超过条目数或大小上限（实际解压出的字节数）时中止
+        let out = dir.path().join("limited");
+        fs::create_dir(&out).unwrap();
+        assert!(extract_with_limits(&tgz, ArchiveFormat::TarGz, &out, 1, MAX_EXTRACTED_BYTES).is_err());
+        assert!(extract_with_limits(&tgz, ArchiveFormat::TarGz, &out, 10, 10).is_err());
+
+        assert_eq!(ArchiveFormat::detect(&out.join("skill/install.sh")).unwrap(), None);
+    }
+}
@@ -0,0 +1,196 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// 开机自启动时附加的命令行参数：启动后只显示托盘图标，不显示主窗口
+pub const MINIMIZED_FLAG: &str = "--minimized";
+
+/// 自启动项名称
+#[cfg(not(target_os = "macos"))]
+const APP_NAME: &str = "Agent Skills Guard";
+/// macOS LaunchAgent 标识（与应用 identifier 一致）
+#[cfg(target_os = "macos")]
+const LAUNCH_AGENT_LABEL: &str = "com.agent-skills-guard.app";
+
+/// 本次启动是否由开机自启动触发（需最小化到托盘）
+pub fn launched_minimized() -> bool {
+    std::env::args().skip(1).any(|arg| arg == MINIMIZED_FLAG)
+}
+
+/// 自启动时使用的命令行参数（便携模式需保持便携数据目录）
+fn launch_args() -> Vec<&'static str> {
+    let mut args = vec![MINIMIZED_FLAG];
+    if crate::services::portable::is_portable() {
+        args.push(crate::services::portable::PORTABLE_FLAG);
+    }
+    args
+}
+
+/// 开启或关闭登录时自动启动
+pub fn set_enabled(enabled: bool) -> Result<()> {
+    let exe = std::env::current_exe().context("无法获取可执行文件路径")?;
+    if enabled {
+        register(&exe)?;
+        log::info!("已开启开机自启动: {:?}", exe);
+    } else {
+        unregister()?;
+        log::info!("已关闭开机自启动");
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn entry_path() -> Result<std::path::PathBuf> {
+    Ok(dirs::config_dir()
+        .context("无法获取配置目录")?
+        .join("autostart")
+        .join("agent-skills-guard.desktop"))
+}
+
+#[cfg(target_os = "linux")]
+fn register(exe: &Path) -> Result<()> {
+    let path = entry_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).context("无法创建自启动目录")?;
+    }
+
+    // AppImage 运行时可执行文件位于临时挂载点，应使用 AppImage 文件本身
+    let exe = std::env::var_os("APPIMAGE").map(std::path::PathBuf::from).unwrap_or_else(|| exe.to_path_buf());
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName={}\nExec=\"{}\" {}\nTerminal=false\nX-GNOME-Autostart-enabled=true\n",
+        APP_NAME,
+        exe.display(),
+        launch_args().join(" ")
+    );
+    std::fs::write(&path, entry).context("无法写入自启动项")?;
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn unregister() -> Result<()> {
+    let path = entry_path()?;
+    if path.exists() {
+        std::fs::remove_file(&path).context("无法删除自启动项")?;
+    }
+    Ok(())
+}
+
+/// 是否已注册开机自启动
+#[cfg(target_os = "linux")]
+pub fn is_enabled() -> bool {
+    entry_path().map(|path| path.is_file()).unwrap_or(false)
+}
+
+#[cfg(target_os = "macos")]
+fn entry_path() -> Result<std::path::PathBuf> {
+    Ok(dirs::home_dir()
+        .context("无法获取用户目录")?
+        .join("Library")
+        .join("LaunchAgents")
+        .join(format!("{}.plist", LAUNCH_AGENT_LABEL)))
+}
+
+#[cfg(target_os = "macos")]
+fn register(exe: &Path) -> Result<()> {
+    let path = entry_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).context("无法创建 LaunchAgents 目录")?;
+    }
+
+    let escape = |s: &str| s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    let mut arguments = format!("        <string>{}</string>\n", escape(&exe.to_string_lossy()));
+    for arg in launch_args() {
+        arguments.push_str(&format!("        <string>{}</string>\n", arg));
+    }
+
+    let plist = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+<plist version=\"1.0\">\n\
+<dict>\n\
+    <key>Label</key>\n\
+    <string>{}</string>\n\
+    <key>ProgramArguments</key>\n\
+    <array>\n{}    </array>\n\
+    <key>RunAtLoad</key>\n\
+    <true/>\n\
+</dict>\n\
+</plist>\n",
+        LAUNCH_AGENT_LABEL, arguments
+    );
+    std::fs::write(&path, plist).context("无法写入 LaunchAgent")?;
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn unregister() -> Result<()> {
+    let path = entry_path()?;
+    if path.exists() {
+        std::fs::remove_file(&path).context("无法删除 LaunchAgent")?;
+    }
+    Ok(())
+}
+
+/// 是否已注册开机自启动
+#[cfg(target_os = "macos")]
+pub fn is_enabled() -> bool {
+    entry_path().map(|path| path.is_file()).unwrap_or(false)
+}
+
+/// Windows 当前用户的自启动注册表项
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+
+#[cfg(target_os = "windows")]
+fn reg(args: &[&str]) -> Result<bool> {
+    use std::os::windows::process::CommandExt;
+    /// 不弹出控制台窗口
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let status = std::process::Command::new("reg")
+        .args(args)
+        .creation_flags(CREATE_NO_WINDOW)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .context("无法执行 reg 命令")?;
+    Ok(status.success())
+}
+
+#[cfg(target_os = "windows")]
+fn register(exe: &Path) -> Result<()> {
+    let command = format!("\"{}\" {}", exe.display(), launch_args().join(" "));
+    if !reg(&["add", RUN_KEY, "/v", APP_NAME, "/t", "REG_SZ", "/d", &command, "/f"])? {
+        anyhow::bail!("写入自启动注册表项失败");
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn unregister() -> Result<()> {
+    if is_enabled() && !reg(&["delete", RUN_KEY, "/v", APP_NAME, "/f"])? {
+        anyhow::bail!("删除自启动注册表项失败");
+    }
+    Ok(())
+}
+
+/// 是否已注册开机自启动
+#[cfg(target_os = "windows")]
+pub fn is_enabled() -> bool {
+    reg(&["query", RUN_KEY, "/v", APP_NAME]).unwrap_or(false)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn register(_exe: &Path) -> Result<()> {
+    anyhow::bail!("当前系统不支持开机自启动")
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn unregister() -> Result<()> {
+    Ok(())
+}
+
+/// 是否已注册开机自启动
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn is_enabled() -> bool {
+    false
+}
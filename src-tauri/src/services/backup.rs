@@ -0,0 +1,186 @@
+use crate::services::Database;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// SQLite 是否报告数据库文件已损坏（或不是数据库文件）
+fn is_corruption(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<rusqlite::Error>(),
+            Some(rusqlite::Error::SqliteFailure(e, _))
+                if matches!(e.code, rusqlite::ErrorCode::DatabaseCorrupt | rusqlite::ErrorCode::NotADatabase)
+        )
+    })
+}
+
+/// 自动备份文件名前缀
+const AUTO_BACKUP_PREFIX: &str = "agent-skills-";
+/// 保留的自动备份数量
+const MAX_AUTO_BACKUPS: usize = 5;
+
+/// 数据库自动备份：启动时完整性检查通过后生成备份，检测到损坏时从最近的备份恢复
+pub struct DatabaseBackups {
+    dir: PathBuf,
+}
+
+impl DatabaseBackups {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// 自动备份列表（按时间从新到旧）
+    pub fn list(&self) -> Vec<PathBuf> {
+        let mut backups: Vec<PathBuf> = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.file_name()
+                        .map(|n| n.to_string_lossy())
+                        .is_some_and(|n| n.starts_with(AUTO_BACKUP_PREFIX) && n.ends_with(".db"))
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+
+        // 文件名包含时间戳，按名称倒序即为从新到旧
+        backups.sort();
+        backups.reverse();
+        backups
+    }
+
+    /// 最近一次的自动备份
+    pub fn latest(&self) -> Option<PathBuf> {
+        self.list().into_iter().next()
+    }
+
+    /// 生成自动备份并清理多余的旧备份
+    pub fn create(&self, db: &Database) -> Result<PathBuf> {
+        let path = self.dir.join(format!(
+            "{}{}.db",
+            AUTO_BACKUP_PREFIX,
+            Utc::now().format("%Y%m%d-%H%M%S%3f")
+        ));
+        db.backup_to(&path)?;
+
+        for old in self.list().into_iter().skip(MAX_AUTO_BACKUPS) {
+            if let Err(e) = fs::remove_file(&old) {
+                log::warn!("删除旧备份失败: {:?}, 错误: {}", old, e);
+            }
+        }
+
+        Ok(path)
+    }
+
+    /// 打开数据库并执行完整性检查
+    /// 数据库损坏（integrity_check 未通过，或 SQLite 报告文件损坏）时，将损坏文件改名保留，并从最近的自动备份恢复。
+    /// 密钥错误、文件被锁定、没有权限等其他错误直接返回，不改动数据库文件
+    pub fn open_with_recovery(&self, db_path: PathBuf, key: Option<&str>) -> Result<Database> {
+        // 已用该密钥成功打开过文件时才能确定密钥正确
+        let (failure, key_verified) = match Database::open(db_path.clone(), key) {
+            Ok(db) => match db.integrity_check() {
+                Ok(true) => {
+                    if let Err(e) = self.create(&db) {
+                        log::warn!("自动备份数据库失败: {}", e);
+                    }
+                    return Ok(db);
+                }
+                Ok(false) => (anyhow::anyhow!("数据库完整性检查未通过"), true),
+                Err(e) if is_corruption(&e) => (e, true),
+                Err(e) => return Err(e),
+            },
+            // 文件不存在时的打开失败不是损坏，直接返回错误
+            Err(e) if !db_path.exists() => return Err(e),
+            Err(e) if is_corruption(&e) => (e, false),
+            Err(e) => return Err(e),
+        };
+
+        let backup = self
+            .latest()
+            .ok_or_else(|| anyhow::anyhow!("数据库已损坏且没有可用的备份: {}", failure))?;
+
+        // 密钥错误或缺少密钥时 SQLite 报告的错误与文件损坏相同：
+        // 自动备份使用同一密钥加密，能用该密钥打开备份才说明密钥正确
+        if !key_verified && !Database::key_opens(&backup, key) {
+            return Err(failure.context("无法打开数据库，密钥可能不正确或缺失，未从备份恢复"));
+        }
+
+        log::error!("数据库已损坏: {}，尝试从备份恢复", failure);
+
+        Self::quarantine(&db_path)?;
+        fs::copy(&backup, &db_path).context(format!("无法从备份恢复数据库: {:?}", backup))?;
+        log::info!("已从备份恢复数据库: {:?}", backup);
+
+        Database::open(db_path, key)
+    }
+
+    /// 将损坏的数据库文件改名保留，便于事后排查
+    fn quarantine(db_path: &Path) -> Result<()> {
+        let corrupt_path = db_path.with_extension(format!(
+            "db.corrupt-{}",
+            Utc::now().format("%Y%m%d-%H%M%S")
+        ));
+        fs::rename(db_path, &corrupt_path)
+            .context(format!("无法移动损坏的数据库文件: {:?}", db_path))?;
+        log::warn!("损坏的数据库已保留为: {:?}", corrupt_path);
+
+        // 旧连接的日志文件与恢复后的数据库不匹配，一并删除
+        for suffix in ["-journal", "-wal", "-shm"] {
+            let sidecar = PathBuf::from(format!("{}{}", db_path.display(), suffix));
+            if sidecar.exists() {
+                let _ = fs::remove_file(&sidecar);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_recover_corrupted_database_from_backup() {
+        let dir = tempdir().expect("tempdir");
+        let db_path = dir.path().join("agent-skills.db");
+        let backups = DatabaseBackups::new(dir.path().join("backups"));
+
+        let db = backups.open_with_recovery(db_path.clone(), None).unwrap();
+        db.set_setting("probe", "value").unwrap();
+        backups.create(&db).unwrap();
+        drop(db);
+
+        fs::write(&db_path, b"definitely not a database file, just garbage bytes").unwrap();
+
+        let db = backups.open_with_recovery(db_path, None).unwrap();
+        assert_eq!(db.get_setting("probe").unwrap().as_deref(), Some("value"));
+    }
+
+    #[test]
+    fn test_wrong_key_does_not_restore_from_backup() {
+        let dir = tempdir().expect("tempdir");
+        let db_path = dir.path().join("agent-skills.db");
+        let backups = DatabaseBackups::new(dir.path().join("backups"));
+        let (key, wrong_key) = (Database::generate_key().unwrap(), Database::generate_key().unwrap());
+
+        let db = Database::open(db_path.clone(), None).unwrap();
+        db.rekey(None, Some(&key)).unwrap();
+        drop(db);
+        let db = backups.open_with_recovery(db_path.clone(), Some(&key)).unwrap();
+        db.set_setting("probe", "value").unwrap();
+        drop(db);
+
+        // 密钥错误与缺少密钥都不应把数据库当作损坏处理
+        assert!(backups.open_with_recovery(db_path.clone(), Some(&wrong_key)).is_err());
+        assert!(backups.open_with_recovery(db_path.clone(), None).is_err());
+        let files: Vec<_> = fs::read_dir(dir.path()).unwrap().filter_map(|e| e.ok()).map(|e| e.file_name()).collect();
+        assert!(!files.iter().any(|name| name.to_string_lossy().contains("corrupt")));
+
+        let db = backups.open_with_recovery(db_path, Some(&key)).unwrap();
+        assert_eq!(db.get_setting("probe").unwrap().as_deref(), Some("value"));
+    }
+}
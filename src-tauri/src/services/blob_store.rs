@@ -0,0 +1,366 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// 仓库缓存清单：记录缓存目录中每个文件（相对路径）对应的内容哈希
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheManifest {
+    pub commit_sha: String,
+    pub created_at: DateTime<Utc>,
+    /// 相对路径（统一使用 / 分隔符）-> SHA256
+    pub files: BTreeMap<String, String>,
+}
+
+/// 内容寻址存储（hash -> 文件）
+///
+/// 目录结构：
+/// - `objects/{hash[0..2]}/{hash}`：文件内容，按 SHA256 去重
+/// - `manifests/{namespace}/{owner}_{repo}.json`：每个仓库缓存的清单
+///
+/// 缓存目录中的文件会被替换为指向 blob 的硬链接，多个仓库/staging 副本中的相同文件只占用一份空间。
+pub struct BlobStore {
+    root: PathBuf,
+}
+
+impl BlobStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// 默认存储位置：当前配置档缓存目录下的 blobs（各配置档的清单互不干扰）
+    pub fn default_root() -> Option<PathBuf> {
+        crate::services::CachePaths::current().map(|paths| paths.blobs())
+    }
+
+    /// 根据缓存基础目录（repositories / staging）和仓库名生成清单键
+    pub fn manifest_key(cache_base_dir: &Path, owner: &str, repo: &str) -> String {
+        let namespace = cache_base_dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "default".to_string());
+        format!("{}/{}_{}", namespace, owner, repo)
+    }
+
+    fn objects_dir(&self) -> PathBuf {
+        self.root.join("objects")
+    }
+
+    fn manifests_dir(&self) -> PathBuf {
+        self.root.join("manifests")
+    }
+
+    fn blob_path(&self, hash: &str) -> PathBuf {
+        self.objects_dir().join(&hash[..2]).join(hash)
+    }
+
+    fn manifest_path(&self, key: &str) -> PathBuf {
+        self.manifests_dir().join(format!("{}.json", key))
+    }
+
+    /// 是否已存储该内容
+    pub fn contains(&self, hash: &str) -> bool {
+        hash.len() > 2 && self.blob_path(hash).is_file()
+    }
+
+    /// 流式计算文件的 SHA256
+    pub fn hash_file(path: &Path) -> Result<String> {
+        let mut file = File::open(path).context(format!("无法打开文件: {:?}", path))?;
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut file, &mut hasher).context(format!("无法读取文件: {:?}", path))?;
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    /// 将目录中的所有文件写入存储，并把原文件替换为指向 blob 的硬链接
+    /// 返回生成的清单（同时写入 manifests 目录）
+    pub fn ingest_directory(&self, dir: &Path, key: &str, commit_sha: &str) -> Result<CacheManifest> {
+        let mut files = BTreeMap::new();
+        let mut deduplicated = 0usize;
+
+        for entry in WalkDir::new(dir)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            let path = entry.path();
+            let relative = path
+                .strip_prefix(dir)
+                .context("无法计算相对路径")?
+                .to_string_lossy()
+                .replace('\\', "/");
+
+            let hash = Self::hash_file(path)?;
+            if self.store_file(path, &hash)? {
+                deduplicated += 1;
+            }
+            files.insert(relative, hash);
+        }
+
+        let manifest = CacheManifest {
+            commit_sha: commit_sha.to_string(),
+            created_at: Utc::now(),
+            files,
+        };
+        self.save_manifest(key, &manifest)?;
+
+        log::info!(
+            "内容寻址存储完成: {} 个文件，其中 {} 个复用已有 blob",
+            manifest.files.len(),
+            deduplicated
+        );
+
+        Ok(manifest)
+    }
+
+    /// 存储单个文件，返回该内容此前是否已存在
+    fn store_file(&self, path: &Path, hash: &str) -> Result<bool> {
+        let blob_path = self.blob_path(hash);
+        let existed = blob_path.is_file();
+
+        if !existed {
+            if let Some(parent) = blob_path.parent() {
+                fs::create_dir_all(parent).context(format!("无法创建 blob 目录: {:?}", parent))?;
+            }
+            // 优先移动（同一文件系统下无需复制），失败时复制
+            if fs::rename(path, &blob_path).is_err() {
+                fs::copy(path, &blob_path).context(format!("无法写入 blob: {:?}", blob_path))?;
+            }
+        } else if !Self::same_permissions(path, &blob_path) {
+            // 内容相同但权限不同（如脚本的可执行位）：硬链接会共享权限，保留原文件不去重
+            return Ok(false);
+        } else {
+            let _ = fs::remove_file(path);
+        }
+
+        // 用硬链接替换原文件；不支持硬链接的文件系统上退化为复制
+        if !path.exists() {
+            if let Err(e) = fs::hard_link(&blob_path, path) {
+                log::debug!("创建硬链接失败，改用复制: {:?}, 错误: {}", path, e);
+                fs::copy(&blob_path, path).context(format!("无法恢复缓存文件: {:?}", path))?;
+            }
+        }
+
+        Ok(existed)
+    }
+
+    fn same_permissions(a: &Path, b: &Path) -> bool {
+        match (fs::metadata(a), fs::metadata(b)) {
+            (Ok(a), Ok(b)) => a.permissions() == b.permissions(),
+            _ => false,
+        }
+    }
+
+    /// 读取清单
+    pub fn load_manifest(&self, key: &str) -> Result<Option<CacheManifest>> {
+        let path = self.manifest_path(key);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path).context(format!("无法读取清单: {:?}", path))?;
+        let manifest = serde_json::from_str(&content).context(format!("解析清单失败: {:?}", path))?;
+        Ok(Some(manifest))
+    }
+
+    fn save_manifest(&self, key: &str, manifest: &CacheManifest) -> Result<()> {
+        let path = self.manifest_path(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(format!("无法创建清单目录: {:?}", parent))?;
+        }
+
+        let content = serde_json::to_string_pretty(manifest)?;
+        fs::write(&path, content).context(format!("无法写入清单: {:?}", path))?;
+        Ok(())
+    }
+
+    /// 重命名清单（staging 提升为仓库缓存时使用）
+    pub fn rename_manifest(&self, from_key: &str, to_key: &str) -> Result<()> {
+        let from = self.manifest_path(from_key);
+        if !from.exists() {
+            return Ok(());
+        }
+
+        let to = self.manifest_path(to_key);
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent).context(format!("无法创建清单目录: {:?}", parent))?;
+        }
+        fs::rename(&from, &to).context(format!("无法重命名清单: {:?} -> {:?}", from, to))?;
+        Ok(())
+    }
+
+    /// 删除清单（blob 由 collect_garbage 统一回收）
+    pub fn remove_manifest(&self, key: &str) -> Result<()> {
+        let path = self.manifest_path(key);
+        if path.exists() {
+            fs::remove_file(&path).context(format!("无法删除清单: {:?}", path))?;
+        }
+        Ok(())
+    }
+
+    /// 按清单校验目录完整性，返回缺失或内容不一致的文件列表
+    /// 没有清单时返回 None（旧版本缓存，无法校验）
+    pub fn verify_directory(&self, dir: &Path, key: &str) -> Result<Option<Vec<String>>> {
+        let manifest = match self.load_manifest(key)? {
+            Some(m) => m,
+            None => return Ok(None),
+        };
+
+        let mut mismatched = Vec::new();
+        for (relative, expected_hash) in &manifest.files {
+            let path = dir.join(relative);
+            match Self::hash_file(&path) {
+                Ok(hash) if &hash == expected_hash => {}
+                _ => mismatched.push(relative.clone()),
+            }
+        }
+
+        Ok(Some(mismatched))
+    }
+
+    /// 回收不再被任何清单引用的 blob
+    /// 返回值：(删除的 blob 数量, 释放的字节数)
+    pub fn collect_garbage(&self) -> Result<(usize, u64)> {
+        let mut referenced = HashSet::new();
+
+        let manifests_dir = self.manifests_dir();
+        if manifests_dir.exists() {
+            for entry in WalkDir::new(&manifests_dir).into_iter().filter_map(|e| e.ok()) {
+                if !entry.file_type().is_file() {
+                    continue;
+                }
+                let content = match fs::read_to_string(entry.path()) {
+                    Ok(c) => c,
+                    Err(e) => {
+                        log::warn!("读取清单失败，跳过: {:?}, 错误: {}", entry.path(), e);
+                        continue;
+                    }
+                };
+                match serde_json::from_str::<CacheManifest>(&content) {
+                    Ok(manifest) => referenced.extend(manifest.files.into_values()),
+                    Err(e) => log::warn!("解析清单失败，跳过: {:?}, 错误: {}", entry.path(), e),
+                }
+            }
+        }
+
+        let mut removed = 0usize;
+        let mut freed: u64 = 0;
+
+        let objects_dir = self.objects_dir();
+        if !objects_dir.exists() {
+            return Ok((0, 0));
+        }
+
+        for entry in WalkDir::new(&objects_dir).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let hash = entry.file_name().to_string_lossy().to_string();
+            if referenced.contains(&hash) {
+                continue;
+            }
+
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            match fs::remove_file(entry.path()) {
+                Ok(()) => {
+                    removed += 1;
+                    freed += size;
+                }
+                Err(e) => log::warn!("删除 blob 失败: {:?}, 错误: {}", entry.path(), e),
+            }
+        }
+
+        log::info!("blob 回收完成: 删除 {} 个, 释放 {} 字节", removed, freed);
+        Ok((removed, freed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_ingest_deduplicates_identical_files() {
+        let root = tempdir().expect("tempdir");
+        let store = BlobStore::new(root.path().join("blobs"));
+
+        let repo_a = root.path().join("a");
+        let repo_b = root.path().join("b");
+        fs::create_dir_all(repo_a.join("skill")).unwrap();
+        fs::create_dir_all(&repo_b).unwrap();
+        fs::write(repo_a.join("skill").join("SKILL.md"), "same content").unwrap();
+        fs::write(repo_b.join("SKILL.md"), "same content").unwrap();
+
+        let manifest_a = store.ingest_directory(&repo_a, "repositories/a", "abc1234").unwrap();
+        let manifest_b = store.ingest_directory(&repo_b, "repositories/b", "def5678").unwrap();
+
+        let hash = &manifest_a.files["skill/SKILL.md"];
+        assert_eq!(hash, &manifest_b.files["SKILL.md"]);
+        assert!(store.contains(hash));
+        assert_eq!(fs::read_to_string(repo_b.join("SKILL.md")).unwrap(), "same content");
+
+        let blob_count = WalkDir::new(store.objects_dir())
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .count();
+        assert_eq!(blob_count, 1, "Identical files should share one blob");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ingest_keeps_executable_bit() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = tempdir().expect("tempdir");
+        let store = BlobStore::new(root.path().join("blobs"));
+
+        let repo_a = root.path().join("a");
+        let repo_b = root.path().join("b");
+        fs::create_dir_all(&repo_a).unwrap();
+        fs::create_dir_all(&repo_b).unwrap();
+        fs::write(repo_a.join("run.sh"), "echo ok").unwrap();
+        fs::write(repo_b.join("run.sh"), "echo ok").unwrap();
+        fs::set_permissions(repo_b.join("run.sh"), fs::Permissions::from_mode(0o755)).unwrap();
+
+        store.ingest_directory(&repo_a, "repositories/a", "abc1234").unwrap();
+        store.ingest_directory(&repo_b, "repositories/b", "def5678").unwrap();
+
+        let mode = |path: PathBuf| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(repo_b.join("run.sh")), 0o755);
+        assert_ne!(mode(repo_a.join("run.sh")), 0o755);
+    }
+
+    #[test]
+    fn test_verify_and_garbage_collect() {
+        let root = tempdir().expect("tempdir");
+        let store = BlobStore::new(root.path().join("blobs"));
+
+        let repo = root.path().join("repo");
+        fs::create_dir_all(&repo).unwrap();
+        fs::write(repo.join("SKILL.md"), "original").unwrap();
+
+        store.ingest_directory(&repo, "repositories/repo", "abc1234").unwrap();
+        assert_eq!(store.verify_directory(&repo, "repositories/repo").unwrap(), Some(vec![]));
+
+        fs::remove_file(repo.join("SKILL.md")).unwrap();
+        fs::write(repo.join("SKILL.md"), "tampered").unwrap();
+        assert_eq!(
+            store.verify_directory(&repo, "repositories/repo").unwrap(),
+            Some(vec!["SKILL.md".to_string()])
+        );
+        assert_eq!(store.verify_directory(&repo, "repositories/missing").unwrap(), None);
+
+        store.remove_manifest("repositories/repo").unwrap();
+        let (removed, _) = store.collect_garbage().unwrap();
+        assert_eq!(removed, 1);
+    }
+}
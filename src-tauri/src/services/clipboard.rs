@@ -0,0 +1,38 @@
+use crate::models::GitHubUrl;
+use std::sync::Mutex;
+
+/// 剪贴板 GitHub 地址识别：同一个地址只提示一次。
+///
+/// 只在内存中保留最近一次提示的地址用于去重，不保存剪贴板内容或历史
+#[derive(Debug, Default)]
+pub struct ClipboardWatcher {
+    last_offered: Mutex<Option<GitHubUrl>>,
+}
+
+impl ClipboardWatcher {
+    /// 剪贴板文本为尚未提示过的 GitHub 仓库/技能地址时返回该地址
+    pub fn check(&self, text: &str) -> Option<GitHubUrl> {
+        let url = GitHubUrl::parse(text)?;
+        let mut last_offered = self.last_offered.lock().unwrap_or_else(|e| e.into_inner());
+        if last_offered.as_ref() == Some(&url) {
+            return None;
+        }
+        *last_offered = Some(url.clone());
+        Some(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clipboard_watcher_offers_each_url_once() {
+        let watcher = ClipboardWatcher::default();
+        assert!(watcher.check("hello world").is_none());
+        assert!(watcher.check("https://github.com/owner/repo").is_some());
+        assert!(watcher.check("https://github.com/Owner/repo/").is_none());
+        assert!(watcher.check("https://github.com/owner/repo/tree/main/skills/pdf").is_some());
+        assert!(watcher.check("https://github.com/owner/repo").is_some());
+    }
+}
@@ -0,0 +1,104 @@
+use crate::services::Database;
+use anyhow::{Context, Result};
+
+/// 系统钥匙串中使用的服务名
+const SERVICE_NAME: &str = "agent-skills-guard";
+
+/// 代理密码在钥匙串中的账户名
+pub const PROXY_PASSWORD_ACCOUNT: &str = "proxy-password";
+/// GitHub Token 在钥匙串中的账户名
+pub const GITHUB_TOKEN_ACCOUNT: &str = "github-token";
+/// 确认安装高风险技能时使用的管理口令在钥匙串中的账户名
+pub const INSTALL_PASSPHRASE_ACCOUNT: &str = "install-passphrase";
+/// 本地数据库加密密钥在钥匙串中的账户名
+pub const DATABASE_KEY_ACCOUNT: &str = "database-key";
+
+/// 指定配置档的数据库加密密钥账户名（默认配置档沿用旧账户名）
+pub fn database_key_account(profile: &str) -> String {
+    if profile == crate::services::profile::DEFAULT_PROFILE {
+        DATABASE_KEY_ACCOUNT.to_string()
+    } else {
+        format!("{}:{}", DATABASE_KEY_ACCOUNT, profile)
+    }
+}
+
+/// 凭据存储：使用系统钥匙串（Windows Credential Manager / macOS Keychain / Secret Service）
+/// 保存代理密码和 GitHub Token，避免以明文形式写入数据库
+pub struct CredentialStore;
+
+impl CredentialStore {
+    fn entry(account: &str) -> Result<keyring::Entry> {
+        keyring::Entry::new(SERVICE_NAME, account)
+            .context(format!("无法访问系统钥匙串: {}", account))
+    }
+
+    /// 读取凭据，不存在时返回 None
+    pub fn get(account: &str) -> Result<Option<String>> {
+        match Self::entry(account)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(anyhow::anyhow!("读取系统钥匙串失败: {}", e)),
+        }
+    }
+
+    /// 保存凭据
+    pub fn set(account: &str, secret: &str) -> Result<()> {
+        Self::entry(account)?
+            .set_password(secret)
+            .map_err(|e| anyhow::anyhow!("写入系统钥匙串失败: {}", e))
+    }
+
+    /// 删除凭据（不存在时视为成功）
+    pub fn delete(account: &str) -> Result<()> {
+        match Self::entry(account)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(anyhow::anyhow!("删除系统钥匙串凭据失败: {}", e)),
+        }
+    }
+
+    /// 保存或删除凭据（secret 为空时删除）
+    pub fn store(account: &str, secret: Option<&str>) -> Result<()> {
+        match secret.filter(|s| !s.is_empty()) {
+            Some(secret) => Self::set(account, secret),
+            None => Self::delete(account),
+        }
+    }
+
+    /// 将 app_settings 中以明文保存的凭据迁移到钥匙串
+    /// 迁移成功后删除明文记录；钥匙串不可用时保留明文，避免丢失凭据
+    /// 返回当前有效的凭据
+    pub fn migrate_plaintext_setting(db: &Database, setting_key: &str, account: &str) -> Option<String> {
+        let plaintext = match db.get_setting(setting_key) {
+            Ok(Some(value)) if !value.trim().is_empty() => value.trim().to_string(),
+            Ok(Some(_)) => {
+                let _ = db.delete_setting(setting_key);
+                return Self::get_or_warn(account);
+            }
+            _ => return Self::get_or_warn(account),
+        };
+
+        match Self::set(account, &plaintext) {
+            Ok(()) => {
+                if let Err(e) = db.delete_setting(setting_key) {
+                    log::warn!("删除明文凭据失败: {}, 错误: {}", setting_key, e);
+                } else {
+                    log::info!("已将明文凭据 {} 迁移到系统钥匙串", setting_key);
+                }
+            }
+            Err(e) => log::warn!("迁移凭据 {} 到系统钥匙串失败，暂时保留明文: {}", setting_key, e),
+        }
+
+        Some(plaintext)
+    }
+
+    /// 读取凭据，失败时记录警告并返回 None
+    pub fn get_or_warn(account: &str) -> Option<String> {
+        match Self::get(account) {
+            Ok(secret) => secret,
+            Err(e) => {
+                log::warn!("读取凭据 {} 失败: {}", account, e);
+                None
+            }
+        }
+    }
+}
@@ -1,32 +1,247 @@
-use crate::models::{Repository, Skill};
+use crate::models::{
+    CommitSignatureStatus, FalsePositiveReport, HardTriggerRecord, OperationKind, OperationStep, PendingOperation, Repository,
+    LevelThresholds, RepositoryStats, RuleStatistics, ScoreHistoryPoint, SecurityReport, Skill, SkillEvent, SkillReview, SkillEventKind, SourceType, SuspiciousUpdate,
+};
 use anyhow::{Result, Context};
 use rusqlite::{Connection, params, OptionalExtension};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
+/// 每个 skill 保留的硬触发记录数量
+const MAX_HARD_TRIGGER_HISTORY: usize = 20;
+/// 每个 skill 保留的评分历史数量
+const MAX_SCORE_HISTORY: usize = 50;
+
+/// repositories 表的查询列（与 repository_from_row 的顺序一致）
+const REPOSITORY_COLUMNS: &str = "id, url, name, description, enabled, scan_subdirs, added_at, last_scanned, \
+    cache_path, cached_at, cached_commit_sha, require_signed_commits, commit_signature, \
+    scan_include_patterns, scan_exclude_patterns, scan_max_depth, skip_auto_refresh, last_error, last_error_at";
+
+/// 未加密 SQLite 数据库文件头
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
 pub struct Database {
     conn: Mutex<Connection>,
+    path: PathBuf,
+    /// 当前数据库密钥（未加密时为 None），备份/恢复时用于打开对应的数据库文件
+    key: Mutex<Option<String>>,
 }
 
 impl Database {
     /// 创建或打开数据库
     pub fn new(db_path: PathBuf) -> Result<Self> {
+        Self::open(db_path, None)
+    }
+
+    /// 创建或打开数据库，key 不为空时按 SQLCipher 加密数据库打开
+    pub fn open(db_path: PathBuf, key: Option<&str>) -> Result<Self> {
         // 确保父目录存在
         if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent)?;
         }
 
-        let conn = Connection::open(db_path)
-            .context("Failed to open database")?;
+        let conn = Self::open_connection(&db_path, key)?;
 
         let db = Self {
             conn: Mutex::new(conn),
+            path: db_path,
+            key: Mutex::new(key.map(|k| k.to_string())),
         };
 
         db.initialize_schema()?;
         Ok(db)
     }
 
+    fn open_connection(db_path: &Path, key: Option<&str>) -> Result<Connection> {
+        let conn = Connection::open(db_path)
+            .context("Failed to open database")?;
+
+        if let Some(key) = key {
+            conn.pragma_update(None, "key", format!("x'{}'", key))
+                .context("设置数据库密钥失败")?;
+            // 密钥错误时首次读取才会失败，这里提前验证
+            conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| row.get::<_, i64>(0))
+                .context("数据库密钥无效或数据库已损坏")?;
+        }
+
+        Ok(conn)
+    }
+
+    /// 判断数据库文件是否已加密（文件存在且不是明文 SQLite 文件头）
+    pub fn is_encrypted_file(db_path: &Path) -> bool {
+        use std::io::Read;
+
+        let mut header = [0u8; 16];
+        match std::fs::File::open(db_path).and_then(|mut f| f.read_exact(&mut header)) {
+            Ok(()) => &header != SQLITE_HEADER,
+            Err(_) => false,
+        }
+    }
+
+    /// 生成新的数据库密钥（系统安全随机数生成器产生的 32 字节，十六进制编码）
+    pub fn generate_key() -> Result<String> {
+        let mut key = [0u8; 32];
+        getrandom::getrandom(&mut key).map_err(|e| anyhow::anyhow!("无法生成数据库密钥: {}", e))?;
+        Ok(hex::encode(key))
+    }
+
+    /// 已存在的数据库文件能否用指定密钥打开并读取（用于区分密钥错误与数据库损坏）
+    pub fn key_opens(db_path: &Path, key: Option<&str>) -> bool {
+        db_path.is_file()
+            && Self::open_connection(db_path, key)
+                .and_then(|conn| Ok(conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| row.get::<_, i64>(0))?))
+                .is_ok()
+    }
+
+    /// 当前数据库是否已加密
+    pub fn is_encrypted(&self) -> bool {
+        Self::is_encrypted_file(&self.path)
+    }
+
+    /// 使用新密钥导出数据库并替换原文件
+    /// new_key 为 None 时导出为明文数据库（关闭加密）
+    pub fn rekey(&self, current_key: Option<&str>, new_key: Option<&str>) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+
+        let export_path = self.path.with_extension("db.export");
+        if export_path.exists() {
+            std::fs::remove_file(&export_path)?;
+        }
+
+        let export_key = new_key.map(|k| format!("x'{}'", k)).unwrap_or_default();
+        conn.execute(
+            "ATTACH DATABASE ?1 AS export KEY ?2",
+            params![export_path.to_string_lossy(), export_key],
+        ).context("创建导出数据库失败")?;
+
+        let exported = conn
+            .query_row("SELECT sqlcipher_export('export')", [], |_| Ok(()))
+            .context("导出数据库失败");
+        conn.execute("DETACH DATABASE export", [])?;
+
+        if let Err(e) = exported {
+            let _ = std::fs::remove_file(&export_path);
+            return Err(e);
+        }
+
+        // 替换前确认导出的文件能用新密钥打开，避免用无法打开的文件覆盖原数据库
+        if let Err(e) = Self::open_connection(&export_path, new_key) {
+            let _ = std::fs::remove_file(&export_path);
+            return Err(e.context("导出的数据库无法使用新密钥打开，已保留原数据库"));
+        }
+
+        // 关闭当前连接后替换文件（Windows 下打开的文件无法被覆盖）；
+        // 原文件先改名保留，新文件打开成功后才删除，失败时移回原文件
+        *conn = Connection::open_in_memory()?;
+        let previous_path = self.path.with_extension("db.previous");
+        let _ = std::fs::remove_file(&previous_path);
+
+        let replaced = std::fs::rename(&self.path, &previous_path)
+            .context("无法保留原数据库文件")
+            .and_then(|()| {
+                std::fs::rename(&export_path, &self.path).context("替换数据库文件失败")?;
+                Self::open_connection(&self.path, new_key)
+            });
+
+        match replaced {
+            Ok(new_conn) => {
+                *conn = new_conn;
+                *self.key.lock().unwrap() = new_key.map(|k| k.to_string());
+                if let Err(e) = std::fs::remove_file(&previous_path) {
+                    log::warn!("删除原数据库文件失败: {:?}, 错误: {}", previous_path, e);
+                }
+                log::info!("数据库已{}", if new_key.is_some() { "加密" } else { "解密" });
+                Ok(())
+            }
+            Err(e) => {
+                // 恢复原文件并使用原密钥重新打开
+                if previous_path.exists() {
+                    if let Err(restore_err) = std::fs::rename(&previous_path, &self.path) {
+                        log::error!("恢复原数据库文件失败: {:?}, 错误: {}", previous_path, restore_err);
+                    }
+                }
+                let _ = std::fs::remove_file(&export_path);
+                *conn = Self::open_connection(&self.path, current_key)?;
+                Err(e)
+            }
+        }
+    }
+
+    /// 执行 PRAGMA integrity_check，数据库完好时返回 true
+    pub fn integrity_check(&self) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+        let results = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+
+        let ok = results.len() == 1 && results[0] == "ok";
+        if !ok {
+            log::error!("数据库完整性检查失败: {:?}", results);
+        }
+        Ok(ok)
+    }
+
+    /// 使用 SQLite 备份 API 将数据库备份到指定文件（加密数据库的备份使用相同密钥加密）
+    pub fn backup_to(&self, dest: &Path) -> Result<()> {
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        if dest.exists() {
+            std::fs::remove_file(dest).context(format!("无法覆盖备份文件: {:?}", dest))?;
+        }
+
+        let key = self.key.lock().unwrap().clone();
+        let mut dest_conn = Self::open_connection(dest, key.as_deref())?;
+
+        let conn = self.conn.lock().unwrap();
+        let backup = rusqlite::backup::Backup::new(&conn, &mut dest_conn)
+            .context("创建数据库备份失败")?;
+        backup
+            .run_to_completion(256, std::time::Duration::from_millis(10), None)
+            .context("数据库备份失败")?;
+
+        log::info!("数据库已备份到: {:?}", dest);
+        Ok(())
+    }
+
+    /// 从备份文件恢复数据库（备份需与当前数据库的加密状态一致）
+    pub fn restore_from(&self, src: &Path) -> Result<()> {
+        if !src.is_file() {
+            anyhow::bail!("备份文件不存在: {:?}", src);
+        }
+
+        let key = self.key.lock().unwrap().clone();
+        if key.is_some() != Self::is_encrypted_file(src) {
+            anyhow::bail!("备份文件与当前数据库的加密状态不一致，无法恢复");
+        }
+
+        let src_conn = Self::open_connection(src, key.as_deref())
+            .context("无法打开备份文件")?;
+        let check: String = src_conn
+            .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+            .context("备份文件校验失败")?;
+        if check != "ok" {
+            anyhow::bail!("备份文件已损坏: {}", check);
+        }
+
+        {
+            let mut conn = self.conn.lock().unwrap();
+            let backup = rusqlite::backup::Backup::new(&src_conn, &mut conn)
+                .context("恢复数据库失败")?;
+            backup
+                .run_to_completion(256, std::time::Duration::from_millis(10), None)
+                .context("恢复数据库失败")?;
+        }
+
+        // 旧版本的备份可能缺少新增的列，恢复后重新执行迁移
+        self.initialize_schema()?;
+
+        log::info!("已从备份恢复数据库: {:?}", src);
+        Ok(())
+    }
+
     /// 初始化数据库架构
     fn initialize_schema(&self) -> Result<()> {
         let conn = self.conn.lock().unwrap();
@@ -95,6 +310,26 @@ impl Database {
         self.migrate_add_security_enhancement_fields()?;
         self.migrate_add_local_paths()?;
         self.migrate_add_installed_commit_sha()?;
+        self.migrate_add_scan_reports()?;
+        self.migrate_add_hard_trigger_history()?;
+        self.migrate_add_rule_statistics()?;
+        self.migrate_add_score_history()?;
+        self.migrate_add_commit_signature()?;
+        self.migrate_add_compatible_tools()?;
+        self.migrate_add_scan_caches()?;
+        self.migrate_add_pending_operations()?;
+        self.migrate_add_repository_scan_paths()?;
+        self.migrate_add_repository_auto_refresh()?;
+        self.migrate_add_suspicious_updates()?;
+        self.migrate_add_skill_events()?;
+        self.migrate_add_skill_source_removed()?;
+        self.migrate_add_repository_last_error()?;
+        self.migrate_add_provenance()?;
+        self.migrate_add_skill_source_type()?;
+        self.migrate_add_skill_origin_tool()?;
+        self.migrate_add_skill_tags()?;
+        self.migrate_add_skill_reviews()?;
+        self.migrate_add_skill_install_targets()?;
 
         // 初始化默认仓库（忽略返回值，因为在这个阶段我们只是初始化数据库）
         let _ = self.initialize_default_repositories()?;
@@ -144,8 +379,10 @@ impl Database {
 
         conn.execute(
             "INSERT OR REPLACE INTO repositories
-            (id, url, name, description, enabled, scan_subdirs, added_at, last_scanned, cache_path, cached_at, cached_commit_sha)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            (id, url, name, description, enabled, scan_subdirs, added_at, last_scanned, cache_path, cached_at, cached_commit_sha,
+             require_signed_commits, commit_signature, scan_include_patterns, scan_exclude_patterns, scan_max_depth,
+             skip_auto_refresh, last_error, last_error_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)",
             params![
                 repo.id,
                 repo.url,
@@ -158,6 +395,14 @@ impl Database {
                 repo.cache_path,
                 repo.cached_at.as_ref().map(|d| d.to_rfc3339()),
                 repo.cached_commit_sha,
+                repo.require_signed_commits as i32,
+                repo.commit_signature.as_ref().map(serde_json::to_string).transpose()?,
+                serde_json::to_string(&repo.scan_include_patterns)?,
+                serde_json::to_string(&repo.scan_exclude_patterns)?,
+                repo.scan_max_depth,
+                repo.skip_auto_refresh as i32,
+                repo.last_error,
+                repo.last_error_at.as_ref().map(|d| d.to_rfc3339()),
             ],
         )?;
 
@@ -168,28 +413,10 @@ impl Database {
     pub fn get_repositories(&self) -> Result<Vec<Repository>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, url, name, description, enabled, scan_subdirs, added_at, last_scanned, cache_path, cached_at, cached_commit_sha
-             FROM repositories
-             ORDER BY added_at DESC"
+            &format!("SELECT {} FROM repositories ORDER BY added_at DESC", REPOSITORY_COLUMNS)
         )?;
 
-        let repos = stmt.query_map([], |row| {
-            Ok(Repository {
-                id: row.get(0)?,
-                url: row.get(1)?,
-                name: row.get(2)?,
-                description: row.get(3)?,
-                enabled: row.get::<_, i32>(4)? != 0,
-                scan_subdirs: row.get::<_, i32>(5)? != 0,
-                added_at: row.get::<_, String>(6)?.parse().unwrap_or_else(|_| chrono::Utc::now()),
-                last_scanned: row.get::<_, Option<String>>(7)?
-                    .and_then(|s| s.parse().ok()),
-                cache_path: row.get(8)?,
-                cached_at: row.get::<_, Option<String>>(9)?
-                    .and_then(|s| s.parse().ok()),
-                cached_commit_sha: row.get(10)?,
-            })
-        })?
+        let repos = stmt.query_map([], repository_from_row)?
         .collect::<Result<Vec<_>, _>>()?;
 
         Ok(repos)
@@ -205,11 +432,16 @@ impl Database {
         let local_paths_json = skill.local_paths.as_ref()
             .map(|paths| serde_json::to_string(paths).unwrap());
 
+        let compatible_tools_json = skill.compatible_tools.as_ref()
+            .map(|tools| serde_json::to_string(tools).unwrap());
+
         conn.execute(
             "INSERT OR REPLACE INTO skills
             (id, name, description, repository_url, repository_owner, file_path, version, author,
-             installed, installed_at, local_path, local_paths, checksum, security_score, security_issues, security_level, scanned_at, installed_commit_sha)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
+             installed, installed_at, local_path, local_paths, checksum, security_score, security_issues, security_level, scanned_at, installed_commit_sha, compatible_tools,
+             source_removed, first_seen_at, source_type, origin_tool)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20,
+                    COALESCE((SELECT first_seen_at FROM skills WHERE id = ?1), ?21), ?22, ?23)",
             params![
                 skill.id,
                 skill.name,
@@ -229,6 +461,11 @@ impl Database {
                 skill.security_level,
                 skill.scanned_at.as_ref().map(|d| d.to_rfc3339()),
                 skill.installed_commit_sha,
+                compatible_tools_json,
+                skill.source_removed as i32,
+                skill.first_seen_at.unwrap_or_else(chrono::Utc::now).to_rfc3339(),
+                skill.source_type.as_str(),
+                skill.origin_tool,
             ],
         )?;
 
@@ -240,7 +477,8 @@ impl Database {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
             "SELECT id, name, description, repository_url, repository_owner, file_path, version, author,
-                    installed, installed_at, local_path, local_paths, checksum, security_score, security_issues, security_level, scanned_at, installed_commit_sha
+                    installed, installed_at, local_path, local_paths, checksum, security_score, security_issues, security_level, scanned_at, installed_commit_sha, compatible_tools,
+                    source_removed, first_seen_at, source_type, origin_tool
              FROM skills"
         )?;
 
@@ -253,11 +491,20 @@ impl Database {
             let local_paths = local_paths
                 .and_then(|s| serde_json::from_str(&s).ok());
 
+            let compatible_tools: Option<String> = row.get(18)?;
+            let compatible_tools = compatible_tools
+                .and_then(|s| serde_json::from_str(&s).ok());
+
+            let repository_url: String = row.get(3)?;
+            let source_type = row.get::<_, Option<String>>(21)?
+                .and_then(|s| SourceType::parse(&s))
+                .unwrap_or_else(|| SourceType::infer(&repository_url));
+
             Ok(Skill {
                 id: row.get(0)?,
                 name: row.get(1)?,
                 description: row.get(2)?,
-                repository_url: row.get(3)?,
+                repository_url,
                 repository_owner: row.get(4)?,
                 file_path: row.get(5)?,
                 version: row.get(6)?,
@@ -274,6 +521,12 @@ impl Database {
                 scanned_at: row.get::<_, Option<String>>(16)?
                     .and_then(|s| s.parse().ok()),
                 installed_commit_sha: row.get(17)?,
+                compatible_tools,
+                source_removed: row.get::<_, i32>(19)? != 0,
+                first_seen_at: row.get::<_, Option<String>>(20)?
+                    .and_then(|s| s.parse().ok()),
+                source_type,
+                origin_tool: row.get(22)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
@@ -298,181 +551,1219 @@ impl Database {
         Ok(deleted_count)
     }
 
+    /// 将指定仓库保留下来的已安装技能标记为来源已删除，返回标记的数量
+    pub fn mark_skills_source_removed(&self, repository_url: &str) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let marked = conn.execute(
+            "UPDATE skills SET source_removed = 1 WHERE repository_url = ?1 AND installed = 1",
+            params![repository_url]
+        )?;
+        Ok(marked)
+    }
+
     /// 删除 skill
     pub fn delete_skill(&self, skill_id: &str) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute("DELETE FROM skills WHERE id = ?1", params![skill_id])?;
         conn.execute("DELETE FROM installations WHERE skill_id = ?1", params![skill_id])?;
+        conn.execute("DELETE FROM scan_reports WHERE skill_id = ?1", params![skill_id])?;
+        conn.execute("DELETE FROM hard_trigger_history WHERE skill_id = ?1", params![skill_id])?;
+        conn.execute("DELETE FROM score_history WHERE skill_id = ?1", params![skill_id])?;
+        conn.execute("DELETE FROM skill_tags WHERE skill_id = ?1", params![skill_id])?;
+        conn.execute("DELETE FROM skill_reviews WHERE skill_id = ?1", params![skill_id])?;
+        conn.execute("DELETE FROM skill_install_targets WHERE skill_id = ?1", params![skill_id])?;
         Ok(())
     }
 
-    /// 数据库迁移：添加缓存相关字段
-    fn migrate_add_cache_fields(&self) -> Result<()> {
+    /// 修改技能 ID，同时迁移安装记录、扫描报告与历史、事件等关联数据（新 ID 已有的记录被覆盖）
+    pub fn rekey_skill(&self, old_id: &str, new_id: &str) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for (table, column) in [
+            ("skills", "id"),
+            ("installations", "skill_id"),
+            ("scan_reports", "skill_id"),
+            ("suspicious_updates", "skill_id"),
+            ("hard_trigger_history", "skill_id"),
+            ("score_history", "skill_id"),
+            ("false_positive_reports", "skill_id"),
+            ("pending_operations", "skill_id"),
+            ("skill_events", "skill_id"),
+            ("skill_tags", "skill_id"),
+            ("skill_reviews", "skill_id"),
+            ("skill_install_targets", "skill_id"),
+        ] {
+            tx.execute(
+                &format!("UPDATE OR REPLACE {} SET {} = ?2 WHERE {} = ?1", table, column, column),
+                params![old_id, new_id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 按安全等级阈值重新计算已扫描技能的安全等级，返回等级发生变化的技能数
+    pub fn relevel_skills(&self, thresholds: &LevelThresholds) -> Result<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let changed: Vec<(String, &'static str)> = {
+            let mut stmt = tx.prepare("SELECT id, security_score, security_level FROM skills WHERE security_score IS NOT NULL")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i32>(1)?, row.get::<_, Option<String>>(2)?))
+            })?;
+            let mut changed = Vec::new();
+            for row in rows {
+                let (id, score, level) = row?;
+                let expected = thresholds.level(score).as_str();
+                if level.as_deref() != Some(expected) {
+                    changed.push((id, expected));
+                }
+            }
+            changed
+        };
+        for (id, level) in &changed {
+            tx.execute("UPDATE skills SET security_level = ?1 WHERE id = ?2", params![level, id])?;
+        }
+        tx.commit()?;
+        Ok(changed.len())
+    }
+
+    /// 数据库迁移：添加 scan_reports 表，保存完整的扫描报告（含行号、代码片段、建议等）
+    fn migrate_add_scan_reports(&self) -> Result<()> {
         let conn = self.conn.lock().unwrap();
 
-        // 添加 cache_path 列
-        let _ = conn.execute(
-            "ALTER TABLE repositories ADD COLUMN cache_path TEXT",
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS scan_reports (
+                skill_id TEXT PRIMARY KEY,
+                report TEXT NOT NULL,
+                scanned_at TEXT NOT NULL
+            )",
             [],
-        );
+        )?;
 
-        // 添加 cached_at 列
-        let _ = conn.execute(
-            "ALTER TABLE repositories ADD COLUMN cached_at TEXT",
+        Ok(())
+    }
+
+    /// 数据库迁移：添加 hard_trigger_history 表，记录历次扫描命中的硬触发规则
+    fn migrate_add_hard_trigger_history(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS hard_trigger_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                skill_id TEXT NOT NULL,
+                scanned_at TEXT NOT NULL,
+                issues TEXT NOT NULL
+            )",
             [],
-        );
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_hard_trigger_history_skill ON hard_trigger_history (skill_id)",
+            [],
+        )?;
 
-        // 添加 cached_commit_sha 列
-        let _ = conn.execute(
-            "ALTER TABLE repositories ADD COLUMN cached_commit_sha TEXT",
+        Ok(())
+    }
+
+    /// 数据库迁移：添加 rule_stats（规则命中统计）与 false_positive_reports（误报反馈）表
+    fn migrate_add_rule_statistics(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS rule_stats (
+                rule_id TEXT PRIMARY KEY,
+                hit_count INTEGER NOT NULL DEFAULT 0,
+                scan_count INTEGER NOT NULL DEFAULT 0,
+                last_hit_at TEXT
+            )",
             [],
-        );
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS false_positive_reports (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                rule_id TEXT NOT NULL,
+                skill_id TEXT,
+                file_path TEXT,
+                snippet TEXT NOT NULL,
+                note TEXT,
+                reported_at TEXT NOT NULL
+            )",
+            [],
+        )?;
 
         Ok(())
     }
 
-    /// 数据库迁移：添加安全扫描增强字段
-    fn migrate_add_security_enhancement_fields(&self) -> Result<()> {
+    /// 数据库迁移：添加 score_history 表，记录每次扫描的评分（用于评分趋势）
+    fn migrate_add_score_history(&self) -> Result<()> {
         let conn = self.conn.lock().unwrap();
 
-        // 添加 security_level 列
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS score_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                skill_id TEXT NOT NULL,
+                scanned_at TEXT NOT NULL,
+                score INTEGER NOT NULL,
+                issue_count INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_score_history_skill ON score_history (skill_id)",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// 保存 skill 的完整扫描报告（覆盖上一次的结果），命中硬触发规则时追加到历史记录
+    pub fn save_scan_report(
+        &self,
+        skill_id: &str,
+        report: &SecurityReport,
+        scanned_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "INSERT OR REPLACE INTO scan_reports (skill_id, report, scanned_at) VALUES (?1, ?2, ?3)",
+            params![skill_id, serde_json::to_string(report)?, scanned_at.to_rfc3339()],
+        )?;
+
+        if !report.hard_trigger_issues.is_empty() {
+            tx.execute(
+                "INSERT INTO hard_trigger_history (skill_id, scanned_at, issues) VALUES (?1, ?2, ?3)",
+                params![skill_id, scanned_at.to_rfc3339(), serde_json::to_string(&report.hard_trigger_issues)?],
+            )?;
+            tx.execute(
+                "DELETE FROM hard_trigger_history WHERE skill_id = ?1 AND id NOT IN (
+                    SELECT id FROM hard_trigger_history WHERE skill_id = ?1 ORDER BY id DESC LIMIT ?2
+                )",
+                params![skill_id, MAX_HARD_TRIGGER_HISTORY as i64],
+            )?;
+        }
+
+        tx.execute(
+            "INSERT INTO score_history (skill_id, scanned_at, score, issue_count) VALUES (?1, ?2, ?3, ?4)",
+            params![skill_id, scanned_at.to_rfc3339(), report.score, report.issues.len() as i64],
+        )?;
+        tx.execute(
+            "DELETE FROM score_history WHERE skill_id = ?1 AND id NOT IN (
+                SELECT id FROM score_history WHERE skill_id = ?1 ORDER BY id DESC LIMIT ?2
+            )",
+            params![skill_id, MAX_SCORE_HISTORY as i64],
+        )?;
+
+        // 累计规则命中次数（每次扫描中同一规则的 scan_count 只加 1）
+        let mut hits: std::collections::HashMap<&str, i64> = std::collections::HashMap::new();
+        for rule_id in report.issues.iter().filter_map(|i| i.rule_id.as_deref()) {
+            *hits.entry(rule_id).or_insert(0) += 1;
+        }
+        for (rule_id, count) in hits {
+            tx.execute(
+                "INSERT INTO rule_stats (rule_id, hit_count, scan_count, last_hit_at) VALUES (?1, ?2, 1, ?3)
+                 ON CONFLICT(rule_id) DO UPDATE SET
+                    hit_count = hit_count + excluded.hit_count,
+                    scan_count = scan_count + 1,
+                    last_hit_at = excluded.last_hit_at",
+                params![rule_id, count, scanned_at.to_rfc3339()],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 获取所有 skill 的硬触发历史：skill_id -> 记录（从新到旧）
+    pub fn get_hard_trigger_history(&self) -> Result<std::collections::HashMap<String, Vec<HardTriggerRecord>>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT skill_id, scanned_at, issues FROM hard_trigger_history ORDER BY id DESC"
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+        })?;
+
+        let mut history: std::collections::HashMap<String, Vec<HardTriggerRecord>> = std::collections::HashMap::new();
+        for row in rows {
+            let (skill_id, scanned_at, issues) = row?;
+            let issues = serde_json::from_str(&issues).unwrap_or_default();
+            history.entry(skill_id).or_default().push(HardTriggerRecord { scanned_at, issues });
+        }
+
+        Ok(history)
+    }
+
+    /// 数据库迁移：添加提交签名验证相关字段
+    fn migrate_add_commit_signature(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        // 列已存在时忽略错误
         let _ = conn.execute(
-            "ALTER TABLE skills ADD COLUMN security_level TEXT",
+            "ALTER TABLE repositories ADD COLUMN require_signed_commits INTEGER NOT NULL DEFAULT 0",
             [],
         );
-
-        // 添加 scanned_at 列
         let _ = conn.execute(
-            "ALTER TABLE skills ADD COLUMN scanned_at TEXT",
+            "ALTER TABLE repositories ADD COLUMN commit_signature TEXT",
             [],
         );
 
         Ok(())
     }
 
-    /// 数据库迁移：添加 local_paths 列,支持多个安装路径
-    fn migrate_add_local_paths(&self) -> Result<()> {
+    /// 数据库迁移：添加仓库的技能发现路径配置（包含/排除模式与最大深度）
+    fn migrate_add_repository_scan_paths(&self) -> Result<()> {
         let conn = self.conn.lock().unwrap();
 
-        // 添加 local_paths 列（JSON 数组格式）
+        // 列已存在时忽略错误
         let _ = conn.execute(
-            "ALTER TABLE skills ADD COLUMN local_paths TEXT",
+            "ALTER TABLE repositories ADD COLUMN scan_include_patterns TEXT",
             [],
         );
-
-        // 将现有的 local_path 迁移到 local_paths 数组中
-        conn.execute(
-            r#"
-            UPDATE skills
-            SET local_paths = json_array(local_path)
-            WHERE local_path IS NOT NULL AND local_paths IS NULL
-            "#,
+        let _ = conn.execute(
+            "ALTER TABLE repositories ADD COLUMN scan_exclude_patterns TEXT",
             [],
-        )?;
+        );
+        let _ = conn.execute(
+            "ALTER TABLE repositories ADD COLUMN scan_max_depth INTEGER",
+            [],
+        );
 
         Ok(())
     }
 
-    /// 更新仓库缓存信息
-    pub fn update_repository_cache(
+    /// 设置仓库的技能发现路径配置
+    pub fn set_repository_scan_paths(
         &self,
         repo_id: &str,
-        cache_path: &str,
-        cached_at: chrono::DateTime<chrono::Utc>,
-        cached_commit_sha: Option<&str>,
+        include_patterns: &[String],
+        exclude_patterns: &[String],
+        max_depth: Option<u32>,
     ) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-
-        conn.execute(
-            "UPDATE repositories
-             SET cache_path = ?1, cached_at = ?2, last_scanned = ?3, cached_commit_sha = ?4
-             WHERE id = ?5",
+        let updated = conn.execute(
+            "UPDATE repositories SET scan_include_patterns = ?1, scan_exclude_patterns = ?2, scan_max_depth = ?3
+             WHERE id = ?4",
             params![
-                cache_path,
-                cached_at.to_rfc3339(),
-                cached_at.to_rfc3339(),
-                cached_commit_sha,
+                serde_json::to_string(include_patterns)?,
+                serde_json::to_string(exclude_patterns)?,
+                max_depth,
                 repo_id,
             ],
         )?;
-
+        if updated == 0 {
+            anyhow::bail!("未找到仓库: {}", repo_id);
+        }
         Ok(())
     }
 
-    /// 清除仓库缓存信息（但不删除文件）
-    pub fn clear_repository_cache_metadata(&self, repo_id: &str) -> Result<()> {
+    /// 更新仓库的提交签名验证结果
+    pub fn update_repository_signature(&self, repo_id: &str, status: &CommitSignatureStatus) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-
         conn.execute(
-            "UPDATE repositories
-             SET cache_path = NULL, cached_at = NULL, cached_commit_sha = NULL
-             WHERE id = ?1",
-            params![repo_id],
+            "UPDATE repositories SET commit_signature = ?1 WHERE id = ?2",
+            params![serde_json::to_string(status)?, repo_id],
         )?;
-
         Ok(())
     }
 
-    /// 数据库迁移：添加 installed_commit_sha 列
-    fn migrate_add_installed_commit_sha(&self) -> Result<()> {
+    /// 数据库迁移：添加仓库的后台刷新开关
+    fn migrate_add_repository_auto_refresh(&self) -> Result<()> {
         let conn = self.conn.lock().unwrap();
 
-        // 添加 installed_commit_sha 列
+        // 列已存在时忽略错误
         let _ = conn.execute(
-            "ALTER TABLE skills ADD COLUMN installed_commit_sha TEXT",
+            "ALTER TABLE repositories ADD COLUMN skip_auto_refresh INTEGER NOT NULL DEFAULT 0",
             [],
         );
 
         Ok(())
     }
 
-    /// 获取单个仓库信息
-    pub fn get_repository(&self, repo_id: &str) -> Result<Option<Repository>> {
+    /// 设置仓库是否跳过后台定时刷新
+    pub fn set_repository_skip_auto_refresh(&self, repo_id: &str, skip: bool) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-
-        let mut stmt = conn.prepare(
-            "SELECT id, url, name, description, enabled, scan_subdirs,
-                    added_at, last_scanned, cache_path, cached_at, cached_commit_sha
-             FROM repositories
-             WHERE id = ?1"
+        let updated = conn.execute(
+            "UPDATE repositories SET skip_auto_refresh = ?1 WHERE id = ?2",
+            params![skip as i32, repo_id],
         )?;
+        if updated == 0 {
+            anyhow::bail!("未找到仓库: {}", repo_id);
+        }
+        Ok(())
+    }
 
-        let repo = stmt.query_row(params![repo_id], |row| {
-            Ok(Repository {
-                id: row.get(0)?,
-                url: row.get(1)?,
-                name: row.get(2)?,
-                description: row.get(3)?,
-                enabled: row.get::<_, i32>(4)? != 0,
-                scan_subdirs: row.get::<_, i32>(5)? != 0,
-                added_at: row.get::<_, String>(6)?.parse().unwrap_or_else(|_| chrono::Utc::now()),
-                last_scanned: row.get::<_, Option<String>>(7)?
-                    .and_then(|s| s.parse().ok()),
-                cache_path: row.get(8)?,
-                cached_at: row.get::<_, Option<String>>(9)?
-                    .and_then(|s| s.parse().ok()),
-                cached_commit_sha: row.get(10)?,
-            })
-        }).optional()?;
+    /// 数据库迁移：添加仓库最近一次失败的错误信息
+    fn migrate_add_repository_last_error(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
 
-        Ok(repo)
+        // 列已存在时忽略错误
+        let _ = conn.execute("ALTER TABLE repositories ADD COLUMN last_error TEXT", []);
+        let _ = conn.execute("ALTER TABLE repositories ADD COLUMN last_error_at TEXT", []);
+
+        Ok(())
     }
 
-    /// 初始化默认仓库
-    /// 返回是否添加了新仓库（用于判断是否需要自动扫描）
-    fn initialize_default_repositories(&self) -> Result<bool> {
+    /// 记录仓库最近一次扫描/下载失败的错误（None 表示成功，清除之前的错误）
+    pub fn set_repository_last_error(&self, repo_id: &str, error: Option<&str>) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-
-        // 检查是否已有仓库
-        let count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM repositories",
-            [],
-            |row| row.get(0),
+        conn.execute(
+            "UPDATE repositories SET last_error = ?1, last_error_at = ?2 WHERE id = ?3",
+            params![error, error.map(|_| chrono::Utc::now().to_rfc3339()), repo_id],
         )?;
+        Ok(())
+    }
 
-        // 如果已有仓库，跳过初始化
-        if count > 0 {
-            return Ok(false);
+    /// 设置仓库是否要求已签名的提交
+    pub fn set_repository_require_signed_commits(&self, repo_id: &str, required: bool) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let updated = conn.execute(
+            "UPDATE repositories SET require_signed_commits = ?1 WHERE id = ?2",
+            params![required as i32, repo_id],
+        )?;
+        if updated == 0 {
+            anyhow::bail!("未找到仓库: {}", repo_id);
+        }
+        Ok(())
+    }
+
+    /// 获取 skill 的评分历史（从旧到新），按给定阈值计算每次评分的安全等级
+    pub fn get_score_history(&self, skill_id: &str, thresholds: &LevelThresholds) -> Result<Vec<ScoreHistoryPoint>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT scanned_at, score, issue_count FROM score_history WHERE skill_id = ?1 ORDER BY id"
+        )?;
+        let history = stmt.query_map(params![skill_id], |row| {
+            let score: i32 = row.get(1)?;
+            Ok(ScoreHistoryPoint {
+                scanned_at: row.get(0)?,
+                score,
+                level: thresholds.level(score),
+                issue_count: row.get::<_, i64>(2)? as usize,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(history)
+    }
+
+    /// 获取规则命中统计（含误报反馈次数），按命中次数从高到低排序
+    pub fn get_rule_statistics(&self) -> Result<Vec<RuleStatistics>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT s.rule_id, s.hit_count, s.scan_count, s.last_hit_at,
+                    (SELECT COUNT(*) FROM false_positive_reports f WHERE f.rule_id = s.rule_id)
+             FROM rule_stats s
+             ORDER BY s.hit_count DESC, s.rule_id"
+        )?;
+        let stats = stmt.query_map([], |row| {
+            Ok(RuleStatistics {
+                rule_id: row.get(0)?,
+                rule_name: String::new(),
+                hit_count: row.get::<_, i64>(1)? as u64,
+                scan_count: row.get::<_, i64>(2)? as u64,
+                last_hit_at: row.get(3)?,
+                false_positive_count: row.get::<_, i64>(4)? as u64,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(stats)
+    }
+
+    /// 记录一条误报反馈，返回记录 ID
+    pub fn add_false_positive_report(&self, report: &FalsePositiveReport) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO false_positive_reports (rule_id, skill_id, file_path, snippet, note, reported_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                report.rule_id,
+                report.skill_id,
+                report.file_path,
+                report.snippet,
+                report.note,
+                report.reported_at,
+            ],
+        )?;
+
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// 获取所有误报反馈（从新到旧）
+    pub fn get_false_positive_reports(&self) -> Result<Vec<FalsePositiveReport>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, rule_id, skill_id, file_path, snippet, note, reported_at
+             FROM false_positive_reports ORDER BY id DESC"
+        )?;
+        let reports = stmt.query_map([], |row| {
+            Ok(FalsePositiveReport {
+                id: row.get(0)?,
+                rule_id: row.get(1)?,
+                skill_id: row.get(2)?,
+                file_path: row.get(3)?,
+                snippet: row.get(4)?,
+                note: row.get(5)?,
+                reported_at: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(reports)
+    }
+
+    /// 获取所有保存的扫描报告：skill_id -> (报告, 扫描时间)
+    /// 无法解析的记录（如旧版本写入的格式）会被跳过
+    pub fn get_scan_reports(
+        &self,
+    ) -> Result<std::collections::HashMap<String, (SecurityReport, chrono::DateTime<chrono::Utc>)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT skill_id, report, scanned_at FROM scan_reports")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+        })?;
+
+        let mut reports = std::collections::HashMap::new();
+        for row in rows {
+            let (skill_id, report, scanned_at) = row?;
+            let parsed = serde_json::from_str::<SecurityReport>(&report).ok().zip(
+                chrono::DateTime::parse_from_rfc3339(&scanned_at)
+                    .ok()
+                    .map(|d| d.with_timezone(&chrono::Utc)),
+            );
+            match parsed {
+                Some(entry) => {
+                    reports.insert(skill_id, entry);
+                }
+                None => log::warn!("跳过无法解析的扫描报告: {}", skill_id),
+            }
+        }
+
+        Ok(reports)
+    }
+
+    /// 数据库迁移：添加缓存相关字段
+    fn migrate_add_cache_fields(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        // 添加 cache_path 列
+        let _ = conn.execute(
+            "ALTER TABLE repositories ADD COLUMN cache_path TEXT",
+            [],
+        );
+
+        // 添加 cached_at 列
+        let _ = conn.execute(
+            "ALTER TABLE repositories ADD COLUMN cached_at TEXT",
+            [],
+        );
+
+        // 添加 cached_commit_sha 列
+        let _ = conn.execute(
+            "ALTER TABLE repositories ADD COLUMN cached_commit_sha TEXT",
+            [],
+        );
+
+        Ok(())
+    }
+
+    /// 数据库迁移：添加安全扫描增强字段
+    fn migrate_add_security_enhancement_fields(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        // 添加 security_level 列
+        let _ = conn.execute(
+            "ALTER TABLE skills ADD COLUMN security_level TEXT",
+            [],
+        );
+
+        // 添加 scanned_at 列
+        let _ = conn.execute(
+            "ALTER TABLE skills ADD COLUMN scanned_at TEXT",
+            [],
+        );
+
+        Ok(())
+    }
+
+    /// 数据库迁移：添加 local_paths 列,支持多个安装路径
+    fn migrate_add_local_paths(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        // 添加 local_paths 列（JSON 数组格式）
+        let _ = conn.execute(
+            "ALTER TABLE skills ADD COLUMN local_paths TEXT",
+            [],
+        );
+
+        // 将现有的 local_path 迁移到 local_paths 数组中
+        conn.execute(
+            r#"
+            UPDATE skills
+            SET local_paths = json_array(local_path)
+            WHERE local_path IS NOT NULL AND local_paths IS NULL
+            "#,
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// 更新仓库缓存信息
+    pub fn update_repository_cache(
+        &self,
+        repo_id: &str,
+        cache_path: &str,
+        cached_at: chrono::DateTime<chrono::Utc>,
+        cached_commit_sha: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "UPDATE repositories
+             SET cache_path = ?1, cached_at = ?2, last_scanned = ?3, cached_commit_sha = ?4
+             WHERE id = ?5",
+            params![
+                cache_path,
+                cached_at.to_rfc3339(),
+                cached_at.to_rfc3339(),
+                cached_commit_sha,
+                repo_id,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// 缓存目录迁移后，把仓库缓存路径与未完成操作的备份路径中的旧目录替换为新目录，返回更新的记录数
+    pub fn rebase_cache_paths(&self, old_root: &Path, new_root: &Path) -> Result<usize> {
+        let rebase = |path: &str| {
+            Path::new(path).strip_prefix(old_root).ok().map(|rest| new_root.join(rest).to_string_lossy().to_string())
+        };
+
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut updated = 0;
+        for (table, column) in [("repositories", "cache_path"), ("pending_operations", "backup_dir")] {
+            let rows: Vec<(String, String)> = {
+                let mut stmt = tx.prepare(&format!("SELECT id, {} FROM {} WHERE {} IS NOT NULL", column, table, column))?;
+                let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+                rows.collect::<rusqlite::Result<_>>()?
+            };
+            for (id, path) in rows {
+                if let Some(path) = rebase(&path) {
+                    tx.execute(&format!("UPDATE {} SET {} = ?1 WHERE id = ?2", table, column), params![path, id])?;
+                    updated += 1;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(updated)
+    }
+
+    /// 清除仓库缓存信息（但不删除文件）
+    pub fn clear_repository_cache_metadata(&self, repo_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "UPDATE repositories
+             SET cache_path = NULL, cached_at = NULL, cached_commit_sha = NULL
+             WHERE id = ?1",
+            params![repo_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// 数据库迁移：添加 installed_commit_sha 列
+    fn migrate_add_installed_commit_sha(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        // 添加 installed_commit_sha 列
+        let _ = conn.execute(
+            "ALTER TABLE skills ADD COLUMN installed_commit_sha TEXT",
+            [],
+        );
+
+        Ok(())
+    }
+
+    /// 数据库迁移：添加 compatible_tools 列（JSON 数组）
+    fn migrate_add_compatible_tools(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        // 列已存在时忽略错误
+        let _ = conn.execute(
+            "ALTER TABLE skills ADD COLUMN compatible_tools TEXT",
+            [],
+        );
+
+        Ok(())
+    }
+
+    /// 数据库迁移：添加 skills.source_removed 列
+    fn migrate_add_skill_source_removed(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        // 列已存在时忽略错误
+        let _ = conn.execute(
+            "ALTER TABLE skills ADD COLUMN source_removed INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        Ok(())
+    }
+
+    /// 数据库迁移：添加 skills.source_type 列（旧记录为空，读取时按 repository_url 推断）
+    fn migrate_add_skill_source_type(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        // 列已存在时忽略错误
+        let _ = conn.execute("ALTER TABLE skills ADD COLUMN source_type TEXT", []);
+
+        Ok(())
+    }
+
+    /// 数据库迁移：添加 skills.origin_tool 列（本地导入技能所在的工具）
+    fn migrate_add_skill_origin_tool(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        // 列已存在时忽略错误
+        let _ = conn.execute("ALTER TABLE skills ADD COLUMN origin_tool TEXT", []);
+
+        Ok(())
+    }
+
+    /// 数据库迁移：添加 skill_tags 表，保存用户为技能添加的标签
+    fn migrate_add_skill_tags(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS skill_tags (
+                skill_id TEXT NOT NULL,
+                tag TEXT NOT NULL,
+                PRIMARY KEY (skill_id, tag)
+            )",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// 数据库迁移：添加 skill_reviews 表，保存每个技能最近一次的人工审阅记录
+    fn migrate_add_skill_reviews(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS skill_reviews (
+                skill_id TEXT PRIMARY KEY,
+                reviewer TEXT NOT NULL,
+                reviewed_at TEXT NOT NULL,
+                commit_sha TEXT,
+                checksum TEXT,
+                note TEXT
+            )",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// 保存审阅记录（覆盖该技能之前的记录）
+    pub fn save_skill_review(&self, review: &SkillReview) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO skill_reviews (skill_id, reviewer, reviewed_at, commit_sha, checksum, note)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                review.skill_id,
+                review.reviewer,
+                review.reviewed_at.to_rfc3339(),
+                review.commit_sha,
+                review.checksum,
+                review.note,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 获取所有审阅记录（按技能 ID），无法解析时间的记录会被跳过
+    pub fn get_skill_reviews(&self) -> Result<HashMap<String, SkillReview>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT skill_id, reviewer, reviewed_at, commit_sha, checksum, note FROM skill_reviews"
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, Option<String>>(5)?,
+            ))
+        })?;
+
+        let mut reviews = HashMap::new();
+        for row in rows {
+            let (skill_id, reviewer, reviewed_at, commit_sha, checksum, note) = row?;
+            let Ok(reviewed_at) = chrono::DateTime::parse_from_rfc3339(&reviewed_at) else {
+                log::warn!("跳过无法解析时间的审阅记录: {}", skill_id);
+                continue;
+            };
+            reviews.insert(skill_id.clone(), SkillReview {
+                skill_id,
+                reviewer,
+                reviewed_at: reviewed_at.with_timezone(&chrono::Utc),
+                commit_sha,
+                checksum,
+                note,
+            });
+        }
+        Ok(reviews)
+    }
+
+    /// 删除技能的审阅记录
+    pub fn delete_skill_review(&self, skill_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM skill_reviews WHERE skill_id = ?1", params![skill_id])?;
+        Ok(())
+    }
+
+    /// 数据库迁移：添加 skill_install_targets 表，记住每个技能选择的安装目录
+    fn migrate_add_skill_install_targets(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS skill_install_targets (
+                skill_id TEXT PRIMARY KEY,
+                install_dir TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// 记住技能的安装目录（安装基础目录，不含技能文件夹名）
+    pub fn set_skill_install_target(&self, skill_id: &str, install_dir: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO skill_install_targets (skill_id, install_dir, updated_at) VALUES (?1, ?2, ?3)",
+            params![skill_id, install_dir, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// 获取技能记住的安装目录
+    pub fn get_skill_install_target(&self, skill_id: &str) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        let install_dir = conn
+            .query_row(
+                "SELECT install_dir FROM skill_install_targets WHERE skill_id = ?1",
+                params![skill_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(install_dir)
+    }
+
+    /// 获取所有技能记住的安装目录（按技能 ID）
+    pub fn get_skill_install_targets(&self) -> Result<HashMap<String, String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT skill_id, install_dir FROM skill_install_targets")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// 忘记技能的安装目录（之后使用默认安装目录）
+    pub fn delete_skill_install_target(&self, skill_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM skill_install_targets WHERE skill_id = ?1", params![skill_id])?;
+        Ok(())
+    }
+
+    /// 获取所有技能的标签（按技能 ID 分组，标签按名称排序）
+    pub fn get_skill_tags(&self) -> Result<HashMap<String, Vec<String>>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT skill_id, tag FROM skill_tags ORDER BY skill_id, tag")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+
+        let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+        for row in rows {
+            let (skill_id, tag) = row?;
+            tags.entry(skill_id).or_default().push(tag);
+        }
+        Ok(tags)
+    }
+
+    /// 为技能添加与移除标签（已有的标签忽略）
+    pub fn update_skill_tags(&self, skill_id: &str, add: &[String], remove: &[String]) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for tag in add {
+            tx.execute(
+                "INSERT OR IGNORE INTO skill_tags (skill_id, tag) VALUES (?1, ?2)",
+                params![skill_id, tag],
+            )?;
+        }
+        for tag in remove {
+            tx.execute(
+                "DELETE FROM skill_tags WHERE skill_id = ?1 AND tag = ?2",
+                params![skill_id, tag],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 数据库迁移：添加 scan_caches 表，按目录保存增量扫描缓存（每个文件的内容哈希与扫描结果）
+    fn migrate_add_scan_caches(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS scan_caches (
+                dir TEXT PRIMARY KEY,
+                cache TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// 读取目录的增量扫描缓存（无法解析的旧缓存视为不存在）
+    pub fn get_scan_cache(&self, dir: &str) -> Result<Option<crate::security::ScanCache>> {
+        let conn = self.conn.lock().unwrap();
+        let cache: Option<String> = conn
+            .query_row("SELECT cache FROM scan_caches WHERE dir = ?1", params![dir], |row| row.get(0))
+            .optional()?;
+
+        Ok(cache.and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    /// 保存目录的增量扫描缓存
+    pub fn save_scan_cache(&self, dir: &str, cache: &crate::security::ScanCache) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO scan_caches (dir, cache, updated_at) VALUES (?1, ?2, ?3)",
+            params![dir, serde_json::to_string(cache)?, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// 删除目录的增量扫描缓存（卸载技能时调用）
+    pub fn delete_scan_cache(&self, dir: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM scan_caches WHERE dir = ?1", params![dir])?;
+        Ok(())
+    }
+
+    /// 数据库迁移：添加 pending_operations 表，记录进行中的安装/更新步骤，用于崩溃后恢复
+    fn migrate_add_pending_operations(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS pending_operations (
+                id TEXT PRIMARY KEY,
+                skill_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                step TEXT NOT NULL,
+                target_dir TEXT NOT NULL,
+                backup_dir TEXT,
+                commit_sha TEXT,
+                started_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// 写入或更新未完成的写操作记录
+    pub fn save_pending_operation(&self, op: &PendingOperation) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO pending_operations
+                (id, skill_id, kind, step, target_dir, backup_dir, commit_sha, started_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                op.id,
+                op.skill_id,
+                op.kind.as_str(),
+                op.step.as_str(),
+                op.target_dir,
+                op.backup_dir,
+                op.commit_sha,
+                op.started_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 获取所有未完成的写操作，按开始时间排序（无法识别的记录会被跳过）
+    pub fn get_pending_operations(&self) -> Result<Vec<PendingOperation>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, skill_id, kind, step, target_dir, backup_dir, commit_sha, started_at
+             FROM pending_operations ORDER BY started_at",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, Option<String>>(5)?,
+                row.get::<_, Option<String>>(6)?,
+                row.get::<_, String>(7)?,
+            ))
+        })?;
+
+        let mut operations = Vec::new();
+        for row in rows {
+            let (id, skill_id, kind, step, target_dir, backup_dir, commit_sha, started_at) = row?;
+            let (Some(kind), Some(step)) = (OperationKind::parse(&kind), OperationStep::parse(&step)) else {
+                log::warn!("跳过无法识别的操作记录: {} ({}, {})", id, kind, step);
+                continue;
+            };
+            operations.push(PendingOperation {
+                id,
+                skill_id,
+                kind,
+                step,
+                target_dir,
+                backup_dir,
+                commit_sha,
+                started_at: chrono::DateTime::parse_from_rfc3339(&started_at)
+                    .map(|t| t.with_timezone(&chrono::Utc))
+                    .unwrap_or_else(|_| chrono::Utc::now()),
+            });
+        }
+        Ok(operations)
+    }
+
+    /// 删除写操作记录（操作结束或恢复完成后调用）
+    pub fn delete_pending_operation(&self, id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM pending_operations WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// 数据库迁移：添加可疑更新记录表
+    fn migrate_add_suspicious_updates(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS suspicious_updates (
+                skill_id TEXT PRIMARY KEY,
+                data TEXT NOT NULL,
+                detected_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// 保存技能的可疑更新记录（覆盖之前的记录）
+    pub fn save_suspicious_update(&self, update: &SuspiciousUpdate) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO suspicious_updates (skill_id, data, detected_at) VALUES (?1, ?2, ?3)",
+            params![update.skill_id, serde_json::to_string(update)?, update.detected_at.to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// 获取所有可疑更新记录，最新的在前（无法解析的记录会被跳过）
+    pub fn get_suspicious_updates(&self) -> Result<Vec<SuspiciousUpdate>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT skill_id, data FROM suspicious_updates ORDER BY detected_at DESC")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+
+        let mut updates = Vec::new();
+        for row in rows {
+            let (skill_id, data) = row?;
+            match serde_json::from_str(&data) {
+                Ok(update) => updates.push(update),
+                Err(e) => log::warn!("跳过无法解析的可疑更新记录: {}: {}", skill_id, e),
+            }
+        }
+        Ok(updates)
+    }
+
+    /// 删除技能的可疑更新记录（已确认安装/更新、已忽略或刷新后不再可疑）
+    pub fn delete_suspicious_update(&self, skill_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM suspicious_updates WHERE skill_id = ?1", params![skill_id])?;
+        Ok(())
+    }
+
+    /// 数据库迁移：添加 skill_events 表，记录技能的安装、更新、卸载与可疑标记（用于时间线）
+    fn migrate_add_skill_events(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS skill_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                skill_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                path TEXT,
+                commit_sha TEXT,
+                detail TEXT,
+                occurred_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_skill_events_skill ON skill_events (skill_id)",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// 追加一条技能历史事件（删除技能记录时保留，以便追溯）
+    pub fn add_skill_event(&self, event: &SkillEvent) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO skill_events (skill_id, kind, path, commit_sha, detail, occurred_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                event.skill_id,
+                event.kind.as_str(),
+                event.path,
+                event.commit_sha,
+                event.detail,
+                event.occurred_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 获取技能的历史事件（从旧到新，无法解析的记录会被跳过）
+    pub fn get_skill_events(&self, skill_id: &str) -> Result<Vec<SkillEvent>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT kind, path, commit_sha, detail, occurred_at FROM skill_events WHERE skill_id = ?1 ORDER BY id"
+        )?;
+        let rows = stmt.query_map(params![skill_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?;
+
+        let mut events = Vec::new();
+        for row in rows {
+            let (kind, path, commit_sha, detail, occurred_at) = row?;
+            let kind = SkillEventKind::parse(&kind);
+            let occurred_at = chrono::DateTime::parse_from_rfc3339(&occurred_at).ok();
+            match (kind, occurred_at) {
+                (Some(kind), Some(occurred_at)) => events.push(SkillEvent {
+                    skill_id: skill_id.to_string(),
+                    kind,
+                    path,
+                    commit_sha,
+                    detail,
+                    occurred_at: occurred_at.with_timezone(&chrono::Utc),
+                }),
+                _ => log::warn!("跳过无法解析的技能历史事件: {}", skill_id),
+            }
+        }
+        Ok(events)
+    }
+
+    /// 数据库迁移：添加技能首次出现时间与仓库统计信息表
+    fn migrate_add_provenance(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        // 列已存在时忽略错误；已有技能以迁移时间作为首次出现时间
+        if conn.execute("ALTER TABLE skills ADD COLUMN first_seen_at TEXT", []).is_ok() {
+            conn.execute(
+                "UPDATE skills SET first_seen_at = ?1 WHERE first_seen_at IS NULL",
+                params![chrono::Utc::now().to_rfc3339()],
+            )?;
+        }
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS repository_stats (
+                repository_url TEXT PRIMARY KEY,
+                data TEXT NOT NULL,
+                fetched_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    /// 保存仓库统计信息（覆盖之前的记录）
+    pub fn save_repository_stats(&self, stats: &RepositoryStats) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO repository_stats (repository_url, data, fetched_at) VALUES (?1, ?2, ?3)",
+            params![stats.repository_url, serde_json::to_string(stats)?, stats.fetched_at.to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// 获取所有仓库统计信息：repository_url -> 统计（无法解析的记录会被跳过）
+    pub fn get_repository_stats(&self) -> Result<std::collections::HashMap<String, RepositoryStats>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT repository_url, data FROM repository_stats")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+
+        let mut stats = std::collections::HashMap::new();
+        for row in rows {
+            let (url, data) = row?;
+            match serde_json::from_str(&data) {
+                Ok(entry) => {
+                    stats.insert(url, entry);
+                }
+                Err(e) => log::warn!("跳过无法解析的仓库统计信息: {}: {}", url, e),
+            }
+        }
+        Ok(stats)
+    }
+
+    /// 获取单个仓库信息
+    pub fn get_repository(&self, repo_id: &str) -> Result<Option<Repository>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            &format!("SELECT {} FROM repositories WHERE id = ?1", REPOSITORY_COLUMNS)
+        )?;
+
+        let repo = stmt.query_row(params![repo_id], repository_from_row).optional()?;
+
+        Ok(repo)
+    }
+
+    /// 初始化默认仓库
+    /// 返回是否添加了新仓库（用于判断是否需要自动扫描）
+    fn initialize_default_repositories(&self) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+
+        // 检查是否已有仓库
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM repositories",
+            [],
+            |row| row.get(0),
+        )?;
+
+        // 如果已有仓库，跳过初始化
+        if count > 0 {
+            return Ok(false);
         }
 
         // 添加默认仓库
@@ -565,4 +1856,315 @@ impl Database {
         )?;
         Ok(())
     }
+
+    /// 删除应用设置
+    pub fn delete_setting(&self, key: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM app_settings WHERE key = ?1",
+            params![key],
+        )?;
+        Ok(())
+    }
+}
+
+fn repository_from_row(row: &rusqlite::Row) -> rusqlite::Result<Repository> {
+    Ok(Repository {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        name: row.get(2)?,
+        description: row.get(3)?,
+        enabled: row.get::<_, i32>(4)? != 0,
+        scan_subdirs: row.get::<_, i32>(5)? != 0,
+        added_at: row.get::<_, String>(6)?.parse().unwrap_or_else(|_| chrono::Utc::now()),
+        last_scanned: row.get::<_, Option<String>>(7)?
+            .and_then(|s| s.parse().ok()),
+        cache_path: row.get(8)?,
+        cached_at: row.get::<_, Option<String>>(9)?
+            .and_then(|s| s.parse().ok()),
+        cached_commit_sha: row.get(10)?,
+        require_signed_commits: row.get::<_, i32>(11)? != 0,
+        commit_signature: row.get::<_, Option<String>>(12)?
+            .and_then(|s| serde_json::from_str(&s).ok()),
+        scan_include_patterns: row.get::<_, Option<String>>(13)?
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default(),
+        scan_exclude_patterns: row.get::<_, Option<String>>(14)?
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default(),
+        scan_max_depth: row.get(15)?,
+        skip_auto_refresh: row.get::<_, i32>(16)? != 0,
+        last_error: row.get(17)?,
+        last_error_at: row.get::<_, Option<String>>(18)?
+            .and_then(|s| s.parse().ok()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_rekey_encrypts_and_decrypts_database() {
+        let dir = tempdir().expect("tempdir");
+        let db_path = dir.path().join("agent-skills.db");
+
+        let db = Database::new(db_path.clone()).unwrap();
+        db.set_setting("probe", "value").unwrap();
+        assert!(!db.is_encrypted());
+
+        let key = Database::generate_key().unwrap();
+        assert_eq!(key.len(), 64);
+        assert!(key.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(key, Database::generate_key().unwrap());
+        db.rekey(None, Some(&key)).unwrap();
+        assert!(db.is_encrypted());
+        assert_eq!(db.get_setting("probe").unwrap().as_deref(), Some("value"));
+        drop(db);
+
+        assert!(Database::new(db_path.clone()).is_err(), "Encrypted database should not open without key");
+
+        let db = Database::open(db_path.clone(), Some(&key)).unwrap();
+        db.rekey(Some(&key), None).unwrap();
+        assert!(!db.is_encrypted());
+        drop(db);
+
+        let db = Database::new(db_path).unwrap();
+        assert_eq!(db.get_setting("probe").unwrap().as_deref(), Some("value"));
+    }
+
+    #[test]
+    fn test_rekey_rotates_key_and_reopens() {
+        let dir = tempdir().expect("tempdir");
+        let db_path = dir.path().join("agent-skills.db");
+        let (old_key, new_key) = (Database::generate_key().unwrap(), Database::generate_key().unwrap());
+
+        let db = Database::new(db_path.clone()).unwrap();
+        db.set_setting("probe", "value").unwrap();
+        db.rekey(None, Some(&old_key)).unwrap();
+        db.rekey(Some(&old_key), Some(&new_key)).unwrap();
+        db.set_setting("after", "rekey").unwrap();
+        drop(db);
+
+        // 替换完成后不残留导出文件与原文件
+        let mut files: Vec<String> = std::fs::read_dir(dir.path()).unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with("agent-skills.db."))
+            .collect();
+        files.retain(|name| !name.ends_with("-wal") && !name.ends_with("-shm"));
+        assert!(files.is_empty(), "unexpected files: {:?}", files);
+
+        assert!(Database::open(db_path.clone(), Some(&old_key)).is_err());
+        let db = Database::open(db_path, Some(&new_key)).unwrap();
+        assert_eq!(db.get_setting("probe").unwrap().as_deref(), Some("value"));
+        assert_eq!(db.get_setting("after").unwrap().as_deref(), Some("rekey"));
+    }
+
+    #[test]
+    fn test_save_and_load_scan_report() {
+        let dir = tempdir().expect("tempdir");
+        let db = Database::new(dir.path().join("agent-skills.db")).unwrap();
+
+        let report = crate::security::SecurityScanner::new()
+            .scan_file("curl https://example.com/install.sh | sh", "SKILL.md", "en")
+            .unwrap();
+        let scanned_at = chrono::Utc::now();
+        db.save_scan_report("skill-1", &report, scanned_at).unwrap();
+
+        let reports = db.get_scan_reports().unwrap();
+        let (loaded, loaded_at) = &reports["skill-1"];
+        assert_eq!(loaded.score, report.score);
+        assert_eq!(loaded.issues.len(), report.issues.len());
+        assert_eq!(loaded.issues[0].line_number, Some(1));
+        assert_eq!(loaded_at.timestamp(), scanned_at.timestamp());
+
+        // curl | sh 命中硬触发规则，每次扫描追加一条历史
+        db.save_scan_report("skill-1", &report, chrono::Utc::now()).unwrap();
+        let history = db.get_hard_trigger_history().unwrap();
+        assert_eq!(history["skill-1"].len(), 2);
+        assert_eq!(history["skill-1"][0].issues, report.hard_trigger_issues);
+        let scores = db.get_score_history("skill-1", &LevelThresholds::default()).unwrap();
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[1].score, report.score);
+        assert_eq!(scores[1].level, report.level);
+
+        // 两次扫描都计入规则命中统计
+        let rule_id = report.issues[0].rule_id.clone().unwrap();
+        let stats = db.get_rule_statistics().unwrap();
+        let stat = stats.iter().find(|s| s.rule_id == rule_id).unwrap();
+        assert_eq!(stat.scan_count, 2);
+        assert_eq!(stat.false_positive_count, 0);
+
+        db.add_false_positive_report(&FalsePositiveReport {
+            id: 0,
+            rule_id: rule_id.clone(),
+            skill_id: Some("skill-1".to_string()),
+            file_path: Some("SKILL.md".to_string()),
+            snippet: "curl <url> | sh".to_string(),
+            note: None,
+            reported_at: chrono::Utc::now().to_rfc3339(),
+        }).unwrap();
+        let stats = db.get_rule_statistics().unwrap();
+        assert_eq!(stats.iter().find(|s| s.rule_id == rule_id).unwrap().false_positive_count, 1);
+        assert_eq!(db.get_false_positive_reports().unwrap()[0].snippet, "curl <url> | sh");
+
+        db.delete_skill("skill-1").unwrap();
+        assert!(db.get_scan_reports().unwrap().is_empty());
+        assert!(db.get_hard_trigger_history().unwrap().is_empty());
+        assert!(db.get_score_history("skill-1", &LevelThresholds::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_pending_operations_roundtrip() {
+        let dir = tempdir().expect("tempdir");
+        let db = Database::new(dir.path().join("agent-skills.db")).unwrap();
+        assert!(db.get_pending_operations().unwrap().is_empty());
+
+        let mut op = PendingOperation::new("skill-1", OperationKind::Update, "/skills/demo");
+        db.save_pending_operation(&op).unwrap();
+
+        op.backup_dir = Some("/cache/skill-backups/demo.bak".to_string());
+        op.step = OperationStep::BackedUp;
+        op.commit_sha = Some("abc123".to_string());
+        db.save_pending_operation(&op).unwrap();
+
+        let operations = db.get_pending_operations().unwrap();
+        assert_eq!(operations.len(), 1);
+        assert_eq!(operations[0].step, OperationStep::BackedUp);
+        assert_eq!(operations[0].backup_dir, op.backup_dir);
+        assert_eq!(operations[0].commit_sha.as_deref(), Some("abc123"));
+        assert_eq!(operations[0].started_at.timestamp(), op.started_at.timestamp());
+
+        db.delete_pending_operation(&op.id).unwrap();
+        assert!(db.get_pending_operations().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_suspicious_updates_roundtrip() {
+        let dir = tempdir().expect("tempdir");
+        let db = Database::new(dir.path().join("agent-skills.db")).unwrap();
+
+        let mut update = SuspiciousUpdate {
+            skill_id: "skill-1".to_string(),
+            skill_name: "demo".to_string(),
+            repository_url: "https://github.com/owner/repo".to_string(),
+            commit_sha: Some("abc1234".to_string()),
+            previous_score: 95,
+            current_score: 20,
+            new_findings: vec!["Critical: curl | sh".to_string()],
+            detected_at: chrono::Utc::now(),
+        };
+        db.save_suspicious_update(&update).unwrap();
+        update.current_score = 10;
+        db.save_suspicious_update(&update).unwrap();
+
+        assert_eq!(db.get_suspicious_updates().unwrap(), vec![update]);
+
+        db.delete_suspicious_update("skill-1").unwrap();
+        assert!(db.get_suspicious_updates().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_mark_skills_source_removed() {
+        let dir = tempdir().expect("tempdir");
+        let db = Database::new(dir.path().join("agent-skills.db")).unwrap();
+
+        let url = "https://github.com/owner/repo";
+        let installed = Skill { installed: true, ..Skill::new("a".to_string(), url.to_string(), "a".to_string()) };
+        let available = Skill::new("b".to_string(), url.to_string(), "b".to_string());
+        db.save_skill(&installed).unwrap();
+        db.save_skill(&available).unwrap();
+
+        assert_eq!(db.delete_uninstalled_skills_by_repository_url(url).unwrap(), 1);
+        assert_eq!(db.mark_skills_source_removed(url).unwrap(), 1);
+
+        let skills = db.get_skills().unwrap();
+        assert_eq!(skills.len(), 1);
+        assert!(skills[0].installed && skills[0].source_removed);
+    }
+
+    #[test]
+    fn test_save_skill_keeps_first_seen_at() {
+        let dir = tempdir().expect("tempdir");
+        let db = Database::new(dir.path().join("agent-skills.db")).unwrap();
+
+        let first_seen = chrono::Utc::now() - chrono::Duration::days(30);
+        let skill = Skill {
+            first_seen_at: Some(first_seen),
+            ..Skill::new("a".to_string(), "https://github.com/owner/repo".to_string(), "a".to_string())
+        };
+        db.save_skill(&skill).unwrap();
+        // 重新扫描生成的新记录不带首次出现时间，保存时保留已有的值
+        db.save_skill(&Skill { first_seen_at: None, ..skill }).unwrap();
+
+        let saved = db.get_skills().unwrap().remove(0);
+        assert_eq!(saved.first_seen_at.map(|t| t.timestamp()), Some(first_seen.timestamp()));
+
+        db.save_skill(&Skill { origin_tool: Some("cursor".to_string()), ..saved }).unwrap();
+        assert_eq!(db.get_skills().unwrap()[0].origin_tool.as_deref(), Some("cursor"));
+    }
+
+    #[test]
+    fn test_relevel_skills() {
+        let dir = tempdir().expect("tempdir");
+        let db = Database::new(dir.path().join("agent-skills.db")).unwrap();
+
+        let skill = |id: &str, score: Option<i32>| Skill {
+            security_score: score,
+            security_level: score.map(|score| LevelThresholds::default().level(score).as_str().to_string()),
+            ..Skill::new(id.to_string(), "https://github.com/owner/repo".to_string(), id.to_string())
+        };
+        db.save_skill(&skill("a", Some(92))).unwrap();
+        db.save_skill(&skill("b", Some(75))).unwrap();
+        db.save_skill(&skill("c", None)).unwrap();
+        assert_eq!(db.relevel_skills(&LevelThresholds::default()).unwrap(), 0);
+
+        let strict = LevelThresholds { safe: 95, low: 85, medium: 60, high: 40 };
+        assert_eq!(db.relevel_skills(&strict).unwrap(), 2);
+        let levels: HashMap<String, Option<String>> =
+            db.get_skills().unwrap().into_iter().map(|s| (s.name, s.security_level)).collect();
+        assert_eq!(levels["a"].as_deref(), Some("Low"));
+        assert_eq!(levels["b"].as_deref(), Some("Medium"));
+        assert_eq!(levels["c"], None);
+    }
+
+    #[test]
+    fn test_skill_tags() {
+        let dir = tempdir().expect("tempdir");
+        let db = Database::new(dir.path().join("agent-skills.db")).unwrap();
+        let tags = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+
+        db.update_skill_tags("a", &tags(&["work", "docs"]), &[]).unwrap();
+        db.update_skill_tags("b", &tags(&["work"]), &[]).unwrap();
+        db.update_skill_tags("a", &tags(&["work"]), &tags(&["docs"])).unwrap();
+        let all = db.get_skill_tags().unwrap();
+        assert_eq!(all["a"], tags(&["work"]));
+        assert_eq!(all["b"], tags(&["work"]));
+
+        // 修改技能 ID 时标签一并迁移，删除技能时一并删除
+        db.rekey_skill("a", "c").unwrap();
+        db.delete_skill("b").unwrap();
+        let all = db.get_skill_tags().unwrap();
+        assert_eq!(all.keys().collect::<Vec<_>>(), vec!["c"]);
+    }
+
+    #[test]
+    fn test_skill_install_targets() {
+        let dir = tempdir().expect("tempdir");
+        let db = Database::new(dir.path().join("agent-skills.db")).unwrap();
+
+        db.set_skill_install_target("a", "/home/me/.cursor/skills").unwrap();
+        db.set_skill_install_target("a", "/home/me/.codex/skills").unwrap();
+        db.set_skill_install_target("b", "/home/me/.cursor/skills").unwrap();
+        assert_eq!(db.get_skill_install_target("a").unwrap().as_deref(), Some("/home/me/.codex/skills"));
+
+        db.rekey_skill("a", "c").unwrap();
+        db.delete_skill_install_target("b").unwrap();
+        assert_eq!(db.get_skill_install_target("a").unwrap(), None);
+        assert_eq!(
+            db.get_skill_install_targets().unwrap(),
+            HashMap::from([("c".to_string(), "/home/me/.codex/skills".to_string())])
+        );
+    }
 }
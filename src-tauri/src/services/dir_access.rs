@@ -0,0 +1,287 @@
+use crate::models::{AppError, ErrorKind};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use skillguard_core::paths::extended_length_path;
+use std::fs;
+use std::io;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// 检查目录时最多遍历的条目数
+const MAX_CHECKED_ENTRIES: usize = 5000;
+/// 错误信息中最多列出的文件数
+const MAX_LISTED_FILES: usize = 3;
+
+/// 云同步客户端的目录名（目录名相同，或以 "名称 - "、"名称-" 开头，如 "OneDrive - Contoso"、"GoogleDrive-me@example.com"）
+const CLOUD_SYNC_DIRS: &[(&str, &str)] = &[
+    ("OneDrive", "OneDrive"),
+    ("Dropbox", "Dropbox"),
+    ("iCloud Drive", "iCloud Drive"),
+    ("iCloudDrive", "iCloud Drive"),
+    ("Mobile Documents", "iCloud Drive"),
+    ("Google Drive", "Google Drive"),
+    ("GoogleDrive", "Google Drive"),
+];
+
+/// 目录的读写检查结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirAccessReport {
+    pub path: String,
+    /// 目录所在的云同步服务（OneDrive、Dropbox 等），未检测到时为 None
+    pub cloud_provider: Option<String>,
+    /// 目录（不存在时为最近的已存在上级目录）无法写入
+    pub not_writable: bool,
+    /// 只读的文件与目录（相对路径，`.` 表示目录本身）
+    pub read_only_files: Vec<String>,
+    /// 尚未下载到本地的云同步占位文件（相对路径）
+    pub placeholder_files: Vec<String>,
+}
+
+/// 修复选项（前端在用户选择后传入）
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DirFixOptions {
+    /// 去掉文件与目录的只读属性
+    pub clear_read_only: bool,
+    /// 读取占位文件，让同步客户端下载文件内容
+    pub download_placeholders: bool,
+}
+
+impl DirAccessReport {
+    pub fn is_ready(&self) -> bool {
+        !self.not_writable && self.read_only_files.is_empty() && self.placeholder_files.is_empty()
+    }
+
+    /// 无法直接写入时返回说明原因与处理方式的错误，在开始复制或删除前调用
+    pub fn ensure_ready(&self) -> Result<(), AppError> {
+        if self.is_ready() {
+            return Ok(());
+        }
+
+        let mut problems = Vec::new();
+        if self.not_writable {
+            problems.push("目录不可写，请检查目录权限，或是否被同步客户端、杀毒软件锁定".to_string());
+        }
+        if !self.read_only_files.is_empty() {
+            problems.push(format!(
+                "{} 个文件或目录为只读（{}），可使用“修复只读属性”后重试",
+                self.read_only_files.len(),
+                list_files(&self.read_only_files)
+            ));
+        }
+        if !self.placeholder_files.is_empty() {
+            problems.push(format!(
+                "{} 个文件尚未从 {} 下载到本地（{}），请联网后使用“下载云端文件”，或在同步客户端中将该目录设为始终保留在此设备上后重试",
+                self.placeholder_files.len(),
+                self.cloud_provider.as_deref().unwrap_or("云端"),
+                list_files(&self.placeholder_files)
+            ));
+        }
+        Err(AppError::new(
+            ErrorKind::PermissionDenied,
+            format!("目录暂时无法写入: {}\n{}", self.path, problems.join("\n")),
+        ))
+    }
+}
+
+fn list_files(files: &[String]) -> String {
+    let mut listed = files.iter().take(MAX_LISTED_FILES).cloned().collect::<Vec<_>>().join("、");
+    if files.len() > MAX_LISTED_FILES {
+        listed.push_str(" 等");
+    }
+    listed
+}
+
+/// 路径所在的云同步服务
+pub fn cloud_provider(path: &Path) -> Option<&'static str> {
+    path.components().find_map(|component| {
+        let name = component.as_os_str().to_str()?;
+        CLOUD_SYNC_DIRS.iter().find_map(|(dir, provider)| {
+            let matches = name == *dir
+                || name.strip_prefix(dir).is_some_and(|rest| rest.starts_with(" - ") || rest.starts_with('-'));
+            matches.then_some(*provider)
+        })
+    })
+}
+
+/// 检查目录能否写入；`recursive` 时同时检查其中的只读文件与云同步占位文件（用于将被覆盖或删除的技能目录）
+pub fn check_dir(dir: &Path, recursive: bool) -> DirAccessReport {
+    let mut report = DirAccessReport {
+        path: dir.to_string_lossy().to_string(),
+        cloud_provider: cloud_provider(dir).map(str::to_string),
+        not_writable: !is_writable(dir),
+        ..DirAccessReport::default()
+    };
+    if !recursive || !dir.is_dir() {
+        return report;
+    }
+
+    let root = extended_length_path(dir);
+    for entry in WalkDir::new(&root).follow_links(false).into_iter().take(MAX_CHECKED_ENTRIES).flatten() {
+        let Ok(metadata) = entry.metadata() else { continue };
+        let relative = match entry.path().strip_prefix(&root) {
+            Ok(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+            Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+            Err(_) => continue,
+        };
+        if metadata.permissions().readonly() && !entry.file_type().is_symlink() {
+            report.read_only_files.push(relative.clone());
+        }
+        if entry.file_type().is_file() && is_placeholder(entry.path(), &metadata) {
+            report.placeholder_files.push(relative);
+        }
+    }
+    report
+}
+
+/// 检查安装目录：必须是绝对路径，目录（或其上级目录，安装时会创建）已存在且可以写入
+pub fn ensure_install_target(dir: &Path) -> Result<(), AppError> {
+    if !dir.is_absolute() {
+        return Err(AppError::invalid_input(format!("安装目录必须是绝对路径: {}", dir.display())));
+    }
+    if dir.exists() && !dir.is_dir() {
+        return Err(AppError::invalid_input(format!("安装目录不是文件夹: {}", dir.display())));
+    }
+    if !dir.exists() && !dir.parent().is_some_and(Path::is_dir) {
+        return Err(AppError::not_found(format!("安装目录及其上级目录不存在: {}", dir.display())));
+    }
+    check_dir(dir, false).ensure_ready()
+}
+
+/// 修复目录后重新检查
+pub fn fix_dir(dir: &Path, options: DirFixOptions) -> Result<DirAccessReport> {
+    let report = check_dir(dir, true);
+
+    if options.clear_read_only {
+        for relative in &report.read_only_files {
+            let path = extended_length_path(&dir.join(relative));
+            make_writable(&path).context(format!("无法去除只读属性: {:?}", path))?;
+        }
+        log::info!("已去除 {} 个文件的只读属性: {:?}", report.read_only_files.len(), dir);
+    }
+
+    if options.download_placeholders {
+        for relative in &report.placeholder_files {
+            if let Err(e) = download_placeholder(&dir.join(relative)) {
+                log::warn!("下载云端文件失败: {}, 错误: {}", relative, e);
+            }
+        }
+    }
+
+    Ok(check_dir(dir, true))
+}
+
+/// 在目录（不存在时为最近的已存在上级目录）中创建临时文件，检查能否写入
+fn is_writable(dir: &Path) -> bool {
+    let Some(existing) = dir.ancestors().find(|path| path.is_dir()) else {
+        return false;
+    };
+    tempfile::Builder::new()
+        .prefix(".skillguard-write-test-")
+        .tempfile_in(extended_length_path(existing))
+        .is_ok()
+}
+
+/// iCloud 未下载的文件以 `.<文件名>.icloud` 占位
+fn is_icloud_stub(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.') && name.ends_with(".icloud") && name.len() > ".icloud".len() + 1)
+}
+
+/// Windows 云文件（OneDrive、Dropbox 等）的占位文件带有离线或按需下载属性
+#[cfg(windows)]
+fn is_placeholder(path: &Path, metadata: &fs::Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_OFFLINE: u32 = 0x1000;
+    const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x40000;
+    const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x400000;
+    let recall = FILE_ATTRIBUTE_OFFLINE | FILE_ATTRIBUTE_RECALL_ON_OPEN | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS;
+    metadata.file_attributes() & recall != 0 || is_icloud_stub(path)
+}
+
+/// macOS 文件提供程序（OneDrive、Dropbox、Google Drive）未下载的文件不占用磁盘块
+#[cfg(target_os = "macos")]
+fn is_placeholder(path: &Path, metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    is_icloud_stub(path) || (metadata.len() > 0 && metadata.blocks() == 0)
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn is_placeholder(path: &Path, _metadata: &fs::Metadata) -> bool {
+    is_icloud_stub(path)
+}
+
+/// 读取文件内容触发同步客户端下载；iCloud 占位文件通过 brctl 请求下载
+fn download_placeholder(path: &Path) -> io::Result<()> {
+    if is_icloud_stub(path) {
+        let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+        let real_path = path.with_file_name(&name[1..name.len() - ".icloud".len()]);
+        let status = std::process::Command::new("brctl").arg("download").arg(&real_path).status()?;
+        if !status.success() {
+            return Err(io::Error::other(format!("brctl download 失败: {}", status)));
+        }
+        return Ok(());
+    }
+    let mut file = fs::File::open(extended_length_path(path))?;
+    io::copy(&mut file, &mut io::sink())?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn make_writable(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = fs::metadata(path)?.permissions();
+    permissions.set_mode(permissions.mode() | 0o200);
+    fs::set_permissions(path, permissions)
+}
+
+// Windows 上只读属性只有一个开关
+#[cfg(not(unix))]
+#[allow(clippy::permissions_set_readonly_false)]
+fn make_writable(path: &Path) -> io::Result<()> {
+    let mut permissions = fs::metadata(path)?.permissions();
+    permissions.set_readonly(false);
+    fs::set_permissions(path, permissions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_and_fix_dir() {
+        assert_eq!(cloud_provider(Path::new("/Users/me/OneDrive - Contoso/.claude/skills")), Some("OneDrive"));
+        assert_eq!(cloud_provider(Path::new("/Users/me/Library/CloudStorage/GoogleDrive-me@example.com/skills")), Some("Google Drive"));
+        assert_eq!(cloud_provider(Path::new("/home/me/Dropbox/skills")), Some("Dropbox"));
+        assert_eq!(cloud_provider(Path::new("/home/me/OneDriveBackup/skills")), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let skill = dir.path().join("pdf");
+        fs::create_dir_all(skill.join("scripts")).unwrap();
+        fs::write(skill.join("SKILL.md"), "---\nname: pdf\n---\n").unwrap();
+        fs::write(skill.join("scripts/run.py"), "print(1)\n").unwrap();
+        fs::write(skill.join("scripts/.data.json.icloud"), "").unwrap();
+        let mut permissions = fs::metadata(skill.join("scripts/run.py")).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(skill.join("scripts/run.py"), permissions).unwrap();
+
+        let report = check_dir(&skill, true);
+        assert_eq!(report.read_only_files, vec!["scripts/run.py".to_string()]);
+        assert_eq!(report.placeholder_files, vec!["scripts/.data.json.icloud".to_string()]);
+        let error = report.ensure_ready().unwrap_err();
+        assert_eq!(error.kind, ErrorKind::PermissionDenied);
+        assert!(error.message.contains("scripts/run.py"), "{}", error.message);
+
+        // 不递归时只检查目录本身能否写入
+        assert!(check_dir(&skill, false).is_ready());
+        assert!(ensure_install_target(dir.path()).is_ok());
+        assert!(ensure_install_target(&dir.path().join("new-tool/skills")).is_err());
+        assert!(ensure_install_target(&skill.join("SKILL.md")).is_err());
+
+        let fixed = fix_dir(&skill, DirFixOptions { clear_read_only: true, ..Default::default() }).unwrap();
+        assert!(fixed.read_only_files.is_empty());
+        assert!(!fs::metadata(skill.join("scripts/run.py")).unwrap().permissions().readonly());
+    }
+}
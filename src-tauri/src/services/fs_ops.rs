@@ -0,0 +1,701 @@
+use crate::models::{AppError, ErrorKind, ManifestNames, IGNORED_DISCOVERY_DIRS, SKILL_MANIFEST_NAME};
+use crate::security::ScanFilter;
+use skillguard_core::paths::{self, extended_length_path};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+/// 分块复制文件时的缓冲区大小（每复制一块检查一次取消标记）
+const COPY_CHUNK_SIZE: usize = 256 * 1024;
+/// 并行复制的最大线程数
+const MAX_COPY_WORKERS: usize = 4;
+/// 单个文件的最大尝试次数（文件被临时占用、校验不一致时重试）
+const COPY_ATTEMPTS: usize = 3;
+/// 重试间隔
+const COPY_RETRY_DELAY: Duration = Duration::from_millis(200);
+/// 汇总错误中最多列出的失败文件数
+const MAX_REPORTED_FAILURES: usize = 10;
+/// 两次进度回调之间的最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 操作被取消时返回的错误信息
+pub const CANCELLED_MESSAGE: &str = "操作已取消";
+
+/// 当前系统的文件系统默认不区分大小写（Windows、macOS），仅大小写不同的文件会互相覆盖
+pub const CASE_INSENSITIVE_FS: bool = cfg!(any(windows, target_os = "macos"));
+
+/// 文件操作进度
+#[derive(Debug, Clone, Serialize)]
+pub struct FsProgress {
+    /// 已复制的文件数
+    pub files: usize,
+    /// 已复制的字节数
+    pub bytes: u64,
+    /// 最近完成的文件（相对路径）
+    pub current: String,
+}
+
+/// 进度回调
+pub type ProgressHandler = Arc<dyn Fn(&FsProgress) + Send + Sync>;
+
+/// 文件操作上下文：取消标记与进度回调
+///
+/// `FsContext::default()` 不可取消、不报告进度，用于失败后的恢复等必须执行完的操作
+#[derive(Clone, Default)]
+pub struct FsContext {
+    cancelled: Option<Arc<AtomicBool>>,
+    progress: Option<ProgressHandler>,
+}
+
+impl FsContext {
+    pub fn new(cancelled: Arc<AtomicBool>, progress: Option<ProgressHandler>) -> Self {
+        Self {
+            cancelled: Some(cancelled),
+            progress,
+        }
+    }
+
+    /// 若已请求取消则返回错误
+    pub fn check_cancelled(&self) -> Result<()> {
+        if self.cancelled.as_ref().is_some_and(|flag| flag.load(Ordering::SeqCst)) {
+            return Err(AppError::new(ErrorKind::Cancelled, CANCELLED_MESSAGE).into());
+        }
+        Ok(())
+    }
+}
+
+/// 复制失败的文件
+#[derive(Debug, Clone, Serialize)]
+pub struct CopyFailure {
+    /// 相对于源目录的路径
+    pub path: String,
+    pub error: String,
+}
+
+/// 目录复制结果
+#[derive(Debug, Default, Serialize)]
+pub struct CopyReport {
+    /// 成功复制并校验通过的文件数
+    pub files: usize,
+    pub bytes: u64,
+    /// 重试后仍失败的文件（其余文件照常复制）
+    pub failures: Vec<CopyFailure>,
+}
+
+impl CopyReport {
+    /// 存在失败文件时返回汇总错误，否则返回复制的文件数
+    pub fn ensure_complete(self) -> Result<usize> {
+        if self.failures.is_empty() {
+            return Ok(self.files);
+        }
+
+        let mut message = format!("{} 个文件复制失败（已成功复制 {} 个）：", self.failures.len(), self.files);
+        for failure in self.failures.iter().take(MAX_REPORTED_FAILURES) {
+            message.push_str(&format!("\n- {}: {}", failure.path, failure.error));
+        }
+        if self.failures.len() > MAX_REPORTED_FAILURES {
+            message.push_str(&format!("\n- ……另有 {} 个文件", self.failures.len() - MAX_REPORTED_FAILURES));
+        }
+        anyhow::bail!(message)
+    }
+}
+
+/// 按时间间隔节流的进度报告（多个复制线程共享）
+struct ProgressReporter {
+    ctx: FsContext,
+    progress: FsProgress,
+    last_report: Option<Instant>,
+}
+
+impl ProgressReporter {
+    fn new(ctx: &FsContext) -> Self {
+        Self {
+            ctx: ctx.clone(),
+            progress: FsProgress {
+                files: 0,
+                bytes: 0,
+                current: String::new(),
+            },
+            last_report: None,
+        }
+    }
+
+    fn report(&mut self, force: bool) {
+        let Some(handler) = &self.ctx.progress else {
+            return;
+        };
+        if !force && self.last_report.is_some_and(|last| last.elapsed() < PROGRESS_INTERVAL) {
+            return;
+        }
+        self.last_report = Some(Instant::now());
+        handler(&self.progress);
+    }
+}
+
+/// 待复制的文件
+struct CopyJob {
+    src: PathBuf,
+    dst: PathBuf,
+    relative: String,
+}
+
+/// 在阻塞线程池中执行文件操作，避免占用异步运行时的工作线程
+pub async fn run_blocking<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .context("文件操作线程异常退出")?
+}
+
+/// 递归复制目录（在阻塞线程池中执行）
+pub async fn copy_dir_async(src: PathBuf, dst: PathBuf, ctx: FsContext) -> Result<CopyReport> {
+    run_blocking(move || copy_dir(&src, &dst, &ctx)).await
+}
+
+/// 递归复制目录
+///
+/// 会阻塞当前线程，异步代码中应使用 [`copy_dir_async`]。
+/// 先创建目录结构，再由有限个线程并行复制文件；每个文件按块复制并计算 SHA256，
+/// 写入后重新读取目标文件校验哈希。单个文件失败（含校验不一致）会重试，
+/// 仍失败时记入 [`CopyReport::failures`] 并继续复制其余文件。
+/// 取消时返回 [`CANCELLED_MESSAGE`] 错误，已复制的部分由调用方清理
+pub fn copy_dir(src: &Path, dst: &Path, ctx: &FsContext) -> Result<CopyReport> {
+    log::info!("复制目录: {:?} -> {:?}", src, dst);
+
+    let src = extended_length_path(src);
+    let dst = extended_length_path(dst);
+
+    let mut jobs = Vec::new();
+    collect_jobs(&src, &dst, "", &mut jobs, ctx)?;
+
+    if CASE_INSENSITIVE_FS {
+        let mut collisions = CaseCollisions::default();
+        for job in &jobs {
+            collisions.insert(&job.relative);
+        }
+        if let Some(err) = collisions.into_error() {
+            return Err(err);
+        }
+    }
+
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .clamp(1, MAX_COPY_WORKERS)
+        .min(jobs.len().max(1));
+
+    let next_job = AtomicUsize::new(0);
+    let reporter = Mutex::new(ProgressReporter::new(ctx));
+    let failures = Mutex::new(Vec::new());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                while ctx.check_cancelled().is_ok() {
+                    let Some(job) = jobs.get(next_job.fetch_add(1, Ordering::SeqCst)) else {
+                        break;
+                    };
+
+                    match copy_file_verified(job, ctx, &reporter) {
+                        Ok(()) => {
+                            let mut reporter = reporter.lock().unwrap();
+                            reporter.progress.files += 1;
+                            reporter.progress.current = job.relative.clone();
+                            reporter.report(false);
+                        }
+                        Err(_) if ctx.check_cancelled().is_err() => break,
+                        Err(e) => {
+                            log::warn!("复制文件失败: {}: {}", job.relative, e);
+                            failures.lock().unwrap().push(CopyFailure {
+                                path: job.relative.clone(),
+                                error: e.to_string(),
+                            });
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    ctx.check_cancelled()?;
+
+    let mut reporter = reporter.into_inner().unwrap();
+    reporter.report(true);
+    let mut failures = failures.into_inner().unwrap();
+    failures.sort_by(|a, b| a.path.cmp(&b.path));
+
+    log::info!(
+        "目录复制完成: {:?} ({} 个文件, {} bytes, {} 个失败)",
+        dst, reporter.progress.files, reporter.progress.bytes, failures.len()
+    );
+    Ok(CopyReport {
+        files: reporter.progress.files,
+        bytes: reporter.progress.bytes,
+        failures,
+    })
+}
+
+/// 创建目标目录结构并收集待复制的文件
+fn collect_jobs(src: &Path, dst: &Path, relative: &str, jobs: &mut Vec<CopyJob>, ctx: &FsContext) -> Result<()> {
+    ctx.check_cancelled()?;
+
+    // 确保目标目录存在
+    if !dst.exists() {
+        fs::create_dir_all(dst)
+            .context(format!("无法创建目标目录: {:?}", dst))?;
+        log::debug!("创建目标目录: {:?}", dst);
+    }
+
+    // 遍历源目录
+    for entry in fs::read_dir(src)
+        .context(format!("无法读取源目录: {:?}", src))? {
+        let entry = entry
+            .context(format!("读取目录项失败: {:?}", src))?;
+        let file_type = entry.file_type()
+            .context(format!("获取文件类型失败: {:?}", entry.path()))?;
+        let file_name = entry.file_name();
+        let child_relative = if relative.is_empty() {
+            file_name.to_string_lossy().to_string()
+        } else {
+            format!("{}/{}", relative, file_name.to_string_lossy())
+        };
+        if cfg!(windows) && paths::is_windows_reserved_name(&file_name.to_string_lossy()) {
+            return Err(reserved_name_error(&child_relative));
+        }
+
+        if file_type.is_dir() {
+            collect_jobs(&entry.path(), &dst.join(&file_name), &child_relative, jobs, ctx)?;
+        } else if file_type.is_file() {
+            jobs.push(CopyJob {
+                src: entry.path(),
+                dst: dst.join(&file_name),
+                relative: child_relative,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// 复制单个文件并校验哈希，失败时重试
+fn copy_file_verified(job: &CopyJob, ctx: &FsContext, reporter: &Mutex<ProgressReporter>) -> Result<()> {
+    let mut attempt = 1;
+    loop {
+        let result = copy_file(&job.src, &job.dst, ctx, reporter).and_then(|(bytes, source_hash)| {
+            let written_hash = hash_file(&job.dst)?;
+            if written_hash != source_hash {
+                anyhow::bail!("校验失败：目标文件内容与源文件不一致");
+            }
+            // 校验通过后再设置权限，避免只读源文件导致重试时无法覆盖
+            copy_metadata(&fs::metadata(&job.src)?, &job.dst)?;
+            log::debug!("已复制文件: {} ({} bytes)", job.relative, bytes);
+            Ok(())
+        });
+
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e) if ctx.check_cancelled().is_err() => return Err(e),
+            Err(e) if attempt < COPY_ATTEMPTS => {
+                log::debug!("复制文件失败，第 {} 次重试: {}: {}", attempt, job.relative, e);
+                attempt += 1;
+                std::thread::sleep(COPY_RETRY_DELAY);
+            }
+            Err(e) => return Err(copy_error(&job.src, &job.dst, e)),
+        }
+    }
+}
+
+/// 分块复制单个文件，返回复制的字节数与源内容的 SHA256
+fn copy_file(src: &Path, dst: &Path, ctx: &FsContext, reporter: &Mutex<ProgressReporter>) -> Result<(u64, String)> {
+    let mut reader = fs::File::open(src)?;
+    let mut writer = fs::File::create(dst)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; COPY_CHUNK_SIZE];
+    let mut copied = 0u64;
+
+    loop {
+        ctx.check_cancelled()?;
+
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        hasher.update(&buffer[..read]);
+        writer.write_all(&buffer[..read])?;
+        copied += read as u64;
+
+        let mut reporter = reporter.lock().unwrap();
+        reporter.progress.bytes += read as u64;
+        reporter.report(false);
+    }
+
+    writer.flush()?;
+    Ok((copied, hex::encode(hasher.finalize())))
+}
+
+/// 将源文件的修改时间与权限（含 Unix 可执行位）应用到目标文件
+pub fn copy_metadata(src: &fs::Metadata, dst: &Path) -> io::Result<()> {
+    if let Ok(modified) = src.modified() {
+        let file = fs::File::options().write(true).open(dst)?;
+        file.set_times(fs::FileTimes::new().set_modified(modified))?;
+    }
+    fs::set_permissions(dst, src.permissions())
+}
+
+fn hash_file(path: &Path) -> Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// 文件名为 Windows 保留名称（如 aux、con、nul.txt）时的错误
+pub fn reserved_name_error(relative: &str) -> anyhow::Error {
+    AppError::invalid_input(format!(
+        "技能包含 Windows 不支持的文件名: {}\nCON、PRN、AUX、NUL、COM1-9、LPT1-9（含带扩展名的形式）以及以空格或 . 结尾的名称无法在 Windows 上使用，请联系技能作者重命名该文件",
+        relative
+    ))
+    .into()
+}
+
+/// 检测仅大小写不同的相对路径（以 `/` 分隔）
+#[derive(Debug, Default)]
+pub struct CaseCollisions {
+    seen: HashMap<String, String>,
+    /// (先出现的路径, 与之冲突的路径)
+    pub found: Vec<(String, String)>,
+}
+
+impl CaseCollisions {
+    /// 记录路径，与已记录的路径仅大小写不同时返回 true
+    pub fn insert(&mut self, path: &str) -> bool {
+        match self.seen.get(&path.to_lowercase()) {
+            Some(existing) if existing != path => {
+                self.found.push((existing.clone(), path.to_string()));
+                true
+            }
+            Some(_) => false,
+            None => {
+                self.seen.insert(path.to_lowercase(), path.to_string());
+                false
+            }
+        }
+    }
+
+    /// 存在冲突时返回列出冲突文件的错误
+    pub fn into_error(self) -> Option<anyhow::Error> {
+        if self.found.is_empty() {
+            return None;
+        }
+        let mut list: Vec<String> = self.found
+            .iter()
+            .take(MAX_REPORTED_FAILURES)
+            .map(|(existing, path)| format!("{} 与 {}", existing, path))
+            .collect();
+        if self.found.len() > MAX_REPORTED_FAILURES {
+            list.push(format!("等 {} 处", self.found.len()));
+        }
+        Some(AppError::invalid_input(format!(
+            "以下文件仅大小写不同，在不区分大小写的文件系统上会互相覆盖: {}\n请联系技能作者重命名其中一个文件",
+            list.join("，")
+        )).into())
+    }
+}
+
+/// 为复制失败提供详细的错误信息
+fn copy_error(src_path: &Path, dst_path: &Path, err: anyhow::Error) -> anyhow::Error {
+    let Some(io_err) = err.downcast_ref::<io::Error>() else {
+        return err;
+    };
+
+    let error_msg = if io_err.raw_os_error() == Some(5) {
+        format!(
+            "复制文件失败（拒绝访问）\n文件: {:?}\n\n可能原因：\n1. 目标文件正在被其他程序使用\n2. 文件被设置为只读\n3. 权限不足\n4. 杀毒软件拦截\n\n建议：\n1. 关闭可能打开该文件的程序\n2. 检查文件是否为只读\n3. 以管理员权限运行\n\n原始错误: {}",
+            src_path.file_name().unwrap_or_default(), io_err
+        )
+    } else {
+        format!("复制文件失败\n源: {:?}\n目标: {:?}\n错误: {}", src_path, dst_path, io_err)
+    };
+    anyhow::anyhow!(error_msg)
+}
+
+/// 递归删除目录，Windows 下使用扩展长度路径（可删除超长路径与保留名称的文件）
+pub fn remove_dir_all(path: impl AsRef<Path>) -> io::Result<()> {
+    fs::remove_dir_all(extended_length_path(path.as_ref()))
+}
+
+/// 删除文件或目录（在阻塞线程池中执行），路径不存在时直接返回
+pub async fn remove_path(path: PathBuf) -> io::Result<()> {
+    let path = extended_length_path(&path);
+    match tokio::fs::symlink_metadata(&path).await {
+        Ok(metadata) if metadata.is_dir() => tokio::fs::remove_dir_all(&path).await,
+        Ok(_) => tokio::fs::remove_file(&path).await,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// 计算目录中所有文件的总大小
+pub fn dir_size(path: &Path) -> io::Result<u64> {
+    let mut size = 0;
+
+    for entry in WalkDir::new(extended_length_path(path)).into_iter().filter_map(|e| e.ok()) {
+        if entry.file_type().is_file() {
+            size += entry.metadata()?.len();
+        }
+    }
+
+    Ok(size)
+}
+
+/// 目录内容的 SHA256（见 [`SecurityScanner::calculate_path_checksum`](crate::security::SecurityScanner::calculate_path_checksum)），
+/// 与文件的修改时间、权限无关，用于在不同机器上校验同一份技能内容
+pub fn dir_hash(dir: &Path) -> Result<String> {
+    crate::security::path_checksum(dir)
+}
+
+/// 技能目录安装后的内容哈希：技能清单按安装时的规则改名为 SKILL.md 后计算（见 [`normalize_skill_manifest`]），
+/// 仓库缓存中使用其他清单名的技能与安装后的目录得到相同的哈希
+pub fn installed_dir_hash(dir: &Path, names: &ManifestNames) -> Result<String> {
+    let manifest = find_skill_manifest(dir, names).and_then(|path| path.file_name().map(|name| name.to_string_lossy().to_string()));
+    crate::security::path_checksum_renamed(dir, |relative| {
+        (Some(relative) == manifest.as_deref()).then(|| SKILL_MANIFEST_NAME.to_string())
+    })
+}
+
+/// 将安装目录中的技能清单统一改名为 SKILL.md（仓库中使用其他清单名时，工具只识别 SKILL.md），返回是否改名
+pub fn normalize_skill_manifest(dir: &Path, names: &ManifestNames) -> Result<bool> {
+    let Some(manifest) = find_skill_manifest(dir, names) else {
+        return Ok(false);
+    };
+    if manifest.file_name().is_some_and(|name| name == SKILL_MANIFEST_NAME) {
+        return Ok(false);
+    }
+
+    let target = dir.join(SKILL_MANIFEST_NAME);
+    fs::rename(&manifest, &target).with_context(|| format!("无法将技能清单改名为 {}: {:?}", SKILL_MANIFEST_NAME, manifest))?;
+    log::info!("技能清单已改名为 {}: {:?}", SKILL_MANIFEST_NAME, manifest);
+    Ok(true)
+}
+
+/// 在目录中查找技能清单文件（SKILL.md 或配置的其他名称，不区分大小写）
+pub fn find_skill_manifest(dir: &Path, names: &ManifestNames) -> Option<PathBuf> {
+    let file_names: Vec<String> = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+
+    names.pick(file_names.iter().map(String::as_str)).map(|name| dir.join(name))
+}
+
+/// 在仓库目录中查找技能（包含清单文件的目录），返回按路径排序的清单文件路径
+///
+/// 与通过 API 扫描的规则一致：不检查根目录本身，技能目录不再向下查找，
+/// 被排除或属于 [`IGNORED_DISCOVERY_DIRS`] 的目录整体跳过，不跟随符号链接；
+/// max_depth 为技能目录相对 root 的最大深度
+pub fn find_skill_manifests(root: &Path, filter: &ScanFilter, max_depth: usize, names: &ManifestNames) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut walker = WalkDir::new(root)
+        .min_depth(1)
+        .max_depth(max_depth)
+        .sort_by_file_name()
+        .into_iter();
+
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else { continue };
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+
+        let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+        let ignored = entry.file_name().to_str().is_some_and(|name| IGNORED_DISCOVERY_DIRS.contains(&name));
+        if ignored || filter.is_excluded(relative) {
+            walker.skip_current_dir();
+            continue;
+        }
+
+        if let Some(manifest) = find_skill_manifest(entry.path(), names) {
+            if filter.should_scan_file(relative) {
+                found.push(manifest);
+            }
+            walker.skip_current_dir();
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dir_hash() {
+        let a = tempfile::tempdir().unwrap();
+        let b = tempfile::tempdir().unwrap();
+        for dir in [a.path(), b.path()] {
+            fs::create_dir_all(dir.join("scripts")).unwrap();
+            fs::write(dir.join("SKILL.md"), "---\nname: pdf\n---\n").unwrap();
+            fs::write(dir.join("scripts/run.py"), "print(1)\n").unwrap();
+        }
+        assert_eq!(dir_hash(a.path()).unwrap(), dir_hash(b.path()).unwrap());
+
+        // 内容或文件路径变化都会改变哈希
+        fs::write(b.path().join("scripts/run.py"), "print(2)\n").unwrap();
+        assert_ne!(dir_hash(a.path()).unwrap(), dir_hash(b.path()).unwrap());
+        fs::write(b.path().join("scripts/run.py"), "print(1)\n").unwrap();
+        fs::rename(b.path().join("scripts/run.py"), b.path().join("scripts/main.py")).unwrap();
+        assert_ne!(dir_hash(a.path()).unwrap(), dir_hash(b.path()).unwrap());
+    }
+
+    #[test]
+    fn test_normalize_skill_manifest() {
+        let names = ManifestNames::new(&["SKILLS.md".to_string()]);
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("SKILLS.md"), "---\nname: pdf\n---\n").unwrap();
+        fs::write(dir.path().join("run.py"), "print(1)\n").unwrap();
+
+        let hash = installed_dir_hash(dir.path(), &names).unwrap();
+        assert_ne!(hash, dir_hash(dir.path()).unwrap());
+        assert!(normalize_skill_manifest(dir.path(), &names).unwrap());
+        assert!(dir.path().join(SKILL_MANIFEST_NAME).exists());
+        assert!(!dir.path().join("SKILLS.md").exists());
+        assert_eq!(dir_hash(dir.path()).unwrap(), hash);
+
+        // 已经是 SKILL.md 或没有清单时不改动
+        assert!(!normalize_skill_manifest(dir.path(), &names).unwrap());
+        let empty = tempfile::tempdir().unwrap();
+        assert!(!normalize_skill_manifest(empty.path(), &names).unwrap());
+    }
+
+    #[test]
+    fn test_case_collisions() {
+        let mut collisions = CaseCollisions::default();
+        assert!(!collisions.insert("README.md"));
+        assert!(!collisions.insert("README.md"));
+        assert!(!collisions.insert("docs/guide.md"));
+        assert!(collisions.insert("Readme.md"));
+        assert!(collisions.insert("Docs/Guide.md"));
+        assert_eq!(collisions.found[0], ("README.md".to_string(), "Readme.md".to_string()));
+        let message = collisions.into_error().unwrap().to_string();
+        assert!(message.contains("docs/guide.md 与 Docs/Guide.md"), "{}", message);
+        assert!(CaseCollisions::default().into_error().is_none());
+    }
+
+    #[test]
+    fn test_copy_dir_progress_and_cancel() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let src = root.join("src");
+        fs::create_dir_all(src.join("nested")).unwrap();
+        fs::write(src.join("SKILL.md"), "# skill").unwrap();
+        fs::write(src.join("nested").join("data.bin"), vec![7u8; COPY_CHUNK_SIZE * 2 + 1]).unwrap();
+
+        let reports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reports);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let ctx = FsContext::new(
+            Arc::clone(&cancelled),
+            Some(Arc::new(move |p: &FsProgress| sink.lock().unwrap().push((p.files, p.bytes)))),
+        );
+
+        let dst = root.join("dst");
+        assert_eq!(copy_dir(&src, &dst, &ctx).unwrap().ensure_complete().unwrap(), 2);
+        assert_eq!(fs::read(dst.join("nested").join("data.bin")).unwrap().len(), COPY_CHUNK_SIZE * 2 + 1);
+        assert_eq!(reports.lock().unwrap().last(), Some(&(2, (COPY_CHUNK_SIZE * 2 + 8) as u64)));
+
+        cancelled.store(true, Ordering::SeqCst);
+        let err = copy_dir(&src, &root.join("cancelled"), &ctx).unwrap_err();
+        assert_eq!(err.to_string(), CANCELLED_MESSAGE);
+        assert!(copy_dir(&src, &root.join("uncancellable"), &FsContext::default()).is_ok());
+    }
+
+    #[test]
+    fn test_copy_dir_reports_failed_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let src = root.join("src");
+        fs::create_dir_all(src.join("nested")).unwrap();
+        fs::write(src.join("SKILL.md"), "# skill").unwrap();
+        fs::write(src.join("nested").join("run.sh"), "echo ok").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(src.join("nested").join("run.sh"), fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        // 目标位置已有同名目录，SKILL.md 无法写入，其余文件照常复制
+        let dst = root.join("dst");
+        fs::create_dir_all(dst.join("SKILL.md")).unwrap();
+
+        let report = copy_dir(&src, &dst, &FsContext::default()).unwrap();
+        assert_eq!(report.files, 1);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].path, "SKILL.md");
+        assert_eq!(fs::read_to_string(dst.join("nested").join("run.sh")).unwrap(), "echo ok");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(dst.join("nested").join("run.sh")).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+        assert!(report.ensure_complete().unwrap_err().to_string().contains("SKILL.md"));
+    }
+
+    #[test]
+    fn test_find_skill_manifests_respects_depth_and_ignores() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        for (dir, manifest) in [
+            ("pdf", "SKILL.md"),
+            ("pdf/examples/nested", "SKILL.md"),
+            ("categories/docs/writer", "skill.md"),
+            ("a/b/c/deep", "Skill.md"),
+            ("node_modules/pkg", "SKILL.md"),
+            ("vendor/tool", "SKILL.md"),
+            ("legacy", "SKILLS.md"),
+        ] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+            fs::write(root.join(dir).join(manifest), "# skill").unwrap();
+        }
+
+        let relative = |manifests: Vec<PathBuf>| -> Vec<String> {
+            manifests.iter()
+                .map(|path| path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/"))
+                .collect()
+        };
+
+        let names = ManifestNames::default();
+        let filter = ScanFilter::new(&[], &["vendor".to_string()], false).unwrap();
+        assert_eq!(
+            relative(find_skill_manifests(root, &filter, 6, &names)),
+            ["a/b/c/deep/Skill.md", "categories/docs/writer/skill.md", "pdf/SKILL.md"]
+        );
+        assert_eq!(
+            relative(find_skill_manifests(root, &filter, 3, &names)),
+            ["categories/docs/writer/skill.md", "pdf/SKILL.md"]
+        );
+        assert_eq!(relative(find_skill_manifests(root, &filter, 1, &names)), ["pdf/SKILL.md"]);
+
+        let names = ManifestNames::new(&["skills.md".to_string()]);
+        assert_eq!(relative(find_skill_manifests(root, &filter, 1, &names)), ["legacy/SKILLS.md", "pdf/SKILL.md"]);
+
+        let filter = ScanFilter::new(&["categories/**".to_string()], &[], false).unwrap();
+        assert_eq!(relative(find_skill_manifests(root, &filter, 6, &names)), ["categories/docs/writer/skill.md"]);
+    }
+}
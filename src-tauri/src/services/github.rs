@@ -1,8 +1,14 @@
-use crate::models::{GitHubContent, Repository, Skill};
-use crate::services::ProxyConfig;
+use crate::models::{AppError, ErrorKind, GitHubContent, ManifestNames, Repository, RepositoryStats, Skill, IGNORED_DISCOVERY_DIRS};
+use crate::security::ScanFilter;
+use crate::services::rate_limit::{is_rate_limited_response, RateLimitStatus, RateLimiter};
+use crate::services::github_auth::{parse_scopes, TokenInfo};
+use crate::services::{fs_ops, BlobStore, NetworkConfig, ProxyConfig};
+use skillguard_core::paths::extended_length_path;
 use anyhow::{Result, Context};
 use reqwest::Client;
 use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
 use std::future::Future;
 use std::pin::Pin;
 use std::path::{Path, PathBuf};
@@ -10,6 +16,21 @@ use std::fs::{self, File};
 use std::io::Write;
 use zip::ZipArchive;
 
+/// 压缩包下载后校验失败（损坏/commit 不匹配）时的最大尝试次数
+const MAX_ARCHIVE_ATTEMPTS: usize = 3;
+
+/// 单个 GraphQL 请求中最多查询的路径数（控制查询复杂度，避免触发节点上限）
+const GRAPHQL_BATCH_SIZE: usize = 50;
+
+/// 批量查询"某仓库某路径的最新提交"时的单个查询项
+#[derive(Debug, Clone)]
+pub struct LatestCommitQuery {
+    pub owner: String,
+    pub repo: String,
+    /// 仓库内路径，"." 表示仓库根目录
+    pub path: String,
+}
+
 /// GitHub Commit API 响应
 #[derive(Debug, Deserialize)]
 struct GitHubCommit {
@@ -34,6 +55,25 @@ struct GitHubCommitAuthor {
     date: String,
 }
 
+/// 提交签名验证信息（GitHub API 返回的 commit.verification）
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommitVerification {
+    pub verified: bool,
+    pub reason: String,
+    /// ASCII 装甲格式的签名（未签名时为 None）
+    pub signature: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubVerifiedCommit {
+    commit: GitHubVerifiedCommitDetail,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubVerifiedCommitDetail {
+    verification: CommitVerification,
+}
+
 /// SKILL.md 文件的 frontmatter
 #[derive(Debug, Deserialize)]
 struct SkillFrontmatter {
@@ -44,21 +84,23 @@ struct SkillFrontmatter {
 pub struct GitHubService {
     client: Client,
     api_base: String,
+    blob_store: Option<BlobStore>,
+    network: NetworkConfig,
+    rate_limiter: RateLimiter,
+    token: RwLock<Option<String>>,
+    manifest_names: RwLock<ManifestNames>,
 }
 
 impl GitHubService {
     pub fn new() -> Self {
-        Self::new_with_proxy(None)
+        Self::new_with_proxy(None, NetworkConfig::default())
     }
 
-    pub fn new_with_proxy(proxy_config: Option<ProxyConfig>) -> Self {
-        let client = super::proxy::ProxyService::build_http_client(proxy_config.as_ref())
+    pub fn new_with_proxy(proxy_config: Option<ProxyConfig>, network: NetworkConfig) -> Self {
+        let client = super::proxy::ProxyService::build_http_client(proxy_config.as_ref(), &network)
             .unwrap_or_else(|e| {
                 log::warn!("创建带代理的 HTTP 客户端失败: {}, 降级使用无代理模式", e);
-                Client::builder()
-                    .user_agent("agent-skills-guard")
-                    .timeout(std::time::Duration::from_secs(30))
-                    .connect_timeout(std::time::Duration::from_secs(10))
+                super::proxy::ProxyService::base_client_builder(&network)
                     .build()
                     .unwrap()
             });
@@ -66,23 +108,251 @@ impl GitHubService {
         Self {
             client,
             api_base: "https://api.github.com".to_string(),
+            blob_store: BlobStore::default_root().map(BlobStore::new),
+            network,
+            rate_limiter: RateLimiter::new(),
+            token: RwLock::new(None),
+            manifest_names: RwLock::new(ManifestNames::default()),
+        }
+    }
+
+    /// 请求发送到指定的 API 地址、不使用内容寻址存储的实例（测试用）
+    #[cfg(test)]
+    pub(crate) fn with_api_base(api_base: String) -> Self {
+        Self { api_base, blob_store: None, ..Self::new() }
+    }
+
+    /// 设置 GitHub Token（为空时清除），用于 API 认证及 GraphQL 批量查询
+    pub fn set_token(&self, token: Option<String>) {
+        let token = token
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty());
+        *self.token.write().unwrap() = token;
+    }
+
+    /// 设置技能清单文件名的匹配规则（来自技能发现设置）
+    pub fn set_manifest_names(&self, names: ManifestNames) {
+        *self.manifest_names.write().unwrap() = names;
+    }
+
+    /// 当前的技能清单文件名匹配规则
+    pub fn manifest_names(&self) -> ManifestNames {
+        self.manifest_names.read().unwrap().clone()
+    }
+
+    /// 是否已配置 GitHub Token
+    pub fn has_token(&self) -> bool {
+        self.token.read().unwrap().is_some()
+    }
+
+    /// 发送请求，网络错误（连接失败/超时）或服务器 5xx 错误时按配置重试
+    /// 发往 GitHub API 的请求经过限流调度：配额耗尽时排队等待重置后自动恢复
+    async fn send(&self, request: reqwest::RequestBuilder) -> reqwest::Result<reqwest::Response> {
+        let mut request = request.build()?;
+        let is_api_request = request.url().as_str().starts_with(&self.api_base);
+
+        if is_api_request {
+            if let Some(token) = self.token.read().unwrap().as_deref() {
+                if let Ok(value) = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token)) {
+                    request.headers_mut().insert(reqwest::header::AUTHORIZATION, value);
+                }
+            }
+        }
+        let mut attempt = 0u32;
+
+        loop {
+            if is_api_request {
+                self.rate_limiter.acquire().await;
+            }
+
+            let can_retry = attempt < self.network.max_retries;
+            let current = match request.try_clone() {
+                Some(r) => r,
+                None => return self.client.execute(request).await,
+            };
+
+            match self.client.execute(current).await {
+                Ok(resp) => {
+                    if is_api_request {
+                        self.rate_limiter.update_from_headers(resp.headers());
+
+                        // 被限流：如果重置时间在可接受范围内，排队等待后重新发送（不计入重试次数）
+                        if is_rate_limited_response(resp.status(), resp.headers())
+                            && self.rate_limiter.pending_wait().is_some()
+                            && self.rate_limiter.acquire().await
+                        {
+                            log::info!("GitHub API 配额已恢复，重新发送请求");
+                            continue;
+                        }
+                    }
+
+                    if resp.status().is_server_error() && can_retry {
+                        log::warn!("请求返回 {}，第 {} 次重试", resp.status(), attempt + 1);
+                    } else {
+                        return Ok(resp);
+                    }
+                }
+                Err(e) if (e.is_timeout() || e.is_connect()) && can_retry => {
+                    log::warn!("请求失败（{}），第 {} 次重试", e, attempt + 1);
+                }
+                Err(e) => return Err(e),
+            }
+
+            tokio::time::sleep(self.network.retry_delay(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    /// 获取 GitHub API 限流状态（含预计恢复时间）
+    pub fn rate_limit_status(&self) -> RateLimitStatus {
+        self.rate_limiter.status()
+    }
+
+    /// 使用已配置的 Token 创建 Gist（单个文件），返回 Gist 的网页地址
+    pub async fn create_gist(&self, filename: &str, content: &str, description: &str, public: bool) -> Result<String> {
+        if !self.has_token() {
+            return Err(AppError::new(ErrorKind::Unauthorized, "上传 Gist 需要先配置 GitHub Token").into());
+        }
+
+        let body = serde_json::json!({
+            "description": description,
+            "public": public,
+            "files": { filename: { "content": content } },
+        });
+        let response = self.send(self.client.post(format!("{}/gists", self.api_base)).json(&body))
+            .await
+            .context("网络请求失败，无法创建 Gist")?;
+        self.check_rate_limit(&response)?;
+
+        let status = response.status();
+        if matches!(status.as_u16(), 401 | 403 | 404) {
+            return Err(AppError::new(
+                ErrorKind::Unauthorized,
+                "GitHub Token 无权创建 Gist（需要 gist 权限），请重新登录 GitHub 并授予 gist 权限",
+            ).into());
+        }
+        if !status.is_success() {
+            anyhow::bail!("创建 Gist 失败，HTTP 状态码: {}", status);
+        }
+
+        #[derive(Deserialize)]
+        struct GistResponse {
+            html_url: String,
+        }
+        let gist: GistResponse = response.json().await.context("无法解析 Gist 响应")?;
+        Ok(gist.html_url)
+    }
+
+    /// 查询当前 Token 对应的账号及授予的权限（来自 X-OAuth-Scopes 响应头）
+    pub async fn fetch_token_info(&self) -> Result<TokenInfo> {
+        if !self.has_token() {
+            return Err(AppError::new(ErrorKind::Unauthorized, "尚未配置 GitHub Token").into());
+        }
+
+        let response = self.send(self.client.get(format!("{}/user", self.api_base)))
+            .await
+            .context("网络请求失败，无法查询 GitHub 账号")?;
+        self.check_rate_limit(&response)?;
+
+        let status = response.status();
+        if status.as_u16() == 401 {
+            return Err(AppError::new(ErrorKind::Unauthorized, "GitHub Token 无效或已被撤销，请重新登录").into());
+        }
+        if !status.is_success() {
+            anyhow::bail!("查询 GitHub 账号失败，HTTP 状态码: {}", status);
+        }
+
+        let scopes = response
+            .headers()
+            .get("x-oauth-scopes")
+            .and_then(|value| value.to_str().ok())
+            .map(parse_scopes);
+
+        #[derive(Deserialize)]
+        struct UserResponse {
+            login: String,
         }
+        let user: UserResponse = response.json().await.context("无法解析 GitHub 账号信息")?;
+        Ok(TokenInfo { login: user.login, scopes })
     }
 
-    /// 扫描仓库中的 skills
+    /// 获取 Gist 中的所有文件，返回 (文件名, 内容)；内容被截断的大文件通过 raw_url 下载。
+    /// 超过 max_file_bytes 的文件跳过，不下载
+    pub async fn fetch_gist_files(&self, gist_id: &str, max_file_bytes: usize) -> Result<Vec<(String, String)>> {
+        let response = self.send(self.client.get(format!("{}/gists/{}", self.api_base, gist_id)))
+            .await
+            .context("网络请求失败，无法获取 Gist")?;
+        self.check_rate_limit(&response)?;
+
+        let status = response.status();
+        if status.as_u16() == 404 {
+            return Err(AppError::not_found(format!("Gist 不存在: {}", gist_id)).into());
+        }
+        if !status.is_success() {
+            anyhow::bail!("获取 Gist 失败，HTTP 状态码: {}", status);
+        }
+
+        #[derive(Deserialize)]
+        struct GistFile {
+            filename: String,
+            raw_url: String,
+            #[serde(default)]
+            truncated: bool,
+            #[serde(default)]
+            size: u64,
+            content: Option<String>,
+        }
+        #[derive(Deserialize)]
+        struct GistResponse {
+            files: std::collections::BTreeMap<String, GistFile>,
+        }
+        let gist: GistResponse = response.json().await.context("无法解析 Gist 响应")?;
+
+        let mut files = Vec::new();
+        for file in gist.files.into_values() {
+            if file.size > max_file_bytes as u64 {
+                log::warn!("Gist 文件过大，已跳过: {} ({} 字节)", file.filename, file.size);
+                continue;
+            }
+            let content = match file.content {
+                Some(content) if !file.truncated => content,
+                _ => String::from_utf8(self.download_file_limited(&file.raw_url, max_file_bytes).await?)
+                    .with_context(|| format!("Gist 文件不是 UTF-8 文本: {}", file.filename))?,
+            };
+            files.push((file.filename, content));
+        }
+        Ok(files)
+    }
+
+    /// 获取内容寻址存储（无法确定缓存目录时为 None）
+    pub fn blob_store(&self) -> Option<&BlobStore> {
+        self.blob_store.as_ref()
+    }
+
+    /// 扫描仓库中的 skills（按仓库的路径配置跳过排除的目录，并限制递归深度）
     pub async fn scan_repository(&self, repo: &Repository) -> Result<Vec<Skill>> {
         let (owner, repo_name, _) = Repository::from_github_url(&repo.url)?;
+        let filter = repo.discovery_filter()?;
+        let max_depth = repo.discovery_depth();
         let mut skills = Vec::new();
 
         // 获取仓库根目录内容
         let contents = self.fetch_directory_contents(&owner, &repo_name, "").await?;
 
         for item in contents {
-            if item.content_type == "dir" {
+            // 被排除的目录不再请求其内容，节省 API 请求
+            if is_discoverable_dir(&item, &filter) {
                 // 检查文件夹是否为 skill（包含 SKILL.md）
-                if self.is_skill_directory(&owner, &repo_name, &item.path).await? {
+                if let Some(manifest) = self.fetch_skill_manifest_name(&owner, &repo_name, &item.path).await? {
+                    if !filter.should_scan_file(Path::new(&item.path)) {
+                        continue;
+                    }
+
                     // 获取 skill 的元数据（name 和 description）
-                    let (name, description) = match self.fetch_skill_metadata(&owner, &repo_name, &item.path).await {
+                    let manifest_path = format!("{}/{}", item.path, manifest);
+                    let metadata = self.fetch_raw_text(&owner, &repo_name, None, &manifest_path).await
+                        .and_then(|content| self.parse_skill_frontmatter(&content));
+                    let (name, description) = match metadata {
                         Ok(metadata) => metadata,
                         Err(e) => {
                             log::warn!("Failed to fetch metadata for {}: {}, using fallback", item.path, e);
@@ -105,9 +375,9 @@ impl GitHubService {
                     );
                     skill.description = description;
                     skills.push(skill);
-                } else if repo.scan_subdirs {
+                } else if max_depth > 1 {
                     // 递归扫描子目录
-                    match self.scan_directory(&owner, &repo_name, &item.path, &repo.url).await {
+                    match self.scan_directory(&owner, &repo_name, &item.path, &repo.url, &filter, max_depth).await {
                         Ok(mut sub_skills) => skills.append(&mut sub_skills),
                         Err(e) => log::warn!("Failed to scan subdirectory {}: {}", item.path, e),
                     }
@@ -118,24 +388,33 @@ impl GitHubService {
         Ok(skills)
     }
 
-    /// 递归扫描目录
+    /// 递归扫描目录（max_depth 为技能目录相对仓库根目录的最大深度）
     fn scan_directory<'a>(
         &'a self,
         owner: &'a str,
         repo: &'a str,
         path: &'a str,
         repo_url: &'a str,
+        filter: &'a ScanFilter,
+        max_depth: usize,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<Skill>>> + Send + 'a>> {
         Box::pin(async move {
             let mut skills = Vec::new();
             let contents = self.fetch_directory_contents(owner, repo, path).await?;
 
             for item in contents {
-                if item.content_type == "dir" {
+                if is_discoverable_dir(&item, filter) {
                     // 检查文件夹是否为 skill（包含 SKILL.md）
-                    if self.is_skill_directory(owner, repo, &item.path).await? {
+                    if let Some(manifest) = self.fetch_skill_manifest_name(owner, repo, &item.path).await? {
+                        if !filter.should_scan_file(Path::new(&item.path)) {
+                            continue;
+                        }
+
                         // 获取 skill 的元数据（name 和 description）
-                        let (name, description) = match self.fetch_skill_metadata(owner, repo, &item.path).await {
+                        let manifest_path = format!("{}/{}", item.path, manifest);
+                        let metadata = self.fetch_raw_text(owner, repo, None, &manifest_path).await
+                            .and_then(|content| self.parse_skill_frontmatter(&content));
+                        let (name, description) = match metadata {
                             Ok(metadata) => metadata,
                             Err(e) => {
                                 log::warn!("Failed to fetch metadata for {}: {}, using fallback", item.path, e);
@@ -158,9 +437,9 @@ impl GitHubService {
                         );
                         skill.description = description;
                         skills.push(skill);
-                    } else if path.split('/').count() < 5 {
+                    } else if item.path.split('/').count() < max_depth {
                         // 递归扫描（限制深度避免无限递归）
-                        match self.scan_directory(owner, repo, &item.path, repo_url).await {
+                        match self.scan_directory(owner, repo, &item.path, repo_url, filter, max_depth).await {
                             Ok(mut sub_skills) => skills.append(&mut sub_skills),
                             Err(e) => log::warn!("Failed to scan subdirectory {}: {}", item.path, e),
                         }
@@ -185,9 +464,7 @@ impl GitHubService {
             format!("{}/repos/{}/{}/contents/{}", self.api_base, owner, repo, path)
         };
 
-        let response = self.client
-            .get(&url)
-            .send()
+        let response = self.send(self.client.get(&url))
             .await
             .context("网络请求失败，请检查您的网络连接")?;
 
@@ -212,27 +489,27 @@ impl GitHubService {
 
                                         if wait_seconds > 0 {
                                             let wait_minutes = (wait_seconds + 59) / 60; // 向上取整
-                                            anyhow::bail!(
+                                            return Err(AppError::new(ErrorKind::RateLimited, format!(
                                                 "GitHub API 速率限制已达上限，请等待约 {} 分钟后重试。\n\n提示：未认证的请求限制为每小时60次，认证后可提升至5000次/小时。",
                                                 wait_minutes
-                                            );
+                                            )).into());
                                         }
                                     }
                                 }
                             }
-                            anyhow::bail!("GitHub API 速率限制已达上限，请稍后重试（约1小时后）");
+                            return Err(AppError::new(ErrorKind::RateLimited, "GitHub API 速率限制已达上限，请稍后重试（约1小时后）").into());
                         }
                     }
-                    anyhow::bail!("无权限访问该仓库，请检查仓库是否为私有仓库");
+                    return Err(AppError::new(ErrorKind::Unauthorized, "无权限访问该仓库，请检查仓库是否为私有仓库").into());
                 }
                 404 => {
-                    anyhow::bail!("仓库或路径不存在: {}/{}", owner, repo);
+                    return Err(AppError::not_found(format!("仓库或路径不存在: {}/{}", owner, repo)).into());
                 }
                 401 => {
-                    anyhow::bail!("未授权访问，请配置 GitHub Token");
+                    return Err(AppError::new(ErrorKind::Unauthorized, "未授权访问，请配置 GitHub Token").into());
                 }
                 500..=599 => {
-                    anyhow::bail!("GitHub 服务器错误，请稍后重试");
+                    return Err(AppError::new(ErrorKind::Network, "GitHub 服务器错误，请稍后重试").into());
                 }
                 _ => {
                     anyhow::bail!("GitHub API 返回错误: {}", status);
@@ -250,9 +527,13 @@ impl GitHubService {
 
     /// 下载文件内容
     pub async fn download_file(&self, download_url: &str) -> Result<Vec<u8>> {
-        let response = self.client
-            .get(download_url)
-            .send()
+        self.download_file_limited(download_url, usize::MAX).await
+    }
+
+    /// 下载文件内容，超过 max_bytes 时中止下载并返回错误：
+    /// 先检查 Content-Length，再边读取边计数（不依赖服务器声明的长度）
+    pub async fn download_file_limited(&self, download_url: &str, max_bytes: usize) -> Result<Vec<u8>> {
+        let mut response = self.send(self.client.get(download_url))
             .await
             .context("网络请求失败，无法下载文件")?;
 
@@ -263,13 +544,13 @@ impl GitHubService {
                 403 => {
                     if let Some(remaining) = response.headers().get("x-ratelimit-remaining") {
                         if remaining == "0" {
-                            anyhow::bail!("GitHub API 速率限制已达上限，请稍后重试");
+                            return Err(AppError::new(ErrorKind::RateLimited, "GitHub API 速率限制已达上限，请稍后重试").into());
                         }
                     }
-                    anyhow::bail!("无权限访问该文件");
+                    return Err(AppError::new(ErrorKind::Unauthorized, "无权限访问该文件").into());
                 }
                 404 => {
-                    anyhow::bail!("文件不存在: {}", download_url);
+                    return Err(AppError::not_found(format!("文件不存在: {}", download_url)).into());
                 }
                 _ => {
                     anyhow::bail!("下载文件失败: {}", status);
@@ -277,67 +558,95 @@ impl GitHubService {
             }
         }
 
-        let bytes = response
-            .bytes()
-            .await
-            .context("读取文件内容失败")?;
+        let too_large = || anyhow::anyhow!("文件过大，超过 {} 字节的下载上限: {}", max_bytes, download_url);
+        if response.content_length().is_some_and(|length| length > max_bytes as u64) {
+            return Err(too_large());
+        }
 
-        Ok(bytes.to_vec())
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await.context("读取文件内容失败")? {
+            if bytes.len() + chunk.len() > max_bytes {
+                return Err(too_large());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        Ok(bytes)
     }
 
-    /// 判断文件夹是否为 skill（包含 SKILL.md）
-    async fn is_skill_directory(&self, owner: &str, repo: &str, path: &str) -> Result<bool> {
+    /// 列出目录并返回其中的技能清单文件名（不区分大小写），不是技能目录时为 None
+    async fn fetch_skill_manifest_name(&self, owner: &str, repo: &str, path: &str) -> Result<Option<String>> {
         // 获取文件夹内容
         match self.fetch_directory_contents(owner, repo, path).await {
             Ok(contents) => {
-                // 检查是否包含 SKILL.md 文件
-                Ok(contents.iter().any(|item| {
-                    item.content_type == "file" && item.name.to_uppercase() == "SKILL.MD"
-                }))
+                let names = self.manifest_names();
+                let files = contents.iter().filter(|item| item.content_type == "file").map(|item| item.name.as_str());
+                Ok(names.pick(files).map(str::to_string))
             }
             Err(e) => {
                 log::warn!("Failed to check directory {}: {}", path, e);
-                Ok(false)
+                Ok(None)
+            }
+        }
+    }
+
+    /// 按候选文件名依次下载技能目录中的清单文件，返回 (文件名, 内容)
+    pub async fn fetch_skill_manifest(&self, owner: &str, repo: &str, branch: Option<&str>, skill_path: &str) -> Result<(String, String)> {
+        let names = self.manifest_names();
+        let mut last_error = None;
+
+        for name in names.candidates() {
+            let path = match skill_path {
+                "." | "" => name.to_string(),
+                dir => format!("{}/{}", dir.trim_end_matches('/'), name),
+            };
+            match self.fetch_raw_text(owner, repo, branch, &path).await {
+                Ok(content) => return Ok((name.to_string(), content)),
+                Err(e) => last_error = Some(e),
             }
         }
+
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("未找到技能清单文件")))
     }
 
     /// 下载并解析 SKILL.md 的 frontmatter
     pub async fn fetch_skill_metadata(&self, owner: &str, repo: &str, skill_path: &str) -> Result<(String, Option<String>)> {
-        // 尝试多个分支获取 SKILL.md
-        let branches = ["main", "master"];
+        let (_, content) = self.fetch_skill_manifest(owner, repo, None, skill_path).await?;
+        self.parse_skill_frontmatter(&content)
+    }
+
+    /// 通过 raw.githubusercontent.com 下载仓库中的文本文件
+    /// 未指定分支时依次尝试 main 与 master
+    pub async fn fetch_raw_text(&self, owner: &str, repo: &str, branch: Option<&str>, path: &str) -> Result<String> {
+        let branches: Vec<&str> = match branch {
+            Some(branch) => vec![branch],
+            None => vec!["main", "master"],
+        };
         let mut last_error = None;
 
-        for branch in branches.iter() {
-            let download_url = format!(
-                "https://raw.githubusercontent.com/{}/{}/{}/{}/SKILL.md",
-                owner, repo, branch, skill_path
-            );
+        for branch in branches {
+            let download_url = format!("https://raw.githubusercontent.com/{}/{}/{}/{}", owner, repo, branch, path);
 
-            log::info!("尝试从分支 {} 获取 SKILL.md: {}", branch, download_url);
+            log::info!("尝试从分支 {} 获取文件: {}", branch, download_url);
 
             match self.download_file(&download_url).await {
-                Ok(content) => {
-                    match String::from_utf8(content) {
-                        Ok(content_str) => {
-                            log::info!("成功从分支 {} 获取 SKILL.md", branch);
-                            return self.parse_skill_frontmatter(&content_str);
-                        }
-                        Err(e) => {
-                            last_error = Some(anyhow::anyhow!("Failed to decode SKILL.md as UTF-8: {}", e));
-                            continue;
-                        }
+                Ok(content) => match String::from_utf8(content) {
+                    Ok(content_str) => {
+                        log::info!("成功从分支 {} 获取文件 {}", branch, path);
+                        return Ok(content_str);
                     }
-                }
+                    Err(e) => {
+                        last_error = Some(anyhow::anyhow!("Failed to decode {} as UTF-8: {}", path, e));
+                    }
+                },
                 Err(e) => {
                     log::info!("分支 {} 不存在或获取失败: {}", branch, e);
                     last_error = Some(e);
-                    continue;
                 }
             }
         }
 
-        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("所有分支均无法获取 SKILL.md")))
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("所有分支均无法获取 {}", path)))
     }
 
     /// 解析 SKILL.md 的 frontmatter
@@ -379,6 +688,8 @@ impl GitHubService {
     }
 
     /// 下载仓库压缩包并解压到本地缓存
+    /// 先通过 API 解析分支指向的 commit 并下载该 commit 的压缩包（无法解析时失败），
+    /// 解压后将根目录中的 commit 与之比对，不一致时重新下载
     /// 返回值：(extract_dir, commit_sha)
     pub async fn download_repository_archive(
         &self,
@@ -392,82 +703,230 @@ impl GitHubService {
         fs::create_dir_all(&repo_cache_dir)
             .context("无法创建缓存目录")?;
 
-        // 2. 尝试下载压缩包
+        let archive_path = repo_cache_dir.join("archive.zip");
+        let extract_dir = repo_cache_dir.join("extracted");
+
         // 如果指定了分支，优先尝试该分支
         let branches = if let Some(b) = branch {
             vec![b.to_string()]
         } else {
             vec!["main".to_string(), "master".to_string()]
         };
-        
+
+        // 2. 先解析分支当前指向的 commit，再按 commit 下载压缩包：
+        //    解析失败时无法校验压缩包，直接失败而不是使用未经校验的内容
+        let (resolved_branch, expected_sha) = self.resolve_archive_commit(owner, repo, &branches).await?;
+        log::info!("分支 {} 指向 commit {}", resolved_branch, expected_sha);
+
         let mut last_error = None;
-        let mut response = None;
 
-        for branch in branches.iter() {
-            let url = format!("{}/repos/{}/{}/zipball/{}", self.api_base, owner, repo, branch);
-            log::info!("正在尝试下载仓库压缩包 (分支: {}): {}", branch, url);
+        for attempt in 1..=MAX_ARCHIVE_ATTEMPTS {
+            // 3. 下载该 commit 的压缩包（HTTP 错误直接返回，不重试）
+            let bytes = self.fetch_archive_bytes(owner, repo, &expected_sha).await?;
 
-            match self.client.get(&url).send().await {
-                Ok(resp) => {
-                    // 检查API限流
-                    if let Err(e) = self.check_rate_limit(&resp) {
-                        return Err(e);
-                    }
+            // 4. 保存压缩包到本地
+            let mut file = File::create(&archive_path)
+                .context("无法创建压缩包文件")?;
+            file.write_all(&bytes)
+                .context("写入压缩包失败")?;
 
-                    if resp.status().is_success() {
-                        log::info!("成功找到分支: {}", branch);
-                        response = Some(resp);
-                        break;
-                    } else if resp.status() == reqwest::StatusCode::NOT_FOUND {
-                        log::info!("分支 {} 不存在，尝试下一个分支", branch);
-                        last_error = Some(anyhow::anyhow!("分支 {} 不存在", branch));
-                        continue;
-                    } else {
-                        last_error = Some(anyhow::anyhow!(
-                            "下载失败，HTTP状态码: {}",
-                            resp.status()
-                        ));
-                        continue;
-                    }
-                }
+            log::info!("压缩包已保存: {:?}, 大小: {} bytes", archive_path, bytes.len());
+
+            // 5. 解压缩（先清理旧的解压目录，避免残留旧版本根目录干扰 SHA 提取）
+            if extract_dir.exists() {
+                fs_ops::remove_dir_all(&extract_dir)
+                    .context("无法清理旧的解压目录")?;
+            }
+
+            if let Err(e) = self.extract_zip(&archive_path, &extract_dir) {
+                log::warn!("第 {} 次下载的压缩包解压失败（可能已损坏或被截断）: {}", attempt, e);
+                last_error = Some(e.context("解压缩失败"));
+                continue;
+            }
+
+            log::info!("解压完成: {:?}", extract_dir);
+
+            // 6. 提取 commit SHA（从解压后的目录名），与解析出的 commit 比对，防止压缩包被截断或篡改
+            let commit_sha = match self.extract_commit_sha_from_cache(&extract_dir) {
+                Ok(sha) => sha,
                 Err(e) => {
-                    log::warn!("请求分支 {} 时发生错误: {}", branch, e);
-                    last_error = Some(anyhow::anyhow!("请求失败: {}", e));
+                    log::warn!("第 {} 次下载的压缩包结构异常: {}", attempt, e);
+                    last_error = Some(e.context("无法提取 commit SHA"));
                     continue;
                 }
+            };
+
+            if !expected_sha.to_lowercase().starts_with(&commit_sha.to_lowercase()) {
+                log::warn!(
+                    "第 {} 次下载的压缩包 commit 不匹配: 压缩包 {}，分支 {} 指向 {}",
+                    attempt, commit_sha, resolved_branch, expected_sha
+                );
+                last_error = Some(anyhow::anyhow!(
+                    "压缩包校验失败：压缩包 commit {} 与分支 {} 的最新 commit {} 不一致",
+                    commit_sha, resolved_branch, expected_sha
+                ));
+                continue;
             }
+            log::info!("压缩包校验通过: {}", expected_sha);
+
+            // 7. 写入内容寻址存储，跨仓库/跨版本去重（失败不影响缓存可用性）
+            if let Some(store) = &self.blob_store {
+                let key = BlobStore::manifest_key(cache_base_dir, owner, repo);
+                if let Err(e) = store.ingest_directory(&extract_dir, &key, &commit_sha) {
+                    log::warn!("写入内容寻址存储失败，缓存将不去重: {}", e);
+                }
+            }
+
+            return Ok((extract_dir, commit_sha));
         }
 
-        let response = response.ok_or_else(|| {
-            last_error.unwrap_or_else(|| anyhow::anyhow!("所有分支均下载失败"))
-        })?;
+        // 多次尝试均失败：清理不可信的下载结果，避免被后续扫描/安装使用
+        let _ = fs::remove_file(&archive_path);
+        let _ = fs_ops::remove_dir_all(&extract_dir);
 
-        // 3. 保存压缩包到本地
-        let archive_path = repo_cache_dir.join("archive.zip");
-        let bytes = response.bytes().await
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("下载仓库压缩包失败")))
+    }
+
+    /// 按分支顺序解析压缩包对应的 commit（分支不存在时尝试下一个）
+    /// 返回值：(实际使用的分支, commit SHA)
+    async fn resolve_archive_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        branches: &[String],
+    ) -> Result<(String, String)> {
+        let mut last_error = None;
+
+        for branch in branches {
+            match self.resolve_commit_sha(owner, repo, branch).await {
+                Ok(sha) => return Ok((branch.clone(), sha)),
+                Err(e) => {
+                    log::info!("无法解析分支 {} 对应的 commit，尝试下一个分支: {}", branch, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error
+            .unwrap_or_else(|| anyhow::anyhow!("没有可下载的分支"))
+            .context("无法解析分支对应的 commit，无法校验仓库压缩包"))
+    }
+
+    /// 下载指定 commit 的仓库压缩包
+    async fn fetch_archive_bytes(&self, owner: &str, repo: &str, commit_sha: &str) -> Result<Vec<u8>> {
+        let url = format!("{}/repos/{}/{}/zipball/{}", self.api_base, owner, repo, commit_sha);
+        log::info!("正在下载仓库压缩包 (commit: {}): {}", commit_sha, url);
+
+        let resp = self.send(self.client.get(&url)).await
+            .context("下载仓库压缩包时网络请求失败")?;
+        self.check_rate_limit(&resp)?;
+
+        if !resp.status().is_success() {
+            anyhow::bail!("下载失败，HTTP状态码: {}", resp.status());
+        }
+
+        let bytes = resp.bytes().await
             .context("读取压缩包内容失败")?;
+        Ok(bytes.to_vec())
+    }
+
+    /// 通过 API 解析分支（或其他 ref）当前指向的完整 commit SHA
+    pub async fn resolve_commit_sha(&self, owner: &str, repo: &str, git_ref: &str) -> Result<String> {
+        let url = format!("{}/repos/{}/{}/commits/{}", self.api_base, owner, repo, git_ref);
 
-        let mut file = File::create(&archive_path)
-            .context("无法创建压缩包文件")?;
-        file.write_all(&bytes)
-            .context("写入压缩包失败")?;
+        let response = self
+            .send(self.client.get(&url).header(reqwest::header::ACCEPT, "application/vnd.github.sha"))
+            .await
+            .context("解析 commit 时网络请求失败")?;
 
-        log::info!("压缩包已保存: {:?}, 大小: {} bytes", archive_path, bytes.len());
+        self.check_rate_limit(&response)?;
 
-        // 4. 解压缩
-        let extract_dir = repo_cache_dir.join("extracted");
-        self.extract_zip(&archive_path, &extract_dir)
-            .context("解压缩失败")?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("解析 commit 失败，HTTP状态码: {}", status);
+        }
+
+        let sha = response.text().await
+            .context("读取 commit 响应失败")?
+            .trim()
+            .to_string();
+
+        if sha.len() < 7 || !sha.chars().all(|c| c.is_ascii_hexdigit()) {
+            anyhow::bail!("API 返回的 commit SHA 格式无效: {}", sha);
+        }
+
+        Ok(sha)
+    }
+
+    /// 获取提交的签名验证信息
+    pub async fn fetch_commit_verification(&self, owner: &str, repo: &str, sha: &str) -> Result<CommitVerification> {
+        let url = format!("{}/repos/{}/{}/commits/{}", self.api_base, owner, repo, sha);
+
+        let response = self
+            .send(self.client.get(&url))
+            .await
+            .context("获取提交签名信息时网络请求失败")?;
+
+        self.check_rate_limit(&response)?;
+
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("获取提交签名信息失败，HTTP状态码: {}", status);
+        }
 
-        log::info!("解压完成: {:?}", extract_dir);
+        let commit: GitHubVerifiedCommit = response.json().await
+            .context("解析提交签名信息失败")?;
 
-        // 5. 提取 commit SHA（从解压后的目录名）
-        let commit_sha = self.extract_commit_sha_from_cache(&extract_dir)
-            .context("无法提取 commit SHA")?;
+        Ok(commit.commit.verification)
+    }
+
+    /// 获取仓库的星标数与所有者账号创建时间（所有者信息获取失败时只记录警告）
+    pub async fn fetch_repository_stats(&self, repository_url: &str, owner: &str, repo: &str) -> Result<RepositoryStats> {
+        #[derive(Deserialize)]
+        struct RepoResponse {
+            stargazers_count: u64,
+            owner: OwnerResponse,
+        }
+        #[derive(Deserialize)]
+        struct OwnerResponse {
+            login: String,
+        }
+        #[derive(Deserialize)]
+        struct UserResponse {
+            created_at: Option<chrono::DateTime<chrono::Utc>>,
+        }
 
-        log::info!("提取到 commit SHA: {}", commit_sha);
+        let response = self
+            .send(self.client.get(format!("{}/repos/{}/{}", self.api_base, owner, repo)))
+            .await
+            .context("获取仓库信息时网络请求失败")?;
+        self.check_rate_limit(&response)?;
+        if !response.status().is_success() {
+            anyhow::bail!("获取仓库信息失败，HTTP状态码: {}", response.status());
+        }
+        let info: RepoResponse = response.json().await.context("解析仓库信息失败")?;
+
+        let owner_created_at = match self.send(self.client.get(format!("{}/users/{}", self.api_base, info.owner.login))).await {
+            Ok(response) if response.status().is_success() => {
+                response.json::<UserResponse>().await.ok().and_then(|user| user.created_at)
+            }
+            Ok(response) => {
+                log::warn!("获取仓库所有者信息失败: {}, HTTP状态码: {}", info.owner.login, response.status());
+                None
+            }
+            Err(e) => {
+                log::warn!("获取仓库所有者信息失败: {}: {}", info.owner.login, e);
+                None
+            }
+        };
 
-        Ok((extract_dir, commit_sha))
+        Ok(RepositoryStats {
+            repository_url: repository_url.to_string(),
+            stars: info.stargazers_count,
+            owner: info.owner.login,
+            owner_created_at,
+            fetched_at: chrono::Utc::now(),
+        })
     }
 
     /// 解压zip文件
@@ -479,6 +938,7 @@ impl GitHubService {
             .context("无法读取ZIP文件")?;
 
         log::info!("正在解压 {} 个文件...", archive.len());
+        let mut case_collisions = fs_ops::CaseCollisions::default();
 
         for i in 0..archive.len() {
             let mut file = archive.by_index(i)
@@ -486,8 +946,9 @@ impl GitHubService {
 
             // GitHub的zipball会在根目录包含一个 {owner}-{repo}-{commit}/ 的文件夹
             // 我们需要提取这个路径
+            // Windows 下使用扩展长度路径，深层目录超过 MAX_PATH 或文件名为保留名称时也能解压
             let outpath = match file.enclosed_name() {
-                Some(path) => extract_dir.join(path),
+                Some(path) => extended_length_path(&extract_dir.join(path)),
                 None => continue,
             };
 
@@ -495,6 +956,13 @@ impl GitHubService {
                 fs::create_dir_all(&outpath)
                     .context(format!("无法创建目录: {:?}", outpath))?;
             } else {
+                // 不区分大小写的文件系统上，仅大小写不同的文件会覆盖先解压的文件：不写入，解压结束后报告
+                let relative = file.enclosed_name().map(|path| path.to_string_lossy().replace('\\', "/")).unwrap_or_default();
+                if case_collisions.insert(&relative) && fs_ops::CASE_INSENSITIVE_FS {
+                    log::warn!("跳过仅大小写不同的文件: {}", relative);
+                    continue;
+                }
+
                 if let Some(parent) = outpath.parent() {
                     fs::create_dir_all(parent)
                         .context(format!("无法创建父目录: {:?}", parent))?;
@@ -505,52 +973,87 @@ impl GitHubService {
 
                 std::io::copy(&mut file, &mut outfile)
                     .context(format!("无法写入文件: {:?}", outpath))?;
+
+                // 保留压缩包中记录的修改时间与 Unix 权限（脚本的可执行位）
+                if let Some(modified) = file.last_modified().and_then(zip_datetime_to_system_time) {
+                    if let Err(e) = outfile.set_times(fs::FileTimes::new().set_modified(modified)) {
+                        log::debug!("设置文件修改时间失败: {:?}, 错误: {}", outpath, e);
+                    }
+                }
+                #[cfg(unix)]
+                if let Some(mode) = file.unix_mode() {
+                    use std::os::unix::fs::PermissionsExt;
+                    fs::set_permissions(&outpath, fs::Permissions::from_mode(mode & 0o777))
+                        .context(format!("无法设置文件权限: {:?}", outpath))?;
+                }
             }
         }
 
+        if let Some(err) = case_collisions.into_error().filter(|_| fs_ops::CASE_INSENSITIVE_FS) {
+            return Err(err);
+        }
         Ok(())
     }
 
-    /// 检查GitHub API限流状态
-    fn check_rate_limit(&self, response: &reqwest::Response) -> Result<()> {
-        if let Some(remaining) = response.headers().get("x-ratelimit-remaining") {
-            if let Ok(remaining_str) = remaining.to_str() {
-                log::debug!("GitHub API剩余配额: {}", remaining_str);
-
-                if remaining_str == "0" {
-                    if let Some(reset) = response.headers().get("x-ratelimit-reset") {
-                        if let Ok(reset_str) = reset.to_str() {
-                            if let Ok(reset_timestamp) = reset_str.parse::<i64>() {
-                                let now = chrono::Utc::now().timestamp();
-                                let wait_seconds = reset_timestamp - now;
-                                let wait_minutes = (wait_seconds + 59) / 60;
-
-                                return Err(anyhow::anyhow!(
-                                    "GitHub API 速率限制已达上限，请等待约 {} 分钟后重试。\n\n提示：未认证的请求限制为每小时60次，认证后可提升至5000次/小时。",
-                                    wait_minutes
-                                ));
-                            }
-                        }
-                    }
-                }
+    /// 按清单校验仓库缓存完整性
+    /// 没有清单（旧缓存）或无法校验时视为有效；有文件缺失或被篡改时返回 false
+    pub fn verify_repository_cache(&self, extract_dir: &Path, cache_base_dir: &Path, owner: &str, repo: &str) -> bool {
+        let store = match &self.blob_store {
+            Some(store) => store,
+            None => return true,
+        };
+
+        let key = BlobStore::manifest_key(cache_base_dir, owner, repo);
+        match store.verify_directory(extract_dir, &key) {
+            Ok(Some(mismatched)) if !mismatched.is_empty() => {
+                log::warn!("仓库缓存校验失败，{} 个文件缺失或被修改: {:?}", mismatched.len(), mismatched);
+                false
+            }
+            Ok(_) => true,
+            Err(e) => {
+                log::warn!("仓库缓存校验出错，跳过校验: {}", e);
+                true
             }
         }
-        Ok(())
     }
 
-    /// 从本地缓存扫描skills（不需要API请求）
-    pub fn scan_cached_repository(
-        &self,
-        cache_path: &Path,
-        repo_url: &str,
-        scan_subdirs: bool,
-    ) -> Result<Vec<Skill>> {
-        use walkdir::WalkDir;
+    /// 检查GitHub API限流状态：只有被拒绝的请求（403/429）才视为限流。
+    /// 成功响应中剩余配额为 0 时仍返回结果，由 [`RateLimiter`] 让后续请求等待配额重置
+    fn check_rate_limit(&self, response: &reqwest::Response) -> Result<()> {
+        if let Some(remaining) = response.headers().get("x-ratelimit-remaining").and_then(|v| v.to_str().ok()) {
+            log::debug!("GitHub API剩余配额: {}", remaining);
+        }
 
+        if !is_rate_limited_response(response.status(), response.headers()) {
+            return Ok(());
+        }
+
+        let reset_timestamp = response
+            .headers()
+            .get("x-ratelimit-reset")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<i64>().ok());
+        match reset_timestamp {
+            Some(reset_timestamp) => {
+                let wait_seconds = reset_timestamp - chrono::Utc::now().timestamp();
+                let wait_minutes = (wait_seconds + 59) / 60;
+                Err(AppError::new(ErrorKind::RateLimited, format!(
+                    "GitHub API 速率限制已达上限，请等待约 {} 分钟后重试。\n\n提示：未认证的请求限制为每小时60次，认证后可提升至5000次/小时。",
+                    wait_minutes
+                )).into())
+            }
+            None => Err(AppError::new(ErrorKind::RateLimited, "GitHub API 速率限制已达上限，请稍后重试").into()),
+        }
+    }
+
+    /// 从本地缓存扫描skills（不需要API请求），按仓库的路径配置跳过排除的目录并限制深度
+    pub fn scan_cached_repository(&self, cache_path: &Path, repo: &Repository) -> Result<Vec<Skill>> {
+        let repo_url = repo.url.as_str();
+        let filter = repo.discovery_filter()?;
         let mut skills = Vec::new();
-        let max_depth = if scan_subdirs { 10 } else { 2 };
+        let max_depth = repo.discovery_depth();
 
-        log::info!("开始扫描本地缓存: {:?}, scan_subdirs: {}", cache_path, scan_subdirs);
+        log::info!("开始扫描本地缓存: {:?}, scan_subdirs: {}, max_depth: {}", cache_path, repo.scan_subdirs, max_depth);
 
         // GitHub zipball的根目录是 {owner}-{repo}-{commit}/
         // 需要找到这个根目录
@@ -558,24 +1061,15 @@ impl GitHubService {
 
         log::info!("找到仓库根目录: {:?}", root_dir);
 
-        // 遍历本地文件系统
-        for entry in WalkDir::new(&root_dir)
-            .max_depth(max_depth)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            if entry.file_type().is_dir() {
-                // 检查是否包含SKILL.md
-                let skill_md_path = entry.path().join("SKILL.md");
-                if skill_md_path.exists() {
-                    log::info!("发现skill: {:?}", entry.path());
-
-                    // 读取并解析SKILL.md
-                    match self.parse_skill_from_file(&skill_md_path, entry.path(), &root_dir, repo_url) {
-                        Ok(skill) => skills.push(skill),
-                        Err(e) => log::warn!("解析skill失败 {:?}: {}", entry.path(), e),
-                    }
-                }
+        // 遍历本地文件系统（与 API 扫描使用相同的深度与忽略规则）
+        for skill_md_path in fs_ops::find_skill_manifests(&root_dir, &filter, max_depth, &self.manifest_names()) {
+            let skill_dir = skill_md_path.parent().unwrap_or(&root_dir);
+            log::info!("发现skill: {:?}", skill_dir);
+
+            // 读取并解析SKILL.md
+            match self.parse_skill_from_file(&skill_md_path, skill_dir, &root_dir, repo_url) {
+                Ok(skill) => skills.push(skill),
+                Err(e) => log::warn!("解析skill失败 {:?}: {}", skill_dir, e),
             }
         }
 
@@ -658,6 +1152,7 @@ impl GitHubService {
         let mut skill = Skill::new(name, repo_url.to_string(), file_path);
         skill.description = description;
         skill.checksum = Some(checksum);
+        skill.compatible_tools = Skill::parse_compatible_tools(&content);
 
         Ok(skill)
     }
@@ -673,6 +1168,67 @@ impl GitHubService {
         hex::encode(result)
     }
 
+    /// 通过 GraphQL 批量查询多个仓库路径的最新提交 SHA
+    /// 返回值与 queries 一一对应；路径不存在或仓库无法访问时为 None
+    /// GraphQL API 要求认证，未配置 Token 时返回错误，由调用方回退到 REST 逐个查询
+    pub async fn batch_latest_commits(&self, queries: &[LatestCommitQuery]) -> Result<Vec<Option<String>>> {
+        if !self.has_token() {
+            return Err(AppError::new(ErrorKind::Unauthorized, "GraphQL 批量查询需要配置 GitHub Token").into());
+        }
+
+        let mut results = vec![None; queries.len()];
+        let indexed: Vec<(usize, &LatestCommitQuery)> = queries.iter().enumerate().collect();
+
+        for chunk in indexed.chunks(GRAPHQL_BATCH_SIZE) {
+            let (query, aliases) = build_latest_commits_query(chunk);
+            log::info!("GraphQL 批量查询 {} 个技能路径的最新提交", chunk.len());
+
+            let response = self
+                .send(
+                    self.client
+                        .post(format!("{}/graphql", self.api_base))
+                        .json(&serde_json::json!({ "query": query })),
+                )
+                .await
+                .context("GraphQL 请求失败")?;
+
+            self.check_rate_limit(&response)?;
+
+            let status = response.status();
+            if !status.is_success() {
+                anyhow::bail!("GitHub GraphQL API 返回错误: {}", status);
+            }
+
+            let body: serde_json::Value = response.json().await
+                .context("解析 GraphQL 响应失败")?;
+
+            // 部分仓库不存在时 GraphQL 会同时返回 errors 和部分 data，只在完全没有 data 时视为失败
+            if let Some(errors) = body.get("errors").and_then(|e| e.as_array()) {
+                for error in errors {
+                    log::debug!("GraphQL 查询返回错误: {}", error);
+                }
+            }
+            let data = match body.get("data").filter(|d| !d.is_null()) {
+                Some(data) => data,
+                None => anyhow::bail!("GraphQL 响应中没有数据"),
+            };
+
+            for (repo_alias, path_alias, index) in aliases {
+                results[index] = data
+                    .get(&repo_alias)
+                    .and_then(|r| r.get("object"))
+                    .and_then(|o| o.get(&path_alias))
+                    .and_then(|h| h.get("nodes"))
+                    .and_then(|n| n.get(0))
+                    .and_then(|n| n.get("oid"))
+                    .and_then(|oid| oid.as_str())
+                    .map(|oid| oid.to_string());
+            }
+        }
+
+        Ok(results)
+    }
+
     /// 检查技能是否有更新
     /// 返回 Option<String>：如果有更新，返回最新的 commit SHA；如果没有更新或出错，返回 None
     pub async fn check_skill_update(
@@ -702,9 +1258,7 @@ impl GitHubService {
         log::info!("检查技能更新: {}", url);
 
         // 发送请求
-        let response = self.client
-            .get(&url)
-            .send()
+        let response = self.send(self.client.get(&url))
             .await
             .context("检查更新时网络请求失败")?;
 
@@ -716,7 +1270,7 @@ impl GitHubService {
                     if let Err(e) = self.check_rate_limit(&response) {
                         return Err(e);
                     }
-                    return Err(anyhow::anyhow!("无权限访问该仓库"));
+                    return Err(AppError::new(ErrorKind::Unauthorized, "无权限访问该仓库").into());
                 }
                 404 => {
                     log::warn!("技能路径不存在: {}/{}/{}", owner, repo, skill_path);
@@ -742,11 +1296,7 @@ impl GitHubService {
                 owner, repo, skill_path, installed_sha, latest_sha
             );
 
-            // 比较 SHA（只比较前 7 位，因为可能存储的是短 SHA）
-            let installed_short = &installed_sha[..installed_sha.len().min(7)];
-            let latest_short = &latest_sha[..7];
-
-            if installed_short != latest_short {
+            if is_commit_changed(installed_sha, latest_sha) {
                 log::info!("检测到更新可用");
                 return Ok(Some(latest_sha.clone()));
             } else {
@@ -759,8 +1309,157 @@ impl GitHubService {
     }
 }
 
+/// 比较已安装与最新的 commit SHA（只比较前 7 位，因为可能存储的是短 SHA）
+pub fn is_commit_changed(installed_sha: &str, latest_sha: &str) -> bool {
+    let installed_short = &installed_sha[..installed_sha.len().min(7)];
+    let latest_short = &latest_sha[..latest_sha.len().min(7)];
+    installed_short != latest_short
+}
+
+/// API 扫描时是否需要检查该条目（与本地缓存扫描的忽略规则一致）
+fn is_discoverable_dir(item: &GitHubContent, filter: &ScanFilter) -> bool {
+    item.content_type == "dir"
+        && !IGNORED_DISCOVERY_DIRS.contains(&item.name.as_str())
+        && !filter.is_excluded(Path::new(&item.path))
+}
+
+/// 将 ZIP 条目的修改时间（无时区，按 UTC 处理）转换为系统时间
+fn zip_datetime_to_system_time(datetime: zip::DateTime) -> Option<std::time::SystemTime> {
+    let naive = chrono::NaiveDate::from_ymd_opt(datetime.year() as i32, datetime.month() as u32, datetime.day() as u32)?
+        .and_hms_opt(datetime.hour() as u32, datetime.minute() as u32, datetime.second() as u32)?;
+    Some(naive.and_utc().into())
+}
+
+/// 构建批量查询最新提交的 GraphQL 语句
+/// 同一仓库的多个路径合并到一个 repository 节点下，返回 (查询语句, [(仓库别名, 路径别名, 原始下标)])
+fn build_latest_commits_query(chunk: &[(usize, &LatestCommitQuery)]) -> (String, Vec<(String, String, usize)>) {
+    let mut repos: BTreeMap<(&str, &str), Vec<(usize, &str)>> = BTreeMap::new();
+    for (index, q) in chunk {
+        repos
+            .entry((q.owner.as_str(), q.repo.as_str()))
+            .or_default()
+            .push((*index, q.path.as_str()));
+    }
+
+    // 使用 JSON 字符串转义，结果同样是合法的 GraphQL 字符串字面量
+    let quote = |s: &str| serde_json::to_string(s).unwrap_or_else(|_| "\"\"".to_string());
+
+    let mut query = String::from("query {");
+    let mut aliases = Vec::new();
+
+    for (repo_index, ((owner, repo), paths)) in repos.into_iter().enumerate() {
+        let repo_alias = format!("r{}", repo_index);
+        query.push_str(&format!(
+            " {}: repository(owner: {}, name: {}) {{ object(expression: \"HEAD\") {{ ... on Commit {{",
+            repo_alias,
+            quote(owner),
+            quote(repo)
+        ));
+
+        for (index, path) in paths {
+            let path_alias = format!("p{}", index);
+            if path == "." || path.is_empty() {
+                query.push_str(&format!(" {}: history(first: 1) {{ nodes {{ oid }} }}", path_alias));
+            } else {
+                query.push_str(&format!(
+                    " {}: history(first: 1, path: {}) {{ nodes {{ oid }} }}",
+                    path_alias,
+                    quote(path)
+                ));
+            }
+            aliases.push((repo_alias.clone(), path_alias, index));
+        }
+
+        query.push_str(" } } }");
+    }
+
+    query.push_str(" }");
+    (query, aliases)
+}
+
 impl Default for GitHubService {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    /// 对所有请求返回 404 的本地 API 服务，记录收到的请求路径
+    fn serve_not_found() -> (String, Arc<Mutex<Vec<String>>>) {
+        serve("HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string())
+    }
+
+    /// 对所有请求返回同一响应的本地 API 服务，记录收到的请求路径
+    fn serve(response: String) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&requests);
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request_line = String::new();
+                BufReader::new(&stream).read_line(&mut request_line).unwrap();
+                recorded.lock().unwrap().push(request_line.split_whitespace().nth(1).unwrap_or_default().to_string());
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        (format!("http://{}", addr), requests)
+    }
+
+    #[tokio::test]
+    async fn test_archive_download_fails_when_commit_cannot_be_resolved() {
+        let (api_base, requests) = serve_not_found();
+        let mut github = GitHubService::new_with_proxy(None, NetworkConfig::default());
+        github.api_base = api_base;
+        let dir = tempfile::tempdir().unwrap();
+
+        let result = github.download_repository_archive("owner", "repo", None, dir.path()).await;
+        assert!(result.is_err(), "无法解析 commit 时不应使用未经校验的压缩包");
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(*requests, vec!["/repos/owner/repo/commits/main", "/repos/owner/repo/commits/master"]);
+        assert!(!dir.path().join("owner_repo/archive.zip").exists());
+        assert!(!dir.path().join("owner_repo/extracted").exists());
+    }
+
+    #[tokio::test]
+    async fn test_successful_response_with_exhausted_quota_is_not_rate_limited() {
+        let body = r#"{"files":{"a.md":{"filename":"a.md","raw_url":"","content":"hello"}}}"#;
+        let reset = chrono::Utc::now().timestamp() + 600;
+        let (api_base, _) = serve(format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nx-ratelimit-remaining: 0\r\nx-ratelimit-reset: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            reset,
+            body.len(),
+            body
+        ));
+        let mut github = GitHubService::new_with_proxy(None, NetworkConfig::default());
+        github.api_base = api_base;
+
+        // 最后一次配额内的请求仍返回结果，之后的请求等待配额重置
+        let files = github.fetch_gist_files("abc", 1024).await.unwrap();
+        assert_eq!(files, vec![("a.md".to_string(), "hello".to_string())]);
+        assert!(github.rate_limit_status().exhausted);
+    }
+
+    #[tokio::test]
+    async fn test_download_file_limited_aborts_past_the_cap() {
+        let github = GitHubService::new_with_proxy(None, NetworkConfig::default());
+        let body = "x".repeat(100);
+
+        // 声明的长度超过上限
+        let (base, _) = serve(format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body));
+        assert!(github.download_file_limited(&format!("{}/big", base), 10).await.is_err());
+        assert_eq!(github.download_file_limited(&format!("{}/big", base), 100).await.unwrap().len(), 100);
+
+        // 未声明长度时边读取边计数
+        let (base, _) = serve(format!("HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n{}", body));
+        assert!(github.download_file_limited(&format!("{}/big", base), 10).await.is_err());
+        assert_eq!(github.download_file_limited(&format!("{}/big", base), 1000).await.unwrap().len(), 100);
+    }
+}
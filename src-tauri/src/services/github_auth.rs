@@ -0,0 +1,286 @@
+use crate::models::{AppError, ErrorKind};
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const DEVICE_CODE_URL: &str = "https://github.com/login/device/code";
+const ACCESS_TOKEN_URL: &str = "https://github.com/login/oauth/access_token";
+const DEVICE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// 构建时通过 SKILLGUARD_GITHUB_CLIENT_ID 指定的 OAuth App Client ID
+const BUILTIN_CLIENT_ID: Option<&str> = option_env!("SKILLGUARD_GITHUB_CLIENT_ID");
+
+/// 记录当前 Token 已授予权限的 app_settings 键（以逗号分隔）
+pub const TOKEN_SCOPES_KEY: &str = "github_token_scopes";
+
+/// 登录时默认申请的权限：创建 Gist（读取公开仓库不需要额外权限）
+pub const DEFAULT_SCOPES: &[&str] = &["gist"];
+
+/// 包含其他权限的权限（如 repo 包含 public_repo）
+const IMPLIED_SCOPES: &[(&str, &[&str])] = &[
+    ("repo", &["public_repo", "repo:status", "repo_deployment", "repo:invite", "security_events"]),
+    ("user", &["read:user", "user:email", "user:follow"]),
+    ("admin:org", &["write:org", "read:org"]),
+    ("write:org", &["read:org"]),
+];
+
+/// GitHub 返回的 slow_down 未带间隔时，每次增加的轮询间隔
+const SLOW_DOWN_STEP: Duration = Duration::from_secs(5);
+
+/// 设备授权码（在浏览器中打开 verification_uri 并输入 user_code）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceAuthorization {
+    pub user_code: String,
+    pub verification_uri: String,
+    /// 授权码的有效期（秒）
+    pub expires_in: u64,
+    /// 轮询间隔（秒）
+    pub interval: u64,
+}
+
+/// 设备授权登录的状态
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum SignInStatus {
+    /// 等待用户在浏览器中授权
+    Pending,
+    /// 已授权，Token 已保存到系统钥匙串
+    Authorized { login: Option<String>, scopes: Vec<String> },
+    /// 授权码已过期，需要重新开始
+    Expired,
+    /// 用户拒绝了授权
+    Denied,
+}
+
+/// 一次轮询的结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum DevicePoll {
+    Pending,
+    /// 轮询过快，GitHub 要求加大间隔（秒）
+    SlowDown(Option<u64>),
+    Granted { token: String, scopes: Vec<String> },
+    Expired,
+    Denied,
+}
+
+/// Token 对应的账号与权限
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenInfo {
+    pub login: String,
+    /// 经典 Token 授予的权限；细粒度 Token 不返回权限列表，此时为 None
+    pub scopes: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceCodeResponse {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    expires_in: u64,
+    interval: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    scope: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+    interval: Option<u64>,
+}
+
+struct PendingSignIn {
+    client_id: String,
+    device_code: String,
+    interval: Duration,
+    expires_at: Instant,
+    next_poll: Instant,
+}
+
+/// 进行中的设备授权登录（同一时间只保留最近开始的一个）
+#[derive(Default)]
+pub struct GitHubSignIn {
+    pending: Mutex<Option<PendingSignIn>>,
+}
+
+/// 设置中填写的 Client ID 优先，否则使用构建时指定的 Client ID
+pub fn client_id(configured: Option<&str>) -> Option<String> {
+    configured
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .or(BUILTIN_CLIENT_ID)
+        .map(str::to_string)
+}
+
+/// 解析以逗号分隔的权限列表（去重并排序）
+pub fn parse_scopes(scopes: &str) -> Vec<String> {
+    normalize_scopes(scopes.split(',').map(str::to_string))
+}
+
+pub fn normalize_scopes(scopes: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut scopes: Vec<String> = scopes
+        .into_iter()
+        .map(|scope| scope.trim().to_string())
+        .filter(|scope| !scope.is_empty())
+        .collect();
+    scopes.sort();
+    scopes.dedup();
+    scopes
+}
+
+/// required 中尚未授予的权限（已授予包含它的权限时视为已授予）
+pub fn missing_scopes(granted: &[String], required: &[String]) -> Vec<String> {
+    required
+        .iter()
+        .filter(|scope| {
+            !granted.iter().any(|granted| {
+                granted == *scope
+                    || IMPLIED_SCOPES.iter().any(|(parent, implied)| granted == parent && implied.contains(&scope.as_str()))
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+fn parse_token_response(response: TokenResponse) -> Result<DevicePoll> {
+    if let Some(token) = response.access_token.filter(|token| !token.is_empty()) {
+        let scopes = parse_scopes(response.scope.as_deref().unwrap_or_default());
+        return Ok(DevicePoll::Granted { token, scopes });
+    }
+    match response.error.as_deref() {
+        Some("authorization_pending") => Ok(DevicePoll::Pending),
+        Some("slow_down") => Ok(DevicePoll::SlowDown(response.interval)),
+        Some("expired_token") => Ok(DevicePoll::Expired),
+        Some("access_denied") => Ok(DevicePoll::Denied),
+        Some(error) => Err(AppError::new(
+            ErrorKind::Unauthorized,
+            format!("GitHub 登录失败: {}", response.error_description.as_deref().unwrap_or(error)),
+        )
+        .into()),
+        None => anyhow::bail!("无法解析 GitHub 授权响应"),
+    }
+}
+
+impl GitHubSignIn {
+    /// 申请设备授权码，之前未完成的登录被替换
+    pub async fn start(&self, client: &Client, client_id: &str, scopes: &[String]) -> Result<DeviceAuthorization> {
+        let response = client
+            .post(DEVICE_CODE_URL)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .form(&[("client_id", client_id), ("scope", &scopes.join(" "))])
+            .send()
+            .await
+            .context("网络请求失败，无法开始 GitHub 登录")?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("申请 GitHub 设备授权码失败，HTTP 状态码: {}", status);
+        }
+        let code: DeviceCodeResponse = response.json().await.context("无法解析 GitHub 设备授权响应（请检查 Client ID 是否正确）")?;
+
+        let now = Instant::now();
+        let interval = Duration::from_secs(code.interval.max(1));
+        *self.pending.lock().unwrap() = Some(PendingSignIn {
+            client_id: client_id.to_string(),
+            device_code: code.device_code,
+            interval,
+            expires_at: now + Duration::from_secs(code.expires_in),
+            next_poll: now + interval,
+        });
+        log::info!("已开始 GitHub 设备授权登录，权限: {:?}", scopes);
+
+        Ok(DeviceAuthorization {
+            user_code: code.user_code,
+            verification_uri: code.verification_uri,
+            expires_in: code.expires_in,
+            interval: code.interval,
+        })
+    }
+
+    /// 查询授权结果；未到轮询间隔时直接返回 Pending，授权完成、过期或被拒绝后结束本次登录
+    pub async fn poll(&self, client: &Client) -> Result<DevicePoll> {
+        let (client_id, device_code) = {
+            let mut pending = self.pending.lock().unwrap();
+            let Some(session) = pending.as_mut() else {
+                return Err(AppError::invalid_input("没有进行中的 GitHub 登录").into());
+            };
+            let now = Instant::now();
+            if now >= session.expires_at {
+                *pending = None;
+                return Ok(DevicePoll::Expired);
+            }
+            if now < session.next_poll {
+                return Ok(DevicePoll::Pending);
+            }
+            session.next_poll = now + session.interval;
+            (session.client_id.clone(), session.device_code.clone())
+        };
+
+        let response = client
+            .post(ACCESS_TOKEN_URL)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .form(&[("client_id", client_id.as_str()), ("device_code", device_code.as_str()), ("grant_type", DEVICE_GRANT_TYPE)])
+            .send()
+            .await
+            .context("网络请求失败，无法查询 GitHub 授权结果")?;
+        let result = parse_token_response(response.json().await.context("无法解析 GitHub 授权响应")?)?;
+
+        let mut pending = self.pending.lock().unwrap();
+        // 轮询期间登录被取消或重新开始时忽略本次结果
+        let Some(session) = pending.as_mut().filter(|session| session.device_code == device_code) else {
+            return Ok(DevicePoll::Pending);
+        };
+        match &result {
+            DevicePoll::Pending => {}
+            DevicePoll::SlowDown(interval) => {
+                session.interval = interval.map(Duration::from_secs).unwrap_or(session.interval + SLOW_DOWN_STEP);
+                session.next_poll = Instant::now() + session.interval;
+            }
+            DevicePoll::Granted { .. } | DevicePoll::Expired | DevicePoll::Denied => *pending = None,
+        }
+        Ok(result)
+    }
+
+    /// 取消进行中的登录
+    pub fn cancel(&self) {
+        if self.pending.lock().unwrap().take().is_some() {
+            log::info!("已取消 GitHub 设备授权登录");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_token_response_and_scopes() {
+        let response = |error: &str| TokenResponse { error: Some(error.to_string()), ..Default::default() };
+        assert_eq!(parse_token_response(response("authorization_pending")).unwrap(), DevicePoll::Pending);
+        assert_eq!(parse_token_response(response("expired_token")).unwrap(), DevicePoll::Expired);
+        assert_eq!(parse_token_response(response("access_denied")).unwrap(), DevicePoll::Denied);
+        assert_eq!(
+            parse_token_response(TokenResponse { interval: Some(10), ..response("slow_down") }).unwrap(),
+            DevicePoll::SlowDown(Some(10))
+        );
+        let error = parse_token_response(response("incorrect_client_credentials")).unwrap_err();
+        assert_eq!(AppError::from(error).kind, ErrorKind::Unauthorized);
+
+        let granted = TokenResponse {
+            access_token: Some("gho_abc".to_string()),
+            scope: Some("repo,gist, gist".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            parse_token_response(granted).unwrap(),
+            DevicePoll::Granted { token: "gho_abc".to_string(), scopes: vec!["gist".to_string(), "repo".to_string()] }
+        );
+
+        let granted = parse_scopes("repo, read:org");
+        let required = parse_scopes("public_repo,gist,read:org");
+        assert_eq!(missing_scopes(&granted, &required), vec!["gist".to_string()]);
+        assert_eq!(client_id(Some(" Iv1.abc ")), Some("Iv1.abc".to_string()));
+    }
+}
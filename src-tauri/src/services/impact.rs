@@ -0,0 +1,281 @@
+use crate::models::{normalize_skill_path, same_repository, AiTool, AppError, ErrorKind, ManifestNames, Skill, SkillList};
+use crate::services::fs_ops;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// frontmatter 中声明依赖的其他技能的字段
+const DEPENDENCY_KEYS: &[&str] = &["dependencies", "depends-on", "depends_on", "requires"];
+
+/// 工具目录（及其技能目录）中视为固定技能清单的文件名，格式同技能清单导入/导出
+const PINNED_MANIFEST_NAMES: &[&str] = &["skills.json", "skills.yaml", "skills.yml"];
+
+/// 依赖该技能的其他已安装技能
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillDependent {
+    pub skill_id: String,
+    pub skill_name: String,
+    /// 声明依赖的技能清单文件
+    pub manifest_path: String,
+}
+
+/// 其他工具目录中指向该技能安装目录的符号链接
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkedLocation {
+    pub tool_id: String,
+    pub tool_name: String,
+    pub path: String,
+    pub target: String,
+}
+
+/// 卸载技能（或其中一个安装路径）前需要确认的影响
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UninstallImpact {
+    /// 在技能清单 frontmatter 中声明依赖该技能的其他已安装技能
+    pub dependents: Vec<SkillDependent>,
+    /// 卸载后会失效的符号链接
+    pub linked_locations: Vec<LinkedLocation>,
+    /// 引用该技能的固定技能清单文件
+    pub pinned_manifests: Vec<String>,
+}
+
+/// 卸载时对各项影响的确认（前端在用户确认后传入）
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct UninstallConfirmation {
+    pub dependents: bool,
+    pub linked_locations: bool,
+    pub pinned_manifests: bool,
+}
+
+impl UninstallImpact {
+    pub fn is_empty(&self) -> bool {
+        self.dependents.is_empty() && self.linked_locations.is_empty() && self.pinned_manifests.is_empty()
+    }
+
+    /// 存在未确认的影响时返回 ConfirmationRequired 错误
+    pub fn check_confirmed(&self, confirmation: &UninstallConfirmation) -> Result<(), AppError> {
+        let mut unconfirmed = Vec::new();
+        if !self.dependents.is_empty() && !confirmation.dependents {
+            let names: Vec<&str> = self.dependents.iter().map(|d| d.skill_name.as_str()).collect();
+            unconfirmed.push(format!("{} 个技能依赖该技能（{}）", names.len(), names.join("、")));
+        }
+        if !self.linked_locations.is_empty() && !confirmation.linked_locations {
+            unconfirmed.push(format!("{} 个工具目录中的符号链接将失效", self.linked_locations.len()));
+        }
+        if !self.pinned_manifests.is_empty() && !confirmation.pinned_manifests {
+            unconfirmed.push(format!("{} 个技能清单引用了该技能", self.pinned_manifests.len()));
+        }
+
+        if unconfirmed.is_empty() {
+            return Ok(());
+        }
+        Err(AppError::new(
+            ErrorKind::ConfirmationRequired,
+            format!("卸载前需要确认以下影响: {}", unconfirmed.join("；")),
+        ))
+    }
+}
+
+/// 分析卸载技能的影响
+///
+/// `removed_dirs` 为将要删除的安装目录；`fully_removed` 表示卸载后技能不再有任何安装路径，
+/// 此时才检查依赖它的技能与引用它的技能清单
+pub fn analyze_uninstall(
+    skill: &Skill,
+    removed_dirs: &[PathBuf],
+    fully_removed: bool,
+    installed_skills: &[Skill],
+    tools: &[AiTool],
+    names: &ManifestNames,
+) -> UninstallImpact {
+    let mut impact = UninstallImpact {
+        linked_locations: find_linked_locations(removed_dirs, tools),
+        ..UninstallImpact::default()
+    };
+    if !fully_removed {
+        return impact;
+    }
+
+    let skill_names = skill_names(skill);
+    for other in installed_skills.iter().filter(|other| other.installed && other.id != skill.id) {
+        for dir in other.local_paths.iter().flatten().chain(other.local_path.iter()).map(PathBuf::from) {
+            let Some(manifest) = fs_ops::find_skill_manifest(&dir, names) else {
+                continue;
+            };
+            let Ok(content) = fs::read_to_string(&manifest) else {
+                continue;
+            };
+            if declared_dependencies(&content).iter().any(|dep| skill_names.iter().any(|name| name.eq_ignore_ascii_case(dep))) {
+                impact.dependents.push(SkillDependent {
+                    skill_id: other.id.clone(),
+                    skill_name: other.name.clone(),
+                    manifest_path: manifest.to_string_lossy().to_string(),
+                });
+                break;
+            }
+        }
+    }
+
+    if !skill.source_type.is_local() {
+        for tool in tools {
+            for dir in [tool.base_path.clone(), tool.skills_path()] {
+                for name in PINNED_MANIFEST_NAMES {
+                    let path = dir.join(name);
+                    if references_skill(&path, skill) && !impact.pinned_manifests.contains(&path.to_string_lossy().to_string()) {
+                        impact.pinned_manifests.push(path.to_string_lossy().to_string());
+                    }
+                }
+            }
+        }
+    }
+    impact
+}
+
+/// 依赖声明中可能使用的技能名称：技能名与技能目录名
+fn skill_names(skill: &Skill) -> Vec<String> {
+    let mut names = vec![skill.name.clone()];
+    if let Some(dir_name) = normalize_skill_path(&skill.file_path).rsplit('/').next().filter(|name| !name.is_empty()) {
+        names.push(dir_name.to_string());
+    }
+    names
+}
+
+/// 技能清单 frontmatter 中声明依赖的技能名称（字符串以逗号分隔，或列表）
+pub fn declared_dependencies(skill_md: &str) -> Vec<String> {
+    let mut lines = skill_md.lines();
+    if lines.next().map(str::trim_end) != Some("---") {
+        return Vec::new();
+    }
+    let frontmatter: Vec<&str> = lines.take_while(|line| line.trim_end() != "---").collect();
+    let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(&frontmatter.join("\n")) else {
+        return Vec::new();
+    };
+
+    let mut dependencies = Vec::new();
+    for key in DEPENDENCY_KEYS {
+        let items: Vec<String> = match value.get(*key) {
+            Some(serde_yaml::Value::String(list)) => list.split(',').map(|item| item.trim().to_string()).collect(),
+            Some(serde_yaml::Value::Sequence(items)) => {
+                items.iter().filter_map(|item| item.as_str()).map(|item| item.trim().to_string()).collect()
+            }
+            _ => continue,
+        };
+        dependencies.extend(items.into_iter().filter(|item| !item.is_empty()));
+    }
+    dependencies
+}
+
+/// 各工具技能目录中指向（或位于）将要删除的目录的符号链接
+pub fn find_linked_locations(removed_dirs: &[PathBuf], tools: &[AiTool]) -> Vec<LinkedLocation> {
+    let removed: Vec<PathBuf> = removed_dirs.iter().map(|dir| canonical(dir)).collect();
+    let mut linked = Vec::new();
+
+    for tool in tools {
+        let Ok(entries) = fs::read_dir(tool.skills_path()) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !entry.file_type().is_ok_and(|file_type| file_type.is_symlink()) {
+                continue;
+            }
+            let Ok(target) = fs::read_link(&path) else {
+                continue;
+            };
+            let target = match target.is_absolute() {
+                true => target,
+                false => path.parent().map(|parent| parent.join(&target)).unwrap_or(target),
+            };
+            let resolved = canonical(&target);
+            if removed.iter().any(|dir| resolved.starts_with(dir)) {
+                linked.push(LinkedLocation {
+                    tool_id: tool.id.clone(),
+                    tool_name: tool.name.clone(),
+                    path: path.to_string_lossy().to_string(),
+                    target: target.to_string_lossy().to_string(),
+                });
+            }
+        }
+    }
+    linked
+}
+
+/// 规范化路径（无法解析时原样返回）
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// 技能清单文件是否引用了该技能（同一仓库且路径一致，或引用整个仓库）
+fn references_skill(path: &Path, skill: &Skill) -> bool {
+    let Ok(content) = fs::read_to_string(path) else {
+        return false;
+    };
+    let Ok(list) = SkillList::parse(&content) else {
+        return false;
+    };
+    let skill_path = normalize_skill_path(&skill.file_path);
+    list.skills.iter().any(|entry| {
+        same_repository(&entry.repository, &skill.repository_url)
+            && entry.path.as_deref().is_none_or(|path| normalize_skill_path(path) == skill_path)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_uninstall_impact() {
+        let dir = tempfile::tempdir().unwrap();
+        let central = dir.path().join("central/pdf");
+        fs::create_dir_all(&central).unwrap();
+        fs::write(central.join("SKILL.md"), "---\nname: pdf\n---\n").unwrap();
+
+        let mut skill = Skill::new(
+            "pdf".to_string(),
+            "https://github.com/anthropics/skills".to_string(),
+            "document-skills/pdf".to_string(),
+        );
+        skill.installed = true;
+        skill.local_paths = Some(vec![central.to_string_lossy().to_string()]);
+
+        let forms = dir.path().join("central/forms");
+        fs::create_dir_all(&forms).unwrap();
+        fs::write(forms.join("SKILL.md"), "---\nname: forms\ndependencies: [PDF, docx]\n---\n").unwrap();
+        let mut dependent = Skill::new("forms".to_string(), "local".to_string(), "forms".to_string());
+        dependent.installed = true;
+        dependent.local_path = Some(forms.to_string_lossy().to_string());
+
+        let tool = AiTool::new("cursor", "Cursor", dir.path().join(".cursor"), "skills");
+        fs::create_dir_all(tool.skills_path()).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&central, tool.skills_path().join("pdf")).unwrap();
+        fs::write(
+            tool.base_path.join("skills.yaml"),
+            "skills:\n  - repository: https://github.com/anthropics/skills\n    path: document-skills/pdf\n",
+        )
+        .unwrap();
+
+        let names = ManifestNames::new(&[]);
+        let skills = vec![skill.clone(), dependent];
+        let impact = analyze_uninstall(&skill, std::slice::from_ref(&central), true, &skills, std::slice::from_ref(&tool), &names);
+        assert_eq!(impact.dependents.len(), 1);
+        assert_eq!(impact.dependents[0].skill_name, "forms");
+        assert_eq!(impact.pinned_manifests.len(), 1);
+        #[cfg(unix)]
+        assert_eq!(impact.linked_locations.len(), 1);
+
+        let error = impact.check_confirmed(&UninstallConfirmation { dependents: true, ..Default::default() }).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::ConfirmationRequired);
+        let all = UninstallConfirmation { dependents: true, linked_locations: true, pinned_manifests: true };
+        assert!(impact.check_confirmed(&all).is_ok());
+
+        // 只移除其中一个安装路径时不检查依赖与清单
+        let partial = analyze_uninstall(&skill, &[], false, &skills, &[tool], &names);
+        assert!(partial.is_empty());
+    }
+}
@@ -0,0 +1,273 @@
+use anyhow::{Context, Result};
+use chrono::Local;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+
+/// 日志文件名（位于应用数据目录下的 logs 子目录）
+const LOG_FILE_NAME: &str = "agent-skills-guard.log";
+/// 单个日志文件的大小上限，超过后轮转
+const MAX_LOG_FILE_SIZE: u64 = 5 * 1024 * 1024;
+/// 保留的历史日志文件数量（agent-skills-guard.log.1 ~ .3）
+const MAX_ROTATED_FILES: usize = 3;
+/// 本应用的日志 target 前缀；第三方依赖的日志只记录 warn 及以上级别，避免刷屏
+const APP_TARGETS: &[&str] = &["agent_skills_guard", "skillguard_core"];
+
+static LOGGER: AppLogger = AppLogger {
+    file: Mutex::new(None),
+};
+
+/// 一条日志记录（供前端日志查看及问题反馈使用）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+/// 应用日志：同时输出到 stderr 与按大小轮转的日志文件
+pub struct AppLogger {
+    file: Mutex<Option<RotatingFile>>,
+}
+
+impl AppLogger {
+    /// 安装全局日志（应用启动时调用一次）
+    /// 设置了 RUST_LOG 时以其为初始级别，否则为 info
+    pub fn init() {
+        let level = std::env::var("RUST_LOG")
+            .ok()
+            .and_then(|value| LevelFilter::from_str(value.trim()).ok())
+            .unwrap_or(LevelFilter::Info);
+
+        if log::set_logger(&LOGGER).is_ok() {
+            log::set_max_level(level);
+        }
+    }
+
+    /// 开始写入日志文件（应用数据目录确定后调用）
+    pub fn attach_file(dir: &Path) -> Result<()> {
+        let file = RotatingFile::open(dir.to_path_buf())?;
+        *LOGGER.file.lock().unwrap_or_else(|e| e.into_inner()) = Some(file);
+        Ok(())
+    }
+
+    /// 运行时调整日志级别
+    pub fn set_level(level: LevelFilter) {
+        log::set_max_level(level);
+        log::info!("日志级别已调整为: {}", level);
+    }
+
+    /// 读取最近的日志（从旧到新），只返回不低于 min_level 严重程度的记录
+    pub fn recent(limit: usize, min_level: LevelFilter) -> Result<Vec<LogEntry>> {
+        let dir = match LOGGER.file.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            Some(file) => file.dir.clone(),
+            None => return Ok(Vec::new()),
+        };
+
+        read_recent(&dir, limit, min_level)
+    }
+}
+
+impl Log for AppLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+            && (metadata.level() <= Level::Warn
+                || APP_TARGETS.iter().any(|t| metadata.target().starts_with(t)))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let line = format!(
+            "{} {:<5} {}: {}\n",
+            Local::now().format("%Y-%m-%dT%H:%M:%S%.3f%:z"),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+
+        let _ = std::io::stderr().write_all(line.as_bytes());
+
+        if let Some(file) = self.file.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+            // 写日志失败时无法再记录日志，只能输出到 stderr
+            if let Err(e) = file.write(line.as_bytes()) {
+                eprintln!("写入日志文件失败: {}", e);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(file) = self.file.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+            let _ = file.file.flush();
+        }
+    }
+}
+
+/// 按大小轮转的日志文件
+struct RotatingFile {
+    dir: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    fn open(dir: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&dir).context(format!("无法创建日志目录: {:?}", dir))?;
+        let path = dir.join(LOG_FILE_NAME);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .context(format!("无法打开日志文件: {:?}", path))?;
+        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+        Ok(Self { dir, file, size })
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        if self.size + bytes.len() as u64 > MAX_LOG_FILE_SIZE && self.size > 0 {
+            self.rotate()?;
+        }
+
+        self.file.write_all(bytes)?;
+        self.size += bytes.len() as u64;
+        Ok(())
+    }
+
+    /// agent-skills-guard.log -> .1 -> .2 -> .3，最旧的文件被删除
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+
+        let _ = fs::remove_file(log_file_path(&self.dir, MAX_ROTATED_FILES));
+        for index in (0..MAX_ROTATED_FILES).rev() {
+            let from = log_file_path(&self.dir, index);
+            if from.exists() {
+                fs::rename(&from, log_file_path(&self.dir, index + 1))?;
+            }
+        }
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_file_path(&self.dir, 0))?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+/// 第 index 个日志文件路径（0 为当前文件）
+fn log_file_path(dir: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        dir.join(LOG_FILE_NAME)
+    } else {
+        dir.join(format!("{}.{}", LOG_FILE_NAME, index))
+    }
+}
+
+/// 从最新的文件开始向前读取，直到凑够 limit 条
+fn read_recent(dir: &Path, limit: usize, min_level: LevelFilter) -> Result<Vec<LogEntry>> {
+    let mut entries: Vec<LogEntry> = Vec::new();
+
+    for index in 0..=MAX_ROTATED_FILES {
+        if entries.len() >= limit {
+            break;
+        }
+
+        let path = log_file_path(dir, index);
+        let content = match fs::read(&path) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e).context(format!("无法读取日志文件: {:?}", path)),
+        };
+
+        let mut older: Vec<LogEntry> = parse_log(&content)
+            .into_iter()
+            .filter(|entry| {
+                Level::from_str(&entry.level).is_ok_and(|level| level <= min_level)
+            })
+            .collect();
+        older.append(&mut entries);
+        entries = older;
+    }
+
+    let skip = entries.len().saturating_sub(limit);
+    Ok(entries.split_off(skip))
+}
+
+/// 解析日志文本；不符合格式的行（多行消息的后续行）并入上一条记录
+fn parse_log(content: &str) -> Vec<LogEntry> {
+    let mut entries: Vec<LogEntry> = Vec::new();
+
+    for line in content.lines() {
+        match parse_line(line) {
+            Some(entry) => entries.push(entry),
+            None => {
+                if let Some(last) = entries.last_mut() {
+                    last.message.push('\n');
+                    last.message.push_str(line);
+                }
+            }
+        }
+    }
+
+    entries
+}
+
+fn parse_line(line: &str) -> Option<LogEntry> {
+    let (timestamp, rest) = line.split_once(' ')?;
+    let (level, rest) = rest.trim_start().split_once(' ')?;
+    let (target, message) = rest.trim_start().split_once(": ")?;
+
+    if Level::from_str(level).is_err() || chrono::DateTime::parse_from_rfc3339(timestamp).is_err() {
+        return None;
+    }
+
+    Some(LogEntry {
+        timestamp: timestamp.to_string(),
+        level: level.to_string(),
+        target: target.to_string(),
+        message: message.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn line(level: &str, message: &str) -> String {
+        format!("2026-01-01T08:00:00.000+08:00 {:<5} agent_skills_guard_lib: {}\n", level, message)
+    }
+
+    #[test]
+    fn test_rotate_and_read_recent() {
+        let dir = tempdir().expect("tempdir");
+        let mut file = RotatingFile::open(dir.path().to_path_buf()).unwrap();
+
+        file.write(line("INFO", "first").as_bytes()).unwrap();
+        file.write(line("ERROR", "second\ncaused by: io").as_bytes()).unwrap();
+        file.rotate().unwrap();
+        file.write(line("DEBUG", "third").as_bytes()).unwrap();
+        file.write(line("WARN", "fourth").as_bytes()).unwrap();
+
+        assert!(log_file_path(dir.path(), 1).exists());
+
+        let all = read_recent(dir.path(), 10, LevelFilter::Trace).unwrap();
+        let messages: Vec<&str> = all.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["first", "second\ncaused by: io", "third", "fourth"]);
+
+        let warnings = read_recent(dir.path(), 10, LevelFilter::Warn).unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[1].level, "WARN");
+
+        let latest = read_recent(dir.path(), 1, LevelFilter::Trace).unwrap();
+        assert_eq!(latest[0].message, "fourth");
+    }
+}
@@ -1,10 +1,45 @@
 pub mod github;
+pub mod github_auth;
 pub mod skill_manager;
 pub mod database;
 pub mod proxy;
+pub mod blob_store;
+pub mod rate_limit;
+pub mod settings;
+pub mod credentials;
+pub mod backup;
+pub mod logging;
+pub mod feedback;
+pub mod signature;
+pub mod profile;
+pub mod portable;
+pub mod autostart;
+pub mod fs_ops;
+pub mod priority;
+pub mod archive;
+pub mod dropped;
+pub mod clipboard;
+pub mod impact;
+pub mod dir_access;
+pub mod direct_url;
+pub mod skill_backups;
+pub mod relocate;
+pub mod rename;
+pub mod remote_content;
+pub mod paths;
+pub mod path_policy;
 
-pub use github::GitHubService;
+pub use github::{GitHubService, LatestCommitQuery};
 pub use skill_manager::SkillManager;
 pub use database::Database;
-pub use proxy::{ProxyConfig, ProxyService};
+pub use proxy::{NetworkConfig, ProxyConfig, ProxyService};
+pub use blob_store::{BlobStore, CacheManifest};
+pub use rate_limit::{RateLimitStatus, RateLimiter};
 
+pub use settings::{Settings, SettingsChange, SettingsService};
+pub use credentials::CredentialStore;
+pub use backup::DatabaseBackups;
+pub use logging::{AppLogger, LogEntry};
+pub use profile::ProfileStore;
+pub use paths::CachePaths;
+pub use path_policy::PathPolicy;
@@ -0,0 +1,162 @@
+use crate::models::{get_all_supported_tools, AppError, ErrorKind};
+use crate::services::settings::DirectorySettings;
+use std::path::{Component, Path, PathBuf};
+
+/// 前端传入的写入路径（安装目录、修复或重定位的目标等）只能位于以下目录中，
+/// 避免 WebView 被利用时可以写入任意位置：
+///
+/// - 技能安装目录（设置中的目录或默认工具的技能目录）
+/// - 各支持工具的技能目录
+/// - 用户在目录选择器中选择过的目录（保存在设置的 approved_install_dirs 中）
+///
+/// 导出的报告、技能清单与数据库备份使用单独的 [`PathPolicy::exports`]，
+/// 只能写入用户在保存对话框中选择过的目录（approved_export_dirs）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathPolicy {
+    roots: Vec<PathBuf>,
+}
+
+impl PathPolicy {
+    pub fn new(directories: &DirectorySettings) -> Self {
+        let mut roots = install_root_paths(directories);
+        roots.extend(directories.approved_install_dirs.iter().cloned());
+        Self::with_roots(roots)
+    }
+
+    /// 技能安装目录：设置中的安装目录、各支持工具的技能目录与已安装技能所在的目录（不含其他批准的目录）
+    pub fn install_roots(directories: &DirectorySettings, installed_dirs: &[PathBuf]) -> Self {
+        let mut roots = install_root_paths(directories);
+        roots.extend(installed_dirs.iter().filter_map(|dir| dir.parent()).map(Path::to_path_buf));
+        Self::with_roots(roots)
+    }
+
+    /// 导出文件允许写入的目录
+    pub fn exports(directories: &DirectorySettings) -> Self {
+        Self::with_roots(directories.approved_export_dirs.clone())
+    }
+
+    fn with_roots(roots: Vec<PathBuf>) -> Self {
+        let mut roots: Vec<PathBuf> = roots.iter().filter_map(|root| resolve(root)).collect();
+        roots.sort();
+        roots.dedup();
+        Self { roots }
+    }
+
+    /// 允许写入的目录
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// 路径是否位于允许写入的目录中（含目录本身）
+    pub fn allows(&self, path: &Path) -> bool {
+        resolve(path).is_some_and(|path| self.roots.iter().any(|root| path.starts_with(root)))
+    }
+
+    /// 路径与任一目录重叠（位于目录中，或包含该目录）
+    pub fn overlaps(&self, path: &Path) -> bool {
+        resolve(path).is_some_and(|path| self.roots.iter().any(|root| path.starts_with(root) || root.starts_with(&path)))
+    }
+
+    /// 检查前端传入的写入路径，不在允许的目录中时返回 PathNotAllowed 错误。
+    /// 返回解析符号链接后的路径，调用方应写入该路径而不是原始字符串
+    pub fn check(&self, path: &str) -> Result<PathBuf, AppError> {
+        let path_buf = PathBuf::from(path);
+        if !path_buf.is_absolute() {
+            return Err(AppError::invalid_input(format!("路径必须是绝对路径: {}", path)));
+        }
+        if path_buf.components().any(|component| component == Component::ParentDir) {
+            return Err(AppError::invalid_input(format!("路径不能包含 \"..\": {}", path)));
+        }
+        match resolve(&path_buf) {
+            Some(resolved) if self.roots.iter().any(|root| resolved.starts_with(root)) => Ok(resolved),
+            _ => {
+                log::warn!("拒绝写入不在允许目录中的路径: {}", path);
+                Err(AppError::new(
+                    ErrorKind::PathNotAllowed,
+                    format!("不允许写入该目录，请通过目录选择器选择目录: {}", path),
+                ))
+            }
+        }
+    }
+}
+
+fn install_root_paths(directories: &DirectorySettings) -> Vec<PathBuf> {
+    let mut roots = vec![directories.resolved_skills_dir()];
+    roots.extend(get_all_supported_tools().iter().map(|tool| tool.skills_path()));
+    roots
+}
+
+/// 规范化路径：解析最深的已存在上级目录中的符号链接（避免通过指向其他位置的符号链接离开允许的目录）。
+/// `..` 在符号链接解析前无法按字面处理（`<root>/link/..` 实际指向链接目标的上级），
+/// 因此包含 `..` 的路径与相对路径一样返回 None
+fn resolve(path: &Path) -> Option<PathBuf> {
+    if !path.is_absolute() || path.components().any(|component| component == Component::ParentDir) {
+        return None;
+    }
+    let normalized: PathBuf = path.components().filter(|component| *component != Component::CurDir).collect();
+
+    let mut existing = normalized.as_path();
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(canonical) = std::fs::canonicalize(existing) {
+            return Some(rest.iter().rev().fold(canonical, |path, name| path.join(name)));
+        }
+        rest.push(existing.file_name()?.to_os_string());
+        existing = existing.parent()?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_policy_restricts_writes_to_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("skills");
+        std::fs::create_dir_all(&root).unwrap();
+        let policy = PathPolicy::with_roots(vec![root.clone(), PathBuf::from("relative")]);
+        assert_eq!(policy.roots().len(), 1);
+
+        assert!(policy.allows(&root));
+        assert!(policy.allows(&root.join("new-skill/nested")));
+        assert!(!policy.allows(&root.join("../outside")));
+        assert!(!policy.allows(dir.path()));
+        assert!(policy.check(&root.join("pdf").to_string_lossy()).is_ok());
+        assert_eq!(policy.check("skills/pdf").unwrap_err().kind, ErrorKind::InvalidInput);
+        assert_eq!(policy.check(&dir.path().join("other").to_string_lossy()).unwrap_err().kind, ErrorKind::PathNotAllowed);
+
+        // 重叠检查同时覆盖上级目录与下级目录
+        assert!(policy.overlaps(&root.join("backups")));
+        assert!(policy.overlaps(dir.path()));
+        assert!(!policy.overlaps(&dir.path().join("backups")));
+
+        // 导出只允许写入批准的导出目录
+        let directories = DirectorySettings { approved_export_dirs: vec![dir.path().join("exports")], ..Default::default() };
+        let exports = PathPolicy::exports(&directories);
+        assert!(exports.check(&dir.path().join("exports/report.md").to_string_lossy()).is_ok());
+        assert_eq!(exports.check(&root.join("report.md").to_string_lossy()).unwrap_err().kind, ErrorKind::PathNotAllowed);
+
+        #[cfg(unix)]
+        {
+            // 指向允许目录之外的符号链接
+            std::os::unix::fs::symlink(dir.path(), root.join("escape")).unwrap();
+            assert!(!policy.allows(&root.join("escape/other")));
+
+            // 先解析符号链接再处理 ".."：<root>/link/../x 实际位于链接目标的上级目录
+            let outside = dir.path().join("outside/deep");
+            std::fs::create_dir_all(&outside).unwrap();
+            std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+            let escape = root.join("link/../x");
+            assert!(!policy.allows(&escape));
+            assert_eq!(policy.check(&escape.to_string_lossy()).unwrap_err().kind, ErrorKind::InvalidInput);
+
+            // 返回解析符号链接后的路径
+            let inner = root.join("inner");
+            std::fs::create_dir_all(&inner).unwrap();
+            std::os::unix::fs::symlink(&inner, root.join("alias")).unwrap();
+            let checked = policy.check(&root.join("alias/pdf").to_string_lossy()).unwrap();
+            assert_eq!(checked, std::fs::canonicalize(&inner).unwrap().join("pdf"));
+        }
+    }
+}
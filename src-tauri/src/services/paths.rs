@@ -0,0 +1,199 @@
+use crate::services::{portable, profile, Database};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 缓存目录名（Linux 等平台位于 $XDG_CACHE_HOME，未设置时为 ~/.cache；Windows 位于 %LOCALAPPDATA%）
+const CACHE_DIR_NAME: &str = "agent-skills-guard";
+/// macOS 按惯例以 Bundle ID 命名 ~/Library/Caches 下的目录（与 tauri.conf.json 中的 identifier 一致）
+#[cfg(target_os = "macos")]
+const BUNDLE_IDENTIFIER: &str = "com.agent-skills-guard.app";
+/// 非默认配置档的缓存目录位于默认配置档缓存目录下的该目录中
+const PROFILES_DIR: &str = "profiles";
+
+/// 配置档的缓存目录布局
+///
+/// - `repositories/{owner}_{repo}/`：仓库压缩包与解压后的缓存
+/// - `staging/{owner}_{repo}/`：准备更新时下载的新版本
+/// - `reinstall/{owner}_{repo}/`：按安装时的 commit 重新安装时的下载
+/// - `skill-backups/`：更新前的安装目录备份（可在设置中改为其他目录）
+/// - `quarantine/`：被自动隔离的技能目录
+/// - `blobs/`：内容寻址存储，仓库缓存与 staging 以硬链接共享其中的文件
+/// - `profiles/{name}/`：非默认配置档的缓存目录（只位于默认配置档的缓存目录下）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachePaths {
+    root: PathBuf,
+}
+
+impl CachePaths {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// 当前配置档的缓存目录（无法确定系统缓存目录时为 None）
+    pub fn current() -> Option<Self> {
+        profile::cache_root().map(Self::new)
+    }
+
+    /// 当前配置档的缓存目录，无法确定系统缓存目录时返回错误
+    pub fn require() -> Result<Self> {
+        Self::current().context("无法获取系统缓存目录")
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn repositories(&self) -> PathBuf {
+        self.root.join("repositories")
+    }
+
+    pub fn staging(&self) -> PathBuf {
+        self.root.join("staging")
+    }
+
+    pub fn reinstall(&self) -> PathBuf {
+        self.root.join("reinstall")
+    }
+
+    pub fn backups(&self) -> PathBuf {
+        self.root.join("skill-backups")
+    }
+
+    pub fn quarantine(&self) -> PathBuf {
+        self.root.join("quarantine")
+    }
+
+    pub fn blobs(&self) -> PathBuf {
+        self.root.join("blobs")
+    }
+}
+
+/// 所有配置档共用的缓存基础目录：便携模式下为便携数据目录中的 cache，否则按平台约定
+pub fn cache_base() -> Option<PathBuf> {
+    match portable::data_root() {
+        Some(data_root) => Some(data_root.join("cache")),
+        None => platform_cache_base(),
+    }
+}
+
+/// 配置档在缓存基础目录下的缓存目录
+pub fn profile_cache_dir(base: &Path, name: &str) -> PathBuf {
+    if name == profile::DEFAULT_PROFILE {
+        base.to_path_buf()
+    } else {
+        base.join(PROFILES_DIR).join(name)
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn platform_cache_base() -> Option<PathBuf> {
+    Some(dirs::cache_dir()?.join(BUNDLE_IDENTIFIER))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn platform_cache_base() -> Option<PathBuf> {
+    Some(dirs::cache_dir()?.join(CACHE_DIR_NAME))
+}
+
+/// 旧版本使用的缓存基础目录：macOS 上曾直接使用 ~/Library/Caches/agent-skills-guard，其他平台没有变化
+fn legacy_cache_base() -> Option<PathBuf> {
+    if cfg!(target_os = "macos") {
+        Some(dirs::cache_dir()?.join(CACHE_DIR_NAME))
+    } else {
+        None
+    }
+}
+
+/// 迁移旧版本的缓存目录：把旧目录中的内容移动到当前约定的位置，并更新当前配置档数据库中
+/// 指向旧目录的缓存路径（其他配置档的记录在切换到该配置档启动时更新）。返回更新的记录数
+///
+/// 需在创建仓库缓存、staging 等目录之前调用（启动时打开数据库后）
+pub fn migrate_legacy_cache(db: &Database) -> Result<usize> {
+    if portable::is_portable() {
+        return Ok(0);
+    }
+    let (Some(legacy_base), Some(base)) = (legacy_cache_base(), cache_base()) else {
+        return Ok(0);
+    };
+    migrate_cache_base(&legacy_base, &base, db, profile::active_profile())
+}
+
+fn migrate_cache_base(legacy_base: &Path, base: &Path, db: &Database, profile: &str) -> Result<usize> {
+    if legacy_base == base {
+        return Ok(0);
+    }
+    if legacy_base.is_dir() {
+        let moved = move_cache_dir(legacy_base, base)?;
+        log::info!("已将 {} 项缓存从 {:?} 迁移到 {:?}", moved, legacy_base, base);
+    }
+    db.rebase_cache_paths(&profile_cache_dir(legacy_base, profile), &profile_cache_dir(base, profile))
+}
+
+/// 逐项移动缓存目录的内容（同名项已存在时保留在旧目录中），profiles 下的配置档缓存目录逐个合并，
+/// 旧目录清空后删除。返回移动的项数
+fn move_cache_dir(from: &Path, to: &Path) -> Result<usize> {
+    fs::create_dir_all(to).context(format!("无法创建缓存目录: {:?}", to))?;
+
+    let mut moved = 0;
+    for entry in fs::read_dir(from).context(format!("无法读取旧的缓存目录: {:?}", from))? {
+        let entry = entry?;
+        let (source, target) = (entry.path(), to.join(entry.file_name()));
+        if !target.exists() {
+            match fs::rename(&source, &target) {
+                Ok(()) => moved += 1,
+                Err(e) => log::warn!("迁移缓存失败: {:?}, 错误: {}", source, e),
+            }
+        } else if entry.file_name() == PROFILES_DIR && source.is_dir() {
+            for profile_dir in fs::read_dir(&source)?.flatten().filter(|dir| dir.path().is_dir()) {
+                moved += move_cache_dir(&profile_dir.path(), &target.join(profile_dir.file_name()))?;
+            }
+            remove_dir_if_empty(&source);
+        } else {
+            log::warn!("缓存目录中已存在同名项，保留旧的缓存: {:?}", source);
+        }
+    }
+    remove_dir_if_empty(from);
+    Ok(moved)
+}
+
+fn remove_dir_if_empty(dir: &Path) {
+    if fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_none()) {
+        let _ = fs::remove_dir(dir);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Repository;
+
+    #[test]
+    fn test_migrate_cache_base() {
+        let dir = tempfile::tempdir().unwrap();
+        let (legacy, base) = (dir.path().join("Caches/agent-skills-guard"), dir.path().join("Caches/com.example.app"));
+        let legacy_paths = CachePaths::new(legacy.clone());
+        fs::create_dir_all(legacy_paths.repositories().join("owner_repo/extracted")).unwrap();
+        fs::create_dir_all(legacy.join("profiles/work/staging")).unwrap();
+        fs::create_dir_all(base.join("profiles/home")).unwrap();
+
+        let db = Database::new(dir.path().join("agent-skills.db")).unwrap();
+        let repo = Repository::new("https://github.com/owner/repo".to_string(), "repo".to_string());
+        db.add_repository(&repo).unwrap();
+        let cache_path = legacy_paths.repositories().join("owner_repo/extracted");
+        db.update_repository_cache(&repo.id, &cache_path.to_string_lossy(), chrono::Utc::now(), None).unwrap();
+
+        assert_eq!(migrate_cache_base(&legacy, &base, &db, profile::DEFAULT_PROFILE).unwrap(), 1);
+        let paths = CachePaths::new(base.clone());
+        assert!(paths.repositories().join("owner_repo/extracted").is_dir());
+        assert!(base.join("profiles/work/staging").is_dir());
+        assert!(base.join("profiles/home").is_dir());
+        assert!(!legacy.exists());
+
+        let cache_path = db.get_repositories().unwrap().into_iter().find(|r| r.id == repo.id).unwrap().cache_path;
+        assert_eq!(cache_path.map(PathBuf::from), Some(paths.repositories().join("owner_repo/extracted")));
+
+        // 再次迁移时没有需要处理的内容
+        assert_eq!(migrate_cache_base(&legacy, &base, &db, profile::DEFAULT_PROFILE).unwrap(), 0);
+    }
+}
@@ -0,0 +1,63 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// 便携模式标记文件：放在可执行文件旁（macOS 为 .app 所在目录）即启用便携模式
+pub const PORTABLE_MARKER: &str = "portable";
+/// 便携模式命令行参数
+pub const PORTABLE_FLAG: &str = "--portable";
+/// 便携模式下的数据目录名（位于可执行文件旁）
+const PORTABLE_DATA_DIR: &str = "data";
+
+/// 便携模式数据目录（首次访问时检测，进程内不变）
+static PORTABLE_ROOT: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// 便携模式的数据目录：启用便携模式时数据库、设置、缓存与日志均存放在此目录，
+/// 而不是系统的应用数据/缓存目录；未启用时返回 None
+pub fn data_root() -> Option<&'static Path> {
+    PORTABLE_ROOT.get_or_init(detect).as_deref()
+}
+
+/// 是否处于便携模式
+pub fn is_portable() -> bool {
+    data_root().is_some()
+}
+
+fn detect() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let base_dir = portable_base_dir(exe.parent()?);
+
+    let flag = std::env::args().skip(1).any(|arg| arg == PORTABLE_FLAG);
+    if !flag && !base_dir.join(PORTABLE_MARKER).is_file() {
+        return None;
+    }
+
+    let data_dir = base_dir.join(PORTABLE_DATA_DIR);
+    log::info!("已启用便携模式，数据目录: {:?}", data_dir);
+    Some(data_dir)
+}
+
+/// 便携模式的基础目录：可执行文件所在目录；macOS 应用包内的可执行文件使用 .app 所在目录
+fn portable_base_dir(exe_dir: &Path) -> PathBuf {
+    if exe_dir.ends_with("Contents/MacOS") {
+        let bundle = exe_dir.parent().and_then(Path::parent);
+        if let Some(parent) = bundle.filter(|b| b.extension().is_some_and(|ext| ext == "app")).and_then(Path::parent) {
+            return parent.to_path_buf();
+        }
+    }
+    exe_dir.to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portable_base_dir() {
+        assert_eq!(
+            portable_base_dir(Path::new("/Volumes/USB/Agent Skills Guard.app/Contents/MacOS")),
+            PathBuf::from("/Volumes/USB")
+        );
+        assert_eq!(portable_base_dir(Path::new("/media/usb/skills-guard")), PathBuf::from("/media/usb/skills-guard"));
+        assert_eq!(portable_base_dir(Path::new("/opt/Contents/MacOS")), PathBuf::from("/opt/Contents/MacOS"));
+    }
+}
@@ -0,0 +1,101 @@
+use anyhow::{Context, Result};
+
+/// 后台线程的 nice 值（Linux 下线程级生效）
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const BACKGROUND_NICE: libc::c_int = 10;
+
+/// 在独立线程中执行后台任务（定时扫描等），`low_priority` 时降低该线程的系统调度优先级，
+/// 避免长时间占满 CPU。
+///
+/// 不使用 tokio 的阻塞线程池：降低的优先级无法在不具备权限时恢复，复用线程会影响之后用户发起的操作
+pub async fn run_background<T, F>(low_priority: bool, f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    std::thread::Builder::new()
+        .name("background-scan".to_string())
+        .spawn(move || {
+            if low_priority {
+                if let Err(e) = lower_current_thread_priority() {
+                    log::warn!("降低后台线程优先级失败: {}", e);
+                }
+            }
+            let _ = sender.send(f());
+        })
+        .context("创建后台线程失败")?;
+
+    receiver.await.context("后台线程异常退出")?
+}
+
+/// 降低当前线程的调度优先级（后台模式）
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn lower_current_thread_priority() -> Result<()> {
+    // Linux 的 nice 值按线程生效；已经更低的优先级保持不变
+    let tid = unsafe { libc::gettid() } as libc::id_t;
+    let current = unsafe { libc::getpriority(libc::PRIO_PROCESS, tid) };
+    if current >= BACKGROUND_NICE {
+        return Ok(());
+    }
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS, tid, BACKGROUND_NICE) } != 0 {
+        return Err(std::io::Error::last_os_error()).context("setpriority 调用失败");
+    }
+    Ok(())
+}
+
+/// 降低当前线程的调度优先级（后台模式）
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub fn lower_current_thread_priority() -> Result<()> {
+    // 后台模式同时降低 CPU 与磁盘 I/O 优先级
+    if unsafe { libc::setpriority(libc::PRIO_DARWIN_THREAD, 0, libc::PRIO_DARWIN_BG) } != 0 {
+        return Err(std::io::Error::last_os_error()).context("setpriority 调用失败");
+    }
+    Ok(())
+}
+
+/// 降低当前线程的调度优先级（后台模式）
+#[cfg(windows)]
+pub fn lower_current_thread_priority() -> Result<()> {
+    use windows_sys::Win32::System::Threading::{GetCurrentThread, SetThreadPriority, THREAD_MODE_BACKGROUND_BEGIN};
+
+    // 后台模式同时降低 CPU 与磁盘 I/O 优先级
+    if unsafe { SetThreadPriority(GetCurrentThread(), THREAD_MODE_BACKGROUND_BEGIN) } == 0 {
+        return Err(std::io::Error::last_os_error()).context("SetThreadPriority 调用失败");
+    }
+    Ok(())
+}
+
+/// 其他平台不支持按线程调整优先级
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos", target_os = "ios", windows)))]
+pub fn lower_current_thread_priority() -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_background_lowers_only_its_thread() {
+        let caller = std::thread::current().id();
+        let (thread, name) = run_background(true, || {
+            Ok((std::thread::current().id(), std::thread::current().name().map(str::to_string)))
+        })
+        .await
+        .unwrap();
+        assert_ne!(thread, caller);
+        assert_eq!(name.as_deref(), Some("background-scan"));
+
+        #[cfg(target_os = "linux")]
+        {
+            let nice = run_background(true, || Ok(unsafe { libc::getpriority(libc::PRIO_PROCESS, libc::gettid() as libc::id_t) }))
+                .await
+                .unwrap();
+            assert!(nice >= BACKGROUND_NICE);
+        }
+
+        let error = run_background(false, || -> Result<()> { anyhow::bail!("failed") }).await.unwrap_err();
+        assert_eq!(error.to_string(), "failed");
+    }
+}
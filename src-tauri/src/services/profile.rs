@@ -0,0 +1,273 @@
+use crate::services::paths;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// 默认配置档：数据直接存放在应用数据目录中（兼容未启用多配置档的旧版本）
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// 配置档列表文件（位于应用数据目录）
+const PROFILES_FILE: &str = "profiles.json";
+/// 配置档名称最大长度
+const MAX_PROFILE_NAME_LEN: usize = 32;
+
+/// 当前进程使用的配置档（启动时确定，切换配置档需重启应用）
+static ACTIVE_PROFILE: OnceLock<String> = OnceLock::new();
+
+/// 配置档信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 配置档列表项（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileInfo {
+    pub name: String,
+    pub created_at: Option<DateTime<Utc>>,
+    /// 是否为下次启动使用的配置档
+    pub active: bool,
+    /// 是否为当前进程正在使用的配置档
+    pub current: bool,
+    pub data_dir: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProfilesFile {
+    active: Option<String>,
+    #[serde(default)]
+    profiles: Vec<Profile>,
+}
+
+/// 配置档管理：每个配置档拥有独立的数据库（含设置与技能目录映射）、备份与缓存目录
+///
+/// 目录结构：
+/// - `{app_dir}/profiles.json`：配置档列表及下次启动使用的配置档
+/// - `{app_dir}/profiles/{name}/`：非默认配置档的数据目录
+/// - `{cache_base}/profiles/{name}/`：非默认配置档的缓存目录（缓存基础目录见 [`paths::cache_base`]）
+///
+/// 便携模式下 `{app_dir}` 与缓存目录均位于可执行文件旁的 data 目录中
+pub struct ProfileStore {
+    app_dir: PathBuf,
+}
+
+impl ProfileStore {
+    pub fn new(app_dir: PathBuf) -> Self {
+        Self { app_dir }
+    }
+
+    fn profiles_path(&self) -> PathBuf {
+        self.app_dir.join(PROFILES_FILE)
+    }
+
+    fn load(&self) -> ProfilesFile {
+        let path = self.profiles_path();
+        match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                log::warn!("配置档列表解析失败，使用默认配置档: {}", e);
+                ProfilesFile::default()
+            }),
+            Err(_) => ProfilesFile::default(),
+        }
+    }
+
+    fn save(&self, file: &ProfilesFile) -> Result<()> {
+        fs::create_dir_all(&self.app_dir).context("无法创建应用数据目录")?;
+        let json = serde_json::to_string_pretty(file)?;
+        fs::write(self.profiles_path(), json).context("无法写入配置档列表")?;
+        Ok(())
+    }
+
+    fn exists(file: &ProfilesFile, name: &str) -> bool {
+        name == DEFAULT_PROFILE || file.profiles.iter().any(|p| p.name == name)
+    }
+
+    /// 下次启动使用的配置档（记录的配置档已不存在时回退到默认配置档）
+    pub fn active(&self) -> String {
+        let file = self.load();
+        file.active
+            .clone()
+            .filter(|name| Self::exists(&file, name))
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+    }
+
+    /// 配置档的数据目录
+    pub fn data_dir(&self, name: &str) -> PathBuf {
+        if name == DEFAULT_PROFILE {
+            self.app_dir.clone()
+        } else {
+            self.app_dir.join("profiles").join(name)
+        }
+    }
+
+    /// 列出所有配置档（默认配置档始终在首位）
+    pub fn list(&self) -> Vec<ProfileInfo> {
+        let file = self.load();
+        let active = self.active();
+        let current = active_profile();
+
+        let default = ProfileInfo {
+            name: DEFAULT_PROFILE.to_string(),
+            created_at: None,
+            active: false,
+            current: false,
+            data_dir: String::new(),
+        };
+        let profiles = file.profiles.iter().map(|p| ProfileInfo {
+            name: p.name.clone(),
+            created_at: Some(p.created_at),
+            active: false,
+            current: false,
+            data_dir: String::new(),
+        });
+
+        std::iter::once(default)
+            .chain(profiles)
+            .map(|mut info| {
+                info.active = info.name == active;
+                info.current = info.name == current;
+                info.data_dir = self.data_dir(&info.name).to_string_lossy().to_string();
+                info
+            })
+            .collect()
+    }
+
+    /// 创建配置档（新配置档使用默认设置与空数据库，首次切换时初始化）
+    pub fn create(&self, name: &str) -> Result<Profile> {
+        let name = validate_profile_name(name)?;
+        let mut file = self.load();
+        if Self::exists(&file, &name) {
+            anyhow::bail!("配置档已存在: {}", name);
+        }
+
+        let profile = Profile {
+            name,
+            created_at: Utc::now(),
+        };
+        fs::create_dir_all(self.data_dir(&profile.name)).context("无法创建配置档数据目录")?;
+        file.profiles.push(profile.clone());
+        self.save(&file)?;
+
+        log::info!("已创建配置档: {}", profile.name);
+        Ok(profile)
+    }
+
+    /// 设置下次启动使用的配置档
+    pub fn set_active(&self, name: &str) -> Result<()> {
+        let mut file = self.load();
+        if !Self::exists(&file, name) {
+            anyhow::bail!("配置档不存在: {}", name);
+        }
+
+        file.active = Some(name.to_string());
+        self.save(&file)
+    }
+
+    /// 删除配置档及其数据目录与缓存（不能删除默认配置档或正在使用的配置档）
+    pub fn delete(&self, name: &str) -> Result<()> {
+        if name == DEFAULT_PROFILE {
+            anyhow::bail!("不能删除默认配置档");
+        }
+        if name == active_profile() || name == self.active() {
+            anyhow::bail!("不能删除正在使用的配置档，请先切换到其他配置档");
+        }
+
+        let mut file = self.load();
+        let before = file.profiles.len();
+        file.profiles.retain(|p| p.name != name);
+        if file.profiles.len() == before {
+            anyhow::bail!("配置档不存在: {}", name);
+        }
+        self.save(&file)?;
+
+        remove_dir_if_exists(&self.data_dir(name))?;
+        if let Some(cache_dir) = profile_cache_root(name) {
+            remove_dir_if_exists(&cache_dir)?;
+        }
+
+        log::info!("已删除配置档: {}", name);
+        Ok(())
+    }
+}
+
+fn remove_dir_if_exists(dir: &Path) -> Result<()> {
+    if dir.exists() {
+        fs::remove_dir_all(dir).context(format!("无法删除目录: {:?}", dir))?;
+    }
+    Ok(())
+}
+
+/// 校验配置档名称：仅允许字母、数字、- 与 _，返回去除首尾空白后的名称
+pub fn validate_profile_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() || name.len() > MAX_PROFILE_NAME_LEN {
+        anyhow::bail!("配置档名称长度必须在 1 到 {} 个字符之间", MAX_PROFILE_NAME_LEN);
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        anyhow::bail!("配置档名称只能包含字母、数字、- 和 _: {}", name);
+    }
+    if name.eq_ignore_ascii_case(DEFAULT_PROFILE) {
+        anyhow::bail!("配置档名称不能为 {}", DEFAULT_PROFILE);
+    }
+    Ok(name.to_string())
+}
+
+/// 设置当前进程使用的配置档（启动时调用一次）
+pub fn activate(name: &str) {
+    if ACTIVE_PROFILE.set(name.to_string()).is_err() {
+        log::warn!("配置档已初始化，忽略重复设置: {}", name);
+    }
+}
+
+/// 当前进程使用的配置档
+pub fn active_profile() -> &'static str {
+    ACTIVE_PROFILE.get().map(String::as_str).unwrap_or(DEFAULT_PROFILE)
+}
+
+/// 指定配置档的缓存根目录（便携模式下位于便携数据目录中）
+fn profile_cache_root(name: &str) -> Option<PathBuf> {
+    paths::cache_base().map(|base| paths::profile_cache_dir(&base, name))
+}
+
+/// 当前配置档的缓存根目录（目录布局见 [`paths::CachePaths`]）
+pub fn cache_root() -> Option<PathBuf> {
+    profile_cache_root(active_profile())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_store() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().to_path_buf();
+        let store = ProfileStore::new(dir.clone());
+
+        assert_eq!(store.active(), DEFAULT_PROFILE);
+        assert_eq!(store.data_dir(DEFAULT_PROFILE), dir);
+
+        store.create("client-a").unwrap();
+        assert!(store.create("client-a").is_err());
+        assert!(store.create("Default").is_err());
+        assert!(store.create("../evil").is_err());
+        assert!(store.data_dir("client-a").is_dir());
+
+        store.set_active("client-a").unwrap();
+        assert_eq!(store.active(), "client-a");
+        assert!(store.set_active("missing").is_err());
+        assert!(store.delete("client-a").is_err());
+
+        let names: Vec<_> = store.list().into_iter().map(|p| (p.name, p.active)).collect();
+        assert_eq!(names, vec![(DEFAULT_PROFILE.to_string(), false), ("client-a".to_string(), true)]);
+
+        store.set_active(DEFAULT_PROFILE).unwrap();
+        store.delete("client-a").unwrap();
+        assert!(!store.data_dir("client-a").exists());
+        assert_eq!(store.list().len(), 1);
+    }
+}
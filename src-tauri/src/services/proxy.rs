@@ -3,8 +3,31 @@ use reqwest::{Client, Proxy};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
+/// 代理 URL 支持的协议
+const PROXY_SCHEMES: &[&str] = &["http", "https", "socks5", "socks5h"];
+
+/// 默认的代理测试地址：验证能否通过代理访问 GitHub API
+pub const DEFAULT_PROXY_TEST_URL: &str = "https://api.github.com";
+
+fn default_proxy_test_url() -> String {
+    DEFAULT_PROXY_TEST_URL.to_string()
+}
+
+/// 按主机覆盖的代理规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProxyOverride {
+    /// 主机匹配模式：完整主机名（github.com）或子域名通配（*.example.com，同时匹配 example.com）
+    pub host: String,
+    /// 该主机使用的代理 URL（如 socks5h://127.0.0.1:1080、http://proxy:8080），为 None 时直连
+    #[serde(default)]
+    pub proxy_url: Option<String>,
+}
+
 /// SOCKS5 代理配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// 按请求的主机解析：先匹配 overrides（第一条匹配的规则生效），再匹配 no_proxy，
+/// 其余主机在启用时使用全局代理。仓库按其 URL 的主机（如 github.com、内部 GitLab）使用对应的规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProxyConfig {
     /// 是否启用代理
     pub enabled: bool,
@@ -16,6 +39,15 @@ pub struct ProxyConfig {
     pub username: Option<String>,
     /// 密码（可选）
     pub password: Option<String>,
+    /// 不使用全局代理的主机（匹配规则同 ProxyOverride::host）
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+    /// 按主机覆盖的代理规则，不受 enabled 影响
+    #[serde(default)]
+    pub overrides: Vec<ProxyOverride>,
+    /// 测试代理连接时访问的地址
+    #[serde(default = "default_proxy_test_url")]
+    pub test_url: String,
 }
 
 impl Default for ProxyConfig {
@@ -26,6 +58,9 @@ impl Default for ProxyConfig {
             port: 1080,
             username: None,
             password: None,
+            no_proxy: Vec::new(),
+            overrides: Vec::new(),
+            test_url: default_proxy_test_url(),
         }
     }
 }
@@ -45,66 +80,301 @@ impl ProxyConfig {
         }
         format!("socks5h://{}:{}", self.host, self.port)
     }
+
+    /// 是否需要为请求设置代理（启用了全局代理或配置了按主机覆盖的规则）
+    pub fn has_routes(&self) -> bool {
+        (self.enabled && self.is_valid()) || !self.overrides.is_empty()
+    }
+
+    /// 解析访问指定主机时使用的代理 URL，None 表示直连
+    pub fn proxy_url_for(&self, host: &str) -> Option<String> {
+        if let Some(rule) = self.overrides.iter().find(|rule| host_matches(&rule.host, host)) {
+            return rule.proxy_url.clone();
+        }
+        if self.no_proxy.iter().any(|pattern| host_matches(pattern, host)) {
+            return None;
+        }
+        (self.enabled && self.is_valid()).then(|| self.to_proxy_url())
+    }
+
+    /// 校验 no_proxy、按主机覆盖的规则与测试地址
+    pub fn validate_rules(&self) -> Result<()> {
+        Self::parse_test_url(&self.test_url)?;
+
+        let hosts = self.no_proxy.iter().chain(self.overrides.iter().map(|rule| &rule.host));
+        if let Some(host) = hosts.into_iter().find(|host| host.trim().is_empty() || host.contains(['/', ':'])) {
+            anyhow::bail!("无效的代理主机规则（需为主机名或 *.域名）: {:?}", host);
+        }
+
+        for url in self.overrides.iter().filter_map(|rule| rule.proxy_url.as_deref()) {
+            let parsed = reqwest::Url::parse(url).with_context(|| format!("无效的代理 URL: {}", url))?;
+            if !PROXY_SCHEMES.contains(&parsed.scheme()) || parsed.host_str().is_none() {
+                anyhow::bail!("无效的代理 URL（支持 http/https/socks5/socks5h）: {}", url);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 解析测试地址，仅支持带主机名的 http/https URL
+    fn parse_test_url(url: &str) -> Result<reqwest::Url> {
+        let parsed = reqwest::Url::parse(url.trim()).with_context(|| format!("无效的代理测试地址: {}", url))?;
+        if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+            anyhow::bail!("无效的代理测试地址（需为 http/https URL）: {}", url);
+        }
+        Ok(parsed)
+    }
+
+    /// NO_PROXY 环境变量的值（*.example.com 转换为 .example.com）
+    pub fn no_proxy_env(&self) -> String {
+        self.no_proxy
+            .iter()
+            .map(|pattern| pattern.trim().trim_start_matches('*'))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// 主机是否匹配规则：不区分大小写；*.example.com / .example.com 匹配 example.com 及其子域名，* 匹配所有主机
+fn host_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.trim().to_ascii_lowercase();
+    let host = host.to_ascii_lowercase();
+
+    if pattern == "*" {
+        return true;
+    }
+    match pattern.strip_prefix("*.").or_else(|| pattern.strip_prefix('.')) {
+        Some(domain) => host == domain || host.ends_with(&format!(".{}", domain)),
+        None => host == pattern,
+    }
+}
+
+/// 代理测试失败的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyTestStage {
+    /// 本地无法解析代理服务器地址
+    ProxyDns,
+    /// 无法建立到代理服务器的 TCP 连接
+    ProxyConnect,
+    /// 代理无法解析或连接测试地址的主机
+    TargetDns,
+    /// 与测试地址的 TLS 握手失败（证书错误或被中间人拦截）
+    Tls,
+    /// 请求已发出但未得到有效响应
+    Request,
+}
+
+impl ProxyTestStage {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            ProxyTestStage::ProxyDns => "DNS 解析代理服务器失败",
+            ProxyTestStage::ProxyConnect => "连接代理服务器失败",
+            ProxyTestStage::TargetDns => "代理无法解析或连接目标主机",
+            ProxyTestStage::Tls => "TLS 握手失败",
+            ProxyTestStage::Request => "请求失败",
+        }
+    }
+
+    /// 根据请求错误（含 source 链）判断失败阶段
+    fn classify(error: &(dyn std::error::Error + 'static)) -> Self {
+        let chain = std::iter::successors(Some(error), |err| err.source())
+            .map(|err| err.to_string().to_ascii_lowercase())
+            .collect::<Vec<_>>()
+            .join(": ");
+
+        if ["certificate", "tls", "handshake", "ssl"].iter().any(|k| chain.contains(k)) {
+            ProxyTestStage::Tls
+        } else if ["dns", "resolve", "lookup", "host unreachable", "name or service"].iter().any(|k| chain.contains(k)) {
+            ProxyTestStage::TargetDns
+        } else if ["socks", "proxy", "connection refused"].iter().any(|k| chain.contains(k)) {
+            ProxyTestStage::ProxyConnect
+        } else {
+            ProxyTestStage::Request
+        }
+    }
+}
+
+/// 网络请求配置（超时与重试）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    /// 连接超时（秒）
+    pub connect_timeout_secs: u64,
+    /// 读取超时（秒）：两次读取之间的最长等待时间，避免连接停滞导致永久挂起
+    pub read_timeout_secs: u64,
+    /// 网络错误或服务器 5xx 错误时的最大重试次数
+    pub max_retries: u32,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout_secs: 10,
+            read_timeout_secs: 30,
+            max_retries: 2,
+        }
+    }
+}
+
+impl NetworkConfig {
+    /// 检查配置是否有效
+    pub fn is_valid(&self) -> bool {
+        (1..=300).contains(&self.connect_timeout_secs)
+            && (1..=600).contains(&self.read_timeout_secs)
+            && self.max_retries <= 10
+    }
+
+    /// 第 attempt 次重试前的等待时间（指数退避，上限 8 秒）
+    pub fn retry_delay(&self, attempt: u32) -> Duration {
+        Duration::from_millis(500u64.saturating_mul(1 << attempt.min(4)))
+    }
 }
 
 /// 代理服务
 pub struct ProxyService;
 
 impl ProxyService {
-    /// 根据代理配置构建 HTTP 客户端
-    pub fn build_http_client(config: Option<&ProxyConfig>) -> Result<Client> {
-        let mut builder = Client::builder()
-            .user_agent("agent-skills-guard")
-            .timeout(Duration::from_secs(30))
-            .connect_timeout(Duration::from_secs(10));
+    /// 根据代理配置和网络配置构建 HTTP 客户端（代理按请求的主机解析，见 [`ProxyConfig::proxy_url_for`]）
+    pub fn build_http_client(config: Option<&ProxyConfig>, network: &NetworkConfig) -> Result<Client> {
+        let mut builder = Self::base_client_builder(network);
 
-        if let Some(cfg) = config {
+        if let Some(cfg) = config.filter(|cfg| cfg.has_routes()) {
             if cfg.enabled && cfg.is_valid() {
-                let proxy_url = cfg.to_proxy_url();
                 log::info!("使用 SOCKS5 代理: {}:{}", cfg.host, cfg.port);
-                let proxy = Proxy::all(&proxy_url)
-                    .context("无法创建代理配置")?;
-                builder = builder.proxy(proxy);
             }
+            if !cfg.overrides.is_empty() || !cfg.no_proxy.is_empty() {
+                log::info!("代理按主机覆盖规则 {} 条，直连主机 {} 条", cfg.overrides.len(), cfg.no_proxy.len());
+            }
+
+            // 按请求的主机解析代理（覆盖规则 > no_proxy > 全局代理）
+            let cfg = cfg.clone();
+            let proxy = Proxy::custom(move |url| {
+                cfg.proxy_url_for(url.host_str()?)
+                    .and_then(|proxy_url| reqwest::Url::parse(&proxy_url).ok())
+            });
+            builder = builder.proxy(proxy);
         }
 
         builder.build().context("无法创建 HTTP 客户端")
     }
 
+    /// 应用网络配置的基础客户端构建器（不含代理）
+    pub fn base_client_builder(network: &NetworkConfig) -> reqwest::ClientBuilder {
+        Client::builder()
+            .user_agent("agent-skills-guard")
+            .connect_timeout(Duration::from_secs(network.connect_timeout_secs))
+            .read_timeout(Duration::from_secs(network.read_timeout_secs))
+    }
+
     /// 测试代理连接
-    /// 通过代理访问 google.com 来验证代理是否可用
-    pub async fn test_proxy(config: &ProxyConfig) -> Result<()> {
+    ///
+    /// 依次检查：解析代理服务器地址、连接代理服务器、通过代理访问 test_url（默认 api.github.com），
+    /// 失败时在错误信息中标明所在阶段（DNS / 连接 / TLS / 请求）
+    pub async fn test_proxy(config: &ProxyConfig, network: &NetworkConfig) -> Result<()> {
         if !config.is_valid() {
             anyhow::bail!("代理配置无效：主机或端口为空");
         }
+        let test_url = ProxyConfig::parse_test_url(&config.test_url)?;
+        let connect_timeout = Duration::from_secs(network.connect_timeout_secs);
 
-        // 创建一个临时的带代理的客户端
-        let proxy_url = config.to_proxy_url();
-        let proxy = Proxy::all(&proxy_url)
-            .context("无法创建代理配置")?;
+        log::info!("测试代理连接: {}:{} -> {}", config.host, config.port, test_url);
 
-        let client = Client::builder()
-            .user_agent("agent-skills-guard")
-            .timeout(Duration::from_secs(10))
-            .connect_timeout(Duration::from_secs(5))
+        // 1. 本地解析代理服务器地址
+        let addrs: Vec<_> = tokio::net::lookup_host((config.host.as_str(), config.port))
+            .await
+            .with_context(|| format!("[{}] {}", ProxyTestStage::ProxyDns.describe(), config.host))?
+            .collect();
+        let Some(addr) = addrs.first().copied() else {
+            anyhow::bail!("[{}] {} 没有可用地址", ProxyTestStage::ProxyDns.describe(), config.host);
+        };
+
+        // 2. 连接代理服务器
+        tokio::time::timeout(connect_timeout, tokio::net::TcpStream::connect(addr))
+            .await
+            .map_err(|_| anyhow::anyhow!("[{}] {} 连接超时", ProxyTestStage::ProxyConnect.describe(), addr))?
+            .with_context(|| format!("[{}] {}", ProxyTestStage::ProxyConnect.describe(), addr))?;
+
+        // 3. 通过代理访问测试地址
+        let proxy = Proxy::all(config.to_proxy_url())
+            .context("无法创建代理配置")?;
+        let client = Self::base_client_builder(network)
             .proxy(proxy)
             .build()
             .context("无法创建测试客户端")?;
 
-        // 尝试通过代理访问 google.com
-        log::info!("测试代理连接: {}:{}", config.host, config.port);
-        
-        let response = client
-            .get("https://www.google.com")
-            .send()
-            .await
-            .context("通过代理访问 google.com 失败")?;
+        let response = match client.get(test_url.clone()).send().await {
+            Ok(response) => response,
+            Err(e) => {
+                // 去掉错误信息中的 URL，避免主机名干扰阶段判断
+                let e = e.without_url();
+                let stage = ProxyTestStage::classify(&e);
+                return Err(anyhow::Error::from(e)
+                    .context(format!("[{}] 通过代理访问 {} 失败", stage.describe(), test_url)));
+            }
+        };
 
         if response.status().is_success() || response.status().is_redirection() {
             log::info!("代理测试成功，状态码: {}", response.status());
             Ok(())
         } else {
-            anyhow::bail!("代理测试失败，HTTP 状态码: {}", response.status())
+            anyhow::bail!("[{}] {} 返回 HTTP 状态码: {}", ProxyTestStage::Request.describe(), test_url, response.status())
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxy_url_for_host() {
+        let config = ProxyConfig {
+            enabled: true,
+            host: "127.0.0.1".to_string(),
+            no_proxy: vec!["*.corp.example".to_string(), "localhost".to_string()],
+            overrides: vec![
+                ProxyOverride { host: "gitlab.corp.example".to_string(), proxy_url: Some("http://proxy.corp:8080".to_string()) },
+                ProxyOverride { host: "raw.githubusercontent.com".to_string(), proxy_url: None },
+            ],
+            ..ProxyConfig::default()
+        };
+        assert!(config.validate_rules().is_ok());
+
+        assert_eq!(config.proxy_url_for("api.github.com").as_deref(), Some("socks5h://127.0.0.1:1080"));
+        assert_eq!(config.proxy_url_for("GitLab.corp.example").as_deref(), Some("http://proxy.corp:8080"));
+        assert_eq!(config.proxy_url_for("wiki.corp.example"), None);
+        assert_eq!(config.proxy_url_for("corp.example"), None);
+        assert_eq!(config.proxy_url_for("notcorp.example").as_deref(), Some("socks5h://127.0.0.1:1080"));
+        assert_eq!(config.proxy_url_for("raw.githubusercontent.com"), None);
+        assert_eq!(config.no_proxy_env(), ".corp.example,localhost");
+
+        // 未启用全局代理时只有覆盖规则生效
+        let config = ProxyConfig { enabled: false, ..config };
+        assert!(config.has_routes());
+        assert_eq!(config.proxy_url_for("api.github.com"), None);
+        assert_eq!(config.proxy_url_for("gitlab.corp.example").as_deref(), Some("http://proxy.corp:8080"));
+
+        let invalid = ProxyConfig {
+            overrides: vec![ProxyOverride { host: "github.com".to_string(), proxy_url: Some("ftp://proxy".to_string()) }],
+            ..ProxyConfig::default()
+        };
+        assert!(invalid.validate_rules().is_err());
+
+        let invalid = ProxyConfig { test_url: "socks5://api.github.com".to_string(), ..ProxyConfig::default() };
+        assert!(invalid.validate_rules().is_err());
+    }
+
+    #[test]
+    fn test_classify_proxy_test_failure() {
+        let tls = std::io::Error::other("invalid peer certificate: UnknownIssuer");
+        assert_eq!(ProxyTestStage::classify(&tls), ProxyTestStage::Tls);
+
+        let dns = std::io::Error::other("socks connect error: Host unreachable");
+        assert_eq!(ProxyTestStage::classify(&dns), ProxyTestStage::TargetDns);
+
+        let refused = std::io::Error::other("socks connect error: Connection refused");
+        assert_eq!(ProxyTestStage::classify(&refused), ProxyTestStage::ProxyConnect);
+
+        let other = std::io::Error::other("operation timed out");
+        assert_eq!(ProxyTestStage::classify(&other), ProxyTestStage::Request);
+    }
+}
@@ -0,0 +1,202 @@
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::header::HeaderMap;
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// 单次限流等待的上限：GitHub 的配额窗口为 1 小时，超过该时长的重置时间视为异常，不再排队
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(65 * 60);
+
+/// GitHub API 限流状态（供前端展示等待时间）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitStatus {
+    pub limit: Option<u32>,
+    pub remaining: Option<u32>,
+    pub reset_at: Option<DateTime<Utc>>,
+    /// 当前是否已耗尽配额
+    pub exhausted: bool,
+    /// 距离配额重置的秒数（未耗尽时为 0）
+    pub eta_seconds: u64,
+    /// 正在排队等待配额重置的请求数
+    pub queued_requests: usize,
+}
+
+#[derive(Debug, Default)]
+struct RateLimitState {
+    limit: Option<u32>,
+    remaining: Option<u32>,
+    reset_at: Option<DateTime<Utc>>,
+    /// 二级限流（retry-after）要求的最早重试时间
+    retry_after_until: Option<DateTime<Utc>>,
+}
+
+/// GitHub 请求调度器：跟踪 x-ratelimit-* 响应头，配额耗尽时让后续请求排队等待重置
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    state: Mutex<RateLimitState>,
+    queued: AtomicUsize,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 根据响应头更新限流状态
+    pub fn update_from_headers(&self, headers: &HeaderMap) {
+        let header_u32 = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u32>().ok())
+        };
+
+        let limit = header_u32("x-ratelimit-limit");
+        let remaining = header_u32("x-ratelimit-remaining");
+        let reset_at = headers
+            .get("x-ratelimit-reset")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<i64>().ok())
+            .and_then(|ts| Utc.timestamp_opt(ts, 0).single());
+        let retry_after = header_u32("retry-after");
+
+        let mut state = self.state.lock().unwrap();
+        if remaining.is_some() {
+            state.limit = limit.or(state.limit);
+            state.remaining = remaining;
+            state.reset_at = reset_at.or(state.reset_at);
+        }
+        if let Some(secs) = retry_after {
+            state.retry_after_until = Some(Utc::now() + chrono::Duration::seconds(secs as i64));
+        }
+    }
+
+    /// 需要等待的时长（配额未耗尽时为 None）
+    pub fn pending_wait(&self) -> Option<Duration> {
+        let state = self.state.lock().unwrap();
+        let now = Utc::now();
+
+        let quota_wait = match (state.remaining, state.reset_at) {
+            (Some(0), Some(reset_at)) if reset_at > now => Some(reset_at),
+            _ => None,
+        };
+        let retry_wait = state.retry_after_until.filter(|until| *until > now);
+
+        let until = match (quota_wait, retry_wait) {
+            (Some(a), Some(b)) => a.max(b),
+            (a, b) => a.or(b)?,
+        };
+
+        // 额外等待 1 秒，避免恰好在重置边界上再次被拒绝
+        (until - now)
+            .to_std()
+            .ok()
+            .map(|d| d + Duration::from_secs(1))
+    }
+
+    /// 配额耗尽时排队等待重置；等待时间超过上限时立即返回 false，由调用方按原错误处理
+    pub async fn acquire(&self) -> bool {
+        let wait = match self.pending_wait() {
+            Some(wait) => wait,
+            None => return true,
+        };
+
+        if wait > MAX_RATE_LIMIT_WAIT {
+            log::warn!("GitHub API 限流重置时间过长（{} 秒），不再排队等待", wait.as_secs());
+            return false;
+        }
+
+        let queued = self.queued.fetch_add(1, Ordering::SeqCst) + 1;
+        log::info!(
+            "GitHub API 配额已耗尽，请求进入队列（当前排队 {} 个），约 {} 秒后自动恢复",
+            queued,
+            wait.as_secs()
+        );
+
+        tokio::time::sleep(wait).await;
+        self.queued.fetch_sub(1, Ordering::SeqCst);
+
+        // 重置后清除耗尽状态，等待下一次响应头刷新
+        let mut state = self.state.lock().unwrap();
+        if state.reset_at.is_some_and(|reset_at| reset_at <= Utc::now()) {
+            state.remaining = None;
+        }
+        if state.retry_after_until.is_some_and(|until| until <= Utc::now()) {
+            state.retry_after_until = None;
+        }
+
+        true
+    }
+
+    /// 获取当前限流状态
+    pub fn status(&self) -> RateLimitStatus {
+        let eta = self.pending_wait();
+        let state = self.state.lock().unwrap();
+
+        RateLimitStatus {
+            limit: state.limit,
+            remaining: state.remaining,
+            reset_at: state.reset_at,
+            exhausted: eta.is_some(),
+            eta_seconds: eta.map(|d| d.as_secs()).unwrap_or(0),
+            queued_requests: self.queued.load(Ordering::SeqCst),
+        }
+    }
+}
+
+/// 判断响应是否为限流拒绝（主限流 403/429 且配额为 0，或带 retry-after 的二级限流）
+pub fn is_rate_limited_response(status: reqwest::StatusCode, headers: &HeaderMap) -> bool {
+    if status != reqwest::StatusCode::FORBIDDEN && status != reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return false;
+    }
+
+    let exhausted = headers
+        .get("x-ratelimit-remaining")
+        .is_some_and(|v| v == "0");
+    exhausted || headers.contains_key("retry-after")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(remaining: &str, reset_offset_secs: i64) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let reset = Utc::now().timestamp() + reset_offset_secs;
+        headers.insert("x-ratelimit-limit", HeaderValue::from_static("60"));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_str(remaining).unwrap());
+        headers.insert("x-ratelimit-reset", HeaderValue::from_str(&reset.to_string()).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_status_reports_eta_when_exhausted() {
+        let limiter = RateLimiter::new();
+        limiter.update_from_headers(&headers("0", 120));
+
+        let status = limiter.status();
+        assert!(status.exhausted);
+        assert_eq!(status.remaining, Some(0));
+        assert!(status.eta_seconds > 100 && status.eta_seconds <= 121, "got {}", status.eta_seconds);
+    }
+
+    #[test]
+    fn test_no_wait_when_quota_available() {
+        let limiter = RateLimiter::new();
+        limiter.update_from_headers(&headers("42", 120));
+
+        assert!(limiter.pending_wait().is_none());
+        assert!(!limiter.status().exhausted);
+    }
+
+    #[test]
+    fn test_rate_limited_response_detection() {
+        let exhausted = headers("0", 60);
+        assert!(is_rate_limited_response(reqwest::StatusCode::FORBIDDEN, &exhausted));
+        assert!(!is_rate_limited_response(reqwest::StatusCode::NOT_FOUND, &exhausted));
+        assert!(!is_rate_limited_response(reqwest::StatusCode::FORBIDDEN, &headers("10", 60)));
+    }
+}
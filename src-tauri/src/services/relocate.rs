@@ -0,0 +1,44 @@
+use std::path::{Path, PathBuf};
+
+/// 将位于 `old_base` 下的安装路径改写到 `new_base` 下（保留相对路径），不在 `old_base` 下时返回 None
+pub fn rebase_path(path: &Path, old_base: &Path, new_base: &Path) -> Option<PathBuf> {
+    let relative = path.strip_prefix(old_base).ok()?;
+    Some(new_base.join(relative))
+}
+
+/// 自动查找失效安装路径的新位置：在各工具的技能目录中查找同名目录（目录须包含技能清单）
+pub fn find_moved_dir(path: &Path, search_dirs: &[PathBuf], has_manifest: impl Fn(&Path) -> bool) -> Option<PathBuf> {
+    let name = path.file_name()?;
+    search_dirs
+        .iter()
+        .map(|dir| dir.join(name))
+        .filter(|candidate| candidate != path)
+        .find(|candidate| candidate.is_dir() && has_manifest(candidate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_rebase_and_find_moved_dir() {
+        assert_eq!(
+            rebase_path(Path::new("/old/home/.claude/skills/pdf"), Path::new("/old/home"), Path::new("/new/home")),
+            Some(PathBuf::from("/new/home/.claude/skills/pdf"))
+        );
+        assert_eq!(rebase_path(Path::new("/other/skills/pdf"), Path::new("/old/home"), Path::new("/new/home")), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let claude = dir.path().join(".claude/skills");
+        let cursor = dir.path().join(".cursor/skills");
+        fs::create_dir_all(claude.join("pdf")).unwrap();
+        fs::create_dir_all(cursor.join("pdf")).unwrap();
+        fs::write(cursor.join("pdf/SKILL.md"), "---\nname: pdf\n---\n").unwrap();
+
+        let has_manifest = |dir: &Path| dir.join("SKILL.md").is_file();
+        let search_dirs = vec![claude, cursor.clone()];
+        assert_eq!(find_moved_dir(Path::new("/gone/.claude/skills/pdf"), &search_dirs, has_manifest), Some(cursor.join("pdf")));
+        assert_eq!(find_moved_dir(Path::new("/gone/.claude/skills/docx"), &search_dirs, has_manifest), None);
+    }
+}
@@ -0,0 +1,277 @@
+use crate::models::{AppError, ErrorKind};
+use crate::services::NetworkConfig;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, USER_AGENT};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// 缓存元数据文件名（位于应用数据目录）
+const METADATA_FILE_NAME: &str = "remote-content.json";
+
+/// 应用从远程获取的内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoteContentKind {
+    /// 精选仓库列表
+    FeaturedRepositories,
+}
+
+impl RemoteContentKind {
+    pub const ALL: &'static [Self] = &[Self::FeaturedRepositories];
+
+    pub fn url(self) -> &'static str {
+        match self {
+            Self::FeaturedRepositories => {
+                "https://raw.githubusercontent.com/tanaer/agent-skills-guard-pro/main/featured-repositories.yaml"
+            }
+        }
+    }
+
+    /// 缓存文件名（位于应用数据目录）
+    pub fn cache_file_name(self) -> &'static str {
+        match self {
+            Self::FeaturedRepositories => "featured-repositories.yaml",
+        }
+    }
+
+    /// 距上次成功检查超过该时间视为过期，后台刷新时重新检查
+    pub fn max_age(self) -> Duration {
+        match self {
+            Self::FeaturedRepositories => Duration::hours(24),
+        }
+    }
+}
+
+/// 缓存的条件请求信息与刷新记录
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct CacheMetadata {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// 最近一次下载到新内容的时间
+    updated_at: Option<DateTime<Utc>>,
+    /// 最近一次成功检查（含内容未变化）的时间
+    checked_at: Option<DateTime<Utc>>,
+    /// 最近一次刷新失败的原因，刷新成功后清除
+    last_error: Option<String>,
+}
+
+/// 远程内容的缓存状态
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteContentStatus {
+    pub kind: RemoteContentKind,
+    pub url: String,
+    /// 已有下载的缓存（否则使用内置的默认内容）
+    pub cached: bool,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub checked_at: Option<DateTime<Utc>>,
+    /// 从未成功检查或距上次检查超过有效期
+    pub stale: bool,
+    pub last_error: Option<String>,
+    /// 本次刷新下载到了新内容
+    pub changed: bool,
+}
+
+/// 远程内容：带 ETag / Last-Modified 的条件请求、按网络设置重试，校验通过后原子写入缓存
+pub struct RemoteContentService {
+    client: Arc<Client>,
+    cache_dir: PathBuf,
+    metadata: Mutex<BTreeMap<RemoteContentKind, CacheMetadata>>,
+}
+
+impl RemoteContentService {
+    pub fn new(client: Arc<Client>, cache_dir: PathBuf) -> Self {
+        let metadata = std::fs::read_to_string(cache_dir.join(METADATA_FILE_NAME))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { client, cache_dir, metadata: Mutex::new(metadata) }
+    }
+
+    fn cache_path(&self, kind: RemoteContentKind) -> PathBuf {
+        self.cache_dir.join(kind.cache_file_name())
+    }
+
+    /// 缓存的内容，尚未下载过时为 None
+    pub fn cached(&self, kind: RemoteContentKind) -> Option<String> {
+        std::fs::read_to_string(self.cache_path(kind)).ok()
+    }
+
+    pub fn status(&self, kind: RemoteContentKind) -> RemoteContentStatus {
+        let metadata = self.metadata.lock().unwrap().get(&kind).cloned().unwrap_or_default();
+        let cached = self.cache_path(kind).is_file();
+        status_of(kind, &metadata, cached, Utc::now())
+    }
+
+    /// 刷新内容：内容未变化（304）时只更新检查时间；下载到的内容经 validate 校验后才写入缓存。
+    /// 失败时保留原有缓存并记录错误
+    pub async fn refresh(
+        &self,
+        kind: RemoteContentKind,
+        network: &NetworkConfig,
+        validate: impl FnOnce(&str) -> Result<()>,
+    ) -> Result<RemoteContentStatus> {
+        let previous = self.metadata.lock().unwrap().get(&kind).cloned().unwrap_or_default();
+        // 缓存文件被删除时不再发送条件请求
+        let conditional = self.cache_path(kind).is_file();
+
+        let result = self.download(kind, network, conditional.then_some(&previous)).await.and_then(|download| {
+            let Some((content, etag, last_modified)) = download else {
+                return Ok(None);
+            };
+            validate(&content).context("下载的内容无效")?;
+            write_atomic(&self.cache_dir, &self.cache_path(kind), content.as_bytes())?;
+            Ok(Some((etag, last_modified)))
+        });
+
+        let now = Utc::now();
+        let mut metadata = previous;
+        let changed = match &result {
+            Ok(Some((etag, last_modified))) => {
+                metadata.etag = etag.clone();
+                metadata.last_modified = last_modified.clone();
+                metadata.updated_at = Some(now);
+                metadata.checked_at = Some(now);
+                metadata.last_error = None;
+                true
+            }
+            Ok(None) => {
+                metadata.checked_at = Some(now);
+                metadata.last_error = None;
+                false
+            }
+            Err(e) => {
+                log::warn!("刷新远程内容失败: {:?}, 错误: {:#}", kind, e);
+                metadata.last_error = Some(format!("{:#}", e));
+                false
+            }
+        };
+        self.save_metadata(kind, metadata.clone());
+        result?;
+
+        if changed {
+            log::info!("远程内容已更新: {:?}", kind);
+        }
+        Ok(RemoteContentStatus { changed, ..status_of(kind, &metadata, self.cache_path(kind).is_file(), now) })
+    }
+
+    /// 下载内容，返回 (内容, ETag, Last-Modified)；内容未变化时为 None。网络错误或 5xx 时按网络设置重试
+    async fn download(
+        &self,
+        kind: RemoteContentKind,
+        network: &NetworkConfig,
+        previous: Option<&CacheMetadata>,
+    ) -> Result<Option<(String, Option<String>, Option<String>)>> {
+        let mut attempt = 0u32;
+        loop {
+            let mut request = self.client.get(kind.url()).header(USER_AGENT, "agent-skills-guard");
+            if let Some(etag) = previous.and_then(|m| m.etag.as_deref()) {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = previous.and_then(|m| m.last_modified.as_deref()) {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+
+            let can_retry = attempt < network.max_retries;
+            match request.send().await {
+                Ok(response) if response.status() == StatusCode::NOT_MODIFIED => return Ok(None),
+                Ok(response) if response.status().is_server_error() && can_retry => {
+                    log::warn!("下载远程内容返回 {}，第 {} 次重试: {:?}", response.status(), attempt + 1, kind);
+                }
+                Ok(response) if response.status().is_success() => {
+                    let header = |name| response.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+                    let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+                    let content = response.text().await.context("无法读取下载的内容")?;
+                    return Ok(Some((content, etag, last_modified)));
+                }
+                Ok(response) => {
+                    return Err(AppError::new(
+                        ErrorKind::Network,
+                        format!("下载远程内容失败，HTTP 状态码: {}", response.status()),
+                    ).into());
+                }
+                Err(e) if (e.is_timeout() || e.is_connect()) && can_retry => {
+                    log::warn!("下载远程内容失败（{}），第 {} 次重试: {:?}", e, attempt + 1, kind);
+                }
+                Err(e) => return Err(AppError::new(ErrorKind::Network, format!("下载远程内容失败: {}", e)).into()),
+            }
+
+            tokio::time::sleep(network.retry_delay(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    fn save_metadata(&self, kind: RemoteContentKind, metadata: CacheMetadata) {
+        let mut all = self.metadata.lock().unwrap();
+        all.insert(kind, metadata);
+        let saved = serde_json::to_vec_pretty(&*all)
+            .context("无法序列化远程内容记录")
+            .and_then(|content| write_atomic(&self.cache_dir, &self.cache_dir.join(METADATA_FILE_NAME), &content));
+        if let Err(e) = saved {
+            log::warn!("保存远程内容记录失败: {:#}", e);
+        }
+    }
+}
+
+fn status_of(kind: RemoteContentKind, metadata: &CacheMetadata, cached: bool, now: DateTime<Utc>) -> RemoteContentStatus {
+    RemoteContentStatus {
+        kind,
+        url: kind.url().to_string(),
+        cached,
+        updated_at: metadata.updated_at,
+        checked_at: metadata.checked_at,
+        stale: metadata.checked_at.is_none_or(|checked_at| now - checked_at > kind.max_age()),
+        last_error: metadata.last_error.clone(),
+        changed: false,
+    }
+}
+
+/// 先写入同目录的临时文件再替换，避免写入中断留下不完整的缓存
+fn write_atomic(dir: &Path, path: &Path, content: &[u8]) -> Result<()> {
+    std::fs::create_dir_all(dir).context(format!("无法创建目录: {:?}", dir))?;
+    let mut tmp = tempfile::NamedTempFile::new_in(dir).context("无法创建临时文件")?;
+    tmp.write_all(content).context("无法写入临时文件")?;
+    tmp.flush().context("无法写入临时文件")?;
+    tmp.persist(path).map_err(|e| anyhow::anyhow!("无法写入缓存文件 {:?}: {}", path, e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_content_status_and_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let kind = RemoteContentKind::FeaturedRepositories;
+        let service = RemoteContentService::new(Arc::new(Client::new()), dir.path().to_path_buf());
+
+        let status = service.status(kind);
+        assert!(!status.cached && status.stale);
+        assert_eq!(service.cached(kind), None);
+
+        let now = Utc::now();
+        write_atomic(dir.path(), &dir.path().join(kind.cache_file_name()), b"categories: []\n").unwrap();
+        service.save_metadata(kind, CacheMetadata {
+            etag: Some("\"abc\"".to_string()),
+            checked_at: Some(now - Duration::hours(1)),
+            ..Default::default()
+        });
+        let status = service.status(kind);
+        assert!(status.cached && !status.stale);
+        assert_eq!(service.cached(kind).as_deref(), Some("categories: []\n"));
+
+        // 重新加载时读取保存的记录
+        let reloaded = RemoteContentService::new(Arc::new(Client::new()), dir.path().to_path_buf());
+        assert_eq!(reloaded.metadata.lock().unwrap()[&kind].etag.as_deref(), Some("\"abc\""));
+
+        let old = CacheMetadata { checked_at: Some(now - Duration::hours(25)), ..Default::default() };
+        assert!(status_of(kind, &old, true, now).stale);
+    }
+}
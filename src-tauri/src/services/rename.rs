@@ -0,0 +1,159 @@
+use crate::models::{AiTool, AppError};
+use crate::services::impact;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 技能目录名长度上限（常见文件系统的文件名上限）
+const MAX_DIR_NAME_LEN: usize = 255;
+
+/// 校验新的技能目录名：不能包含路径分隔符、Windows 不允许的字符或控制字符，返回去除首尾空白后的名称
+pub fn validate_dir_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() || name.len() > MAX_DIR_NAME_LEN {
+        anyhow::bail!("技能目录名长度必须在 1 到 {} 个字节之间", MAX_DIR_NAME_LEN);
+    }
+    if name.starts_with('.') {
+        anyhow::bail!("技能目录名不能以 . 开头: {}", name);
+    }
+    if name.chars().any(|c| c.is_control() || matches!(c, '/' | '\\' | '<' | '>' | ':' | '"' | '|' | '?' | '*')) {
+        anyhow::bail!("技能目录名包含不允许的字符: {}", name);
+    }
+    Ok(name.to_string())
+}
+
+/// 重命名后的安装目录
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenamedDir {
+    pub old_path: PathBuf,
+    pub new_path: PathBuf,
+}
+
+/// 将各安装目录重命名为 `new_name`（目录仍在原父目录下），并更新各工具技能目录中指向它们的符号链接。
+///
+/// 先检查所有目标路径都不存在再开始重命名；中途失败时撤销已完成的重命名
+pub fn rename_dirs(dirs: &[PathBuf], new_name: &str, tools: &[AiTool]) -> Result<Vec<RenamedDir>> {
+    let mut renames = Vec::new();
+    for dir in dirs {
+        let parent = dir.parent().context(format!("无法确定技能目录的父目录: {:?}", dir))?;
+        let new_path = parent.join(new_name);
+        if new_path == *dir {
+            continue;
+        }
+        // 大小写不敏感的文件系统上只改变大小写时，目标路径指向的就是原目录
+        let same_dir = fs::canonicalize(&new_path).ok() == fs::canonicalize(dir).ok();
+        if new_path.symlink_metadata().is_ok() && !same_dir {
+            return Err(AppError::invalid_input(format!("目标目录已存在: {:?}", new_path)).into());
+        }
+        renames.push(RenamedDir { old_path: dir.clone(), new_path });
+    }
+
+    // 重命名前记录符号链接（重命名后旧路径无法再解析）
+    let links: Vec<(PathBuf, PathBuf, usize)> = renames
+        .iter()
+        .enumerate()
+        .flat_map(|(index, rename)| {
+            let old_dir = fs::canonicalize(&rename.old_path).unwrap_or_else(|_| rename.old_path.clone());
+            impact::find_linked_locations(std::slice::from_ref(&rename.old_path), tools)
+                .into_iter()
+                .filter_map(move |link| {
+                    let target = fs::canonicalize(&link.target).ok()?;
+                    let relative = target.strip_prefix(&old_dir).ok()?.to_path_buf();
+                    Some((PathBuf::from(link.path), relative, index))
+                })
+        })
+        .collect();
+
+    for (done, rename) in renames.iter().enumerate() {
+        if let Err(e) = fs::rename(&rename.old_path, &rename.new_path) {
+            for undo in renames[..done].iter().rev() {
+                if let Err(e) = fs::rename(&undo.new_path, &undo.old_path) {
+                    log::error!("撤销重命名失败: {:?} -> {:?}, 错误: {}", undo.new_path, undo.old_path, e);
+                }
+            }
+            return Err(e).context(format!("无法重命名技能目录: {:?}，请检查文件是否被占用", rename.old_path));
+        }
+    }
+
+    let old_name = renames.first().and_then(|rename| rename.old_path.file_name().map(|name| name.to_os_string()));
+    for (link, relative, index) in links {
+        let target = renames[index].new_path.join(relative);
+        // 链接名与原目录名相同时一并改名
+        let new_link = match (&old_name, link.parent()) {
+            (Some(old_name), Some(parent)) if link.file_name() == Some(old_name.as_os_str()) && parent.join(new_name).symlink_metadata().is_err() => {
+                parent.join(new_name)
+            }
+            _ => link.clone(),
+        };
+        if let Err(e) = relink(&link, &new_link, &target) {
+            log::warn!("更新符号链接失败: {:?} -> {:?}, 错误: {}", link, target, e);
+        }
+    }
+
+    Ok(renames)
+}
+
+/// 删除旧符号链接并在 `new_link` 处创建指向 `target` 的链接
+fn relink(old_link: &Path, new_link: &Path, target: &Path) -> Result<()> {
+    remove_symlink(old_link).context(format!("无法删除符号链接: {:?}", old_link))?;
+    create_dir_symlink(target, new_link).context(format!("无法创建符号链接: {:?}", new_link))?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn remove_symlink(link: &Path) -> std::io::Result<()> {
+    fs::remove_file(link)
+}
+
+#[cfg(unix)]
+fn create_dir_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+// Windows 上指向目录的符号链接需要按目录删除
+#[cfg(windows)]
+fn remove_symlink(link: &Path) -> std::io::Result<()> {
+    fs::remove_dir(link).or_else(|_| fs::remove_file(link))
+}
+
+#[cfg(windows)]
+fn create_dir_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_dir(target, link)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_dirs_updates_links_and_rejects_collisions() {
+        assert_eq!(validate_dir_name(" pdf-tools ").unwrap(), "pdf-tools");
+        assert!(validate_dir_name("../pdf").is_err());
+        assert!(validate_dir_name(".hidden").is_err());
+        assert!(validate_dir_name("").is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let central = dir.path().join("central");
+        fs::create_dir_all(central.join("pdf")).unwrap();
+        fs::create_dir_all(central.join("docx")).unwrap();
+        fs::write(central.join("pdf/SKILL.md"), "---\nname: pdf\n---\n").unwrap();
+
+        let tool = AiTool::new("cursor", "Cursor", dir.path().join(".cursor"), "skills");
+        fs::create_dir_all(tool.skills_path()).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(central.join("pdf"), tool.skills_path().join("pdf")).unwrap();
+
+        let dirs = vec![central.join("pdf")];
+        assert!(rename_dirs(&dirs, "docx", std::slice::from_ref(&tool)).is_err());
+        assert!(central.join("pdf/SKILL.md").is_file());
+
+        let renamed = rename_dirs(&dirs, "pdf-tools", std::slice::from_ref(&tool)).unwrap();
+        assert_eq!(renamed, vec![RenamedDir { old_path: central.join("pdf"), new_path: central.join("pdf-tools") }]);
+        assert!(central.join("pdf-tools/SKILL.md").is_file());
+        #[cfg(unix)]
+        {
+            assert!(tool.skills_path().join("pdf").symlink_metadata().is_err());
+            assert!(tool.skills_path().join("pdf-tools/SKILL.md").is_file());
+        }
+    }
+}
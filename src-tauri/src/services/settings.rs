@@ -0,0 +1,953 @@
+use crate::services::credentials::PROXY_PASSWORD_ACCOUNT;
+use crate::models::{get_all_supported_tools, AiTool, AppError, BlockReason, ErrorKind, InstallBlocked, LevelThresholds, ManifestNames};
+use crate::security::{RuleOverride, ScanFilter, SecurityRules, SecurityScanner};
+use crate::services::{signature, CredentialStore, Database, NetworkConfig, ProxyConfig};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use skillguard_core::i18n;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use tokio::sync::broadcast;
+
+/// 统一设置在 app_settings 表中的键
+const SETTINGS_KEY: &str = "settings";
+/// 旧版本分散存储的代理/网络配置键（首次加载时迁移）
+const LEGACY_PROXY_CONFIG_KEY: &str = "proxy_config";
+const LEGACY_NETWORK_CONFIG_KEY: &str = "network_config";
+
+/// 评分低于最低安装评分时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LowScoreAction {
+    /// 直接拒绝安装
+    #[default]
+    Block,
+    /// 确认安装时需输入技能名称再次确认（一步安装没有确认环节，仍直接拒绝）
+    Confirm,
+}
+
+/// 安全扫描策略
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanPolicy {
+    /// 命中硬触发规则（如反弹 shell、删除根目录）时禁止安装
+    pub block_hard_triggers: bool,
+    /// 允许安装的最低安全评分（0 表示不限制）
+    pub min_install_score: i32,
+    /// 评分低于最低安装评分时的处理方式
+    pub low_score_action: LowScoreAction,
+    /// 确认安装高危/严重级别的技能时，需输入技能名称（设置了管理口令时输入口令）
+    pub confirm_risky_installs: bool,
+    /// 首次启动时自动扫描尚未扫描的仓库
+    pub auto_scan_repositories: bool,
+    /// 受信任的提交签名密钥（16 位长密钥 ID 或完整指纹），用于验证要求签名提交的仓库
+    pub approved_signing_keys: Vec<String>,
+    /// 单个文件的扫描大小上限（MB），超过的文件跳过扫描并报告警告
+    pub max_scan_file_size_mb: u64,
+    /// 只扫描匹配的文件（glob 模式，为空时扫描全部文件）
+    pub scan_include_patterns: Vec<String>,
+    /// 额外忽略的目录或文件（glob 模式），默认忽略模式始终生效
+    pub scan_exclude_patterns: Vec<String>,
+    /// 按规则 ID 调整内置规则的级别、权重与硬触发标记（核心规则不允许调整）
+    pub rule_overrides: BTreeMap<String, RuleOverride>,
+    /// 由安全评分划分安全等级的阈值（扫描、已保存的技能与报告统一使用）
+    pub level_thresholds: LevelThresholds,
+}
+
+impl Default for ScanPolicy {
+    fn default() -> Self {
+        Self {
+            block_hard_triggers: true,
+            min_install_score: 0,
+            low_score_action: LowScoreAction::Block,
+            confirm_risky_installs: false,
+            auto_scan_repositories: true,
+            approved_signing_keys: Vec::new(),
+            max_scan_file_size_mb: crate::security::DEFAULT_MAX_FILE_SIZE / (1024 * 1024),
+            scan_include_patterns: Vec::new(),
+            scan_exclude_patterns: Vec::new(),
+            rule_overrides: BTreeMap::new(),
+            level_thresholds: LevelThresholds::default(),
+        }
+    }
+}
+
+impl ScanPolicy {
+    /// 扫描路径过滤规则
+    pub fn scan_filter(&self) -> Result<ScanFilter> {
+        ScanFilter::new(&self.scan_include_patterns, &self.scan_exclude_patterns, true)
+    }
+
+    /// 确认安装前检查安全评分：低于最低安装评分时按策略拒绝，或要求输入的确认文本与技能名称一致
+    /// 尚未扫描的技能视为不满足要求
+    pub fn check_install_score(&self, skill_name: &str, score: Option<i32>, confirmation: Option<&str>) -> Result<()> {
+        if self.min_install_score <= 0 {
+            return Ok(());
+        }
+        let Some(score) = score else {
+            return Err(InstallBlocked::new(skill_name, vec![BlockReason::NotScanned]).into());
+        };
+        if score >= self.min_install_score {
+            return Ok(());
+        }
+
+        let confirmable = self.low_score_action == LowScoreAction::Confirm;
+        if confirmable && confirmation.map(str::trim) == Some(skill_name.trim()) {
+            log::warn!("技能 {} 安全评分 {} 低于最低安装评分 {}，用户已确认安装", skill_name, score, self.min_install_score);
+            return Ok(());
+        }
+        Err(InstallBlocked::new(skill_name, vec![BlockReason::LowScore {
+            score,
+            min_score: self.min_install_score,
+            confirmable,
+        }]).into())
+    }
+
+    /// 确认安装前检查高风险技能（High / Critical）的输入确认：设置了管理口令时需输入口令，否则需输入技能名称
+    /// 管理口令只在需要时读取
+    pub fn check_risky_install(
+        &self,
+        skill_name: &str,
+        security_level: Option<&str>,
+        confirmation: Option<&str>,
+        passphrase: impl FnOnce() -> Option<String>,
+    ) -> Result<()> {
+        if !self.confirm_risky_installs || !matches!(security_level, Some("High" | "Critical")) {
+            return Ok(());
+        }
+
+        let confirmation = confirmation.map(str::trim).unwrap_or_default();
+        let passphrase = passphrase().filter(|p| !p.is_empty());
+        let confirmed = match &passphrase {
+            Some(passphrase) => confirmation == passphrase,
+            None => confirmation == skill_name.trim(),
+        };
+        if confirmed {
+            return Ok(());
+        }
+        Err(InstallBlocked::new(skill_name, vec![BlockReason::ConfirmationRequired {
+            security_level: security_level.unwrap_or_default().to_string(),
+            passphrase: passphrase.is_some(),
+        }]).into())
+    }
+
+    /// 修改或清除管理口令、关闭高风险安装确认前校验当前的管理口令（未设置口令时无需校验）
+    pub fn verify_install_passphrase(stored: Option<&str>, provided: Option<&str>) -> Result<(), AppError> {
+        match stored.filter(|p| !p.is_empty()) {
+            Some(stored) if provided.map(str::trim) != Some(stored) => {
+                Err(AppError::new(ErrorKind::Unauthorized, "管理口令不正确"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 生效的安全等级阈值（无效时使用默认阈值）
+    pub fn level_thresholds(&self) -> LevelThresholds {
+        match self.level_thresholds.validate() {
+            Ok(()) => self.level_thresholds,
+            Err(e) => {
+                log::warn!("安全等级阈值无效，使用默认阈值: {}", e);
+                LevelThresholds::default()
+            }
+        }
+    }
+
+    /// 按当前策略创建安全扫描器（过滤模式无效时只使用默认忽略模式，规则调整无效时使用内置规则）
+    pub fn scanner(&self) -> SecurityScanner {
+        let filter = self.scan_filter().unwrap_or_else(|e| {
+            log::warn!("扫描过滤模式无效，使用默认忽略模式: {}", e);
+            ScanFilter::default()
+        });
+        let thresholds = self.level_thresholds();
+        let scanner = || {
+            SecurityScanner::new()
+                .with_max_file_size(self.max_scan_file_size_mb.saturating_mul(1024 * 1024))
+                .with_filter(filter.clone())
+                .with_level_thresholds(thresholds)
+        };
+        scanner().with_rule_overrides(&self.rule_overrides).unwrap_or_else(|e| {
+            log::warn!("规则调整无效，使用内置规则: {}", e);
+            scanner()
+        })
+    }
+}
+
+/// 本地扫描时间上限的最大值（秒）
+const MAX_LOCAL_SCAN_TIMEOUT_SECS: u32 = 600;
+
+/// 目录设置（为 None 时使用默认位置）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DirectorySettings {
+    /// 技能安装目录，默认 ~/.claude/skills
+    pub skills_dir: Option<PathBuf>,
+    /// 默认安装到该工具（工具 ID，如 "cursor"）的技能目录，设置了 skills_dir 时不生效
+    pub default_tool: Option<String>,
+    /// 用户在目录选择器中选择过的安装目录，安装等写入操作只允许使用技能目录与这些目录
+    pub approved_install_dirs: Vec<PathBuf>,
+    /// 用户在保存对话框中选择过的导出目录，导出报告、技能清单与数据库备份只允许写入这些目录
+    pub approved_export_dirs: Vec<PathBuf>,
+    /// 扫描本地技能时额外扫描的目录（扫描其下一层子目录）
+    pub local_scan_roots: Vec<PathBuf>,
+    /// 扫描本地技能时是否扫描已安装技能所在的上级目录
+    pub scan_installed_parents: bool,
+    /// 扫描本地技能时跳过的目录：绝对路径（含其子目录）或目录名（不区分大小写）
+    pub local_scan_exclude: Vec<String>,
+    /// 扫描本地技能的时间上限（秒，0 表示不限制），超时后跳过剩余目录
+    pub local_scan_timeout_secs: u32,
+}
+
+impl Default for DirectorySettings {
+    fn default() -> Self {
+        Self {
+            skills_dir: None,
+            default_tool: None,
+            approved_install_dirs: Vec::new(),
+            approved_export_dirs: Vec::new(),
+            local_scan_roots: Vec::new(),
+            scan_installed_parents: true,
+            local_scan_exclude: Vec::new(),
+            local_scan_timeout_secs: 30,
+        }
+    }
+}
+
+impl DirectorySettings {
+    /// 扫描本地技能时是否跳过该目录
+    pub fn is_local_scan_excluded(&self, path: &Path) -> bool {
+        self.local_scan_exclude.iter().map(|entry| entry.trim()).filter(|entry| !entry.is_empty()).any(|entry| {
+            let excluded = Path::new(entry);
+            if excluded.is_absolute() {
+                path.starts_with(excluded)
+            } else {
+                path.file_name().is_some_and(|name| name.to_string_lossy().eq_ignore_ascii_case(entry))
+            }
+        })
+    }
+
+    /// 默认技能安装目录
+    pub fn default_skills_dir() -> PathBuf {
+        let home = dirs::home_dir().expect("Failed to get home directory");
+        home.join(".claude").join("skills")
+    }
+
+    /// 实际使用的技能安装目录：skills_dir 优先，其次为默认工具的技能目录
+    pub fn resolved_skills_dir(&self) -> PathBuf {
+        self.skills_dir
+            .clone()
+            .or_else(|| self.default_tool().map(|tool| tool.skills_path()))
+            .unwrap_or_else(Self::default_skills_dir)
+    }
+
+    /// 设置的默认工具（未设置或不是支持的工具时为 None）
+    pub fn default_tool(&self) -> Option<AiTool> {
+        let id = self.default_tool.as_deref().map(str::trim).filter(|id| !id.is_empty())?;
+        get_all_supported_tools().into_iter().find(|tool| tool.id == id)
+    }
+}
+
+/// 通知设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    /// 总开关
+    pub enabled: bool,
+    /// 已安装技能有更新时通知
+    pub skill_updates: bool,
+    /// 扫描发现高风险问题时通知
+    pub scan_findings: bool,
+    /// 后台刷新仓库发现新技能时通知
+    pub new_skills: bool,
+    /// 不发送更新通知的技能 ID（仍会检查更新并在界面中显示）
+    pub muted_skill_updates: Vec<String>,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            skill_updates: true,
+            scan_findings: true,
+            new_skills: true,
+            muted_skill_updates: Vec::new(),
+        }
+    }
+}
+
+/// 后台扫描发现已安装技能命中硬触发规则时的自动处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThreatResponse {
+    /// 只发送通知
+    #[default]
+    Notify,
+    /// 禁用技能（重命名技能清单，使其不再被加载）
+    Disable,
+    /// 立即隔离（移出安装目录）
+    Quarantine,
+}
+
+/// 后台扫描线程数上限
+const MAX_BACKGROUND_SCAN_THREADS: u32 = 16;
+
+/// 后台运行设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackgroundSettings {
+    /// 登录系统时自动启动（最小化到托盘，后台保护继续运行）
+    pub launch_at_login: bool,
+    /// 暂停后台保护（文件监控、定时扫描等后台任务不再运行）
+    pub protection_paused: bool,
+    /// 定时重新扫描已安装技能的间隔（小时，0 表示不自动扫描）
+    pub rescan_interval_hours: u32,
+    /// 启动时快速检查所有已安装技能（基于上次扫描缓存的增量扫描），汇总与上次运行相比的变化
+    pub rescan_on_startup: bool,
+    /// 定时检查已安装技能更新的间隔（小时，0 表示不自动检查）
+    pub update_check_interval_hours: u32,
+    /// 定时扫描发现已安装技能命中硬触发规则时的自动处理方式
+    pub threat_response: ThreatResponse,
+    /// 后台扫描（定时扫描、仓库刷新后的重新扫描）同时使用的最大线程数；用户手动发起的扫描不受限制
+    pub max_scan_threads: u32,
+    /// 以较低的系统优先级运行后台扫描，减少对前台应用的影响
+    pub low_priority_scans: bool,
+}
+
+impl Default for BackgroundSettings {
+    fn default() -> Self {
+        Self {
+            launch_at_login: false,
+            protection_paused: false,
+            rescan_interval_hours: 24,
+            rescan_on_startup: false,
+            update_check_interval_hours: 24,
+            threat_response: ThreatResponse::Notify,
+            max_scan_threads: 2,
+            low_priority_scans: true,
+        }
+    }
+}
+
+/// 临时目录清理设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheSettings {
+    /// 更新临时目录（staging）与更新备份的保留天数，超过后自动删除（0 表示不自动清理）
+    pub temp_retention_days: u32,
+    /// 更新备份的保存位置，备份写入其中的 [`BACKUP_SUBDIR`] 子目录；默认为缓存目录下的 skill-backups
+    pub backup_dir: Option<PathBuf>,
+    /// 每个技能目录最多保留的更新备份数（0 表示不限制）
+    pub max_backups_per_skill: u32,
+    /// 更新备份的总大小上限（MB，0 表示不限制），超过时从最旧的备份开始删除
+    pub max_backup_size_mb: u64,
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            temp_retention_days: 7,
+            backup_dir: None,
+            max_backups_per_skill: 3,
+            max_backup_size_mb: 1024,
+        }
+    }
+}
+
+/// 自定义备份位置下由本应用管理的子目录，清理备份时只删除其中的内容
+pub const BACKUP_SUBDIR: &str = "agent-skills-guard-backups";
+
+impl CacheSettings {
+    /// 实际使用的更新备份目录（无法确定缓存目录时为 None）
+    pub fn resolved_backup_dir(&self) -> Option<PathBuf> {
+        match &self.backup_dir {
+            Some(dir) => Some(dir.join(BACKUP_SUBDIR)),
+            None => crate::services::CachePaths::current().map(|paths| paths.backups()),
+        }
+    }
+}
+
+/// 仓库后台刷新设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RefreshSettings {
+    /// 定时刷新已启用仓库的间隔（小时，0 表示不自动刷新）
+    pub interval_hours: u32,
+}
+
+impl Default for RefreshSettings {
+    fn default() -> Self {
+        Self { interval_hours: 24 }
+    }
+}
+
+/// 技能发现设置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiscoverySettings {
+    /// 除 SKILL.md 外也视为技能清单的文件名（如 SKILLS.md），匹配时均不区分大小写
+    pub alternate_manifest_names: Vec<String>,
+}
+
+impl DiscoverySettings {
+    pub fn manifest_names(&self) -> ManifestNames {
+        ManifestNames::new(&self.alternate_manifest_names)
+    }
+}
+
+/// GitHub 登录设置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GitHubSettings {
+    /// 设备授权登录使用的 OAuth App Client ID，为空时使用构建时指定的 Client ID
+    pub oauth_client_id: Option<String>,
+}
+
+/// 剪贴板设置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardSettings {
+    /// 主窗口获得焦点时检查剪贴板中的 GitHub 仓库/技能地址，提示一键添加并扫描（默认关闭）。
+    /// 只读取当时的剪贴板内容，不保存剪贴板历史
+    pub detect_github_urls: bool,
+}
+
+/// 显示/隐藏主窗口的默认全局快捷键（按平台区分，避开系统常用快捷键）
+#[cfg(target_os = "macos")]
+const DEFAULT_TOGGLE_WINDOW_SHORTCUT: &str = "Alt+Command+K";
+#[cfg(not(target_os = "macos"))]
+const DEFAULT_TOGGLE_WINDOW_SHORTCUT: &str = "Ctrl+Alt+K";
+
+/// 全局快捷键设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HotkeySettings {
+    pub enabled: bool,
+    /// 显示/隐藏主窗口的快捷键（如 Ctrl+Alt+K）
+    pub toggle_window: String,
+}
+
+impl Default for HotkeySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            toggle_window: DEFAULT_TOGGLE_WINDOW_SHORTCUT.to_string(),
+        }
+    }
+}
+
+/// 应用更新渠道
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    /// 预发布版本，包含尚未正式发布的功能
+    Beta,
+}
+
+/// 应用更新设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateSettings {
+    pub channel: UpdateChannel,
+    /// 启动时自动检查应用更新
+    pub check_on_startup: bool,
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self {
+            channel: UpdateChannel::Stable,
+            check_on_startup: true,
+        }
+    }
+}
+
+/// 应用设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub proxy: ProxyConfig,
+    pub network: NetworkConfig,
+    /// 界面及扫描报告语言（内置 zh / en / ja / ko / de / fr / es，或从应用数据目录加载的语言）
+    pub locale: String,
+    pub scan_policy: ScanPolicy,
+    pub directories: DirectorySettings,
+    pub cache: CacheSettings,
+    pub discovery: DiscoverySettings,
+    pub clipboard: ClipboardSettings,
+    pub github: GitHubSettings,
+    pub refresh: RefreshSettings,
+    pub notifications: NotificationSettings,
+    pub background: BackgroundSettings,
+    pub hotkeys: HotkeySettings,
+    pub updates: UpdateSettings,
+    /// 日志级别（off / error / warn / info / debug / trace），修改后立即生效
+    pub log_level: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            proxy: ProxyConfig::default(),
+            network: NetworkConfig::default(),
+            locale: i18n::DEFAULT_LOCALE.to_string(),
+            scan_policy: ScanPolicy::default(),
+            directories: DirectorySettings::default(),
+            cache: CacheSettings::default(),
+            discovery: DiscoverySettings::default(),
+            clipboard: ClipboardSettings::default(),
+            github: GitHubSettings::default(),
+            refresh: RefreshSettings::default(),
+            notifications: NotificationSettings::default(),
+            background: BackgroundSettings::default(),
+            hotkeys: HotkeySettings::default(),
+            updates: UpdateSettings::default(),
+            log_level: "info".to_string(),
+        }
+    }
+}
+
+impl Settings {
+    /// 校验设置，返回第一个无效项的说明
+    pub fn validate(&self) -> Result<()> {
+        if self.proxy.enabled && !self.proxy.is_valid() {
+            anyhow::bail!("代理配置无效：请填写代理地址和端口");
+        }
+
+        self.proxy.validate_rules()?;
+
+        if !self.network.is_valid() {
+            anyhow::bail!("网络配置无效：连接超时需在 1-300 秒，读取超时需在 1-600 秒，重试次数不超过 10");
+        }
+
+        if !i18n::is_supported_locale(&self.locale) {
+            anyhow::bail!("不支持的语言: {}", self.locale);
+        }
+
+        if !(0..=100).contains(&self.scan_policy.min_install_score) {
+            anyhow::bail!("最低安装评分需在 0-100 之间");
+        }
+
+        if let Some(key) = self
+            .scan_policy
+            .approved_signing_keys
+            .iter()
+            .find(|key| signature::normalize_key_id(key).is_none())
+        {
+            anyhow::bail!("无效的签名密钥（需为 16 位密钥 ID 或完整指纹）: {}", key);
+        }
+
+        self.scan_policy.scan_filter()?;
+        SecurityRules::validate_overrides(&self.scan_policy.rule_overrides)?;
+        self.scan_policy.level_thresholds.validate()?;
+
+        if let Some(id) = self.github.oauth_client_id.as_deref().map(str::trim).filter(|id| !id.is_empty()) {
+            if !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_') {
+                anyhow::bail!("无效的 GitHub OAuth Client ID: {}", id);
+            }
+        }
+
+        if let Some(dir) = &self.directories.skills_dir {
+            if !dir.is_absolute() {
+                anyhow::bail!("技能安装目录必须是绝对路径: {:?}", dir);
+            }
+        }
+        if let Some(id) = self.directories.default_tool.as_deref().map(str::trim).filter(|id| !id.is_empty()) {
+            if self.directories.default_tool().is_none() {
+                anyhow::bail!("不支持的默认安装工具: {}", id);
+            }
+        }
+        if let Some(dir) = self.directories.approved_install_dirs.iter().find(|dir| !dir.is_absolute()) {
+            anyhow::bail!("批准的安装目录必须是绝对路径: {:?}", dir);
+        }
+        if let Some(dir) = self.directories.approved_export_dirs.iter().find(|dir| !dir.is_absolute()) {
+            anyhow::bail!("批准的导出目录必须是绝对路径: {:?}", dir);
+        }
+        if let Some(dir) = self.directories.local_scan_roots.iter().find(|dir| !dir.is_absolute()) {
+            anyhow::bail!("本地扫描目录必须是绝对路径: {:?}", dir);
+        }
+        if let Some(entry) = self.directories.local_scan_exclude.iter().map(|entry| entry.trim()).find(|entry| {
+            entry.is_empty() || (!Path::new(entry).is_absolute() && entry.contains(['/', '\\']))
+        }) {
+            anyhow::bail!("无效的本地扫描排除项（需为绝对路径或目录名）: {:?}", entry);
+        }
+        if self.directories.local_scan_timeout_secs > MAX_LOCAL_SCAN_TIMEOUT_SECS {
+            anyhow::bail!("本地扫描时间上限需在 0-{} 秒之间", MAX_LOCAL_SCAN_TIMEOUT_SECS);
+        }
+
+        if self.cache.temp_retention_days > 365 {
+            anyhow::bail!("临时目录保留天数需在 0-365 之间");
+        }
+        if let Some(dir) = &self.cache.backup_dir {
+            if !dir.is_absolute() {
+                anyhow::bail!("更新备份目录必须是绝对路径: {:?}", dir);
+            }
+        }
+
+        if let Some(name) = self.discovery.alternate_manifest_names.iter().find(|name| {
+            let name = name.trim();
+            name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\'])
+        }) {
+            anyhow::bail!("无效的技能清单文件名（需为不含路径的文件名）: {:?}", name);
+        }
+
+        if self.refresh.interval_hours > 24 * 30 {
+            anyhow::bail!("仓库刷新间隔需在 0-720 小时之间");
+        }
+
+        if self.background.rescan_interval_hours > 24 * 30 {
+            anyhow::bail!("已安装技能的定时扫描间隔需在 0-720 小时之间");
+        }
+
+        if self.background.update_check_interval_hours > 24 * 30 {
+            anyhow::bail!("技能更新的定时检查间隔需在 0-720 小时之间");
+        }
+
+        if !(1..=MAX_BACKGROUND_SCAN_THREADS).contains(&self.background.max_scan_threads) {
+            anyhow::bail!("后台扫描线程数需在 1-{} 之间", MAX_BACKGROUND_SCAN_THREADS);
+        }
+
+        if self.hotkeys.enabled && self.hotkeys.toggle_window.trim().is_empty() {
+            anyhow::bail!("请设置显示/隐藏主窗口的快捷键");
+        }
+
+        if log::LevelFilter::from_str(&self.log_level).is_err() {
+            anyhow::bail!("不支持的日志级别: {}", self.log_level);
+        }
+
+        Ok(())
+    }
+
+    /// 解析后的日志级别（无效时为 info）
+    pub fn log_level_filter(&self) -> log::LevelFilter {
+        log::LevelFilter::from_str(&self.log_level).unwrap_or(log::LevelFilter::Info)
+    }
+}
+
+/// 设置变更事件（包含变更前后的完整设置，由订阅方自行判断关心的部分）
+#[derive(Debug, Clone)]
+pub struct SettingsChange {
+    pub previous: Settings,
+    pub current: Settings,
+}
+
+impl SettingsChange {
+    pub fn proxy_changed(&self) -> bool {
+        self.previous.proxy != self.current.proxy
+    }
+
+    pub fn network_changed(&self) -> bool {
+        self.previous.network != self.current.network
+    }
+
+    pub fn locale_changed(&self) -> bool {
+        self.previous.locale != self.current.locale
+    }
+
+    pub fn launch_at_login_changed(&self) -> bool {
+        self.previous.background.launch_at_login != self.current.background.launch_at_login
+    }
+
+    pub fn hotkeys_changed(&self) -> bool {
+        self.previous.hotkeys != self.current.hotkeys
+    }
+
+    pub fn protection_changed(&self) -> bool {
+        self.previous.background.protection_paused != self.current.background.protection_paused
+    }
+
+    pub fn log_level_changed(&self) -> bool {
+        self.previous.log_level_filter() != self.current.log_level_filter()
+    }
+}
+
+/// 设置服务：负责加载、校验、持久化设置，并向订阅方广播变更
+pub struct SettingsService {
+    db: Arc<Database>,
+    current: RwLock<Settings>,
+    sender: broadcast::Sender<SettingsChange>,
+}
+
+impl SettingsService {
+    /// 从数据库加载设置；没有统一设置时从旧版分散的配置项迁移
+    /// 代理密码保存在系统钥匙串中，数据库中残留的明文密码会在加载时迁移
+    pub fn load(db: Arc<Database>) -> Result<Self> {
+        let mut settings = match db.get_setting(SETTINGS_KEY)? {
+            Some(json) => match serde_json::from_str::<Settings>(&json) {
+                Ok(settings) => settings,
+                Err(e) => {
+                    log::warn!("解析设置失败，使用默认值: {}", e);
+                    Settings::default()
+                }
+            },
+            None => {
+                log::info!("将旧版配置迁移到统一设置");
+                let mut settings = Self::migrate_legacy(&db);
+                // 首次运行：界面语言跟随系统语言
+                if let Some(locale) = Self::detect_system_locale() {
+                    log::info!("根据系统语言设置界面语言: {}", locale);
+                    settings.locale = locale;
+                }
+                settings
+            }
+        };
+
+        match settings.proxy.password.clone().filter(|p| !p.is_empty()) {
+            Some(password) => {
+                // 明文密码：写入钥匙串后从数据库中移除；钥匙串不可用时保留原样
+                match CredentialStore::set(PROXY_PASSWORD_ACCOUNT, &password) {
+                    Ok(()) => {
+                        log::info!("已将代理密码迁移到系统钥匙串");
+                        Self::persist(&db, &settings)?;
+                    }
+                    Err(e) => {
+                        log::warn!("迁移代理密码到系统钥匙串失败，暂时保留明文: {}", e);
+                        db.set_setting(SETTINGS_KEY, &serde_json::to_string(&settings)?)?;
+                    }
+                }
+            }
+            None => {
+                Self::persist(&db, &settings)?;
+                if settings.proxy.username.as_deref().is_some_and(|u| !u.is_empty()) {
+                    settings.proxy.password = CredentialStore::get_or_warn(PROXY_PASSWORD_ACCOUNT);
+                }
+            }
+        }
+
+        // 旧版配置项可能含有明文密码，统一设置写入后删除
+        for key in [LEGACY_PROXY_CONFIG_KEY, LEGACY_NETWORK_CONFIG_KEY] {
+            if let Err(e) = db.delete_setting(key) {
+                log::warn!("删除旧版配置 {} 失败: {}", key, e);
+            }
+        }
+
+        let (sender, _) = broadcast::channel(16);
+
+        Ok(Self {
+            db,
+            current: RwLock::new(settings),
+            sender,
+        })
+    }
+
+    /// 写入数据库（不含代理密码）
+    fn persist(db: &Database, settings: &Settings) -> Result<()> {
+        let mut persisted = settings.clone();
+        persisted.proxy.password = None;
+
+        let json = serde_json::to_string(&persisted).context("序列化设置失败")?;
+        db.set_setting(SETTINGS_KEY, &json)
+    }
+
+    fn migrate_legacy(db: &Database) -> Settings {
+        let mut settings = Settings::default();
+
+        if let Ok(Some(json)) = db.get_setting(LEGACY_PROXY_CONFIG_KEY) {
+            match serde_json::from_str(&json) {
+                Ok(proxy) => settings.proxy = proxy,
+                Err(e) => log::warn!("解析旧版代理配置失败: {}", e),
+            }
+        }
+
+        if let Ok(Some(json)) = db.get_setting(LEGACY_NETWORK_CONFIG_KEY) {
+            match serde_json::from_str(&json) {
+                Ok(network) => settings.network = network,
+                Err(e) => log::warn!("解析旧版网络配置失败: {}", e),
+            }
+        }
+
+        settings
+    }
+
+    /// 系统语言对应的可用语言（无法识别时返回 None）
+    fn detect_system_locale() -> Option<String> {
+        sys_locale::get_locales().find_map(|locale| i18n::match_system_locale(&locale))
+    }
+
+    /// 获取当前设置
+    pub fn get(&self) -> Settings {
+        self.current.read().unwrap().clone()
+    }
+
+    /// 校验并保存设置，成功后广播变更事件
+    /// 代理密码写入系统钥匙串，钥匙串不可用时返回错误而不是以明文保存
+    pub fn set(&self, settings: Settings) -> Result<()> {
+        settings.validate().map_err(|e| AppError::invalid_input(e.to_string()))?;
+
+        let previous_password = self.current.read().unwrap().proxy.password.clone();
+        if settings.proxy.password != previous_password {
+            CredentialStore::store(PROXY_PASSWORD_ACCOUNT, settings.proxy.password.as_deref())
+                .context("无法保存代理密码")?;
+        }
+
+        Self::persist(&self.db, &settings)?;
+
+        let previous = {
+            let mut current = self.current.write().unwrap();
+            std::mem::replace(&mut *current, settings.clone())
+        };
+
+        if previous != settings {
+            // 没有订阅方时 send 会返回错误，可以忽略
+            let _ = self.sender.send(SettingsChange {
+                previous,
+                current: settings,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// 修改部分设置
+    pub fn update<F>(&self, f: F) -> Result<Settings>
+    where
+        F: FnOnce(&mut Settings),
+    {
+        let mut settings = self.get();
+        f(&mut settings);
+        self.set(settings.clone())?;
+        Ok(settings)
+    }
+
+    /// 订阅设置变更
+    pub fn subscribe(&self) -> broadcast::Receiver<SettingsChange> {
+        self.sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_settings() {
+        assert!(Settings::default().validate().is_ok());
+
+        let settings = Settings {
+            locale: "xx".to_string(),
+            ..Settings::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = Settings {
+            locale: "fr".to_string(),
+            ..Settings::default()
+        };
+        assert!(settings.validate().is_ok());
+
+        let mut settings = Settings::default();
+        settings.proxy.enabled = true;
+        assert!(settings.validate().is_err(), "Enabled proxy without host should be rejected");
+
+        let mut settings = Settings::default();
+        settings.scan_policy.min_install_score = 120;
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings.scan_policy.level_thresholds.medium = settings.scan_policy.level_thresholds.low;
+        assert!(settings.validate().is_err(), "Level thresholds must stay ordered");
+
+        let mut settings = Settings::default();
+        settings.scan_policy.scan_exclude_patterns = vec!["[invalid".to_string()];
+        assert!(settings.validate().is_err(), "Invalid glob pattern should be rejected");
+
+        let mut settings = Settings::default();
+        settings.cache.temp_retention_days = 400;
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings.directories.local_scan_exclude = vec!["node_modules".to_string()];
+        assert!(settings.validate().is_ok());
+        assert!(settings.directories.is_local_scan_excluded(Path::new("/home/me/projects/Node_Modules")));
+        assert!(!settings.directories.is_local_scan_excluded(Path::new("/home/me/projects/app")));
+        settings.directories.local_scan_exclude.push("projects/app".to_string());
+        assert!(settings.validate().is_err(), "Relative exclusions must be plain directory names");
+        settings.directories.local_scan_roots = vec![PathBuf::from("relative/skills")];
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings.directories.default_tool = Some("cursor".to_string());
+        assert!(settings.validate().is_ok());
+        assert!(settings.directories.resolved_skills_dir().ends_with(".cursor/skills"));
+        settings.directories.skills_dir = Some(PathBuf::from("/opt/skills"));
+        assert_eq!(settings.directories.resolved_skills_dir(), PathBuf::from("/opt/skills"));
+        settings.directories.default_tool = Some("notepad".to_string());
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings.cache.backup_dir = Some(PathBuf::from("relative/backups"));
+        assert!(settings.validate().is_err());
+        // 备份写入所选位置下由本应用管理的子目录
+        settings.cache.backup_dir = Some(PathBuf::from("/data"));
+        assert_eq!(settings.cache.resolved_backup_dir(), Some(PathBuf::from("/data").join(BACKUP_SUBDIR)));
+
+        let mut settings = Settings::default();
+        settings.discovery.alternate_manifest_names = vec!["SKILLS.md".to_string()];
+        assert!(settings.validate().is_ok());
+        settings.discovery.alternate_manifest_names.push("docs/SKILL.md".to_string());
+        assert!(settings.validate().is_err(), "Manifest names must not contain paths");
+
+        let mut settings = Settings::default();
+        settings.refresh.interval_hours = 1000;
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings.background.rescan_interval_hours = 1000;
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings.background.update_check_interval_hours = 1000;
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings.background.max_scan_threads = 0;
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_check_install_score() {
+        let mut policy = ScanPolicy::default();
+        assert!(policy.check_install_score("demo", Some(10), None).is_ok(), "No threshold by default");
+
+        policy.min_install_score = 60;
+        assert!(policy.check_install_score("demo", Some(60), None).is_ok());
+        assert!(policy.check_install_score("demo", None, None).is_err());
+        assert!(policy.check_install_score("demo", Some(40), Some("demo")).is_err(), "Block ignores confirmation");
+
+        policy.low_score_action = LowScoreAction::Confirm;
+        assert!(policy.check_install_score("demo", Some(40), None).is_err());
+        assert!(policy.check_install_score("demo", Some(40), Some("other")).is_err());
+        assert!(policy.check_install_score("demo", Some(40), Some(" demo ")).is_ok());
+    }
+
+    #[test]
+    fn test_check_risky_install() {
+        let mut policy = ScanPolicy::default();
+        let no_passphrase = || None;
+        assert!(policy.check_risky_install("demo", Some("Critical"), None, no_passphrase).is_ok(), "Disabled by default");
+
+        policy.confirm_risky_installs = true;
+        assert!(policy.check_risky_install("demo", Some("Medium"), None, no_passphrase).is_ok());
+        assert!(policy.check_risky_install("demo", Some("High"), None, no_passphrase).is_err());
+        assert!(policy.check_risky_install("demo", Some("High"), Some("demo"), no_passphrase).is_ok());
+
+        let passphrase = || Some("s3cret".to_string());
+        assert!(policy.check_risky_install("demo", Some("Critical"), Some("demo"), passphrase).is_err());
+        assert!(policy.check_risky_install("demo", Some("Critical"), Some("s3cret"), passphrase).is_ok());
+
+        assert!(ScanPolicy::verify_install_passphrase(None, None).is_ok());
+        assert!(ScanPolicy::verify_install_passphrase(Some("s3cret"), Some(" s3cret ")).is_ok());
+        assert!(ScanPolicy::verify_install_passphrase(Some("s3cret"), None).is_err());
+        assert_eq!(ScanPolicy::verify_install_passphrase(Some("s3cret"), Some("other")).unwrap_err().kind, ErrorKind::Unauthorized);
+    }
+
+    #[test]
+    fn test_partial_settings_use_defaults() {
+        let settings: Settings = serde_json::from_str(r#"{"locale":"en"}"#).unwrap();
+        assert_eq!(settings.locale, "en");
+        assert_eq!(settings.scan_policy, ScanPolicy::default());
+        assert_eq!(settings.network, NetworkConfig::default());
+    }
+}
@@ -0,0 +1,250 @@
+use anyhow::{Context, Result};
+use base64::Engine;
+
+/// OpenPGP 签名包类型
+const SIGNATURE_PACKET_TAG: u8 = 2;
+/// 签名子包：签名者密钥指纹（版本号 + 20/32 字节指纹）
+const SUBPACKET_ISSUER_FINGERPRINT: u8 = 33;
+
+/// 规范化用户填写的密钥 ID / 指纹：去除空格与 0x 前缀并转为大写
+/// 只接受 16 位长密钥 ID 或 40/64 位指纹（短密钥 ID 容易碰撞，不予支持）
+pub fn normalize_key_id(key: &str) -> Option<String> {
+    let key: String = key.chars().filter(|c| !c.is_whitespace()).collect();
+    let key = key
+        .strip_prefix("0x")
+        .or_else(|| key.strip_prefix("0X"))
+        .unwrap_or(&key)
+        .to_ascii_uppercase();
+
+    (matches!(key.len(), 16 | 40 | 64) && key.chars().all(|c| c.is_ascii_hexdigit())).then_some(key)
+}
+
+/// 签名者是否在受信任密钥列表中
+/// 长密钥 ID 是指纹的后 16 位，因此指纹与密钥 ID 之间按后缀匹配
+pub fn is_trusted_signer(signer_key_ids: &[String], approved_keys: &[String]) -> bool {
+    approved_keys
+        .iter()
+        .filter_map(|key| normalize_key_id(key))
+        .any(|approved| {
+            signer_key_ids
+                .iter()
+                .any(|signer| signer.ends_with(&approved) || approved.ends_with(signer.as_str()))
+        })
+}
+
+/// 从 ASCII 装甲格式的 OpenPGP 签名中提取签名者密钥指纹（大写十六进制）
+/// 只采用哈希子包区中的签名者指纹：非哈希子包区（含其中的签名者密钥 ID）不受签名保护，
+/// 任何人都可以改写而不破坏签名，不能用来判断签名者。
+/// 非 OpenPGP 签名（如 SSH、S/MIME 签名）与 v3 签名返回空列表
+pub fn signature_key_ids(armored: &str) -> Result<Vec<String>> {
+    if !armored.contains("-----BEGIN PGP SIGNATURE-----") {
+        return Ok(Vec::new());
+    }
+
+    let packets = dearmor(armored)?;
+    let mut key_ids = Vec::new();
+    let mut rest = packets.as_slice();
+
+    while !rest.is_empty() {
+        let (tag, body, remaining) = read_packet(rest)?;
+        if tag == SIGNATURE_PACKET_TAG {
+            for key_id in signature_packet_key_ids(body)? {
+                if !key_ids.contains(&key_id) {
+                    key_ids.push(key_id);
+                }
+            }
+        }
+        rest = remaining;
+    }
+
+    Ok(key_ids)
+}
+
+/// 去除 ASCII 装甲：跳过头部字段，忽略 CRC 校验行
+fn dearmor(armored: &str) -> Result<Vec<u8>> {
+    let mut body = String::new();
+    let mut in_body = false;
+    let mut in_headers = false;
+
+    for line in armored.lines().map(str::trim) {
+        if line.starts_with("-----BEGIN PGP SIGNATURE-----") {
+            in_headers = true;
+            continue;
+        }
+        if line.starts_with("-----END PGP SIGNATURE-----") {
+            break;
+        }
+        if in_headers {
+            // 头部字段（如 Version: / Comment:）以空行结束；没有头部时直接是正文
+            if line.is_empty() {
+                in_headers = false;
+                in_body = true;
+                continue;
+            }
+            if line.contains(": ") {
+                continue;
+            }
+            in_headers = false;
+            in_body = true;
+        }
+        if in_body && !line.starts_with('=') {
+            body.push_str(line);
+        }
+    }
+
+    base64::engine::general_purpose::STANDARD
+        .decode(body)
+        .context("签名内容不是有效的 Base64")
+}
+
+/// 读取一个 OpenPGP 包，返回 (类型, 包体, 剩余数据)
+fn read_packet(data: &[u8]) -> Result<(u8, &[u8], &[u8])> {
+    let header = *data.first().context("签名数据为空")?;
+    if header & 0x80 == 0 {
+        anyhow::bail!("无效的 OpenPGP 包头");
+    }
+
+    let (tag, length, offset) = if header & 0x40 != 0 {
+        // 新格式包头
+        let tag = header & 0x3F;
+        let first = *data.get(1).context("签名数据被截断")? as usize;
+        match first {
+            0..=191 => (tag, first, 2),
+            192..=223 => {
+                let second = *data.get(2).context("签名数据被截断")? as usize;
+                (tag, ((first - 192) << 8) + second + 192, 3)
+            }
+            255 => (tag, read_be(data.get(2..6).context("签名数据被截断")?), 6),
+            _ => anyhow::bail!("不支持分段长度的 OpenPGP 包"),
+        }
+    } else {
+        // 旧格式包头
+        let tag = (header >> 2) & 0x0F;
+        match header & 0x03 {
+            0 => (tag, *data.get(1).context("签名数据被截断")? as usize, 2),
+            1 => (tag, read_be(data.get(1..3).context("签名数据被截断")?), 3),
+            2 => (tag, read_be(data.get(1..5).context("签名数据被截断")?), 5),
+            _ => (tag, data.len() - 1, 1),
+        }
+    };
+
+    let body = data.get(offset..offset + length).context("签名数据被截断")?;
+    Ok((tag, body, &data[offset + length..]))
+}
+
+fn read_be(bytes: &[u8]) -> usize {
+    bytes.iter().fold(0, |acc, b| (acc << 8) | *b as usize)
+}
+
+fn signature_packet_key_ids(body: &[u8]) -> Result<Vec<String>> {
+    let version = *body.first().context("签名包为空")?;
+
+    match version {
+        // v3 签名的密钥 ID 不在签名的哈希范围内，无法确认签名者
+        3 => Ok(Vec::new()),
+        4..=6 => {
+            // 版本、类型、公钥算法、哈希算法之后是哈希子包区（v6 的长度为 4 字节，v4/v5 为 2 字节）
+            let length_size = if version == 6 { 4 } else { 2 };
+            let offset = 4;
+            let length = read_be(body.get(offset..offset + length_size).context("签名包被截断")?);
+            let hashed = body.get(offset + length_size..offset + length_size + length).context("签名包被截断")?;
+            issuer_fingerprints(hashed)
+        }
+        _ => anyhow::bail!("不支持的签名版本: {}", version),
+    }
+}
+
+fn issuer_fingerprints(mut area: &[u8]) -> Result<Vec<String>> {
+    let mut fingerprints = Vec::new();
+
+    while !area.is_empty() {
+        let first = area[0] as usize;
+        let (length, offset) = match first {
+            0..=191 => (first, 1),
+            192..=254 => {
+                let second = *area.get(1).context("签名子包被截断")? as usize;
+                (((first - 192) << 8) + second + 192, 2)
+            }
+            _ => (read_be(area.get(1..5).context("签名子包被截断")?), 5),
+        };
+
+        let subpacket = area.get(offset..offset + length).context("签名子包被截断")?;
+        if let Some((kind, data)) = subpacket.split_first() {
+            if kind & 0x7F == SUBPACKET_ISSUER_FINGERPRINT && data.len() > 1 {
+                fingerprints.push(hex::encode_upper(&data[1..]));
+            }
+        }
+        area = &area[offset + length..];
+    }
+
+    Ok(fingerprints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FINGERPRINT: &str = "5DE3E0509C47EA3CF04A42D34AEE18F83AFDEB23";
+    const OTHER_FINGERPRINT: &str = "0123456789ABCDEF0123456789ABCDEF01234567";
+    /// 签名子包：签名者密钥 ID（8 字节）
+    const SUBPACKET_ISSUER: u8 = 16;
+
+    /// 构造只包含签名者子包的 v4 签名（签名数值部分不参与解析，用占位字节代替）：
+    /// 哈希子包区为签名者指纹，非哈希子包区为签名者密钥 ID 与（可被任意改写的）指纹
+    fn armored_signature(hashed_fingerprint: &str, unhashed_fingerprint: &str) -> String {
+        let fingerprint = hex::decode(hashed_fingerprint).unwrap();
+        let mut hashed = vec![22, SUBPACKET_ISSUER_FINGERPRINT, 4];
+        hashed.extend(&fingerprint);
+        let forged = hex::decode(unhashed_fingerprint).unwrap();
+        let mut unhashed = vec![9, SUBPACKET_ISSUER];
+        unhashed.extend(&forged[12..]);
+        unhashed.extend([22, SUBPACKET_ISSUER_FINGERPRINT, 4]);
+        unhashed.extend(&forged);
+
+        let mut body = vec![4, 0x00, 1, 8];
+        body.extend((hashed.len() as u16).to_be_bytes());
+        body.extend(&hashed);
+        body.extend((unhashed.len() as u16).to_be_bytes());
+        body.extend(&unhashed);
+        body.extend([0xAB, 0xCD, 0x00, 0x01, 0x01]);
+
+        let mut packet = vec![0xC0 | SIGNATURE_PACKET_TAG, body.len() as u8];
+        assert!(body.len() < 192);
+        packet.extend(body);
+
+        let encoded = base64::engine::general_purpose::STANDARD.encode(packet);
+        format!(
+            "-----BEGIN PGP SIGNATURE-----\n\n{}\n{}\n=abcd\n-----END PGP SIGNATURE-----\n",
+            &encoded[..40],
+            &encoded[40..]
+        )
+    }
+
+    #[test]
+    fn test_signature_key_ids() {
+        let key_ids = signature_key_ids(&armored_signature(FINGERPRINT, FINGERPRINT)).unwrap();
+        assert_eq!(key_ids, vec![FINGERPRINT.to_string()]);
+
+        let ssh = "-----BEGIN SSH SIGNATURE-----\nU1NIU0lH\n-----END SSH SIGNATURE-----";
+        assert!(signature_key_ids(ssh).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unhashed_issuer_is_ignored() {
+        // 非哈希子包区中伪造的签名者（受信任的密钥）不能让其他密钥的签名通过检查
+        let key_ids = signature_key_ids(&armored_signature(OTHER_FINGERPRINT, FINGERPRINT)).unwrap();
+        assert_eq!(key_ids, vec![OTHER_FINGERPRINT.to_string()]);
+        assert!(!is_trusted_signer(&key_ids, &[FINGERPRINT.to_string()]));
+        assert!(!is_trusted_signer(&key_ids, &[FINGERPRINT[24..].to_string()]));
+    }
+
+    #[test]
+    fn test_trusted_signer() {
+        let signers = vec![FINGERPRINT[24..].to_string()];
+        assert!(is_trusted_signer(&signers, &["5DE3 E050 9C47 EA3C F04A  42D3 4AEE 18F8 3AFD EB23".to_string()]));
+        assert!(is_trusted_signer(&signers, &["0x4aee18f83afdeb23".to_string()]));
+        assert!(!is_trusted_signer(&signers, &["3AFDEB23".to_string()]));
+        assert!(!is_trusted_signer(&signers, &[]));
+        assert_eq!(normalize_key_id("zz"), None);
+    }
+}
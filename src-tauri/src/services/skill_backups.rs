@@ -0,0 +1,133 @@
+use crate::services::fs_ops;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 更新前保留的安装目录备份
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillBackup {
+    /// 备份对应的技能目录名
+    pub skill_dir_name: String,
+    pub path: String,
+    pub size_bytes: u64,
+    /// 备份时间（备份目录的修改时间）
+    pub created_at: DateTime<Utc>,
+}
+
+/// 新备份的目录名：`<技能目录名>.bak-<时间>`，同一技能可保留多个备份
+pub fn backup_dir_name(skill_dir_name: &str, now: DateTime<Utc>) -> String {
+    format!("{}.bak-{}", skill_dir_name, now.format("%Y%m%d%H%M%S%3f"))
+}
+
+/// 从备份目录名解析技能目录名（兼容旧的 `<技能目录名>.bak`），不是备份目录时为 None
+fn skill_dir_name(backup_name: &str) -> Option<&str> {
+    let (name, suffix) = backup_name.rsplit_once(".bak")?;
+    (suffix.is_empty() || suffix.starts_with('-')).then_some(name).filter(|name| !name.is_empty())
+}
+
+/// 列出备份目录中的备份，按时间从新到旧排列
+pub fn list_backups(root: &Path) -> Result<Vec<SkillBackup>> {
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(root).context(format!("无法读取备份目录: {:?}", root))? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some(name) = skill_dir_name(&file_name).filter(|_| metadata.is_dir()) else {
+            continue;
+        };
+        backups.push(SkillBackup {
+            skill_dir_name: name.to_string(),
+            path: entry.path().to_string_lossy().to_string(),
+            size_bytes: fs_ops::dir_size(&entry.path()).unwrap_or(0),
+            created_at: metadata.modified().map(DateTime::<Utc>::from).unwrap_or_else(|_| Utc::now()),
+        });
+    }
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| b.path.cmp(&a.path)));
+    Ok(backups)
+}
+
+/// 按每个技能的备份数与总大小上限删除最旧的备份（0 表示不限制），返回删除的备份目录及其大小；
+/// 删除失败的备份记录警告后跳过
+pub fn prune_backups(root: &Path, max_per_skill: usize, max_total_bytes: u64) -> Result<Vec<(PathBuf, u64)>> {
+    let backups = list_backups(root)?;
+    let mut kept_per_skill: HashMap<&str, usize> = HashMap::new();
+    let mut expired = Vec::new();
+    let mut kept = Vec::new();
+
+    for backup in &backups {
+        let count = kept_per_skill.entry(backup.skill_dir_name.as_str()).or_default();
+        if max_per_skill > 0 && *count >= max_per_skill {
+            expired.push(backup);
+        } else {
+            *count += 1;
+            kept.push(backup);
+        }
+    }
+
+    // 超过总大小上限时从最旧的备份开始删除
+    if max_total_bytes > 0 {
+        let mut total: u64 = kept.iter().map(|backup| backup.size_bytes).sum();
+        while total > max_total_bytes {
+            let Some(oldest) = kept.pop() else { break };
+            total -= oldest.size_bytes;
+            expired.push(oldest);
+        }
+    }
+
+    let mut removed = Vec::new();
+    for backup in expired {
+        match fs_ops::remove_dir_all(&backup.path) {
+            Ok(()) => removed.push((PathBuf::from(&backup.path), backup.size_bytes)),
+            Err(e) => log::warn!("删除更新备份失败: {}, 错误: {}", backup.path, e),
+        }
+    }
+    if !removed.is_empty() {
+        log::info!("已清理 {} 个更新备份", removed.len());
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prune_backups_by_count_and_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let create = |name: &str, bytes: usize, age_secs: u64| {
+            let path = root.join(name);
+            fs::create_dir_all(&path).unwrap();
+            fs::write(path.join("SKILL.md"), vec![b'x'; bytes]).unwrap();
+            let mtime = std::time::SystemTime::now() - std::time::Duration::from_secs(age_secs);
+            filetime::set_file_mtime(&path, filetime::FileTime::from_system_time(mtime)).unwrap();
+        };
+        create("pdf.bak", 100, 400);
+        create("pdf.bak-20260101000000000", 100, 300);
+        create("pdf.bak-20260201000000000", 100, 200);
+        create("docx.bak-20260301000000000", 100, 100);
+        fs::create_dir(root.join("not-a-backup")).unwrap();
+
+        let backups = list_backups(root).unwrap();
+        assert_eq!(backups.len(), 4);
+        assert_eq!(backups[0].skill_dir_name, "docx");
+        assert_eq!(backups[3].skill_dir_name, "pdf");
+
+        // 每个技能保留 2 个：删除最旧的 pdf.bak
+        let removed = prune_backups(root, 2, 0).unwrap();
+        assert_eq!(removed, vec![(root.join("pdf.bak"), 100)]);
+
+        // 总大小上限 250 字节：再删除最旧的一个 pdf 备份
+        let removed = prune_backups(root, 2, 250).unwrap();
+        assert_eq!(removed, vec![(root.join("pdf.bak-20260101000000000"), 100)]);
+        assert_eq!(list_backups(root).unwrap().len(), 2);
+    }
+}
@@ -1,85 +1,201 @@
-use crate::models::Skill;
-use crate::security::SecurityScanner;
-use crate::services::{Database, GitHubService};
+use crate::models::{
+    AppError, BlockReason, CommitSignatureStatus, ErrorKind, InstallBlocked, InstallResult, OperationKind, OperationStep, PendingOperation, Repository, Skill,
+    SkillEvent, SkillEventKind, SkillHealthProblem, LocalScanResult, SkillHealthReport, SkillReadme, SkillReadmeFile, SkillReadmeSource, SkillRelocation, SkillRepairAction,
+    ManifestNames, SKILL_MANIFEST_NAME, ScanSummary, SourceType, ChecksumSource, PathChecksum, SkillChecksumVerification, normalize_checksum, DEFAULT_DISCOVERY_DEPTH, LOCAL_REPOSITORY_URL, get_all_supported_tools, local_skill_id, tool_for_path,
+};
+use crate::security::prompt_injection::detect_prompt_injection;
+use crate::security::{FileChanges, ScanFilter, SecurityScanner};
+use crate::services::settings::{CacheSettings, DirectorySettings, ScanPolicy};
+use crate::services::fs_ops::{self, CopyReport, FsContext, ProgressHandler};
+use crate::services::impact::{self, UninstallImpact};
+use crate::services::{direct_url, relocate, rename};
+use crate::services::direct_url::DirectSource;
+use crate::services::dir_access::{self, DirAccessReport, DirFixOptions};
+use crate::services::skill_backups::{self, SkillBackup};
+use crate::services::{signature, BlobStore, CachePaths, CredentialStore, Database, GitHubService, Settings};
+use crate::services::credentials::INSTALL_PASSPHRASE_ACCOUNT;
 use anyhow::{Result, Context};
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
 use chrono::Utc;
 
+/// 技能管理器
+///
+/// 内部自行同步，可通过 `Arc<SkillManager>` 在多个命令间共享：
+/// 读取类操作不加锁；安装、更新、卸载等写操作通过 [`SkillManager::begin_operation`] 互斥，
+/// 长时间的安装不会阻塞技能列表等读取命令。
+///
+/// 目录复制、删除等耗时的文件操作在阻塞线程池中执行（见 [`fs_ops`]），不占用异步运行时；
+/// 复制过程会报告进度，并可通过 [`SkillManager::request_cancel`] 中途取消。
 pub struct SkillManager {
     db: Arc<Database>,
     github: Arc<GitHubService>,
     scanner: SecurityScanner,
-    skills_dir: PathBuf,
+    skills_dir: RwLock<PathBuf>,
+    /// 目录设置（本地技能扫描目录、排除列表与时间上限）
+    directories: RwLock<DirectorySettings>,
+    scan_policy: RwLock<ScanPolicy>,
+    cache_settings: RwLock<CacheSettings>,
+    /// 写操作互斥锁（避免并发安装/更新同一技能时互相覆盖文件与数据库记录）
+    operation_lock: tokio::sync::Mutex<()>,
+    /// 取消当前写操作的请求标记（新的写操作开始时清除）
+    cancel_requested: Arc<AtomicBool>,
+    /// 文件复制进度回调
+    progress_handler: RwLock<Option<ProgressHandler>>,
 }
 
 impl SkillManager {
     pub fn new(db: Arc<Database>, github: Arc<GitHubService>) -> Self {
-        let skills_dir = Self::get_skills_directory();
+        let skills_dir = DirectorySettings::default_skills_dir();
 
         Self {
             db,
             github,
             scanner: SecurityScanner::new(),
-            skills_dir,
+            skills_dir: RwLock::new(skills_dir),
+            directories: RwLock::new(DirectorySettings::default()),
+            scan_policy: RwLock::new(ScanPolicy::default()),
+            cache_settings: RwLock::new(CacheSettings::default()),
+            operation_lock: tokio::sync::Mutex::new(()),
+            cancel_requested: Arc::new(AtomicBool::new(false)),
+            progress_handler: RwLock::new(None),
         }
     }
 
-    /// 获取 skills 安装目录
-    fn get_skills_directory() -> PathBuf {
-        let home = dirs::home_dir().expect("Failed to get home directory");
-        home.join(".claude").join("skills")
+    /// 应用设置（安装目录、扫描策略与技能清单文件名），启动时及设置变更时调用
+    pub fn apply_settings(&self, settings: &Settings) {
+        let skills_dir = settings.directories.resolved_skills_dir();
+        log::info!("SkillManager 已应用设置: skills_dir={:?}", skills_dir);
+        *self.skills_dir.write().unwrap() = skills_dir;
+        *self.directories.write().unwrap() = settings.directories.clone();
+        let previous = std::mem::replace(&mut *self.scan_policy.write().unwrap(), settings.scan_policy.clone());
+        let thresholds = settings.scan_policy.level_thresholds();
+        if previous.level_thresholds() != thresholds {
+            match self.db.relevel_skills(&thresholds) {
+                Ok(changed) => log::info!("安全等级阈值已变更，{} 个技能的安全等级已更新", changed),
+                Err(e) => log::warn!("按新的安全等级阈值更新技能失败: {}", e),
+            }
+        }
+        *self.cache_settings.write().unwrap() = settings.cache.clone();
+        self.github.set_manifest_names(settings.discovery.manifest_names());
     }
 
-    /// 下载并分析 skill，返回文件内容和安全报告
-    pub async fn download_and_analyze(&self, skill: &mut Skill) -> Result<(Vec<u8>, crate::models::SecurityReport)> {
-        // 构建下载 URL
-        let (owner, repo, url_branch) = crate::models::Repository::from_github_url(&skill.repository_url)?;
+    /// 当前的默认技能安装目录
+    fn skills_dir(&self) -> PathBuf {
+        self.skills_dir.read().unwrap().clone()
+    }
+
+    /// 技能的安装基础目录：指定的路径优先，其次为该技能记住的安装目录，最后为默认安装目录
+    fn install_base_dir(&self, skill_id: &str, install_path: Option<&str>) -> PathBuf {
+        if let Some(path) = install_path {
+            return PathBuf::from(path);
+        }
+        match self.db.get_skill_install_target(skill_id) {
+            Ok(Some(dir)) => PathBuf::from(dir),
+            Ok(None) => self.skills_dir(),
+            Err(e) => {
+                log::warn!("读取技能的安装目录失败: {}, 错误: {}", skill_id, e);
+                self.skills_dir()
+            }
+        }
+    }
 
-        // 尝试多个分支下载 SKILL.md 文件
-        // 如果 URL 中包含分支，优先使用该分支
-        let branches = if let Some(b) = url_branch {
-            vec![b]
+    /// 安装成功后记住指定的安装目录，之后安装与更新不再询问（选择默认安装目录时忘记之前的选择）
+    fn remember_install_target(&self, skill_id: &str, install_path: Option<&str>) {
+        let Some(path) = install_path else { return };
+        let result = if std::path::Path::new(path) == self.skills_dir() {
+            self.db.delete_skill_install_target(skill_id)
         } else {
-            vec!["main".to_string(), "master".to_string()]
+            self.db.set_skill_install_target(skill_id, path)
         };
-        let mut content = None;
-        let mut last_error = None;
-
-        for branch in branches.iter() {
-            let download_url = format!(
-                "https://raw.githubusercontent.com/{}/{}/{}/{}/SKILL.md",
-                owner, repo, branch, skill.file_path
-            );
-
-            log::info!("尝试从分支 {} 下载 SKILL.md: {}", branch, download_url);
+        if let Err(e) = result {
+            log::warn!("记录技能的安装目录失败: {}, 错误: {}", skill_id, e);
+        }
+    }
 
-            match self.github.download_file(&download_url).await {
-                Ok(file_content) => {
-                    log::info!("成功从分支 {} 下载 SKILL.md", branch);
-                    content = Some(file_content);
-                    break;
-                }
-                Err(e) => {
-                    log::info!("分支 {} 下载失败: {}", branch, e);
-                    last_error = Some(e);
-                    continue;
-                }
+    /// 设置技能的安装目录（目录需已存在或可创建且可写入），为 None 时恢复使用默认安装目录
+    pub fn set_skill_install_target(&self, skill_id: &str, install_path: Option<&str>) -> Result<()> {
+        if !self.db.get_skills()?.iter().any(|s| s.id == skill_id) {
+            return Err(AppError::not_found("未找到该技能").into());
+        }
+        match install_path.map(str::trim).filter(|path| !path.is_empty()) {
+            Some(path) => {
+                dir_access::ensure_install_target(std::path::Path::new(path))?;
+                self.db.set_skill_install_target(skill_id, path)?;
             }
+            None => self.db.delete_skill_install_target(skill_id)?,
         }
+        Ok(())
+    }
 
-        let content = content.ok_or_else(|| {
-            last_error.unwrap_or_else(|| anyhow::anyhow!("所有分支均无法下载 SKILL.md"))
-        })?;
+    /// 更新时写入的安装目录：优先使用位于记住的安装目录下的路径，否则使用第一个安装路径
+    fn update_target_dir(&self, skill_id: &str, install_paths: &[String]) -> PathBuf {
+        let remembered = self.db.get_skill_install_target(skill_id).ok().flatten().map(PathBuf::from);
+        install_paths.iter()
+            .map(PathBuf::from)
+            .find(|path| remembered.as_deref().is_some_and(|base| path.parent() == Some(base)))
+            .unwrap_or_else(|| PathBuf::from(&install_paths[0]))
+    }
+
+    /// 当前的扫描策略
+    fn scan_policy(&self) -> ScanPolicy {
+        self.scan_policy.read().unwrap().clone()
+    }
+
+    /// 开始一个写操作（安装、更新、卸载、导入），返回的守卫释放前其他写操作需等待
+    pub async fn begin_operation(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        let guard = self.operation_lock.lock().await;
+        self.cancel_requested.store(false, Ordering::SeqCst);
+        guard
+    }
+
+    /// 请求取消正在进行的写操作：进行中的目录复制会尽快中止并清理已复制的文件
+    pub fn request_cancel(&self) {
+        log::info!("请求取消当前技能操作");
+        self.cancel_requested.store(true, Ordering::SeqCst);
+    }
+
+    /// 设置文件复制进度回调
+    pub fn set_progress_handler(&self, handler: ProgressHandler) {
+        *self.progress_handler.write().unwrap() = Some(handler);
+    }
+
+    /// 当前写操作的文件操作上下文（响应取消请求并报告进度）
+    fn fs_context(&self) -> FsContext {
+        FsContext::new(Arc::clone(&self.cancel_requested), self.progress_handler.read().unwrap().clone())
+    }
+
+    /// 在阻塞线程池中执行同步的技能操作（卸载、确认安装/更新、导入本地技能等涉及大量文件读写的操作）
+    pub async fn run_blocking<T, F>(self: &Arc<Self>, f: F) -> Result<T>
+    where
+        F: FnOnce(&SkillManager) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let manager = Arc::clone(self);
+        fs_ops::run_blocking(move || f(&manager)).await
+    }
+
+    /// 下载并分析 skill，返回文件内容和安全报告
+    pub async fn download_and_analyze(&self, skill: &mut Skill) -> Result<(Vec<u8>, crate::models::SecurityReport)> {
+        // 构建下载 URL
+        let (owner, repo, url_branch) = crate::models::Repository::from_github_url(&skill.repository_url)?;
+
+        // 下载 SKILL.md（不区分大小写，也尝试配置的其他清单文件名）
+        // 如果 URL 中包含分支，只使用该分支，否则依次尝试 main 与 master
+        let (manifest_name, content_str) = self.github
+            .fetch_skill_manifest(&owner, &repo, url_branch.as_deref(), &skill.file_path)
+            .await?;
+        log::info!("成功下载技能清单: {}/{}", skill.file_path, manifest_name);
 
         // 解析 frontmatter 更新 skill 元数据
-        let (name, description) = self.github.fetch_skill_metadata(&owner, &repo, &skill.file_path).await?;
+        let (name, description) = self.github.parse_skill_frontmatter(&content_str)?;
         skill.name = name;
         skill.description = description;
 
         // 安全扫描
-        let content_str = String::from_utf8_lossy(&content);
-        let report = self.scanner.scan_file(&content_str, "SKILL.md", "zh")?;
+        skill.compatible_tools = Skill::parse_compatible_tools(&content_str);
+        let report = self.scan_policy().scanner().scan_file(&content_str, &manifest_name, "zh")?;
 
         // 更新 skill 信息
         skill.security_score = Some(report.score);
@@ -90,35 +206,48 @@ impl SkillManager {
                 .collect()
         );
         skill.scanned_at = Some(Utc::now());
-        skill.checksum = Some(self.scanner.calculate_checksum(&content));
+        skill.checksum = Some(self.scanner.calculate_checksum(content_str.as_bytes()));
 
-        Ok((content, report))
+        Ok((content_str.into_bytes(), report))
     }
 
-    /// 安装 skill 到本地
-    pub async fn install_skill(&self, skill_id: &str, install_path: Option<String>, skip_scan: bool) -> Result<()> {
+    /// 安装 skill 到本地；高风险技能需传入确认文本（同 confirm_skill_installation），
+    /// 跳过扫描（同步）时按保存的安全等级检查
+    pub async fn install_skill(&self, skill_id: &str, install_path: Option<String>, skip_scan: bool, confirmation: Option<&str>) -> Result<InstallResult> {
         // 从数据库获取 skill
         let mut skill = self.db.get_skills()?
             .into_iter()
             .find(|s| s.id == skill_id)
-            .context("未找到该技能，请检查技能是否存在")?;
+            .ok_or_else(|| AppError::not_found("未找到该技能，请检查技能是否存在"))?;
+        ensure_repository_source(&skill)?;
+        if skip_scan {
+            self.scan_policy().check_install_score(&skill.name, skill.security_score, confirmation)?;
+            self.check_risky_install(&skill.name, skill.security_level.as_deref(), confirmation)?;
+        }
 
         // 获取对应的仓库记录以获取缓存路径
         let repositories = self.db.get_repositories()?;
         let repo = repositories.iter()
             .find(|r| r.url == skill.repository_url)
-            .context("未找到对应的仓库记录")?;
+            .ok_or_else(|| AppError::not_found("未找到对应的仓库记录"))?;
+
+        if let Some(commit_sha) = &repo.cached_commit_sha {
+            self.ensure_signed_commit(repo, commit_sha, repo.commit_signature.as_ref())?;
+        } else if repo.require_signed_commits {
+            return Err(AppError::new(
+                ErrorKind::SignatureRejected,
+                format!("仓库 {} 要求已签名的提交，但尚未缓存，无法确认提交签名", repo.name),
+            )
+            .into());
+        }
 
-        // 确定安装基础目录（使用自定义路径或默认路径）
-        let install_base_dir = if let Some(user_path) = install_path {
-            PathBuf::from(user_path)
-        } else {
-            self.skills_dir.clone()
-        };
+        // 确定安装基础目录（指定的路径、该技能记住的目录或默认路径）
+        let install_base_dir = self.install_base_dir(&skill.id, install_path.as_deref());
 
         // 确保目标目录存在
-        std::fs::create_dir_all(&install_base_dir)
+        tokio::fs::create_dir_all(&install_base_dir).await
             .context("无法创建技能目录，请检查磁盘权限")?;
+        dir_access::check_dir(&install_base_dir, false).ensure_ready()?;
 
         // 创建 skill 文件夹（使用 skill 的文件夹名）
         // 如果 file_path 是 "."（位于仓库根目录），使用技能名称作为文件夹名
@@ -138,16 +267,17 @@ impl SkillManager {
 
         // 如果目标目录已存在，先清理（避免旧文件冲突）
         if skill_dir.exists() {
+            dir_access::check_dir(&skill_dir, true).ensure_ready()?;
             log::info!("目标目录已存在，先清理: {:?}", skill_dir);
-            std::fs::remove_dir_all(&skill_dir)
+            fs_ops::remove_path(skill_dir.clone()).await
                 .context("无法清理现有技能目录")?;
         }
 
-        std::fs::create_dir_all(&skill_dir)
+        tokio::fs::create_dir_all(&skill_dir).await
             .context("无法创建技能子目录，请检查磁盘空间和权限")?;
 
         // 优先从本地缓存复制文件
-        if let Some(cache_path) = &repo.cache_path {
+        let files_copied = if let Some(cache_path) = &repo.cache_path {
             let cache_path_buf = PathBuf::from(cache_path);
 
             // 在缓存中找到技能目录
@@ -175,28 +305,47 @@ impl SkillManager {
                 if cached_skill_dir.exists() {
                     log::info!("从本地缓存复制文件: {:?}", cached_skill_dir);
 
-                    // 复制整个目录
-                    self.copy_directory(&cached_skill_dir, &skill_dir)
-                        .context("从缓存复制文件失败")?;
-
-                    log::info!("成功从本地缓存安装技能");
+                    // 复制整个目录（失败或被取消时清理已复制的部分）
+                    let copied = fs_ops::copy_dir_async(cached_skill_dir, skill_dir.clone(), self.fs_context())
+                        .await
+                        .and_then(CopyReport::ensure_complete);
+                    match copied {
+                        Ok(files) => {
+                            log::info!("成功从本地缓存安装技能");
+                            files
+                        }
+                        Err(e) => {
+                            if let Err(clean_err) = fs_ops::remove_path(skill_dir.clone()).await {
+                                log::warn!("清理未完成的技能目录失败: {:?}, 错误: {}", skill_dir, clean_err);
+                            }
+                            return Err(e.context("从缓存复制文件失败"));
+                        }
+                    }
                 } else {
                     log::warn!("缓存中未找到技能目录，降级使用网络下载");
-                    self.install_from_network(&skill, &skill_dir).await?;
+                    self.install_from_network(&skill, &skill_dir).await?
                 }
             } else {
                 log::warn!("缓存目录格式异常，降级使用网络下载");
-                self.install_from_network(&skill, &skill_dir).await?;
+                self.install_from_network(&skill, &skill_dir).await?
             }
         } else {
             log::info!("仓库未缓存，使用网络下载");
-            self.install_from_network(&skill, &skill_dir).await?;
+            self.install_from_network(&skill, &skill_dir).await?
+        };
+
+        // 技能清单固定了内容哈希时校验安装的内容
+        if let Err(e) = self.verify_pinned_content(&skill, &skill_dir) {
+            if let Err(clean_err) = fs_ops::remove_path(skill_dir.clone()).await {
+                log::warn!("清理未通过校验的技能目录失败: {:?}, 错误: {}", skill_dir, clean_err);
+            }
+            return Err(e);
         }
+        fs_ops::normalize_skill_manifest(&skill_dir, &self.github.manifest_names())?;
 
         // 从缓存读取 SKILL.md 进行元数据提取
-        let skill_md_path = skill_dir.join("SKILL.md");
-        if skill_md_path.exists() {
-            let skill_md_content = std::fs::read_to_string(&skill_md_path)
+        if let Some(skill_md_path) = fs_ops::find_skill_manifest(&skill_dir, &self.github.manifest_names()) {
+            let skill_md_content = tokio::fs::read_to_string(&skill_md_path).await
                 .context("读取 SKILL.md 失败")?;
 
             // 解析 frontmatter
@@ -204,34 +353,42 @@ impl SkillManager {
                 skill.name = name;
                 skill.description = description;
             }
+            skill.compatible_tools = Skill::parse_compatible_tools(&skill_md_content);
+        }
+
+        if let Some(warning) = Self::compatibility_warning(&skill, &install_base_dir) {
+            log::warn!("{}", warning);
         }
 
         // 扫描整个技能目录
-        if !skip_scan {
-            let scan_report = self.scanner.scan_directory(
-                skill_dir.to_str().context("技能目录路径无效")?,
-                &skill.id,
-                "zh"
-            )?;
+        let scan_summary = if !skip_scan {
+            let scan_report = self.scan_directory_async(&skill_dir, &skill.id, "zh").await?;
 
             log::info!("Security scan completed: score={}, scanned {} files",
                 scan_report.score, scan_report.scanned_files.len());
 
+            let scan_policy = self.scan_policy();
+
             // 检查是否被 hard_trigger 阻止
-            if scan_report.blocked {
+            if scan_report.blocked && scan_policy.block_hard_triggers {
                 // 先删除已下载的文件
-                if skill_dir.exists() {
-                    std::fs::remove_dir_all(&skill_dir)?;
-                }
+                fs_ops::remove_path(skill_dir.clone()).await?;
+                return Err(InstallBlocked::hard_triggers(&skill.name, &scan_report).into());
+            }
 
-                let mut error_msg = format!(
-                    "⛔ 安全检测发现严重威胁，禁止安装！\n\n检测到以下高危操作：\n"
-                );
-                for (idx, issue) in scan_report.hard_trigger_issues.iter().enumerate() {
-                    error_msg.push_str(&format!("{}. {}\n", idx + 1, issue));
-                }
-                error_msg.push_str("\n这些操作可能对您的系统造成严重危害，强烈建议不要安装此技能。");
-                anyhow::bail!(error_msg);
+            // 检查是否低于扫描策略要求的最低评分（按策略可输入技能名称确认）
+            if let Err(e) = scan_policy.check_install_score(&skill.name, Some(scan_report.score), confirmation) {
+                fs_ops::remove_path(skill_dir.clone()).await?;
+                return Err(match e.downcast::<InstallBlocked>() {
+                    Ok(blocked) => blocked.with_scan(&scan_report).into(),
+                    Err(e) => e,
+                });
+            }
+
+            // 高风险技能需要输入确认
+            if let Err(e) = self.check_risky_install(&skill.name, Some(scan_report.level.as_str()), confirmation) {
+                fs_ops::remove_path(skill_dir.clone()).await?;
+                return Err(e);
             }
 
             // 更新 skill 安全信息
@@ -247,11 +404,15 @@ impl SkillManager {
                     })
                     .collect()
             );
-            skill.scanned_at = Some(Utc::now());
+            let scanned_at = Utc::now();
+            skill.scanned_at = Some(scanned_at);
+            self.save_scan_report(&skill.id, &scan_report, scanned_at);
+            Some(ScanSummary::from(&scan_report))
         } else {
             log::info!("Skipping security scan for trusted installation");
             // 保持原有的安全分数（如果存在）
-        }
+            self.stored_scan_summary(&skill.id)
+        };
 
         // 更新数据库
         let new_path = skill_dir.to_string_lossy().to_string();
@@ -266,26 +427,134 @@ impl SkillManager {
         // 更新 installed 状态和时间
         skill.installed = true;
         skill.installed_at = Some(Utc::now());
-        skill.local_path = Some(new_path); // 保持向后兼容,存储最新的路径
+        skill.local_path = Some(new_path.clone()); // 保持向后兼容,存储最新的路径
 
         self.db.save_skill(&skill)?;
+        self.record_event(&skill.id, SkillEventKind::Installed, skill.local_path.as_deref(), repo.cached_commit_sha.as_deref());
+        self.unpin_skill_content(&skill.id);
+        self.remember_install_target(&skill.id, install_path.as_deref());
 
         log::info!("Skill installed successfully: {}", skill.name);
-        Ok(())
+        Ok(InstallResult::installed(&skill.id, new_path, files_copied, scan_summary))
+    }
+
+    /// 从直接地址（技能清单的原始文件地址或 Gist）安装技能：下载清单及其以相对路径引用的文件，
+    /// 扫描通过后安装为 DirectUrl 来源的技能。再次安装同一地址时替换原安装目录（用于更新）
+    pub async fn install_skill_from_url(&self, url: &str, install_path: Option<String>, locale: &str, confirmation: Option<&str>) -> Result<Skill> {
+        let source = DirectSource::parse(url)?;
+        let files = direct_url::download(&self.github, &source, &self.github.manifest_names()).await?;
+        let manifest = String::from_utf8_lossy(&files[0].1).to_string();
+        let (name, description) = self.github.parse_skill_frontmatter(&manifest)?;
+
+        let repository_url = source.url();
+        let mut skill = self.db.get_skills()?
+            .into_iter()
+            .find(|s| s.repository_url == repository_url)
+            .unwrap_or_else(|| Skill::new(name.clone(), repository_url.clone(), ".".to_string()));
+        skill.name = name;
+        skill.description = description;
+        skill.source_type = SourceType::DirectUrl;
+        skill.compatible_tools = Skill::parse_compatible_tools(&manifest);
+
+        let install_base_dir = self.install_base_dir(&skill.id, install_path.as_deref());
+        tokio::fs::create_dir_all(&install_base_dir).await
+            .context("无法创建技能目录，请检查磁盘权限")?;
+        dir_access::check_dir(&install_base_dir, false).ensure_ready()?;
+        // 已安装在同一目录下时沿用原目录名
+        let skill_dir = installed_dirs(&skill)
+            .into_iter()
+            .find(|dir| dir.parent() == Some(install_base_dir.as_path()))
+            .unwrap_or_else(|| {
+                let dir_name = rename::validate_dir_name(&skill.name).unwrap_or_else(|_| source.default_dir_name());
+                install_base_dir.join(dir_name)
+            });
+
+        // 先写入同一目录下的临时目录并扫描，通过后再替换安装目录
+        let staging = tempfile::Builder::new()
+            .prefix(".skill-download-")
+            .tempdir_in(&install_base_dir)
+            .context("无法创建临时目录")?;
+        let staging_dir = staging.path().to_path_buf();
+        let checksum = direct_url::content_checksum(&files);
+        {
+            let staging_dir = staging_dir.clone();
+            fs_ops::run_blocking(move || direct_url::write_files(&staging_dir, &files)).await?;
+        }
+
+        let report = self.scan_directory_async(&staging_dir, &skill.id, locale).await;
+        if let Err(e) = self.db.delete_scan_cache(&staging_dir.to_string_lossy()) {
+            log::warn!("删除扫描缓存失败: {:?}, 错误: {}", staging_dir, e);
+        }
+        let report = report?;
+        let scan_policy = self.scan_policy();
+        if report.blocked && scan_policy.block_hard_triggers {
+            return Err(InstallBlocked::hard_triggers(&skill.name, &report).into());
+        }
+        scan_policy.check_install_score(&skill.name, Some(report.score), confirmation)?;
+        self.check_risky_install(&skill.name, Some(report.level.as_str()), confirmation)?;
+
+        if skill_dir.exists() {
+            fs_ops::remove_path(skill_dir.clone()).await
+                .context("无法清理现有技能目录")?;
+        }
+        std::fs::rename(&staging_dir, &skill_dir)
+            .context(format!("无法移动技能目录到 {:?}", skill_dir))?;
+
+        let new_path = skill_dir.to_string_lossy().to_string();
+        let mut paths = skill.local_paths.clone().unwrap_or_default();
+        if !paths.contains(&new_path) {
+            paths.push(new_path.clone());
+        }
+        let updated = skill.installed;
+        skill.local_paths = Some(paths);
+        skill.local_path = Some(new_path);
+        skill.installed = true;
+        skill.installed_at = Some(Utc::now());
+        skill.checksum = Some(checksum);
+        skill.security_score = Some(report.score);
+        skill.security_level = Some(report.level.as_str().to_string());
+        skill.security_issues = Some(
+            report.issues.iter()
+                .map(|i| {
+                    let file_info = i.file_path.as_ref()
+                        .map(|f| format!("[{}] ", f))
+                        .unwrap_or_default();
+                    format!("{}{:?}: {}", file_info, i.severity, i.description)
+                })
+                .collect()
+        );
+        let scanned_at = Utc::now();
+        skill.scanned_at = Some(scanned_at);
+        self.save_scan_report(&skill.id, &report, scanned_at);
+
+        self.db.save_skill(&skill)?;
+        let kind = if updated { SkillEventKind::Updated } else { SkillEventKind::Installed };
+        self.record_event(&skill.id, kind, skill.local_path.as_deref(), None);
+        self.remember_install_target(&skill.id, install_path.as_deref());
+
+        log::info!("已从直接地址安装技能 {}: {}", skill.name, repository_url);
+        Ok(skill)
+    }
+
+    /// 检查直接地址来源的技能是否有更新：重新下载并比较内容哈希，有变化时返回新的哈希
+    pub async fn check_direct_url_update(&self, skill: &Skill) -> Result<Option<String>> {
+        let source = DirectSource::parse(&skill.repository_url)?;
+        let files = direct_url::download(&self.github, &source, &self.github.manifest_names()).await?;
+        let checksum = direct_url::content_checksum(&files);
+        Ok((skill.checksum.as_deref() != Some(checksum.as_str())).then_some(checksum))
     }
 
     /// 准备安装技能：扫描缓存中的技能，但不复制文件，不标记为已安装
     /// 返回扫描报告供前端判断是否需要用户确认
     pub async fn prepare_skill_installation(&self, skill_id: &str, locale: &str) -> Result<crate::models::security::SecurityReport> {
-        use anyhow::Context;
-
         log::info!("Preparing installation for skill: {}", skill_id);
 
         // 从数据库获取 skill
         let mut skill = self.db.get_skills()?
             .into_iter()
             .find(|s| s.id == skill_id)
-            .context("未找到该技能")?;
+            .ok_or_else(|| AppError::not_found("未找到该技能"))?;
+        ensure_repository_source(&skill)?;
 
         // 下载并分析 SKILL.md
         let (_skill_md_content, _report) = self.download_and_analyze(&mut skill).await?;
@@ -294,7 +563,7 @@ impl SkillManager {
         let repositories = self.db.get_repositories()?;
         let repo = repositories.iter()
             .find(|r| r.url == skill.repository_url)
-            .context("未找到对应的仓库记录")?
+            .ok_or_else(|| AppError::not_found("未找到对应的仓库记录"))?
             .clone();
 
         // 确保仓库缓存存在
@@ -314,6 +583,13 @@ impl SkillManager {
             self.download_and_cache_repository(&repo.id, &skill.repository_url).await?
         };
 
+        // 缓存可能刚刚下载，重新读取仓库记录以获得最新的签名验证结果
+        if repo.require_signed_commits {
+            let repo = self.db.get_repository(&repo.id)?.ok_or_else(|| AppError::not_found("未找到对应的仓库记录"))?;
+            let commit_sha = repo.cached_commit_sha.clone().unwrap_or_default();
+            self.ensure_signed_commit(&repo, &commit_sha, repo.commit_signature.as_ref())?;
+        }
+
         // 定位缓存中的技能目录
         log::info!("从仓库缓存定位技能: {:?}", cache_path);
         let skill_cache_dir = self.locate_skill_in_cache(
@@ -324,11 +600,7 @@ impl SkillManager {
         log::info!("在缓存中找到技能目录: {:?}", skill_cache_dir);
 
         // 直接扫描缓存中的技能目录
-        let scan_report = self.scanner.scan_directory(
-            skill_cache_dir.to_str().context("技能目录路径无效")?,
-            &skill.id,
-            locale
-        )?;
+        let scan_report = self.scan_directory_async(&skill_cache_dir, &skill.id, locale).await?;
 
         log::info!("Security scan completed: score={}, scanned {} files",
             scan_report.score, scan_report.scanned_files.len());
@@ -346,7 +618,9 @@ impl SkillManager {
                 })
                 .collect()
         );
-        skill.scanned_at = Some(Utc::now());
+        let scanned_at = Utc::now();
+        skill.scanned_at = Some(scanned_at);
+        self.save_scan_report(&skill.id, &scan_report, scanned_at);
         // 注意：这里暂时保存缓存路径，确认安装时会更新为实际安装路径
         skill.local_path = Some(skill_cache_dir.to_string_lossy().to_string());
 
@@ -367,10 +641,7 @@ impl SkillManager {
         let (owner, repo_name, branch) = crate::models::Repository::from_github_url(repo_url)?;
 
         // 获取缓存基础目录
-        let cache_base_dir = dirs::cache_dir()
-            .context("无法获取系统缓存目录")?
-            .join("agent-skills-guard")
-            .join("repositories");
+        let cache_base_dir = CachePaths::require()?.repositories();
 
         // 下载仓库压缩包并解压
         let (extract_dir, commit_sha) = self.github
@@ -390,6 +661,16 @@ impl SkillManager {
 
         log::info!("Repository cached successfully: {}", cache_path_str);
 
+        // 更新提交签名验证结果（失败不影响缓存）
+        match self.check_commit_signature(&owner, &repo_name, &commit_sha).await {
+            Ok(status) => {
+                if let Err(e) = self.db.update_repository_signature(repo_id, &status) {
+                    log::warn!("保存提交签名验证结果失败: {}", e);
+                }
+            }
+            Err(e) => log::warn!("验证提交签名失败: {}/{}@{}: {}", owner, repo_name, commit_sha, e),
+        }
+
         // 扫描并更新仓库中的技能
         if let Err(e) = self.scan_cached_repository(repo_id, &cache_path_str, repo_url) {
             log::error!("Failed to scan cached repository: {}", e);
@@ -398,6 +679,89 @@ impl SkillManager {
         Ok(cache_path_str)
     }
 
+    /// 验证仓库提交的签名并保存结果
+    /// 验证已缓存的提交；尚未缓存时验证默认分支（或 URL 中指定的分支）的最新提交
+    pub async fn verify_repository_signature(&self, repo_id: &str) -> Result<CommitSignatureStatus> {
+        let repo = self.db.get_repository(repo_id)?.ok_or_else(|| AppError::not_found("未找到对应的仓库记录"))?;
+        let (owner, repo_name, branch) = Repository::from_github_url(&repo.url)?;
+
+        let commit_sha = match &repo.cached_commit_sha {
+            Some(sha) => sha.clone(),
+            None => self.github
+                .resolve_commit_sha(&owner, &repo_name, branch.as_deref().unwrap_or("HEAD"))
+                .await?,
+        };
+
+        let status = self.check_commit_signature(&owner, &repo_name, &commit_sha).await?;
+        self.db.update_repository_signature(repo_id, &status)?;
+        log::info!(
+            "提交签名验证完成: {} @ {} signed={} verified={}",
+            repo.url, commit_sha, status.signed, status.verified
+        );
+
+        Ok(status)
+    }
+
+    /// 通过 GitHub 获取提交签名，并检查签名者是否在受信任密钥列表中
+    async fn check_commit_signature(&self, owner: &str, repo_name: &str, commit_sha: &str) -> Result<CommitSignatureStatus> {
+        let verification = self.github
+            .fetch_commit_verification(owner, repo_name, commit_sha)
+            .await?;
+
+        let signer_key_ids = match &verification.signature {
+            Some(armored) => signature::signature_key_ids(armored).unwrap_or_else(|e| {
+                log::warn!("解析提交签名失败: {}/{}@{}: {}", owner, repo_name, commit_sha, e);
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+        let trusted = signature::is_trusted_signer(&signer_key_ids, &self.scan_policy().approved_signing_keys);
+
+        Ok(CommitSignatureStatus {
+            commit_sha: commit_sha.to_string(),
+            signed: verification.verified,
+            reason: verification.reason,
+            verified: verification.verified && trusted,
+            signer_key_ids,
+            checked_at: Utc::now(),
+        })
+    }
+
+    /// 仓库要求已签名的提交时，确认 commit_sha 的签名有效且签名者受信任
+    /// 受信任密钥列表可能在验证之后被修改，因此按当前设置重新判断签名者
+    fn ensure_signed_commit(&self, repo: &Repository, commit_sha: &str, status: Option<&CommitSignatureStatus>) -> Result<()> {
+        if !repo.require_signed_commits {
+            return Ok(());
+        }
+
+        let short_sha = &commit_sha[..commit_sha.len().min(7)];
+        let status = match status.filter(|s| s.commit_sha == commit_sha) {
+            Some(status) => status,
+            None => {
+                return Err(AppError::new(
+                    ErrorKind::SignatureRejected,
+                    format!("仓库 {} 要求已签名的提交，提交 {} 尚未验证签名", repo.name, short_sha),
+                )
+                .into())
+            }
+        };
+
+        if !status.signed {
+            return Err(AppError::new(ErrorKind::SignatureRejected, format!(
+                "仓库 {} 要求已签名的提交，提交 {} 的签名无效（{}）",
+                repo.name, short_sha, status.reason
+            )).into());
+        }
+        if !signature::is_trusted_signer(&status.signer_key_ids, &self.scan_policy().approved_signing_keys) {
+            return Err(AppError::new(ErrorKind::SignatureRejected, format!(
+                "仓库 {} 要求已签名的提交，提交 {} 的签名者 {} 不在受信任密钥列表中",
+                repo.name, short_sha, status.signer_key_ids.join(", ")
+            )).into());
+        }
+
+        Ok(())
+    }
+
     /// 扫描缓存的仓库并更新技能列表
     fn scan_cached_repository(&self, repo_id: &str, cache_path: &str, repo_url: &str) -> Result<()> {
         log::info!("Scanning cached repository: {} ({})", repo_id, cache_path);
@@ -406,50 +770,23 @@ impl SkillManager {
         let repo_root = self.find_repo_root_in_cache(std::path::Path::new(cache_path))?;
         let (repo_owner, _, _) = crate::models::Repository::from_github_url(repo_url)?;
 
-        // 遍历仓库目录寻找 SKILL.md
-        // 这里简单遍历两层目录
-        let mut skill_files = Vec::new();
-
-        // 辅助函数：扫描目录
-        let scan_dir = |dir: &std::path::Path| -> Vec<PathBuf> {
-            let mut found = Vec::new();
-            if let Ok(entries) = std::fs::read_dir(dir) {
-                for entry in entries.flatten() {
-                    let path = entry.path();
-                    if path.is_dir() {
-                        let skill_md = path.join("SKILL.md");
-                        if skill_md.exists() {
-                            found.push(path);
-                        }
-                    }
-                }
-            }
-            found
+        // 仓库的路径配置（包含/排除模式与最大深度）
+        let repo = self.db.get_repository(repo_id)?;
+        let filter = match &repo {
+            Some(repo) => repo.discovery_filter()?,
+            None => ScanFilter::new(&[], &[], false)?,
         };
+        let max_depth = repo.as_ref().map_or(DEFAULT_DISCOVERY_DEPTH, |repo| repo.discovery_depth());
 
-        // 1. 扫描根目录的子目录
-        skill_files.extend(scan_dir(&repo_root));
-
-        // 2. 扫描二级目录 (例如 categories/skill-name)
-        if let Ok(entries) = std::fs::read_dir(&repo_root) {
-            for entry in entries.flatten() {
-                if entry.path().is_dir() {
-                    // 排除 .git 等隐藏目录
-                    if let Some(name) = entry.file_name().to_str() {
-                        if !name.starts_with('.') {
-                           skill_files.extend(scan_dir(&entry.path()));
-                        }
-                    }
-                }
-            }
-        }
+        // 遍历仓库目录寻找 SKILL.md（与 API 扫描使用相同的深度与忽略规则）
+        let skill_files = fs_ops::find_skill_manifests(&repo_root, &filter, max_depth, &self.github.manifest_names());
 
         log::info!("Found {} skills in repository", skill_files.len());
 
         let mut skills_to_save = Vec::new();
 
-        for skill_dir in skill_files {
-            let skill_md_path = skill_dir.join("SKILL.md");
+        for skill_md_path in skill_files {
+            let skill_dir = skill_md_path.parent().unwrap_or(&repo_root).to_path_buf();
             if let Ok(content) = std::fs::read_to_string(&skill_md_path) {
                  // 解析 frontmatter
                 let (name, description) = self.parse_frontmatter(&content).unwrap_or_else(|_| {
@@ -477,6 +814,7 @@ impl SkillManager {
                     repository_owner: Some(repo_owner.clone()),
                     file_path: relative_path,
                     installed: false, // 仓库扫描的技能默认未安装
+                    compatible_tools: Skill::parse_compatible_tools(&content),
                     ..Default::default()
                 };
 
@@ -508,6 +846,101 @@ impl SkillManager {
         Ok(())
     }
 
+    /// 扫描仓库缓存中的技能目录（不保存扫描报告与扫描缓存），用于与上次的扫描结果比较
+    pub fn scan_cached_skill(&self, cache_path: &std::path::Path, skill: &Skill, locale: &str) -> Result<crate::models::SecurityReport> {
+        let dir = self.locate_skill_in_cache(cache_path, &skill.file_path)?;
+        let dir = dir.to_str().context("技能目录路径无效")?;
+        let scan = self.scan_policy().scanner().scan_directory_incremental(dir, &skill.id, locale, None)?;
+        Ok(scan.report)
+    }
+
+    /// 记录技能历史事件（失败只记录警告，不影响安装、更新与卸载的结果）
+    fn record_event(&self, skill_id: &str, kind: SkillEventKind, path: Option<&str>, commit_sha: Option<&str>) {
+        let event = SkillEvent {
+            path: path.map(str::to_string),
+            commit_sha: commit_sha.map(str::to_string),
+            ..SkillEvent::new(skill_id, kind)
+        };
+        if let Err(e) = self.db.add_skill_event(&event) {
+            log::warn!("记录技能历史事件失败: {}: {}", skill_id, e);
+        }
+    }
+
+    /// 记录带说明的技能历史事件（失败只记录警告）
+    fn record_event_detail(&self, skill_id: &str, kind: SkillEventKind, path: Option<&str>, detail: &str) {
+        let event = SkillEvent {
+            path: path.map(str::to_string),
+            detail: Some(detail.to_string()),
+            ..SkillEvent::new(skill_id, kind)
+        };
+        if let Err(e) = self.db.add_skill_event(&event) {
+            log::warn!("记录技能历史事件失败: {}: {}", skill_id, e);
+        }
+    }
+
+    /// 用户确认安装/更新后删除技能的可疑更新记录（失败只记录警告）
+    fn clear_suspicious_update(&self, skill_id: &str) {
+        if let Err(e) = self.db.delete_suspicious_update(skill_id) {
+            log::warn!("删除可疑更新记录失败: {}: {}", skill_id, e);
+        }
+    }
+
+    /// 技能文件所在目录：优先使用已安装的副本，其次为仓库缓存中的目录（都不存在时为 None）
+    pub fn skill_files_dir(&self, skill: &Skill) -> Result<Option<PathBuf>> {
+        if let Some(dir) = skill.local_path.as_deref().map(PathBuf::from).filter(|dir| dir.is_dir()) {
+            return Ok(Some(dir));
+        }
+
+        Ok(self.db.get_repositories()?
+            .iter()
+            .find(|r| r.url == skill.repository_url)
+            .and_then(|r| r.cache_path.as_deref())
+            .and_then(|cache_path| self.locate_skill_in_cache(std::path::Path::new(cache_path), &skill.file_path).ok()))
+    }
+
+    /// 按技能清单中固定的哈希校验仓库缓存中的技能内容，一致时记录该哈希，安装时再次校验
+    pub fn pin_skill_content(&self, skill: &Skill, sha256: &str) -> Result<()> {
+        let cache_path = self.db.get_repositories()?
+            .into_iter()
+            .find(|r| r.url == skill.repository_url)
+            .and_then(|r| r.cache_path)
+            .ok_or_else(|| AppError::not_found(format!("仓库尚未缓存: {}", skill.repository_url)))?;
+        let dir = self.locate_skill_in_cache(std::path::Path::new(&cache_path), &skill.file_path)?;
+        ensure_content_hash(&skill.name, &dir, sha256, &self.github.manifest_names())?;
+
+        let mut pins = self.content_pins();
+        pins.insert(skill.id.clone(), sha256.to_ascii_lowercase());
+        self.db.set_setting(CONTENT_PINS_KEY, &serde_json::to_string(&pins)?)?;
+        Ok(())
+    }
+
+    /// 技能清单固定的内容哈希（技能 ID -> SHA256）
+    fn content_pins(&self) -> std::collections::HashMap<String, String> {
+        self.db.get_setting(CONTENT_PINS_KEY).ok().flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// 技能有固定的内容哈希时校验目录内容；校验通过并完成安装后调用 [`Self::unpin_skill_content`]
+    fn verify_pinned_content(&self, skill: &Skill, dir: &std::path::Path) -> Result<()> {
+        match self.content_pins().get(&skill.id) {
+            Some(sha256) => ensure_content_hash(&skill.name, dir, sha256, &self.github.manifest_names()),
+            None => Ok(()),
+        }
+    }
+
+    fn unpin_skill_content(&self, skill_id: &str) {
+        let mut pins = self.content_pins();
+        if pins.remove(skill_id).is_none() {
+            return;
+        }
+        let saved = serde_json::to_string(&pins).map_err(anyhow::Error::from)
+            .and_then(|json| self.db.set_setting(CONTENT_PINS_KEY, &json));
+        if let Err(e) = saved {
+            log::warn!("清除技能的固定内容哈希失败: {}, 错误: {}", skill_id, e);
+        }
+    }
+
     /// 在仓库缓存中定位技能目录
     fn locate_skill_in_cache(&self, cache_path: &std::path::Path, skill_file_path: &str) -> Result<PathBuf> {
         // 找到仓库根目录（cache_path 指向 extracted/ 目录）
@@ -544,33 +977,8 @@ impl SkillManager {
         anyhow::bail!("未找到仓库根目录")
     }
 
-    /// 递归复制目录
-    fn copy_dir_recursive(&self, src: &std::path::Path, dst: &std::path::Path, counter: &mut usize) -> Result<()> {
-        use anyhow::Context;
-
-        for entry in std::fs::read_dir(src).context(format!("无法读取源目录: {:?}", src))? {
-            let entry = entry?;
-            let src_path = entry.path();
-            let file_name = entry.file_name();
-            let dst_path = dst.join(&file_name);
-
-            if src_path.is_dir() {
-                std::fs::create_dir_all(&dst_path)
-                    .context(format!("无法创建目标目录: {:?}", dst_path))?;
-                self.copy_dir_recursive(&src_path, &dst_path, counter)?;
-            } else {
-                std::fs::copy(&src_path, &dst_path)
-                    .context(format!("无法复制文件: {:?} -> {:?}", src_path, dst_path))?;
-                *counter += 1;
-                log::debug!("Copied file: {:?}", file_name);
-            }
-        }
-
-        Ok(())
-    }
-
     /// 确认安装技能：从缓存复制到目标路径，标记为已安装
-    pub fn confirm_skill_installation(&self, skill_id: &str, install_path: Option<String>) -> Result<()> {
+    pub fn confirm_skill_installation(&self, skill_id: &str, install_path: Option<String>, confirmation: Option<&str>) -> Result<InstallResult> {
         use anyhow::Context;
         use std::path::PathBuf;
 
@@ -579,7 +987,11 @@ impl SkillManager {
         let mut skill = self.db.get_skills()?
             .into_iter()
             .find(|s| s.id == skill_id)
-            .context("未找到该技能")?;
+            .ok_or_else(|| AppError::not_found("未找到该技能"))?;
+
+        // 检查 prepare 阶段扫描得到的评分是否满足最低安装评分，高风险技能是否已输入确认
+        self.scan_policy().check_install_score(&skill.name, skill.security_score, confirmation)?;
+        self.check_risky_install(&skill.name, skill.security_level.as_deref(), confirmation)?;
 
         // 获取缓存中的技能路径（prepare阶段保存的）
         let cache_path = skill.local_path.as_ref()
@@ -592,25 +1004,41 @@ impl SkillManager {
             .find(|r| r.url == skill.repository_url);
         let commit_sha = repo.and_then(|r| r.cached_commit_sha.clone());
 
-        // 确定最终安装路径
-        let install_base_dir = if let Some(user_path) = install_path {
-            PathBuf::from(user_path)
-        } else {
-            self.skills_dir.clone()
-        };
+        // 确定最终安装路径（指定的路径、该技能记住的目录或默认路径）
+        let install_base_dir = self.install_base_dir(&skill.id, install_path.as_deref());
 
         // 获取技能目录名
         let skill_dir_name = cache_dir.file_name()
             .context("无效的技能目录名")?;
         let final_install_dir = install_base_dir.join(skill_dir_name);
 
+        if let Some(warning) = Self::compatibility_warning(&skill, &install_base_dir) {
+            log::warn!("{}", warning);
+        }
+
+        // 技能清单固定了内容哈希时校验缓存中的内容（导入清单后仓库缓存可能已刷新）
+        self.verify_pinned_content(&skill, &cache_dir)?;
+
         // 确保目标基础目录存在
         std::fs::create_dir_all(&install_base_dir)
             .context("无法创建目标目录")?;
 
+        // 开始写入前检查目标目录（只读属性、云同步占位文件），避免复制到一半失败
+        dir_access::check_dir(&install_base_dir, false).ensure_ready()?;
+        if final_install_dir.exists() {
+            dir_access::check_dir(&final_install_dir, true).ensure_ready()?;
+        }
+
+        // 记录操作日志：应用在写入中途退出时，下次启动会清理写入一半的安装目录
+        let install_path_str = final_install_dir.to_string_lossy().to_string();
+        let mut journal = OperationJournal::begin(&self.db, PendingOperation {
+            commit_sha: commit_sha.clone(),
+            ..PendingOperation::new(skill_id, OperationKind::Install, &install_path_str)
+        })?;
+
         // 如果目标目录已存在，先删除
         if final_install_dir.exists() {
-            std::fs::remove_dir_all(&final_install_dir)
+            fs_ops::remove_dir_all(&final_install_dir)
                 .context("无法删除已存在的目标目录")?;
         }
 
@@ -620,45 +1048,120 @@ impl SkillManager {
 
         // 从缓存复制到目标路径
         log::info!("Copying skill from cache {:?} to {:?}", cache_dir, final_install_dir);
-        let mut files_copied = 0;
-        self.copy_dir_recursive(&cache_dir, &final_install_dir, &mut files_copied)?;
+        let copied = fs_ops::copy_dir(&cache_dir, &final_install_dir, &self.fs_context())
+            .and_then(CopyReport::ensure_complete)
+            .and_then(|files_copied| {
+                self.verify_installed_files(&skill.name, &cache_dir, &final_install_dir)?;
+                fs_ops::normalize_skill_manifest(&final_install_dir, &self.github.manifest_names())?;
+                Ok(files_copied)
+            });
+        let files_copied = match copied {
+            Ok(files_copied) => files_copied,
+            Err(e) => {
+                // 失败、被取消或校验不通过时清理已复制的部分
+                if let Err(clean_err) = fs_ops::remove_dir_all(&final_install_dir) {
+                    log::warn!("清理未完成的安装目录失败: {:?}, 错误: {}", final_install_dir, clean_err);
+                }
+                return Err(e);
+            }
+        };
 
         log::info!("Copied {} files from cache to install directory", files_copied);
+        journal.advance(OperationStep::Copied)?;
 
-        // 更新安装路径
-        let install_path_str = final_install_dir.to_string_lossy().to_string();
+        mark_installed(&mut skill, &install_path_str, commit_sha);
+        self.db.save_skill(&skill)?;
+        self.record_event(&skill.id, SkillEventKind::Installed, Some(&install_path_str), skill.installed_commit_sha.as_deref());
+        self.clear_suspicious_update(&skill.id);
+        self.unpin_skill_content(&skill.id);
+        self.remember_install_target(&skill.id, install_path.as_deref());
 
-        // 更新 local_path（向后兼容）
-        skill.local_path = Some(install_path_str.clone());
+        log::info!("Skill installation confirmed: {}", skill.name);
+        Ok(InstallResult::installed(&skill.id, install_path_str, files_copied, self.stored_scan_summary(&skill.id)))
+    }
 
-        // 更新 local_paths 数组（支持多路径安装）
-        let mut paths = skill.local_paths.clone().unwrap_or_default();
-        if !paths.contains(&install_path_str) {
-            paths.push(install_path_str);
+    /// 恢复上次运行中断的安装/更新操作（启动时调用，调用方需持有写操作锁）
+    ///
+    /// 文件已完整写入并通过校验的操作补写数据库；其余操作回滚：删除写入一半的安装目录，
+    /// 或从备份恢复原安装目录。返回处理的操作数
+    pub fn recover_pending_operations(&self) -> Result<usize> {
+        let operations = self.db.get_pending_operations()?;
+
+        for op in &operations {
+            log::warn!(
+                "发现未完成的操作: kind={:?}, skill={}, step={:?}, target={}, started_at={}",
+                op.kind, op.skill_id, op.step, op.target_dir, op.started_at
+            );
+            if let Err(e) = self.recover_operation(op) {
+                log::error!("恢复未完成的操作失败: {}, 错误: {:#}", op.id, e);
+            }
+            self.db.delete_pending_operation(&op.id)?;
         }
-        skill.local_paths = Some(paths);
 
-        // 标记为已安装
-        skill.installed = true;
-        skill.installed_at = Some(Utc::now());
-        skill.installed_commit_sha = commit_sha;
+        Ok(operations.len())
+    }
 
-        self.db.save_skill(&skill)?;
+    fn recover_operation(&self, op: &PendingOperation) -> Result<()> {
+        let target_dir = PathBuf::from(&op.target_dir);
+        let backup_dir = op.backup_dir.as_ref().map(PathBuf::from).filter(|p| p.exists());
+
+        match (op.kind, op.step) {
+            (_, OperationStep::Copied) => {
+                // 文件已完整写入，只差更新数据库（更新时仓库缓存基线的同步不再补做）
+                let mut skill = self.db.get_skills()?
+                    .into_iter()
+                    .find(|s| s.id == op.skill_id)
+                    .ok_or_else(|| AppError::not_found("未找到该技能"))?;
+                let commit_sha = op.commit_sha.clone().or_else(|| skill.installed_commit_sha.clone());
+                mark_installed(&mut skill, &op.target_dir, commit_sha);
+                self.db.save_skill(&skill)?;
+                let kind = match op.kind {
+                    OperationKind::Install => SkillEventKind::Installed,
+                    OperationKind::Update => SkillEventKind::Updated,
+                };
+                self.record_event(&skill.id, kind, Some(&op.target_dir), skill.installed_commit_sha.as_deref());
+                log::info!("已完成中断的操作: {} -> {:?}", skill.name, target_dir);
+            }
+            (OperationKind::Install, _) => {
+                // 删除写入一半的目录，技能保持“已准备”状态，可重新确认安装
+                if target_dir.exists() {
+                    fs_ops::remove_dir_all(&target_dir)
+                        .context(format!("无法删除未完成的安装目录: {:?}", target_dir))?;
+                }
+                log::info!("已回滚中断的安装: {:?}", target_dir);
+            }
+            (OperationKind::Update, step) => match backup_dir {
+                // 备份已完成，或原目录已被移动到备份位置（移动是原子的）
+                Some(backup_dir) if step == OperationStep::BackedUp || !target_dir.exists() => {
+                    if target_dir.exists() {
+                        fs_ops::remove_dir_all(&target_dir)
+                            .context(format!("无法删除未完成的安装目录: {:?}", target_dir))?;
+                    }
+                    if let Err(rename_err) = std::fs::rename(&backup_dir, &target_dir) {
+                        log::warn!("无法移回备份目录，将改用复制: {}", rename_err);
+                        fs_ops::copy_dir(&backup_dir, &target_dir, &FsContext::default())
+                            .and_then(CopyReport::ensure_complete)?;
+                    }
+                    log::info!("已从备份恢复中断的更新: {:?}", target_dir);
+                }
+                _ if step == OperationStep::Started => {
+                    log::info!("中断的更新尚未修改安装目录: {:?}", target_dir);
+                }
+                _ => log::warn!("中断的更新没有可用的备份，安装目录保持现状: {:?}", target_dir),
+            },
+        }
 
-        log::info!("Skill installation confirmed: {}", skill.name);
         Ok(())
     }
 
     /// 取消安装技能：清除准备阶段的数据（不删除缓存）
     pub fn cancel_skill_installation(&self, skill_id: &str) -> Result<()> {
-        use anyhow::Context;
-
         log::info!("Canceling installation for skill: {}", skill_id);
 
         let skill = self.db.get_skills()?
             .into_iter()
             .find(|s| s.id == skill_id)
-            .context("未找到该技能")?;
+            .ok_or_else(|| AppError::not_found("未找到该技能"))?;
 
         // 注意：不删除缓存中的文件，因为缓存是共享的仓库缓存
         // 只清除数据库中的准备阶段信息
@@ -677,13 +1180,45 @@ impl SkillManager {
         Ok(())
     }
 
+    /// 分析卸载技能（`path` 为 None 时卸载全部安装路径）的影响：依赖它的技能、其他工具目录中的符号链接、引用它的技能清单
+    pub fn uninstall_impact(&self, skill_id: &str, path: Option<&str>) -> Result<UninstallImpact> {
+        let skills = self.db.get_skills()?;
+        let skill = skills.iter()
+            .find(|s| s.id == skill_id)
+            .ok_or_else(|| AppError::not_found("未找到该技能"))?;
+
+        let installed = installed_dirs(skill);
+        let (removed, fully_removed) = match path {
+            Some(path) => {
+                let path = PathBuf::from(path);
+                let fully_removed = installed.iter().all(|dir| *dir == path);
+                (vec![path], fully_removed)
+            }
+            None => (installed, true),
+        };
+
+        Ok(impact::analyze_uninstall(
+            skill,
+            &removed,
+            fully_removed,
+            &skills,
+            &get_all_supported_tools(),
+            &self.github.manifest_names(),
+        ))
+    }
+
     /// 卸载 skill
     pub fn uninstall_skill(&self, skill_id: &str) -> Result<()> {
         // 从数据库获取 skill
         let mut skill = self.db.get_skills()?
             .into_iter()
             .find(|s| s.id == skill_id)
-            .context("未找到该技能")?;
+            .ok_or_else(|| AppError::not_found("未找到该技能"))?;
+
+        // 删除前检查各安装目录，避免只删除了一部分
+        for dir in installed_dirs(&skill) {
+            dir_access::check_dir(&dir, true).ensure_ready()?;
+        }
 
         // 删除所有安装路径的文件
         if let Some(local_paths) = &skill.local_paths {
@@ -692,7 +1227,7 @@ impl SkillManager {
                 if path.exists() {
                     // 如果是目录，删除整个目录
                     if path.is_dir() {
-                        if let Err(e) = std::fs::remove_dir_all(&path) {
+                        if let Err(e) = fs_ops::remove_dir_all(&path) {
                             log::warn!("删除技能目录失败: {:?}, 错误: {}", path, e);
                         }
                     } else {
@@ -710,7 +1245,7 @@ impl SkillManager {
                 let path = PathBuf::from(local_path);
                 if path.exists() {
                     if path.is_dir() {
-                        std::fs::remove_dir_all(&path)
+                        fs_ops::remove_dir_all(&path)
                             .context("无法删除技能目录，请检查文件是否被占用")?;
                     } else {
                         std::fs::remove_file(&path)
@@ -720,6 +1255,13 @@ impl SkillManager {
             }
         }
 
+        // 清除各安装路径的增量扫描缓存
+        for local_path in skill.local_paths.iter().flatten().chain(skill.local_path.iter()) {
+            if let Err(e) = self.db.delete_scan_cache(local_path) {
+                log::warn!("删除扫描缓存失败: {}, 错误: {}", local_path, e);
+            }
+        }
+
         // 更新数据库
         skill.installed = false;
         skill.installed_at = None;
@@ -728,6 +1270,7 @@ impl SkillManager {
 
         self.db.save_skill(&skill)
             .context("更新数据库失败")?;
+        self.record_event(&skill.id, SkillEventKind::Uninstalled, None, None);
 
         log::info!("Skill uninstalled successfully: {}", skill.name);
         Ok(())
@@ -739,13 +1282,20 @@ impl SkillManager {
         let mut skill = self.db.get_skills()?
             .into_iter()
             .find(|s| s.id == skill_id)
-            .context("未找到该技能")?;
+            .ok_or_else(|| AppError::not_found("未找到该技能"))?;
 
-        // 删除指定路径的文件
+        // 只能删除技能记录的安装路径
+        let recorded = skill.local_paths.iter().flatten().chain(skill.local_path.iter());
+        if !recorded.into_iter().any(|p| p == path_to_remove) {
+            return Err(AppError::new(ErrorKind::PathNotAllowed, format!("该路径不是技能的安装路径: {}", path_to_remove)).into());
+        }
+
+        // 删除指定路径的文件
         let path = PathBuf::from(path_to_remove);
         if path.exists() {
             if path.is_dir() {
-                std::fs::remove_dir_all(&path)
+                dir_access::check_dir(&path, true).ensure_ready()?;
+                fs_ops::remove_dir_all(&path)
                     .context("无法删除技能目录，请检查文件是否被占用")?;
             } else {
                 std::fs::remove_file(&path)
@@ -753,6 +1303,10 @@ impl SkillManager {
             }
         }
 
+        if let Err(e) = self.db.delete_scan_cache(path_to_remove) {
+            log::warn!("删除扫描缓存失败: {}, 错误: {}", path_to_remove, e);
+        }
+
         // 从 local_paths 中移除该路径
         if let Some(mut paths) = skill.local_paths.clone() {
             paths.retain(|p| p != path_to_remove);
@@ -772,73 +1326,579 @@ impl SkillManager {
 
         self.db.save_skill(&skill)
             .context("更新数据库失败")?;
+        self.record_event(&skill.id, SkillEventKind::Uninstalled, Some(path_to_remove), None);
 
         log::info!("Skill path uninstalled: {} from {}", skill.name, path_to_remove);
         Ok(())
     }
 
+    /// 重命名技能的安装目录（部分工具会把目录名提供给模型），同时更新安装路径记录与指向它的符号链接。
+    /// 技能 ID 不变；任一目标目录已存在时不做任何修改。调用方需持有写操作锁
+    pub fn rename_installed_skill(&self, skill_id: &str, new_name: &str) -> Result<Skill> {
+        let new_name = rename::validate_dir_name(new_name)
+            .map_err(|e| AppError::invalid_input(e.to_string()))?;
+        let mut skill = self.db.get_skills()?
+            .into_iter()
+            .find(|s| s.id == skill_id)
+            .ok_or_else(|| AppError::not_found("未找到该技能"))?;
+
+        let dirs = installed_dirs(&skill);
+        if dirs.is_empty() {
+            return Err(AppError::not_found("该技能没有可重命名的安装目录").into());
+        }
+        let renamed = rename::rename_dirs(&dirs, &new_name, &get_all_supported_tools())?;
+
+        for rename in &renamed {
+            let old_path = rename.old_path.to_string_lossy().to_string();
+            let new_path = rename.new_path.to_string_lossy().to_string();
+            if let Some(paths) = skill.local_paths.as_mut() {
+                for path in paths.iter_mut().filter(|path| **path == old_path) {
+                    *path = new_path.clone();
+                }
+            }
+            if skill.local_path.as_deref() == Some(old_path.as_str()) {
+                skill.local_path = Some(new_path.clone());
+            }
+            if let Err(e) = self.db.delete_scan_cache(&old_path) {
+                log::warn!("删除扫描缓存失败: {}, 错误: {}", old_path, e);
+            }
+            log::info!("技能 {} 的安装目录已重命名: {} -> {}", skill.name, old_path, new_path);
+        }
+
+        self.db.save_skill(&skill).context("更新数据库失败")?;
+        Ok(skill)
+    }
+
+    /// 禁用已安装的技能：将各安装目录中的技能清单重命名为 <清单名>.disabled，使其不再被加载。
+    /// 返回禁用的目录数（清单已不存在的目录跳过）。调用方需持有写操作锁
+    pub fn disable_skill(&self, skill_id: &str, detail: &str) -> Result<usize> {
+        let skill = self.db.get_skills()?
+            .into_iter()
+            .find(|s| s.id == skill_id)
+            .ok_or_else(|| AppError::not_found("未找到该技能"))?;
+
+        let mut disabled = 0;
+        for dir in installed_dirs(&skill) {
+            let Some(manifest) = fs_ops::find_skill_manifest(&dir, &self.github.manifest_names()) else {
+                continue;
+            };
+            let mut target = manifest.clone().into_os_string();
+            target.push(".disabled");
+            std::fs::rename(&manifest, &target)
+                .with_context(|| format!("无法禁用技能清单: {:?}", manifest))?;
+
+            disabled += 1;
+            self.record_event_detail(skill_id, SkillEventKind::Disabled, Some(&dir.to_string_lossy()), detail);
+        }
+
+        log::warn!("已禁用技能 {} 的 {} 个安装目录", skill.name, disabled);
+        Ok(disabled)
+    }
+
+    /// 隔离已安装的技能：将各安装目录移动到缓存目录下的 quarantine/<技能目录名>-<时间戳>，
+    /// 并将技能标记为未安装。返回隔离目录。调用方需持有写操作锁
+    pub fn quarantine_skill(&self, skill_id: &str, detail: &str) -> Result<PathBuf> {
+        let quarantine_root = CachePaths::require()?.quarantine();
+        self.quarantine_skill_into(skill_id, detail, &quarantine_root, |from, to| std::fs::rename(from, to))
+    }
+
+    /// 将技能的各安装目录隔离到 quarantine_root 下；rename 失败（如跨文件系统）时复制后删除原目录
+    fn quarantine_skill_into(
+        &self,
+        skill_id: &str,
+        detail: &str,
+        quarantine_root: &Path,
+        rename: impl Fn(&Path, &Path) -> std::io::Result<()>,
+    ) -> Result<PathBuf> {
+        let mut skill = self.db.get_skills()?
+            .into_iter()
+            .find(|s| s.id == skill_id)
+            .ok_or_else(|| AppError::not_found("未找到该技能"))?;
+
+        let dirs = installed_dirs(&skill);
+        let dir_name = dirs.first()
+            .and_then(|dir| dir.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| skill.id.clone());
+        let quarantine_dir = quarantine_root.join(format!("{}-{}", dir_name, Utc::now().format("%Y%m%d%H%M%S")));
+        std::fs::create_dir_all(&quarantine_dir).context("无法创建隔离目录")?;
+
+        for (index, dir) in dirs.iter().enumerate() {
+            let target = quarantine_dir.join(index.to_string());
+            // 跨文件系统时无法直接重命名，复制后删除原目录
+            if rename(dir, &target).is_err() {
+                fs_ops::copy_dir(dir, &target, &self.fs_context())?;
+                fs_ops::remove_dir_all(dir)
+                    .with_context(|| format!("无法删除已隔离的技能目录: {:?}", dir))?;
+            }
+            if let Err(e) = self.db.delete_scan_cache(&dir.to_string_lossy()) {
+                log::warn!("删除扫描缓存失败: {:?}, 错误: {}", dir, e);
+            }
+            self.record_event_detail(skill_id, SkillEventKind::Quarantined, Some(&dir.to_string_lossy()), detail);
+        }
+
+        skill.installed = false;
+        skill.installed_at = None;
+        skill.local_path = None;
+        skill.local_paths = None;
+        self.db.save_skill(&skill).context("更新数据库失败")?;
+
+        log::warn!("已隔离技能 {}: {:?}", skill.name, quarantine_dir);
+        Ok(quarantine_dir)
+    }
+
+    /// 保存完整扫描报告（失败不影响安装/导入流程，只记录警告）
+    fn save_scan_report(&self, skill_id: &str, report: &crate::models::SecurityReport, scanned_at: chrono::DateTime<Utc>) {
+        if let Err(e) = self.db.save_scan_report(skill_id, report, scanned_at) {
+            log::warn!("保存扫描报告失败: {}, 错误: {}", skill_id, e);
+        }
+    }
+
+    /// 高风险技能（High / Critical）的输入确认，所有安装与重新安装路径共用（见 [`ScanPolicy::check_risky_install`]）
+    fn check_risky_install(&self, skill_name: &str, security_level: Option<&str>, confirmation: Option<&str>) -> Result<()> {
+        self.scan_policy().check_risky_install(skill_name, security_level, confirmation, || {
+            CredentialStore::get_or_warn(INSTALL_PASSPHRASE_ACCOUNT)
+        })
+    }
+
+    /// 保存的扫描报告摘要（没有或读取失败时为 None）
+    fn stored_scan_summary(&self, skill_id: &str) -> Option<ScanSummary> {
+        match self.db.get_scan_reports() {
+            Ok(mut reports) => reports.remove(skill_id).map(|(report, _)| ScanSummary::from(&report)),
+            Err(e) => {
+                log::warn!("读取扫描报告失败: {}, 错误: {}", skill_id, e);
+                None
+            }
+        }
+    }
+
     /// 获取所有 skills
     pub fn get_all_skills(&self) -> Result<Vec<Skill>> {
         self.db.get_skills()
     }
 
+    /// 检查技能与安装目标的兼容性（未指定路径时使用默认安装目录）
+    pub fn check_skill_compatibility(&self, skill_id: &str, install_path: Option<String>) -> Result<Option<String>> {
+        let skill = self.db.get_skills()?
+            .into_iter()
+            .find(|s| s.id == skill_id)
+            .ok_or_else(|| AppError::not_found("未找到该技能"))?;
+        let install_dir = self.install_base_dir(&skill.id, install_path.as_deref());
+
+        Ok(Self::compatibility_warning(&skill, &install_dir))
+    }
+
     /// 获取已安装的 skills
     pub fn get_installed_skills(&self) -> Result<Vec<Skill>> {
         let skills = self.db.get_skills()?;
         Ok(skills.into_iter().filter(|s| s.installed).collect())
     }
 
+    /// 检查已安装技能的完整性：安装目录存在、SKILL.md 可读且 frontmatter 有效、没有空文件
+    /// 只返回存在问题的安装路径，并附带建议的修复操作
+    pub fn check_skills_health(&self) -> Result<Vec<SkillHealthReport>> {
+        let repositories = self.db.get_repositories()?;
+        let mut reports = Vec::new();
+
+        for skill in self.get_installed_skills()? {
+            let mut paths = skill.local_paths.clone().unwrap_or_default();
+            if paths.is_empty() {
+                paths.extend(skill.local_path.clone());
+            }
+
+            for local_path in paths {
+                let problems = self.check_installation(std::path::Path::new(&local_path));
+                if problems.is_empty() {
+                    continue;
+                }
+                log::warn!("技能 {} 安装不完整: {:?}, 问题: {:?}", skill.name, local_path, problems);
+
+                // 仓库缓存中仍有该技能时可直接重新安装，否则只能删除记录
+                let cached = repositories
+                    .iter()
+                    .find(|r| r.url == skill.repository_url)
+                    .and_then(|r| r.cache_path.as_deref())
+                    .is_some_and(|cache_path| {
+                        self.locate_skill_in_cache(std::path::Path::new(cache_path), &skill.file_path).is_ok()
+                    });
+                let mut suggested_actions = Vec::new();
+                if cached {
+                    suggested_actions.push(SkillRepairAction::ReinstallFromCache);
+                }
+                suggested_actions.push(SkillRepairAction::RemoveRecord);
+
+                reports.push(SkillHealthReport {
+                    skill_id: skill.id.clone(),
+                    skill_name: skill.name.clone(),
+                    local_path,
+                    problems,
+                    suggested_actions,
+                });
+            }
+        }
+
+        log::info!("技能健康检查完成，发现 {} 个异常安装", reports.len());
+        Ok(reports)
+    }
+
+    /// 重定位失效的安装路径，返回受影响的路径及新位置
+    ///
+    /// 给出 `old_base` 与 `new_base` 时，将位于 `old_base` 下的路径改写到 `new_base` 下；
+    /// 都不给出时，对不存在的安装目录在各工具的技能目录中查找同名技能目录。
+    /// 只有新路径存在时才改写 local_path/local_paths，调用方需持有写操作锁
+    pub fn relocate_skills(&self, old_base: Option<&str>, new_base: Option<&str>) -> Result<Vec<SkillRelocation>> {
+        let bases = match (old_base, new_base) {
+            (Some(old), Some(new)) => Some((PathBuf::from(old), PathBuf::from(new))),
+            (None, None) => None,
+            _ => return Err(AppError::invalid_input("需要同时提供原目录和新目录").into()),
+        };
+        let mut search_dirs: Vec<PathBuf> = get_all_supported_tools().iter().map(|tool| tool.skills_path()).collect();
+        search_dirs.push(self.skills_dir());
+        let manifest_names = self.github.manifest_names();
+        let has_manifest = |dir: &std::path::Path| fs_ops::find_skill_manifest(dir, &manifest_names).is_some();
+
+        let mut relocations = Vec::new();
+        for mut skill in self.get_installed_skills()? {
+            let mut paths = skill.local_paths.clone().unwrap_or_default();
+            if paths.is_empty() {
+                paths.extend(skill.local_path.clone());
+            }
+
+            let mut changed = false;
+            for old_path in paths.clone() {
+                if old_path.starts_with("__staging__:") {
+                    continue;
+                }
+                let path = PathBuf::from(&old_path);
+                let new_path = match &bases {
+                    Some((old_base, new_base)) => match relocate::rebase_path(&path, old_base, new_base) {
+                        Some(new_path) => Some(new_path),
+                        None => continue,
+                    },
+                    None if path.is_dir() => continue,
+                    None => relocate::find_moved_dir(&path, &search_dirs, has_manifest),
+                };
+                let exists = new_path.as_ref().is_some_and(|new_path| new_path.is_dir());
+                let new_path = new_path.map(|new_path| new_path.to_string_lossy().to_string());
+
+                if let Some(new_path) = new_path.as_ref().filter(|_| exists) {
+                    // 新路径已在记录中时只删除旧路径
+                    if paths.contains(new_path) {
+                        paths.retain(|p| *p != old_path);
+                    } else if let Some(entry) = paths.iter_mut().find(|p| **p == old_path) {
+                        *entry = new_path.clone();
+                    }
+                    if skill.local_path.as_deref() == Some(old_path.as_str()) {
+                        skill.local_path = Some(new_path.clone());
+                    }
+                    if let Err(e) = self.db.delete_scan_cache(&old_path) {
+                        log::warn!("删除扫描缓存失败: {}, 错误: {}", old_path, e);
+                    }
+                    changed = true;
+                    log::info!("技能 {} 的安装路径已重定位: {} -> {}", skill.name, old_path, new_path);
+                } else {
+                    log::warn!("技能 {} 的安装路径 {} 未找到新位置: {:?}", skill.name, old_path, new_path);
+                }
+
+                relocations.push(SkillRelocation {
+                    skill_id: skill.id.clone(),
+                    skill_name: skill.name.clone(),
+                    old_path,
+                    new_path,
+                    exists,
+                });
+            }
+
+            if changed {
+                skill.local_paths = Some(paths);
+                self.db.save_skill(&skill).context("更新数据库失败")?;
+            }
+        }
+
+        Ok(relocations)
+    }
+
+    /// 检查单个安装目录
+    /// 检查技能目录能否写入：指定技能时检查其各安装目录（含其中的只读文件与云同步占位文件），
+    /// 否则检查默认安装目录与各工具的技能目录本身
+    pub fn check_skill_dirs(&self, skill_id: Option<&str>) -> Result<Vec<DirAccessReport>> {
+        let Some(skill_id) = skill_id else {
+            let mut dirs = vec![self.skills_dir()];
+            for dir in get_all_supported_tools().iter().map(|tool| tool.skills_path()) {
+                if dir.is_dir() && !dirs.contains(&dir) {
+                    dirs.push(dir);
+                }
+            }
+            return Ok(dirs.iter().map(|dir| dir_access::check_dir(dir, false)).collect());
+        };
+
+        let skill = self.db.get_skills()?
+            .into_iter()
+            .find(|s| s.id == skill_id)
+            .ok_or_else(|| AppError::not_found("未找到该技能"))?;
+        Ok(installed_dirs(&skill).iter().map(|dir| dir_access::check_dir(dir, true)).collect())
+    }
+
+    /// 计算技能各安装目录的内容校验和并与预期比较：expected 为调用方提供的 SHA256（如发布的哈希），
+    /// 未提供时使用导入技能清单时固定的内容哈希
+    pub fn verify_skill_checksum(&self, skill_id: &str, expected: Option<&str>) -> Result<SkillChecksumVerification> {
+        let skill = self.db.get_skills()?
+            .into_iter()
+            .find(|s| s.id == skill_id)
+            .ok_or_else(|| AppError::not_found("未找到该技能"))?;
+
+        let expected = match expected.map(str::trim).filter(|checksum| !checksum.is_empty()) {
+            Some(checksum) => Some((normalize_checksum(checksum)?, ChecksumSource::Provided)),
+            None => self.content_pins().remove(&skill.id)
+                .map(|checksum| (checksum.to_ascii_lowercase(), ChecksumSource::Pinned)),
+        };
+
+        let mut paths = Vec::new();
+        for dir in installed_dirs(&skill) {
+            let checksum = self.scanner.calculate_path_checksum(&dir)?;
+            let expected = expected.as_ref().map(|(checksum, _)| checksum.as_str());
+            paths.push(PathChecksum::new(dir.to_string_lossy().to_string(), true, checksum, expected));
+        }
+
+        let verification = SkillChecksumVerification::new(&skill.id, expected, paths);
+        log::info!("技能内容校验: {}, 结果: {:?}", skill.name, verification.status);
+        Ok(verification)
+    }
+
+    /// 去除技能目录中的只读属性或下载云同步占位文件，返回修复后的检查结果。
+    /// 只允许修复技能的安装目录、默认安装目录与各工具的技能目录；调用方需持有写操作锁
+    pub fn fix_skill_dir(&self, path: &str, options: DirFixOptions) -> Result<DirAccessReport> {
+        let dir = PathBuf::from(path);
+        let mut allowed = vec![self.skills_dir()];
+        allowed.extend(get_all_supported_tools().iter().map(|tool| tool.skills_path()));
+        for skill in self.get_installed_skills()? {
+            allowed.extend(installed_dirs(&skill));
+        }
+        if !allowed.contains(&dir) {
+            return Err(AppError::invalid_input(format!("只能修复技能目录: {}", path)).into());
+        }
+        dir_access::fix_dir(&dir, options)
+    }
+
+    fn check_installation(&self, dir: &std::path::Path) -> Vec<SkillHealthProblem> {
+        if !dir.is_dir() {
+            return vec![SkillHealthProblem::MissingDirectory];
+        }
+
+        let mut problems = Vec::new();
+        if let Some(skill_md) = fs_ops::find_skill_manifest(dir, &self.github.manifest_names()) {
+            match std::fs::read_to_string(&skill_md) {
+                Ok(content) => {
+                    if let Err(e) = self.parse_frontmatter(&content) {
+                        problems.push(SkillHealthProblem::InvalidFrontmatter { error: e.to_string() });
+                    }
+                }
+                Err(e) => problems.push(SkillHealthProblem::UnreadableSkillFile { error: e.to_string() }),
+            }
+        } else {
+            problems.push(SkillHealthProblem::MissingSkillFile);
+        }
+
+        // 依赖/构建产物目录中的空文件通常是正常的，与扫描一样跳过
+        let filter = ScanFilter::default();
+        let empty_files: Vec<String> = walkdir::WalkDir::new(dir)
+            .follow_links(false)
+            .into_iter()
+            .filter_entry(|entry| entry.depth() == 0 || !entry.file_type().is_dir() || !filter.is_excluded(entry.path().strip_prefix(dir).unwrap_or(entry.path())))
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file() && entry.metadata().is_ok_and(|m| m.len() == 0))
+            .map(|entry| entry.path().strip_prefix(dir).unwrap_or(entry.path()).to_string_lossy().to_string())
+            .collect();
+        if !empty_files.is_empty() {
+            problems.push(SkillHealthProblem::EmptyFiles { files: empty_files });
+        }
+
+        problems
+    }
+
+    /// 获取技能的说明文档（SKILL.md 及其引用的本地 Markdown 文件），用于安装前预览
+    /// 优先读取本地目录（本地技能或仓库缓存），否则从 GitHub raw 地址下载；
+    /// 每个文件都经过提示注入检测，返回可疑片段的位置
+    pub async fn get_skill_readme(&self, skill_id: &str, locale: &str) -> Result<SkillReadme> {
+        let skill = self.db.get_skills()?
+            .into_iter()
+            .find(|s| s.id == skill_id)
+            .ok_or_else(|| AppError::not_found("未找到该技能"))?;
+
+        let local_dir = if skill.source_type.is_local() {
+            skill.local_path.as_ref().map(PathBuf::from)
+        } else {
+            self.db.get_repositories()?
+                .iter()
+                .find(|r| r.url == skill.repository_url)
+                .and_then(|r| r.cache_path.as_deref())
+                .and_then(|cache_path| self.locate_skill_in_cache(std::path::Path::new(cache_path), &skill.file_path).ok())
+        };
+
+        let (source, raw_files) = match local_dir {
+            Some(dir) => {
+                log::info!("从本地目录读取技能说明文档: {:?}", dir);
+                let manifest_names = self.github.manifest_names();
+                let files = fs_ops::run_blocking(move || Ok(read_readme_files(&dir, &manifest_names))).await?;
+                if files.is_empty() {
+                    return Err(AppError::not_found("技能目录中没有可读取的 SKILL.md").into());
+                }
+                (SkillReadmeSource::Local, files)
+            }
+            None => {
+                let (owner, repo, branch) = Repository::from_github_url(&skill.repository_url)?;
+                let remote_path = |name: &str| match skill.file_path.as_str() {
+                    "." | "" => name.to_string(),
+                    dir => format!("{}/{}", dir.trim_end_matches('/'), name),
+                };
+
+                let (manifest_name, skill_md) = self.github
+                    .fetch_skill_manifest(&owner, &repo, branch.as_deref(), &skill.file_path)
+                    .await?;
+                let mut files = vec![(manifest_name, skill_md)];
+                for path in referenced_markdown_files(&files[0].1) {
+                    match self.github.fetch_raw_text(&owner, &repo, branch.as_deref(), &remote_path(&path)).await {
+                        Ok(content) if content.len() as u64 <= MAX_README_FILE_BYTES => files.push((path, content)),
+                        Ok(_) => log::warn!("引用的文档过大，已跳过: {}", path),
+                        Err(e) => log::warn!("下载引用的文档失败: {}, 错误: {}", path, e),
+                    }
+                }
+                (SkillReadmeSource::Remote, files)
+            }
+        };
+
+        let files = raw_files
+            .into_iter()
+            .map(|(path, content)| SkillReadmeFile {
+                findings: detect_prompt_injection(&content, locale),
+                path,
+                content,
+            })
+            .collect();
+
+        Ok(SkillReadme {
+            skill_id: skill.id,
+            source,
+            files,
+        })
+    }
+
     /// 扫描本地 ~/.claude/skills/ 目录，导入未追踪的技能
-    pub fn scan_local_skills(&self) -> Result<Vec<Skill>> {
-        use std::collections::HashSet;
+    pub fn scan_local_skills(&self) -> Result<LocalScanResult> {
+        let timeout_secs = self.directories.read().unwrap().local_scan_timeout_secs;
+        let deadline = (timeout_secs > 0)
+            .then(|| std::time::Instant::now() + std::time::Duration::from_secs(timeout_secs as u64));
+
+        // 检测到的各工具的技能目录
+        let tool_dirs = get_all_supported_tools().into_iter().filter(|tool| tool.is_installed).map(|tool| tool.skills_path());
+        self.scan_local_dirs(tool_dirs, || deadline.is_some_and(|deadline| std::time::Instant::now() >= deadline))
+    }
+
+    /// 扫描 `extra_dirs`、默认安装目录、已安装技能的父目录与设置中的扫描目录，导入未追踪的技能；
+    /// `timed_out` 返回 true 后跳过剩余的目录与目录项
+    fn scan_local_dirs(&self, extra_dirs: impl IntoIterator<Item = PathBuf>, timed_out: impl Fn() -> bool) -> Result<LocalScanResult> {
+        use std::collections::{BTreeSet, HashSet};
 
+        let mut result = LocalScanResult::default();
         let mut scanned_skills = Vec::new();  // 所有扫描到的技能
         let mut imported_skills = Vec::new(); // 新导入的技能（用于日志）
 
         // 获取当前数据库中的所有技能（用于去重和提取路径）
         let existing_skills = self.db.get_skills()?;
+        let directories = self.directories.read().unwrap().clone();
 
         // 1. 获取所有 unique 的 local_path 父目录
-        let mut scan_dirs: HashSet<PathBuf> = HashSet::new();
-
-        // 从已安装技能的 local_path 提取父目录
-        for skill in &existing_skills {
-            if let Some(local_path) = &skill.local_path {
-                if let Some(parent) = PathBuf::from(local_path).parent() {
-                    scan_dirs.insert(parent.to_path_buf());
+        let mut scan_dirs: BTreeSet<PathBuf> = BTreeSet::new();
+
+        // 从已安装技能的 local_path 提取父目录（可在设置中关闭）
+        if directories.scan_installed_parents {
+            for skill in &existing_skills {
+                if let Some(local_path) = &skill.local_path {
+                    if let Some(parent) = PathBuf::from(local_path).parent() {
+                        scan_dirs.insert(parent.to_path_buf());
+                    }
                 }
             }
         }
 
-        // 2. 添加默认的用户目录（确保始终扫描）
-        scan_dirs.insert(self.skills_dir.clone());
+        // 2. 添加默认的用户目录（确保始终扫描）、指定的目录与设置中的扫描目录
+        scan_dirs.insert(self.skills_dir());
+        scan_dirs.extend(extra_dirs);
+        scan_dirs.extend(directories.local_scan_roots.iter().cloned());
 
         log::info!("Will scan {} directories for local skills", scan_dirs.len());
 
-        // 3. 扫描所有目录
-        for scan_dir in scan_dirs {
+        // 符号链接指向的目录已在扫描范围内时不重复导入（如多个工具链接到同一份技能）
+        let canonical_dirs: HashSet<PathBuf> = scan_dirs
+            .iter()
+            .filter(|dir| !directories.is_local_scan_excluded(dir))
+            .filter_map(|dir| std::fs::canonicalize(dir).ok())
+            .collect();
+        let mut linked_targets: HashSet<PathBuf> = HashSet::new();
+        let mut scanned_canonical: HashSet<PathBuf> = HashSet::new();
+
+        // 3. 扫描所有目录（超过时间上限后跳过剩余目录）
+        let manifest_names = self.github.manifest_names();
+        let total_dirs = scan_dirs.len();
+        for (dir_index, scan_dir) in scan_dirs.into_iter().enumerate() {
+            if timed_out() {
+                result.timed_out = true;
+                result.skipped_dirs = total_dirs - dir_index;
+                break;
+            }
+            if directories.is_local_scan_excluded(&scan_dir) {
+                log::debug!("Skipping excluded directory: {:?}", scan_dir);
+                result.excluded_dirs += 1;
+                continue;
+            }
             if !scan_dir.exists() {
                 log::debug!("Skipping non-existent directory: {:?}", scan_dir);
                 continue;
             }
+            // 工具目录本身是指向其他扫描目录的符号链接时只扫描一次
+            if let Ok(canonical) = std::fs::canonicalize(&scan_dir) {
+                if !scanned_canonical.insert(canonical) {
+                    log::debug!("Skipping already scanned directory: {:?}", scan_dir);
+                    continue;
+                }
+            }
 
             log::info!("Scanning directory: {:?}", scan_dir);
+            result.scanned_dirs += 1;
 
             // 遍历技能目录
             if let Ok(entries) = std::fs::read_dir(&scan_dir) {
-            for entry in entries.flatten() {
+            let mut entries = entries.flatten();
+            while let Some(entry) = entries.next() {
+                if timed_out() {
+                    result.timed_out = true;
+                    result.skipped_entries += 1 + entries.count();
+                    break;
+                }
                 let path = entry.path();
 
                 // 只处理目录
                 if !path.is_dir() {
                     continue;
                 }
+                if directories.is_local_scan_excluded(&path) {
+                    result.excluded_dirs += 1;
+                    continue;
+                }
+                if entry.file_type().is_ok_and(|file_type| file_type.is_symlink()) {
+                    let Ok(target) = std::fs::canonicalize(&path) else { continue };
+                    let in_scanned_dir = target.parent().is_some_and(|parent| canonical_dirs.contains(parent));
+                    if in_scanned_dir || !linked_targets.insert(target) {
+                        log::debug!("Skipping symlinked copy: {:?}", path);
+                        continue;
+                    }
+                }
+                let origin_tool = tool_for_path(&path).map(|tool| tool.id);
 
                 // 检查是否包含 SKILL.md
-                let skill_md_path = path.join("SKILL.md");
-                if !skill_md_path.exists() {
+                let Some(skill_md_path) = fs_ops::find_skill_manifest(&path, &manifest_names) else {
                     continue;
-                }
+                };
 
                 // 读取 SKILL.md 内容
                 match std::fs::read_to_string(&skill_md_path) {
@@ -858,11 +1918,12 @@ impl SkillManager {
                                 )
                             });
 
-                        // 检查是否已存在（按 local_path 去重，避免目录不变但名称变化导致重复导入）
+                        // 检查是否已存在（按 local_path 或路径生成的 ID 去重，避免目录不变但名称或内容变化导致重复导入）
                         let local_path_str = path.to_string_lossy().to_string();
+                        let skill_id = local_skill_id(&path);
                         let existing_by_path = existing_skills
                             .iter()
-                            .filter(|s| s.local_path.as_deref() == Some(local_path_str.as_str()))
+                            .filter(|s| s.local_path.as_deref() == Some(local_path_str.as_str()) || s.id == skill_id)
                             .cloned()
                             .collect::<Vec<_>>();
 
@@ -875,6 +1936,12 @@ impl SkillManager {
                         }
 
                         if let Some(mut existing_skill) = existing_by_path.into_iter().next() {
+                            // 旧版本按 SKILL.md 内容生成的 ID 迁移为按路径生成，保留扫描历史
+                            if existing_skill.source_type == SourceType::LocalImport && existing_skill.id != skill_id {
+                                log::info!("迁移本地技能 ID: {} -> {}", existing_skill.id, skill_id);
+                                self.db.rekey_skill(&existing_skill.id, &skill_id)?;
+                                existing_skill.id = skill_id.clone();
+                            }
                             // 确保安装状态/路径一致
                             if !existing_skill.installed {
                                 existing_skill.installed = true;
@@ -889,20 +1956,20 @@ impl SkillManager {
                                 existing_skill.checksum = Some(checksum.clone());
                             }
 
-                            // 仅对本地导入的技能（repository_url == local）更新 name/description/file_path
+                            // 仅对本地导入的技能更新 name/description/file_path
                             // 避免覆盖市场技能的元数据来源（仓库扫描/市场配置）
-                            if existing_skill.repository_url == "local" {
+                            if existing_skill.source_type == SourceType::LocalImport {
                                 existing_skill.name = skill_name;
                                 existing_skill.description = skill_description;
                                 existing_skill.file_path = local_path_str.clone();
                             }
+                            existing_skill.compatible_tools = Skill::parse_compatible_tools(&content);
+                            if existing_skill.origin_tool.is_none() {
+                                existing_skill.origin_tool = origin_tool.clone();
+                            }
 
                             // 命中已有 local_path：刷新安全扫描信息，避免安全结果陈旧
-                            let report = self.scanner.scan_directory(
-                                path.to_str().unwrap_or(""),
-                                &existing_skill.id,
-                                "zh",
-                            )?;
+                            let report = scan_directory_cached(&self.db, &self.scan_policy().scanner(), &path, &existing_skill.id, "zh")?;
 
                             existing_skill.security_score = Some(report.score);
                             existing_skill.security_issues = Some(
@@ -926,22 +1993,17 @@ impl SkillManager {
                                 crate::models::security::SecurityLevel::High => "High".to_string(),
                                 crate::models::security::SecurityLevel::Critical => "Critical".to_string(),
                             });
-                            existing_skill.scanned_at = Some(Utc::now());
+                            let scanned_at = Utc::now();
+                            existing_skill.scanned_at = Some(scanned_at);
 
                             self.db.save_skill(&existing_skill)?;
+                            self.save_scan_report(&existing_skill.id, &report, scanned_at);
                             scanned_skills.push(existing_skill);
                             continue;
                         }
 
-                        // 生成技能 ID
-                        let skill_id = format!("local::{}", checksum[..16].to_string());
-
                         // 扫描整个技能目录
-                        let report = self.scanner.scan_directory(
-                            path.to_str().unwrap_or(""),
-                            &skill_id,
-                            "zh"
-                        )?;
+                        let report = scan_directory_cached(&self.db, &self.scan_policy().scanner(), &path, &skill_id, "zh")?;
 
                         log::info!("Scanned local skill '{}': score={}, files={:?}",
                             skill_name, report.score, report.scanned_files);
@@ -952,8 +2014,8 @@ impl SkillManager {
                             id: skill_id,
                             name: skill_name,
                             description: skill_description,
-                            repository_url: "local".to_string(),
-                            repository_owner: Some("local".to_string()),
+                            repository_url: LOCAL_REPOSITORY_URL.to_string(),
+                            repository_owner: Some(LOCAL_REPOSITORY_URL.to_string()),
                             file_path: path.to_string_lossy().to_string(),
                             version: None,
                             author: None,
@@ -982,10 +2044,18 @@ impl SkillManager {
                             }),
                             scanned_at: Some(Utc::now()),
                             installed_commit_sha: None,
+                            compatible_tools: Skill::parse_compatible_tools(&content),
+                            source_removed: false,
+                            first_seen_at: None,
+                            source_type: SourceType::LocalImport,
+                            origin_tool,
                         };
 
                         // 保存到数据库
                         self.db.save_skill(&skill)?;
+                        if let Some(scanned_at) = skill.scanned_at {
+                            self.save_scan_report(&skill.id, &report, scanned_at);
+                        }
                         imported_skills.push(skill.clone());
                         scanned_skills.push(skill);
 
@@ -1001,7 +2071,30 @@ impl SkillManager {
 
         log::info!("Scanned {} local skills, imported {} new skills",
                    scanned_skills.len(), imported_skills.len());
-        Ok(scanned_skills)
+        if result.timed_out {
+            log::warn!(
+                "本地技能扫描超过时间上限（{} 秒），跳过 {} 个目录与 {} 个目录项",
+                directories.local_scan_timeout_secs, result.skipped_dirs, result.skipped_entries
+            );
+        }
+        result.skills = scanned_skills;
+        Ok(result)
+    }
+
+    /// 检查安装目标是否在技能声明的兼容工具之列，不兼容时返回提示信息
+    /// 技能未声明兼容工具、或安装目录不属于任何已知工具时不做提示
+    pub fn compatibility_warning(skill: &Skill, install_dir: &std::path::Path) -> Option<String> {
+        let tool = crate::models::tool_for_path(install_dir)?;
+        if skill.is_compatible_with(&tool.id) {
+            return None;
+        }
+
+        Some(format!(
+            "技能 {} 未声明支持 {}（声明支持: {}），安装后可能无法正常工作",
+            skill.name,
+            tool.name,
+            skill.compatible_tools.as_deref().unwrap_or_default().join(", ")
+        ))
     }
 
     /// 解析 SKILL.md 的 frontmatter
@@ -1041,8 +2134,42 @@ impl SkillManager {
         Ok((name, description))
     }
 
+    /// 在阻塞线程池中全量扫描技能目录（扫描会读取目录中的全部文件），
+    /// 并保存每个文件（包括扫描时跳过的文件）的内容哈希，供确认安装时校验写入的文件（见 [`Self::verify_installed_files`]）
+    async fn scan_directory_async(&self, dir: &std::path::Path, skill_id: &str, locale: &str) -> Result<crate::models::SecurityReport> {
+        let dir = dir.to_str().context("技能目录路径无效")?.to_string();
+        let (skill_id, locale) = (skill_id.to_string(), locale.to_string());
+        let scanner = self.scan_policy().scanner();
+        let db = Arc::clone(&self.db);
+        fs_ops::run_blocking(move || {
+            let mut scan = scanner.scan_directory_incremental(&dir, &skill_id, &locale, None)?;
+            scan.cache.record_unscanned_files(std::path::Path::new(&dir));
+            db.save_scan_cache(&dir, &scan.cache).context("保存扫描记录失败")?;
+            Ok(scan.report)
+        }).await
+    }
+
+    /// 安装后校验：重新计算安装目录中文件的哈希，与扫描 `scanned_dir` 时保存的记录比较，
+    /// 避免扫描之后、复制之前缓存目录中的文件被替换（扫描与安装之间的 TOCTOU 窗口）
+    fn verify_installed_files(&self, skill_name: &str, scanned_dir: &std::path::Path, installed_dir: &std::path::Path) -> Result<()> {
+        let scanned = scanned_dir.to_str().context("技能目录路径无效")?;
+        let manifest = self.db.get_scan_cache(scanned)?.ok_or_else(|| {
+            AppError::new(ErrorKind::BlockedByScan, "未找到安装前的扫描记录，请重新扫描后再安装")
+        })?;
+
+        let mismatched = self.scan_policy().scanner().verify_directory(installed_dir, &manifest)?;
+        if !mismatched.is_empty() {
+            log::error!("安装后校验失败，{} 个文件与扫描时不一致: {:?}", mismatched.len(), mismatched);
+            return Err(InstallBlocked::new(skill_name, vec![BlockReason::ContentMismatch { files: mismatched }]).into());
+        }
+
+        log::info!("安装后校验通过: {:?}", installed_dir);
+        Ok(())
+    }
+
     /// 从网络下载并安装技能（降级方案）
-    async fn install_from_network(&self, skill: &crate::models::Skill, skill_dir: &PathBuf) -> Result<()> {
+    /// 返回写入的文件数
+    async fn install_from_network(&self, skill: &crate::models::Skill, skill_dir: &PathBuf) -> Result<usize> {
         let (owner, repo, _) = crate::models::Repository::from_github_url(&skill.repository_url)?;
 
         // 如果 file_path 是 "."，转换为空字符串以获取根目录内容
@@ -1053,6 +2180,7 @@ impl SkillManager {
         log::info!("Found {} files in skill directory", skill_files.len());
 
         // 下载每个文件
+        let mut files_written = 0;
         for file_info in &skill_files {
             if file_info.content_type != "file" {
                 continue; // 跳过子目录
@@ -1067,20 +2195,23 @@ impl SkillManager {
 
             // 写入文件到本地
             let local_file_path = skill_dir.join(&file_info.name);
-            std::fs::write(&local_file_path, file_content)
+            tokio::fs::write(&local_file_path, file_content).await
                 .context(format!("无法写入文件: {}", file_info.name))?;
 
             log::info!("Saved file: {}", file_info.name);
+            files_written += 1;
         }
 
-        Ok(())
+        Ok(files_written)
     }
 
     /// 检测本地文件是否被修改（与缓存中的版本比较）
-    fn detect_local_modifications(&self, installed_dir: &PathBuf, cached_dir: &PathBuf) -> Result<Vec<String>> {
+    /// 安装时技能清单统一改名为 SKILL.md，缓存中使用其他清单名时按缓存中的清单文件比较
+    fn detect_local_modifications(installed_dir: &PathBuf, cached_dir: &PathBuf, names: &ManifestNames) -> Result<Vec<String>> {
         use std::fs;
 
         let mut modified_files = Vec::new();
+        let cached_manifest = fs_ops::find_skill_manifest(cached_dir, names);
 
         // 遍历已安装目录中的所有文件
         for entry in walkdir::WalkDir::new(installed_dir)
@@ -1095,7 +2226,10 @@ impl SkillManager {
                     .context("无法计算相对路径")?;
 
                 // 对应的缓存文件路径
-                let cached_file = cached_dir.join(relative_path);
+                let cached_file = match &cached_manifest {
+                    Some(manifest) if relative_path == std::path::Path::new(SKILL_MANIFEST_NAME) => manifest.clone(),
+                    _ => cached_dir.join(relative_path),
+                };
 
                 // 如果缓存中没有该文件，说明是用户新增的
                 if !cached_file.exists() {
@@ -1126,8 +2260,9 @@ impl SkillManager {
         let skill = self.db.get_skills()?
             .into_iter()
             .find(|s| s.id == skill_id)
-            .context("未找到该技能")?;
+            .ok_or_else(|| AppError::not_found("未找到该技能"))?;
 
+        ensure_repository_source(&skill)?;
         if !skill.installed {
             anyhow::bail!("该技能尚未安装，无法更新");
         }
@@ -1136,23 +2271,18 @@ impl SkillManager {
         let repositories = self.db.get_repositories()?;
         let repo = repositories.iter()
             .find(|r| r.url == skill.repository_url)
-            .context("未找到对应的仓库记录")?
+            .ok_or_else(|| AppError::not_found("未找到对应的仓库记录"))?
             .clone();
 
         // 重新下载仓库到新的临时缓存（staging）
         log::info!("下载最新版本到 staging 目录");
         let (owner, repo_name, branch) = crate::models::Repository::from_github_url(&skill.repository_url)?;
 
-        let staging_base_dir = dirs::cache_dir()
-            .context("无法获取系统缓存目录")?
-            .join("agent-skills-guard")
-            .join("staging");
+        let staging_base_dir = CachePaths::require()?.staging();
 
         // 清理旧的 staging 目录（如果存在）
         let staging_repo_dir = staging_base_dir.join(format!("{}_{}", owner, repo_name));
-        if staging_repo_dir.exists() {
-            std::fs::remove_dir_all(&staging_repo_dir)?;
-        }
+        fs_ops::remove_path(staging_repo_dir).await?;
 
         // 下载最新版本
         let (extract_dir, new_commit_sha) = self.github
@@ -1162,6 +2292,11 @@ impl SkillManager {
 
         log::info!("下载完成，最新 commit: {}", new_commit_sha);
 
+        if repo.require_signed_commits {
+            let status = self.check_commit_signature(&owner, &repo_name, &new_commit_sha).await?;
+            self.ensure_signed_commit(&repo, &new_commit_sha, Some(&status))?;
+        }
+
         // 定位 staging 中的技能目录
         let staging_skill_dir = self.locate_skill_in_cache(
             extract_dir.as_path(),
@@ -1169,11 +2304,7 @@ impl SkillManager {
         )?;
 
         // 扫描最新版本
-        let scan_report = self.scanner.scan_directory(
-            staging_skill_dir.to_str().context("技能目录路径无效")?,
-            &skill.id,
-            locale
-        )?;
+        let scan_report = self.scan_directory_async(&staging_skill_dir, &skill.id, locale).await?;
 
         log::info!("Security scan completed: score={}, scanned {} files",
             scan_report.score, scan_report.scanned_files.len());
@@ -1188,7 +2319,10 @@ impl SkillManager {
                     if cache_path_buf.exists() {
                         match self.locate_skill_in_cache(cache_path_buf.as_path(), &skill.file_path) {
                             Ok(cached_skill_dir) => {
-                                self.detect_local_modifications(&installed_dir, &cached_skill_dir)?
+                                let names = self.github.manifest_names();
+                                fs_ops::run_blocking(move || {
+                                    Self::detect_local_modifications(&installed_dir, &cached_skill_dir, &names)
+                                }).await?
                             }
                             Err(e) => {
                                 log::warn!("无法定位缓存中的技能目录: {}", e);
@@ -1227,10 +2361,12 @@ impl SkillManager {
 
         log::info!("Confirming update for skill: {}", skill_id);
 
+        let fs_context = self.fs_context();
+
         let mut skill = self.db.get_skills()?
             .into_iter()
             .find(|s| s.id == skill_id)
-            .context("未找到该技能")?;
+            .ok_or_else(|| AppError::not_found("未找到该技能"))?;
 
         // 获取 staging 路径
         let staging_marker = skill.local_path.as_ref()
@@ -1255,8 +2391,43 @@ impl SkillManager {
             anyhow::bail!("技能没有有效的安装路径");
         }
 
-        // 使用第一个路径作为目标（通常只有一个）
-        let target_install_dir = PathBuf::from(&install_paths[0]);
+        // 通常只有一个路径；有多个时优先使用记住的安装目录下的路径
+        let target_install_dir = self.update_target_dir(&skill.id, install_paths);
+
+        // 开始备份与覆盖前检查安装目录（只读属性、云同步占位文件），避免更新到一半失败
+        dir_access::check_dir(&target_install_dir, true).ensure_ready()?;
+
+        // 从 staging 路径推导出 extracted 目录并提取 commit SHA
+        // - staging_dir 指向 skill 目录（可能是仓库根目录或其子目录）
+        // - extracted_dir 是 {cache}/.../extracted/，其下第一层目录名为 {owner}-{repo}-{sha}
+        let extract_dir = {
+            let mut repo_root = staging_dir.clone();
+            if skill.file_path != "." {
+                let components_count = std::path::Path::new(&skill.file_path)
+                    .components()
+                    .filter(|c| matches!(c, std::path::Component::Normal(_)))
+                    .count();
+
+                for _ in 0..components_count {
+                    repo_root = repo_root
+                        .parent()
+                        .context("无效的 staging 路径：无法定位仓库根目录")?
+                        .to_path_buf();
+                }
+            }
+
+            repo_root
+                .parent()
+                .context("无效的 staging 路径：无法定位 extracted 目录")?
+                .to_path_buf()
+        };
+        let new_commit_sha = self.github.extract_commit_sha_from_cache(&extract_dir);
+
+        // 记录操作日志：应用在备份或写入中途退出时，下次启动会从备份恢复原安装目录
+        let mut journal = OperationJournal::begin(&self.db, PendingOperation {
+            commit_sha: new_commit_sha.as_ref().ok().cloned(),
+            ..PendingOperation::new(skill_id, OperationKind::Update, &target_install_dir.to_string_lossy())
+        })?;
 
         #[derive(Debug)]
         enum BackupDir {
@@ -1303,37 +2474,17 @@ impl SkillManager {
             let dir_name = target_install_dir.file_name()
                 .context("无效的目录名")?
                 .to_string_lossy();
-            let backup_root = dirs::cache_dir()
-                .context("无法获取系统缓存目录")?
-                .join("agent-skills-guard")
-                .join("skill-backups");
+            let backup_root = self.backup_root()
+                .context("无法确定备份目录")?;
 
             std::fs::create_dir_all(&backup_root)
-                .context(format!("无法创建备份缓存目录: {:?}", backup_root))?;
-
-            let mut backup_path = backup_root.join(format!("{}.bak", dir_name));
-
-            if backup_path.exists() {
-                match std::fs::remove_dir_all(&backup_path) {
-                    Ok(()) => {}
-                    Err(remove_err) => {
-                        if !force_overwrite {
-                            return Err(anyhow::anyhow!(format!(
-                                "无法删除旧备份目录（缓存目录）: {:?}\n错误: {}\n\n请检查该目录是否被其他程序占用",
-                                backup_path, remove_err
-                            )));
-                        }
+                .context(format!("无法创建备份目录: {:?}", backup_root))?;
 
-                        // 强制覆盖时，为了不中断流程，改用一个唯一的备份目录名
-                        let epoch_ms = std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_millis();
-                        backup_path = backup_root.join(format!("{}.bak-{}", dir_name, epoch_ms));
-                        let _ = std::fs::remove_dir_all(&backup_path);
-                    }
-                }
-            }
+            // 每次更新使用新的备份目录，旧备份按保留设置清理
+            let backup_path = backup_root.join(skill_backups::backup_dir_name(&dir_name, Utc::now()));
+            let _ = fs_ops::remove_dir_all(&backup_path);
+
+            journal.set_backup_dir(&backup_path)?;
 
             // 尝试移动：移动成功意味着我们可以“干净地”写入新版本（更接近原子替换）
             match rename_with_retry(&target_install_dir, &backup_path) {
@@ -1347,8 +2498,8 @@ impl SkillManager {
                         move_err
                     );
 
-                    match self.copy_directory(&target_install_dir, &backup_path) {
-                        Ok(()) => {
+                    match fs_ops::copy_dir(&target_install_dir, &backup_path, &fs_context).and_then(CopyReport::ensure_complete) {
+                        Ok(_) => {
                             log::info!("创建备份(复制到缓存): {:?}", backup_path);
                             Some(BackupDir::Copied(backup_path))
                         }
@@ -1370,6 +2521,22 @@ impl SkillManager {
             None
         };
 
+        if let Some(BackupDir::Renamed(p) | BackupDir::Copied(p)) = &backup_dir {
+            // 移动保留了原目录的修改时间，更新为备份时间，供临时目录清理按保留期限判断
+            if let Err(e) = filetime::set_file_mtime(p, filetime::FileTime::now()) {
+                log::warn!("更新备份目录时间失败: {:?}, 错误: {}", p, e);
+            }
+            journal.advance(OperationStep::BackedUp)?;
+        }
+
+        // 备份完成后、覆盖安装目录前响应取消请求（移动备份时先移回原位）
+        if let Err(e) = fs_context.check_cancelled() {
+            if let Some(BackupDir::Renamed(p)) = &backup_dir {
+                let _ = std::fs::rename(p, &target_install_dir);
+            }
+            return Err(e);
+        }
+
         // 确保目标父目录存在
         std::fs::create_dir_all(&target_install_dir.parent().context("无效的安装路径")?)?;
 
@@ -1379,7 +2546,7 @@ impl SkillManager {
                 .context(format!("无法创建目标目录: {:?}", target_install_dir))?;
         } else if force_overwrite {
             // 强制覆盖时，尽量清空旧目录以避免遗留文件
-            if let Err(clear_err) = std::fs::remove_dir_all(&target_install_dir) {
+            if let Err(clear_err) = fs_ops::remove_dir_all(&target_install_dir) {
                 log::warn!(
                     "无法清空旧技能目录，将尝试直接覆盖写入（可能保留部分旧文件）: {}",
                     clear_err
@@ -1390,8 +2557,13 @@ impl SkillManager {
             }
         }
 
-        match self.copy_directory(&staging_dir, &target_install_dir) {
-            Ok(_) => {
+        // 复制后校验写入的文件与扫描时一致，不一致时与复制失败一样恢复备份
+        let copied = fs_ops::copy_dir(&staging_dir, &target_install_dir, &fs_context)
+            .and_then(CopyReport::ensure_complete)
+            .and_then(|_| self.verify_installed_files(&skill.name, &staging_dir, &target_install_dir))
+            .and_then(|()| fs_ops::normalize_skill_manifest(&target_install_dir, &self.github.manifest_names()).map(drop));
+        match copied.and_then(|()| journal.advance(OperationStep::Copied)) {
+            Ok(()) => {
                 log::info!("成功更新技能到: {:?}", target_install_dir);
 
                 // 备份保留在缓存目录，便于必要时人工回滚；下一次更新会覆盖旧备份
@@ -1399,42 +2571,15 @@ impl SkillManager {
                 // 更新数据库：恢复 local_path，更新 installed_commit_sha
                 skill.local_path = Some(target_install_dir.to_string_lossy().to_string());
 
-                // 从 staging 路径推导出 extracted 目录并提取 commit SHA
-                // - staging_dir 指向 skill 目录（可能是仓库根目录或其子目录）
-                // - extracted_dir 是 {cache}/.../extracted/，其下第一层目录名为 {owner}-{repo}-{sha}
-                let extract_dir = {
-                    let mut repo_root = staging_dir.clone();
-                    if skill.file_path != "." {
-                        let components_count = std::path::Path::new(&skill.file_path)
-                            .components()
-                            .filter(|c| matches!(c, std::path::Component::Normal(_)))
-                            .count();
-
-                        for _ in 0..components_count {
-                            repo_root = repo_root
-                                .parent()
-                                .context("无效的 staging 路径：无法定位仓库根目录")?
-                                .to_path_buf();
-                        }
-                    }
-
-                    repo_root
-                        .parent()
-                        .context("无效的 staging 路径：无法定位 extracted 目录")?
-                        .to_path_buf()
-                };
-
-                match self.github.extract_commit_sha_from_cache(&extract_dir) {
+                match new_commit_sha {
                     Ok(new_sha) => {
                         skill.installed_commit_sha = Some(new_sha.clone());
                         log::info!("更新 installed_commit_sha");
 
                         // 将 staging 下载的版本提升为“仓库缓存基线”，避免后续把已更新内容误判为“本地修改”
                         if let Ok((owner, repo_name, _)) = crate::models::Repository::from_github_url(&skill.repository_url) {
-                            if let Some(cache_base_dir) = dirs::cache_dir() {
-                                let repositories_base_dir = cache_base_dir
-                                    .join("agent-skills-guard")
-                                    .join("repositories");
+                            if let Some(cache_paths) = CachePaths::current() {
+                                let repositories_base_dir = cache_paths.repositories();
                                 let repo_cache_dir = repositories_base_dir.join(format!("{}_{}", owner, repo_name));
                                 let extracted_dest = repo_cache_dir.join("extracted");
 
@@ -1442,7 +2587,7 @@ impl SkillManager {
                                     log::warn!("无法创建仓库缓存目录，将跳过缓存同步: {}", e);
                                 } else {
                                     if extracted_dest.exists() {
-                                        let _ = std::fs::remove_dir_all(&extracted_dest);
+                                        let _ = fs_ops::remove_dir_all(&extracted_dest);
                                     }
 
                                     match rename_with_retry(&extract_dir, &extracted_dest) {
@@ -1454,7 +2599,9 @@ impl SkillManager {
                                                 "无法移动 staging 缓存到仓库缓存，将尝试复制: {}",
                                                 rename_err
                                             );
-                                            if let Err(copy_err) = self.copy_directory(&extract_dir, &extracted_dest) {
+                                            if let Err(copy_err) = fs_ops::copy_dir(&extract_dir, &extracted_dest, &FsContext::default())
+                                                .and_then(CopyReport::ensure_complete)
+                                            {
                                                 log::warn!("同步仓库缓存(复制)失败: {}", copy_err);
                                             } else {
                                                 log::info!("已同步仓库缓存(复制): {:?}", extracted_dest);
@@ -1462,6 +2609,19 @@ impl SkillManager {
                                         }
                                     }
 
+                                    // 同步内容寻址清单：staging 清单提升为仓库缓存清单
+                                    if let Some(store) = self.github.blob_store() {
+                                        let from_key = BlobStore::manifest_key(
+                                            &cache_paths.staging(),
+                                            &owner,
+                                            &repo_name,
+                                        );
+                                        let to_key = BlobStore::manifest_key(&repositories_base_dir, &owner, &repo_name);
+                                        if let Err(e) = store.rename_manifest(&from_key, &to_key) {
+                                            log::warn!("同步缓存清单失败: {}", e);
+                                        }
+                                    }
+
                                     if extracted_dest.exists() {
                                         if let Ok(repositories) = self.db.get_repositories() {
                                             if let Some(repo) = repositories.iter().find(|r| r.url == skill.repository_url) {
@@ -1488,6 +2648,13 @@ impl SkillManager {
 
                 skill.installed_at = Some(Utc::now());
                 self.db.save_skill(&skill)?;
+                let install_dir = target_install_dir.to_string_lossy();
+                self.record_event(&skill.id, SkillEventKind::Updated, Some(&install_dir), skill.installed_commit_sha.as_deref());
+                self.clear_suspicious_update(&skill.id);
+
+                if let Err(e) = self.prune_backups() {
+                    log::warn!("清理更新备份失败: {}", e);
+                }
 
                 log::info!("技能更新确认完成: {}", skill.name);
                 Ok(())
@@ -1496,7 +2663,7 @@ impl SkillManager {
                 // 恢复备份
                 if let Some(backup) = backup_dir {
                     if target_install_dir.exists() {
-                        let _ = std::fs::remove_dir_all(&target_install_dir);
+                        let _ = fs_ops::remove_dir_all(&target_install_dir);
                     }
 
                     match backup {
@@ -1505,8 +2672,11 @@ impl SkillManager {
                             log::warn!("更新失败，已恢复备份(重命名): {:?}", p);
                         }
                         BackupDir::Copied(p) => {
-                            let _ = self.copy_directory(&p, &target_install_dir);
-                            log::warn!("更新失败，已恢复备份(复制): {:?}", p);
+                            // 恢复备份必须完整执行，不响应取消请求
+                            match fs_ops::copy_dir(&p, &target_install_dir, &FsContext::default()).and_then(CopyReport::ensure_complete) {
+                                Ok(_) => log::warn!("更新失败，已恢复备份(复制): {:?}", p),
+                                Err(restore_err) => log::error!("更新失败，恢复备份失败: {:?}: {}", p, restore_err),
+                            }
                         }
                     }
                 }
@@ -1515,6 +2685,126 @@ impl SkillManager {
         }
     }
 
+    /// 清理超过保留期限的更新临时目录（staging）与更新备份（skill-backups），
+    /// 启动时及定时调用，调用方需持有写操作锁。返回 (删除的目录数, 释放的字节数)
+    pub fn cleanup_temp_dirs(&self) -> Result<(usize, u64)> {
+        let (pruned, pruned_freed) = self.prune_backups()?;
+        let retention_days = self.cache_settings.read().unwrap().temp_retention_days;
+        if retention_days == 0 {
+            return Ok((pruned, pruned_freed));
+        }
+
+        let retention = std::time::Duration::from_secs(u64::from(retention_days) * 24 * 60 * 60);
+        let cutoff = std::time::SystemTime::now().checked_sub(retention).unwrap_or(std::time::UNIX_EPOCH);
+
+        let (staging_removed, staging_freed) = self.clear_staging_dirs(Some(cutoff))?;
+        let (backups_removed, backups_freed) = self.clear_backup_dirs(Some(cutoff))?;
+        Ok((staging_removed + backups_removed + pruned, staging_freed + backups_freed + pruned_freed))
+    }
+
+    /// 删除更新临时目录（staging），cutoff 为 None 时删除全部，否则只删除最后修改早于 cutoff 的目录。
+    /// 调用方需持有写操作锁。返回 (删除的目录数, 释放的字节数，含回收的 blob)
+    ///
+    /// 被删除的 staging 对应的“待确认更新”会一并取消，技能记录恢复为原安装路径
+    pub fn clear_staging_dirs(&self, cutoff: Option<std::time::SystemTime>) -> Result<(usize, u64)> {
+        let Some(cache_paths) = CachePaths::current() else {
+            return Ok((0, 0));
+        };
+        let staging_base_dir = cache_paths.staging();
+
+        let removed_dirs = remove_temp_dirs(&staging_base_dir, cutoff)?;
+        let mut freed: u64 = removed_dirs.iter().map(|(_, size)| size).sum();
+        if removed_dirs.is_empty() {
+            return Ok((0, 0));
+        }
+
+        let mut skills = self.db.get_skills()?;
+        for (dir, _) in &removed_dirs {
+            // 删除 staging 清单，并取消指向该目录的待确认更新
+            let dir_name = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            if let (Some(store), Some((owner, repo_name))) = (self.github.blob_store(), dir_name.split_once('_')) {
+                let key = BlobStore::manifest_key(&staging_base_dir, owner, repo_name);
+                if let Err(e) = store.remove_manifest(&key) {
+                    log::warn!("删除 staging 清单失败: {}", e);
+                }
+            }
+
+            for skill in skills.iter_mut() {
+                let staged_in_dir = skill.local_path.as_deref()
+                    .and_then(|p| p.strip_prefix("__staging__:"))
+                    .is_some_and(|p| std::path::Path::new(p).starts_with(dir));
+                if staged_in_dir {
+                    skill.local_path = skill.local_paths.as_ref().and_then(|paths| paths.first().cloned());
+                    self.db.save_skill(skill)?;
+                    log::info!("已取消待确认的更新: {}", skill.name);
+                }
+            }
+        }
+
+        if let Some(store) = self.github.blob_store() {
+            match store.collect_garbage() {
+                Ok((_, blob_freed)) => freed += blob_freed,
+                Err(e) => log::warn!("回收 blob 失败: {}", e),
+            }
+        }
+
+        Ok((removed_dirs.len(), freed))
+    }
+
+    /// 删除更新前保留的安装目录备份（skill-backups），cutoff 含义同 [`Self::clear_staging_dirs`]。
+    /// 调用方需持有写操作锁。返回 (删除的目录数, 释放的字节数)
+    pub fn clear_backup_dirs(&self, cutoff: Option<std::time::SystemTime>) -> Result<(usize, u64)> {
+        let Some(backup_root) = self.backup_root() else {
+            return Ok((0, 0));
+        };
+
+        let removed_dirs = remove_temp_dirs(&backup_root, cutoff)?;
+        Ok((removed_dirs.len(), removed_dirs.iter().map(|(_, size)| size).sum()))
+    }
+
+    /// 更新备份目录（设置中的备份目录，默认为缓存目录下的 skill-backups）
+    pub fn backup_root(&self) -> Option<PathBuf> {
+        self.cache_settings.read().unwrap().resolved_backup_dir()
+    }
+
+    /// 按每个技能的备份数与总大小上限删除最旧的更新备份，调用方需持有写操作锁。返回 (删除的目录数, 释放的字节数)
+    pub fn prune_backups(&self) -> Result<(usize, u64)> {
+        let Some(backup_root) = self.backup_root() else {
+            return Ok((0, 0));
+        };
+        let settings = self.cache_settings.read().unwrap().clone();
+        let removed = skill_backups::prune_backups(
+            &backup_root,
+            settings.max_backups_per_skill as usize,
+            settings.max_backup_size_mb.saturating_mul(1024 * 1024),
+        )?;
+        Ok((removed.len(), removed.iter().map(|(_, size)| size).sum()))
+    }
+
+    /// 列出更新备份（按时间从新到旧），指定技能时只返回该技能安装目录的备份
+    pub fn list_skill_backups(&self, skill_id: Option<&str>) -> Result<Vec<SkillBackup>> {
+        let Some(backup_root) = self.backup_root() else {
+            return Ok(Vec::new());
+        };
+        let backups = skill_backups::list_backups(&backup_root)?;
+        let Some(skill_id) = skill_id else {
+            return Ok(backups);
+        };
+
+        let skill = self.db.get_skills()?
+            .into_iter()
+            .find(|s| s.id == skill_id)
+            .ok_or_else(|| AppError::not_found("未找到该技能"))?;
+        let mut paths = skill.local_paths.clone().unwrap_or_default();
+        paths.extend(skill.local_path.clone());
+        let dir_names: Vec<String> = paths
+            .iter()
+            .filter_map(|path| std::path::Path::new(path).file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .collect();
+        Ok(backups.into_iter().filter(|backup| dir_names.contains(&backup.skill_dir_name)).collect())
+    }
+
     /// 取消技能更新：清理 staging 目录
     pub fn cancel_skill_update(&self, skill_id: &str) -> Result<()> {
         use anyhow::Context;
@@ -1524,7 +2814,7 @@ impl SkillManager {
         let mut skill = self.db.get_skills()?
             .into_iter()
             .find(|s| s.id == skill_id)
-            .context("未找到该技能")?;
+            .ok_or_else(|| AppError::not_found("未找到该技能"))?;
 
         // 获取 staging 路径
         let staging_marker = skill.local_path.as_ref()
@@ -1542,12 +2832,32 @@ impl SkillManager {
         if let Some(parent) = staging_dir.parent() {
             if let Some(repo_dir) = parent.parent() {
                 if repo_dir.exists() {
-                    std::fs::remove_dir_all(repo_dir)?;
+                    fs_ops::remove_dir_all(repo_dir)?;
                     log::info!("已删除 staging 目录: {:?}", repo_dir);
                 }
             }
         }
 
+        // 删除 staging 清单并回收不再引用的 blob
+        if let Some(store) = self.github.blob_store() {
+            if let (Ok((owner, repo_name, _)), Some(cache_paths)) = (
+                crate::models::Repository::from_github_url(&skill.repository_url),
+                CachePaths::current(),
+            ) {
+                let key = BlobStore::manifest_key(
+                    &cache_paths.staging(),
+                    &owner,
+                    &repo_name,
+                );
+                if let Err(e) = store.remove_manifest(&key) {
+                    log::warn!("删除 staging 清单失败: {}", e);
+                }
+            }
+            if let Err(e) = store.collect_garbage() {
+                log::warn!("回收 blob 失败: {}", e);
+            }
+        }
+
         // 恢复数据库中的 local_path
         if let Some(local_paths) = &skill.local_paths {
             if !local_paths.is_empty() {
@@ -1565,65 +2875,834 @@ impl SkillManager {
         Ok(())
     }
 
-    /// 递归复制目录
-    fn copy_directory(&self, src: &PathBuf, dst: &PathBuf) -> Result<()> {
-        use std::fs;
-
-        log::info!("复制目录: {:?} -> {:?}", src, dst);
+    /// 按安装时记录的 commit 重新安装技能：从 GitHub 下载该 commit（而非分支最新版本），
+    /// 扫描通过后清空并重新写入所有安装目录（用于发现篡改或文件被误删后恢复）
+    pub async fn reinstall_skill(&self, skill_id: &str, locale: &str, confirmation: Option<&str>) -> Result<Skill> {
+        self.reinstall_skill_into(skill_id, &CachePaths::require()?.reinstall(), locale, confirmation).await
+    }
 
-        // 确保目标目录存在
-        if !dst.exists() {
-            fs::create_dir_all(dst)
-                .context(format!("无法创建目标目录: {:?}", dst))?;
-            log::debug!("创建目标目录: {:?}", dst);
-        }
-
-        // 遍历源目录
-        for entry in fs::read_dir(src)
-            .context(format!("无法读取源目录: {:?}", src))? {
-            let entry = entry
-                .context(format!("读取目录项失败: {:?}", src))?;
-            let file_type = entry.file_type()
-                .context(format!("获取文件类型失败: {:?}", entry.path()))?;
-            let src_path = entry.path();
-            let file_name = entry.file_name();
-            let dst_path = dst.join(&file_name);
-
-            if file_type.is_dir() {
-                // 递归复制子目录
-                log::debug!("复制子目录: {:?}", file_name);
-                self.copy_directory(&src_path, &dst_path)?;
-            } else if file_type.is_file() {
-                // 确保目标文件的父目录存在
-                if let Some(parent) = dst_path.parent() {
-                    if !parent.exists() {
-                        fs::create_dir_all(parent)
-                            .context(format!("无法创建文件父目录: {:?}", parent))?;
-                    }
+    /// 同 [`Self::reinstall_skill`]，下载到 `reinstall_base_dir`（不影响仓库缓存与 staging）
+    async fn reinstall_skill_into(&self, skill_id: &str, reinstall_base_dir: &std::path::Path, locale: &str, confirmation: Option<&str>) -> Result<Skill> {
+        let mut skill = self.db.get_skills()?
+            .into_iter()
+            .find(|s| s.id == skill_id)
+            .ok_or_else(|| AppError::not_found("未找到该技能"))?;
+        ensure_repository_source(&skill)?;
+        if !skill.installed {
+            return Err(AppError::invalid_input(format!("技能未安装，无法重新安装: {}", skill.name)).into());
+        }
+        if skill.local_path.as_deref().is_some_and(|path| path.starts_with("__staging__:")) {
+            return Err(AppError::invalid_input("技能正在等待确认更新，请先确认或取消更新").into());
+        }
+        let commit_sha = skill.installed_commit_sha.clone().ok_or_else(|| {
+            AppError::invalid_input(format!("技能 {} 没有记录安装的 commit，无法按固定版本重新安装", skill.name))
+        })?;
+        let install_dirs: Vec<PathBuf> = skill.local_paths.clone().unwrap_or_default()
+            .into_iter()
+            .chain(skill.local_path.clone())
+            .fold(Vec::new(), |mut dirs, path| {
+                let path = PathBuf::from(path);
+                if !dirs.contains(&path) {
+                    dirs.push(path);
                 }
+                dirs
+            });
+        if install_dirs.is_empty() {
+            return Err(AppError::invalid_input("技能没有有效的安装路径").into());
+        }
 
-                // 复制文件
-                match fs::copy(&src_path, &dst_path) {
-                    Ok(bytes) => {
-                        log::debug!("已复制文件: {:?} ({} bytes)", file_name, bytes);
-                    }
-                    Err(e) => {
-                        // 提供详细的错误信息
-                        let error_msg = if e.raw_os_error() == Some(5) {
-                            format!(
-                                "复制文件失败（拒绝访问）\n文件: {:?}\n\n可能原因：\n1. 目标文件正在被其他程序使用\n2. 文件被设置为只读\n3. 权限不足\n4. 杀毒软件拦截\n\n建议：\n1. 关闭可能打开该文件的程序\n2. 检查文件是否为只读\n3. 以管理员权限运行\n\n原始错误: {}",
-                                file_name, e
-                            )
-                        } else {
-                            format!("复制文件失败\n源: {:?}\n目标: {:?}\n错误: {}", src_path, dst_path, e)
-                        };
-                        return Err(anyhow::anyhow!(error_msg));
-                    }
-                }
+        let repo = self.db.get_repositories()?
+            .into_iter()
+            .find(|r| r.url == skill.repository_url)
+            .ok_or_else(|| AppError::not_found("未找到对应的仓库记录"))?;
+        let (owner, repo_name, _) = Repository::from_github_url(&skill.repository_url)?;
+
+        fs_ops::remove_path(reinstall_base_dir.join(format!("{}_{}", owner, repo_name))).await?;
+
+        let result = self.reinstall_from_commit(&mut skill, &repo, &owner, &repo_name, &commit_sha, reinstall_base_dir, &install_dirs, locale, confirmation).await;
+
+        if let Err(e) = fs_ops::remove_path(reinstall_base_dir.join(format!("{}_{}", owner, repo_name))).await {
+            log::warn!("清理重新安装的下载目录失败: {}", e);
+        }
+        if let Some(store) = self.github.blob_store() {
+            if let Err(e) = store.remove_manifest(&BlobStore::manifest_key(reinstall_base_dir, &owner, &repo_name)) {
+                log::warn!("删除重新安装的清单失败: {}", e);
+            }
+            if let Err(e) = store.collect_garbage() {
+                log::warn!("回收 blob 失败: {}", e);
+            }
+        }
+        result?;
+
+        log::info!("技能已按 commit {} 重新安装: {}", commit_sha, skill.name);
+        Ok(skill)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn reinstall_from_commit(
+        &self,
+        skill: &mut Skill,
+        repo: &Repository,
+        owner: &str,
+        repo_name: &str,
+        commit_sha: &str,
+        reinstall_base_dir: &std::path::Path,
+        install_dirs: &[PathBuf],
+        locale: &str,
+        confirmation: Option<&str>,
+    ) -> Result<()> {
+        let (extract_dir, downloaded_sha) = self.github
+            .download_repository_archive(owner, repo_name, Some(commit_sha), reinstall_base_dir)
+            .await
+            .context(format!("下载 commit {} 失败", commit_sha))?;
+        if crate::services::github::is_commit_changed(commit_sha, &downloaded_sha) {
+            anyhow::bail!("下载的 commit {} 与安装时记录的 commit {} 不一致", downloaded_sha, commit_sha);
+        }
+        if repo.require_signed_commits {
+            let status = self.check_commit_signature(owner, repo_name, commit_sha).await?;
+            self.ensure_signed_commit(repo, commit_sha, Some(&status))?;
+        }
+
+        let source_dir = self.locate_skill_in_cache(&extract_dir, &skill.file_path)?;
+        self.reinstall_from_source(skill, &source_dir, commit_sha, install_dirs, locale, confirmation).await
+    }
+
+    /// 扫描 `source_dir`，通过与安装相同的检查（高危规则、最低评分、高风险确认）后重新写入所有安装目录
+    async fn reinstall_from_source(
+        &self,
+        skill: &mut Skill,
+        source_dir: &std::path::Path,
+        commit_sha: &str,
+        install_dirs: &[PathBuf],
+        locale: &str,
+        confirmation: Option<&str>,
+    ) -> Result<()> {
+        let scan_report = self.scan_directory_async(source_dir, &skill.id, locale).await?;
+        let scan_policy = self.scan_policy();
+        if scan_report.blocked && scan_policy.block_hard_triggers {
+            return Err(AppError::new(
+                ErrorKind::BlockedByScan,
+                format!("⛔ commit {} 的内容命中高危规则，已阻止重新安装：\n{}", commit_sha, scan_report.hard_trigger_issues.join("\n")),
+            ).into());
+        }
+        scan_policy.check_install_score(&skill.name, Some(scan_report.score), confirmation)?;
+        self.check_risky_install(&skill.name, Some(scan_report.level.as_str()), confirmation)?;
+
+        // 先检查所有安装目录都能写入，避免只恢复了一部分目录
+        for dir in install_dirs {
+            dir_access::check_dir(dir, true).ensure_ready()?;
+        }
+        self.replace_install_dirs(&skill.name, source_dir, install_dirs).await?;
+        for dir in install_dirs {
+            self.record_event(&skill.id, SkillEventKind::Reinstalled, Some(&dir.to_string_lossy()), Some(commit_sha));
+        }
+        if let Err(e) = self.prune_backups() {
+            log::warn!("清理更新备份失败: {}", e);
+        }
+
+        skill.security_score = Some(scan_report.score);
+        skill.security_level = Some(scan_report.level.as_str().to_string());
+        skill.security_issues = Some(
+            scan_report.issues.iter()
+                .map(|i| {
+                    let file_info = i.file_path.as_ref()
+                        .map(|f| format!("[{}] ", f))
+                        .unwrap_or_default();
+                    format!("{}{:?}: {}", file_info, i.severity, i.description)
+                })
+                .collect()
+        );
+        let scanned_at = Utc::now();
+        skill.scanned_at = Some(scanned_at);
+        self.save_scan_report(&skill.id, &scan_report, scanned_at);
+        self.db.save_skill(skill)?;
+        Ok(())
+    }
+
+    /// 重新写入安装目录：先把内容复制到每个安装目录旁的临时目录并校验，再把原目录备份到更新备份目录，
+    /// 最后逐个重命名替换。任一步骤失败时删除临时目录并恢复已替换的目录，不会只恢复一部分目录
+    async fn replace_install_dirs(&self, skill_name: &str, source_dir: &std::path::Path, install_dirs: &[PathBuf]) -> Result<()> {
+        let mut staged = Vec::new();
+        let result = match self.stage_install_dirs(skill_name, source_dir, install_dirs, &mut staged).await {
+            Ok(()) => swap_install_dirs(install_dirs, &staged),
+            Err(e) => Err(e),
+        };
+        for staging in staged {
+            if let Err(e) = fs_ops::remove_path(staging.clone()).await {
+                log::warn!("清理重新安装的临时目录失败: {:?}, 错误: {}", staging, e);
             }
         }
+        result
+    }
+
+    /// 复制并校验每个安装目录的新内容（写入 `staged`，由调用方清理），然后备份原目录
+    async fn stage_install_dirs(
+        &self,
+        skill_name: &str,
+        source_dir: &std::path::Path,
+        install_dirs: &[PathBuf],
+        staged: &mut Vec<PathBuf>,
+    ) -> Result<()> {
+        for dir in install_dirs {
+            let staging = reinstall_sibling(dir, "reinstall-new")?;
+            fs_ops::remove_path(staging.clone()).await?;
+            staged.push(staging.clone());
+            fs_ops::copy_dir_async(source_dir.to_path_buf(), staging.clone(), self.fs_context())
+                .await
+                .and_then(CopyReport::ensure_complete)
+                .and_then(|_| self.verify_installed_files(skill_name, source_dir, &staging))
+                .and_then(|()| fs_ops::normalize_skill_manifest(&staging, &self.github.manifest_names()).map(drop))
+                .context(format!("重新写入技能目录失败: {:?}", dir))?;
+        }
 
-        log::info!("目录复制完成: {:?}", dst);
+        // 原目录完整备份到更新备份目录，便于必要时人工回滚
+        let backup_root = self.backup_root().context("无法确定备份目录")?;
+        for dir in install_dirs.iter().filter(|dir| dir.exists()) {
+            let dir_name = dir.file_name().context("无效的目录名")?.to_string_lossy();
+            let backup_path = backup_root.join(skill_backups::backup_dir_name(&dir_name, Utc::now()));
+            fs_ops::copy_dir_async(dir.clone(), backup_path.clone(), self.fs_context())
+                .await
+                .and_then(CopyReport::ensure_complete)
+                .context(format!("无法为重新安装创建备份: {:?}", dir))?;
+            log::info!("重新安装前已备份: {:?} -> {:?}", dir, backup_path);
+        }
         Ok(())
     }
 }
+
+/// 与安装目录位于同一父目录的临时目录（重新安装时替换目录只需重命名）
+fn reinstall_sibling(dir: &std::path::Path, suffix: &str) -> Result<PathBuf> {
+    let name = dir.file_name().context("无效的安装路径")?.to_string_lossy();
+    Ok(dir.with_file_name(format!(".{}.{}", name, suffix)))
+}
+
+/// 逐个用临时目录替换安装目录，失败时恢复已替换的目录；全部成功后删除原目录
+fn swap_install_dirs(install_dirs: &[PathBuf], staged: &[PathBuf]) -> Result<()> {
+    let mut swapped = Vec::new();
+    for (dir, staging) in install_dirs.iter().zip(staged) {
+        match swap_dir(dir, staging) {
+            Ok(previous) => swapped.push((dir, previous)),
+            Err(e) => {
+                for (dir, previous) in swapped.into_iter().rev() {
+                    restore_dir(dir, previous);
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    for previous in swapped.into_iter().filter_map(|(_, previous)| previous) {
+        if let Err(e) = fs_ops::remove_dir_all(&previous) {
+            log::warn!("删除被替换的技能目录失败: {:?}, 错误: {}", previous, e);
+        }
+    }
+    Ok(())
+}
+
+/// 用 `staging` 替换 `dir`，返回原目录重命名后的位置（原目录不存在时为 None）
+fn swap_dir(dir: &std::path::Path, staging: &std::path::Path) -> Result<Option<PathBuf>> {
+    let previous = if dir.exists() {
+        let previous = reinstall_sibling(dir, "reinstall-old")?;
+        let _ = fs_ops::remove_dir_all(&previous);
+        std::fs::rename(dir, &previous).context(format!("无法移动原技能目录: {:?}", dir))?;
+        Some(previous)
+    } else {
+        std::fs::create_dir_all(dir.parent().context("无效的安装路径")?)?;
+        None
+    };
+
+    if let Err(e) = std::fs::rename(staging, dir) {
+        if let Some(previous) = &previous {
+            let _ = std::fs::rename(previous, dir);
+        }
+        return Err(anyhow::Error::from(e).context(format!("无法替换技能目录: {:?}", dir)));
+    }
+    Ok(previous)
+}
+
+/// 撤销 [`swap_dir`]：删除替换进来的目录并移回原目录
+fn restore_dir(dir: &std::path::Path, previous: Option<PathBuf>) {
+    if let Err(e) = fs_ops::remove_dir_all(dir) {
+        log::warn!("删除替换进来的技能目录失败: {:?}, 错误: {}", dir, e);
+    }
+    if let Some(previous) = previous {
+        match std::fs::rename(&previous, dir) {
+            Ok(()) => log::warn!("重新安装失败，已恢复原技能目录: {:?}", dir),
+            Err(e) => log::error!("重新安装失败，恢复原技能目录失败: {:?} -> {:?}: {}", previous, dir, e),
+        }
+    }
+}
+
+/// 安装与更新需要从仓库下载技能，目前只支持 GitHub 仓库来源
+fn ensure_repository_source(skill: &Skill) -> Result<()> {
+    if !skill.source_type.supports_updates() {
+        return Err(AppError::invalid_input(format!(
+            "该技能的来源类型（{}）不支持从仓库安装或更新",
+            skill.source_type.as_str()
+        )).into());
+    }
+    Ok(())
+}
+
+/// 导入技能清单时固定的内容哈希（JSON，技能 ID -> SHA256）
+const CONTENT_PINS_KEY: &str = "skill_content_pins";
+
+/// 校验技能目录的内容哈希（按安装后的清单名计算，与导出时对安装目录计算的哈希一致），不一致时说明上游内容在导出清单后被改写
+fn ensure_content_hash(skill_name: &str, dir: &std::path::Path, expected: &str, names: &ManifestNames) -> Result<()> {
+    let actual = fs_ops::installed_dir_hash(dir, names)?;
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(AppError::new(
+            ErrorKind::IntegrityMismatch,
+            format!(
+                "技能 {} 的内容与技能清单中固定的哈希不一致，上游可能在导出清单后被修改或强制推送\n预期: {}\n实际: {}",
+                skill_name, expected, actual
+            ),
+        ).into());
+    }
+    Ok(())
+}
+
+/// 技能现存的安装目录（兼容只有 local_path 的旧记录）
+fn installed_dirs(skill: &Skill) -> Vec<PathBuf> {
+    let paths = match skill.local_paths.as_deref() {
+        Some(paths) if !paths.is_empty() => paths.to_vec(),
+        _ => skill.local_path.iter().cloned().collect(),
+    };
+    paths.into_iter()
+        .filter(|path| !path.starts_with("__staging__:"))
+        .map(PathBuf::from)
+        .filter(|path| path.is_dir())
+        .collect()
+}
+
+/// 删除 base_dir 下的子目录（cutoff 不为 None 时只删除最后修改早于 cutoff 的），
+/// 返回已删除的目录及其大小；删除失败的目录记录警告后跳过
+fn remove_temp_dirs(base_dir: &std::path::Path, cutoff: Option<std::time::SystemTime>) -> Result<Vec<(PathBuf, u64)>> {
+    if !base_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut removed = Vec::new();
+    for entry in std::fs::read_dir(base_dir).context(format!("无法读取目录: {:?}", base_dir))? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        let expired = cutoff.is_none_or(|cutoff| metadata.modified().is_ok_and(|modified| modified < cutoff));
+        if !metadata.is_dir() || !expired {
+            continue;
+        }
+
+        let dir = entry.path();
+        let size = fs_ops::dir_size(&dir).unwrap_or(0);
+        if let Err(e) = fs_ops::remove_dir_all(&dir) {
+            log::warn!("删除临时目录失败: {:?}, 错误: {}", dir, e);
+            continue;
+        }
+        log::info!("已删除临时目录: {:?}", dir);
+        removed.push((dir, size));
+    }
+    Ok(removed)
+}
+
+/// 说明文档预览中单个文件的大小上限
+const MAX_README_FILE_BYTES: u64 = 1024 * 1024;
+/// 说明文档预览最多附带的引用文件数
+const MAX_README_REFERENCES: usize = 20;
+
+lazy_static::lazy_static! {
+    /// Markdown 链接中指向 .md 文件的目标，如 [用法](docs/usage.md#示例)
+    static ref MARKDOWN_LINK: regex::Regex = regex::Regex::new(r"\]\(\s*<?([^)\s#>]+\.md)>?(#[^)]*)?\s*\)").unwrap();
+}
+
+/// 提取 Markdown 中引用的本地 .md 文件（相对路径，不允许跳出技能目录）
+fn referenced_markdown_files(content: &str) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    for captures in MARKDOWN_LINK.captures_iter(content) {
+        let link = captures[1].trim_start_matches("./");
+        let path = std::path::Path::new(link);
+        let is_local = !link.contains("://")
+            && path.components().all(|c| matches!(c, std::path::Component::Normal(_)));
+        if is_local && link != "SKILL.md" && !files.iter().any(|f| f == link) {
+            files.push(link.to_string());
+        }
+    }
+    files.truncate(MAX_README_REFERENCES);
+    files
+}
+
+/// 读取本地技能目录中的 SKILL.md 及其引用的 Markdown 文件（跳过缺失或过大的文件）
+fn read_readme_files(dir: &std::path::Path, manifest_names: &ManifestNames) -> Vec<(String, String)> {
+    let read = |path: &str| -> Option<String> {
+        let file = dir.join(path);
+        let size = std::fs::metadata(&file).ok()?.len();
+        if size > MAX_README_FILE_BYTES {
+            log::warn!("文档过大，已跳过: {:?}", file);
+            return None;
+        }
+        std::fs::read_to_string(&file)
+            .map_err(|e| log::warn!("读取文档失败: {:?}, 错误: {}", file, e))
+            .ok()
+    };
+
+    let Some(manifest) = fs_ops::find_skill_manifest(dir, manifest_names) else {
+        return Vec::new();
+    };
+    let manifest_name = manifest.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let Some(skill_md) = read(&manifest_name) else {
+        return Vec::new();
+    };
+    let references = referenced_markdown_files(&skill_md);
+    let mut files = vec![(manifest_name, skill_md)];
+    files.extend(references.into_iter().filter_map(|path| read(&path).map(|content| (path, content))));
+    files
+}
+
+/// 写操作日志守卫：创建时写入 pending_operations 表，操作结束（成功或已处理的失败）时随守卫释放删除；
+/// 应用在操作中途退出时记录会保留，下次启动由 [`SkillManager::recover_pending_operations`] 回滚或完成
+struct OperationJournal<'a> {
+    db: &'a Database,
+    op: PendingOperation,
+}
+
+impl<'a> OperationJournal<'a> {
+    fn begin(db: &'a Database, op: PendingOperation) -> Result<Self> {
+        db.save_pending_operation(&op).context("写入操作日志失败")?;
+        Ok(Self { db, op })
+    }
+
+    /// 记录即将写入的备份位置（应在移动或复制原目录之前调用）
+    fn set_backup_dir(&mut self, backup_dir: &std::path::Path) -> Result<()> {
+        self.op.backup_dir = Some(backup_dir.to_string_lossy().to_string());
+        self.db.save_pending_operation(&self.op).context("更新操作日志失败")
+    }
+
+    fn advance(&mut self, step: OperationStep) -> Result<()> {
+        self.op.step = step;
+        self.db.save_pending_operation(&self.op).context("更新操作日志失败")
+    }
+}
+
+impl Drop for OperationJournal<'_> {
+    fn drop(&mut self) {
+        if let Err(e) = self.db.delete_pending_operation(&self.op.id) {
+            log::warn!("删除操作日志失败: {}, 错误: {}", self.op.id, e);
+        }
+    }
+}
+
+/// 将技能标记为已安装到 install_dir：更新 local_path（向后兼容）与 local_paths（支持多路径安装）
+fn mark_installed(skill: &mut Skill, install_dir: &str, commit_sha: Option<String>) {
+    skill.local_path = Some(install_dir.to_string());
+
+    let mut paths = skill.local_paths.clone().unwrap_or_default();
+    if !paths.iter().any(|p| p == install_dir) {
+        paths.push(install_dir.to_string());
+    }
+    skill.local_paths = Some(paths);
+
+    skill.installed = true;
+    skill.installed_at = Some(Utc::now());
+    skill.installed_commit_sha = commit_sha;
+}
+
+/// 扫描已安装的技能目录：使用上次扫描的缓存，只重新扫描有变化的文件，并保存新的缓存
+/// 缓存读写失败不影响扫描结果
+pub fn scan_directory_cached(
+    db: &Database,
+    scanner: &SecurityScanner,
+    dir: &std::path::Path,
+    skill_id: &str,
+    locale: &str,
+) -> Result<crate::models::SecurityReport> {
+    scan_directory_with_changes(db, scanner, dir, skill_id, locale).map(|(report, _)| report)
+}
+
+/// 同 [`scan_directory_cached`]，同时返回与上次扫描相比的文件变化（没有上次的缓存时为 None）
+pub fn scan_directory_with_changes(
+    db: &Database,
+    scanner: &SecurityScanner,
+    dir: &std::path::Path,
+    skill_id: &str,
+    locale: &str,
+) -> Result<(crate::models::SecurityReport, Option<FileChanges>)> {
+    let dir_str = dir.to_str().context(format!("路径包含无效字符: {:?}", dir))?;
+    let previous = db.get_scan_cache(dir_str).unwrap_or_else(|e| {
+        log::warn!("读取扫描缓存失败: {:?}, 错误: {}", dir, e);
+        None
+    });
+
+    let scan = scanner.scan_directory_incremental(dir_str, skill_id, locale, previous.as_ref())?;
+    log::info!(
+        "增量扫描完成: {:?}, 重新扫描 {} 个文件, 复用 {} 个文件的缓存结果",
+        dir, scan.rescanned_files, scan.reused_files
+    );
+
+    if let Err(e) = db.save_scan_cache(dir_str, &scan.cache) {
+        log::warn!("保存扫描缓存失败: {:?}, 错误: {}", dir, e);
+    }
+    let changes = previous.map(|previous| scan.cache.changes_since(&previous));
+    Ok((scan.report, changes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::InstallStatus;
+    use crate::services::settings::LowScoreAction;
+    use crate::models::LevelThresholds;
+    use tempfile::tempdir;
+
+    /// 仓库缓存中包含一个有风险的技能，阈值调高后扫描结果为高风险级别
+    fn risky_skill_manager(root: &std::path::Path) -> (SkillManager, Skill) {
+        risky_skill_manager_with(root, GitHubService::new())
+    }
+
+    fn risky_skill_manager_with(root: &std::path::Path, github: GitHubService) -> (SkillManager, Skill) {
+        let db = Arc::new(Database::new(root.join("agent-skills.db")).unwrap());
+        let mut repo = Repository::new("https://github.com/owner/skills".to_string(), "skills".to_string());
+        let extracted = root.join("cache/extracted");
+        let skill_src = extracted.join("owner-skills-abc123/risky");
+        std::fs::create_dir_all(&skill_src).unwrap();
+        std::fs::write(skill_src.join("SKILL.md"), "---\nname: risky\ndescription: test\n---\nRun setup.sh first.\n").unwrap();
+        std::fs::write(skill_src.join("setup.sh"), "sudo apt-get install -y jq\ncurl -o /tmp/data.json https://example.com/data.json\n").unwrap();
+        repo.cache_path = Some(extracted.to_string_lossy().to_string());
+        db.add_repository(&repo).unwrap();
+        let skill = Skill::new("risky".to_string(), repo.url.clone(), "risky".to_string());
+        db.save_skill(&skill).unwrap();
+
+        let manager = SkillManager::new(db, Arc::new(github));
+        let mut settings = Settings::default();
+        settings.directories.skills_dir = Some(root.join("skills"));
+        settings.cache.backup_dir = Some(root.join("backups"));
+        settings.scan_policy.confirm_risky_installs = true;
+        settings.scan_policy.level_thresholds = LevelThresholds { safe: 100, low: 99, medium: 98, high: 97 };
+        manager.apply_settings(&settings);
+        (manager, skill)
+    }
+
+    fn block_reasons<T>(result: Result<T>) -> Vec<BlockReason> {
+        result.err().and_then(|e| e.downcast::<InstallBlocked>().ok()).map(|blocked| blocked.reasons).unwrap_or_default()
+    }
+
+    fn confirmation_required<T>(result: Result<T>) -> bool {
+        block_reasons(result).iter().any(|reason| matches!(reason, BlockReason::ConfirmationRequired { .. }))
+    }
+
+    #[tokio::test]
+    async fn test_install_requires_confirmation_for_risky_skill() {
+        let dir = tempdir().unwrap();
+        let (manager, skill) = risky_skill_manager(dir.path());
+        let skill_dir = dir.path().join("skills/risky");
+
+        assert!(confirmation_required(manager.install_skill(&skill.id, None, false, None).await));
+        assert!(confirmation_required(manager.install_skill(&skill.id, None, false, Some("wrong")).await));
+        assert!(!skill_dir.exists(), "被阻止的安装应清理已复制的文件");
+
+        let result = manager.install_skill(&skill.id, None, false, Some("risky")).await.unwrap();
+        assert_eq!(result.status, InstallStatus::Installed);
+        assert!(skill_dir.join("SKILL.md").exists());
+
+        // 同步（跳过扫描）按保存的安全等级检查
+        let stored = manager.db.get_skills().unwrap().into_iter().find(|s| s.id == skill.id).unwrap();
+        assert!(matches!(stored.security_level.as_deref(), Some("High" | "Critical")));
+        assert!(confirmation_required(manager.install_skill(&skill.id, None, true, None).await));
+        let result = manager.install_skill(&skill.id, None, true, Some("risky")).await.unwrap();
+        assert_eq!(result.status, InstallStatus::Installed);
+    }
+
+    #[tokio::test]
+    async fn test_min_install_score_applies_to_every_install_path() {
+        let dir = tempdir().unwrap();
+        let (manager, skill) = risky_skill_manager(dir.path());
+        let mut settings = Settings::default();
+        settings.directories.skills_dir = Some(dir.path().join("skills"));
+        settings.scan_policy.min_install_score = 100;
+        settings.scan_policy.low_score_action = LowScoreAction::Confirm;
+        manager.apply_settings(&settings);
+
+        let low_score = |reasons: Vec<BlockReason>| reasons.iter().any(|reason| matches!(reason, BlockReason::LowScore { confirmable: true, .. }));
+        assert!(low_score(block_reasons(manager.install_skill(&skill.id, None, false, None).await)));
+        assert!(manager.install_skill(&skill.id, None, false, Some("risky")).await.is_ok());
+
+        // 同步（跳过扫描）按保存的评分检查
+        assert!(low_score(block_reasons(manager.install_skill(&skill.id, None, true, None).await)));
+        assert!(manager.install_skill(&skill.id, None, true, Some("risky")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_reinstall_keeps_installed_files_when_blocked_or_copy_fails() {
+        let dir = tempdir().unwrap();
+        let (manager, skill) = risky_skill_manager(dir.path());
+        manager.install_skill(&skill.id, None, false, Some("risky")).await.unwrap();
+        let mut skill = manager.db.get_skills().unwrap().into_iter().find(|s| s.id == skill.id).unwrap();
+        let source = dir.path().join("cache/extracted/owner-skills-abc123/risky");
+        let skill_dir = dir.path().join("skills/risky");
+        let install_dirs = vec![skill_dir.clone()];
+        std::fs::write(skill_dir.join("setup.sh"), "echo tampered\n").unwrap();
+        let installed_files = || {
+            let mut names: Vec<String> = std::fs::read_dir(dir.path().join("skills")).unwrap()
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+                .collect();
+            names.sort();
+            (names, std::fs::read_to_string(skill_dir.join("setup.sh")).unwrap())
+        };
+        let before = installed_files();
+
+        // 高风险技能未确认时阻止重新安装，不改动安装目录
+        let blocked = manager.reinstall_from_source(&mut skill, &source, "abc123", &install_dirs, "zh", None).await;
+        assert!(confirmation_required(blocked));
+        assert_eq!(installed_files(), before);
+
+        // 复制中途失败（取消）时删除临时目录，原安装目录保持不变
+        manager.request_cancel();
+        assert!(manager.reinstall_from_source(&mut skill, &source, "abc123", &install_dirs, "zh", Some("risky")).await.is_err());
+        assert_eq!(installed_files(), before);
+
+        drop(manager.begin_operation().await);
+        manager.reinstall_from_source(&mut skill, &source, "abc123", &install_dirs, "zh", Some("risky")).await.unwrap();
+        assert_eq!(installed_files().0, before.0);
+        assert!(installed_files().1.contains("sudo apt-get install"));
+        let backups = skill_backups::list_backups(&manager.backup_root().unwrap()).unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(std::fs::read_to_string(PathBuf::from(&backups[0].path).join("setup.sh")).unwrap(), "echo tampered\n");
+    }
+
+    #[test]
+    fn test_recover_pending_operations_at_each_step() {
+        let dir = tempdir().unwrap();
+        let (manager, skill) = risky_skill_manager(dir.path());
+        let target = dir.path().join("skills/risky");
+        let backup = dir.path().join("backups/risky");
+        let write_dir = |path: &Path, content: &str| {
+            std::fs::create_dir_all(path).unwrap();
+            std::fs::write(path.join("SKILL.md"), content).unwrap();
+        };
+        let manifest = || std::fs::read_to_string(target.join("SKILL.md")).ok();
+        let recover = |kind: OperationKind, step: OperationStep, backup_dir: Option<&Path>, commit_sha: Option<&str>| {
+            let mut op = PendingOperation::new(&skill.id, kind, &target.to_string_lossy());
+            op.step = step;
+            op.backup_dir = backup_dir.map(|path| path.to_string_lossy().to_string());
+            op.commit_sha = commit_sha.map(str::to_string);
+            manager.db.save_pending_operation(&op).unwrap();
+            assert_eq!(manager.recover_pending_operations().unwrap(), 1);
+            assert!(manager.db.get_pending_operations().unwrap().is_empty());
+            manager.db.get_skills().unwrap().into_iter().find(|s| s.id == skill.id).unwrap()
+        };
+
+        // 安装中途退出：删除写入一半的目录，技能保持未安装
+        write_dir(&target, "partial");
+        let stored = recover(OperationKind::Install, OperationStep::Started, None, Some("abc123"));
+        assert!(!target.exists());
+        assert!(!stored.installed);
+
+        // 安装文件已写入并通过校验：补写数据库
+        write_dir(&target, "v1");
+        let stored = recover(OperationKind::Install, OperationStep::Copied, None, Some("abc123"));
+        assert_eq!(manifest().as_deref(), Some("v1"));
+        assert!(stored.installed);
+        assert_eq!(stored.local_path.as_deref(), Some(target.to_string_lossy().as_ref()));
+        assert_eq!(stored.installed_commit_sha.as_deref(), Some("abc123"));
+
+        // 更新刚开始、尚未备份：安装目录保持原样
+        recover(OperationKind::Update, OperationStep::Started, None, Some("def456"));
+        assert_eq!(manifest().as_deref(), Some("v1"));
+
+        // 原目录已移动到备份位置、尚未写入新版本：移回备份
+        std::fs::create_dir_all(backup.parent().unwrap()).unwrap();
+        std::fs::rename(&target, &backup).unwrap();
+        recover(OperationKind::Update, OperationStep::Started, Some(&backup), Some("def456"));
+        assert_eq!(manifest().as_deref(), Some("v1"));
+        assert!(!backup.exists());
+
+        // 备份完成后写入一半：删除写入一半的目录并从备份恢复
+        write_dir(&backup, "v1");
+        write_dir(&target, "partial");
+        let stored = recover(OperationKind::Update, OperationStep::BackedUp, Some(&backup), Some("def456"));
+        assert_eq!(manifest().as_deref(), Some("v1"));
+        assert_eq!(stored.installed_commit_sha.as_deref(), Some("abc123"));
+
+        // 更新文件已写入并通过校验：保留新版本并补写数据库
+        write_dir(&backup, "v1");
+        write_dir(&target, "v2");
+        let stored = recover(OperationKind::Update, OperationStep::Copied, Some(&backup), Some("def456"));
+        assert_eq!(manifest().as_deref(), Some("v2"));
+        assert_eq!(stored.installed_commit_sha.as_deref(), Some("def456"));
+    }
+
+    #[tokio::test]
+    async fn test_install_renames_alternate_manifest_to_skill_md() {
+        let dir = tempdir().unwrap();
+        let (manager, skill) = risky_skill_manager(dir.path());
+        let source = dir.path().join("cache/extracted/owner-skills-abc123/risky");
+        std::fs::rename(source.join("SKILL.md"), source.join("SKILLS.md")).unwrap();
+        let mut settings = Settings::default();
+        settings.directories.skills_dir = Some(dir.path().join("skills"));
+        settings.discovery.alternate_manifest_names = vec!["SKILLS.md".to_string()];
+        manager.apply_settings(&settings);
+
+        manager.install_skill(&skill.id, None, false, Some("risky")).await.unwrap();
+        let skill_dir = dir.path().join("skills/risky");
+        assert!(skill_dir.join(SKILL_MANIFEST_NAME).exists());
+        assert!(!skill_dir.join("SKILLS.md").exists());
+
+        // 改名后的清单与缓存中的清单视为同一文件：导出的哈希可校验缓存内容，也不算本地修改
+        let names = manager.github.manifest_names();
+        let exported = fs_ops::dir_hash(&skill_dir).unwrap();
+        assert!(ensure_content_hash(&skill.name, &source, &exported, &names).is_ok());
+        assert!(SkillManager::detect_local_modifications(&skill_dir, &source, &names).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_local_scan_roots_exclusions_and_skipped_counts() {
+        let dir = tempdir().unwrap();
+        let (manager, _) = risky_skill_manager(dir.path());
+        let write_skill = |path: &Path, name: &str| {
+            std::fs::create_dir_all(path).unwrap();
+            std::fs::write(path.join("SKILL.md"), format!("---\nname: {}\ndescription: test\n---\n", name)).unwrap();
+        };
+        write_skill(&dir.path().join("a/one"), "one");
+        write_skill(&dir.path().join("a/node_modules"), "by-name");
+        write_skill(&dir.path().join("b/two"), "two");
+        write_skill(&dir.path().join("b/private"), "by-prefix");
+        write_skill(&dir.path().join("c/three"), "root-by-prefix");
+        let mut settings = Settings::default();
+        settings.directories.skills_dir = Some(dir.path().join("skills"));
+        settings.directories.local_scan_roots = vec![dir.path().join("a"), dir.path().join("b"), dir.path().join("c")];
+        settings.directories.local_scan_exclude = vec![
+            "node_modules".to_string(),
+            dir.path().join("b/private").to_string_lossy().to_string(),
+            dir.path().join("c").to_string_lossy().to_string(),
+        ];
+        manager.apply_settings(&settings);
+
+        // 按名称排除的子目录、按路径前缀排除的子目录与扫描目录都不导入
+        let result = manager.scan_local_dirs(Vec::new(), || false).unwrap();
+        let mut names: Vec<_> = result.skills.iter().map(|skill| skill.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["one", "two"]);
+        assert_eq!((result.scanned_dirs, result.excluded_dirs), (2, 3));
+        assert!(!result.timed_out);
+        assert_eq!((result.skipped_dirs, result.skipped_entries), (0, 0));
+
+        // 超时发生在第一个目录的第二个目录项：该目录剩余 1 项，之后的 b、c 与默认目录共 3 个目录未扫描
+        let checks = std::cell::Cell::new(0);
+        let result = manager.scan_local_dirs(Vec::new(), || {
+            checks.set(checks.get() + 1);
+            checks.get() > 2
+        }).unwrap();
+        assert!(result.timed_out);
+        assert_eq!(result.scanned_dirs, 1);
+        assert_eq!((result.skipped_dirs, result.skipped_entries), (3, 1));
+    }
+
+    /// 对所有请求返回 404 的本地 API 服务
+    fn serve_not_found() -> String {
+        use std::io::Write;
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request_line = String::new();
+                let _ = std::io::BufRead::read_line(&mut std::io::BufReader::new(&stream), &mut request_line);
+                let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_reinstall_skill_keeps_install_when_download_or_scan_fails() {
+        let dir = tempdir().unwrap();
+        let (manager, skill) = risky_skill_manager_with(dir.path(), GitHubService::with_api_base(serve_not_found()));
+        manager.install_skill(&skill.id, None, false, Some("risky")).await.unwrap();
+        let mut skill = manager.db.get_skills().unwrap().into_iter().find(|s| s.id == skill.id).unwrap();
+        skill.installed_commit_sha = Some("abc123".to_string());
+        manager.db.save_skill(&skill).unwrap();
+        let skill_dir = dir.path().join("skills/risky");
+        std::fs::write(skill_dir.join("setup.sh"), "echo local\n").unwrap();
+        let skill_id = skill.id.clone();
+        let snapshot = || {
+            let stored = manager.db.get_skills().unwrap().into_iter().find(|s| s.id == skill_id).unwrap();
+            (fs_ops::dir_hash(&skill_dir).unwrap(), stored.local_paths, stored.installed_commit_sha, stored.security_score)
+        };
+        let before = snapshot();
+
+        // 下载失败：安装目录与技能记录保持不变，也不产生备份
+        let reinstall_dir = dir.path().join("reinstall");
+        assert!(manager.reinstall_skill_into(&skill.id, &reinstall_dir, "zh", Some("risky")).await.is_err());
+        assert_eq!(snapshot(), before);
+        assert!(!reinstall_dir.join("owner_skills").exists());
+
+        // 扫描失败（下载的内容中没有该技能目录）：同样不改动安装目录
+        let missing = dir.path().join("reinstall/owner_skills/extracted/missing");
+        assert!(manager.reinstall_from_source(&mut skill, &missing, "abc123", std::slice::from_ref(&skill_dir), "zh", Some("risky")).await.is_err());
+        assert_eq!(snapshot(), before);
+        assert!(skill_backups::list_backups(&manager.backup_root().unwrap()).unwrap().is_empty());
+    }
+
+    /// 将仓库缓存中的技能手动安装到两个目录（不经过扫描），返回安装目录
+    fn install_directly(manager: &SkillManager, root: &Path, skill: &Skill) -> Vec<PathBuf> {
+        let source = root.join("cache/extracted/owner-skills-abc123/risky");
+        let dirs = vec![root.join("skills/risky"), root.join("tool/skills/risky")];
+        for dir in &dirs {
+            fs_ops::copy_dir(&source, dir, &FsContext::default()).unwrap();
+        }
+        let mut skill = skill.clone();
+        skill.installed = true;
+        skill.local_path = Some(dirs[0].to_string_lossy().to_string());
+        skill.local_paths = Some(dirs.iter().map(|dir| dir.to_string_lossy().to_string()).collect());
+        manager.db.save_skill(&skill).unwrap();
+        dirs
+    }
+
+    #[test]
+    fn test_disable_skill_renames_manifests() {
+        let dir = tempdir().unwrap();
+        let (manager, skill) = risky_skill_manager(dir.path());
+        let dirs = install_directly(&manager, dir.path(), &skill);
+
+        assert_eq!(manager.disable_skill(&skill.id, "test").unwrap(), 2);
+        for dir in &dirs {
+            assert!(!dir.join("SKILL.md").exists());
+            assert!(dir.join("SKILL.md.disabled").exists());
+            assert!(dir.join("setup.sh").exists());
+        }
+
+        // 已禁用的目录不再计数
+        assert_eq!(manager.disable_skill(&skill.id, "test").unwrap(), 0);
+        assert!(manager.disable_skill("missing", "test").is_err());
+    }
+
+    #[test]
+    fn test_quarantine_skill_moves_install_dirs() {
+        let dir = tempdir().unwrap();
+        let (manager, skill) = risky_skill_manager(dir.path());
+        let quarantine_root = dir.path().join("quarantine");
+
+        let check = |quarantine_dir: &Path, dirs: &[PathBuf]| {
+            for (index, dir) in dirs.iter().enumerate() {
+                assert!(!dir.exists(), "隔离后原安装目录应被移走: {:?}", dir);
+                let moved = quarantine_dir.join(index.to_string());
+                assert!(moved.join("SKILL.md").exists());
+                assert!(moved.join("setup.sh").exists());
+            }
+            let stored = manager.db.get_skills().unwrap().into_iter().find(|s| s.id == skill.id).unwrap();
+            assert!(!stored.installed);
+            assert!(stored.local_path.is_none() && stored.local_paths.is_none());
+        };
+
+        let dirs = install_directly(&manager, dir.path(), &skill);
+        let quarantine_dir = manager
+            .quarantine_skill_into(&skill.id, "test", &quarantine_root, |from, to| std::fs::rename(from, to))
+            .unwrap();
+        assert!(quarantine_dir.starts_with(&quarantine_root));
+        check(&quarantine_dir, &dirs);
+
+        // 无法重命名（跨文件系统）时复制后删除原目录
+        std::fs::remove_dir_all(&quarantine_root).unwrap();
+        let dirs = install_directly(&manager, dir.path(), &skill);
+        let quarantine_dir = manager
+            .quarantine_skill_into(&skill.id, "test", &quarantine_root, |_, _| Err(std::io::Error::other("cross-device link")))
+            .unwrap();
+        check(&quarantine_dir, &dirs);
+    }
+}
@@ -1,7 +1,7 @@
 import { useEffect, useLayoutEffect, useMemo, useRef, useState } from "react";
 import { useInstalledSkills, useUninstallSkill, useUninstallSkillPath } from "../hooks/useSkills";
 import { useSkillTranslation, TranslatedSkillType } from "../hooks/useTranslatedSkills";
-import { Skill } from "../types";
+import { InstallResult, Skill } from "../types";
 import { SecurityReport } from "../types/security";
 import {
   Trash2,
@@ -21,7 +21,7 @@ import {
 import { useTranslation } from "react-i18next";
 import { invoke } from "@tauri-apps/api/core";
 import { openPath } from "@tauri-apps/plugin-opener";
-import { formatRepositoryTag } from "../lib/utils";
+import { formatInstallBlock, formatRepositoryTag } from "../lib/utils";
 import { CyberSelect, type CyberSelectOption } from "./ui/CyberSelect";
 import { useQueryClient, useMutation } from "@tanstack/react-query";
 import { api } from "../lib/api";
@@ -120,14 +120,21 @@ export function InstalledSkillsPage() {
   const scanMutation = useMutation({
     mutationFn: async () => {
       setIsScanning(true);
-      const localSkills = await api.scanLocalSkills();
-      return localSkills;
+      return api.scanLocalSkills();
     },
-    onSuccess: (localSkills) => {
+    onSuccess: (result) => {
       queryClient.invalidateQueries({ queryKey: ["skills", "installed"] });
       queryClient.invalidateQueries({ queryKey: ["skills"] });
       queryClient.invalidateQueries({ queryKey: ["scanResults"] });
-      appToast.success(t("skills.installedPage.scanCompleted", { count: localSkills.length }));
+      appToast.success(t("skills.installedPage.scanCompleted", { count: result.skills.length }));
+      if (result.timed_out) {
+        appToast.warning(
+          t("skills.installedPage.scanTimedOut", {
+            count: result.skipped_entries,
+            dirs: result.skipped_dirs,
+          })
+        );
+      }
     },
     onError: (error: any) => {
       appToast.error(t("skills.installedPage.scanFailed", { error: error.message }));
@@ -517,10 +524,13 @@ export function InstalledSkillsPage() {
           if (pendingInstall && selectedPaths.length > 0) {
             try {
               for (const path of selectedPaths) {
-                await invoke("confirm_skill_installation", {
+                const result = await invoke<InstallResult>("confirm_skill_installation", {
                   skillId: pendingInstall.skill.id,
                   installPath: path,
                 });
+                if (result.status === "blocked") {
+                  throw new Error(formatInstallBlock(result, pendingInstall.skill.name, t));
+                }
                 addRecentInstallPath(path);
               }
               await queryClient.refetchQueries({ queryKey: ["skills"] });
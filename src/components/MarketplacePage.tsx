@@ -8,7 +8,7 @@ import {
   useDeleteSkill,
 } from "../hooks/useSkills";
 import { useSkillTranslation, TranslatedSkill } from "../hooks/useTranslatedSkills";
-import { Skill } from "../types";
+import { InstallResult, Skill } from "../types";
 import { SecurityReport } from "../types/security";
 import {
   Trash2,
@@ -21,7 +21,7 @@ import {
 } from "lucide-react";
 import { useTranslation } from "react-i18next";
 import { openPath } from "@tauri-apps/plugin-opener";
-import { formatRepositoryTag } from "../lib/utils";
+import { formatInstallBlock, formatRepositoryTag } from "../lib/utils";
 import { invoke } from "@tauri-apps/api/core";
 import { CyberSelect, type CyberSelectOption } from "./ui/CyberSelect";
 import { appToast } from "@/lib/toast";
@@ -415,10 +415,13 @@ export function MarketplacePage({ onNavigateToRepositories }: MarketplacePagePro
             try {
               // 循环安装到每个选中的路径
               for (const path of selectedPaths) {
-                await invoke("confirm_skill_installation", {
+                const result = await invoke<InstallResult>("confirm_skill_installation", {
                   skillId: pendingInstall.skill.id,
                   installPath: path,
                 });
+                if (result.status === "blocked") {
+                  throw new Error(formatInstallBlock(result, pendingInstall.skill.name, t));
+                }
                 addRecentInstallPath(path);
               }
               await queryClient.refetchQueries({ queryKey: ["skills"] });
@@ -59,7 +59,7 @@ export function OverviewPage() {
 
       try {
         const localSkills = await api.scanLocalSkills();
-        localSkillsCount = localSkills.length;
+        localSkillsCount = localSkills.skills.length;
         await queryClient.refetchQueries({ queryKey: ["skills", "installed"] });
         await queryClient.refetchQueries({ queryKey: ["skills"] });
       } catch (error: any) {
@@ -3,6 +3,7 @@ import { Info, Github, RefreshCw, ExternalLink, Hash, Languages, Network, Eye, E
 import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { appToast } from "@/lib/toast";
+import { getErrorMessage } from "@/lib/utils";
 import { useUpdate } from "../contexts/UpdateContext";
 import { GroupCard, GroupCardItem } from "./ui/GroupCard";
 
@@ -143,6 +144,14 @@ interface ProxyConfig {
   port: number;
   username: string | null;
   password: string | null;
+  no_proxy?: string[];
+  overrides?: ProxyOverride[];
+  test_url?: string;
+}
+
+interface ProxyOverride {
+  host: string;
+  proxy_url: string | null;
 }
 
 export function SettingsPage() {
@@ -225,7 +234,7 @@ export function SettingsPage() {
       appToast.success(t("settings.proxy.saved"));
     } catch (error) {
       console.error("Failed to save proxy config:", error);
-      appToast.error(t("settings.proxy.saveFailed", { error: String(error) }));
+      appToast.error(t("settings.proxy.saveFailed", { error: getErrorMessage(error) }));
     } finally {
       setIsSavingProxy(false);
     }
@@ -243,7 +252,7 @@ export function SettingsPage() {
       appToast.success(t("settings.proxy.testSuccess"));
     } catch (error) {
       console.error("Proxy test failed:", error);
-      appToast.error(t("settings.proxy.testFailed", { error: String(error) }));
+      appToast.error(t("settings.proxy.testFailed", { error: getErrorMessage(error) }));
     } finally {
       setIsTestingProxy(false);
     }
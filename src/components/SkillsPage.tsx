@@ -15,6 +15,7 @@ import { useTranslation } from "react-i18next";
 import { invoke } from "@tauri-apps/api/core";
 import { openPath } from "@tauri-apps/plugin-opener";
 import { appToast } from "../lib/toast";
+import { formatInstallBlock } from "../lib/utils";
 
 export function SkillsPage() {
   const { t } = useTranslation();
@@ -130,8 +131,14 @@ export function SkillsPage() {
                 installMutation.mutate(
                   { skillId: skill.id },
                   {
-                    onSuccess: () => {
+                    onSuccess: (result) => {
                       setInstallingSkillId(null);
+                      if (result.status === "blocked") {
+                        appToast.error(
+                          `${t("skills.toast.installFailed")}: ${formatInstallBlock(result, skill.name, t)}`
+                        );
+                        return;
+                      }
                       appToast.success(t("skills.toast.installed"));
                     },
                     onError: (error: any) => {
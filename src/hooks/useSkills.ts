@@ -1,5 +1,6 @@
 import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
 import { api } from "../lib/api";
+import type { InstallResult } from "../types";
 
 export function useSkills() {
   return useQuery({
@@ -18,14 +19,15 @@ export function useInstalledSkills() {
 interface InstallSkillVariables {
   skillId: string;
   installPath?: string;
+  confirmation?: string;
 }
 
 export function useInstallSkill() {
   const queryClient = useQueryClient();
 
-  return useMutation<unknown, Error, InstallSkillVariables>({
-    mutationFn: ({ skillId, installPath }) =>
-      api.installSkill(skillId, installPath),
+  return useMutation<InstallResult, Error, InstallSkillVariables>({
+    mutationFn: ({ skillId, installPath, confirmation }) =>
+      api.installSkill(skillId, installPath, confirmation),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ["skills"] });
       queryClient.invalidateQueries({ queryKey: ["skills", "installed"] });
@@ -1,6 +1,44 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { Repository, Skill, CacheStats, FeaturedRepositoriesConfig, ClearAllCachesResult } from "../types";
-import type { SecurityReport } from "../types/security";
+import type {
+  Repository,
+  Skill,
+  CacheStats,
+  CacheUsage,
+  CacheClearResult,
+  FeaturedRepositoriesConfig,
+  ClearAllCachesResult,
+  SuspiciousUpdate,
+  TimelineEntry,
+  SkillReviewStatus,
+  RepositoryDeletionResult,
+  SkillListImportResult,
+  SkillListExportResult,
+  SkillProvenance,
+  DroppedItemResult,
+  AddAndScanResult,
+  DetailKind,
+  DetailWindowState,
+  UninstallImpact,
+  UninstallConfirmation,
+  SkillRelocation,
+  SkillBackup,
+  LocalScanResult,
+  BatchItemResult,
+  SkillQuery,
+  SkillPage,
+  DirAccessReport,
+  DirFixOptions,
+  StartupScanSummary,
+  DeviceAuthorization,
+  GitHubSignInStatus,
+  GitHubAuthStatus,
+  RemoteContentKind,
+  RemoteContentStatus,
+  InstallResult,
+  PathChecksum,
+  SkillChecksumVerification,
+} from "../types";
+import type { IssueContext, SecurityReport } from "../types/security";
 
 export const api = {
   // Repository APIs
@@ -12,8 +50,68 @@ export const api = {
     return invoke("get_repositories");
   },
 
-  async deleteRepository(repoId: string): Promise<void> {
-    return invoke("delete_repository", { repoId });
+  async importSkillList(source: string): Promise<SkillListImportResult> {
+    return invoke("import_skill_list", { source });
+  },
+
+  async handleDroppedPaths(paths: string[], locale: string): Promise<DroppedItemResult[]> {
+    return invoke("handle_dropped_paths", { paths, locale });
+  },
+
+  async addAndScanRepositoryUrl(url: string): Promise<AddAndScanResult> {
+    return invoke("add_and_scan_repository_url", { url });
+  },
+
+  async openDetailWindow(options: {
+    kind: DetailKind;
+    id: string;
+    title?: string;
+    payload?: unknown;
+  }): Promise<string> {
+    return invoke("open_detail_window", options);
+  },
+
+  async getDetailWindowState(): Promise<DetailWindowState | null> {
+    return invoke("get_detail_window_state");
+  },
+
+  async exportSkillList(options: {
+    name?: string;
+    description?: string;
+    format: "json" | "yaml";
+    path?: string;
+    uploadGist?: boolean;
+    public?: boolean;
+    pinHashes?: boolean;
+  }): Promise<SkillListExportResult> {
+    return invoke("export_skill_list", options);
+  },
+
+  // 选择导出文件的保存位置，所在目录会被批准用于导出（取消时返回 null）
+  async selectExportPath(defaultName?: string): Promise<string | null> {
+    return invoke("select_export_path", { defaultName });
+  },
+
+  async getSkillsProvenance(): Promise<SkillProvenance[]> {
+    return invoke("get_skills_provenance");
+  },
+
+  async getIssueContext(skillId: string, contextLines?: number): Promise<IssueContext[]> {
+    return invoke("get_issue_context", { skillId, contextLines });
+  },
+
+  // 计算文件或目录的 SHA256，给出 expected 时同时比较（如发布的哈希）
+  async computePathChecksum(path: string, expected?: string): Promise<PathChecksum> {
+    return invoke("compute_path_checksum", { path, expected: expected ?? null });
+  },
+
+  // 校验已安装技能的内容，未给出 expected 时与导入技能清单时固定的哈希比较
+  async verifySkillChecksum(skillId: string, expected?: string): Promise<SkillChecksumVerification> {
+    return invoke("verify_skill_checksum", { skillId, expected: expected ?? null });
+  },
+
+  async deleteRepository(repoId: string, uninstallSkills = false): Promise<RepositoryDeletionResult> {
+    return invoke("delete_repository", { repoId, uninstallSkills });
   },
 
   async scanRepository(repoId: string): Promise<Skill[]> {
@@ -25,20 +123,92 @@ export const api = {
     return invoke("get_skills");
   },
 
+  async querySkills(query: SkillQuery = {}): Promise<SkillPage> {
+    return invoke("query_skills", { query });
+  },
+
   async getInstalledSkills(): Promise<Skill[]> {
     return invoke("get_installed_skills");
   },
 
-  async installSkill(skillId: string, installPath?: string): Promise<void> {
-    return invoke("install_skill", { skillId, installPath: installPath || null });
+  // 高风险技能需传入技能名称（或管理口令）作为确认文本
+  async installSkill(skillId: string, installPath?: string, confirmation?: string): Promise<InstallResult> {
+    return invoke("install_skill", { skillId, installPath: installPath || null, confirmation });
+  },
+
+  async installSkillFromUrl(url: string, locale: string, installPath?: string, confirmation?: string): Promise<Skill> {
+    return invoke("install_skill_from_url", { url, installPath: installPath || null, locale, confirmation });
+  },
+
+  // 按安装时记录的 commit 清空并重新安装，不会更新到最新版本
+  async reinstallSkill(skillId: string, locale: string, confirmation?: string): Promise<Skill> {
+    return invoke("reinstall_skill", { skillId, locale, confirmation });
+  },
+
+  // 技能记住的安装目录（技能 ID -> 目录），未指定安装路径时安装与更新使用该目录
+  async getSkillInstallTargets(): Promise<Record<string, string>> {
+    return invoke("get_skill_install_targets");
+  },
+
+  async setSkillInstallTarget(skillId: string, installPath?: string | null): Promise<void> {
+    return invoke("set_skill_install_target", { skillId, installPath: installPath || null });
+  },
+
+  async checkUninstallImpact(skillId: string, path?: string): Promise<UninstallImpact> {
+    return invoke("check_uninstall_impact", { skillId, path: path ?? null });
+  },
+
+  async uninstallSkill(skillId: string, confirm?: UninstallConfirmation): Promise<void> {
+    return invoke("uninstall_skill", { skillId, confirm: confirm ?? null });
+  },
+
+  // 批量操作：逐个处理，单个失败不影响其他技能，进度通过 batch-progress 事件发送
+  async uninstallSkills(skillIds: string[], confirm?: UninstallConfirmation): Promise<BatchItemResult[]> {
+    return invoke("uninstall_skills", { skillIds, confirm: confirm ?? null });
+  },
+
+  async rescanSkills(skillIds: string[], locale: string): Promise<BatchItemResult[]> {
+    return invoke("rescan_skills", { skillIds, locale });
+  },
+
+  async tagSkills(skillIds: string[], add: string[], remove: string[] = []): Promise<BatchItemResult[]> {
+    return invoke("tag_skills", { skillIds, add, remove });
+  },
+
+  async deleteSkillRecords(skillIds: string[]): Promise<BatchItemResult[]> {
+    return invoke("delete_skill_records", { skillIds });
+  },
+
+  async getSkillTags(): Promise<Record<string, string[]>> {
+    return invoke("get_skill_tags");
+  },
+
+  async uninstallSkillPath(skillId: string, path: string, confirm?: UninstallConfirmation): Promise<void> {
+    return invoke("uninstall_skill_path", { skillId, path, confirm: confirm ?? null });
+  },
+
+  async renameInstalledSkill(skillId: string, newName: string): Promise<Skill> {
+    return invoke("rename_installed_skill", { skillId, newName });
   },
 
-  async uninstallSkill(skillId: string): Promise<void> {
-    return invoke("uninstall_skill", { skillId });
+  async getStartupScanSummary(): Promise<StartupScanSummary | null> {
+    return invoke("get_startup_scan_summary");
   },
 
-  async uninstallSkillPath(skillId: string, path: string): Promise<void> {
-    return invoke("uninstall_skill_path", { skillId, path });
+  async checkSkillDirectories(skillId?: string): Promise<DirAccessReport[]> {
+    return invoke("check_skill_directories", { skillId: skillId ?? null });
+  },
+
+  async fixSkillDirectory(path: string, options: DirFixOptions): Promise<DirAccessReport> {
+    return invoke("fix_skill_directory", { path, options });
+  },
+
+  async relocateSkills(oldBase?: string, newBase?: string): Promise<SkillRelocation[]> {
+    return invoke("relocate_skills", { oldBase: oldBase ?? null, newBase: newBase ?? null });
+  },
+
+  async listSkillBackups(skillId?: string): Promise<SkillBackup[]> {
+    return invoke("list_skill_backups", { skillId: skillId ?? null });
   },
 
   async deleteSkill(skillId: string): Promise<void> {
@@ -46,11 +216,24 @@ export const api = {
   },
 
   // Scan local skills directory
-  async scanLocalSkills(): Promise<Skill[]> {
+  async scanLocalSkills(): Promise<LocalScanResult> {
     return invoke("scan_local_skills");
   },
 
   // 缓存管理
+  async setRepositoryScanPaths(
+    repoId: string,
+    includePatterns: string[],
+    excludePatterns: string[],
+    maxDepth: number | null,
+  ): Promise<void> {
+    return invoke("set_repository_scan_paths", { repoId, includePatterns, excludePatterns, maxDepth });
+  },
+
+  async setRepositorySkipAutoRefresh(repoId: string, skip: boolean): Promise<void> {
+    return invoke("set_repository_skip_auto_refresh", { repoId, skip });
+  },
+
   async clearRepositoryCache(repoId: string): Promise<void> {
     return invoke("clear_repository_cache", { repoId });
   },
@@ -67,6 +250,26 @@ export const api = {
     return invoke("get_cache_stats");
   },
 
+  async getCacheUsage(): Promise<CacheUsage> {
+    return invoke("get_cache_usage");
+  },
+
+  async clearStagingCaches(): Promise<CacheClearResult> {
+    return invoke("clear_staging_caches");
+  },
+
+  async clearSkillBackups(): Promise<CacheClearResult> {
+    return invoke("clear_skill_backups");
+  },
+
+  async clearExtractedArchives(): Promise<CacheClearResult> {
+    return invoke("clear_extracted_archives");
+  },
+
+  async clearUnusedCaches(days: number): Promise<CacheClearResult> {
+    return invoke("clear_unused_caches", { days });
+  },
+
   // 打开技能目录
   async openSkillDirectory(localPath: string): Promise<void> {
     return invoke("open_skill_directory", { localPath });
@@ -81,15 +284,80 @@ export const api = {
     return invoke("refresh_featured_repositories");
   },
 
+  async getRemoteContentStatus(): Promise<RemoteContentStatus[]> {
+    return invoke("get_remote_content_status");
+  },
+
+  // kinds 为空时刷新全部远程内容
+  async refreshRemoteContent(kinds?: RemoteContentKind[]): Promise<RemoteContentStatus[]> {
+    return invoke("refresh_remote_content", { kinds });
+  },
+
   async isRepositoryAdded(url: string): Promise<boolean> {
     return invoke("is_repository_added", { url });
   },
 
+  // GitHub 设备授权登录：开始后按 interval 秒轮询结果
+  async startGithubSignIn(scopes?: string[]): Promise<DeviceAuthorization> {
+    return invoke("start_github_sign_in", { scopes });
+  },
+
+  async pollGithubSignIn(): Promise<GitHubSignInStatus> {
+    return invoke("poll_github_sign_in");
+  },
+
+  async cancelGithubSignIn(): Promise<void> {
+    return invoke("cancel_github_sign_in");
+  },
+
+  async getGithubAuthStatus(requiredScopes?: string[]): Promise<GitHubAuthStatus> {
+    return invoke("get_github_auth_status", { requiredScopes });
+  },
+
   // Skill Update APIs
   async checkSkillsUpdates(): Promise<Array<[string, string]>> {
     return invoke("check_skills_updates");
   },
 
+  // 开启或关闭单个技能的更新通知
+  async setSkillUpdateNotifications(skillId: string, enabled: boolean): Promise<void> {
+    return invoke("set_skill_update_notifications", { skillId, enabled });
+  },
+
+  async getSuspiciousUpdates(): Promise<SuspiciousUpdate[]> {
+    return invoke("get_suspicious_updates");
+  },
+
+  async dismissSuspiciousUpdate(skillId: string): Promise<void> {
+    return invoke("dismiss_suspicious_update", { skillId });
+  },
+
+  async getSkillTimeline(skillId: string): Promise<TimelineEntry[]> {
+    return invoke("get_skill_timeline", { skillId });
+  },
+
+  async markSkillReviewed(skillId: string, reviewer: string, note?: string): Promise<SkillReviewStatus> {
+    return invoke("mark_skill_reviewed", { skillId, reviewer, note });
+  },
+
+  async clearSkillReview(skillId: string): Promise<void> {
+    return invoke("clear_skill_review", { skillId });
+  },
+
+  async getSkillReviews(): Promise<SkillReviewStatus[]> {
+    return invoke("get_skill_reviews");
+  },
+
+  // 评分过低或风险较高时需传入技能名称（或管理口令）作为确认文本
+  async confirmSkillInstallation(skillId: string, installPath?: string, confirmation?: string): Promise<InstallResult> {
+    return invoke("confirm_skill_installation", { skillId, installPath, confirmation });
+  },
+
+  // 已设置口令时需传入当前口令
+  async setInstallPassphrase(passphrase: string | null, current?: string): Promise<void> {
+    return invoke("set_install_passphrase", { passphrase, current });
+  },
+
   async prepareSkillUpdate(skillId: string, locale: string): Promise<[SecurityReport, string[]]> {
     return invoke("prepare_skill_update", { skillId, locale });
   },
@@ -1,4 +1,6 @@
-import { Skill } from "../types";
+import type { TFunction } from "i18next";
+import { Skill, isAppError } from "../types";
+import type { BlockReason, InstallResult } from "../types";
 
 /**
  * 从 repository_url 解析仓库所有者
@@ -26,3 +28,40 @@ export function getRepositoryDisplayName(owner: string): string {
   if (owner === "local") return "本地";
   return `@${owner}`;
 }
+
+/**
+ * 获取错误的展示文本（兼容后端返回的结构化错误）
+ */
+export function getErrorMessage(error: unknown): string {
+  if (isAppError(error) || error instanceof Error) return error.message;
+  return String(error);
+}
+
+/**
+ * 被阻止的安装结果的展示文本（每个原因一行）
+ */
+export function formatInstallBlock(result: InstallResult, skillName: string, t: TFunction): string {
+  return result.blockReasons.map((reason) => formatBlockReason(reason, skillName, t)).join("\n");
+}
+
+function formatBlockReason(reason: BlockReason, skillName: string, t: TFunction): string {
+  switch (reason.kind) {
+    case "hard_trigger":
+      return t("skills.blocked.hardTrigger", { issue: reason.issue });
+    case "low_score":
+      return t(reason.confirmable ? "skills.blocked.lowScoreConfirmable" : "skills.blocked.lowScore", {
+        score: reason.score,
+        minScore: reason.minScore,
+        name: skillName,
+      });
+    case "not_scanned":
+      return t("skills.blocked.notScanned");
+    case "confirmation_required":
+      return t(reason.passphrase ? "skills.blocked.passphraseRequired" : "skills.blocked.confirmationRequired", {
+        level: reason.securityLevel,
+        name: skillName,
+      });
+    case "content_mismatch":
+      return t("skills.blocked.contentMismatch", { files: reason.files.join(", ") });
+  }
+}
@@ -2,6 +2,7 @@ export interface CacheStats {
   totalRepositories: number;
   cachedRepositories: number;
   totalSizeBytes: number;
+  tempSizeBytes: number;
 }
 
 export interface ClearAllCachesResult {
@@ -10,3 +11,17 @@ export interface ClearAllCachesResult {
   failedCount: number;
   totalSizeFreed: number;
 }
+
+export interface CacheClearResult {
+  removedCount: number;
+  freedBytes: number;
+}
+
+export interface CacheUsage {
+  repositoriesBytes: number;
+  archivesBytes: number;
+  stagingBytes: number;
+  backupsBytes: number;
+  blobsBytes: number;
+  totalBytes: number;
+}
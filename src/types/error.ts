@@ -0,0 +1,30 @@
+/**
+ * 后端命令返回的结构化错误
+ */
+export type ErrorKind =
+  | "rate_limited"
+  | "network"
+  | "unauthorized"
+  | "blocked_by_scan"
+  | "signature_rejected"
+  | "integrity_mismatch"
+  | "not_found"
+  | "invalid_input"
+  | "cancelled"
+  | "confirmation_required"
+  | "permission_denied"
+  | "path_not_allowed"
+  | "io"
+  | "database"
+  | "internal";
+
+export interface AppError {
+  kind: ErrorKind;
+  message: string;
+  details?: string;
+  retryable: boolean;
+}
+
+export function isAppError(error: unknown): error is AppError {
+  return typeof error === "object" && error !== null && "kind" in error && "message" in error;
+}
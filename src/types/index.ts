@@ -1,3 +1,6 @@
+import type { SecurityReport } from "./security";
+import type { AppError } from "./error";
+
 export interface Repository {
   id: string;
   url: string;
@@ -11,8 +14,121 @@ export interface Repository {
   cache_path?: string;
   cached_at?: string;
   cached_commit_sha?: string;
+  // 技能发现路径配置
+  scan_include_patterns?: string[];
+  scan_exclude_patterns?: string[];
+  scan_max_depth?: number | null;
+  skip_auto_refresh?: boolean;
+  // 最近一次扫描/下载失败的错误（成功后清除）
+  last_error?: string | null;
+  last_error_at?: string | null;
+}
+
+// 可疑更新：内容变化且新出现高危/严重问题
+export interface SuspiciousUpdate {
+  skill_id: string;
+  skill_name: string;
+  repository_url: string;
+  commit_sha?: string | null;
+  previous_score: number;
+  current_score: number;
+  new_findings: string[];
+  detected_at: string;
+}
+
+// 技能时间线条目（从旧到新）
+export interface TimelineEntry {
+  kind: "installed" | "updated" | "uninstalled" | "flagged" | "disabled" | "quarantined" | "score_changed" | "reviewed" | "reinstalled";
+  occurred_at: string;
+  path?: string | null;
+  commit_sha?: string | null;
+  previous_score?: number | null;
+  score?: number | null;
+  detail?: string | null;
+}
+
+// 人工审阅记录，outdated 表示已安装的版本不再是审阅过的版本
+export interface SkillReviewStatus {
+  skillId: string;
+  skillName: string;
+  reviewer: string;
+  reviewedAt: string;
+  commitSha?: string | null;
+  checksum?: string | null;
+  note?: string | null;
+  installedCommitSha?: string | null;
+  outdated: boolean;
+}
+
+// 后台刷新仓库的结果
+export interface RepositoryRefreshResult {
+  repoId: string;
+  repoName: string;
+  newSkills: string[];
+  changedSkills: string[];
+  removedSkills: string[];
+  suspiciousUpdates: SuspiciousUpdate[];
+}
+
+// new-skills-available 事件
+export interface NewSkillsNotification {
+  count: number;
+  repositories: RepositoryRefreshResult[];
+}
+
+// skill-updates-available 事件：后台检查发现的技能更新（合并为一条通知）
+export interface SkillUpdateNotice {
+  skillId: string;
+  skillName: string;
+  latest: string;
+}
+
+export interface SkillUpdatesNotification {
+  count: number;
+  skills: SkillUpdateNotice[];
+}
+
+// installed-skill-threats 事件：定时扫描发现已安装技能命中硬触发规则
+export interface InstalledSkillThreat {
+  skillId: string;
+  skillName: string;
+  hardTriggerIssues: string[];
+  response: "notify" | "disable" | "quarantine";
+  quarantinePath?: string | null;
+  error?: string | null;
+}
+
+export interface FileChanges {
+  added: string[];
+  removed: string[];
+  modified: string[];
+}
+
+// skill-regressions 事件中的条目：启动检查发现有变化的已安装技能
+export interface SkillChangeSummary {
+  skillId: string;
+  skillName: string;
+  path: string;
+  missing: boolean;
+  files: FileChanges;
+  previousScore: number | null;
+  score: number | null;
+  newlyBlocked: boolean;
+  regression: boolean;
+}
+
+// startup-scan-summary 事件：启动检查与上次运行相比的变化
+export interface StartupScanSummary {
+  checkedAt: string;
+  previousCheckedAt: string | null;
+  scanned: number;
+  failed: number;
+  changes: SkillChangeSummary[];
 }
 
+// 技能来源类型
+export type SourceType = "github_repo" | "git_repo" | "local_import" | "archive" | "direct_url";
+
 export interface Skill {
   id: string;
   name: string;
@@ -31,6 +147,96 @@ export interface Skill {
   security_issues?: string[];
   installed_commit_sha?: string;  // 安装时的 commit SHA，用于版本追踪
   scanned_at?: string;
+  source_removed?: boolean;  // 来源仓库已删除
+  first_seen_at?: string;  // 首次出现在本地索引中的时间
+  source_type?: SourceType;  // 来源类型
+  origin_tool?: string | null;  // 本地导入时所在的工具 ID
+}
+
+export interface LocalScanResult {
+  skills: Skill[];
+  scanned_dirs: number;
+  excluded_dirs: number;
+  skipped_entries: number;
+  skipped_dirs: number;
+  timed_out: boolean;
+}
+
+// 技能的热度与来源信号
+export interface SkillProvenance {
+  skill_id: string;
+  stars?: number | null;
+  featured_count: number;
+  first_seen_at?: string | null;
+  owner?: string | null;
+  owner_created_at?: string | null;
+}
+
+// 导入技能清单的结果
+export interface SkillListImportResult {
+  name?: string | null;
+  description?: string | null;
+  addedRepositories: string[];
+  skills: Skill[];
+  missingSkills: string[];
+  failedRepositories: string[];
+  // 内容与清单中固定的哈希不一致的技能及原因
+  integrityFailures: string[];
+}
+
+// 拖放到窗口的路径类型
+export type DroppedItemKind = "skill_folder" | "archive" | "skill_list" | "repository_urls" | "unsupported";
+
+// 拖放路径的处理结果
+export interface DroppedItemResult {
+  path: string;
+  kind: DroppedItemKind;
+  report?: SecurityReport | null;
+  skillList?: SkillListImportResult | null;
+  addedRepositories: string[];
+  skills: Skill[];
+  error?: string | null;
+}
+
+// 剪贴板中识别出的 GitHub 地址（clipboard-github-url 事件）
+export interface ClipboardGitHubUrl {
+  repositoryUrl: string;
+  owner: string;
+  repo: string;
+  path?: string | null;
+  alreadyAdded: boolean;
+}
+
+// 添加并扫描仓库的结果
+export interface AddAndScanResult {
+  repoId: string;
+  added: boolean;
+  skills: Skill[];
+}
+
+// 详情窗口的内容类型
+export type DetailKind = "skill" | "report";
+
+// 详情窗口的状态（get_detail_window_state、detail-window-state 事件）
+export interface DetailWindowState {
+  kind: DetailKind;
+  id: string;
+  payload?: unknown;
+}
+
+// 导出技能清单的结果
+export interface SkillListExportResult {
+  content: string;
+  skillCount: number;
+  gistUrl?: string | null;
+}
+
+// 删除仓库的结果
+export interface RepositoryDeletionResult {
+  deletedSkills: number;
+  uninstalledSkills: number;
+  orphanedSkills: number;
+  freedBytes: number;
 }
 
 export enum SecurityLevel {
@@ -41,7 +247,7 @@ export enum SecurityLevel {
   Critical = "Critical",
 }
 
-export type { CacheStats, ClearAllCachesResult } from './cache';
+export type { CacheStats, CacheUsage, CacheClearResult, ClearAllCachesResult } from './cache';
 export type {
   FeaturedRepositoriesConfig,
   FeaturedRepository,
@@ -53,3 +259,183 @@ export interface InstallPathSelection {
   path: string;
   displayName: string;
 }
+
+export type { AppError, ErrorKind } from './error';
+export { isAppError } from './error';
+
+// 卸载前检查出的影响
+export interface SkillDependent {
+  skillId: string;
+  skillName: string;
+  manifestPath: string;
+}
+
+export interface LinkedLocation {
+  toolId: string;
+  toolName: string;
+  path: string;
+  target: string;
+}
+
+export interface UninstallImpact {
+  dependents: SkillDependent[];
+  linkedLocations: LinkedLocation[];
+  pinnedManifests: string[];
+}
+
+// 卸载时对各项影响的确认
+export interface UninstallConfirmation {
+  dependents?: boolean;
+  linkedLocations?: boolean;
+  pinnedManifests?: boolean;
+}
+
+// 技能目录查询（排序与分页在后端完成）
+export type SkillSort = "name" | "security_score" | "upstream_updated" | "installed_at" | "stars";
+
+export interface SkillQuery {
+  search?: string;
+  repositoryOwner?: string;
+  hideInstalled?: boolean;
+  includeLocal?: boolean;
+  sort?: SkillSort;
+  descending?: boolean;
+  offset?: number;
+  limit?: number;
+}
+
+export interface SkillPage {
+  skills: Skill[];
+  total: number;
+  offset: number;
+}
+
+// 批量操作（batch-progress 事件与每个技能的结果）
+export type BatchOperation = "uninstall" | "rescan" | "tag" | "delete_record";
+
+export interface BatchItemResult {
+  skillId: string;
+  error: AppError | null;
+}
+
+export interface BatchProgress {
+  operation: BatchOperation;
+  completed: number;
+  total: number;
+  skillId: string;
+  error: AppError | null;
+}
+
+// 安装路径重定位结果
+export interface SkillRelocation {
+  skill_id: string;
+  skill_name: string;
+  old_path: string;
+  new_path: string | null;
+  exists: boolean;
+}
+
+export interface SkillBackup {
+  skillDirName: string;
+  path: string;
+  sizeBytes: number;
+  createdAt: string;
+}
+
+export interface DirAccessReport {
+  path: string;
+  cloudProvider: string | null;
+  notWritable: boolean;
+  readOnlyFiles: string[];
+  placeholderFiles: string[];
+}
+
+export interface DirFixOptions {
+  clearReadOnly?: boolean;
+  downloadPlaceholders?: boolean;
+}
+
+// GitHub 设备授权登录：在浏览器中打开 verificationUri 并输入 userCode
+export interface DeviceAuthorization {
+  userCode: string;
+  verificationUri: string;
+  expiresIn: number;
+  interval: number;
+}
+
+export type GitHubSignInStatus =
+  | { status: "pending" }
+  | { status: "authorized"; login: string | null; scopes: string[] }
+  | { status: "expired" }
+  | { status: "denied" };
+
+export interface GitHubAuthStatus {
+  hasToken: boolean;
+  login: string | null;
+  // 细粒度 Token 不返回权限列表
+  scopes: string[] | null;
+  missingScopes: string[];
+}
+
+export type RemoteContentKind = "featured_repositories";
+
+// 远程内容的缓存状态；remote-content-updated 事件携带内容有变化的项
+export interface RemoteContentStatus {
+  kind: RemoteContentKind;
+  url: string;
+  cached: boolean;
+  updatedAt: string | null;
+  checkedAt: string | null;
+  stale: boolean;
+  lastError: string | null;
+  changed: boolean;
+}
+
+export type InstallStatus = "installed" | "blocked";
+
+// 阻止安装的原因，前端据此生成本地化说明
+export type BlockReason =
+  | { kind: "hard_trigger"; issue: string }
+  | { kind: "low_score"; score: number; minScore: number; confirmable: boolean }
+  | { kind: "not_scanned" }
+  | { kind: "confirmation_required"; securityLevel: string; passphrase: boolean }
+  | { kind: "content_mismatch"; files: string[] };
+
+export interface ScanSummary {
+  score: number;
+  level: string;
+  issueCount: number;
+  hardTriggerCount: number;
+  scannedFiles: number;
+}
+
+// 安装、同步与确认安装的结果；被阻止时 status 为 blocked 并给出原因
+export interface InstallResult {
+  skillId: string;
+  status: InstallStatus;
+  installedPath: string | null;
+  filesCopied: number;
+  scan: ScanSummary | null;
+  blockReasons: BlockReason[];
+}
+
+export type ChecksumSource = "provided" | "pinned";
+
+export type ChecksumStatus = "verified" | "mismatch" | "unverified";
+
+// 文件或目录的 SHA256（目录按相对路径与内容计算）；matches 为与预期哈希的比较结果
+export interface PathChecksum {
+  path: string;
+  isDir: boolean;
+  checksum: string;
+  matches: boolean | null;
+}
+
+// 已安装技能的内容校验结果，每个安装目录分别计算
+export interface SkillChecksumVerification {
+  skillId: string;
+  expected: string | null;
+  source: ChecksumSource | null;
+  paths: PathChecksum[];
+  status: ChecksumStatus;
+}
@@ -5,6 +5,22 @@ export interface SecurityIssue {
   line_number?: number;
   code_snippet?: string;
   file_path?: string;  // 记录哪个文件有风险
+  original_severity?: string;  // 说明文字中仅被提及而降为 Info 时的原始级别
+}
+
+// 问题所在位置的上下文代码
+export interface IssueContext {
+  file_path: string;
+  line_number: number;
+  rule_id?: string | null;
+  language?: string | null;
+  lines: ContextLine[];
+}
+
+export interface ContextLine {
+  number: number;
+  text: string;
+  highlight?: [number, number] | null;  // 命中范围（字符偏移，左闭右开）
 }
 
 export interface SecurityReport {
@@ -16,6 +32,25 @@ export interface SecurityReport {
   blocked: boolean;
   hard_trigger_issues: string[];
   scanned_files: string[];  // 已扫描的文件列表
+  modified_rules?: string[];  // 被用户调整过级别、权重或硬触发的规则
+  capabilities?: CapabilitySummary;  // 技能可执行的操作
+}
+
+export type Capability =
+  | "shell"
+  | "privilege"
+  | "persistence"
+  | "network"
+  | "sensitive_files"
+  | "file_write"
+  | "file_read";
+
+// 能力摘要：清单声明的工具权限与扫描检测到的能力
+export interface CapabilitySummary {
+  allowed_tools: string[];
+  declared: Capability[];
+  detected: Capability[];
+  summary: string;  // 可读摘要（按扫描语言）
 }
 
 export interface SkillScanResult {